@@ -0,0 +1,173 @@
+mod channel;
+use channel::{Dmc, Noise, Pulse, Triangle};
+
+/// NTSC CPU clock, the APU is driven from the same clock
+pub const CPU_CLOCK_NTSC: f64 = 1_789_773.0;
+pub const CPU_CLOCK_PAL: f64 = 1_662_607.0;
+
+/// CPU cycles of each frame counter step, the last one is only used in 5-step mode
+const FRAME_STEPS: [u32; 5] = [7457, 14913, 22371, 29829, 37281];
+
+#[derive(Debug, Clone)]
+pub struct Apu2A03 {
+    pulse1: Pulse,
+    pulse2: Pulse,
+    triangle: Triangle,
+    noise: Noise,
+    dmc: Dmc,
+    five_step_mode: bool,
+    frame_cycle: u32,
+    cycle: u64,
+}
+
+impl Apu2A03 {
+    pub fn new() -> Apu2A03 {
+        Apu2A03 {
+            pulse1: Pulse::new(true),
+            pulse2: Pulse::new(false),
+            triangle: Triangle::default(),
+            noise: Noise::default(),
+            dmc: Dmc::default(),
+            five_step_mode: false,
+            frame_cycle: 0,
+            cycle: 0,
+        }
+    }
+
+    /// Advance the APU by one CPU cycle
+    pub fn tick(&mut self) {
+        if self.cycle % 2 == 1 {
+            self.pulse1.clock_timer();
+            self.pulse2.clock_timer();
+        }
+        self.triangle.clock_timer();
+        self.noise.clock_timer();
+        self.dmc.clock_timer();
+
+        self.frame_cycle += 1;
+        let last_step = if self.five_step_mode { 4 } else { 3 };
+        match FRAME_STEPS.iter().position(|&c| c == self.frame_cycle) {
+            Some(1) => {
+                self.clock_quarter_frame();
+                self.clock_half_frame();
+            }
+            Some(step) if step == last_step => {
+                self.clock_quarter_frame();
+                self.clock_half_frame();
+                self.frame_cycle = 0;
+            }
+            Some(0) | Some(2) => self.clock_quarter_frame(),
+            _ => {}
+        }
+
+        self.cycle += 1;
+    }
+
+    fn clock_quarter_frame(&mut self) {
+        self.pulse1.clock_quarter_frame();
+        self.pulse2.clock_quarter_frame();
+        self.triangle.clock_quarter_frame();
+        self.noise.clock_quarter_frame();
+    }
+
+    fn clock_half_frame(&mut self) {
+        self.pulse1.clock_half_frame();
+        self.pulse2.clock_half_frame();
+        self.triangle.clock_half_frame();
+        self.noise.clock_half_frame();
+    }
+
+    /// Address the DMC wants to read its next sample byte from,
+    /// the caller is expected to answer with [`Apu2A03::dmc_load`]
+    pub fn dmc_dma_address(&self) -> Option<u16> {
+        self.dmc.dma_address()
+    }
+
+    pub fn dmc_load(&mut self, data: u8) {
+        self.dmc.load_sample(data);
+    }
+
+    /// Mixed output in the range 0.0..=1.0, using the non-linear mixer formula
+    pub fn output(&self) -> f32 {
+        let pulse = (self.pulse1.output() + self.pulse2.output()) as f32;
+        let pulse_out = if pulse == 0.0 {
+            0.0
+        } else {
+            95.88 / (8128.0 / pulse + 100.0)
+        };
+
+        let tnd = self.triangle.output() as f32 / 8227.0
+            + self.noise.output() as f32 / 12241.0
+            + self.dmc.output() as f32 / 22638.0;
+        let tnd_out = if tnd == 0.0 {
+            0.0
+        } else {
+            159.79 / (1.0 / tnd + 100.0)
+        };
+
+        pulse_out + tnd_out
+    }
+
+    pub fn cpu_read(&mut self, addr: u16) -> u8 {
+        match addr {
+            // Status
+            0x4015 => {
+                (self.pulse1.length > 0) as u8
+                    | ((self.pulse2.length > 0) as u8) << 1
+                    | ((self.triangle.length > 0) as u8) << 2
+                    | ((self.noise.length > 0) as u8) << 3
+                    | ((self.dmc.bytes_remaining > 0) as u8) << 4
+            }
+            _ => 0,
+        }
+    }
+
+    pub fn cpu_write(&mut self, addr: u16, data: u8) {
+        match addr {
+            0x4000..=0x4003 => self.pulse1.write(addr & 0x0003, data),
+            0x4004..=0x4007 => self.pulse2.write(addr & 0x0003, data),
+            0x4008..=0x400B => self.triangle.write(addr & 0x0003, data),
+            0x400C..=0x400F => self.noise.write(addr & 0x0003, data),
+            0x4010..=0x4013 => self.dmc.write(addr & 0x0003, data),
+
+            // Channel enable
+            0x4015 => {
+                self.pulse1.enabled = data & 0x01 > 0;
+                self.pulse2.enabled = data & 0x02 > 0;
+                self.triangle.enabled = data & 0x04 > 0;
+                self.noise.enabled = data & 0x08 > 0;
+                self.dmc.set_enabled(data & 0x10 > 0);
+
+                if !self.pulse1.enabled {
+                    self.pulse1.length = 0;
+                }
+                if !self.pulse2.enabled {
+                    self.pulse2.length = 0;
+                }
+                if !self.triangle.enabled {
+                    self.triangle.length = 0;
+                }
+                if !self.noise.enabled {
+                    self.noise.length = 0;
+                }
+            }
+
+            // Frame counter
+            0x4017 => {
+                self.five_step_mode = data & 0x80 > 0;
+                self.frame_cycle = 0;
+                if self.five_step_mode {
+                    self.clock_quarter_frame();
+                    self.clock_half_frame();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Default for Apu2A03 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,61 @@
+use std::{env, fs::File, io::BufWriter, process};
+
+use nes::{
+    nsf::{Nsf, NsfPlayer},
+    wav::write_wav,
+};
+
+const SAMPLE_RATE: u32 = 44100;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        eprintln!("usage: {} <file.nsf> [track] [seconds] [out.wav]", args[0]);
+        process::exit(1);
+    }
+
+    let nsf = match Nsf::from_file(args[1].clone().into()) {
+        Ok(nsf) => nsf,
+        Err(_) => {
+            eprintln!("{}: not a valid NSF file", args[1]);
+            process::exit(1);
+        }
+    };
+
+    let track = args.get(2).and_then(|s| s.parse::<u8>().ok());
+    let seconds = args
+        .get(3)
+        .and_then(|s| s.parse::<f64>().ok())
+        .unwrap_or(30.0);
+    let out_path = args.get(4).cloned().unwrap_or_else(|| {
+        format!(
+            "{}.wav",
+            args[1].trim_end_matches(".nsf").trim_end_matches(".NSF")
+        )
+    });
+
+    println!("{} - {} ({})", nsf.name, nsf.artist, nsf.copyright);
+
+    let mut player = NsfPlayer::new(nsf, SAMPLE_RATE);
+    if let Some(track) = track {
+        // Tracks are 1-based on the command line, like every other player
+        player.select_track(track.saturating_sub(1));
+    }
+
+    println!(
+        "Track {}/{}, rendering {}s to {}",
+        player.track() + 1,
+        player.track_count(),
+        seconds,
+        out_path
+    );
+
+    let samples = player.render_seconds(seconds, SAMPLE_RATE);
+    let result = File::create(&out_path)
+        .and_then(|file| write_wav(&mut BufWriter::new(file), SAMPLE_RATE, &samples));
+
+    if let Err(e) = result {
+        eprintln!("{}: {}", out_path, e);
+        process::exit(1);
+    }
+}
@@ -1,17 +1,83 @@
-use crate::mapper::Mapper;
+use crate::{gamedb, mapper::Mapper};
 use std::{
     ffi::OsString,
+    fmt,
     fs::File,
     io::{Read, Seek, SeekFrom},
+    rc::Rc,
+    str::FromStr,
 };
+use utils::prelude::*;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mirror {
+    Horizontal,
+    Vertical,
+    FourScreen,
+}
+
+impl FromStr for Mirror {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "horizontal" => Ok(Mirror::Horizontal),
+            "vertical" => Ok(Mirror::Vertical),
+            "four-screen" => Ok(Mirror::FourScreen),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Region {
+    Ntsc,
+    Pal,
+}
+
+impl FromStr for Region {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ntsc" => Ok(Region::Ntsc),
+            "pal" => Ok(Region::Pal),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Cloning a cartridge only copy its RAM and mapper registers, the ROM is shared
+#[derive(Debug, Clone)]
 pub struct Cartridge {
+    rom: Rc<Rom>,
+    state: CartridgeState,
+    region: Region,
+}
+
+/// What never change once the file is loaded
+#[derive(Debug)]
+struct Rom {
     prg_mem: Vec<u8>,
+    /// Empty when the cartridge has CHR RAM instead
     chr_mem: Vec<u8>,
     mapper_id: u8,
     prg_banks: u8,
     chr_banks: u8,
+    mirror: Mirror,
+    battery: bool,
+    crc32: u32,
+    sha1: [u8; 20],
+    title: Option<String>,
+}
+
+/// What the game can change, the part of the cartridge in a save state
+#[derive(Debug, Clone)]
+pub struct CartridgeState {
+    /// Work RAM at $6000-$7FFF, kept across sessions when the cartridge has a battery
+    prg_ram: Vec<u8>,
+    /// Pattern tables of cartridges without CHR ROM
+    chr_ram: Vec<u8>,
     mapper: Mapper,
 }
 
@@ -26,14 +92,32 @@ struct Header {
     prg_ram_size: u8,
     tv_system1: u8,
     tv_system2: u8,
-    unused: [u8; 4],
+    // iNES header is 16 bytes, the checksums are off if we don't skip all of it
+    unused: [u8; 5],
 }
 
+#[derive(Debug)]
 pub enum Error {
     IO(std::io::Error),
     InvalidData,
+    UnsupportedMapper(u8),
 }
 
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::IO(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                write!(f, "file is truncated")
+            }
+            Error::IO(e) => write!(f, "{}", e),
+            Error::InvalidData => write!(f, "not a valid ROM file"),
+            Error::UnsupportedMapper(id) => write!(f, "mapper {} is not supported yet", id),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Self {
         Error::IO(e)
@@ -62,7 +146,20 @@ impl Cartridge {
             file.seek(SeekFrom::Current(512))?;
         }
 
-        let mapper_id = ((header.mapper2 >> 4) << 4) | (header.mapper1) >> 4;
+        let mut mapper_id = ((header.mapper2 >> 4) << 4) | (header.mapper1) >> 4;
+        let mut mirror = if header.mapper1 & 0x08 > 0 {
+            Mirror::FourScreen
+        } else if header.mapper1 & 0x01 > 0 {
+            Mirror::Vertical
+        } else {
+            Mirror::Horizontal
+        };
+        let mut region = if header.tv_system1 & 0x01 > 0 {
+            Region::Pal
+        } else {
+            Region::Ntsc
+        };
+        let mut battery = header.mapper1 & 0x02 > 0;
 
         let file_type = 1;
 
@@ -86,39 +183,257 @@ impl Cartridge {
             _ => unreachable!(),
         };
 
+        let (crc32, sha1) = {
+            let mut crc32 = Crc32::new();
+            let mut sha1 = Sha1::new();
+            for mem in [&prg_mem, &chr_mem] {
+                crc32.update(mem);
+                sha1.update(mem);
+            }
+            (crc32.finish(), sha1.finish())
+        };
+
+        // Without CHR ROM the pattern tables are 8KB of RAM on the cartridge
+        let chr_ram = if chr_mem.is_empty() {
+            vec![0u8; 8192]
+        } else {
+            Vec::new()
+        };
+
+        // Lots of dumps have a broken header, trust the database over them
+        let title = match gamedb::lookup(crc32, &sha1) {
+            Some(game) => {
+                if (mapper_id, mirror, region, battery)
+                    != (game.mapper_id, game.mirror, game.region, game.battery)
+                {
+                    warn!(
+                        "Header of \"{}\" corrected from the database: mapper {} -> {}, {:?} -> {:?}, {:?} -> {:?}, battery {} -> {}",
+                        game.title,
+                        mapper_id,
+                        game.mapper_id,
+                        mirror,
+                        game.mirror,
+                        region,
+                        game.region,
+                        battery,
+                        game.battery
+                    );
+                }
+
+                mapper_id = game.mapper_id;
+                mirror = game.mirror;
+                region = game.region;
+                battery = game.battery;
+                Some(game.title.clone())
+            }
+            None => {
+                info!(
+                    "ROM crc32 {:08x} sha1 {} not in database, using its header",
+                    crc32,
+                    to_hex(&sha1)
+                );
+                None
+            }
+        };
+
+        if !Mapper::is_supported(mapper_id) {
+            return Err(Error::UnsupportedMapper(mapper_id));
+        }
+
         Ok(Cartridge {
-            prg_mem,
-            chr_mem,
-            mapper_id,
-            prg_banks,
-            chr_banks,
-            mapper: Mapper::new(mapper_id, prg_banks, chr_banks),
+            rom: Rc::new(Rom {
+                prg_mem,
+                chr_mem,
+                mapper_id,
+                prg_banks,
+                chr_banks,
+                mirror,
+                battery,
+                crc32,
+                sha1,
+                title,
+            }),
+            state: CartridgeState {
+                prg_ram: vec![0u8; 8192],
+                chr_ram,
+                mapper: Mapper::new(mapper_id, prg_banks, chr_banks),
+            },
+            region,
         })
     }
 
+    pub fn mapper_id(&self) -> u8 {
+        self.rom.mapper_id
+    }
+
+    pub fn mirror(&self) -> Mirror {
+        self.rom.mirror
+    }
+
+    pub fn region(&self) -> Region {
+        self.region
+    }
+
+    /// Override the region from the header or database, for multi-region games
+    pub fn set_region(&mut self, region: Region) {
+        self.region = region;
+    }
+
+    pub fn has_battery(&self) -> bool {
+        self.rom.battery
+    }
+
+    /// Content of the PRG RAM to save, if the cartridge keep it powered
+    pub fn battery_ram(&self) -> Option<&[u8]> {
+        if self.rom.battery {
+            Some(&self.state.prg_ram)
+        } else {
+            None
+        }
+    }
+
+    /// Restore the PRG RAM saved from `battery_ram`, a save of the wrong size is truncated
+    /// or padded with zeroes
+    pub fn load_battery_ram(&mut self, data: &[u8]) {
+        let prg_ram = &mut self.state.prg_ram;
+        let len = data.len().min(prg_ram.len());
+        prg_ram[..len].copy_from_slice(&data[..len]);
+        prg_ram[len..].fill(0);
+    }
+
+    /// CRC-32 of the PRG+CHR data
+    pub fn crc32(&self) -> u32 {
+        self.rom.crc32
+    }
+
+    /// SHA-1 of the PRG+CHR data
+    pub fn sha1(&self) -> &[u8; 20] {
+        &self.rom.sha1
+    }
+
+    /// Title from the game database, if the ROM is known
+    pub fn title(&self) -> Option<&str> {
+        self.rom.title.as_deref()
+    }
+
+    pub fn save_state(&self) -> CartridgeState {
+        self.state.clone()
+    }
+
+    pub fn load_state(&mut self, state: &CartridgeState) {
+        self.state.clone_from(state);
+    }
+
     pub fn cpu_read(&mut self, addr: u16) -> Option<u8> {
-        self.mapper
+        if (0x6000..=0x7FFF).contains(&addr) {
+            return Some(self.state.prg_ram[(addr & 0x1FFF) as usize]);
+        }
+
+        self.state
+            .mapper
             .cpu_map_read(addr)
-            .map(|mapped_addr| self.prg_mem[mapped_addr as usize])
+            .map(|mapped_addr| self.rom.prg_mem[mapped_addr as usize])
     }
 
+    /// Writes to the PRG ROM are swallowed, they only matter to mappers with registers
     pub fn cpu_write(&mut self, addr: u16, data: u8) -> bool {
-        self.mapper
-            .cpu_map_write(addr)
-            .map(|mapped_addr| self.prg_mem[mapped_addr as usize] = data)
-            .is_some()
+        if (0x6000..=0x7FFF).contains(&addr) {
+            self.state.prg_ram[(addr & 0x1FFF) as usize] = data;
+            return true;
+        }
+
+        self.state.mapper.cpu_map_write(addr).is_some()
     }
 
     pub fn ppu_read(&mut self, addr: u16) -> Option<u8> {
-        self.mapper
+        let chr = if self.rom.chr_mem.is_empty() {
+            &self.state.chr_ram
+        } else {
+            &self.rom.chr_mem
+        };
+        self.state
+            .mapper
             .ppu_map_read(addr)
-            .map(|mapped_addr| self.chr_mem[mapped_addr as usize])
+            .map(|mapped_addr| chr[mapped_addr as usize])
     }
 
     pub fn ppu_write(&mut self, addr: u16, data: u8) -> bool {
-        self.mapper
+        let chr_ram = &mut self.state.chr_ram;
+        self.state
+            .mapper
             .ppu_map_write(addr)
-            .map(|mapped_addr| self.chr_mem[mapped_addr as usize] = data)
+            .map(|mapped_addr| {
+                if let Some(byte) = chr_ram.get_mut(mapped_addr as usize) {
+                    *byte = data;
+                }
+            })
             .is_some()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ppu2C02::Ppu2C02;
+
+    #[test]
+    fn identify_from_database() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../res/nes/nestest.nes");
+        let cartridge = match Cartridge::from_file(path.into()) {
+            Ok(cartridge) => cartridge,
+            Err(e) => panic!("could not load {}: {}", path, e),
+        };
+
+        assert_eq!(cartridge.crc32(), 0x158b0388);
+        assert_eq!(cartridge.title(), Some("nestest"));
+        assert_eq!(cartridge.mapper_id(), 0);
+        assert_eq!(cartridge.mirror(), Mirror::Horizontal);
+        assert_eq!(cartridge.region(), Region::Ntsc);
+    }
+
+    #[test]
+    fn database_overrides_header() {
+        let dir = std::env::temp_dir().join(format!("nes-gamedb-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // NROM with 16KB PRG and 8KB CHR, the header says horizontal and no battery
+        let mut rom = b"NES\x1A\x01\x01\x00\x00".to_vec();
+        rom.resize(16, 0);
+        let data: Vec<u8> = (0..16384 + 8192).map(|i| (i * 7 + 3) as u8).collect();
+        rom.extend_from_slice(&data);
+        let rom_path = dir.join("override.nes");
+        std::fs::write(&rom_path, &rom).unwrap();
+
+        let db_path = dir.join("gamedb.txt");
+        std::fs::write(
+            &db_path,
+            format!(
+                "# test database\n{:08x} {} 0 vertical ntsc yes Override Test\n",
+                crc32(&data),
+                to_hex(&sha1(&data))
+            ),
+        )
+        .unwrap();
+        assert_eq!(gamedb::load(&db_path).unwrap(), 1);
+
+        let mut cartridge = Cartridge::from_file(rom_path.into_os_string()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(cartridge.title(), Some("Override Test"));
+        assert_eq!(cartridge.mirror(), Mirror::Vertical);
+        assert!(cartridge.has_battery());
+
+        // Vertical mirroring: $2000 and $2800 are the same nametable, $2400 is the other one
+        let mut ppu = Ppu2C02::new();
+        ppu.ppu_write(Some(&mut cartridge), 0x2000, 0x42);
+        assert_eq!(ppu.ppu_read(Some(&mut cartridge), 0x2800), 0x42);
+        assert_eq!(ppu.ppu_read(Some(&mut cartridge), 0x2400), 0x00);
+
+        // The PRG RAM is what the battery keep
+        assert!(cartridge.cpu_write(0x6010, 0x99));
+        assert_eq!(cartridge.battery_ram().unwrap()[0x10], 0x99);
+        cartridge.load_battery_ram(&[1, 2, 3]);
+        assert_eq!(cartridge.cpu_read(0x6001), Some(2));
+        assert_eq!(cartridge.cpu_read(0x6010), Some(0));
+    }
+}
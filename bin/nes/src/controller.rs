@@ -0,0 +1,71 @@
+use bitflags::bitflags;
+
+bitflags! {
+    /// Buttons of a standard controller, in the order they are shifted out
+    #[derive(Default)]
+    pub struct Buttons: u8 {
+        const A = 1 << 0;
+        const B = 1 << 1;
+        const SELECT = 1 << 2;
+        const START = 1 << 3;
+        const UP = 1 << 4;
+        const DOWN = 1 << 5;
+        const LEFT = 1 << 6;
+        const RIGHT = 1 << 7;
+    }
+}
+
+/// Standard controller, read one button at a time through $4016/$4017
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Controller {
+    buttons: Buttons,
+    shift: u8,
+    strobe: bool,
+}
+
+impl Controller {
+    pub fn set_buttons(&mut self, buttons: Buttons) {
+        self.buttons = buttons;
+    }
+
+    pub fn buttons(&self) -> Buttons {
+        self.buttons
+    }
+
+    pub fn write(&mut self, data: u8) {
+        self.strobe = data & 0x01 > 0;
+        if self.strobe {
+            self.shift = self.buttons.bits();
+        }
+    }
+
+    pub fn read(&mut self, readonly: bool) -> u8 {
+        if self.strobe {
+            return self.buttons.bits() & 0x01;
+        }
+
+        let bit = self.shift & 0x01;
+        if !readonly {
+            // Once all 8 buttons are read, official controllers keep returning 1
+            self.shift = (self.shift >> 1) | 0x80;
+        }
+        bit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shift_out_buttons() {
+        let mut controller = Controller::default();
+        controller.set_buttons(Buttons::A | Buttons::START | Buttons::RIGHT);
+
+        controller.write(1);
+        controller.write(0);
+
+        let bits: Vec<u8> = (0..10).map(|_| controller.read(false)).collect();
+        assert_eq!(bits, [1, 0, 0, 1, 0, 0, 0, 1, 1, 1]);
+    }
+}
@@ -3,7 +3,7 @@ use super::*;
 impl super::Cpu6502 {
     /// Relative addressing mode, only used by branch instruction
     /// i8 value relative to current program counter
-    pub fn rel(&mut self, bus: Bus) -> u8 {
+    pub fn rel<B: CpuBus + ?Sized>(&mut self, bus: &mut B) -> u8 {
         self.addr_rel = bus.read(self.pc, false) as u16;
         self.pc += 1;
 
@@ -15,20 +15,20 @@ impl super::Cpu6502 {
     }
 
     /// Implied addressing mode, copy value from Accumulator Register to fetched
-    pub fn imp(&mut self, _: Bus) -> u8 {
+    pub fn imp<B: CpuBus + ?Sized>(&mut self, _: &mut B) -> u8 {
         self.fetched = self.a;
         0
     }
 
     /// Immediate addressing mode, the value is in the next byte
-    pub fn imm(&mut self, _: Bus) -> u8 {
+    pub fn imm<B: CpuBus + ?Sized>(&mut self, _: &mut B) -> u8 {
         self.addr_abs = self.pc;
         self.pc += 1;
         0
     }
 
     /// Zero Page addressing mode
-    pub fn zp0(&mut self, bus: Bus) -> u8 {
+    pub fn zp0<B: CpuBus + ?Sized>(&mut self, bus: &mut B) -> u8 {
         self.addr_abs = bus.read(self.pc, false) as u16;
         self.pc += 1;
         self.addr_abs &= 0x00FF;
@@ -36,7 +36,7 @@ impl super::Cpu6502 {
     }
 
     /// Zero Page with offset from register X
-    pub fn zpx(&mut self, bus: Bus) -> u8 {
+    pub fn zpx<B: CpuBus + ?Sized>(&mut self, bus: &mut B) -> u8 {
         self.addr_abs = bus.read(self.pc, false) as u16 + self.x as u16;
         self.pc += 1;
         self.addr_abs &= 0x00FF;
@@ -44,7 +44,7 @@ impl super::Cpu6502 {
     }
 
     /// Zero Page with offset from register Y
-    pub fn zpy(&mut self, bus: Bus) -> u8 {
+    pub fn zpy<B: CpuBus + ?Sized>(&mut self, bus: &mut B) -> u8 {
         self.addr_abs = bus.read(self.pc, false) as u16 + self.y as u16;
         self.pc += 1;
         self.addr_abs &= 0x00FF;
@@ -52,7 +52,7 @@ impl super::Cpu6502 {
     }
 
     /// Absolute addressing mode
-    pub fn abs(&mut self, bus: Bus) -> u8 {
+    pub fn abs<B: CpuBus + ?Sized>(&mut self, bus: &mut B) -> u8 {
         let lo = bus.read(self.pc, false) as u16;
         self.pc += 1;
         let hi = bus.read(self.pc, false) as u16;
@@ -63,14 +63,14 @@ impl super::Cpu6502 {
     }
 
     /// Absolute with offset from register X
-    pub fn abx(&mut self, bus: Bus) -> u8 {
+    pub fn abx<B: CpuBus + ?Sized>(&mut self, bus: &mut B) -> u8 {
         let lo = bus.read(self.pc, false) as u16;
         self.pc += 1;
         let hi = bus.read(self.pc, false) as u16;
         self.pc += 1;
 
         self.addr_abs = (hi << 8) | lo;
-        self.addr_abs += self.x as u16;
+        self.addr_abs = self.addr_abs.wrapping_add(self.x as u16);
 
         // Page changed, need more cycles
         if (self.addr_abs & 0xFF00) != (hi << 8) {
@@ -81,14 +81,14 @@ impl super::Cpu6502 {
     }
 
     /// Absolute with offset from register Y
-    pub fn aby(&mut self, bus: Bus) -> u8 {
+    pub fn aby<B: CpuBus + ?Sized>(&mut self, bus: &mut B) -> u8 {
         let lo = bus.read(self.pc, false) as u16;
         self.pc += 1;
         let hi = bus.read(self.pc, false) as u16;
         self.pc += 1;
 
         self.addr_abs = (hi << 8) | lo;
-        self.addr_abs += self.y as u16;
+        self.addr_abs = self.addr_abs.wrapping_add(self.y as u16);
 
         // Page changed, need more cycles
         if (self.addr_abs & 0xFF00) != (hi << 8) {
@@ -99,7 +99,7 @@ impl super::Cpu6502 {
     }
 
     /// Indirect addressing mode, read address from a pointer
-    pub fn ind(&mut self, bus: Bus) -> u8 {
+    pub fn ind<B: CpuBus + ?Sized>(&mut self, bus: &mut B) -> u8 {
         let ptr_lo = bus.read(self.pc, false) as u16;
         self.pc += 1;
         let ptr_hi = bus.read(self.pc, false) as u16;
@@ -110,7 +110,7 @@ impl super::Cpu6502 {
         let hi = if ptr_lo == 0x00FF {
             bus.read(ptr & 0xFF00, false) as u16
         } else {
-            bus.read(ptr + 1, false) as u16
+            bus.read(ptr.wrapping_add(1), false) as u16
         };
 
         let lo = bus.read(ptr, false) as u16;
@@ -119,7 +119,7 @@ impl super::Cpu6502 {
     }
 
     /// Indirect addressing mode with X offset
-    pub fn izx(&mut self, bus: Bus) -> u8 {
+    pub fn izx<B: CpuBus + ?Sized>(&mut self, bus: &mut B) -> u8 {
         let t = bus.read(self.pc, false) as u16;
         self.pc += 1;
 
@@ -131,7 +131,7 @@ impl super::Cpu6502 {
     }
 
     /// Indirect addressing mode with Y offset
-    pub fn izy(&mut self, bus: Bus) -> u8 {
+    pub fn izy<B: CpuBus + ?Sized>(&mut self, bus: &mut B) -> u8 {
         let t = bus.read(self.pc, false) as u16;
         self.pc += 1;
 
@@ -139,7 +139,7 @@ impl super::Cpu6502 {
         let hi = bus.read((t + 1) & 0x00FF, false) as u16;
 
         self.addr_abs = (hi << 8) | lo;
-        self.addr_abs += self.y as u16;
+        self.addr_abs = self.addr_abs.wrapping_add(self.y as u16);
 
         // Page changed, need more cycles
         if (self.addr_abs & 0xFF00) != (hi << 8) {
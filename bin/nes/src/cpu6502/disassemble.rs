@@ -4,46 +4,47 @@ use std::{collections::HashMap, ops::Range};
 impl Cpu6502 {
     pub fn disassemble(&mut self, bus: Bus, addr_range: Range<u16>) -> HashMap<u16, String> {
         let mut out = HashMap::new();
-        let mut addr = addr_range.start;
+        // Wider than the bus so the operands of the last instruction can't wrap around
+        let mut addr = addr_range.start as u32;
 
-        while addr <= addr_range.end {
-            let line_addr = addr;
+        while addr <= addr_range.end as u32 {
+            let line_addr = addr as u16;
 
-            let opcode = bus.read(addr, true);
+            let opcode = bus.read(addr as u16, true);
             addr += 1;
 
-            if addr >= addr_range.end {
+            if addr >= addr_range.end as u32 {
                 break;
             }
 
             let insn = lookup_instruction(opcode);
 
-            let str = if is_same_addr_mode(insn.addr_mode, Cpu6502::imp) {
+            let str = if insn.addr_mode == AddrMode::Imp {
                 format!("${:04x}: {} {{IMP}}", line_addr, insn.name)
-            } else if is_same_addr_mode(insn.addr_mode, Cpu6502::imm) {
-                let lo = bus.read(addr, true) as u16;
+            } else if insn.addr_mode == AddrMode::Imm {
+                let lo = bus.read(addr as u16, true) as u16;
                 addr += 1;
 
                 format!("${:04x}: {} ${:02x} {{IMM}}", line_addr, insn.name, lo)
-            } else if is_same_addr_mode(insn.addr_mode, Cpu6502::zp0) {
-                let lo = bus.read(addr, true) as u16;
+            } else if insn.addr_mode == AddrMode::Zp0 {
+                let lo = bus.read(addr as u16, true) as u16;
                 addr += 1;
 
                 format!("${:04x}: {} ${:02x} {{ZP0}}", line_addr, insn.name, lo)
-            } else if is_same_addr_mode(insn.addr_mode, Cpu6502::zpx) {
-                let lo = bus.read(addr, true) as u16;
+            } else if insn.addr_mode == AddrMode::Zpx {
+                let lo = bus.read(addr as u16, true) as u16;
                 addr += 1;
 
                 format!("${:04x}: {} ${:02x}, X {{ZPX}}", line_addr, insn.name, lo)
-            } else if is_same_addr_mode(insn.addr_mode, Cpu6502::zpy) {
-                let lo = bus.read(addr, true) as u16;
+            } else if insn.addr_mode == AddrMode::Zpy {
+                let lo = bus.read(addr as u16, true) as u16;
                 addr += 1;
 
                 format!("${:04x}: {} ${:02x}, Y {{ZPY}}", line_addr, insn.name, lo)
-            } else if is_same_addr_mode(insn.addr_mode, Cpu6502::ind) {
-                let lo = bus.read(addr, true) as u16;
+            } else if insn.addr_mode == AddrMode::Ind {
+                let lo = bus.read(addr as u16, true) as u16;
                 addr += 1;
-                let hi = bus.read(addr, true) as u16;
+                let hi = bus.read(addr as u16, true) as u16;
                 addr += 1;
 
                 format!(
@@ -52,20 +53,20 @@ impl Cpu6502 {
                     insn.name,
                     (hi << 8) | lo
                 )
-            } else if is_same_addr_mode(insn.addr_mode, Cpu6502::izx) {
-                let lo = bus.read(addr, true) as u16;
+            } else if insn.addr_mode == AddrMode::Izx {
+                let lo = bus.read(addr as u16, true) as u16;
                 addr += 1;
 
                 format!("${:04x}: {} (${:02x}), X {{IZX}}", line_addr, insn.name, lo)
-            } else if is_same_addr_mode(insn.addr_mode, Cpu6502::izy) {
-                let lo = bus.read(addr, true) as u16;
+            } else if insn.addr_mode == AddrMode::Izy {
+                let lo = bus.read(addr as u16, true) as u16;
                 addr += 1;
 
                 format!("${:04x}: {} (${:2x}), Y {{IZY}}", line_addr, insn.name, lo)
-            } else if is_same_addr_mode(insn.addr_mode, Cpu6502::abs) {
-                let lo = bus.read(addr, true) as u16;
+            } else if insn.addr_mode == AddrMode::Abs {
+                let lo = bus.read(addr as u16, true) as u16;
                 addr += 1;
-                let hi = bus.read(addr, true) as u16;
+                let hi = bus.read(addr as u16, true) as u16;
                 addr += 1;
 
                 format!(
@@ -74,10 +75,10 @@ impl Cpu6502 {
                     insn.name,
                     (hi << 8) | lo
                 )
-            } else if is_same_addr_mode(insn.addr_mode, Cpu6502::abx) {
-                let lo = bus.read(addr, true) as u16;
+            } else if insn.addr_mode == AddrMode::Abx {
+                let lo = bus.read(addr as u16, true) as u16;
                 addr += 1;
-                let hi = bus.read(addr, true) as u16;
+                let hi = bus.read(addr as u16, true) as u16;
                 addr += 1;
 
                 format!(
@@ -86,10 +87,10 @@ impl Cpu6502 {
                     insn.name,
                     (hi << 8) | lo
                 )
-            } else if is_same_addr_mode(insn.addr_mode, Cpu6502::aby) {
-                let lo = bus.read(addr, true) as u16;
+            } else if insn.addr_mode == AddrMode::Aby {
+                let lo = bus.read(addr as u16, true) as u16;
                 addr += 1;
-                let hi = bus.read(addr, true) as u16;
+                let hi = bus.read(addr as u16, true) as u16;
                 addr += 1;
 
                 format!(
@@ -98,8 +99,8 @@ impl Cpu6502 {
                     insn.name,
                     (hi << 8) | lo
                 )
-            } else if is_same_addr_mode(insn.addr_mode, Cpu6502::rel) {
-                let lo = bus.read(addr, true) as i8;
+            } else if insn.addr_mode == AddrMode::Rel {
+                let lo = bus.read(addr as u16, true) as i8;
                 addr += 1;
 
                 format!(
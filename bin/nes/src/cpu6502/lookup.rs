@@ -1,24 +1,184 @@
-use crate::cpu6502::Cpu6502;
-use lazy_static::lazy_static;
+use crate::{cpu6502::Cpu6502, CpuBus};
 
 pub fn lookup_instruction(opcode: u8) -> &'static Instruction {
     &INSN_LOOKUP[opcode as usize]
 }
 
-pub type InsnFunc = fn(&mut Cpu6502, bus: &mut crate::system::SystemBus) -> u8;
+/// The instruction itself, dispatched with a `match` so the bus type stays generic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Adc,
+    And,
+    Asl,
+    Bcc,
+    Bcs,
+    Beq,
+    Bit,
+    Bmi,
+    Bne,
+    Bpl,
+    Brk,
+    Bvc,
+    Bvs,
+    Clc,
+    Cld,
+    Cli,
+    Clv,
+    Cmp,
+    Cpx,
+    Cpy,
+    Dec,
+    Dex,
+    Dey,
+    Eor,
+    Inc,
+    Inx,
+    Iny,
+    Jmp,
+    Jsr,
+    Lda,
+    Ldx,
+    Ldy,
+    Lsr,
+    Nop,
+    Ora,
+    Pha,
+    Php,
+    Pla,
+    Plp,
+    Rol,
+    Ror,
+    Rti,
+    Rts,
+    Sbc,
+    Sec,
+    Sed,
+    Sei,
+    Sta,
+    Stx,
+    Sty,
+    Tax,
+    Tay,
+    Tsx,
+    Txa,
+    Txs,
+    Tya,
+    Xxx,
+}
+
+impl Operation {
+    #[inline]
+    pub fn run<B: CpuBus + ?Sized>(self, cpu: &mut Cpu6502, bus: &mut B) -> u8 {
+        match self {
+            Operation::Adc => cpu.adc(bus),
+            Operation::And => cpu.and(bus),
+            Operation::Asl => cpu.asl(bus),
+            Operation::Bcc => cpu.bcc(bus),
+            Operation::Bcs => cpu.bcs(bus),
+            Operation::Beq => cpu.beq(bus),
+            Operation::Bit => cpu.bit(bus),
+            Operation::Bmi => cpu.bmi(bus),
+            Operation::Bne => cpu.bne(bus),
+            Operation::Bpl => cpu.bpl(bus),
+            Operation::Brk => cpu.brk(bus),
+            Operation::Bvc => cpu.bvc(bus),
+            Operation::Bvs => cpu.bvs(bus),
+            Operation::Clc => cpu.clc(bus),
+            Operation::Cld => cpu.cld(bus),
+            Operation::Cli => cpu.cli(bus),
+            Operation::Clv => cpu.clv(bus),
+            Operation::Cmp => cpu.cmp(bus),
+            Operation::Cpx => cpu.cpx(bus),
+            Operation::Cpy => cpu.cpy(bus),
+            Operation::Dec => cpu.dec(bus),
+            Operation::Dex => cpu.dex(bus),
+            Operation::Dey => cpu.dey(bus),
+            Operation::Eor => cpu.eor(bus),
+            Operation::Inc => cpu.inc(bus),
+            Operation::Inx => cpu.inx(bus),
+            Operation::Iny => cpu.iny(bus),
+            Operation::Jmp => cpu.jmp(bus),
+            Operation::Jsr => cpu.jsr(bus),
+            Operation::Lda => cpu.lda(bus),
+            Operation::Ldx => cpu.ldx(bus),
+            Operation::Ldy => cpu.ldy(bus),
+            Operation::Lsr => cpu.lsr(bus),
+            Operation::Nop => cpu.nop(bus),
+            Operation::Ora => cpu.ora(bus),
+            Operation::Pha => cpu.pha(bus),
+            Operation::Php => cpu.php(bus),
+            Operation::Pla => cpu.pla(bus),
+            Operation::Plp => cpu.plp(bus),
+            Operation::Rol => cpu.rol(bus),
+            Operation::Ror => cpu.ror(bus),
+            Operation::Rti => cpu.rti(bus),
+            Operation::Rts => cpu.rts(bus),
+            Operation::Sbc => cpu.sbc(bus),
+            Operation::Sec => cpu.sec(bus),
+            Operation::Sed => cpu.sed(bus),
+            Operation::Sei => cpu.sei(bus),
+            Operation::Sta => cpu.sta(bus),
+            Operation::Stx => cpu.stx(bus),
+            Operation::Sty => cpu.sty(bus),
+            Operation::Tax => cpu.tax(bus),
+            Operation::Tay => cpu.tay(bus),
+            Operation::Tsx => cpu.tsx(bus),
+            Operation::Txa => cpu.txa(bus),
+            Operation::Txs => cpu.txs(bus),
+            Operation::Tya => cpu.tya(bus),
+            Operation::Xxx => cpu.xxx(bus),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddrMode {
+    Imp,
+    Imm,
+    Zp0,
+    Zpx,
+    Zpy,
+    Rel,
+    Abs,
+    Abx,
+    Aby,
+    Ind,
+    Izx,
+    Izy,
+}
+
+impl AddrMode {
+    #[inline]
+    pub fn run<B: CpuBus + ?Sized>(self, cpu: &mut Cpu6502, bus: &mut B) -> u8 {
+        match self {
+            AddrMode::Imp => cpu.imp(bus),
+            AddrMode::Imm => cpu.imm(bus),
+            AddrMode::Zp0 => cpu.zp0(bus),
+            AddrMode::Zpx => cpu.zpx(bus),
+            AddrMode::Zpy => cpu.zpy(bus),
+            AddrMode::Rel => cpu.rel(bus),
+            AddrMode::Abs => cpu.abs(bus),
+            AddrMode::Abx => cpu.abx(bus),
+            AddrMode::Aby => cpu.aby(bus),
+            AddrMode::Ind => cpu.ind(bus),
+            AddrMode::Izx => cpu.izx(bus),
+            AddrMode::Izy => cpu.izy(bus),
+        }
+    }
+}
 
 pub struct Instruction {
     pub name: &'static str,
-    pub operate: InsnFunc,
-    pub addr_mode: InsnFunc,
+    pub operate: Operation,
+    pub addr_mode: AddrMode,
     pub cycles: u8,
 }
 
 impl Instruction {
-    pub fn new(
+    pub const fn new(
         name: &'static str,
-        operate: InsnFunc,
-        addr_mode: InsnFunc,
+        operate: Operation,
+        addr_mode: AddrMode,
         cycles: u8,
     ) -> Instruction {
         Instruction {
@@ -30,266 +190,266 @@ impl Instruction {
     }
 }
 
-lazy_static! {
-    static ref INSN_LOOKUP: [Instruction; 256] = {
-        type I = Instruction;
-        [
-            I::new("BRK", Cpu6502::brk, Cpu6502::imm, 7),
-            I::new("ORA", Cpu6502::ora, Cpu6502::izx, 6),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 8),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 3),
-            I::new("ORA", Cpu6502::ora, Cpu6502::zp0, 3),
-            I::new("ASL", Cpu6502::asl, Cpu6502::zp0, 5),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 5),
-            I::new("PHP", Cpu6502::php, Cpu6502::imp, 3),
-            I::new("ORA", Cpu6502::ora, Cpu6502::imm, 2),
-            I::new("ASL", Cpu6502::asl, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 4),
-            I::new("ORA", Cpu6502::ora, Cpu6502::abs, 4),
-            I::new("ASL", Cpu6502::asl, Cpu6502::abs, 6),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 6),
-            I::new("BPL", Cpu6502::bpl, Cpu6502::rel, 2),
-            I::new("ORA", Cpu6502::ora, Cpu6502::izy, 5),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 8),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 4),
-            I::new("ORA", Cpu6502::ora, Cpu6502::zpx, 4),
-            I::new("ASL", Cpu6502::asl, Cpu6502::zpx, 6),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 6),
-            I::new("CLC", Cpu6502::clc, Cpu6502::imp, 2),
-            I::new("ORA", Cpu6502::ora, Cpu6502::aby, 4),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 7),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 4),
-            I::new("ORA", Cpu6502::ora, Cpu6502::abx, 4),
-            I::new("ASL", Cpu6502::asl, Cpu6502::abx, 7),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 7),
-            I::new("JSR", Cpu6502::jsr, Cpu6502::abs, 6),
-            I::new("AND", Cpu6502::and, Cpu6502::izx, 6),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 8),
-            I::new("BIT", Cpu6502::bit, Cpu6502::zp0, 3),
-            I::new("AND", Cpu6502::and, Cpu6502::zp0, 3),
-            I::new("ROL", Cpu6502::rol, Cpu6502::zp0, 5),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 5),
-            I::new("PLP", Cpu6502::plp, Cpu6502::imp, 4),
-            I::new("AND", Cpu6502::and, Cpu6502::imm, 2),
-            I::new("ROL", Cpu6502::rol, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 2),
-            I::new("BIT", Cpu6502::bit, Cpu6502::abs, 4),
-            I::new("AND", Cpu6502::and, Cpu6502::abs, 4),
-            I::new("ROL", Cpu6502::rol, Cpu6502::abs, 6),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 6),
-            I::new("BMI", Cpu6502::bmi, Cpu6502::rel, 2),
-            I::new("AND", Cpu6502::and, Cpu6502::izy, 5),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 8),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 4),
-            I::new("AND", Cpu6502::and, Cpu6502::zpx, 4),
-            I::new("ROL", Cpu6502::rol, Cpu6502::zpx, 6),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 6),
-            I::new("SEC", Cpu6502::sec, Cpu6502::imp, 2),
-            I::new("AND", Cpu6502::and, Cpu6502::aby, 4),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 7),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 4),
-            I::new("AND", Cpu6502::and, Cpu6502::abx, 4),
-            I::new("ROL", Cpu6502::rol, Cpu6502::abx, 7),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 7),
-            I::new("RTI", Cpu6502::rti, Cpu6502::imp, 6),
-            I::new("EOR", Cpu6502::eor, Cpu6502::izx, 6),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 8),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 3),
-            I::new("EOR", Cpu6502::eor, Cpu6502::zp0, 3),
-            I::new("LSR", Cpu6502::lsr, Cpu6502::zp0, 5),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 5),
-            I::new("PHA", Cpu6502::pha, Cpu6502::imp, 3),
-            I::new("EOR", Cpu6502::eor, Cpu6502::imm, 2),
-            I::new("LSR", Cpu6502::lsr, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 2),
-            I::new("JMP", Cpu6502::jmp, Cpu6502::abs, 3),
-            I::new("EOR", Cpu6502::eor, Cpu6502::abs, 4),
-            I::new("LSR", Cpu6502::lsr, Cpu6502::abs, 6),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 6),
-            I::new("BVC", Cpu6502::bvc, Cpu6502::rel, 2),
-            I::new("EOR", Cpu6502::eor, Cpu6502::izy, 5),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 8),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 4),
-            I::new("EOR", Cpu6502::eor, Cpu6502::zpx, 4),
-            I::new("LSR", Cpu6502::lsr, Cpu6502::zpx, 6),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 6),
-            I::new("CLI", Cpu6502::cli, Cpu6502::imp, 2),
-            I::new("EOR", Cpu6502::eor, Cpu6502::aby, 4),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 7),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 4),
-            I::new("EOR", Cpu6502::eor, Cpu6502::abx, 4),
-            I::new("LSR", Cpu6502::lsr, Cpu6502::abx, 7),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 7),
-            I::new("RTS", Cpu6502::rts, Cpu6502::imp, 6),
-            I::new("ADC", Cpu6502::adc, Cpu6502::izx, 6),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 8),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 3),
-            I::new("ADC", Cpu6502::adc, Cpu6502::zp0, 3),
-            I::new("ROR", Cpu6502::ror, Cpu6502::zp0, 5),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 5),
-            I::new("PLA", Cpu6502::pla, Cpu6502::imp, 4),
-            I::new("ADC", Cpu6502::adc, Cpu6502::imm, 2),
-            I::new("ROR", Cpu6502::ror, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 2),
-            I::new("JMP", Cpu6502::jmp, Cpu6502::ind, 5),
-            I::new("ADC", Cpu6502::adc, Cpu6502::abs, 4),
-            I::new("ROR", Cpu6502::ror, Cpu6502::abs, 6),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 6),
-            I::new("BVS", Cpu6502::bvs, Cpu6502::rel, 2),
-            I::new("ADC", Cpu6502::adc, Cpu6502::izy, 5),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 8),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 4),
-            I::new("ADC", Cpu6502::adc, Cpu6502::zpx, 4),
-            I::new("ROR", Cpu6502::ror, Cpu6502::zpx, 6),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 6),
-            I::new("SEI", Cpu6502::sei, Cpu6502::imp, 2),
-            I::new("ADC", Cpu6502::adc, Cpu6502::aby, 4),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 7),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 4),
-            I::new("ADC", Cpu6502::adc, Cpu6502::abx, 4),
-            I::new("ROR", Cpu6502::ror, Cpu6502::abx, 7),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 7),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 2),
-            I::new("STA", Cpu6502::sta, Cpu6502::izx, 6),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 6),
-            I::new("STY", Cpu6502::sty, Cpu6502::zp0, 3),
-            I::new("STA", Cpu6502::sta, Cpu6502::zp0, 3),
-            I::new("STX", Cpu6502::stx, Cpu6502::zp0, 3),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 3),
-            I::new("DEY", Cpu6502::dey, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 2),
-            I::new("TXA", Cpu6502::txa, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 2),
-            I::new("STY", Cpu6502::sty, Cpu6502::abs, 4),
-            I::new("STA", Cpu6502::sta, Cpu6502::abs, 4),
-            I::new("STX", Cpu6502::stx, Cpu6502::abs, 4),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 4),
-            I::new("BCC", Cpu6502::bcc, Cpu6502::rel, 2),
-            I::new("STA", Cpu6502::sta, Cpu6502::izy, 6),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 6),
-            I::new("STY", Cpu6502::sty, Cpu6502::zpx, 4),
-            I::new("STA", Cpu6502::sta, Cpu6502::zpx, 4),
-            I::new("STX", Cpu6502::stx, Cpu6502::zpy, 4),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 4),
-            I::new("TYA", Cpu6502::tya, Cpu6502::imp, 2),
-            I::new("STA", Cpu6502::sta, Cpu6502::aby, 5),
-            I::new("TXS", Cpu6502::txs, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 5),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 5),
-            I::new("STA", Cpu6502::sta, Cpu6502::abx, 5),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 5),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 5),
-            I::new("LDY", Cpu6502::ldy, Cpu6502::imm, 2),
-            I::new("LDA", Cpu6502::lda, Cpu6502::izx, 6),
-            I::new("LDX", Cpu6502::ldx, Cpu6502::imm, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 6),
-            I::new("LDY", Cpu6502::ldy, Cpu6502::zp0, 3),
-            I::new("LDA", Cpu6502::lda, Cpu6502::zp0, 3),
-            I::new("LDX", Cpu6502::ldx, Cpu6502::zp0, 3),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 3),
-            I::new("TAY", Cpu6502::tay, Cpu6502::imp, 2),
-            I::new("LDA", Cpu6502::lda, Cpu6502::imm, 2),
-            I::new("TAX", Cpu6502::tax, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 2),
-            I::new("LDY", Cpu6502::ldy, Cpu6502::abs, 4),
-            I::new("LDA", Cpu6502::lda, Cpu6502::abs, 4),
-            I::new("LDX", Cpu6502::ldx, Cpu6502::abs, 4),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 4),
-            I::new("BCS", Cpu6502::bcs, Cpu6502::rel, 2),
-            I::new("LDA", Cpu6502::lda, Cpu6502::izy, 5),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 5),
-            I::new("LDY", Cpu6502::ldy, Cpu6502::zpx, 4),
-            I::new("LDA", Cpu6502::lda, Cpu6502::zpx, 4),
-            I::new("LDX", Cpu6502::ldx, Cpu6502::zpy, 4),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 4),
-            I::new("CLV", Cpu6502::clv, Cpu6502::imp, 2),
-            I::new("LDA", Cpu6502::lda, Cpu6502::aby, 4),
-            I::new("TSX", Cpu6502::tsx, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 4),
-            I::new("LDY", Cpu6502::ldy, Cpu6502::abx, 4),
-            I::new("LDA", Cpu6502::lda, Cpu6502::abx, 4),
-            I::new("LDX", Cpu6502::ldx, Cpu6502::aby, 4),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 4),
-            I::new("CPY", Cpu6502::cpy, Cpu6502::imm, 2),
-            I::new("CMP", Cpu6502::cmp, Cpu6502::izx, 6),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 8),
-            I::new("CPY", Cpu6502::cpy, Cpu6502::zp0, 3),
-            I::new("CMP", Cpu6502::cmp, Cpu6502::zp0, 3),
-            I::new("DEC", Cpu6502::dec, Cpu6502::zp0, 5),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 5),
-            I::new("INY", Cpu6502::iny, Cpu6502::imp, 2),
-            I::new("CMP", Cpu6502::cmp, Cpu6502::imm, 2),
-            I::new("DEX", Cpu6502::dex, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 2),
-            I::new("CPY", Cpu6502::cpy, Cpu6502::abs, 4),
-            I::new("CMP", Cpu6502::cmp, Cpu6502::abs, 4),
-            I::new("DEC", Cpu6502::dec, Cpu6502::abs, 6),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 6),
-            I::new("BNE", Cpu6502::bne, Cpu6502::rel, 2),
-            I::new("CMP", Cpu6502::cmp, Cpu6502::izy, 5),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 8),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 4),
-            I::new("CMP", Cpu6502::cmp, Cpu6502::zpx, 4),
-            I::new("DEC", Cpu6502::dec, Cpu6502::zpx, 6),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 6),
-            I::new("CLD", Cpu6502::cld, Cpu6502::imp, 2),
-            I::new("CMP", Cpu6502::cmp, Cpu6502::aby, 4),
-            I::new("NOP", Cpu6502::nop, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 7),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 4),
-            I::new("CMP", Cpu6502::cmp, Cpu6502::abx, 4),
-            I::new("DEC", Cpu6502::dec, Cpu6502::abx, 7),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 7),
-            I::new("CPX", Cpu6502::cpx, Cpu6502::imm, 2),
-            I::new("SBC", Cpu6502::sbc, Cpu6502::izx, 6),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 8),
-            I::new("CPX", Cpu6502::cpx, Cpu6502::zp0, 3),
-            I::new("SBC", Cpu6502::sbc, Cpu6502::zp0, 3),
-            I::new("INC", Cpu6502::inc, Cpu6502::zp0, 5),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 5),
-            I::new("INX", Cpu6502::inx, Cpu6502::imp, 2),
-            I::new("SBC", Cpu6502::sbc, Cpu6502::imm, 2),
-            I::new("NOP", Cpu6502::nop, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::sbc, Cpu6502::imp, 2),
-            I::new("CPX", Cpu6502::cpx, Cpu6502::abs, 4),
-            I::new("SBC", Cpu6502::sbc, Cpu6502::abs, 4),
-            I::new("INC", Cpu6502::inc, Cpu6502::abs, 6),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 6),
-            I::new("BEQ", Cpu6502::beq, Cpu6502::rel, 2),
-            I::new("SBC", Cpu6502::sbc, Cpu6502::izy, 5),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 8),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 4),
-            I::new("SBC", Cpu6502::sbc, Cpu6502::zpx, 4),
-            I::new("INC", Cpu6502::inc, Cpu6502::zpx, 6),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 6),
-            I::new("SED", Cpu6502::sed, Cpu6502::imp, 2),
-            I::new("SBC", Cpu6502::sbc, Cpu6502::aby, 4),
-            I::new("NOP", Cpu6502::nop, Cpu6502::imp, 2),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 7),
-            I::new("???", Cpu6502::nop, Cpu6502::imp, 4),
-            I::new("SBC", Cpu6502::sbc, Cpu6502::abx, 4),
-            I::new("INC", Cpu6502::inc, Cpu6502::abx, 7),
-            I::new("???", Cpu6502::xxx, Cpu6502::imp, 7),
-        ]
-    };
-}
+static INSN_LOOKUP: [Instruction; 256] = {
+    use AddrMode as Mode;
+    use Instruction as I;
+    use Operation as Op;
+    [
+        I::new("BRK", Op::Brk, Mode::Imm, 7),
+        I::new("ORA", Op::Ora, Mode::Izx, 6),
+        I::new("???", Op::Xxx, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 8),
+        I::new("???", Op::Nop, Mode::Imp, 3),
+        I::new("ORA", Op::Ora, Mode::Zp0, 3),
+        I::new("ASL", Op::Asl, Mode::Zp0, 5),
+        I::new("???", Op::Xxx, Mode::Imp, 5),
+        I::new("PHP", Op::Php, Mode::Imp, 3),
+        I::new("ORA", Op::Ora, Mode::Imm, 2),
+        I::new("ASL", Op::Asl, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 2),
+        I::new("???", Op::Nop, Mode::Imp, 4),
+        I::new("ORA", Op::Ora, Mode::Abs, 4),
+        I::new("ASL", Op::Asl, Mode::Abs, 6),
+        I::new("???", Op::Xxx, Mode::Imp, 6),
+        I::new("BPL", Op::Bpl, Mode::Rel, 2),
+        I::new("ORA", Op::Ora, Mode::Izy, 5),
+        I::new("???", Op::Xxx, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 8),
+        I::new("???", Op::Nop, Mode::Imp, 4),
+        I::new("ORA", Op::Ora, Mode::Zpx, 4),
+        I::new("ASL", Op::Asl, Mode::Zpx, 6),
+        I::new("???", Op::Xxx, Mode::Imp, 6),
+        I::new("CLC", Op::Clc, Mode::Imp, 2),
+        I::new("ORA", Op::Ora, Mode::Aby, 4),
+        I::new("???", Op::Nop, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 7),
+        I::new("???", Op::Nop, Mode::Imp, 4),
+        I::new("ORA", Op::Ora, Mode::Abx, 4),
+        I::new("ASL", Op::Asl, Mode::Abx, 7),
+        I::new("???", Op::Xxx, Mode::Imp, 7),
+        I::new("JSR", Op::Jsr, Mode::Abs, 6),
+        I::new("AND", Op::And, Mode::Izx, 6),
+        I::new("???", Op::Xxx, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 8),
+        I::new("BIT", Op::Bit, Mode::Zp0, 3),
+        I::new("AND", Op::And, Mode::Zp0, 3),
+        I::new("ROL", Op::Rol, Mode::Zp0, 5),
+        I::new("???", Op::Xxx, Mode::Imp, 5),
+        I::new("PLP", Op::Plp, Mode::Imp, 4),
+        I::new("AND", Op::And, Mode::Imm, 2),
+        I::new("ROL", Op::Rol, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 2),
+        I::new("BIT", Op::Bit, Mode::Abs, 4),
+        I::new("AND", Op::And, Mode::Abs, 4),
+        I::new("ROL", Op::Rol, Mode::Abs, 6),
+        I::new("???", Op::Xxx, Mode::Imp, 6),
+        I::new("BMI", Op::Bmi, Mode::Rel, 2),
+        I::new("AND", Op::And, Mode::Izy, 5),
+        I::new("???", Op::Xxx, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 8),
+        I::new("???", Op::Nop, Mode::Imp, 4),
+        I::new("AND", Op::And, Mode::Zpx, 4),
+        I::new("ROL", Op::Rol, Mode::Zpx, 6),
+        I::new("???", Op::Xxx, Mode::Imp, 6),
+        I::new("SEC", Op::Sec, Mode::Imp, 2),
+        I::new("AND", Op::And, Mode::Aby, 4),
+        I::new("???", Op::Nop, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 7),
+        I::new("???", Op::Nop, Mode::Imp, 4),
+        I::new("AND", Op::And, Mode::Abx, 4),
+        I::new("ROL", Op::Rol, Mode::Abx, 7),
+        I::new("???", Op::Xxx, Mode::Imp, 7),
+        I::new("RTI", Op::Rti, Mode::Imp, 6),
+        I::new("EOR", Op::Eor, Mode::Izx, 6),
+        I::new("???", Op::Xxx, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 8),
+        I::new("???", Op::Nop, Mode::Imp, 3),
+        I::new("EOR", Op::Eor, Mode::Zp0, 3),
+        I::new("LSR", Op::Lsr, Mode::Zp0, 5),
+        I::new("???", Op::Xxx, Mode::Imp, 5),
+        I::new("PHA", Op::Pha, Mode::Imp, 3),
+        I::new("EOR", Op::Eor, Mode::Imm, 2),
+        I::new("LSR", Op::Lsr, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 2),
+        I::new("JMP", Op::Jmp, Mode::Abs, 3),
+        I::new("EOR", Op::Eor, Mode::Abs, 4),
+        I::new("LSR", Op::Lsr, Mode::Abs, 6),
+        I::new("???", Op::Xxx, Mode::Imp, 6),
+        I::new("BVC", Op::Bvc, Mode::Rel, 2),
+        I::new("EOR", Op::Eor, Mode::Izy, 5),
+        I::new("???", Op::Xxx, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 8),
+        I::new("???", Op::Nop, Mode::Imp, 4),
+        I::new("EOR", Op::Eor, Mode::Zpx, 4),
+        I::new("LSR", Op::Lsr, Mode::Zpx, 6),
+        I::new("???", Op::Xxx, Mode::Imp, 6),
+        I::new("CLI", Op::Cli, Mode::Imp, 2),
+        I::new("EOR", Op::Eor, Mode::Aby, 4),
+        I::new("???", Op::Nop, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 7),
+        I::new("???", Op::Nop, Mode::Imp, 4),
+        I::new("EOR", Op::Eor, Mode::Abx, 4),
+        I::new("LSR", Op::Lsr, Mode::Abx, 7),
+        I::new("???", Op::Xxx, Mode::Imp, 7),
+        I::new("RTS", Op::Rts, Mode::Imp, 6),
+        I::new("ADC", Op::Adc, Mode::Izx, 6),
+        I::new("???", Op::Xxx, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 8),
+        I::new("???", Op::Nop, Mode::Imp, 3),
+        I::new("ADC", Op::Adc, Mode::Zp0, 3),
+        I::new("ROR", Op::Ror, Mode::Zp0, 5),
+        I::new("???", Op::Xxx, Mode::Imp, 5),
+        I::new("PLA", Op::Pla, Mode::Imp, 4),
+        I::new("ADC", Op::Adc, Mode::Imm, 2),
+        I::new("ROR", Op::Ror, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 2),
+        I::new("JMP", Op::Jmp, Mode::Ind, 5),
+        I::new("ADC", Op::Adc, Mode::Abs, 4),
+        I::new("ROR", Op::Ror, Mode::Abs, 6),
+        I::new("???", Op::Xxx, Mode::Imp, 6),
+        I::new("BVS", Op::Bvs, Mode::Rel, 2),
+        I::new("ADC", Op::Adc, Mode::Izy, 5),
+        I::new("???", Op::Xxx, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 8),
+        I::new("???", Op::Nop, Mode::Imp, 4),
+        I::new("ADC", Op::Adc, Mode::Zpx, 4),
+        I::new("ROR", Op::Ror, Mode::Zpx, 6),
+        I::new("???", Op::Xxx, Mode::Imp, 6),
+        I::new("SEI", Op::Sei, Mode::Imp, 2),
+        I::new("ADC", Op::Adc, Mode::Aby, 4),
+        I::new("???", Op::Nop, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 7),
+        I::new("???", Op::Nop, Mode::Imp, 4),
+        I::new("ADC", Op::Adc, Mode::Abx, 4),
+        I::new("ROR", Op::Ror, Mode::Abx, 7),
+        I::new("???", Op::Xxx, Mode::Imp, 7),
+        I::new("???", Op::Nop, Mode::Imp, 2),
+        I::new("STA", Op::Sta, Mode::Izx, 6),
+        I::new("???", Op::Nop, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 6),
+        I::new("STY", Op::Sty, Mode::Zp0, 3),
+        I::new("STA", Op::Sta, Mode::Zp0, 3),
+        I::new("STX", Op::Stx, Mode::Zp0, 3),
+        I::new("???", Op::Xxx, Mode::Imp, 3),
+        I::new("DEY", Op::Dey, Mode::Imp, 2),
+        I::new("???", Op::Nop, Mode::Imp, 2),
+        I::new("TXA", Op::Txa, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 2),
+        I::new("STY", Op::Sty, Mode::Abs, 4),
+        I::new("STA", Op::Sta, Mode::Abs, 4),
+        I::new("STX", Op::Stx, Mode::Abs, 4),
+        I::new("???", Op::Xxx, Mode::Imp, 4),
+        I::new("BCC", Op::Bcc, Mode::Rel, 2),
+        I::new("STA", Op::Sta, Mode::Izy, 6),
+        I::new("???", Op::Xxx, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 6),
+        I::new("STY", Op::Sty, Mode::Zpx, 4),
+        I::new("STA", Op::Sta, Mode::Zpx, 4),
+        I::new("STX", Op::Stx, Mode::Zpy, 4),
+        I::new("???", Op::Xxx, Mode::Imp, 4),
+        I::new("TYA", Op::Tya, Mode::Imp, 2),
+        I::new("STA", Op::Sta, Mode::Aby, 5),
+        I::new("TXS", Op::Txs, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 5),
+        I::new("???", Op::Nop, Mode::Imp, 5),
+        I::new("STA", Op::Sta, Mode::Abx, 5),
+        I::new("???", Op::Xxx, Mode::Imp, 5),
+        I::new("???", Op::Xxx, Mode::Imp, 5),
+        I::new("LDY", Op::Ldy, Mode::Imm, 2),
+        I::new("LDA", Op::Lda, Mode::Izx, 6),
+        I::new("LDX", Op::Ldx, Mode::Imm, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 6),
+        I::new("LDY", Op::Ldy, Mode::Zp0, 3),
+        I::new("LDA", Op::Lda, Mode::Zp0, 3),
+        I::new("LDX", Op::Ldx, Mode::Zp0, 3),
+        I::new("???", Op::Xxx, Mode::Imp, 3),
+        I::new("TAY", Op::Tay, Mode::Imp, 2),
+        I::new("LDA", Op::Lda, Mode::Imm, 2),
+        I::new("TAX", Op::Tax, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 2),
+        I::new("LDY", Op::Ldy, Mode::Abs, 4),
+        I::new("LDA", Op::Lda, Mode::Abs, 4),
+        I::new("LDX", Op::Ldx, Mode::Abs, 4),
+        I::new("???", Op::Xxx, Mode::Imp, 4),
+        I::new("BCS", Op::Bcs, Mode::Rel, 2),
+        I::new("LDA", Op::Lda, Mode::Izy, 5),
+        I::new("???", Op::Xxx, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 5),
+        I::new("LDY", Op::Ldy, Mode::Zpx, 4),
+        I::new("LDA", Op::Lda, Mode::Zpx, 4),
+        I::new("LDX", Op::Ldx, Mode::Zpy, 4),
+        I::new("???", Op::Xxx, Mode::Imp, 4),
+        I::new("CLV", Op::Clv, Mode::Imp, 2),
+        I::new("LDA", Op::Lda, Mode::Aby, 4),
+        I::new("TSX", Op::Tsx, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 4),
+        I::new("LDY", Op::Ldy, Mode::Abx, 4),
+        I::new("LDA", Op::Lda, Mode::Abx, 4),
+        I::new("LDX", Op::Ldx, Mode::Aby, 4),
+        I::new("???", Op::Xxx, Mode::Imp, 4),
+        I::new("CPY", Op::Cpy, Mode::Imm, 2),
+        I::new("CMP", Op::Cmp, Mode::Izx, 6),
+        I::new("???", Op::Nop, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 8),
+        I::new("CPY", Op::Cpy, Mode::Zp0, 3),
+        I::new("CMP", Op::Cmp, Mode::Zp0, 3),
+        I::new("DEC", Op::Dec, Mode::Zp0, 5),
+        I::new("???", Op::Xxx, Mode::Imp, 5),
+        I::new("INY", Op::Iny, Mode::Imp, 2),
+        I::new("CMP", Op::Cmp, Mode::Imm, 2),
+        I::new("DEX", Op::Dex, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 2),
+        I::new("CPY", Op::Cpy, Mode::Abs, 4),
+        I::new("CMP", Op::Cmp, Mode::Abs, 4),
+        I::new("DEC", Op::Dec, Mode::Abs, 6),
+        I::new("???", Op::Xxx, Mode::Imp, 6),
+        I::new("BNE", Op::Bne, Mode::Rel, 2),
+        I::new("CMP", Op::Cmp, Mode::Izy, 5),
+        I::new("???", Op::Xxx, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 8),
+        I::new("???", Op::Nop, Mode::Imp, 4),
+        I::new("CMP", Op::Cmp, Mode::Zpx, 4),
+        I::new("DEC", Op::Dec, Mode::Zpx, 6),
+        I::new("???", Op::Xxx, Mode::Imp, 6),
+        I::new("CLD", Op::Cld, Mode::Imp, 2),
+        I::new("CMP", Op::Cmp, Mode::Aby, 4),
+        I::new("NOP", Op::Nop, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 7),
+        I::new("???", Op::Nop, Mode::Imp, 4),
+        I::new("CMP", Op::Cmp, Mode::Abx, 4),
+        I::new("DEC", Op::Dec, Mode::Abx, 7),
+        I::new("???", Op::Xxx, Mode::Imp, 7),
+        I::new("CPX", Op::Cpx, Mode::Imm, 2),
+        I::new("SBC", Op::Sbc, Mode::Izx, 6),
+        I::new("???", Op::Nop, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 8),
+        I::new("CPX", Op::Cpx, Mode::Zp0, 3),
+        I::new("SBC", Op::Sbc, Mode::Zp0, 3),
+        I::new("INC", Op::Inc, Mode::Zp0, 5),
+        I::new("???", Op::Xxx, Mode::Imp, 5),
+        I::new("INX", Op::Inx, Mode::Imp, 2),
+        I::new("SBC", Op::Sbc, Mode::Imm, 2),
+        I::new("NOP", Op::Nop, Mode::Imp, 2),
+        I::new("???", Op::Sbc, Mode::Imp, 2),
+        I::new("CPX", Op::Cpx, Mode::Abs, 4),
+        I::new("SBC", Op::Sbc, Mode::Abs, 4),
+        I::new("INC", Op::Inc, Mode::Abs, 6),
+        I::new("???", Op::Xxx, Mode::Imp, 6),
+        I::new("BEQ", Op::Beq, Mode::Rel, 2),
+        I::new("SBC", Op::Sbc, Mode::Izy, 5),
+        I::new("???", Op::Xxx, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 8),
+        I::new("???", Op::Nop, Mode::Imp, 4),
+        I::new("SBC", Op::Sbc, Mode::Zpx, 4),
+        I::new("INC", Op::Inc, Mode::Zpx, 6),
+        I::new("???", Op::Xxx, Mode::Imp, 6),
+        I::new("SED", Op::Sed, Mode::Imp, 2),
+        I::new("SBC", Op::Sbc, Mode::Aby, 4),
+        I::new("NOP", Op::Nop, Mode::Imp, 2),
+        I::new("???", Op::Xxx, Mode::Imp, 7),
+        I::new("???", Op::Nop, Mode::Imp, 4),
+        I::new("SBC", Op::Sbc, Mode::Abx, 4),
+        I::new("INC", Op::Inc, Mode::Abx, 7),
+        I::new("???", Op::Xxx, Mode::Imp, 7),
+    ]
+};
@@ -4,7 +4,7 @@ pub mod instruction;
 pub mod lookup;
 
 use crate::*;
-use lookup::{lookup_instruction, InsnFunc};
+use lookup::{lookup_instruction, AddrMode};
 
 pub enum Flags {
     /// Carry Bit
@@ -128,16 +128,16 @@ impl Cpu6502 {
 }
 
 impl crate::Device for Cpu6502 {
-    fn tick(&mut self, bus: Bus) {
+    fn tick<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         if self.cycles == 0 {
             self.opcode = bus.read(self.pc, false);
-            self.pc += 1;
+            self.pc = self.pc.wrapping_add(1);
 
             let insn = lookup_instruction(self.opcode);
             self.cycles = insn.cycles;
 
-            let addr_need_more_cycles = (insn.addr_mode)(self, bus);
-            let oper_need_more_cycles = (insn.operate)(self, bus);
+            let addr_need_more_cycles = insn.addr_mode.run(self, bus);
+            let oper_need_more_cycles = insn.operate.run(self, bus);
             self.cycles += addr_need_more_cycles & oper_need_more_cycles;
         }
 
@@ -146,7 +146,7 @@ impl crate::Device for Cpu6502 {
 }
 
 impl Cpu6502 {
-    pub fn reset(&mut self, bus: Bus) {
+    pub fn reset<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         self.a = 0;
         self.x = 0;
         self.y = 0;
@@ -165,7 +165,40 @@ impl Cpu6502 {
         self.cycles = 8;
     }
 
-    pub fn interrupt_requested(&mut self, bus: Bus) {
+    /// Call the subroutine at `addr` from the host side, as if a JSR
+    /// at `return_addr - 3` was executed, with A and X loaded as arguments.
+    ///
+    /// The routine is done once the program counter reach `return_addr`
+    pub fn call_subroutine<B: CpuBus + ?Sized>(
+        &mut self,
+        bus: &mut B,
+        addr: u16,
+        return_addr: u16,
+        a: u8,
+        x: u8,
+    ) {
+        self.a = a;
+        self.x = x;
+
+        // Same as JSR, the return address minus one is pushed
+        let ret = return_addr.wrapping_sub(1);
+        bus.write(
+            Self::BASE_STACK_PTR + self.stkp as u16,
+            ((ret >> 8) & 0x00FF) as u8,
+        );
+        self.stkp = self.stkp.wrapping_sub(1);
+
+        bus.write(
+            Self::BASE_STACK_PTR + self.stkp as u16,
+            (ret & 0x00FF) as u8,
+        );
+        self.stkp = self.stkp.wrapping_sub(1);
+
+        self.pc = addr;
+        self.cycles = 0;
+    }
+
+    pub fn interrupt_requested<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         // Interupt is disabled
         if self.flag(Flags::I) == 1 {
             return;
@@ -176,13 +209,13 @@ impl Cpu6502 {
             Self::BASE_STACK_PTR + self.stkp as u16,
             ((self.pc >> 8) & 0x00FF) as u8,
         );
-        self.stkp -= 1;
+        self.stkp = self.stkp.wrapping_sub(1);
 
         bus.write(
             Self::BASE_STACK_PTR + self.stkp as u16,
             (self.pc & 0x00FF) as u8,
         );
-        self.stkp -= 1;
+        self.stkp = self.stkp.wrapping_sub(1);
 
         self.set_flag(Flags::B, false);
         self.set_flag(Flags::U, true);
@@ -190,7 +223,7 @@ impl Cpu6502 {
 
         // Store status register
         bus.write(Self::BASE_STACK_PTR + self.stkp as u16, self.status);
-        self.stkp -= 1;
+        self.stkp = self.stkp.wrapping_sub(1);
 
         self.addr_abs = Self::INTERUPT_PC;
         let lo = bus.read(self.addr_abs + 0, false) as u16;
@@ -200,19 +233,19 @@ impl Cpu6502 {
         self.cycles = 7;
     }
 
-    pub fn non_maskable_interrupt(&mut self, bus: Bus) {
+    pub fn non_maskable_interrupt<B: CpuBus + ?Sized>(&mut self, bus: &mut B) {
         // Store program counter, which is u16 so its take 2 write
         bus.write(
             Self::BASE_STACK_PTR + self.stkp as u16,
             ((self.pc >> 8) & 0x00FF) as u8,
         );
-        self.stkp -= 1;
+        self.stkp = self.stkp.wrapping_sub(1);
 
         bus.write(
             Self::BASE_STACK_PTR + self.stkp as u16,
             (self.pc & 0x00FF) as u8,
         );
-        self.stkp -= 1;
+        self.stkp = self.stkp.wrapping_sub(1);
 
         self.set_flag(Flags::B, false);
         self.set_flag(Flags::U, true);
@@ -220,7 +253,7 @@ impl Cpu6502 {
 
         // Store status register
         bus.write(Self::BASE_STACK_PTR + self.stkp as u16, self.status);
-        self.stkp -= 1;
+        self.stkp = self.stkp.wrapping_sub(1);
 
         self.addr_abs = Self::NON_MASKABLE_INTERUPT_PC;
         let lo = bus.read(self.addr_abs + 0, false) as u16;
@@ -231,8 +264,89 @@ impl Cpu6502 {
     }
 }
 
-/// Pointer comparison to check if two addressing mode is the same
-#[inline]
-pub fn is_same_addr_mode(a: InsnFunc, b: InsnFunc) -> bool {
-    (a as *const InsnFunc).eq(&(b as *const InsnFunc))
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flat 64KB of RAM
+    struct TestBus([u8; 0x10000]);
+
+    impl CpuBus for TestBus {
+        fn read(&mut self, addr: u16, _readonly: bool) -> u8 {
+            self.0[addr as usize]
+        }
+
+        fn write(&mut self, addr: u16, data: u8) {
+            self.0[addr as usize] = data;
+        }
+    }
+
+    /// Reset then run `instructions` instructions
+    fn run<B: CpuBus + ?Sized>(bus: &mut B, instructions: usize) -> Cpu6502 {
+        let mut cpu = Cpu6502::new();
+        cpu.reset(bus);
+        for _ in 0..=instructions {
+            run_instruction(&mut cpu, bus);
+        }
+        cpu
+    }
+
+    fn run_instruction<B: CpuBus + ?Sized>(cpu: &mut Cpu6502, bus: &mut B) {
+        loop {
+            crate::Device::tick(cpu, bus);
+            if cpu.complete() {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn same_program_on_any_bus() {
+        let mut bus = TestBus([0u8; 0x10000]);
+        // LDX #$05; LDA #$00; loop: CLC; ADC #$03; DEX; BNE loop; STA $10
+        let program = [
+            0xA2, 0x05, 0xA9, 0x00, 0x18, 0x69, 0x03, 0xCA, 0xD0, 0xFA, 0x85, 0x10,
+        ];
+        bus.0[0x8000..0x8000 + program.len()].copy_from_slice(&program);
+        bus.0[0xFFFD] = 0x80;
+        let mut erased = TestBus(bus.0);
+
+        let instructions = 2 + 5 * 4 + 1;
+        let cpu = run(&mut bus, instructions);
+        assert_eq!(bus.0[0x10], 15);
+        assert_eq!(cpu.register_x(), 0);
+        assert_eq!(cpu.program_counter(), 0x800C);
+
+        // The type erased bus of the disassembler runs the same code
+        let erased_bus: Bus = &mut erased;
+        let erased_cpu = run(erased_bus, instructions);
+        assert_eq!(erased.0[..], bus.0[..]);
+        assert_eq!(erased_cpu.register_a(), cpu.register_a());
+    }
+
+    #[test]
+    fn nestest_official_opcodes() {
+        let rom = std::fs::read(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../res/nes/nestest.nes"
+        ))
+        .unwrap();
+        let prg = &rom[16..16 + 0x4000];
+        let mut bus = TestBus([0u8; 0x10000]);
+        bus.0[0x8000..0xC000].copy_from_slice(prg);
+        bus.0[0xC000..].copy_from_slice(prg);
+
+        // Automation mode start at $C000, the unofficial opcodes are tested from $C6BD
+        let mut cpu = run(&mut bus, 0);
+        cpu.pc = 0xC000;
+        let mut instructions = 0;
+        while cpu.program_counter() != 0xC6BD {
+            run_instruction(&mut cpu, &mut bus);
+            instructions += 1;
+            assert!(instructions < 10_000, "stuck at ${:04x}", cpu.pc);
+        }
+
+        // Only copied to $02 once the unofficial ones are done too
+        assert_eq!(bus.0[0x00], 0x00, "error code of the last failed test");
+    }
 }
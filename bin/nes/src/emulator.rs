@@ -1,12 +1,32 @@
 use std::{collections::HashMap, ops::Range};
 
-use crate::{cpu6502::Cpu6502, ppu2C02::Ppu2C02, system::SystemBus, Device};
+use crate::{
+    cartridge::{Cartridge, CartridgeState, Region},
+    controller::{Buttons, Controller},
+    cpu6502::Cpu6502,
+    ppu2C02::{Ppu2C02, PpuState},
+    system::SystemBus,
+    Device,
+};
 
-#[derive(Debug, Default)]
+/// Snapshot of what the machine can change, see [`Emulator::save_state`]. The ROM and the
+/// rendered images are left out, netplay take one of these every frame.
+#[derive(Debug, Clone)]
+pub struct SaveState {
+    cpu: Cpu6502,
+    ram: [u8; 2 * 1024],
+    ppu: PpuState,
+    controllers: [Controller; 2],
+    cartridge: Option<CartridgeState>,
+    clock_counter: u32,
+}
+
+#[derive(Debug, Clone)]
 pub struct Emulator {
     cpu: Cpu6502,
     system_bus: SystemBus,
     clock_counter: u32,
+    region: Region,
 }
 
 impl Emulator {
@@ -15,6 +35,7 @@ impl Emulator {
             cpu: Cpu6502::new(),
             system_bus: SystemBus::new(),
             clock_counter: 0,
+            region: Region::Ntsc,
         }
     }
 
@@ -22,11 +43,29 @@ impl Emulator {
         let bus = &mut self.system_bus;
 
         bus.ppu.tick();
-        if self.clock_counter % 3 == 0 {
+        // 3 PPU dots per CPU cycle on NTSC, 3.2 on PAL
+        let cpu_cycle = match self.region {
+            Region::Ntsc => self.clock_counter.is_multiple_of(3),
+            Region::Pal => self.clock_counter % 16 * 5 % 16 < 5,
+        };
+        if cpu_cycle {
             self.cpu.tick(bus);
         }
+        if bus.ppu.take_nmi() {
+            self.cpu.non_maskable_interrupt(bus);
+        }
+
+        self.clock_counter = self.clock_counter.wrapping_add(1);
+    }
 
-        self.clock_counter += 1;
+    /// Run until the PPU finish the current frame
+    pub fn run_frame(&mut self) {
+        loop {
+            self.tick();
+            if self.system_bus.ppu.take_frame_complete() {
+                break;
+            }
+        }
     }
 
     pub fn disassemble(&mut self, addr_range: Range<u16>) -> HashMap<u16, String> {
@@ -42,6 +81,56 @@ impl Emulator {
         &self.system_bus.ppu
     }
 
+    /// System RAM, handy to tell if two emulators are still in sync
+    pub fn ram(&self) -> &[u8] {
+        &self.system_bus.ram
+    }
+
+    /// Set the buttons held on the controller plugged in `port` (0 or 1)
+    pub fn set_buttons(&mut self, port: usize, buttons: Buttons) {
+        self.system_bus.controllers[port].set_buttons(buttons);
+    }
+
+    pub fn save_state(&self) -> SaveState {
+        let bus = &self.system_bus;
+        SaveState {
+            cpu: self.cpu,
+            ram: bus.ram,
+            ppu: bus.ppu.save_state(),
+            controllers: bus.controllers,
+            cartridge: bus.cartridge().map(Cartridge::save_state),
+            clock_counter: self.clock_counter,
+        }
+    }
+
+    /// Restore a state saved from this emulator, or one running the same ROM
+    pub fn load_state(&mut self, state: &SaveState) {
+        let bus = &mut self.system_bus;
+        self.cpu = state.cpu;
+        bus.ram = state.ram;
+        bus.ppu.load_state(&state.ppu);
+        bus.controllers = state.controllers;
+        if let (Some(cartridge), Some(saved)) = (bus.cartridge_mut(), &state.cartridge) {
+            cartridge.load_state(saved);
+        }
+        self.clock_counter = state.clock_counter;
+    }
+
+    pub fn insert_cartridge(&mut self, cartridge: Cartridge) {
+        self.region = cartridge.region();
+        self.system_bus.ppu.set_pal(self.region == Region::Pal);
+        self.system_bus.insert_cartridge(cartridge);
+    }
+
+    /// Timing of the console, from the cartridge
+    pub fn region(&self) -> Region {
+        self.region
+    }
+
+    pub fn cartridge(&self) -> Option<&Cartridge> {
+        self.system_bus.cartridge()
+    }
+
     pub fn reset(&mut self) {
         let bus = &mut self.system_bus;
         self.cpu.reset(bus);
@@ -58,3 +147,73 @@ impl Emulator {
         }
     }
 }
+
+impl Default for Emulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// nestest draw its menu with ASCII tiles, one row of the first name table as text
+    fn name_table_row(emulator: &Emulator, row: usize) -> String {
+        emulator.ppu().name_table[0][row * 32..(row + 1) * 32]
+            .iter()
+            .map(|&tile| tile as char)
+            .collect()
+    }
+
+    fn run_frames(emulator: &mut Emulator, buttons: Buttons, frames: u32) {
+        emulator.set_buttons(0, buttons);
+        for _ in 0..frames {
+            emulator.run_frame();
+        }
+    }
+
+    #[test]
+    fn load_state_rewind() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../res/nes/nestest.nes");
+        let mut emulator = Emulator::new();
+        emulator.insert_cartridge(Cartridge::from_file(path.into()).unwrap());
+        emulator.reset();
+        run_frames(&mut emulator, Buttons::empty(), 30);
+
+        let state = emulator.save_state();
+        run_frames(&mut emulator, Buttons::START, 5);
+        run_frames(&mut emulator, Buttons::empty(), 60);
+        let after = (emulator.ram().to_vec(), name_table_row(&emulator, 4));
+
+        // Replaying the same inputs from the snapshot land on the same machine
+        emulator.load_state(&state);
+        assert!(name_table_row(&emulator, 4).contains("-- Run all tests"));
+        run_frames(&mut emulator, Buttons::START, 5);
+        run_frames(&mut emulator, Buttons::empty(), 60);
+        assert_eq!(
+            (emulator.ram().to_vec(), name_table_row(&emulator, 4)),
+            after
+        );
+    }
+
+    #[test]
+    fn run_nestest_from_its_menu() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../res/nes/nestest.nes");
+        let mut emulator = Emulator::new();
+        emulator.insert_cartridge(Cartridge::from_file(path.into()).unwrap());
+        emulator.reset();
+
+        // The menu is written through $2006/$2007 once vertical blank is reached
+        run_frames(&mut emulator, Buttons::empty(), 30);
+        assert!(name_table_row(&emulator, 4).contains("-- Run all tests"));
+
+        // Start is read in the NMI handler
+        run_frames(&mut emulator, Buttons::START, 5);
+        run_frames(&mut emulator, Buttons::empty(), 60);
+        for row in 4..18 {
+            let line = name_table_row(&emulator, row);
+            assert!(line.contains("OK "), "{}", line.trim());
+        }
+    }
+}
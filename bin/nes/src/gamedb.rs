@@ -0,0 +1,141 @@
+use std::{collections::HashMap, fs, io, path::Path, sync::RwLock};
+
+use lazy_static::lazy_static;
+use utils::prelude::*;
+
+use crate::cartridge::{Mirror, Region};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameInfo {
+    pub crc32: u32,
+    pub sha1: [u8; 20],
+    pub mapper_id: u8,
+    pub mirror: Mirror,
+    pub region: Region,
+    pub battery: bool,
+    pub title: String,
+}
+
+lazy_static! {
+    static ref GAMES: RwLock<HashMap<u32, Vec<GameInfo>>> = {
+        let mut games = HashMap::new();
+        parse_into(&mut games, "gamedb.txt", include_str!("gamedb.txt"));
+        RwLock::new(games)
+    };
+}
+
+/// Add the valid entries of `text` to `games`, return how many there were
+fn parse_into(games: &mut HashMap<u32, Vec<GameInfo>>, name: &str, text: &str) -> usize {
+    let mut count = 0;
+    for (line_number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        match parse_line(line) {
+            Some(game) => {
+                // A later database override the entries of the same dump
+                let dumps: &mut Vec<GameInfo> = games.entry(game.crc32).or_default();
+                dumps.retain(|known| known.sha1 != game.sha1);
+                dumps.push(game);
+                count += 1;
+            }
+            None => error!("{}:{}: invalid entry", name, line_number + 1),
+        }
+    }
+    count
+}
+
+/// Load a database in the format of the embedded one on top of it, the embedded one only
+/// know a handful of dumps. Return the number of games loaded.
+pub fn load(path: &Path) -> io::Result<usize> {
+    let text = fs::read_to_string(path)?;
+    let mut games = GAMES.write().unwrap();
+    Ok(parse_into(&mut games, &path.display().to_string(), &text))
+}
+
+fn parse_line(line: &str) -> Option<GameInfo> {
+    let mut fields = line.split_whitespace();
+
+    let crc32 = u32::from_str_radix(fields.next()?, 16).ok()?;
+
+    let sha1_hex = fields.next()?;
+    if sha1_hex.len() != 40 {
+        return None;
+    }
+    let mut sha1 = [0u8; 20];
+    for (i, byte) in sha1.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(sha1_hex.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+
+    let mapper_id = fields.next()?.parse().ok()?;
+    let mirror = fields.next()?.parse().ok()?;
+    let region = fields.next()?.parse().ok()?;
+    let battery = match fields.next()? {
+        "yes" => true,
+        "no" => false,
+        _ => return None,
+    };
+    let title = fields.collect::<Vec<_>>().join(" ");
+    if title.is_empty() {
+        return None;
+    }
+
+    Some(GameInfo {
+        crc32,
+        sha1,
+        mapper_id,
+        mirror,
+        region,
+        battery,
+        title,
+    })
+}
+
+/// Find a game by the checksums of its PRG+CHR data, the SHA-1 settle CRC collisions
+pub fn lookup(crc32: u32, sha1: &[u8; 20]) -> Option<GameInfo> {
+    let games = GAMES.read().unwrap();
+    games
+        .get(&crc32)?
+        .iter()
+        .find(|game| &game.sha1 == sha1)
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_database_is_valid() {
+        let entries = include_str!("gamedb.txt")
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+            .count();
+        let mut games = HashMap::new();
+        assert_eq!(
+            parse_into(&mut games, "gamedb.txt", include_str!("gamedb.txt")),
+            entries
+        );
+        assert_eq!(games.values().map(Vec::len).sum::<usize>(), entries);
+    }
+
+    #[test]
+    fn parse_entry() {
+        let game = parse_line(
+            "158b0388 4131307f0f69f2a5c54b7d438328c5b2a5ed0820 1 vertical pal yes Some Game (E)",
+        )
+        .unwrap();
+
+        assert_eq!(game.crc32, 0x158b0388);
+        assert_eq!(game.sha1[0], 0x41);
+        assert_eq!(game.mapper_id, 1);
+        assert_eq!(game.mirror, Mirror::Vertical);
+        assert_eq!(game.region, Region::Pal);
+        assert!(game.battery);
+        assert_eq!(game.title, "Some Game (E)");
+
+        assert!(parse_line("158b0388 4131 0 vertical pal yes Bad").is_none());
+    }
+}
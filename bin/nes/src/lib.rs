@@ -1,15 +1,33 @@
 #![allow(clippy::identity_op)]
 #![allow(clippy::upper_case_acronyms)]
 
+#[allow(non_snake_case)]
+pub mod apu2A03;
 pub mod cartridge;
+pub mod controller;
 pub mod cpu6502;
 pub mod emulator;
+pub mod gamedb;
 pub mod mapper;
+pub mod netplay;
+pub mod nsf;
+#[allow(non_snake_case)]
 pub mod ppu2C02;
 pub mod system;
+pub mod video;
+pub mod wav;
 
-pub type Bus<'a> = &'a mut crate::system::SystemBus;
+/// Type erased bus for the cold paths like the disassembler, the CPU itself is generic over
+/// [`CpuBus`] so the hot loop has no dynamic dispatch
+pub type Bus<'a> = &'a mut dyn CpuBus;
+
+/// Anything the CPU can be attached to, the full [`system::SystemBus`]
+/// or a stripped down one like [`nsf::NsfBus`]
+pub trait CpuBus {
+    fn read(&mut self, addr: u16, readonly: bool) -> u8;
+    fn write(&mut self, addr: u16, data: u8);
+}
 
 pub trait Device {
-    fn tick(&mut self, bus: &mut crate::system::SystemBus);
+    fn tick<B: CpuBus + ?Sized>(&mut self, bus: &mut B);
 }
@@ -1,52 +1,447 @@
 #![allow(clippy::identity_op)]
 
+mod picker;
+mod recent;
+
+use clap::{crate_version, Arg, ArgMatches};
 use ggez::{
     conf::{WindowMode, WindowSetup},
-    event::{self, quit, EventHandler, KeyCode},
-    graphics::{self, Color, DrawParam, Font, Image, Scale, Text, TextFragment},
+    event::{
+        self,
+        winit_event::{ElementState, Event, KeyboardInput, MouseScrollDelta, WindowEvent},
+        EventHandler, EventsLoop, KeyCode,
+    },
+    filesystem,
+    graphics::{self, Color, DrawParam, FilterMode, Font, Rect, Scale, Text, TextFragment},
+    input::{keyboard, mouse},
     timer, Context, ContextBuilder, GameResult,
 };
 use nes::{
-    cpu6502::{Cpu6502, Flags},
+    cartridge::{Cartridge, Region},
+    controller::Buttons,
+    cpu6502::Flags,
     emulator::Emulator,
-    ppu2C02::{Ppu2C02, SCREEN_HEIGHT, SCREEN_WIDTH},
+    gamedb,
+    netplay::{Connection, Session},
+    ppu2C02::{SCREEN_HEIGHT, SCREEN_WIDTH},
+    video::{self, Filter, Overscan, VideoSettings, Viewport, PIXEL_ASPECT_RATIO},
+};
+use picker::{Action, FilePicker};
+use recent::RecentRoms;
+use std::{
+    collections::HashMap,
+    env,
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+    process,
+    str::FromStr,
+    thread,
+    time::Duration,
 };
-use std::{collections::HashMap, env};
 use utils::prelude::*;
 
 const WIDTH: f32 = 960.0;
 const HEIGHT: f32 = 540.0;
 
+/// Width of the debug panel on the right of the window
+const PANEL_WIDTH: f32 = 275.0;
+
+struct Options {
+    rom: Option<PathBuf>,
+    region: Option<Region>,
+    gamedb: Option<PathBuf>,
+    scale: Option<f32>,
+    filter: Filter,
+    headless: bool,
+    frames: u32,
+    screenshot: Option<PathBuf>,
+    host: Option<u16>,
+    connect: Option<String>,
+    input_delay: u8,
+}
+
+fn parse_args() -> Options {
+    let matches = clap::App::new("NES Emulator")
+        .version(crate_version!())
+        .arg(Arg::with_name("rom").help("iNES ROM to load").index(1))
+        .arg(
+            Arg::with_name("region")
+                .long("region")
+                .takes_value(true)
+                .possible_values(&["ntsc", "pal"])
+                .help("Override the region of the ROM"),
+        )
+        .arg(
+            Arg::with_name("gamedb")
+                .long("gamedb")
+                .takes_value(true)
+                .value_name("FILE")
+                .help("Game database to fix up bad headers with, in the format of src/gamedb.txt"),
+        )
+        .arg(
+            Arg::with_name("scale")
+                .long("scale")
+                .takes_value(true)
+                .help("Initial window size, as a multiple of the NES resolution"),
+        )
+        .arg(
+            Arg::with_name("filter")
+                .long("filter")
+                .takes_value(true)
+                .possible_values(&["none", "scanlines", "xbr", "ntsc"])
+                .default_value("none"),
+        )
+        .arg(
+            Arg::with_name("headless")
+                .long("headless")
+                .requires("rom")
+                .help("Run without a window"),
+        )
+        .arg(
+            Arg::with_name("frames")
+                .long("frames")
+                .takes_value(true)
+                .default_value("60")
+                .help("Number of frames to run in headless mode"),
+        )
+        .arg(
+            Arg::with_name("screenshot")
+                .long("screenshot")
+                .takes_value(true)
+                .help("Save the last frame as a PPM image in headless mode"),
+        )
+        .arg(
+            Arg::with_name("host")
+                .long("host")
+                .takes_value(true)
+                .value_name("PORT")
+                .requires("rom")
+                .conflicts_with("connect")
+                .help("Wait for a second player on this port, you are player 1"),
+        )
+        .arg(
+            Arg::with_name("connect")
+                .long("connect")
+                .takes_value(true)
+                .value_name("ADDR")
+                .requires("rom")
+                .help("Join a game hosted at ADDR (ip:port), you are player 2"),
+        )
+        .arg(
+            Arg::with_name("input-delay")
+                .long("input-delay")
+                .takes_value(true)
+                .default_value("2")
+                .help("Frames of delay on netplay inputs, less rollbacks but more lag"),
+        )
+        .get_matches();
+
+    Options {
+        rom: matches.value_of_os("rom").map(PathBuf::from),
+        region: matches.value_of("region").and_then(|s| s.parse().ok()),
+        gamedb: matches.value_of_os("gamedb").map(PathBuf::from),
+        scale: parse_or_exit(&matches, "scale"),
+        filter: parse_or_exit(&matches, "filter").unwrap_or(Filter::None),
+        headless: matches.is_present("headless"),
+        frames: parse_or_exit(&matches, "frames").unwrap_or(60),
+        screenshot: matches.value_of_os("screenshot").map(PathBuf::from),
+        host: parse_or_exit(&matches, "host"),
+        connect: matches.value_of("connect").map(String::from),
+        input_delay: parse_or_exit(&matches, "input-delay").unwrap_or(2),
+    }
+}
+
+fn parse_or_exit<T: FromStr>(matches: &ArgMatches, name: &str) -> Option<T> {
+    matches.value_of(name).map(|value| {
+        value.parse().unwrap_or_else(|_| {
+            eprintln!("error: invalid value '{}' for '--{}'", value, name);
+            process::exit(1);
+        })
+    })
+}
+
+fn load_cartridge(path: &Path, region: Option<Region>) -> Result<Cartridge, String> {
+    let mut cartridge = Cartridge::from_file(path.as_os_str().to_owned())
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    if let Some(region) = region {
+        cartridge.set_region(region);
+    }
+
+    if cartridge.has_battery() {
+        match fs::read(save_path(path)) {
+            Ok(data) => cartridge.load_battery_ram(&data),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Could not load {}: {}", save_path(path).display(), e),
+        }
+    }
+
+    Ok(cartridge)
+}
+
+/// Battery backed RAM is saved next to the ROM, like most emulators do
+fn save_path(rom: &Path) -> PathBuf {
+    rom.with_extension("sav")
+}
+
+fn save_battery_ram(emulator: &Emulator, rom: &Path) -> Result<(), String> {
+    let battery_ram = emulator
+        .cartridge()
+        .and_then(|cartridge| cartridge.battery_ram());
+    match battery_ram {
+        Some(data) => {
+            let path = save_path(rom);
+            fs::write(&path, data).map_err(|e| format!("{}: {}", path.display(), e))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Connect to the other player if asked to, blocking until the session starts
+fn start_netplay(
+    options: &Options,
+    emulator: &Emulator,
+) -> Result<Option<Session<Emulator>>, String> {
+    let crc32 = match emulator.cartridge() {
+        Some(cartridge) => cartridge.crc32(),
+        None => return Ok(None),
+    };
+
+    let (connection, port) = if let Some(port) = options.host {
+        info!("Waiting for player 2 on port {}", port);
+        (Connection::host(port), 0)
+    } else if let Some(addr) = &options.connect {
+        info!("Connecting to {}", addr);
+        (Connection::connect(addr.as_str()), 1)
+    } else {
+        return Ok(None);
+    };
+
+    let connection = connection.map_err(|e| format!("netplay: {}", e))?;
+    let session =
+        Session::new(connection, port, crc32, options.input_delay).map_err(|e| e.to_string())?;
+    info!(
+        "Netplay started as player {}, input delay {}",
+        port + 1,
+        session.input_delay()
+    );
+
+    Ok(Some(session))
+}
+
+/// Controller 1 layout: arrows, Z = B, X = A, Enter = Start, right Shift = Select
+fn keyboard_buttons(ctx: &Context) -> Buttons {
+    let mapping = [
+        (KeyCode::Up, Buttons::UP),
+        (KeyCode::Down, Buttons::DOWN),
+        (KeyCode::Left, Buttons::LEFT),
+        (KeyCode::Right, Buttons::RIGHT),
+        (KeyCode::Z, Buttons::B),
+        (KeyCode::X, Buttons::A),
+        (KeyCode::Return, Buttons::START),
+        (KeyCode::RShift, Buttons::SELECT),
+    ];
+
+    mapping
+        .iter()
+        .filter(|(key, _)| keyboard::is_key_pressed(ctx, *key))
+        .fold(Buttons::empty(), |buttons, (_, button)| buttons | *button)
+}
+
+fn run_headless(options: &Options) -> Result<(), String> {
+    // `--headless` requires a ROM, clap already checked it
+    let path = options.rom.as_ref().unwrap();
+    let cartridge = load_cartridge(path, options.region)?;
+    println!(
+        "{} (crc32 {:08x}, mapper {}, {:?})",
+        cartridge.title().unwrap_or("Unknown ROM"),
+        cartridge.crc32(),
+        cartridge.mapper_id(),
+        cartridge.region()
+    );
+
+    let mut emulator = Emulator::new();
+    emulator.insert_cartridge(cartridge);
+    emulator.reset();
+
+    match start_netplay(options, &emulator)? {
+        Some(mut session) => {
+            while session.frame() < options.frames {
+                let ran = session
+                    .advance(&mut emulator, Buttons::empty())
+                    .map_err(|e| e.to_string())?;
+                if !ran {
+                    thread::sleep(Duration::from_millis(1));
+                }
+            }
+            println!("{} rollbacks", session.rollbacks());
+            if let Some(frame) = session.desync() {
+                return Err(format!("desync detected at frame {}", frame));
+            }
+        }
+        None => {
+            for _ in 0..options.frames {
+                emulator.run_frame();
+            }
+        }
+    }
+    save_battery_ram(&emulator, path)?;
+
+    if let Some(screenshot) = &options.screenshot {
+        let settings = VideoSettings {
+            filter: options.filter,
+            ..Default::default()
+        };
+        let frame = video::process(emulator.ppu().screen(), &settings);
+        File::create(screenshot)
+            .and_then(|file| frame.write_ppm(&mut BufWriter::new(file)))
+            .map_err(|e| format!("{}: {}", screenshot.display(), e))?;
+    }
+
+    Ok(())
+}
+
 fn main() -> GameResult<()> {
     utils::init_logger().unwrap();
 
+    let options = parse_args();
+    if let Some(path) = &options.gamedb {
+        match gamedb::load(path) {
+            Ok(count) => info!("Loaded {} games from {}", count, path.display()),
+            Err(e) => {
+                eprintln!("error: {}: {}", path.display(), e);
+                process::exit(1);
+            }
+        }
+    }
+
+    if options.headless {
+        if let Err(e) = run_headless(&options) {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let (width, height) = match options.scale {
+        Some(scale) => {
+            let overscan = VideoSettings::default().overscan;
+            (
+                (SCREEN_WIDTH as f32 * PIXEL_ASPECT_RATIO * scale).ceil() + PANEL_WIDTH,
+                (SCREEN_HEIGHT - overscan.top - overscan.bottom) as f32 * scale,
+            )
+        }
+        None => (WIDTH, HEIGHT),
+    };
+
     let (mut ctx, mut event_loop) = ContextBuilder::new("nes_emulator", "remtori")
         .window_setup(WindowSetup::default().title("NES Emulator"))
         .window_mode(
             WindowMode::default()
-                .dimensions(WIDTH, HEIGHT)
+                .dimensions(width, height)
                 .resizable(true),
         )
         .add_resource_path(env::current_dir()?.join("res"))
         .build()
         .expect("aieee, could not create ggez context!");
 
-    let mut app = App::new(&mut ctx)?;
+    let mut app = App::new(&mut ctx, &options)?;
 
     // Run!
-    event::run(&mut ctx, &mut event_loop, &mut app)
-    // Ok(())
+    run(&mut ctx, &mut event_loop, &mut app)
+}
+
+/// Same as `event::run`, but also forward the files dropped on the window which ggez ignore
+fn run(ctx: &mut Context, events_loop: &mut EventsLoop, app: &mut App) -> GameResult {
+    while ctx.continuing {
+        ctx.timer_context.tick();
+        events_loop.poll_events(|event| {
+            ctx.process_event(&event);
+            if let Event::WindowEvent { event, .. } = event {
+                match event {
+                    WindowEvent::Resized(size) => {
+                        app.resize_event(ctx, size.width as f32, size.height as f32)
+                    }
+                    WindowEvent::CloseRequested if !app.quit_event(ctx) => event::quit(ctx),
+                    WindowEvent::Focused(gained) => app.focus_event(ctx, gained),
+                    WindowEvent::ReceivedCharacter(ch) => app.text_input_event(ctx, ch),
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state,
+                                virtual_keycode: Some(keycode),
+                                modifiers,
+                                ..
+                            },
+                        ..
+                    } => match state {
+                        ElementState::Pressed => {
+                            let repeat = keyboard::is_key_repeated(ctx);
+                            app.key_down_event(ctx, keycode, modifiers.into(), repeat);
+                        }
+                        ElementState::Released => app.key_up_event(ctx, keycode, modifiers.into()),
+                    },
+                    WindowEvent::MouseWheel { delta, .. } => {
+                        let (x, y) = match delta {
+                            MouseScrollDelta::LineDelta(x, y) => (x, y),
+                            MouseScrollDelta::PixelDelta(position) => {
+                                (position.x as f32, position.y as f32)
+                            }
+                        };
+                        app.mouse_wheel_event(ctx, x, y);
+                    }
+                    WindowEvent::MouseInput { state, button, .. } => {
+                        let position = mouse::position(ctx);
+                        match state {
+                            ElementState::Pressed => {
+                                app.mouse_button_down_event(ctx, button, position.x, position.y)
+                            }
+                            ElementState::Released => {
+                                app.mouse_button_up_event(ctx, button, position.x, position.y)
+                            }
+                        }
+                    }
+                    WindowEvent::CursorMoved { .. } => {
+                        let position = mouse::position(ctx);
+                        let delta = mouse::delta(ctx);
+                        app.mouse_motion_event(ctx, position.x, position.y, delta.x, delta.y);
+                    }
+                    WindowEvent::DroppedFile(path) => app.load_rom(ctx, &path),
+                    _ => {}
+                }
+            }
+        });
+
+        // ggez only hand out gamepad ids to its own loop, drain the events so the gamepad
+        // state stays current
+        while ctx.gamepad_context.next_event().is_some() {}
+
+        app.update(ctx)?;
+        app.draw(ctx)?;
+    }
+
+    Ok(())
 }
 
 struct App {
     font: Font,
     is_step_mode: bool,
     emulator: Emulator,
+    /// Path of the loaded ROM, where its save goes
+    rom: Option<PathBuf>,
     disassembly: HashMap<u16, String>,
+    video: VideoSettings,
+    region: Option<Region>,
+    recent: RecentRoms,
+    picker: Option<FilePicker>,
+    netplay: Option<Session<Emulator>>,
+    /// Last error, shown under the debug panel
+    status: Option<String>,
 }
 
 impl App {
-    pub fn new(ctx: &mut Context) -> GameResult<App> {
+    pub fn new(ctx: &mut Context, options: &Options) -> GameResult<App> {
         let font = Font::new(ctx, "/CascadiaMono.ttf")?;
         let mut emulator = {
             let mut nes = Emulator::default();
@@ -56,23 +451,129 @@ impl App {
         };
 
         let disassembly = emulator.disassemble(0x0000..0xFFFF);
+        let recent = RecentRoms::load(filesystem::user_config_dir(ctx).join("recent_roms.txt"));
 
-        Ok(App {
+        let mut app = App {
             font,
             is_step_mode: true,
             disassembly,
             emulator,
-        })
+            rom: None,
+            video: VideoSettings {
+                filter: options.filter,
+                ..Default::default()
+            },
+            region: options.region,
+            recent,
+            picker: None,
+            netplay: None,
+            status: None,
+        };
+
+        if let Some(rom) = &options.rom {
+            app.load_rom(ctx, rom);
+        }
+
+        match start_netplay(options, &app.emulator) {
+            Ok(session) => app.netplay = session,
+            Err(e) => {
+                error!("{}", e);
+                app.status = Some(e);
+            }
+        }
+
+        Ok(app)
     }
+
+    fn load_rom(&mut self, ctx: &mut Context, path: &Path) {
+        if self.netplay.is_some() {
+            self.status = Some("Cannot change ROM during netplay".to_string());
+            return;
+        }
+
+        match load_cartridge(path, self.region) {
+            Ok(cartridge) => {
+                info!("Loaded {}", path.display());
+
+                self.save_battery_ram();
+                self.emulator = Emulator::new();
+                self.emulator.insert_cartridge(cartridge);
+                self.emulator.reset();
+                self.disassembly = self.emulator.disassemble(0x0000..0xFFFF);
+                self.is_step_mode = false;
+                self.recent.push(path);
+                self.rom = Some(path.to_path_buf());
+                self.picker = None;
+                self.status = None;
+                graphics::set_window_title(ctx, &window_title(&self.emulator));
+            }
+            Err(e) => {
+                error!("{}", e);
+                self.status = Some(e);
+            }
+        }
+    }
+
+    fn save_battery_ram(&mut self) {
+        if let Some(rom) = &self.rom {
+            if let Err(e) = save_battery_ram(&self.emulator, rom) {
+                error!("Could not save the game: {}", e);
+                self.status = Some(e);
+            }
+        }
+    }
+
+    fn open_picker(&mut self) {
+        // Start where the last ROM was, people tend to keep them together
+        let dir = self
+            .recent
+            .roms()
+            .first()
+            .and_then(|rom| rom.parent())
+            .map(Path::to_path_buf)
+            .or_else(|| env::current_dir().ok())
+            .unwrap_or_default();
+
+        self.picker = Some(FilePicker::new(dir, self.recent.roms()));
+    }
+}
+
+/// Title of the game from the ROM database when we know it
+fn window_title(emulator: &Emulator) -> String {
+    emulator
+        .cartridge()
+        .and_then(|cartridge| cartridge.title())
+        .unwrap_or("NES Emulator")
+        .to_string()
 }
 
 impl EventHandler for App {
     fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
-        if !self.is_step_mode {
-            self.emulator.tick();
+        let buttons = keyboard_buttons(ctx);
+        let fps = match self.emulator.region() {
+            Region::Ntsc => 60,
+            Region::Pal => 50,
+        };
+
+        if let Some(session) = &mut self.netplay {
+            while timer::check_update_time(ctx, fps) {
+                if let Err(e) = session.advance(&mut self.emulator, buttons) {
+                    error!("{}", e);
+                    self.status = Some(e.to_string());
+                    self.netplay = None;
+                    break;
+                }
+            }
+            return Ok(());
+        }
+
+        self.emulator.set_buttons(0, buttons);
+        while timer::check_update_time(ctx, fps) {
+            if !self.is_step_mode {
+                self.emulator.run_frame();
+            }
         }
 
-        // timer::sleep(timer::remaining_update_time(ctx));
         Ok(())
     }
 
@@ -148,15 +649,65 @@ impl EventHandler for App {
             }
         }
 
-        graphics::draw(ctx, &stats, ([WIDTH - 275.0, 0.0], graphics::WHITE))?;
+        if self.emulator.cartridge().is_none() {
+            stats.add("\nDrop a .nes file here\nor press L to open one\n");
+        }
+        if let Some(session) = &self.netplay {
+            stats.add(format!(
+                "\nNetplay P{} frame {}\ndelay {}, {} rollbacks\n",
+                session.local_port() + 1,
+                session.frame(),
+                session.input_delay(),
+                session.rollbacks()
+            ));
+            if let Some(frame) = session.desync() {
+                stats.add(
+                    TextFragment::new(format!("DESYNC at frame {}\n", frame))
+                        .color(Color::from_rgb_u32(0xFF5555)),
+                );
+            }
+        }
+        if let Some(status) = &self.status {
+            stats.add(
+                TextFragment::new(format!("\n{}\n", status)).color(Color::from_rgb_u32(0xFF5555)),
+            );
+        }
+
+        let (width, height) = graphics::drawable_size(ctx);
+        graphics::draw(ctx, &stats, ([width - PANEL_WIDTH, 0.0], graphics::WHITE))?;
+
+        if let Some(picker) = &self.picker {
+            let mut text = Text::default();
+            text.set_font(self.font, Scale::uniform(18.0));
+            for (line, selected) in picker.lines(((height - 40.0) / 20.0).max(1.0) as usize) {
+                text.add(if selected {
+                    TextFragment::new(format!("> {}\n", line)).color(Color::from_rgb_u32(0x00CCCC))
+                } else {
+                    TextFragment::new(format!("  {}\n", line))
+                });
+            }
+            graphics::draw(ctx, &text, ([10.0, 10.0], graphics::WHITE))?;
+
+            return graphics::present(ctx);
+        }
+
+        let frame = video::process(self.emulator.ppu().screen(), &self.video);
+        let mut img = graphics::Image::from_rgba8(
+            ctx,
+            frame.width as u16,
+            frame.height as u16,
+            &frame.pixels,
+        )?;
+        img.set_filter(FilterMode::Nearest);
 
-        let img = graphics::Image::from_rgba8(
+        let viewport = Viewport::fit(&frame, &self.video, width - PANEL_WIDTH, height);
+        graphics::draw(
             ctx,
-            SCREEN_WIDTH as u16,
-            SCREEN_HEIGHT as u16,
-            self.emulator.ppu().screen(),
+            &img,
+            DrawParam::default()
+                .dest([viewport.x, viewport.y])
+                .scale([viewport.scale_x, viewport.scale_y]),
         )?;
-        graphics::draw(ctx, &img, DrawParam::default().scale([2.0, 2.0]))?;
 
         graphics::present(ctx)
     }
@@ -168,7 +719,23 @@ impl EventHandler for App {
         _keymods: event::KeyMods,
         _repeat: bool,
     ) {
+        if let Some(picker) = &mut self.picker {
+            match picker.key_down(keycode) {
+                Action::Load(path) => self.load_rom(ctx, &path),
+                Action::Close => self.picker = None,
+                Action::None => {}
+            }
+            return;
+        }
+
         match keycode {
+            // Would desync the other player
+            KeyCode::L | KeyCode::Space | KeyCode::R | KeyCode::P if self.netplay.is_some() => {}
+            KeyCode::L => self.open_picker(),
+            KeyCode::P => {
+                self.is_step_mode = !self.is_step_mode;
+                info!("Step mode: {}", self.is_step_mode);
+            }
             KeyCode::Space => {
                 info!("Emulator Step");
                 self.emulator.step();
@@ -177,9 +744,53 @@ impl EventHandler for App {
                 info!("Emulator Reset!");
                 self.emulator.reset();
             }
+            KeyCode::F => {
+                self.video.filter = self.video.filter.next();
+                info!("Video filter: {}", self.video.filter.name());
+            }
+            KeyCode::O => {
+                self.video.overscan = if self.video.overscan == Overscan::NONE {
+                    Overscan::NTSC
+                } else {
+                    Overscan::NONE
+                };
+                info!("Overscan: {:?}", self.video.overscan);
+            }
+            KeyCode::A => {
+                self.video.aspect_correction = !self.video.aspect_correction;
+                info!("Aspect correction: {}", self.video.aspect_correction);
+            }
+            KeyCode::I => {
+                self.video.integer_scaling = !self.video.integer_scaling;
+                info!("Integer scaling: {}", self.video.integer_scaling);
+            }
+            KeyCode::F12 => {
+                let frame = video::process(self.emulator.ppu().screen(), &self.video);
+                let result = File::create("screenshot.ppm")
+                    .and_then(|file| frame.write_ppm(&mut BufWriter::new(file)));
+                match result {
+                    Ok(_) => info!("Screenshot saved to screenshot.ppm"),
+                    Err(e) => error!("Could not save screenshot: {}", e),
+                }
+            }
 
-            KeyCode::Escape => event::quit(ctx),
+            KeyCode::Escape => {
+                self.save_battery_ram();
+                event::quit(ctx);
+            }
             _ => {}
         }
     }
+
+    fn quit_event(&mut self, _ctx: &mut Context) -> bool {
+        self.save_battery_ram();
+        false
+    }
+
+    fn resize_event(&mut self, ctx: &mut Context, width: f32, height: f32) {
+        // Keep one unit per physical pixel, otherwise ggez stretch everything to the window
+        if let Err(e) = graphics::set_screen_coordinates(ctx, Rect::new(0.0, 0.0, width, height)) {
+            error!("Could not resize: {}", e);
+        }
+    }
 }
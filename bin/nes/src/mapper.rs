@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Mapper {
     prg_banks: u8,
     chr_banks: u8,
@@ -14,6 +14,10 @@ impl Mapper {
         }
     }
 
+    pub fn is_supported(mapper_id: u8) -> bool {
+        matches!(mapper_id, 0)
+    }
+
     #[inline]
     pub fn cpu_map_read(&self, addr: u16) -> Option<u16> {
         (self.imp.cpu_map_read)(addr, self.prg_banks, self.chr_banks)
@@ -35,7 +39,7 @@ impl Mapper {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct MapperImpl {
     cpu_map_read: fn(addr: u16, prg_banks: u8, chr_banks: u8) -> Option<u16>,
     cpu_map_write: fn(addr: u16, prg_banks: u8, chr_banks: u8) -> Option<u16>,
@@ -0,0 +1,517 @@
+mod protocol;
+pub use protocol::{Connection, Message, PROTOCOL_VERSION};
+
+use std::{collections::VecDeque, fmt, io, thread, time::Duration};
+
+use utils::prelude::*;
+
+use crate::{controller::Buttons, emulator::Emulator};
+
+/// How many frames we can run on predicted inputs before waiting for the other player
+pub const MAX_ROLLBACK: u32 = 8;
+
+/// Frames between two desync checks
+pub const HASH_INTERVAL: u32 = 60;
+
+/// What netplay needs from a game to rewind and replay it
+pub trait Rollback {
+    type State;
+
+    fn save_state(&self) -> Self::State;
+    fn load_state(&mut self, state: &Self::State);
+    /// Run one frame with the buttons of player 1 and 2
+    fn run_frame(&mut self, inputs: [Buttons; 2]);
+    /// Cheap hash of the state, compared between players to detect desyncs
+    fn checksum(&self) -> u32;
+}
+
+impl Rollback for Emulator {
+    type State = crate::emulator::SaveState;
+
+    fn save_state(&self) -> Self::State {
+        Emulator::save_state(self)
+    }
+
+    fn load_state(&mut self, state: &Self::State) {
+        Emulator::load_state(self, state)
+    }
+
+    fn run_frame(&mut self, inputs: [Buttons; 2]) {
+        self.set_buttons(0, inputs[0]);
+        self.set_buttons(1, inputs[1]);
+        Emulator::run_frame(self);
+    }
+
+    fn checksum(&self) -> u32 {
+        crc32(self.ram())
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    IO(io::Error),
+    /// The other side is running another ROM or another version
+    Mismatch(String),
+    /// Message that make no sense at this point of the session
+    Protocol(String),
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::IO(e) => write!(f, "netplay connection lost: {}", e),
+            Error::Mismatch(reason) => write!(f, "cannot play together: {}", reason),
+            Error::Protocol(reason) => write!(f, "netplay protocol error: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+struct Snapshot<S> {
+    /// The state is taken at the start of this frame
+    frame: u32,
+    state: S,
+    checksum: u32,
+}
+
+/// Two player session with input delay and rollback.
+///
+/// Local inputs are scheduled `input_delay` frames in the future and sent right away,
+/// the remote inputs we don't have yet are predicted by repeating the last one.
+/// When a prediction turns out wrong, the game is rewound to that frame and replayed
+pub struct Session<G: Rollback> {
+    connection: Connection,
+    /// Received during the handshake, not processed yet
+    pending: Vec<Message>,
+    /// Controller port of the local player, the host is player 1
+    local_port: usize,
+    input_delay: u32,
+    /// Next frame to run
+    frame: u32,
+    local_inputs: Vec<Buttons>,
+    /// Confirmed remote inputs, `remote_inputs.len()` is the first unconfirmed frame
+    remote_inputs: Vec<Buttons>,
+    /// Remote inputs we guessed for the frames that are not confirmed yet
+    predictions: VecDeque<Buttons>,
+    snapshots: VecDeque<Snapshot<G::State>>,
+    rollback_to: Option<u32>,
+    rollbacks: u64,
+
+    next_hash_frame: u32,
+    local_hashes: VecDeque<(u32, u32)>,
+    remote_hashes: VecDeque<(u32, u32)>,
+    desync: Option<u32>,
+}
+
+impl<G: Rollback> Session<G> {
+    /// Exchange hellos with the other player, blocking until they answer
+    pub fn new(
+        mut connection: Connection,
+        local_port: usize,
+        rom_crc32: u32,
+        input_delay: u8,
+    ) -> Result<Session<G>, Error> {
+        connection.send(Message::Hello {
+            version: PROTOCOL_VERSION,
+            rom_crc32,
+            input_delay,
+        });
+
+        let mut pending = Vec::new();
+        let (remote_crc32, remote_delay) = loop {
+            connection.flush()?;
+            pending.extend(connection.receive()?);
+            if pending.is_empty() {
+                thread::sleep(Duration::from_millis(1));
+                continue;
+            }
+
+            // The other side may already have sent inputs right after its hello
+            match pending.remove(0) {
+                Message::Hello {
+                    version,
+                    rom_crc32,
+                    input_delay,
+                } => {
+                    if version != PROTOCOL_VERSION {
+                        return Err(Error::Mismatch(format!(
+                            "protocol version {} but we use {}",
+                            version, PROTOCOL_VERSION
+                        )));
+                    }
+                    break (rom_crc32, input_delay);
+                }
+                message => {
+                    return Err(Error::Protocol(format!(
+                        "expected hello, got {:?}",
+                        message
+                    )))
+                }
+            }
+        };
+
+        if remote_crc32 != rom_crc32 {
+            return Err(Error::Mismatch(format!(
+                "different ROM, crc32 {:08x} but ours is {:08x}",
+                remote_crc32, rom_crc32
+            )));
+        }
+
+        // Both side must use the same delay, the first frames have no input at all
+        let input_delay = input_delay.max(remote_delay) as u32;
+
+        Ok(Session {
+            connection,
+            pending,
+            local_port,
+            input_delay,
+            frame: 0,
+            local_inputs: vec![Buttons::empty(); input_delay as usize],
+            remote_inputs: vec![Buttons::empty(); input_delay as usize],
+            predictions: VecDeque::new(),
+            snapshots: VecDeque::new(),
+            rollback_to: None,
+            rollbacks: 0,
+            next_hash_frame: HASH_INTERVAL,
+            local_hashes: VecDeque::new(),
+            remote_hashes: VecDeque::new(),
+            desync: None,
+        })
+    }
+
+    pub fn local_port(&self) -> usize {
+        self.local_port
+    }
+
+    pub fn input_delay(&self) -> u32 {
+        self.input_delay
+    }
+
+    /// Next frame to run
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    /// Every frame before this one run with the real inputs of both players
+    pub fn confirmed_frame(&self) -> u32 {
+        (self.remote_inputs.len() as u32).min(self.frame)
+    }
+
+    pub fn rollbacks(&self) -> u64 {
+        self.rollbacks
+    }
+
+    /// First frame where the players disagreed on the state
+    pub fn desync(&self) -> Option<u32> {
+        self.desync
+    }
+
+    fn inputs(&mut self, frame: u32) -> [Buttons; 2] {
+        let local = self.local_inputs[frame as usize];
+        let remote = match self.remote_inputs.get(frame as usize) {
+            Some(&buttons) => buttons,
+            None => {
+                let index = (frame as usize) - self.remote_inputs.len();
+                if index >= self.predictions.len() {
+                    let last = self.remote_inputs.last().copied().unwrap_or_default();
+                    self.predictions.resize(index + 1, last);
+                }
+                self.predictions[index]
+            }
+        };
+
+        if self.local_port == 0 {
+            [local, remote]
+        } else {
+            [remote, local]
+        }
+    }
+
+    fn save_snapshot(&mut self, game: &G) {
+        while self
+            .snapshots
+            .back()
+            .is_some_and(|snapshot| snapshot.frame >= self.frame)
+        {
+            self.snapshots.pop_back();
+        }
+
+        self.snapshots.push_back(Snapshot {
+            frame: self.frame,
+            state: game.save_state(),
+            checksum: game.checksum(),
+        });
+
+        while self.snapshots.len() > MAX_ROLLBACK as usize + 2 {
+            self.snapshots.pop_front();
+        }
+    }
+
+    fn run_frame(&mut self, game: &mut G) {
+        self.save_snapshot(game);
+        let inputs = self.inputs(self.frame);
+        game.run_frame(inputs);
+        self.frame += 1;
+    }
+
+    fn receive_input(&mut self, frame: u32, buttons: Buttons) -> Result<(), Error> {
+        // TCP keep everything in order, a gap means a bug on the other side
+        if frame as usize != self.remote_inputs.len() {
+            return Err(Error::Protocol(format!(
+                "expected input for frame {}, got {}",
+                self.remote_inputs.len(),
+                frame
+            )));
+        }
+
+        let predicted = self.predictions.pop_front();
+        self.remote_inputs.push(buttons);
+
+        if frame < self.frame && predicted != Some(buttons) {
+            self.rollback_to = Some(self.rollback_to.map_or(frame, |f| f.min(frame)));
+        }
+
+        Ok(())
+    }
+
+    /// Process everything the other player sent, replaying the frames we mispredicted
+    pub fn poll(&mut self, game: &mut G) -> Result<(), Error> {
+        let mut messages = std::mem::take(&mut self.pending);
+        messages.extend(self.connection.receive()?);
+        for message in messages {
+            match message {
+                Message::Input { frame, buttons } => {
+                    self.receive_input(frame, Buttons::from_bits_truncate(buttons))?
+                }
+                Message::Hash { frame, hash } => self.remote_hashes.push_back((frame, hash)),
+                Message::Hello { .. } => {
+                    return Err(Error::Protocol("unexpected hello".to_string()))
+                }
+            }
+        }
+
+        if let Some(target) = self.rollback_to.take() {
+            let snapshot = self
+                .snapshots
+                .iter()
+                .find(|snapshot| snapshot.frame == target)
+                .ok_or_else(|| {
+                    Error::Protocol(format!(
+                        "cannot roll back to frame {} from frame {}, more than {} frames ago",
+                        target, self.frame, MAX_ROLLBACK
+                    ))
+                })?;
+            game.load_state(&snapshot.state);
+
+            // Predictions for the replayed frames are made again from the new confirmed input
+            self.predictions.clear();
+            let end = self.frame;
+            self.frame = target;
+            while self.frame < end {
+                self.run_frame(game);
+            }
+            self.rollbacks += 1;
+        }
+
+        self.check_hashes();
+        self.connection.flush()?;
+        Ok(())
+    }
+
+    fn check_hashes(&mut self) {
+        // A snapshot checksum is final once every input before it is confirmed
+        while self.next_hash_frame <= self.confirmed_frame() {
+            let frame = self.next_hash_frame;
+            match self
+                .snapshots
+                .iter()
+                .find(|snapshot| snapshot.frame == frame)
+            {
+                Some(snapshot) => {
+                    self.local_hashes.push_back((frame, snapshot.checksum));
+                    self.connection.send(Message::Hash {
+                        frame,
+                        hash: snapshot.checksum,
+                    });
+                }
+                // Not saved yet, the frame is the one about to run
+                None => break,
+            }
+            self.next_hash_frame += HASH_INTERVAL;
+        }
+
+        while let (Some(&(local_frame, local)), Some(&(remote_frame, remote))) =
+            (self.local_hashes.front(), self.remote_hashes.front())
+        {
+            if local_frame < remote_frame {
+                self.local_hashes.pop_front();
+            } else if remote_frame < local_frame {
+                self.remote_hashes.pop_front();
+            } else {
+                self.local_hashes.pop_front();
+                self.remote_hashes.pop_front();
+
+                if local != remote && self.desync.is_none() {
+                    error!(
+                        "Netplay desync at frame {}: {:08x} != {:08x}",
+                        local_frame, local, remote
+                    );
+                    self.desync = Some(local_frame);
+                }
+            }
+        }
+    }
+
+    /// Schedule the local input and run the next frame.
+    ///
+    /// Returns `false` without running anything if we are too far ahead of the other player
+    pub fn advance(&mut self, game: &mut G, local: Buttons) -> Result<bool, Error> {
+        self.poll(game)?;
+
+        if self.frame >= self.remote_inputs.len() as u32 + MAX_ROLLBACK {
+            return Ok(false);
+        }
+
+        let frame = self.frame + self.input_delay;
+        self.local_inputs.push(local);
+        self.connection.send(Message::Input {
+            frame,
+            buttons: local.bits(),
+        });
+
+        self.run_frame(game);
+        self.connection.flush()?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::*;
+
+    /// Tiny deterministic "game" whose state depends on every input
+    #[derive(Default)]
+    struct Game {
+        state: u64,
+        frame: u32,
+        corrupt_at: Option<u32>,
+    }
+
+    impl Rollback for Game {
+        type State = (u64, u32);
+
+        fn save_state(&self) -> Self::State {
+            (self.state, self.frame)
+        }
+
+        fn load_state(&mut self, state: &Self::State) {
+            self.state = state.0;
+            self.frame = state.1;
+        }
+
+        fn run_frame(&mut self, inputs: [Buttons; 2]) {
+            self.state = self
+                .state
+                .wrapping_mul(31)
+                .wrapping_add(inputs[0].bits() as u64 + 7 * inputs[1].bits() as u64);
+            if self.corrupt_at == Some(self.frame) {
+                self.state ^= 1;
+            }
+            self.frame += 1;
+        }
+
+        fn checksum(&self) -> u32 {
+            self.state as u32 ^ (self.state >> 32) as u32
+        }
+    }
+
+    fn play(
+        connection: Connection,
+        port: usize,
+        frames: u32,
+        mut game: Game,
+    ) -> (u64, Session<Game>) {
+        let mut session = Session::new(connection, port, 0x1234, 2).unwrap();
+
+        while session.frame() < frames {
+            // Change input often so predictions keep failing
+            let buttons =
+                Buttons::from_bits_truncate((session.frame() / 3 * 37 + port as u32) as u8);
+            if !session.advance(&mut game, buttons).unwrap() {
+                thread::sleep(Duration::from_micros(100));
+            }
+        }
+
+        // Wait for the last inputs of the other side
+        while session.confirmed_frame() < frames {
+            session.poll(&mut game).unwrap();
+            thread::sleep(Duration::from_micros(100));
+        }
+
+        (game.state, session)
+    }
+
+    fn run_pair(
+        frames: u32,
+        host_game: Game,
+        client_game: Game,
+    ) -> ((u64, Session<Game>), (u64, Session<Game>)) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let client = thread::spawn(move || {
+            let connection = Connection::connect(addr).unwrap();
+            play(connection, 1, frames, client_game)
+        });
+
+        let (stream, _) = listener.accept().unwrap();
+        let host = play(Connection::new(stream).unwrap(), 0, frames, host_game);
+
+        (host, client.join().unwrap())
+    }
+
+    #[test]
+    fn both_sides_agree() {
+        let ((host_state, host), (client_state, client)) =
+            run_pair(600, Game::default(), Game::default());
+
+        assert_eq!(host_state, client_state);
+        assert_eq!(host.desync(), None);
+        assert_eq!(client.desync(), None);
+
+        // Same result as running every input locally
+        let mut reference = Game::default();
+        for frame in 0..600 {
+            let input = |port: u32| {
+                if frame < 2 {
+                    Buttons::empty()
+                } else {
+                    Buttons::from_bits_truncate(((frame - 2) / 3 * 37 + port) as u8)
+                }
+            };
+            reference.run_frame([input(0), input(1)]);
+        }
+        assert_eq!(reference.state, host_state);
+    }
+
+    #[test]
+    fn detect_desync() {
+        let corrupted = Game {
+            corrupt_at: Some(100),
+            ..Default::default()
+        };
+        let ((_, host), (_, client)) = run_pair(300, Game::default(), corrupted);
+
+        // The first hash after the corruption
+        assert!(host.desync() == Some(120) || client.desync() == Some(120));
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    io::{self, ErrorKind, Read, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+};
+
+/// Bumped whenever the messages change, both sides must agree on it
+pub const PROTOCOL_VERSION: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    Hello {
+        version: u8,
+        rom_crc32: u32,
+        input_delay: u8,
+    },
+    /// Buttons of the sender for `frame`
+    Input { frame: u32, buttons: u8 },
+    /// RAM checksum of the sender at the end of confirmed `frame`
+    Hash { frame: u32, hash: u32 },
+}
+
+impl Message {
+    const HELLO: u8 = 0;
+    const INPUT: u8 = 1;
+    const HASH: u8 = 2;
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        match *self {
+            Message::Hello {
+                version,
+                rom_crc32,
+                input_delay,
+            } => {
+                out.push(Self::HELLO);
+                out.push(version);
+                out.extend_from_slice(&rom_crc32.to_le_bytes());
+                out.push(input_delay);
+            }
+            Message::Input { frame, buttons } => {
+                out.push(Self::INPUT);
+                out.extend_from_slice(&frame.to_le_bytes());
+                out.push(buttons);
+            }
+            Message::Hash { frame, hash } => {
+                out.push(Self::HASH);
+                out.extend_from_slice(&frame.to_le_bytes());
+                out.extend_from_slice(&hash.to_le_bytes());
+            }
+        }
+    }
+
+    /// Decode one message from the front of `bytes`, with the number of bytes it used.
+    /// `Ok(None)` if more bytes are needed
+    fn decode(bytes: &[u8]) -> io::Result<Option<(Message, usize)>> {
+        let tag = match bytes.first() {
+            Some(&tag) => tag,
+            None => return Ok(None),
+        };
+
+        let size = match tag {
+            Self::HELLO => 7,
+            Self::INPUT => 6,
+            Self::HASH => 9,
+            _ => {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    format!("unknown netplay message {}", tag),
+                ))
+            }
+        };
+        if bytes.len() < size {
+            return Ok(None);
+        }
+
+        let u32_at = |offset: usize| {
+            u32::from_le_bytes([
+                bytes[offset],
+                bytes[offset + 1],
+                bytes[offset + 2],
+                bytes[offset + 3],
+            ])
+        };
+
+        let message = match tag {
+            Self::HELLO => Message::Hello {
+                version: bytes[1],
+                rom_crc32: u32_at(2),
+                input_delay: bytes[6],
+            },
+            Self::INPUT => Message::Input {
+                frame: u32_at(1),
+                buttons: bytes[5],
+            },
+            _ => Message::Hash {
+                frame: u32_at(1),
+                hash: u32_at(5),
+            },
+        };
+
+        Ok(Some((message, size)))
+    }
+}
+
+/// Non-blocking message stream to the other player
+#[derive(Debug)]
+pub struct Connection {
+    stream: TcpStream,
+    incoming: Vec<u8>,
+    outgoing: Vec<u8>,
+}
+
+impl Connection {
+    /// Wait for the other player to connect on `port`
+    pub fn host(port: u16) -> io::Result<Connection> {
+        let listener = TcpListener::bind(("0.0.0.0", port))?;
+        let (stream, _) = listener.accept()?;
+        Connection::new(stream)
+    }
+
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<Connection> {
+        Connection::new(TcpStream::connect(addr)?)
+    }
+
+    pub fn new(stream: TcpStream) -> io::Result<Connection> {
+        stream.set_nodelay(true)?;
+        stream.set_nonblocking(true)?;
+
+        Ok(Connection {
+            stream,
+            incoming: Vec::new(),
+            outgoing: Vec::new(),
+        })
+    }
+
+    /// Queue a message, it is sent on the next [`Connection::flush`]
+    pub fn send(&mut self, message: Message) {
+        message.encode(&mut self.outgoing);
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        while !self.outgoing.is_empty() {
+            match self.stream.write(&self.outgoing) {
+                Ok(0) => return Err(ErrorKind::WriteZero.into()),
+                Ok(n) => {
+                    self.outgoing.drain(..n);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Every message received so far, never blocks
+    pub fn receive(&mut self) -> io::Result<Vec<Message>> {
+        let mut buffer = [0u8; 1024];
+        loop {
+            match self.stream.read(&mut buffer) {
+                Ok(0) => return Err(ErrorKind::ConnectionAborted.into()),
+                Ok(n) => self.incoming.extend_from_slice(&buffer[..n]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let mut messages = Vec::new();
+        let mut offset = 0;
+        while let Some((message, size)) = Message::decode(&self.incoming[offset..])? {
+            messages.push(message);
+            offset += size;
+        }
+        self.incoming.drain(..offset);
+
+        Ok(messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode() {
+        let messages = [
+            Message::Hello {
+                version: PROTOCOL_VERSION,
+                rom_crc32: 0xDEADBEEF,
+                input_delay: 2,
+            },
+            Message::Input {
+                frame: 1234,
+                buttons: 0x81,
+            },
+            Message::Hash {
+                frame: 60,
+                hash: 0x12345678,
+            },
+        ];
+
+        let mut bytes = Vec::new();
+        for message in messages.iter() {
+            message.encode(&mut bytes);
+        }
+
+        let mut decoded = Vec::new();
+        let mut offset = 0;
+        while let Some((message, size)) = Message::decode(&bytes[offset..]).unwrap() {
+            decoded.push(message);
+            offset += size;
+        }
+        assert_eq!(decoded, messages);
+
+        // Partial message wait for more bytes
+        assert!(Message::decode(&bytes[..3]).unwrap().is_none());
+    }
+}
@@ -0,0 +1,428 @@
+use std::{ffi::OsString, fs::File, io::Read};
+
+use utils::prelude::*;
+
+use crate::{
+    apu2A03::{Apu2A03, CPU_CLOCK_NTSC, CPU_CLOCK_PAL},
+    cartridge::Error,
+    cpu6502::Cpu6502,
+    CpuBus, Device,
+};
+
+const HEADER_SIZE: usize = 0x80;
+
+/// The CPU is parked here between init/play calls, nothing is mapped at this address
+/// so it can never be reached by a real jump
+const IDLE_ADDR: u16 = 0x4100;
+
+/// Give up on an init/play routine that does not return after this many seconds
+const ROUTINE_TIMEOUT_SECONDS: f64 = 2.0;
+
+#[derive(Debug, Clone)]
+pub struct Nsf {
+    pub version: u8,
+    pub total_songs: u8,
+    /// 1-based, as stored in the file
+    pub starting_song: u8,
+    pub load_addr: u16,
+    pub init_addr: u16,
+    pub play_addr: u16,
+    pub name: String,
+    pub artist: String,
+    pub copyright: String,
+    /// Play routine period in microseconds
+    pub ntsc_speed: u16,
+    pub pal_speed: u16,
+    pub bankswitch: [u8; 8],
+    pub pal: bool,
+    pub extra_chips: u8,
+    data: Vec<u8>,
+}
+
+impl Nsf {
+    pub fn from_file(file_path: OsString) -> Result<Nsf, Error> {
+        let mut file = File::open(file_path)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        Nsf::from_bytes(bytes)
+    }
+
+    pub fn from_bytes(mut bytes: Vec<u8>) -> Result<Nsf, Error> {
+        if bytes.len() <= HEADER_SIZE || !bytes.starts_with(b"NESM\x1A") {
+            return Err(Error::InvalidData);
+        }
+
+        let u16_at = |offset: usize| u16::from_le_bytes([bytes[offset], bytes[offset + 1]]);
+        let str_at = |offset: usize| {
+            let raw = &bytes[offset..offset + 32];
+            let len = raw.iter().position(|&b| b == 0).unwrap_or(raw.len());
+            String::from_utf8_lossy(&raw[..len]).into_owned()
+        };
+
+        let mut bankswitch = [0u8; 8];
+        bankswitch.copy_from_slice(&bytes[0x70..0x78]);
+
+        // Bit 0: PAL, bit 1: dual PAL/NTSC which we play as NTSC
+        let region = bytes[0x7A];
+
+        let nsf = Nsf {
+            version: bytes[0x05],
+            total_songs: bytes[0x06],
+            starting_song: bytes[0x07],
+            load_addr: u16_at(0x08),
+            init_addr: u16_at(0x0A),
+            play_addr: u16_at(0x0C),
+            name: str_at(0x0E),
+            artist: str_at(0x2E),
+            copyright: str_at(0x4E),
+            ntsc_speed: u16_at(0x6E),
+            pal_speed: u16_at(0x78),
+            bankswitch,
+            pal: region & 0x03 == 0x01,
+            extra_chips: bytes[0x7B],
+            data: bytes.split_off(HEADER_SIZE),
+        };
+
+        if nsf.load_addr < 0x8000 && !nsf.is_bankswitched() {
+            return Err(Error::InvalidData);
+        }
+
+        Ok(nsf)
+    }
+
+    pub fn is_bankswitched(&self) -> bool {
+        self.bankswitch.iter().any(|&bank| bank != 0)
+    }
+
+    fn clock_rate(&self) -> f64 {
+        if self.pal {
+            CPU_CLOCK_PAL
+        } else {
+            CPU_CLOCK_NTSC
+        }
+    }
+
+    /// How often the play routine is called, in Hz
+    pub fn play_rate(&self) -> f64 {
+        let speed = match (self.pal, self.ntsc_speed, self.pal_speed) {
+            (false, 0, _) => 16639,
+            (false, speed, _) => speed,
+            (true, _, 0) => 19997,
+            (true, _, speed) => speed,
+        };
+
+        1_000_000.0 / speed as f64
+    }
+}
+
+/// Minimal bus for NSF playback: RAM, PRG-RAM at $6000-$7FFF, the APU
+/// and the tune data behind the optional NSF bank switching at $5FF8-$5FFF
+#[derive(Debug)]
+pub struct NsfBus {
+    pub(crate) ram: [u8; 2 * 1024],
+    pub(crate) prg_ram: [u8; 8 * 1024],
+    pub(crate) apu: Apu2A03,
+    rom: Vec<u8>,
+    banks: [u8; 8],
+    bankswitched: bool,
+}
+
+impl NsfBus {
+    pub fn new(nsf: &Nsf) -> NsfBus {
+        // Lay the data out so that 4KiB bank `n` of `rom` is what the tune expect there,
+        // without bank switching this is simply the data placed at its load address
+        let (padding, banks) = if nsf.is_bankswitched() {
+            ((nsf.load_addr & 0x0FFF) as usize, nsf.bankswitch)
+        } else {
+            ((nsf.load_addr - 0x8000) as usize, [0, 1, 2, 3, 4, 5, 6, 7])
+        };
+
+        let mut rom = vec![0u8; padding];
+        rom.extend_from_slice(&nsf.data);
+
+        NsfBus {
+            ram: [0u8; 2 * 1024],
+            prg_ram: [0u8; 8 * 1024],
+            apu: Apu2A03::new(),
+            rom,
+            banks,
+            bankswitched: nsf.is_bankswitched(),
+        }
+    }
+
+    /// Advance the APU by one CPU cycle, serving DMC sample fetches
+    pub fn clock_apu(&mut self) {
+        self.apu.tick();
+        if let Some(addr) = self.apu.dmc_dma_address() {
+            let data = self.read(addr, false);
+            self.apu.dmc_load(data);
+        }
+    }
+
+    pub fn apu(&self) -> &Apu2A03 {
+        &self.apu
+    }
+}
+
+impl CpuBus for NsfBus {
+    fn write(&mut self, addr: u16, data: u8) {
+        match addr {
+            0x0000..=0x1FFF => self.ram[(addr & 0x07FF) as usize] = data,
+            0x4000..=0x4017 => self.apu.cpu_write(addr, data),
+            0x5FF8..=0x5FFF if self.bankswitched => self.banks[(addr - 0x5FF8) as usize] = data,
+            0x6000..=0x7FFF => self.prg_ram[(addr - 0x6000) as usize] = data,
+            _ => {}
+        }
+    }
+
+    fn read(&mut self, addr: u16, _readonly: bool) -> u8 {
+        match addr {
+            0x0000..=0x1FFF => self.ram[(addr & 0x07FF) as usize],
+            0x4015 => self.apu.cpu_read(addr),
+            0x6000..=0x7FFF => self.prg_ram[(addr - 0x6000) as usize],
+            0x8000..=0xFFFF => {
+                let bank = self.banks[((addr - 0x8000) >> 12) as usize] as usize;
+                let offset = bank * 0x1000 + (addr & 0x0FFF) as usize;
+                self.rom.get(offset).copied().unwrap_or(0)
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// Runs the init/play routines of an [`Nsf`] and collect the APU output
+#[derive(Debug)]
+pub struct NsfPlayer {
+    nsf: Nsf,
+    cpu: Cpu6502,
+    bus: NsfBus,
+    track: u8,
+    play_count: u64,
+
+    cycles_per_play: f64,
+    play_counter: f64,
+
+    cycles_per_sample: f64,
+    sample_counter: f64,
+    sample_sum: f32,
+    sample_cycles: u32,
+
+    // DC blocking high-pass filter state
+    last_input: f32,
+    last_output: f32,
+}
+
+impl NsfPlayer {
+    pub fn new(nsf: Nsf, sample_rate: u32) -> NsfPlayer {
+        if nsf.extra_chips != 0 {
+            warn!(
+                "NSF use expansion audio (flags ${:02x}), only the 2A03 channels will be played",
+                nsf.extra_chips
+            );
+        }
+
+        let clock_rate = nsf.clock_rate();
+        let mut player = NsfPlayer {
+            cpu: Cpu6502::new(),
+            bus: NsfBus::new(&nsf),
+            track: 0,
+            play_count: 0,
+            cycles_per_play: clock_rate / nsf.play_rate(),
+            play_counter: 0.0,
+            cycles_per_sample: clock_rate / sample_rate as f64,
+            sample_counter: 0.0,
+            sample_sum: 0.0,
+            sample_cycles: 0,
+            last_input: 0.0,
+            last_output: 0.0,
+            nsf,
+        };
+
+        player.select_track(player.nsf.starting_song.saturating_sub(1));
+        player
+    }
+
+    pub fn nsf(&self) -> &Nsf {
+        &self.nsf
+    }
+
+    pub fn bus(&self) -> &NsfBus {
+        &self.bus
+    }
+
+    /// Current track, 0-based
+    pub fn track(&self) -> u8 {
+        self.track
+    }
+
+    pub fn track_count(&self) -> u8 {
+        self.nsf.total_songs
+    }
+
+    /// Number of time the play routine has been called since the track started
+    pub fn play_count(&self) -> u64 {
+        self.play_count
+    }
+
+    /// Restart the player on `track` (0-based) and run its init routine
+    pub fn select_track(&mut self, track: u8) {
+        let track = track.min(self.nsf.total_songs.saturating_sub(1));
+
+        self.bus = NsfBus::new(&self.nsf);
+        self.cpu.reset(&mut self.bus);
+        self.track = track;
+        self.play_count = 0;
+        self.play_counter = 0.0;
+
+        for addr in 0x4000..=0x4013 {
+            self.bus.write(addr, 0x00);
+        }
+        self.bus.write(0x4015, 0x0F);
+        self.bus.write(0x4017, 0x40);
+
+        let region = self.nsf.pal as u8;
+        self.cpu
+            .call_subroutine(&mut self.bus, self.nsf.init_addr, IDLE_ADDR, track, region);
+
+        let timeout = (self.nsf.clock_rate() * ROUTINE_TIMEOUT_SECONDS) as u64;
+        let mut cycles = 0;
+        while !self.is_idle() {
+            self.cpu.tick(&mut self.bus);
+            self.bus.clock_apu();
+
+            cycles += 1;
+            if cycles > timeout {
+                error!("NSF init routine for track {} never returned", track + 1);
+                break;
+            }
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.cpu.complete() && self.cpu.program_counter() == IDLE_ADDR
+    }
+
+    /// Run the tune until `count` more samples have been produced.
+    /// Samples are centered around 0.0, roughly in -1.0..=1.0
+    pub fn render(&mut self, count: usize) -> Vec<f32> {
+        let mut samples = Vec::with_capacity(count);
+
+        while samples.len() < count {
+            if self.play_counter <= 0.0 {
+                self.play_counter += self.cycles_per_play;
+
+                // A play routine that overrun its frame simply skip the next call
+                if self.is_idle() {
+                    self.cpu.call_subroutine(
+                        &mut self.bus,
+                        self.nsf.play_addr,
+                        IDLE_ADDR,
+                        self.cpu.register_a(),
+                        self.cpu.register_x(),
+                    );
+                    self.play_count += 1;
+                }
+            }
+            self.play_counter -= 1.0;
+
+            if !self.is_idle() {
+                self.cpu.tick(&mut self.bus);
+            }
+            self.bus.clock_apu();
+
+            // Average every cycles that fall into a sample, a cheap low-pass before decimation
+            self.sample_sum += self.bus.apu.output();
+            self.sample_cycles += 1;
+            self.sample_counter += 1.0;
+            if self.sample_counter >= self.cycles_per_sample {
+                self.sample_counter -= self.cycles_per_sample;
+
+                let input = self.sample_sum / self.sample_cycles as f32;
+                self.sample_sum = 0.0;
+                self.sample_cycles = 0;
+
+                let output = input - self.last_input + 0.995 * self.last_output;
+                self.last_input = input;
+                self.last_output = output;
+
+                samples.push(output.clamp(-1.0, 1.0));
+            }
+        }
+
+        samples
+    }
+
+    /// Render `seconds` of the current track at `sample_rate`
+    pub fn render_seconds(&mut self, seconds: f64, sample_rate: u32) -> Vec<f32> {
+        self.render((seconds * sample_rate as f64) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Init enable pulse 1 with a constant volume square wave and store the track number at $01,
+    /// play increment $00
+    fn test_nsf() -> Vec<u8> {
+        #[rustfmt::skip]
+        let program: &[u8] = &[
+            // init: $8000
+            0x85, 0x01,       // STA $01
+            0xA9, 0x01,       // LDA #$01
+            0x8D, 0x15, 0x40, // STA $4015
+            0xA9, 0xBF,       // LDA #$BF
+            0x8D, 0x00, 0x40, // STA $4000
+            0xA9, 0xFD,       // LDA #$FD
+            0x8D, 0x02, 0x40, // STA $4002
+            0xA9, 0x00,       // LDA #$00
+            0x8D, 0x03, 0x40, // STA $4003
+            0x60,             // RTS
+            // play: $8017
+            0xE6, 0x00,       // INC $00
+            0x60,             // RTS
+        ];
+
+        let mut bytes = vec![0u8; HEADER_SIZE];
+        bytes[..5].copy_from_slice(b"NESM\x1A");
+        bytes[0x05] = 1;
+        bytes[0x06] = 3;
+        bytes[0x07] = 1;
+        bytes[0x08..0x0A].copy_from_slice(&0x8000u16.to_le_bytes());
+        bytes[0x0A..0x0C].copy_from_slice(&0x8000u16.to_le_bytes());
+        bytes[0x0C..0x0E].copy_from_slice(&0x8017u16.to_le_bytes());
+        bytes[0x0E..0x12].copy_from_slice(b"Test");
+        bytes[0x6E..0x70].copy_from_slice(&16639u16.to_le_bytes());
+        bytes.extend_from_slice(program);
+        bytes
+    }
+
+    #[test]
+    fn parse_header() {
+        let nsf = Nsf::from_bytes(test_nsf()).ok().unwrap();
+        assert_eq!(nsf.name, "Test");
+        assert_eq!(nsf.total_songs, 3);
+        assert_eq!(nsf.play_addr, 0x8017);
+        assert!(!nsf.is_bankswitched());
+
+        assert!(Nsf::from_bytes(b"NES\x1A".to_vec()).is_err());
+    }
+
+    #[test]
+    fn play_routine_rate() {
+        let nsf = Nsf::from_bytes(test_nsf()).ok().unwrap();
+        let mut player = NsfPlayer::new(nsf, 44100);
+        player.select_track(2);
+        assert_eq!(player.bus().ram[0x01], 2);
+
+        let samples = player.render_seconds(1.0, 44100);
+        assert_eq!(samples.len(), 44100);
+        assert_eq!(player.play_count(), 61);
+        assert_eq!(player.bus().ram[0x00], 61);
+
+        // The square wave should actually be audible
+        let peak = samples.iter().fold(0f32, |acc, s| acc.max(s.abs()));
+        assert!(peak > 0.05);
+    }
+}
@@ -0,0 +1,131 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use ggez::event::KeyCode;
+
+#[derive(Debug, Clone)]
+enum Entry {
+    Recent(PathBuf),
+    Parent,
+    Dir(PathBuf),
+    Rom(PathBuf),
+}
+
+pub enum Action {
+    None,
+    Load(PathBuf),
+    Close,
+}
+
+/// Keyboard driven ROM browser drawn inside the window, recent ROMs are listed first
+#[derive(Debug)]
+pub struct FilePicker {
+    dir: PathBuf,
+    recent: Vec<PathBuf>,
+    entries: Vec<Entry>,
+    selected: usize,
+}
+
+fn is_rom(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("nes"))
+}
+
+impl FilePicker {
+    pub fn new(dir: PathBuf, recent: &[PathBuf]) -> FilePicker {
+        let mut picker = FilePicker {
+            dir,
+            recent: recent.to_vec(),
+            entries: Vec::new(),
+            selected: 0,
+        };
+
+        picker.refresh();
+        picker
+    }
+
+    fn refresh(&mut self) {
+        let mut dirs = Vec::new();
+        let mut roms = Vec::new();
+        if let Ok(read_dir) = fs::read_dir(&self.dir) {
+            for path in read_dir.flatten().map(|entry| entry.path()) {
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if is_rom(&path) {
+                    roms.push(path);
+                }
+            }
+        }
+        dirs.sort();
+        roms.sort();
+
+        self.entries = self.recent.iter().cloned().map(Entry::Recent).collect();
+        if self.dir.parent().is_some() {
+            self.entries.push(Entry::Parent);
+        }
+        self.entries.extend(dirs.into_iter().map(Entry::Dir));
+        self.entries.extend(roms.into_iter().map(Entry::Rom));
+        self.selected = 0;
+    }
+
+    fn change_dir(&mut self, dir: PathBuf) {
+        self.dir = dir;
+        self.refresh();
+    }
+
+    pub fn key_down(&mut self, keycode: KeyCode) -> Action {
+        match keycode {
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => {
+                self.selected = (self.selected + 1).min(self.entries.len().saturating_sub(1))
+            }
+            KeyCode::Back => {
+                if let Some(parent) = self.dir.parent() {
+                    self.change_dir(parent.to_path_buf());
+                }
+            }
+            KeyCode::Return => match self.entries.get(self.selected).cloned() {
+                Some(Entry::Recent(path)) | Some(Entry::Rom(path)) => return Action::Load(path),
+                Some(Entry::Dir(path)) => self.change_dir(path),
+                Some(Entry::Parent) => {
+                    if let Some(parent) = self.dir.parent() {
+                        self.change_dir(parent.to_path_buf());
+                    }
+                }
+                None => {}
+            },
+            KeyCode::Escape => return Action::Close,
+            _ => {}
+        }
+
+        Action::None
+    }
+
+    /// Lines to draw, at most `count` of them around the selection, with whether it is selected
+    pub fn lines(&self, count: usize) -> Vec<(String, bool)> {
+        let start = self
+            .selected
+            .saturating_sub(count / 2)
+            .min(self.entries.len().saturating_sub(count));
+
+        let file_name = |path: &Path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        };
+
+        let mut lines = vec![(format!("{}", self.dir.display()), false)];
+        for (index, entry) in self.entries.iter().enumerate().skip(start).take(count) {
+            let text = match entry {
+                Entry::Recent(path) => format!("[recent] {}", file_name(path)),
+                Entry::Parent => "../".to_string(),
+                Entry::Dir(path) => format!("{}/", file_name(path)),
+                Entry::Rom(path) => file_name(path),
+            };
+            lines.push((text, index == self.selected));
+        }
+        lines
+    }
+}
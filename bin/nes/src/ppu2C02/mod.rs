@@ -1,39 +1,86 @@
 mod palette;
 use palette::PALETTE;
 
+use crate::cartridge::{Cartridge, Mirror};
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Pixel(u8, u8, u8, u8);
 
 const BLACK: Pixel = Pixel(0, 0, 0, 255);
 
+const CONTROL_INCREMENT_MODE: u8 = 1 << 2;
+const CONTROL_ENABLE_NMI: u8 = 1 << 7;
+const STATUS_VERTICAL_BLANK: u8 = 1 << 7;
+
 pub const SCREEN_WIDTH: usize = 256;
 pub const SCREEN_HEIGHT: usize = 240;
 pub const PATTERN_TABLE_SIZE: usize = 128;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Ppu2C02 {
-    pub(crate) name_table: [[u8; 1024]; 2],
+    /// The console only has the first two, the others are for four-screen cartridges
+    pub(crate) name_table: [[u8; 1024]; 4],
     pub(crate) palette_table: [u8; 32],
     pub(crate) pattern_table: [u8; 4096],
     cycle: i16,
     scanline: i16,
+    /// Last scanline before the pre-render one, PAL frames are longer
+    last_scanline: i16,
     frame_complete: bool,
+    nmi: bool,
+
+    // Registers the CPU sees at $2000-$2007
+    control: u8,
+    status: u8,
+    oam_address: u8,
+    oam: [u8; 256],
+    /// Whether the next write to Scroll or PPU Address is the second one
+    address_latch: bool,
+    vram_address: u16,
+    data_buffer: u8,
 
     rendered_screen: Vec<Pixel>,
     rendered_name_table: [Vec<Pixel>; 2],
     rendered_pattern_table: [Vec<Pixel>; 2],
 }
 
+/// Memory and registers of the PPU, without the rendered images which are only output
+#[derive(Debug, Clone)]
+pub struct PpuState {
+    name_table: [[u8; 1024]; 4],
+    palette_table: [u8; 32],
+    cycle: i16,
+    scanline: i16,
+    frame_complete: bool,
+    nmi: bool,
+    control: u8,
+    status: u8,
+    oam_address: u8,
+    oam: [u8; 256],
+    address_latch: bool,
+    vram_address: u16,
+    data_buffer: u8,
+}
+
 impl Ppu2C02 {
     pub fn new() -> Ppu2C02 {
         Ppu2C02 {
-            name_table: [[0u8; 1024]; 2],
+            name_table: [[0u8; 1024]; 4],
             palette_table: [0u8; 32],
             pattern_table: [0u8; 4096],
             cycle: 0,
             scanline: 0,
+            last_scanline: 260,
             frame_complete: false,
+            nmi: false,
+            control: 0,
+            status: 0,
+            oam_address: 0,
+            oam: [0u8; 256],
+            address_latch: false,
+            vram_address: 0,
+            data_buffer: 0,
             rendered_screen: vec![BLACK; SCREEN_WIDTH * SCREEN_HEIGHT],
             rendered_name_table: [
                 vec![BLACK; SCREEN_WIDTH * SCREEN_HEIGHT],
@@ -46,6 +93,55 @@ impl Ppu2C02 {
         }
     }
 
+    /// Whether a frame has been completed since the last call
+    pub fn take_frame_complete(&mut self) -> bool {
+        std::mem::replace(&mut self.frame_complete, false)
+    }
+
+    /// Whether the CPU must take a non-maskable interrupt since the last call
+    pub fn take_nmi(&mut self) -> bool {
+        std::mem::replace(&mut self.nmi, false)
+    }
+
+    /// NTSC frames have 262 scanlines, PAL ones 312
+    pub fn set_pal(&mut self, pal: bool) {
+        self.last_scanline = if pal { 310 } else { 260 };
+    }
+
+    pub fn save_state(&self) -> PpuState {
+        PpuState {
+            name_table: self.name_table,
+            palette_table: self.palette_table,
+            cycle: self.cycle,
+            scanline: self.scanline,
+            frame_complete: self.frame_complete,
+            nmi: self.nmi,
+            control: self.control,
+            status: self.status,
+            oam_address: self.oam_address,
+            oam: self.oam,
+            address_latch: self.address_latch,
+            vram_address: self.vram_address,
+            data_buffer: self.data_buffer,
+        }
+    }
+
+    pub fn load_state(&mut self, state: &PpuState) {
+        self.name_table = state.name_table;
+        self.palette_table = state.palette_table;
+        self.cycle = state.cycle;
+        self.scanline = state.scanline;
+        self.frame_complete = state.frame_complete;
+        self.nmi = state.nmi;
+        self.control = state.control;
+        self.status = state.status;
+        self.oam_address = state.oam_address;
+        self.oam = state.oam;
+        self.address_latch = state.address_latch;
+        self.vram_address = state.vram_address;
+        self.data_buffer = state.data_buffer;
+    }
+
     pub fn screen(&self) -> &[u8] {
         unsafe {
             core::slice::from_raw_parts(
@@ -64,7 +160,12 @@ impl Ppu2C02 {
         }
     }
 
-    pub fn pattern_table(&mut self, index: u8, palette: u8) -> &[u8] {
+    pub fn pattern_table(
+        &mut self,
+        mut cartridge: Option<&mut Cartridge>,
+        index: u8,
+        palette: u8,
+    ) -> &[u8] {
         let index = index as u16;
 
         for tile_y in 0..16 {
@@ -72,8 +173,9 @@ impl Ppu2C02 {
                 let offset = tile_y * 256 + tile_x * 16;
 
                 for row in 0..8 {
-                    let mut tile_lsb = self.ppu_read(index * 0x1000 + offset + row + 0, false);
-                    let mut tile_msb = self.ppu_read(index * 0x1000 + offset + row + 8, false);
+                    let addr = index * 0x1000 + offset + row;
+                    let mut tile_lsb = self.ppu_read(cartridge.as_deref_mut(), addr + 0);
+                    let mut tile_msb = self.ppu_read(cartridge.as_deref_mut(), addr + 8);
 
                     for col in 0..8 {
                         let pixel = (tile_lsb & 0x01) + (tile_msb & 0x01);
@@ -101,90 +203,187 @@ impl Ppu2C02 {
     }
 
     fn color_from_palette_ram(&mut self, palette: u8, pixel: u8) -> Pixel {
-        PALETTE[(self.ppu_read(0x3F00 + ((palette as u16) << 2) + pixel as u16, false) & 0x3F)
-            as usize]
+        let addr = 0x3F00 + ((palette as u16) << 2) + pixel as u16;
+        PALETTE[(self.ppu_read(None, addr) & 0x3F) as usize]
     }
 
     pub fn tick(&mut self) {
+        if self.cycle == 1 {
+            match self.scanline {
+                241 => {
+                    self.status |= STATUS_VERTICAL_BLANK;
+                    if self.control & CONTROL_ENABLE_NMI != 0 {
+                        self.nmi = true;
+                    }
+                }
+                -1 => self.status &= !STATUS_VERTICAL_BLANK,
+                _ => {}
+            }
+        }
+
         self.cycle += 1;
         if self.cycle >= 341 {
             self.cycle = 0;
             self.scanline += 1;
-            if self.scanline >= 261 {
+            if self.scanline > self.last_scanline {
                 self.scanline = -1;
                 self.frame_complete = true;
             }
         }
     }
 
-    pub fn cpu_read(&mut self, addr: u16, readonly: bool) -> u8 {
+    pub fn cpu_read(&mut self, cartridge: Option<&mut Cartridge>, addr: u16, readonly: bool) -> u8 {
         match addr {
-            // Control
-            0x0000 => todo!(),
-
-            // Mask
-            0x0001 => todo!(),
-
-            // Status
-            0x0002 => todo!(),
-
-            // OAM Address
-            0x0003 => todo!(),
+            // Status, the low bits are whatever was last on the data bus
+            0x0002 => {
+                let data = (self.status & 0xE0) | (self.data_buffer & 0x1F);
+                if !readonly {
+                    self.status &= !STATUS_VERTICAL_BLANK;
+                    self.address_latch = false;
+                }
+                data
+            }
 
             // OAM Data
-            0x0004 => todo!(),
-
-            // Scroll
-            0x0005 => todo!(),
+            0x0004 => self.oam[self.oam_address as usize],
 
-            // PPU Address
-            0x0006 => todo!(),
+            // PPU Data, delayed by one read except for the palette
+            0x0007 => {
+                if readonly {
+                    return self.data_buffer;
+                }
+                let mut data = self.data_buffer;
+                self.data_buffer = self.ppu_read(cartridge, self.vram_address);
+                if self.vram_address >= 0x3F00 {
+                    data = self.data_buffer;
+                }
+                self.increment_vram_address();
+                data
+            }
 
-            // PPU Data
-            0x0007 => todo!(),
+            // Control, Mask, OAM Address, Scroll and PPU Address are write only
+            0x0000..=0x0007 => 0,
 
             _ => unreachable!(),
         }
     }
 
-    pub fn cpu_write(&mut self, addr: u16, data: u8) {
+    pub fn cpu_write(&mut self, cartridge: Option<&mut Cartridge>, addr: u16, data: u8) {
         match addr {
-            // Control
-            0x0000 => todo!(),
+            // Control, enabling NMI during vertical blank fire one right away
+            0x0000 => {
+                let enabled = !self.control & data & CONTROL_ENABLE_NMI != 0;
+                if enabled && self.status & STATUS_VERTICAL_BLANK != 0 {
+                    self.nmi = true;
+                }
+                self.control = data;
+            }
 
-            // Mask
-            0x0001 => todo!(),
+            // Mask, rendering isn't emulated yet
+            0x0001 => {}
 
-            // Status
-            0x0002 => todo!(),
+            // Status is read only
+            0x0002 => {}
 
             // OAM Address
-            0x0003 => todo!(),
+            0x0003 => self.oam_address = data,
 
             // OAM Data
-            0x0004 => todo!(),
-
-            // Scroll
-            0x0005 => todo!(),
+            0x0004 => {
+                self.oam[self.oam_address as usize] = data;
+                self.oam_address = self.oam_address.wrapping_add(1);
+            }
 
-            // PPU Address
-            0x0006 => todo!(),
+            // Scroll, x then y through the shared latch
+            0x0005 => self.address_latch = !self.address_latch,
+
+            // PPU Address, high byte then low byte
+            0x0006 => {
+                self.vram_address = if self.address_latch {
+                    (self.vram_address & 0xFF00) | data as u16
+                } else {
+                    (self.vram_address & 0x00FF) | ((data as u16 & 0x3F) << 8)
+                };
+                self.address_latch = !self.address_latch;
+            }
 
             // PPU Data
-            0x0007 => todo!(),
+            0x0007 => {
+                self.ppu_write(cartridge, self.vram_address, data);
+                self.increment_vram_address();
+            }
 
             _ => unreachable!(),
         }
     }
 
-    pub fn ppu_read(&mut self, addr: u16, readonly: bool) -> u8 {
-        let mut data = 0u8;
+    /// By 1 or by a row of 32 tiles, as picked by the control register
+    fn increment_vram_address(&mut self) {
+        let step = if self.control & CONTROL_INCREMENT_MODE != 0 {
+            32
+        } else {
+            1
+        };
+        self.vram_address = self.vram_address.wrapping_add(step) & 0x3FFF;
+    }
+
+    pub fn ppu_read(&mut self, cartridge: Option<&mut Cartridge>, addr: u16) -> u8 {
         let addr = addr & 0x3FFF;
-        data
+        match addr {
+            // Pattern tables, on the cartridge
+            0x0000..=0x1FFF => cartridge
+                .and_then(|cartridge| cartridge.ppu_read(addr))
+                .unwrap_or(0),
+
+            0x2000..=0x3EFF => {
+                let (table, offset) = name_table_index(mirror_of(cartridge), addr);
+                self.name_table[table][offset]
+            }
+
+            _ => self.palette_table[palette_index(addr)],
+        }
     }
 
-    pub fn ppu_write(&mut self, addr: u16, data: u8) {
+    pub fn ppu_write(&mut self, cartridge: Option<&mut Cartridge>, addr: u16, data: u8) {
         let addr = addr & 0x3FFF;
+        match addr {
+            0x0000..=0x1FFF => {
+                if let Some(cartridge) = cartridge {
+                    cartridge.ppu_write(addr, data);
+                }
+            }
+
+            0x2000..=0x3EFF => {
+                let (table, offset) = name_table_index(mirror_of(cartridge), addr);
+                self.name_table[table][offset] = data;
+            }
+
+            _ => self.palette_table[palette_index(addr)] = data,
+        }
+    }
+}
+
+fn mirror_of(cartridge: Option<&mut Cartridge>) -> Mirror {
+    cartridge.map_or(Mirror::Horizontal, |cartridge| cartridge.mirror())
+}
+
+/// The four 1KB screens at $2000-$2FFF are folded on the two tables of the console, the
+/// cartridge picks how. $3000-$3EFF mirror them.
+fn name_table_index(mirror: Mirror, addr: u16) -> (usize, usize) {
+    let screen = ((addr >> 10) & 0x03) as usize;
+    let table = match mirror {
+        Mirror::Horizontal => screen >> 1,
+        Mirror::Vertical => screen & 0x01,
+        Mirror::FourScreen => screen,
+    };
+    (table, (addr & 0x03FF) as usize)
+}
+
+/// The backdrop color of the sprite palettes is the one of the background palettes
+fn palette_index(addr: u16) -> usize {
+    match addr & 0x001F {
+        addr @ (0x10 | 0x14 | 0x18 | 0x1C) => (addr - 0x10) as usize,
+        addr => addr as usize,
     }
 }
 
@@ -0,0 +1,54 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use utils::prelude::*;
+
+const MAX_RECENT: usize = 9;
+
+/// Most recently opened ROMs, newest first, persisted as one path per line
+#[derive(Debug)]
+pub struct RecentRoms {
+    file: PathBuf,
+    roms: Vec<PathBuf>,
+}
+
+impl RecentRoms {
+    pub fn load(file: PathBuf) -> RecentRoms {
+        let roms = fs::read_to_string(&file)
+            .map(|content| {
+                content
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(PathBuf::from)
+                    .take(MAX_RECENT)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        RecentRoms { file, roms }
+    }
+
+    pub fn roms(&self) -> &[PathBuf] {
+        &self.roms
+    }
+
+    pub fn push(&mut self, rom: &Path) {
+        let rom = rom.canonicalize().unwrap_or_else(|_| rom.to_path_buf());
+        self.roms.retain(|r| r != &rom);
+        self.roms.insert(0, rom);
+        self.roms.truncate(MAX_RECENT);
+
+        let content: Vec<_> = self.roms.iter().map(|r| r.to_string_lossy()).collect();
+        let result = self
+            .file
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&self.file, content.join("\n")));
+
+        if let Err(e) = result {
+            warn!("Could not save recent ROMs to {:?}: {}", self.file, e);
+        }
+    }
+}
@@ -1,9 +1,10 @@
-use crate::{cartridge::Cartridge, ppu2C02::Ppu2C02};
+use crate::{cartridge::Cartridge, controller::Controller, ppu2C02::Ppu2C02, CpuBus};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SystemBus {
     pub(crate) ram: [u8; 2 * 1024],
     pub(crate) ppu: Ppu2C02,
+    pub(crate) controllers: [Controller; 2],
     cartridge: Option<Cartridge>,
 }
 
@@ -12,6 +13,7 @@ impl SystemBus {
         SystemBus {
             ram: [0u8; 2 * 1024],
             ppu: Ppu2C02::new(),
+            controllers: [Controller::default(); 2],
             cartridge: None,
         }
     }
@@ -20,26 +22,45 @@ impl SystemBus {
         self.cartridge = Some(cartridge)
     }
 
-    pub fn write(&mut self, addr: u16, data: u8) {
-        let cart = self.cartridge.as_mut().unwrap();
+    pub fn cartridge(&self) -> Option<&Cartridge> {
+        self.cartridge.as_ref()
+    }
 
-        if cart.cpu_write(addr, data) {
+    pub(crate) fn cartridge_mut(&mut self) -> Option<&mut Cartridge> {
+        self.cartridge.as_mut()
+    }
+}
+
+impl CpuBus for SystemBus {
+    fn write(&mut self, addr: u16, data: u8) {
+        if self
+            .cartridge
+            .as_mut()
+            .is_some_and(|cart| cart.cpu_write(addr, data))
+        {
         } else if (0x0000..=0x1fff).contains(&addr) {
             self.ram[(addr & 0x07FF) as usize] = data;
         } else if (0x2000..=0x3FFF).contains(&addr) {
-            self.ppu.cpu_write(addr & 0x0007, data);
+            self.ppu
+                .cpu_write(self.cartridge.as_mut(), addr & 0x0007, data);
+        } else if addr == 0x4016 {
+            // Strobe both controllers at once
+            for controller in self.controllers.iter_mut() {
+                controller.write(data);
+            }
         }
     }
 
-    pub fn read(&mut self, addr: u16, readonly: bool) -> u8 {
-        let cart = self.cartridge.as_mut().unwrap();
-
-        if let Some(data) = cart.cpu_read(addr) {
+    fn read(&mut self, addr: u16, readonly: bool) -> u8 {
+        if let Some(data) = self.cartridge.as_mut().and_then(|cart| cart.cpu_read(addr)) {
             data
         } else if (0x0000..=0x1fff).contains(&addr) {
             self.ram[(addr & 0x07FF) as usize]
         } else if (0x2000..=0x3FFF).contains(&addr) {
-            self.ppu.cpu_read(addr & 0x0007, readonly)
+            self.ppu
+                .cpu_read(self.cartridge.as_mut(), addr & 0x0007, readonly)
+        } else if (0x4016..=0x4017).contains(&addr) {
+            self.controllers[(addr - 0x4016) as usize].read(readonly)
         } else {
             0
         }
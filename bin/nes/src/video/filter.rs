@@ -0,0 +1,121 @@
+use super::Frame;
+
+/// Brightness of the gap between two scanlines
+const SCANLINE_INTENSITY: u32 = 160;
+
+/// Double the picture and darken every other line, like the gaps between
+/// scanlines on a CRT
+pub fn scanlines(frame: &Frame) -> Frame {
+    let width = frame.width * 2;
+    let height = frame.height * 2;
+    let mut pixels = Vec::with_capacity(width * height * 4);
+
+    for y in 0..frame.height {
+        let row = &frame.pixels[y * frame.width * 4..(y + 1) * frame.width * 4];
+
+        for pixel in row.chunks_exact(4) {
+            pixels.extend_from_slice(pixel);
+            pixels.extend_from_slice(pixel);
+        }
+
+        for pixel in row.chunks_exact(4) {
+            let dim = |c: u8| (c as u32 * SCANLINE_INTENSITY / 255) as u8;
+            let dimmed = [dim(pixel[0]), dim(pixel[1]), dim(pixel[2]), pixel[3]];
+            pixels.extend_from_slice(&dimmed);
+            pixels.extend_from_slice(&dimmed);
+        }
+    }
+
+    Frame {
+        width,
+        height,
+        scale: (frame.scale.0 * 2, frame.scale.1 * 2),
+        pixels,
+    }
+}
+
+/// Weighted YUV distance used by xBR to find edges
+fn distance(a: [u8; 4], b: [u8; 4]) -> u32 {
+    let yuv = |p: [u8; 4]| {
+        let (r, g, b) = (p[0] as f32, p[1] as f32, p[2] as f32);
+        (
+            0.299 * r + 0.587 * g + 0.114 * b,
+            -0.169 * r - 0.331 * g + 0.5 * b,
+            0.5 * r - 0.419 * g - 0.081 * b,
+        )
+    };
+
+    let (ya, ua, va) = yuv(a);
+    let (yb, ub, vb) = yuv(b);
+    ((ya - yb).abs() * 48.0 + (ua - ub).abs() * 7.0 + (va - vb).abs() * 6.0) as u32
+}
+
+fn blend(a: [u8; 4], b: [u8; 4]) -> [u8; 4] {
+    let mix = |i: usize| ((a[i] as u16 + b[i] as u16) / 2) as u8;
+    [mix(0), mix(1), mix(2), mix(3)]
+}
+
+/// 2xBR (level 1): each pixel become a 2x2 block, and a corner is blended with
+/// its neighbour when an edge cuts through it
+pub fn xbr2x(frame: &Frame) -> Frame {
+    let width = frame.width * 2;
+    let height = frame.height * 2;
+    let mut pixels = vec![0u8; width * height * 4];
+
+    for y in 0..frame.height as isize {
+        for x in 0..frame.width as isize {
+            let e = frame.pixel(x, y);
+
+            // Each rotation handle one corner of the output block, the offsets
+            // are written for the bottom right corner and rotated by 90° each time
+            for rotation in 0..4 {
+                let rotate =
+                    |dx: isize, dy: isize| (0..rotation).fold((dx, dy), |(dx, dy), _| (-dy, dx));
+                let at = |dx: isize, dy: isize| {
+                    let (dx, dy) = rotate(dx, dy);
+                    frame.pixel(x + dx, y + dy)
+                };
+
+                let (f, h, i) = (at(1, 0), at(0, 1), at(1, 1));
+                let (b, c, d, g) = (at(0, -1), at(1, -1), at(-1, 0), at(-1, 1));
+                let (f4, i4, h5, i5) = (at(2, 0), at(2, 1), at(0, 2), at(1, 2));
+
+                let mut out = e;
+                if e != f && e != h {
+                    let edge_e = distance(e, c)
+                        + distance(e, g)
+                        + distance(i, h5)
+                        + distance(i, f4)
+                        + 4 * distance(h, f);
+                    let edge_i = distance(h, d)
+                        + distance(h, i5)
+                        + distance(f, i4)
+                        + distance(f, b)
+                        + 4 * distance(e, i);
+
+                    if edge_e < edge_i {
+                        let px = if distance(e, f) <= distance(e, h) {
+                            f
+                        } else {
+                            h
+                        };
+                        out = blend(e, px);
+                    }
+                }
+
+                let (cx, cy) = rotate(1, 1);
+                let ox = (x * 2 + (cx + 1) / 2) as usize;
+                let oy = (y * 2 + (cy + 1) / 2) as usize;
+                let index = (oy * width + ox) * 4;
+                pixels[index..index + 4].copy_from_slice(&out);
+            }
+        }
+    }
+
+    Frame {
+        width,
+        height,
+        scale: (frame.scale.0 * 2, frame.scale.1 * 2),
+        pixels,
+    }
+}
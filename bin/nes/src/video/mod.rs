@@ -0,0 +1,251 @@
+mod filter;
+mod ntsc;
+
+use std::io::{self, Write};
+
+use crate::ppu2C02::{SCREEN_HEIGHT, SCREEN_WIDTH};
+
+/// Pixel aspect ratio of the NES on a 4:3 NTSC television
+pub const PIXEL_ASPECT_RATIO: f32 = 8.0 / 7.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    None,
+    Scanlines,
+    Xbr,
+    Ntsc,
+}
+
+impl Filter {
+    pub const ALL: [Filter; 4] = [Filter::None, Filter::Scanlines, Filter::Xbr, Filter::Ntsc];
+
+    /// Cycle through the filters, for the window hotkey
+    pub fn next(self) -> Filter {
+        let index = Self::ALL.iter().position(|&f| f == self).unwrap();
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Filter::None => "none",
+            Filter::Scanlines => "scanlines",
+            Filter::Xbr => "xbr",
+            Filter::Ntsc => "ntsc",
+        }
+    }
+}
+
+impl std::str::FromStr for Filter {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .find(|f| f.name().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or(())
+    }
+}
+
+/// Number of NES pixels cut from each edge of the picture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overscan {
+    pub top: usize,
+    pub bottom: usize,
+    pub left: usize,
+    pub right: usize,
+}
+
+impl Overscan {
+    pub const NONE: Overscan = Overscan {
+        top: 0,
+        bottom: 0,
+        left: 0,
+        right: 0,
+    };
+
+    /// What a typical NTSC television hide
+    pub const NTSC: Overscan = Overscan {
+        top: 8,
+        bottom: 8,
+        left: 0,
+        right: 0,
+    };
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct VideoSettings {
+    pub filter: Filter,
+    pub overscan: Overscan,
+    /// Stretch horizontally by [`PIXEL_ASPECT_RATIO`]
+    pub aspect_correction: bool,
+    /// Only scale by whole multiples of the NES resolution
+    pub integer_scaling: bool,
+}
+
+impl Default for VideoSettings {
+    fn default() -> Self {
+        VideoSettings {
+            filter: Filter::None,
+            overscan: Overscan::NTSC,
+            aspect_correction: true,
+            integer_scaling: true,
+        }
+    }
+}
+
+/// RGBA8 picture produced by the video pipeline
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    /// How many frame pixels make up one NES pixel, horizontally and vertically
+    pub scale: (usize, usize),
+    pub pixels: Vec<u8>,
+}
+
+impl Frame {
+    /// Crop the overscan out of a full [`crate::ppu2C02::Ppu2C02::screen`]
+    pub fn from_screen(screen: &[u8], overscan: Overscan) -> Frame {
+        let width = SCREEN_WIDTH - overscan.left - overscan.right;
+        let height = SCREEN_HEIGHT - overscan.top - overscan.bottom;
+
+        let mut pixels = Vec::with_capacity(width * height * 4);
+        for y in overscan.top..SCREEN_HEIGHT - overscan.bottom {
+            let row = y * SCREEN_WIDTH * 4;
+            pixels.extend_from_slice(
+                &screen[row + overscan.left * 4..row + (SCREEN_WIDTH - overscan.right) * 4],
+            );
+        }
+
+        Frame {
+            width,
+            height,
+            scale: (1, 1),
+            pixels,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn pixel(&self, x: isize, y: isize) -> [u8; 4] {
+        let x = x.clamp(0, self.width as isize - 1) as usize;
+        let y = y.clamp(0, self.height as isize - 1) as usize;
+        let i = (y * self.width + x) * 4;
+        [
+            self.pixels[i],
+            self.pixels[i + 1],
+            self.pixels[i + 2],
+            self.pixels[i + 3],
+        ]
+    }
+
+    /// Dump as a binary PPM, alpha is dropped
+    pub fn write_ppm<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        for pixel in self.pixels.chunks_exact(4) {
+            writer.write_all(&pixel[..3])?;
+        }
+        Ok(())
+    }
+}
+
+/// Run the PPU output through overscan cropping and the selected filter
+pub fn process(screen: &[u8], settings: &VideoSettings) -> Frame {
+    let frame = Frame::from_screen(screen, settings.overscan);
+    match settings.filter {
+        Filter::None => frame,
+        Filter::Scanlines => filter::scanlines(&frame),
+        Filter::Xbr => filter::xbr2x(&frame),
+        Filter::Ntsc => ntsc::composite(&frame),
+    }
+}
+
+/// Where and how big a [`Frame`] should be drawn inside an area of the window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub scale_x: f32,
+    pub scale_y: f32,
+}
+
+impl Viewport {
+    pub fn fit(frame: &Frame, settings: &VideoSettings, area_width: f32, area_height: f32) -> Self {
+        let par = if settings.aspect_correction {
+            PIXEL_ASPECT_RATIO
+        } else {
+            1.0
+        };
+
+        // Size of the picture in NES pixels, with the aspect correction applied
+        let nes_width = (frame.width / frame.scale.0) as f32 * par;
+        let nes_height = (frame.height / frame.scale.1) as f32;
+
+        let mut scale = (area_width / nes_width).min(area_height / nes_height);
+        if settings.integer_scaling {
+            scale = scale.floor().max(1.0);
+        }
+
+        let scale_x = scale * par / frame.scale.0 as f32;
+        let scale_y = scale / frame.scale.1 as f32;
+
+        Viewport {
+            x: ((area_width - frame.width as f32 * scale_x) / 2.0)
+                .max(0.0)
+                .floor(),
+            y: ((area_height - frame.height as f32 * scale_y) / 2.0)
+                .max(0.0)
+                .floor(),
+            scale_x,
+            scale_y,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen() -> Vec<u8> {
+        (0..SCREEN_WIDTH * SCREEN_HEIGHT)
+            .flat_map(|i| [(i % 256) as u8, (i / 256) as u8, 0x80, 0xFF])
+            .collect()
+    }
+
+    #[test]
+    fn overscan_crop() {
+        let frame = Frame::from_screen(&screen(), Overscan::NTSC);
+        assert_eq!((frame.width, frame.height), (256, 224));
+        assert_eq!(frame.pixel(0, 0), [0, 8, 0x80, 0xFF]);
+        assert_eq!(frame.pixels.len(), 256 * 224 * 4);
+    }
+
+    #[test]
+    fn filter_output_size() {
+        for &filter in Filter::ALL.iter() {
+            let settings = VideoSettings {
+                filter,
+                ..Default::default()
+            };
+            let frame = process(&screen(), &settings);
+            assert_eq!(frame.width, 256 * frame.scale.0, "{:?}", filter);
+            assert_eq!(frame.height, 224 * frame.scale.1, "{:?}", filter);
+            assert_eq!(frame.pixels.len(), frame.width * frame.height * 4);
+        }
+    }
+
+    #[test]
+    fn integer_viewport() {
+        let settings = VideoSettings::default();
+        let frame = Frame::from_screen(&screen(), settings.overscan);
+
+        let viewport = Viewport::fit(&frame, &settings, 1000.0, 700.0);
+        assert_eq!(viewport.scale_y, 3.0);
+        assert!((viewport.scale_x - 3.0 * PIXEL_ASPECT_RATIO).abs() < 1e-4);
+        assert_eq!(viewport.y, 14.0);
+
+        // Never scale below 1x, even if the window is tiny
+        let viewport = Viewport::fit(&frame, &settings, 100.0, 100.0);
+        assert_eq!(viewport.scale_y, 1.0);
+    }
+}
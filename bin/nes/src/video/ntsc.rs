@@ -0,0 +1,83 @@
+use std::f32::consts::PI;
+
+use super::Frame;
+
+/// Each NES pixel is 2/3 of a color subcarrier cycle, we take 2 samples per pixel
+/// so a full cycle is exactly 3 samples
+const SAMPLES_PER_CYCLE: usize = 3;
+
+/// The subcarrier phase shift by a third of a cycle every scanline
+const LINE_PHASE_STEP: f32 = 2.0 * PI / 3.0;
+
+fn to_yiq(p: &[u8]) -> (f32, f32, f32) {
+    let (r, g, b) = (p[0] as f32, p[1] as f32, p[2] as f32);
+    (
+        0.299 * r + 0.587 * g + 0.114 * b,
+        0.596 * r - 0.274 * g - 0.322 * b,
+        0.211 * r - 0.523 * g + 0.312 * b,
+    )
+}
+
+fn to_rgb(y: f32, i: f32, q: f32) -> [u8; 3] {
+    let clamp = |c: f32| c.round().clamp(0.0, 255.0) as u8;
+    [
+        clamp(y + 0.956 * i + 0.621 * q),
+        clamp(y - 0.272 * i - 0.647 * q),
+        clamp(y - 1.106 * i + 1.703 * q),
+    ]
+}
+
+/// Encode every line into a composite signal and decode it back with simple box
+/// filters, which gives the color bleeding and fringing of a real composite
+/// connection. The output is twice as wide as the input
+pub fn composite(frame: &Frame) -> Frame {
+    let width = frame.width * 2;
+    let height = frame.height;
+    let mut pixels = Vec::with_capacity(width * height * 4);
+
+    let mut signal = vec![0f32; width];
+    let mut phases = vec![0f32; width];
+
+    for y in 0..height {
+        let line_phase = (y % 3) as f32 * LINE_PHASE_STEP;
+        let row = &frame.pixels[y * frame.width * 4..(y + 1) * frame.width * 4];
+
+        for x in 0..width {
+            let (luma, i, q) = to_yiq(&row[(x / 2) * 4..]);
+            let phase = x as f32 * 2.0 * PI / SAMPLES_PER_CYCLE as f32 + line_phase;
+            phases[x] = phase;
+            signal[x] = luma + i * phase.cos() + q * phase.sin();
+        }
+
+        for x in 0..width {
+            // One cycle cancel the chroma out for luma, two cycles to demodulate chroma
+            let window = |len: usize| {
+                let start = x.saturating_sub(len / 2);
+                let end = (start + len).min(width);
+                start..end
+            };
+
+            let luma_range = window(SAMPLES_PER_CYCLE);
+            let luma = signal[luma_range.clone()].iter().sum::<f32>() / luma_range.len() as f32;
+
+            let chroma_range = window(SAMPLES_PER_CYCLE * 2);
+            let len = chroma_range.len() as f32;
+            let (mut i, mut q) = (0.0, 0.0);
+            for n in chroma_range {
+                i += signal[n] * phases[n].cos();
+                q += signal[n] * phases[n].sin();
+            }
+
+            let rgb = to_rgb(luma, 2.0 * i / len, 2.0 * q / len);
+            pixels.extend_from_slice(&rgb);
+            pixels.push(row[(x / 2) * 4 + 3]);
+        }
+    }
+
+    Frame {
+        width,
+        height,
+        scale: (frame.scale.0 * 2, frame.scale.1),
+        pixels,
+    }
+}
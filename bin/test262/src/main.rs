@@ -0,0 +1,216 @@
+mod metadata;
+mod runner;
+
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+    time::Duration,
+};
+
+use clap::{crate_version, Arg};
+use runner::{Outcome, Runner};
+use walkdir::WalkDir;
+
+struct Options {
+    test262_dir: PathBuf,
+    /// Directories or files under `test/` to run, everything when empty
+    filters: Vec<PathBuf>,
+    baseline: PathBuf,
+    timeout: Duration,
+    verbose: bool,
+}
+
+fn parse_args() -> Options {
+    let matches = clap::App::new("test262 runner")
+        .version(crate_version!())
+        .about("Run a tc39/test262 checkout against the js crate")
+        .arg(
+            Arg::with_name("test262")
+                .help("Root of the test262 checkout")
+                .required(true)
+                .index(1),
+        )
+        .arg(
+            Arg::with_name("filter")
+                .help("Only run these directories or files, relative to test/")
+                .multiple(true)
+                .index(2),
+        )
+        .arg(
+            Arg::with_name("baseline")
+                .long("baseline")
+                .takes_value(true)
+                .default_value("test262.baseline")
+                .help("File to write the result of every test to"),
+        )
+        .arg(
+            Arg::with_name("timeout")
+                .long("timeout")
+                .takes_value(true)
+                .default_value("10")
+                .help("Seconds before a test is counted as failed"),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .long("verbose")
+                .short("v")
+                .help("Print why each failing test failed"),
+        )
+        .get_matches();
+
+    let timeout = matches.value_of("timeout").unwrap();
+    let timeout = timeout.parse().unwrap_or_else(|_| {
+        eprintln!("error: invalid value '{}' for '--timeout'", timeout);
+        process::exit(1);
+    });
+
+    Options {
+        test262_dir: matches.value_of_os("test262").map(PathBuf::from).unwrap(),
+        filters: matches
+            .values_of_os("filter")
+            .map(|filters| filters.map(PathBuf::from).collect())
+            .unwrap_or_default(),
+        baseline: matches.value_of_os("baseline").map(PathBuf::from).unwrap(),
+        timeout: Duration::from_secs(timeout),
+        verbose: matches.is_present("verbose"),
+    }
+}
+
+/// Test files under `roots`, in a stable order. `_FIXTURE` files are imported by module tests
+fn collect_tests(roots: &[PathBuf]) -> Vec<PathBuf> {
+    let mut tests: Vec<_> = roots
+        .iter()
+        .flat_map(|root| WalkDir::new(root).into_iter().filter_map(Result::ok))
+        .map(|entry| entry.into_path())
+        .filter(|path| {
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            name.ends_with(".js") && !name.contains("_FIXTURE")
+        })
+        .collect();
+
+    tests.sort();
+    tests.dedup();
+    tests
+}
+
+#[derive(Default)]
+struct Summary {
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+}
+
+impl Summary {
+    fn add(&mut self, outcome: &Outcome) {
+        match outcome {
+            Outcome::Pass => self.passed += 1,
+            Outcome::Fail(_) => self.failed += 1,
+            Outcome::Skip(_) => self.skipped += 1,
+        }
+    }
+
+    fn print(&self, name: &str) {
+        let run = self.passed + self.failed;
+        let percent = if run == 0 {
+            0.0
+        } else {
+            self.passed as f64 * 100.0 / run as f64
+        };
+        println!(
+            "{:<60} {:>6}/{:<6} {:>6.2}%  ({} skipped)",
+            name, self.passed, run, percent, self.skipped
+        );
+    }
+}
+
+/// One `STATUS path` line per test, sorted, so a regression is a one line diff
+fn write_baseline(path: &Path, results: &[(String, Outcome)]) -> io::Result<()> {
+    let mut file = io::BufWriter::new(fs::File::create(path)?);
+    for (test, outcome) in results {
+        let status = match outcome {
+            Outcome::Pass => "PASS",
+            Outcome::Fail(_) => "FAIL",
+            Outcome::Skip(_) => "SKIP",
+        };
+        writeln!(file, "{} {}", status, test)?;
+    }
+    file.flush()
+}
+
+fn main() {
+    if env::args_os()
+        .nth(1)
+        .is_some_and(|arg| arg == runner::CHILD_ARG)
+    {
+        runner::run_child();
+    }
+
+    let options = parse_args();
+
+    let test_dir = options.test262_dir.join("test");
+    if !test_dir.is_dir() || !options.test262_dir.join("harness").is_dir() {
+        eprintln!(
+            "error: {} is not a test262 checkout",
+            options.test262_dir.display()
+        );
+        process::exit(1);
+    }
+
+    let roots = if options.filters.is_empty() {
+        vec![test_dir.clone()]
+    } else {
+        options.filters.iter().map(|f| test_dir.join(f)).collect()
+    };
+    let tests = collect_tests(&roots);
+
+    let mut runner = match Runner::new(&options.test262_dir, options.timeout) {
+        Ok(runner) => runner,
+        Err(err) => {
+            eprintln!("error: cannot find the runner executable: {}", err);
+            process::exit(1);
+        }
+    };
+    let mut directories: BTreeMap<String, Summary> = BTreeMap::new();
+    let mut total = Summary::default();
+    let mut results = Vec::with_capacity(tests.len());
+
+    for path in tests.iter() {
+        let outcome = runner.run(path);
+        let relative = path.strip_prefix(&options.test262_dir).unwrap_or(path);
+        let name = relative.to_string_lossy().replace('\\', "/");
+        let directory = relative
+            .parent()
+            .map(|dir| dir.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default();
+
+        if options.verbose {
+            if let Outcome::Fail(reason) = &outcome {
+                println!("FAIL {}: {}", name, reason);
+            }
+        }
+
+        directories.entry(directory).or_default().add(&outcome);
+        total.add(&outcome);
+        results.push((name, outcome));
+    }
+
+    for (directory, summary) in directories.iter() {
+        summary.print(directory);
+    }
+    total.print("total");
+
+    if let Err(err) = write_baseline(&options.baseline, &results) {
+        eprintln!(
+            "error: cannot write the baseline to {}: {}",
+            options.baseline.display(),
+            err
+        );
+        process::exit(1);
+    }
+}
@@ -0,0 +1,180 @@
+use std::fmt;
+
+use yaml_rust::{ScanError, Yaml, YamlLoader};
+
+/// When a negative test is expected to fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Parse,
+    Resolution,
+    Runtime,
+}
+
+/// Expected error of a test that must not complete normally
+#[derive(Debug, PartialEq, Eq)]
+pub struct Negative {
+    pub phase: Phase,
+    /// Name of the error constructor, `SyntaxError`, `TypeError`...
+    pub error_type: String,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Flags {
+    pub only_strict: bool,
+    pub no_strict: bool,
+    pub module: bool,
+    /// Run the source as is, without the harness
+    pub raw: bool,
+    pub is_async: bool,
+}
+
+/// The YAML frontmatter of a test, between `/*---` and `---*/`
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Metadata {
+    pub description: String,
+    /// Harness files to run before the test, on top of `assert.js` and `sta.js`
+    pub includes: Vec<String>,
+    pub flags: Flags,
+    pub negative: Option<Negative>,
+    pub features: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum MetadataError {
+    Missing,
+    Yaml(ScanError),
+    Invalid(&'static str),
+}
+
+impl fmt::Display for MetadataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetadataError::Missing => write!(f, "no frontmatter"),
+            MetadataError::Yaml(err) => write!(f, "invalid frontmatter: {}", err),
+            MetadataError::Invalid(field) => write!(f, "invalid frontmatter field '{}'", field),
+        }
+    }
+}
+
+impl Metadata {
+    pub fn from_source(source: &str) -> Result<Metadata, MetadataError> {
+        let start = source.find("/*---").ok_or(MetadataError::Missing)? + "/*---".len();
+        let end = source[start..]
+            .find("---*/")
+            .ok_or(MetadataError::Missing)?
+            + start;
+
+        let documents =
+            YamlLoader::load_from_str(&source[start..end]).map_err(MetadataError::Yaml)?;
+        let yaml = match documents.into_iter().next() {
+            Some(yaml) => yaml,
+            None => return Ok(Metadata::default()),
+        };
+
+        let mut flags = Flags::default();
+        for flag in string_list(&yaml["flags"], "flags")? {
+            match flag.as_str() {
+                "onlyStrict" => flags.only_strict = true,
+                "noStrict" => flags.no_strict = true,
+                "module" => flags.module = true,
+                "raw" => flags.raw = true,
+                "async" => flags.is_async = true,
+                // `generated`, `CanBlockIsFalse`... don't change how the test runs
+                _ => {}
+            }
+        }
+
+        let negative = match &yaml["negative"] {
+            Yaml::BadValue => None,
+            negative => {
+                let phase = match negative["phase"].as_str() {
+                    Some("parse") | Some("early") => Phase::Parse,
+                    Some("resolution") => Phase::Resolution,
+                    Some("runtime") => Phase::Runtime,
+                    _ => return Err(MetadataError::Invalid("negative.phase")),
+                };
+                let error_type = negative["type"]
+                    .as_str()
+                    .ok_or(MetadataError::Invalid("negative.type"))?;
+                Some(Negative {
+                    phase,
+                    error_type: error_type.into(),
+                })
+            }
+        };
+
+        Ok(Metadata {
+            description: yaml["description"]
+                .as_str()
+                .unwrap_or_default()
+                .trim()
+                .into(),
+            includes: string_list(&yaml["includes"], "includes")?,
+            flags,
+            negative,
+            features: string_list(&yaml["features"], "features")?,
+        })
+    }
+}
+
+/// `[a, b]` or a block list, missing fields are empty
+fn string_list(yaml: &Yaml, field: &'static str) -> Result<Vec<String>, MetadataError> {
+    match yaml {
+        Yaml::BadValue => Ok(Vec::new()),
+        Yaml::Array(items) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .map(String::from)
+                    .ok_or(MetadataError::Invalid(field))
+            })
+            .collect(),
+        _ => Err(MetadataError::Invalid(field)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_frontmatter() {
+        let source = r#"
+// Copyright header
+/*---
+esid: sec-let
+description: >
+  let declarations can't be
+  redeclared
+includes: [compareArray.js, propertyHelper.js]
+flags: [onlyStrict, generated]
+negative:
+  phase: parse
+  type: SyntaxError
+features:
+  - let
+---*/
+let a; let a;
+"#;
+        let metadata = Metadata::from_source(source).unwrap();
+        assert_eq!(metadata.description, "let declarations can't be redeclared");
+        assert_eq!(metadata.includes, ["compareArray.js", "propertyHelper.js"]);
+        assert!(metadata.flags.only_strict && !metadata.flags.raw);
+        assert_eq!(
+            metadata.negative,
+            Some(Negative {
+                phase: Phase::Parse,
+                error_type: "SyntaxError".into()
+            })
+        );
+        assert_eq!(metadata.features, ["let"]);
+    }
+
+    #[test]
+    fn missing_frontmatter() {
+        assert!(matches!(
+            Metadata::from_source("1 + 1;"),
+            Err(MetadataError::Missing)
+        ));
+    }
+}
@@ -0,0 +1,376 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    io::{self, Read, Write},
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    process::{self, Command, Stdio},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use js::{
+    ast::Completion,
+    jsrt::JsValue,
+    parser::Parser,
+    vm::{Compiler, Context, Interpreter, Runtime},
+};
+
+use crate::metadata::{Metadata, Negative, Phase};
+
+/// Native stack of the thread running a test, deep recursion stops at `MAX_CALL_DEPTH` first
+const TEST_STACK_SIZE: usize = 256 * 1024 * 1024;
+
+/// First argument of the runner when it is started again to run a single script, see
+/// `Runner::execute`
+pub const CHILD_ARG: &str = "--execute-script";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Fail(String),
+    Skip(&'static str),
+}
+
+/// How a script ended when it didn't complete normally
+#[derive(Debug, PartialEq, Eq)]
+enum Abrupt {
+    /// Parse or early error
+    Parse(String),
+    /// Uncaught exception, with the name of its constructor
+    Throw { name: String, message: String },
+    /// The interpreter itself panicked
+    Crash(String),
+}
+
+/// Run the tests of a test262 checkout, the harness files are loaded once
+pub struct Runner {
+    harness_dir: PathBuf,
+    harness: HashMap<String, String>,
+    timeout: Duration,
+    /// The runner itself, which run each script in a child process
+    program: PathBuf,
+}
+
+impl Runner {
+    pub fn new(test262_dir: &Path, timeout: Duration) -> io::Result<Runner> {
+        Ok(Runner {
+            harness_dir: test262_dir.join("harness"),
+            harness: HashMap::new(),
+            timeout,
+            program: env::current_exe()?,
+        })
+    }
+
+    fn harness_file(&mut self, name: &str) -> io::Result<&str> {
+        if !self.harness.contains_key(name) {
+            let source = fs::read_to_string(self.harness_dir.join(name))?;
+            self.harness.insert(name.into(), source);
+        }
+        Ok(&self.harness[name])
+    }
+
+    pub fn run(&mut self, path: &Path) -> Outcome {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(err) => return Outcome::Fail(format!("cannot read the test: {}", err)),
+        };
+        let metadata = match Metadata::from_source(&source) {
+            Ok(metadata) => metadata,
+            Err(err) => return Outcome::Fail(err.to_string()),
+        };
+
+        if metadata.flags.module {
+            return Outcome::Skip("module");
+        }
+        if metadata.flags.is_async {
+            return Outcome::Skip("async");
+        }
+
+        // Raw tests run as they are, the others in the modes their flags allow
+        let flags = &metadata.flags;
+        let modes: &[bool] = if flags.only_strict {
+            &[true]
+        } else if flags.no_strict || flags.raw {
+            &[false]
+        } else {
+            &[false, true]
+        };
+
+        for &strict in modes {
+            let script = match self.script(&source, &metadata, strict) {
+                Ok(script) => script,
+                Err(outcome) => return outcome,
+            };
+            let outcome = match self.execute(&script) {
+                Some(result) => check(result, metadata.negative.as_ref()),
+                None => Outcome::Fail(format!("timed out after {:?}", self.timeout)),
+            };
+            match outcome {
+                Outcome::Fail(reason) if strict && modes.len() > 1 => {
+                    return Outcome::Fail(format!("in strict mode: {}", reason))
+                }
+                Outcome::Pass => {}
+                outcome => return outcome,
+            }
+        }
+        Outcome::Pass
+    }
+
+    /// The test with the harness and its includes in front, as the same script
+    fn script(
+        &mut self,
+        source: &str,
+        metadata: &Metadata,
+        strict: bool,
+    ) -> Result<String, Outcome> {
+        let mut script = String::new();
+        if strict {
+            script.push_str("\"use strict\";\n");
+        }
+        if !metadata.flags.raw {
+            let includes = ["assert.js", "sta.js"]
+                .iter()
+                .copied()
+                .chain(metadata.includes.iter().map(String::as_str));
+            for include in includes {
+                match self.harness_file(include) {
+                    Ok(harness) => script.push_str(harness),
+                    Err(err) => {
+                        return Err(Outcome::Fail(format!("cannot read {}: {}", include, err)))
+                    }
+                }
+                script.push('\n');
+            }
+        }
+        script.push_str(source);
+        Ok(script)
+    }
+
+    /// Run `script` in a child process, `None` if it didn't finish in time.
+    ///
+    /// The interpreter can't be interrupted, a script that loops forever is stopped by killing
+    /// its process. A native stack overflow only kills the child too.
+    fn execute(&self, script: &str) -> Option<Result<(), Abrupt>> {
+        let mut child = match Command::new(&self.program)
+            .arg(CHILD_ARG)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(err) => return Some(Err(Abrupt::Crash(format!("cannot start: {}", err)))),
+        };
+
+        // Writing the script could block on a full pipe, read the output on another thread
+        let mut stdout = child.stdout.take().unwrap();
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let mut output = String::new();
+            let _ = stdout.read_to_string(&mut output);
+            let _ = sender.send(output);
+        });
+        if let Some(mut stdin) = child.stdin.take() {
+            // A child that died early is reported from its exit status
+            let _ = stdin.write_all(script.as_bytes());
+        }
+
+        let output = match receiver.recv_timeout(self.timeout) {
+            Ok(output) => output,
+            Err(_) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        };
+        let status = match child.wait() {
+            Ok(status) => status,
+            Err(err) => return Some(Err(Abrupt::Crash(err.to_string()))),
+        };
+
+        Some(match decode(&output) {
+            Some(result) => result,
+            None => Err(Abrupt::Crash(format!(
+                "test process exited with {}",
+                status
+            ))),
+        })
+    }
+}
+
+/// Entry point of the child process: run the script read from stdin and print how it ended
+pub fn run_child() -> ! {
+    // Crashes are reported as failures, the default hook would print every one of them
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut script = String::new();
+    if let Err(err) = io::stdin().read_to_string(&mut script) {
+        eprintln!("error: cannot read the script: {}", err);
+        process::exit(1);
+    }
+
+    let result = thread::Builder::new()
+        .stack_size(TEST_STACK_SIZE)
+        .spawn(move || {
+            panic::catch_unwind(AssertUnwindSafe(|| execute(&script))).unwrap_or_else(|payload| {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_default();
+                Err(Abrupt::Crash(message))
+            })
+        })
+        .expect("failed to spawn a test thread")
+        .join()
+        .unwrap_or_else(|_| Err(Abrupt::Crash(String::new())));
+
+    print!("{}", encode(&result));
+    let _ = io::stdout().flush();
+    process::exit(0);
+}
+
+/// How the child reports a result: the kind on the first line, then its fields one per
+/// line, the message last as it can span several
+fn encode(result: &Result<(), Abrupt>) -> String {
+    match result {
+        Ok(()) => "ok\n".to_string(),
+        Err(Abrupt::Parse(message)) => format!("parse\n{}", message),
+        Err(Abrupt::Throw { name, message }) => format!("throw\n{}\n{}", name, message),
+        Err(Abrupt::Crash(message)) => format!("crash\n{}", message),
+    }
+}
+
+fn decode(output: &str) -> Option<Result<(), Abrupt>> {
+    let (kind, rest) = output.split_once('\n')?;
+    Some(match kind {
+        "ok" => Ok(()),
+        "parse" => Err(Abrupt::Parse(rest.into())),
+        "throw" => {
+            let (name, message) = rest.split_once('\n')?;
+            Err(Abrupt::Throw {
+                name: name.into(),
+                message: message.into(),
+            })
+        }
+        "crash" => Err(Abrupt::Crash(rest.into())),
+        _ => return None,
+    })
+}
+
+fn execute(script: &str) -> Result<(), Abrupt> {
+    let program = Parser::new(script)
+        .parse_program()
+        .map_err(|err| Abrupt::Parse(err.to_string()))?;
+    let code = Compiler::compile_program(&program).map_err(|err| Abrupt::Parse(err.to_string()))?;
+
+    let mut context = Context::new(Runtime::new());
+    match Interpreter::run(&mut context, &code) {
+        Ok(_) => Ok(()),
+        Err(Completion::Throw(error)) => {
+            let name = match &error {
+                JsValue::Object(obj) => obj.get_data_property("name").to_string(),
+                _ => String::new(),
+            };
+            Err(Abrupt::Throw {
+                name,
+                message: error.to_string(),
+            })
+        }
+        Err(completion) => Err(Abrupt::Crash(format!("{:?} at the top level", completion))),
+    }
+}
+
+/// Compare how the test ended with what its metadata expects
+fn check(result: Result<(), Abrupt>, negative: Option<&Negative>) -> Outcome {
+    match (result, negative) {
+        (Ok(()), None) => Outcome::Pass,
+        (Ok(()), Some(negative)) => Outcome::Fail(format!(
+            "expected a {} at the {:?} phase",
+            negative.error_type, negative.phase
+        )),
+        (Err(Abrupt::Crash(message)), _) => Outcome::Fail(format!("crashed: {}", message)),
+        // Parse errors don't say which error they are, they are all `SyntaxError`s
+        (Err(Abrupt::Parse(_)), Some(negative))
+            if negative.phase == Phase::Parse && negative.error_type == "SyntaxError" =>
+        {
+            Outcome::Pass
+        }
+        (Err(Abrupt::Throw { name, .. }), Some(negative))
+            if negative.phase != Phase::Parse && name == negative.error_type =>
+        {
+            Outcome::Pass
+        }
+        (Err(Abrupt::Parse(message)), _) => Outcome::Fail(format!("SyntaxError: {}", message)),
+        (Err(Abrupt::Throw { message, .. }), _) => Outcome::Fail(format!("Uncaught {}", message)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::Flags;
+
+    fn negative(phase: Phase, error_type: &str) -> Negative {
+        Negative {
+            phase,
+            error_type: error_type.into(),
+        }
+    }
+
+    #[test]
+    fn negative_tests() {
+        let parse = negative(Phase::Parse, "SyntaxError");
+        let runtime = negative(Phase::Runtime, "TypeError");
+
+        assert_eq!(check(execute("let a = ;"), Some(&parse)), Outcome::Pass);
+        assert_eq!(check(execute("break;"), Some(&parse)), Outcome::Pass);
+        assert_eq!(check(execute("null();"), Some(&runtime)), Outcome::Pass);
+        assert!(matches!(
+            check(execute("1;"), Some(&runtime)),
+            Outcome::Fail(_)
+        ));
+        assert!(matches!(
+            check(execute("throw RangeError();"), Some(&runtime)),
+            Outcome::Fail(_)
+        ));
+        assert!(matches!(
+            check(execute("null();"), None),
+            Outcome::Fail(message) if message.starts_with("Uncaught TypeError")
+        ));
+    }
+
+    #[test]
+    fn child_results_round_trip() {
+        let results = [
+            execute("1;"),
+            execute("let a = ;"),
+            execute("throw TypeError('two\\nlines');"),
+            Err(Abrupt::Crash("stack overflow".into())),
+        ];
+        for result in results {
+            assert_eq!(decode(&encode(&result)), Some(result));
+        }
+
+        // A child killed by a signal print nothing, or only part of a result
+        assert_eq!(decode(""), None);
+        assert_eq!(decode("throw\nTypeError"), None);
+    }
+
+    #[test]
+    fn strict_mode_directive() {
+        let mut runner = Runner::new(Path::new("test262"), Duration::from_secs(1)).unwrap();
+        let metadata = Metadata {
+            flags: Flags {
+                raw: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let script = runner.script("1;", &metadata, true).unwrap();
+        assert_eq!(script, "\"use strict\";\n1;");
+        assert_eq!(runner.script("1;", &metadata, false).unwrap(), "1;");
+    }
+}
@@ -98,5 +98,9 @@ impl BitIndexing for BigUInt {
         } else {
             unreachable!();
         }
+
+        if value == 0 {
+            self.adjust_bit_count();
+        }
     }
 }
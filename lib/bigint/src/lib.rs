@@ -3,7 +3,9 @@ use std::mem::size_of;
 
 pub mod indexing;
 mod operators;
+mod signed;
 use indexing::*;
+pub use signed::BigInt;
 
 #[derive(Default, Clone)]
 pub struct BigUInt {
@@ -23,7 +25,7 @@ impl BigUInt {
     }
 
     pub fn from_le_bytes(bytes: &[u8]) -> Self {
-        let mut chunks: Vec<u32> = Vec::with_capacity((bytes.len() - 1) / Self::CHUNK_SIZE + 1);
+        let mut chunks: Vec<u32> = Vec::with_capacity(bytes.len() / Self::CHUNK_SIZE + 1);
 
         let mut bit_count = 0usize;
         let mut acc = 0u32;
@@ -46,14 +48,49 @@ impl BigUInt {
 
         // println!("bit_count={}, chunks={:?}", bit_count, chunks);
 
-        Self {
+        let mut out = Self {
             bit_count,
             chunks
+        };
+        out.adjust_bit_count();
+        out
+    }
+
+    /// Little endian chunks, trailing zero chunks are dropped
+    pub fn from_chunks(chunks: Vec<u32>) -> Self {
+        let mut out = Self {
+            bit_count: 0,
+            chunks,
+        };
+        out.adjust_bit_count();
+        out
+    }
+
+    /// The integral part of `value`, `None` for NaN and infinities
+    pub fn from_f64(value: f64) -> Option<Self> {
+        if !value.is_finite() {
+            return None;
+        }
+
+        let value = value.abs().trunc();
+        if value < 1.0 {
+            return Some(Self::new());
         }
+
+        // value = mantissa * 2^exponent, with the implicit leading bit added back
+        let bits = value.to_bits();
+        let exponent = ((bits >> 52) & 0x7ff) as i64 - 1075;
+        let mantissa = (bits & ((1 << 52) - 1)) | (1 << 52);
+        let out = Self::from(mantissa);
+        Some(if exponent >= 0 {
+            out << exponent as usize
+        } else {
+            out >> (-exponent) as usize
+        })
     }
 
     pub fn from_str_radix(str: &str, radix: u32) -> Option<Self> {
-        if !(2..36).contains(&radix) {
+        if !(2..=36).contains(&radix) {
             return None;
         }
 
@@ -81,6 +118,26 @@ impl BigUInt {
         out
     }
 
+    /// The nearest f64, ties to even like `as` casts do
+    pub fn to_f64(&self) -> f64 {
+        if self.bit_count <= 64 {
+            return self.to_u64_clamp() as f64;
+        }
+
+        // Keep 64 bits and fold the rest into the lowest one, which is enough to round right
+        let shift = self.bit_count - 64;
+        let mut top = (self.clone() >> shift).to_u64_clamp();
+        if (0..shift).any(|index| self.bit_at(index) > 0) {
+            top |= 1;
+        }
+
+        if shift > 1024 {
+            f64::INFINITY
+        } else {
+            top as f64 * 2f64.powi(shift as i32)
+        }
+    }
+
     pub fn is_zero(&self) -> bool {
         self.chunks.is_empty()
     }
@@ -100,9 +157,11 @@ impl BigUInt {
         BitIter::new(self)
     }
 
+    /// Grow the chunks to hold `bit_count` bits, the value is not changed
     pub fn pad_to(&mut self, bit_count: usize) {
-        if bit_count >= self.bit_count {
-            self.set_bit(bit_count - 1, 0);
+        let len = bit_count.div_ceil(Self::CHUNK_BIT_SIZE);
+        if len > self.chunks.len() {
+            self.chunks.resize(len, 0);
         }
     }
 
@@ -111,45 +170,111 @@ impl BigUInt {
         self.chunks.clear();
     }
 
+    /// Drop the zero chunks at the top, then count the bits again
     fn adjust_bit_count(&mut self) {
-        for (idx, chunk) in self.chunks.iter().rev().enumerate() {
-            if *chunk > 0 {
-                self.bit_count =
-                    (self.chunks.len() - idx - 1) * Self::CHUNK_BIT_SIZE +
-                    (Self::CHUNK_BIT_SIZE - chunk.leading_zeros() as usize);
-                break;
+        while self.chunks.last() == Some(&0) {
+            self.chunks.pop();
+        }
+
+        self.bit_count = match self.chunks.last() {
+            Some(chunk) => {
+                self.chunks.len() * Self::CHUNK_BIT_SIZE - chunk.leading_zeros() as usize
+            }
+            None => 0,
+        };
+    }
+
+    /// `self ^ exponent`, by squaring
+    pub fn pow(&self, exponent: u32) -> Self {
+        let mut out = Self::from(1);
+        let mut base = self.clone();
+        let mut exponent = exponent;
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                out *= &base;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.clone() * &base;
             }
         }
+
+        out
     }
 
-    #[allow(clippy::inherent_to_string)]
-    pub fn to_string(&self) -> String {
-        let mut out = String::with_capacity(self.bit_count() / 3);
-        let mut divided = self.clone();
-        let mut quotient = BigUInt::new();
+    /// Quotient and remainder of the division by a single chunk
+    pub fn div_rem_u32(&self, divisor: u32) -> (Self, u32) {
+        assert!(divisor != 0, "attempt to divide by zero");
 
-        while !divided.is_zero() {
-            quotient.clear();
-            let mut remainder = 0u32;
-            let len = divided.bit_count();
-
-            // MSB -> LSB
-            for (idx, bit) in divided.bit_iter().rev().enumerate() {
-                remainder = remainder * 2 + (bit as u32);
-
-                if remainder >= 10 {
-                    quotient.set_bit(len - idx - 1, 1);
-                    remainder -= 10;
-                }
+        let divisor = divisor as u64;
+        let mut quotient = vec![0u32; self.chunks.len()];
+        let mut remainder = 0u64;
+
+        // MSB -> LSB
+        for (idx, chunk) in self.chunks.iter().enumerate().rev() {
+            let current = (remainder << Self::CHUNK_BIT_SIZE) | *chunk as u64;
+            quotient[idx] = (current / divisor) as u32;
+            remainder = current % divisor;
+        }
+
+        (Self::from_chunks(quotient), remainder as u32)
+    }
+
+    /// Quotient and remainder, the quotient is rounded toward zero
+    pub fn div_rem(&self, divisor: &BigUInt) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "attempt to divide by zero");
+
+        if divisor.chunk_len() == 1 {
+            let (quotient, remainder) = self.div_rem_u32(divisor.chunks[0]);
+            return (quotient, Self::from(remainder as u64));
+        }
+
+        if self < divisor {
+            return (Self::new(), self.clone());
+        }
+
+        // Shift and subtract, one bit of the quotient at a time
+        let mut quotient = Self::new();
+        let mut remainder = Self::new();
+        for index in (0..self.bit_count).rev() {
+            remainder <<= 1;
+            if self.bit_at(index) > 0 {
+                remainder.set_bit(0, 1);
             }
 
-            // println!("d={}\nq={}\nr={}", divided.to_bit_string(), quotient.to_bit_string(), remainder);
+            if &remainder >= divisor {
+                remainder -= divisor;
+                quotient.set_bit(index, 1);
+            }
+        }
+
+        (quotient, remainder)
+    }
+
+    /// Digits in `radix`, lowercase letters are used past 9
+    pub fn to_string_radix(&self, radix: u32) -> String {
+        assert!((2..=36).contains(&radix), "radix must be between 2 and 36");
+
+        if self.is_zero() {
+            return "0".into();
+        }
 
-            out.push(char::from_digit(remainder %  10, 10).unwrap());
-            std::mem::swap(&mut divided, &mut quotient);
+        let mut out = Vec::with_capacity(self.bit_count());
+        let mut divided = self.clone();
+
+        while !divided.is_zero() {
+            let (quotient, remainder) = divided.div_rem_u32(radix);
+            out.push(char::from_digit(remainder, radix).unwrap());
+            divided = quotient;
         }
 
-        out.chars().rev().collect()
+        out.iter().rev().collect()
+    }
+
+    #[allow(clippy::inherent_to_string)]
+    pub fn to_string(&self) -> String {
+        self.to_string_radix(10)
     }
 
     pub fn to_bit_string(&self) -> String {
@@ -1,4 +1,5 @@
 use core::ops;
+use std::cmp::Ordering;
 use super::*;
 
 macro_rules! impl_ops {
@@ -34,6 +35,7 @@ impl ops::BitAnd<&BigUInt> for BigUInt {
             *chunk &= rhs.chunk_at(idx);
         }
 
+        out.adjust_bit_count();
         out
     }
 }
@@ -43,6 +45,8 @@ impl ops::BitAndAssign<&BigUInt> for BigUInt {
         for (idx, chunk) in self.chunks.iter_mut().enumerate() {
             *chunk &= rhs.chunk_at(idx);
         }
+
+        self.adjust_bit_count();
     }
 }
 
@@ -74,6 +78,8 @@ impl ops::BitOrAssign<&BigUInt> for BigUInt {
         for (idx, chunk) in self.chunks.iter_mut().enumerate() {
             *chunk |= rhs.chunk_at(idx);
         }
+
+        self.adjust_bit_count();
     }
 }
 
@@ -95,6 +101,7 @@ impl ops::BitXor<&BigUInt> for BigUInt {
             *chunk ^= rhs.chunk_at(idx);
         }
 
+        out.adjust_bit_count();
         out
     }
 }
@@ -105,6 +112,8 @@ impl ops::BitXorAssign<&BigUInt> for BigUInt {
         for (idx, chunk) in self.chunks.iter_mut().enumerate() {
             *chunk ^= rhs.chunk_at(idx);
         }
+
+        self.adjust_bit_count();
     }
 }
 
@@ -121,6 +130,7 @@ impl ops::Not for BigUInt {
             *chunk = !(*chunk);
         }
 
+        out.adjust_bit_count();
         out
     }
 }
@@ -137,23 +147,29 @@ impl ops::Shl<usize> for BigUInt {
 
 impl ops::ShlAssign<usize> for BigUInt {
     fn shl_assign(&mut self, amount: usize) {
-        self.pad_to(self.bit_count() + amount);
+        if self.is_zero() {
+            return;
+        }
 
         let shift_chunk = amount / Self::CHUNK_BIT_SIZE;
         let shift_amount = amount % Self::CHUNK_BIT_SIZE;
 
-        self.chunks.extend(iter::repeat(0).take(shift_chunk));
+        if shift_amount > 0 {
+            let mut remainder = 0;
+            for chunk in self.chunks.iter_mut() {
+                let tmp = *chunk >> (Self::CHUNK_BIT_SIZE - shift_amount);
+                *chunk <<= shift_amount;
+                *chunk |= remainder;
+                remainder = tmp;
+            }
 
-        let mut remainder = 0;
-        for chunk in self.chunks.iter_mut() {
-            let tmp = *chunk >> (Self::CHUNK_BIT_SIZE - shift_amount);
-            *chunk <<= shift_amount;
-            *chunk |= remainder;
-            remainder = tmp;
+            if remainder > 0 {
+                self.chunks.push(remainder);
+            }
         }
 
-        self.chunks.rotate_left(shift_chunk);
-        self.bit_count += amount;
+        self.chunks.splice(0..0, iter::repeat_n(0, shift_chunk));
+        self.adjust_bit_count();
     }
 }
 
@@ -177,18 +193,19 @@ impl ops::ShrAssign<usize> for BigUInt {
         let shift_chunk = amount / Self::CHUNK_BIT_SIZE;
         let shift_amount = amount % Self::CHUNK_BIT_SIZE;
 
-        self.chunks.rotate_right(shift_chunk);
-        self.chunks.truncate(self.chunks.len() - shift_chunk);
+        self.chunks.drain(..shift_chunk);
 
-        let mut remainder = 0u32;
-        for chunk in self.chunks.iter_mut().rev() {
-            let tmp = *chunk << (Self::CHUNK_BIT_SIZE - shift_amount);
-            *chunk >>= shift_amount;
-            *chunk |= remainder;
-            remainder = tmp;
+        if shift_amount > 0 {
+            let mut remainder = 0u32;
+            for chunk in self.chunks.iter_mut().rev() {
+                let tmp = *chunk << (Self::CHUNK_BIT_SIZE - shift_amount);
+                *chunk >>= shift_amount;
+                *chunk |= remainder;
+                remainder = tmp;
+            }
         }
 
-        self.bit_count -= amount;
+        self.adjust_bit_count();
     }
 }
 
@@ -294,7 +311,7 @@ impl ops::MulAssign<&BigUInt> for BigUInt {
                 current.chunks.push(remainder);
             }
 
-            current <<= idx;
+            current <<= idx * Self::CHUNK_BIT_SIZE;
             *self += &current;
             current.clear();
         }
@@ -334,3 +351,82 @@ impl ops::MulAssign<u32> for BigUInt {
         self.adjust_bit_count();
     }
 }
+
+impl ops::Sub<&BigUInt> for BigUInt {
+    type Output = BigUInt;
+
+    fn sub(self, rhs: &BigUInt) -> Self::Output {
+        let mut out = self;
+        ops::SubAssign::sub_assign(&mut out, rhs);
+        out
+    }
+}
+
+impl ops::SubAssign<&BigUInt> for BigUInt {
+    /// Panics when `rhs` is bigger, the result can't be negative
+    fn sub_assign(&mut self, rhs: &BigUInt) {
+        assert!(&*self >= rhs, "attempt to subtract with overflow");
+
+        let mut borrow = 0i64;
+        for (idx, chunk) in self.chunks.iter_mut().enumerate() {
+            let mut diff = (*chunk as i64) - (rhs.chunk_at(idx) as i64) - borrow;
+            borrow = 0;
+            if diff < 0 {
+                diff += 1 << Self::CHUNK_BIT_SIZE;
+                borrow = 1;
+            }
+            *chunk = diff as u32;
+        }
+
+        self.adjust_bit_count();
+    }
+}
+
+impl_ops!(Sub, sub, BigUInt);
+impl_ops!(assign, SubAssign, sub_assign, BigUInt);
+
+impl ops::Div<&BigUInt> for BigUInt {
+    type Output = BigUInt;
+
+    fn div(self, rhs: &BigUInt) -> Self::Output {
+        self.div_rem(rhs).0
+    }
+}
+
+impl ops::Rem<&BigUInt> for BigUInt {
+    type Output = BigUInt;
+
+    fn rem(self, rhs: &BigUInt) -> Self::Output {
+        self.div_rem(rhs).1
+    }
+}
+
+impl_ops!(Div, div, BigUInt);
+impl_ops!(Rem, rem, BigUInt);
+
+impl PartialEq for BigUInt {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for BigUInt {}
+
+impl PartialOrd for BigUInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // MSB -> LSB, once the lengths are equal
+        self.bit_count().cmp(&other.bit_count()).then_with(|| {
+            (0..self.chunk_len())
+                .rev()
+                .map(|idx| self.chunk_at(idx).cmp(&other.chunk_at(idx)))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        })
+    }
+}
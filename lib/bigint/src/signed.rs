@@ -0,0 +1,304 @@
+use core::ops;
+use std::cmp::Ordering;
+use super::*;
+
+/// A signed integer, stored as a sign and a magnitude
+///
+/// Zero is never negative, so equal values always have the same representation
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: BigUInt,
+}
+
+impl BigInt {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_magnitude(negative: bool, magnitude: BigUInt) -> Self {
+        Self {
+            negative: negative && !magnitude.is_zero(),
+            magnitude,
+        }
+    }
+
+    /// The integral part of `value`, `None` for NaN and infinities
+    pub fn from_f64(value: f64) -> Option<Self> {
+        BigUInt::from_f64(value).map(|magnitude| Self::from_magnitude(value < 0.0, magnitude))
+    }
+
+    /// Digits with an optional leading `-`
+    pub fn from_str_radix(str: &str, radix: u32) -> Option<Self> {
+        let (negative, digits) = match str.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, str),
+        };
+
+        if digits.is_empty() {
+            return None;
+        }
+
+        BigUInt::from_str_radix(digits, radix).map(|magnitude| Self::from_magnitude(negative, magnitude))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn magnitude(&self) -> &BigUInt {
+        &self.magnitude
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let value = self.magnitude.to_f64();
+        if self.negative { -value } else { value }
+    }
+
+    pub fn to_string_radix(&self, radix: u32) -> String {
+        let digits = self.magnitude.to_string_radix(radix);
+        if self.negative {
+            format!("-{}", digits)
+        } else {
+            digits
+        }
+    }
+
+    /// `self ^ exponent`
+    pub fn pow(&self, exponent: u32) -> Self {
+        Self::from_magnitude(self.negative && exponent % 2 == 1, self.magnitude.pow(exponent))
+    }
+
+    /// Quotient rounded toward zero and a remainder with the sign of `self`, `None` when
+    /// dividing by zero
+    pub fn div_rem(&self, divisor: &BigInt) -> Option<(Self, Self)> {
+        if divisor.is_zero() {
+            return None;
+        }
+
+        let (quotient, remainder) = self.magnitude.div_rem(&divisor.magnitude);
+        Some((
+            Self::from_magnitude(self.negative != divisor.negative, quotient),
+            Self::from_magnitude(self.negative, remainder),
+        ))
+    }
+
+    /// `self mod 2^bits`, always positive
+    pub fn as_uint_n(&self, bits: usize) -> Self {
+        if !self.negative && self.magnitude.bit_count() <= bits {
+            return self.clone();
+        }
+
+        let len = bits.div_ceil(BigUInt::CHUNK_BIT_SIZE);
+        let mut chunks = self.to_twos_complement(len);
+        chunks.truncate(len);
+
+        let extra_bits = len * BigUInt::CHUNK_BIT_SIZE - bits;
+        if let Some(last) = chunks.last_mut() {
+            *last &= u32::MAX >> extra_bits;
+        }
+
+        Self::from_magnitude(false, BigUInt::from_chunks(chunks))
+    }
+
+    /// `self mod 2^bits`, read as a `bits` wide two's complement number
+    pub fn as_int_n(&self, bits: usize) -> Self {
+        if bits == 0 {
+            return Self::new();
+        }
+
+        if self.magnitude.bit_count() < bits {
+            return self.clone();
+        }
+
+        let out = self.as_uint_n(bits);
+        if out.magnitude.bit_at(bits - 1) > 0 {
+            let mut modulo = BigUInt::new();
+            modulo.set_bit(bits, 1);
+            Self::from_magnitude(true, modulo - out.magnitude)
+        } else {
+            out
+        }
+    }
+
+    /// The `len` lowest chunks of the infinite two's complement representation
+    fn to_twos_complement(&self, len: usize) -> Vec<u32> {
+        let mut chunks: Vec<u32> = (0..len).map(|idx| self.magnitude.chunk_at(idx)).collect();
+
+        if self.negative {
+            // -x == !x + 1
+            let mut carry = 1u64;
+            for chunk in chunks.iter_mut() {
+                let sum = (!*chunk) as u64 + carry;
+                *chunk = sum as u32;
+                carry = sum >> BigUInt::CHUNK_BIT_SIZE;
+            }
+        }
+
+        chunks
+    }
+
+    /// Reverse of `to_twos_complement`, the top bit of the last chunk is the sign
+    fn from_twos_complement(chunks: Vec<u32>) -> Self {
+        let negative = chunks.last().is_some_and(|chunk| chunk >> (BigUInt::CHUNK_BIT_SIZE - 1) == 1);
+
+        if !negative {
+            return Self::from_magnitude(false, BigUInt::from_chunks(chunks));
+        }
+
+        let mut chunks = chunks;
+        let mut carry = 1u64;
+        for chunk in chunks.iter_mut() {
+            let sum = (!*chunk) as u64 + carry;
+            *chunk = sum as u32;
+            carry = sum >> BigUInt::CHUNK_BIT_SIZE;
+        }
+
+        Self::from_magnitude(true, BigUInt::from_chunks(chunks))
+    }
+
+    /// Bitwise operations work on the two's complement, one more chunk leaves room for the sign
+    fn bitwise(&self, rhs: &BigInt, f: impl Fn(u32, u32) -> u32) -> Self {
+        let len = cmp::max(self.magnitude.chunk_len(), rhs.magnitude.chunk_len()) + 1;
+        let lhs = self.to_twos_complement(len);
+        let rhs = rhs.to_twos_complement(len);
+
+        Self::from_twos_complement(lhs.iter().zip(rhs.iter()).map(|(a, b)| f(*a, *b)).collect())
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        Self::from_magnitude(value < 0, BigUInt::from(value.unsigned_abs()))
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+impl ops::Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> Self::Output {
+        BigInt::from_magnitude(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl ops::Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, rhs: &BigInt) -> Self::Output {
+        if self.negative == rhs.negative {
+            return BigInt::from_magnitude(self.negative, self.magnitude.clone() + &rhs.magnitude);
+        }
+
+        // The sign of the bigger magnitude wins
+        if self.magnitude >= rhs.magnitude {
+            BigInt::from_magnitude(self.negative, self.magnitude.clone() - &rhs.magnitude)
+        } else {
+            BigInt::from_magnitude(rhs.negative, rhs.magnitude.clone() - &self.magnitude)
+        }
+    }
+}
+
+impl ops::Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, rhs: &BigInt) -> Self::Output {
+        self + &-rhs
+    }
+}
+
+impl ops::Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, rhs: &BigInt) -> Self::Output {
+        BigInt::from_magnitude(self.negative != rhs.negative, self.magnitude.clone() * &rhs.magnitude)
+    }
+}
+
+impl ops::BitAnd for &BigInt {
+    type Output = BigInt;
+
+    fn bitand(self, rhs: &BigInt) -> Self::Output {
+        self.bitwise(rhs, |a, b| a & b)
+    }
+}
+
+impl ops::BitOr for &BigInt {
+    type Output = BigInt;
+
+    fn bitor(self, rhs: &BigInt) -> Self::Output {
+        self.bitwise(rhs, |a, b| a | b)
+    }
+}
+
+impl ops::BitXor for &BigInt {
+    type Output = BigInt;
+
+    fn bitxor(self, rhs: &BigInt) -> Self::Output {
+        self.bitwise(rhs, |a, b| a ^ b)
+    }
+}
+
+impl ops::Not for &BigInt {
+    type Output = BigInt;
+
+    /// `!x == -x - 1`
+    fn not(self) -> Self::Output {
+        &-self - &BigInt::from(1)
+    }
+}
+
+impl ops::Shl<usize> for &BigInt {
+    type Output = BigInt;
+
+    fn shl(self, amount: usize) -> Self::Output {
+        BigInt::from_magnitude(self.negative, self.magnitude.clone() << amount)
+    }
+}
+
+impl ops::Shr<usize> for &BigInt {
+    type Output = BigInt;
+
+    /// Rounds toward negative infinity, like an arithmetic shift does
+    fn shr(self, amount: usize) -> Self::Output {
+        if !self.negative {
+            return BigInt::from_magnitude(false, self.magnitude.clone() >> amount);
+        }
+
+        // -x >> n == -(((x - 1) >> n) + 1)
+        let magnitude = ((self.magnitude.clone() - BigUInt::from(1)) >> amount) + 1;
+        BigInt::from_magnitude(true, magnitude)
+    }
+}
+
+impl fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BigInt({})", self.to_string_radix(10))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_string_radix(10))
+    }
+}
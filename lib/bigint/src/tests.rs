@@ -267,3 +267,136 @@ fn from_str_radix() {
         0o10736523112
     );
 }
+
+#[test]
+fn ops_sub() {
+    let a = BigUInt::from(123213213131231);
+    let b = BigUInt::from(56756776529);
+    assert_eq!((a - b).to_u64_clamp(), 123213213131231 - 56756776529);
+
+    let a = BigUInt::from(1 << 32);
+    let b = BigUInt::from(1);
+    assert_eq!((a - b).to_u64_clamp(), (1 << 32) - 1);
+
+    let mut n = BigUInt::from(999999);
+    n -= BigUInt::from(999999);
+    assert!(n.is_zero());
+}
+
+#[test]
+fn ops_mult() {
+    let a = BigUInt::from(u64::MAX);
+    let b = BigUInt::from(u64::MAX);
+    assert_eq!((a * b).to_string(), "340282366920938463426481119284349108225");
+
+    let a = BigUInt::from(987987987987);
+    let b = BigUInt::from(123123);
+    assert_eq!((a * b).to_u64_clamp(), 987987987987 * 123123);
+
+    assert_eq!(BigUInt::from(2).pow(100).to_string(), "1267650600228229401496703205376");
+    assert_eq!(BigUInt::from(7).pow(0).to_u64_clamp(), 1);
+}
+
+#[test]
+fn ops_shift_chunks() {
+    let n = BigUInt::from(0xdeadbeef) << 64;
+    assert_eq!(n.to_string_radix(16), "deadbeef0000000000000000");
+    assert_eq!((n >> 68).to_u64_clamp(), 0xdeadbee);
+
+    let n = BigUInt::from(1) << 32;
+    assert_eq!(n.to_u64_clamp(), 1 << 32);
+    assert_eq!(n.bit_count(), 33);
+}
+
+#[test]
+fn div_rem() {
+    let (q, r) = BigUInt::from(123213213131231).div_rem(&BigUInt::from(56756776529));
+    assert_eq!(q.to_u64_clamp(), 123213213131231 / 56756776529);
+    assert_eq!(r.to_u64_clamp(), 123213213131231 % 56756776529);
+
+    let (q, r) = BigUInt::from(100).div_rem_u32(7);
+    assert_eq!((q.to_u64_clamp(), r), (14, 2));
+
+    let n = BigUInt::from_str_radix("340282366920938463426481119284349108225", 10).unwrap();
+    let (q, r) = n.div_rem(&BigUInt::from(u64::MAX));
+    assert_eq!(q.to_u64_clamp(), u64::MAX);
+    assert!(r.is_zero());
+
+    let (q, r) = BigUInt::from(3).div_rem(&BigUInt::from(1 << 40));
+    assert!(q.is_zero());
+    assert_eq!(r.to_u64_clamp(), 3);
+}
+
+#[test]
+fn compare() {
+    assert!(BigUInt::from(1 << 40) > BigUInt::from(1 << 39));
+    assert!(BigUInt::from(5) < BigUInt::from(6));
+    assert_eq!(BigUInt::from(0), BigUInt::new());
+    assert_eq!(BigUInt::from(77) << 40, BigUInt::from(77 << 40));
+}
+
+#[test]
+fn to_string_radix() {
+    assert_eq!(BigUInt::new().to_string(), "0");
+    assert_eq!(BigUInt::from(255).to_string_radix(16), "ff");
+    assert_eq!(BigUInt::from(5).to_string_radix(2), "101");
+    assert_eq!(BigUInt::from_str_radix("zz", 36).unwrap().to_string_radix(36), "zz");
+}
+
+#[test]
+fn f64_conversion() {
+    assert_eq!(BigUInt::from_f64(12345.9).unwrap().to_u64_clamp(), 12345);
+    assert_eq!(BigUInt::from_f64(2f64.powi(80)).unwrap().to_string(), "1208925819614629174706176");
+    assert!(BigUInt::from_f64(f64::NAN).is_none());
+
+    assert_eq!(BigUInt::from(u64::MAX).to_f64(), u64::MAX as f64);
+    assert_eq!((BigUInt::from(1) << 80).to_f64(), 2f64.powi(80));
+    // 2^64 + 2^11 + 1 is past the half way point, it rounds up
+    let n = (BigUInt::from(1) << 64) + BigUInt::from(2049);
+    assert_eq!(n.to_f64(), 2f64.powi(64) + 4096.0);
+    assert_eq!((BigUInt::from(1) << 1100).to_f64(), f64::INFINITY);
+}
+
+#[test]
+fn signed_arithmetic() {
+    let a = BigInt::from(-15);
+    let b = BigInt::from(4);
+    assert_eq!(&a + &b, BigInt::from(-11));
+    assert_eq!(&b - &a, BigInt::from(19));
+    assert_eq!(&a * &b, BigInt::from(-60));
+    assert_eq!(a.div_rem(&b), Some((BigInt::from(-3), BigInt::from(-3))));
+    assert_eq!(a.pow(3), BigInt::from(-3375));
+    assert_eq!(&a + &BigInt::from(15), BigInt::new());
+    assert!(!(&a + &BigInt::from(15)).is_negative());
+    assert!(a.div_rem(&BigInt::new()).is_none());
+    assert!(a < b);
+    assert!(BigInt::from(-20) < a);
+    assert_eq!(BigInt::from_str_radix("-ff", 16), Some(BigInt::from(-255)));
+}
+
+#[test]
+fn signed_bitwise() {
+    let a = BigInt::from(-6);
+    let b = BigInt::from(13);
+    assert_eq!(&a & &b, BigInt::from(-6 & 13));
+    assert_eq!(&a | &b, BigInt::from(-6 | 13));
+    assert_eq!(&a ^ &b, BigInt::from(-6 ^ 13));
+    assert_eq!(&a & &BigInt::from(-3), BigInt::from(-6 & -3));
+    assert_eq!(!&a, BigInt::from(5));
+    assert_eq!(&a << 3, BigInt::from(-48));
+    assert_eq!(&a >> 2, BigInt::from(-2));
+    assert_eq!(&BigInt::from(-8) >> 2, BigInt::from(-2));
+    assert_eq!(&b >> 2, BigInt::from(3));
+}
+
+#[test]
+fn as_int_n() {
+    assert_eq!(BigInt::from(-1).as_uint_n(8), BigInt::from(255));
+    assert_eq!(BigInt::from(-1).as_uint_n(64).to_string_radix(10), u64::MAX.to_string());
+    assert_eq!(BigInt::from(255).as_int_n(8), BigInt::from(-1));
+    assert_eq!(BigInt::from(128).as_int_n(8), BigInt::from(-128));
+    assert_eq!(BigInt::from(127).as_int_n(8), BigInt::from(127));
+    assert_eq!(BigInt::from(-129).as_int_n(8), BigInt::from(127));
+    assert_eq!(BigInt::from(1 << 40).as_uint_n(40), BigInt::new());
+    assert_eq!(BigInt::from(5).as_int_n(0), BigInt::new());
+}
@@ -1,4 +1,5 @@
 use quote::quote;
+use syn::{Lit, Meta, NestedMeta};
 use synstructure::{decl_derive, BindStyle, Structure};
 
 decl_derive!([GcTrace, attributes(unsafe_ignore_trace)] => derive_trace);
@@ -30,3 +31,70 @@ fn derive_trace(mut s: Structure<'_>) -> proc_macro2::TokenStream {
         #trace_impl
     }
 }
+
+/// Declare a `NativeFunction` constant for a `fn(&mut Context, JsValue, Vec<JsValue>) ->
+/// ast::Result<JsValue>`, named like the function in upper case so it doesn't shadow anything.
+///
+/// The JS name is the Rust one in camelCase and `length` is 0 unless given:
+/// `#[js_function(name = "isNaN", length = 1)]`
+#[proc_macro_attribute]
+pub fn js_function(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let args = syn::parse_macro_input!(attr as syn::AttributeArgs);
+    let function = syn::parse_macro_input!(item as syn::ItemFn);
+
+    let ident = &function.sig.ident;
+    let rust_name = ident.to_string().trim_start_matches("r#").to_string();
+    let constant = syn::Ident::new(&rust_name.to_uppercase(), ident.span());
+    let mut name = camel_case(&rust_name);
+    let mut length = 0u32;
+    for arg in args {
+        match arg {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("name") => match nv.lit {
+                Lit::Str(s) => name = s.value(),
+                lit => return error(lit, "expected a string"),
+            },
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("length") => match nv.lit {
+                Lit::Int(i) => match i.base10_parse() {
+                    Ok(i) => length = i,
+                    Err(e) => return e.to_compile_error().into(),
+                },
+                lit => return error(lit, "expected an integer"),
+            },
+            arg => return error(arg, "expected `name = \"...\"` or `length = n`"),
+        }
+    }
+
+    let vis = &function.vis;
+    let expanded = quote! {
+        #function
+
+        #vis const #constant: crate::jsrt::NativeFunction =
+            crate::jsrt::NativeFunction::new(#name, #length, #ident);
+    };
+    expanded.into()
+}
+
+fn camel_case(name: &str) -> String {
+    let mut result = String::new();
+    let mut upper = false;
+    for c in name.chars() {
+        match c {
+            '_' => upper = !result.is_empty(),
+            c if upper => {
+                result.extend(c.to_uppercase());
+                upper = false;
+            }
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+fn error<T: quote::ToTokens>(tokens: T, message: &str) -> proc_macro::TokenStream {
+    syn::Error::new_spanned(tokens, message)
+        .to_compile_error()
+        .into()
+}
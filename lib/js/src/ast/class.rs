@@ -0,0 +1,462 @@
+use std::rc::Rc;
+
+use super::*;
+use crate::{gc::GcPointer, vm::EnvironmentRecordKind};
+
+/// `class Name extends Parent { ... }`, shared by class declarations and expressions
+#[derive(Debug)]
+pub struct Class {
+    name: Option<Identifier>,
+    heritage: Option<Box<Expression>>,
+    /// Written in the body or the default one, which forwards its arguments to `super()`
+    constructor: Rc<Function>,
+    elements: Vec<ClassElement>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassElementKind {
+    Method,
+    Getter,
+    Setter,
+    Field,
+}
+
+/// Method, accessor or field of a class body
+#[derive(Debug)]
+pub struct ClassElement {
+    /// Name, literal, private name or the expression of a computed key
+    pub(crate) key: Expression,
+    pub(crate) computed: bool,
+    pub(crate) kind: ClassElementKind,
+    pub(crate) is_static: bool,
+    /// The method, or a method returning the initial value of a field
+    pub(crate) function: Option<Rc<Function>>,
+}
+
+impl ClassElement {
+    pub fn new(
+        key: Expression,
+        computed: bool,
+        kind: ClassElementKind,
+        is_static: bool,
+        function: Option<Function>,
+    ) -> ClassElement {
+        ClassElement {
+            key,
+            computed,
+            kind,
+            is_static,
+            function: function.map(Rc::new),
+        }
+    }
+
+    /// `#name` of a private element
+    pub fn private_name(&self) -> Option<&str> {
+        match &self.key {
+            Expression::PrivateName(name) if !self.computed => Some(name.name()),
+            _ => None,
+        }
+    }
+
+    /// The key as a value, names are strings and private names their symbol
+    fn key(&self, context: &mut Context) -> Result<JsValue> {
+        match &self.key {
+            Expression::Identifier(ident) if !self.computed => {
+                Ok(JsValue::string(ident.name().as_ref()))
+            }
+            key => key.eval(context),
+        }
+    }
+}
+
+impl Class {
+    pub fn new(
+        name: Option<Identifier>,
+        heritage: Option<Expression>,
+        constructor: Function,
+        elements: Vec<ClassElement>,
+    ) -> Class {
+        Class {
+            name,
+            heritage: heritage.map(Box::new),
+            constructor: Rc::new(constructor),
+            elements,
+        }
+    }
+
+    pub fn name(&self) -> Option<&Identifier> {
+        self.name.as_ref()
+    }
+
+    pub fn heritage(&self) -> Option<&Expression> {
+        self.heritage.as_deref()
+    }
+
+    pub fn constructor(&self) -> &Rc<Function> {
+        &self.constructor
+    }
+
+    pub fn elements(&self) -> &[ClassElement] {
+        &self.elements
+    }
+
+    /// Bind a new private symbol to each `#name` of the body, a getter and a setter share one
+    pub(crate) fn declare_private_names(&self, context: &mut Context) -> Result<()> {
+        let mut names: Vec<&str> = Vec::new();
+        for name in self.elements.iter().filter_map(ClassElement::private_name) {
+            if names.contains(&name) {
+                continue;
+            }
+            names.push(name);
+            context.declare_variable(name, DeclarationKind::Const)?;
+            let symbol = JsSymbol::private(context, name);
+            context.initialize_variable(name, JsValue::Symbol(symbol));
+        }
+        Ok(())
+    }
+
+    /// `ClassDefinitionEvaluation` once the parent, the keys and the functions of the elements
+    /// are evaluated, `elements` has a key and a function or `undefined` for each of them.
+    ///
+    /// Runs in the scope of the class, where it initializes the name of the class
+    pub(crate) fn define(
+        &self,
+        context: &mut Context,
+        heritage: Option<JsValue>,
+        mut constructor: GcPointer<JsObject>,
+        elements: Vec<(JsValue, JsValue)>,
+    ) -> Result<JsValue> {
+        let (proto_parent, constructor_parent) = match heritage {
+            None => (
+                Some(context.object_prototype()),
+                context.function_prototype(),
+            ),
+            Some(JsValue::Null) => (None, context.function_prototype()),
+            Some(JsValue::Object(parent)) if parent.is_constructor() => {
+                let prototype = JsValue::object(parent.clone());
+                let prototype = prototype.get_property(context, &PropertyKey::from("prototype"))?;
+                match prototype {
+                    JsValue::Object(prototype) => (Some(prototype), parent),
+                    JsValue::Null => (None, parent),
+                    prototype => {
+                        let message = format!(
+                            "Class extends value does not have valid prototype property {}",
+                            prototype.to_string()
+                        );
+                        return Err(context.throw_error(ErrorKind::TypeError, &message));
+                    }
+                }
+            }
+            Some(heritage) => {
+                let message = format!(
+                    "Class extends value {} is not a constructor or null",
+                    heritage.to_string()
+                );
+                return Err(context.throw_error(ErrorKind::TypeError, &message));
+            }
+        };
+
+        let mut prototype = JsObject::with_prototype(context, proto_parent, ObjectKind::Ordinary);
+        constructor.set_prototype_of(Some(constructor_parent));
+        set_home_object(&mut constructor, &prototype);
+        let attributes = Attributes::WRITABLE | Attributes::CONFIGURABLE;
+        constructor.define_property(
+            "prototype",
+            JsValue::object(prototype.clone()),
+            Attributes::empty(),
+        );
+        prototype.define_property(
+            "constructor",
+            JsValue::object(constructor.clone()),
+            attributes,
+        );
+
+        let mut private_methods = Vec::new();
+        let mut static_private_methods = Vec::new();
+        let mut fields = Vec::new();
+        let mut static_fields = Vec::new();
+        for (element, (key, function)) in self.elements.iter().zip(elements) {
+            let mut target = match element.is_static {
+                true => constructor.clone(),
+                false => prototype.clone(),
+            };
+            if let JsValue::Object(function) = &function {
+                let mut function = function.clone();
+                set_home_object(&mut function, &target);
+                if element.computed && element.kind != ClassElementKind::Field {
+                    set_function_name(&mut function, &key, element.kind);
+                }
+            }
+
+            if element.kind == ClassElementKind::Field {
+                let field = ClassField {
+                    key,
+                    initializer: Some(function).filter(|function| !function.is_undefined()),
+                };
+                match element.is_static {
+                    true => static_fields.push(field),
+                    false => fields.push(field),
+                }
+                continue;
+            }
+
+            if let JsValue::Symbol(symbol) = &key {
+                if symbol.is_private() {
+                    let private_element = PrivateElement {
+                        key: symbol.clone(),
+                        kind: match element.kind {
+                            ClassElementKind::Getter => PrivateElementKind::Accessor {
+                                get: function,
+                                set: JsValue::Undefined,
+                            },
+                            ClassElementKind::Setter => PrivateElementKind::Accessor {
+                                get: JsValue::Undefined,
+                                set: function,
+                            },
+                            _ => PrivateElementKind::Method(function),
+                        },
+                    };
+                    match element.is_static {
+                        true => add_private_method(&mut static_private_methods, private_element),
+                        false => add_private_method(&mut private_methods, private_element),
+                    }
+                    continue;
+                }
+            }
+
+            let key = PropertyKey::from_value(&key);
+            let desc = match element.kind {
+                ClassElementKind::Getter => PropertyDescriptor {
+                    get: Some(function),
+                    enumerable: Some(false),
+                    configurable: Some(true),
+                    ..Default::default()
+                },
+                ClassElementKind::Setter => PropertyDescriptor {
+                    set: Some(function),
+                    enumerable: Some(false),
+                    configurable: Some(true),
+                    ..Default::default()
+                },
+                _ => PropertyDescriptor::data(function, attributes),
+            };
+            target.define_own_property(key, desc);
+        }
+
+        if let ObjectKind::Function(function) = &mut constructor.kind {
+            function.set_class_elements(private_methods, fields);
+        }
+        for element in static_private_methods {
+            constructor.add_private_element(context, element)?;
+        }
+
+        // Static fields see the methods and the accessors of the class, and its name
+        let class = JsValue::object(constructor);
+        if let Some(name) = &self.name {
+            context.initialize_variable(name.name(), class.clone());
+        }
+        for field in static_fields {
+            define_field(context, &class, field)?;
+        }
+        Ok(class)
+    }
+}
+
+impl ASTNode for Class {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        // The name and the private names are only seen from inside the class
+        context.push_environment(EnvironmentRecordKind::Declarative);
+        let result = self.evaluate_in_scope(context);
+        context.pop_environment();
+        result
+    }
+}
+
+impl Class {
+    fn evaluate_in_scope(&self, context: &mut Context) -> Result<JsValue> {
+        if let Some(name) = &self.name {
+            context.declare_variable(name.name(), DeclarationKind::Const)?;
+        }
+        self.declare_private_names(context)?;
+
+        let heritage = match &self.heritage {
+            Some(heritage) => Some(heritage.eval(context)?),
+            None => None,
+        };
+        let constructor = JsObject::function(context, FunctionCode::Ast(self.constructor.clone()));
+
+        let mut elements = Vec::with_capacity(self.elements.len());
+        for element in self.elements.iter() {
+            let key = element.key(context)?;
+            let function = match &element.function {
+                Some(function) => JsValue::object(JsObject::function(
+                    context,
+                    FunctionCode::Ast(function.clone()),
+                )),
+                None => JsValue::Undefined,
+            };
+            elements.push((key, function));
+        }
+
+        self.define(context, heritage, constructor, elements)
+    }
+}
+
+/// `class Name { ... }`, bound like `let`
+#[derive(Debug)]
+pub struct ClassDeclaration {
+    class: Rc<Class>,
+}
+
+impl ClassDeclaration {
+    pub fn new(class: Class) -> ClassDeclaration {
+        ClassDeclaration {
+            class: Rc::new(class),
+        }
+    }
+
+    pub fn class(&self) -> &Rc<Class> {
+        &self.class
+    }
+
+    pub fn name(&self) -> &str {
+        self.class.name().map_or("", |name| name.name())
+    }
+}
+
+impl ASTNode for ClassDeclaration {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        let class = self.class.eval(context)?;
+        context.initialize_variable(self.name(), class);
+        Ok(JsValue::Undefined)
+    }
+}
+
+#[derive(Debug)]
+pub struct ClassExpression {
+    class: Rc<Class>,
+}
+
+impl ClassExpression {
+    pub fn new(class: Class) -> ClassExpression {
+        ClassExpression {
+            class: Rc::new(class),
+        }
+    }
+
+    pub fn class(&self) -> &Rc<Class> {
+        &self.class
+    }
+}
+
+impl ASTNode for ClassExpression {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        self.class.eval(context)
+    }
+}
+
+/// Add the private methods and the fields of the class of `constructor` to a new instance,
+/// before its constructor runs or once `super()` returns
+pub(crate) fn initialize_instance_elements(
+    context: &mut Context,
+    this: &JsValue,
+    constructor: &GcPointer<JsObject>,
+) -> Result<()> {
+    let (private_methods, fields) = match &constructor.kind {
+        ObjectKind::Function(function) => (
+            function.private_methods().to_vec(),
+            function.fields().to_vec(),
+        ),
+        _ => return Ok(()),
+    };
+    let mut obj = match this {
+        JsValue::Object(obj) => obj.clone(),
+        _ => return Ok(()),
+    };
+
+    for element in private_methods {
+        obj.add_private_element(context, element)?;
+    }
+    for field in fields {
+        define_field(context, this, field)?;
+    }
+    Ok(())
+}
+
+/// Define a field on `target` with the value of its initializer
+fn define_field(context: &mut Context, target: &JsValue, field: ClassField) -> Result<()> {
+    let value = match &field.initializer {
+        Some(initializer) => context.call(initializer, target.clone(), Vec::new())?,
+        None => JsValue::Undefined,
+    };
+    let mut obj = match target {
+        JsValue::Object(obj) => obj.clone(),
+        _ => return Ok(()),
+    };
+    match field.key {
+        JsValue::Symbol(symbol) if symbol.is_private() => {
+            let kind = PrivateElementKind::Field(value);
+            obj.add_private_element(context, PrivateElement { key: symbol, kind })
+        }
+        key => {
+            obj.create_data_property(PropertyKey::from_value(&key), value);
+            Ok(())
+        }
+    }
+}
+
+/// Add a private method or accessor, the getter and the setter of a name are merged
+fn add_private_method(elements: &mut Vec<PrivateElement>, element: PrivateElement) {
+    let existing = elements.iter_mut().find(|e| e.key == element.key);
+    match (existing, element.kind) {
+        (
+            Some(PrivateElement {
+                kind: PrivateElementKind::Accessor { get, set },
+                ..
+            }),
+            PrivateElementKind::Accessor {
+                get: new_get,
+                set: new_set,
+            },
+        ) => {
+            if !new_get.is_undefined() {
+                *get = new_get;
+            }
+            if !new_set.is_undefined() {
+                *set = new_set;
+            }
+        }
+        (_, kind) => elements.push(PrivateElement {
+            key: element.key,
+            kind,
+        }),
+    }
+}
+
+fn set_home_object(function: &mut GcPointer<JsObject>, home_object: &GcPointer<JsObject>) {
+    if let ObjectKind::Function(function) = &mut function.kind {
+        function.set_home_object(home_object.clone());
+    }
+}
+
+/// `name` of a method whose key is only known once evaluated, `[Symbol.iterator]` is named
+/// "[Symbol.iterator]"
+fn set_function_name(function: &mut GcPointer<JsObject>, key: &JsValue, kind: ClassElementKind) {
+    let name = match key {
+        JsValue::Symbol(symbol) if symbol.is_private() => {
+            symbol.description().unwrap_or_default().to_string()
+        }
+        JsValue::Symbol(symbol) => format!("[{}]", symbol.description().unwrap_or_default()),
+        key => PropertyKey::from_value(key).to_string(),
+    };
+    let name = match kind {
+        ClassElementKind::Getter => format!("get {}", name),
+        ClassElementKind::Setter => format!("set {}", name),
+        _ => name,
+    };
+    function.define_property(
+        "name",
+        JsValue::string(name.as_ref()),
+        Attributes::CONFIGURABLE,
+    );
+}
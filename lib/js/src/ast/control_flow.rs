@@ -0,0 +1,503 @@
+use super::*;
+use crate::vm::EnvironmentRecordKind;
+
+/// What a loop does after running its body once
+enum LoopFlow {
+    Next,
+    Exit,
+}
+
+/// Handle the completion of a loop body, `labels` are the labels of the loop itself.
+///
+/// `value` is the completion value of the loop so far, a `break` or `continue` keeps it
+/// unless statements before them in the body had one
+fn loop_flow(result: Result<JsValue>, value: &mut JsValue, labels: &[&str]) -> Result<LoopFlow> {
+    match result {
+        Ok(v) => {
+            *value = v;
+            Ok(LoopFlow::Next)
+        }
+        Err(Completion::Break(None, v)) => {
+            if let Some(v) = v {
+                *value = v;
+            }
+            Ok(LoopFlow::Exit)
+        }
+        Err(Completion::Continue(label, v))
+            if label
+                .as_ref()
+                .is_none_or(|label| labels.contains(&label.as_str())) =>
+        {
+            if let Some(v) = v {
+                *value = v;
+            }
+            Ok(LoopFlow::Next)
+        }
+        Err(completion) => Err(completion.update_empty(value)),
+    }
+}
+
+#[derive(Debug)]
+pub struct IfStatement {
+    pub(crate) test: Expression,
+    pub(crate) consequent: Box<Statement>,
+    pub(crate) alternate: Option<Box<Statement>>,
+}
+
+impl IfStatement {
+    pub fn new(test: Expression, consequent: Statement, alternate: Option<Statement>) -> Self {
+        IfStatement {
+            test,
+            consequent: Box::new(consequent),
+            alternate: alternate.map(Box::new),
+        }
+    }
+}
+
+impl ASTNode for IfStatement {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        let result = if self.test.eval(context)?.to_boolean() {
+            self.consequent.eval(context)
+        } else if let Some(alternate) = &self.alternate {
+            alternate.eval(context)
+        } else {
+            Ok(JsValue::Undefined)
+        };
+        result.map_err(|completion| completion.update_empty(&JsValue::Undefined))
+    }
+}
+
+#[derive(Debug)]
+pub struct WhileStatement {
+    pub(crate) test: Expression,
+    pub(crate) body: Box<Statement>,
+    /// `do ... while`, the body run once before the first test
+    pub(crate) is_do_while: bool,
+}
+
+impl WhileStatement {
+    pub fn new(test: Expression, body: Statement, is_do_while: bool) -> Self {
+        WhileStatement {
+            test,
+            body: Box::new(body),
+            is_do_while,
+        }
+    }
+
+    pub fn eval_loop(&self, context: &mut Context, labels: &[&str]) -> Result<JsValue> {
+        let mark = context.allocation_mark();
+        let mut value = JsValue::Undefined;
+        let mut skip_test = self.is_do_while;
+        loop {
+            if !skip_test && !self.test.eval(context)?.to_boolean() {
+                break;
+            }
+            skip_test = false;
+
+            let result = self.body.eval(context);
+            if let LoopFlow::Exit = loop_flow(result, &mut value, labels)? {
+                break;
+            }
+            context.release(mark, &mut value);
+        }
+
+        Ok(value)
+    }
+}
+
+impl ASTNode for WhileStatement {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        self.eval_loop(context, &[])
+    }
+}
+
+#[derive(Debug)]
+pub struct ForStatement {
+    /// A variable declaration or an expression statement
+    pub(crate) init: Option<Box<Statement>>,
+    pub(crate) test: Option<Expression>,
+    pub(crate) update: Option<Expression>,
+    pub(crate) body: Box<Statement>,
+}
+
+impl ForStatement {
+    pub fn new(
+        init: Option<Statement>,
+        test: Option<Expression>,
+        update: Option<Expression>,
+        body: Statement,
+    ) -> Self {
+        ForStatement {
+            init: init.map(Box::new),
+            test,
+            update,
+            body: Box::new(body),
+        }
+    }
+
+    /// `let` declared in the head, each iteration get its own copy of them
+    pub(crate) fn per_iteration_names(&self) -> Vec<&str> {
+        match self.init.as_deref() {
+            Some(Statement::VariableDeclaration(vd)) if vd.kind == DeclarationKind::Let => {
+                vd.bound_names()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Closures created in the previous iteration keep the old bindings
+    fn copy_iteration_environment(context: &mut Context, names: &[&str]) -> Result<()> {
+        if names.is_empty() {
+            return Ok(());
+        }
+
+        let mut values = Vec::with_capacity(names.len());
+        for name in names {
+            values.push(context.get_variable(name)?);
+        }
+
+        context.pop_environment();
+        context.push_environment(EnvironmentRecordKind::Declarative);
+        for (name, value) in names.iter().zip(values) {
+            context.declare_variable(name, DeclarationKind::Let)?;
+            context.initialize_variable(name, value);
+        }
+        Ok(())
+    }
+
+    pub fn eval_loop(&self, context: &mut Context, labels: &[&str]) -> Result<JsValue> {
+        context.push_environment(EnvironmentRecordKind::Declarative);
+        let result = self.run(context, labels);
+        context.pop_environment();
+        result
+    }
+
+    fn run(&self, context: &mut Context, labels: &[&str]) -> Result<JsValue> {
+        if let Some(init) = &self.init {
+            if let Statement::VariableDeclaration(vd) = init.as_ref() {
+                if vd.kind != DeclarationKind::Var {
+                    for name in vd.bound_names() {
+                        context.declare_variable(name, vd.kind)?;
+                    }
+                }
+            }
+            init.eval(context)?;
+        }
+
+        let names = self.per_iteration_names();
+        Self::copy_iteration_environment(context, &names)?;
+
+        // What an iteration allocates is garbage once it's over, unless the scope references it
+        let mark = context.allocation_mark();
+        let mut value = JsValue::Undefined;
+        loop {
+            if let Some(test) = &self.test {
+                if !test.eval(context)?.to_boolean() {
+                    break;
+                }
+            }
+
+            let result = self.body.eval(context);
+            if let LoopFlow::Exit = loop_flow(result, &mut value, labels)? {
+                break;
+            }
+            context.release(mark, &mut value);
+
+            Self::copy_iteration_environment(context, &names)?;
+            if let Some(update) = &self.update {
+                update.eval(context)?;
+            }
+        }
+
+        Ok(value)
+    }
+}
+
+impl ASTNode for ForStatement {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        self.eval_loop(context, &[])
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ForInKind {
+    /// Iterate over the property keys
+    In,
+    /// Iterate over the values of an iterable
+    Of,
+}
+
+/// Left side of `for (x in y)`
+#[derive(Debug)]
+pub enum ForBinding {
+    Declaration(DeclarationKind, BindingPattern),
+    Identifier(Identifier),
+}
+
+#[derive(Debug)]
+pub struct ForInStatement {
+    pub(crate) kind: ForInKind,
+    pub(crate) binding: ForBinding,
+    pub(crate) iterated: Expression,
+    pub(crate) body: Box<Statement>,
+}
+
+impl ForInStatement {
+    pub fn new(
+        kind: ForInKind,
+        binding: ForBinding,
+        iterated: Expression,
+        body: Statement,
+    ) -> Self {
+        ForInStatement {
+            kind,
+            binding,
+            iterated,
+            body: Box::new(body),
+        }
+    }
+
+    pub fn eval_loop(&self, context: &mut Context, labels: &[&str]) -> Result<JsValue> {
+        let iterated = self.iterated.eval(context)?;
+        let mut iterator = ForInIterator::new(self.kind, context, iterated)?;
+
+        let mark = context.allocation_mark();
+        let mut value = JsValue::Undefined;
+        while let Some(item) = iterator.next(context)? {
+            let result = match &self.binding {
+                ForBinding::Identifier(id) => context
+                    .set_variable(id.name(), item)
+                    .and_then(|_| self.body.eval(context)),
+                ForBinding::Declaration(DeclarationKind::Var, target) => target
+                    .bind(context, item, false)
+                    .and_then(|_| self.body.eval(context)),
+                ForBinding::Declaration(kind, target) => {
+                    context.push_environment(EnvironmentRecordKind::Declarative);
+                    let mut names = Vec::new();
+                    target.bound_names(&mut names);
+                    let result = names
+                        .into_iter()
+                        .try_for_each(|name| context.declare_variable(name, *kind))
+                        .and_then(|_| target.bind(context, item, true))
+                        .and_then(|_| self.body.eval(context));
+                    context.pop_environment();
+                    result
+                }
+            };
+
+            match loop_flow(result, &mut value, labels) {
+                Ok(LoopFlow::Next) => context.release(mark, &mut value),
+                Ok(LoopFlow::Exit) => return iterator.close(context, Ok(value)),
+                Err(completion) => return iterator.close(context, Err(completion)),
+            }
+        }
+
+        Ok(value)
+    }
+}
+
+/// What a `for in` or `for of` loop steps through
+pub(crate) enum ForInIterator {
+    /// The keys of `for in`, collected before the first iteration
+    Keys(vec::IntoIter<JsValue>),
+    /// The iterator of `for of`, stepped lazily
+    Values(IteratorRecord),
+}
+
+unsafe impl Trace for ForInIterator {
+    fn trace(&mut self, tracer: &mut Tracer) {
+        match self {
+            ForInIterator::Keys(keys) => {
+                keys.as_mut_slice().iter_mut().for_each(|k| k.trace(tracer))
+            }
+            ForInIterator::Values(record) => record.trace(tracer),
+        }
+    }
+}
+
+impl ForInIterator {
+    pub(crate) fn new(
+        kind: ForInKind,
+        context: &mut Context,
+        iterated: JsValue,
+    ) -> Result<ForInIterator> {
+        let keys: Vec<_> = match (kind, &iterated) {
+            (ForInKind::Of, _) => {
+                let record = IteratorRecord::from_iterable(context, &iterated)?;
+                return Ok(ForInIterator::Values(record));
+            }
+            (ForInKind::In, JsValue::Object(obj)) => obj
+                .enumerable_keys()
+                .iter()
+                .map(PropertyKey::to_value)
+                .collect(),
+            (ForInKind::In, JsValue::String(s)) => (0..s.string.chars().count())
+                .map(|i| JsValue::string(i.to_string().as_ref()))
+                .collect(),
+            (ForInKind::In, _) => Vec::new(),
+        };
+        Ok(ForInIterator::Keys(keys.into_iter()))
+    }
+
+    pub(crate) fn next(&mut self, context: &mut Context) -> Result<Option<JsValue>> {
+        match self {
+            ForInIterator::Keys(keys) => Ok(keys.next()),
+            ForInIterator::Values(record) => record.step(context),
+        }
+    }
+
+    /// Close the iterator of a loop left with `result` before the end, an exception thrown by
+    /// the loop wins over one thrown by `return`
+    pub(crate) fn close<T>(&mut self, context: &mut Context, result: Result<T>) -> Result<T> {
+        let record = match self {
+            ForInIterator::Keys(_) => return result,
+            ForInIterator::Values(record) => record,
+        };
+        match result {
+            Err(Completion::Throw(exception)) => {
+                let _ = record.close(context);
+                Err(Completion::Throw(exception))
+            }
+            result => record.close(context).and(result),
+        }
+    }
+}
+
+impl ASTNode for ForInStatement {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        self.eval_loop(context, &[])
+    }
+}
+
+#[derive(Debug)]
+pub struct SwitchCase {
+    /// `None` for `default:`
+    pub(crate) test: Option<Expression>,
+    pub(crate) consequent: Vec<Statement>,
+}
+
+impl SwitchCase {
+    pub fn new(test: Option<Expression>, consequent: Vec<Statement>) -> Self {
+        SwitchCase { test, consequent }
+    }
+}
+
+#[derive(Debug)]
+pub struct SwitchStatement {
+    pub(crate) discriminant: Expression,
+    pub(crate) cases: Vec<SwitchCase>,
+}
+
+impl SwitchStatement {
+    pub fn new(discriminant: Expression, cases: Vec<SwitchCase>) -> Self {
+        SwitchStatement {
+            discriminant,
+            cases,
+        }
+    }
+
+    fn run(&self, context: &mut Context, discriminant: JsValue) -> Result<JsValue> {
+        let mut start = None;
+        for (index, case) in self.cases.iter().enumerate() {
+            if let Some(test) = &case.test {
+                let value = test.eval(context)?;
+                if discriminant.strict_equals(&value) {
+                    start = Some(index);
+                    break;
+                }
+            }
+        }
+
+        let start = match start.or_else(|| self.cases.iter().position(|c| c.test.is_none())) {
+            Some(start) => start,
+            None => return Ok(JsValue::Undefined),
+        };
+
+        // Fall through every case after the matching one
+        let mut value = JsValue::Undefined;
+        for case in self.cases[start..].iter() {
+            for statement in case.consequent.iter() {
+                let result = statement
+                    .eval(context)
+                    .map_err(|completion| completion.update_empty(&value))?;
+                if statement.has_completion_value() {
+                    value = result;
+                }
+            }
+        }
+
+        Ok(value)
+    }
+}
+
+impl ASTNode for SwitchStatement {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        let discriminant = self.discriminant.eval(context)?;
+
+        // All the cases share one scope
+        context.push_environment(EnvironmentRecordKind::Declarative);
+        let mut result = Ok(JsValue::Undefined);
+        for case in self.cases.iter() {
+            result = instantiate_lexical_declarations(&case.consequent, context)
+                .map(|_| JsValue::Undefined);
+            if result.is_err() {
+                break;
+            }
+        }
+        if result.is_ok() {
+            result = self.run(context, discriminant);
+        }
+        context.pop_environment();
+
+        match result {
+            Err(Completion::Break(None, value)) => Ok(value.unwrap_or_default()),
+            result => result,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LabelledStatement {
+    pub(crate) label: Identifier,
+    pub(crate) body: Box<Statement>,
+}
+
+impl LabelledStatement {
+    pub fn new(label: Identifier, body: Statement) -> Self {
+        LabelledStatement {
+            label,
+            body: Box::new(body),
+        }
+    }
+
+    /// `outer` are the labels directly around this one, as in `a: b: while (...)`
+    pub fn eval_labelled<'a>(
+        &'a self,
+        context: &mut Context,
+        outer: &[&'a str],
+    ) -> Result<JsValue> {
+        let mut labels = outer.to_vec();
+        labels.push(self.label.name());
+
+        let result = match self.body.as_ref() {
+            Statement::LabelledStatement(s) => s.eval_labelled(context, &labels),
+            Statement::WhileStatement(s) => s.eval_loop(context, &labels),
+            Statement::ForStatement(s) => s.eval_loop(context, &labels),
+            Statement::ForInStatement(s) => s.eval_loop(context, &labels),
+            body => body.eval(context),
+        };
+
+        match result {
+            Err(Completion::Break(Some(label), value)) if &label == self.label.name() => {
+                Ok(value.unwrap_or_default())
+            }
+            result => result,
+        }
+    }
+}
+
+impl ASTNode for LabelledStatement {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        self.eval_labelled(context, &[])
+    }
+}
@@ -0,0 +1,93 @@
+use super::*;
+use crate::vm::EnvironmentRecordKind;
+
+#[derive(Debug)]
+pub struct ThrowStatement {
+    pub(crate) argument: Expression,
+    pub(crate) position: Position,
+}
+
+impl ThrowStatement {
+    pub fn new(argument: Expression, position: Position) -> Self {
+        ThrowStatement { argument, position }
+    }
+}
+
+impl ASTNode for ThrowStatement {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        let value = self.argument.eval(context)?;
+        context.set_position(self.position);
+        Err(Completion::Throw(value))
+    }
+}
+
+/// `catch (param) { ... }`, the binding is optional
+#[derive(Debug)]
+pub struct CatchClause {
+    pub(crate) param: Option<BindingPattern>,
+    pub(crate) body: BlockStatement,
+}
+
+impl CatchClause {
+    pub fn new(param: Option<BindingPattern>, body: BlockStatement) -> Self {
+        CatchClause { param, body }
+    }
+
+    fn eval(&self, context: &mut Context, exception: JsValue) -> Result<JsValue> {
+        let param = match &self.param {
+            Some(param) => param,
+            None => return self.body.eval(context),
+        };
+        let mut names = Vec::new();
+        param.bound_names(&mut names);
+
+        context.push_environment(EnvironmentRecordKind::Declarative);
+        let result = names
+            .into_iter()
+            .try_for_each(|name| context.declare_variable(name, DeclarationKind::Let))
+            .and_then(|_| param.bind(context, exception, true))
+            .and_then(|_| self.body.eval(context));
+        context.pop_environment();
+        result
+    }
+}
+
+#[derive(Debug)]
+pub struct TryStatement {
+    pub(crate) block: BlockStatement,
+    pub(crate) handler: Option<CatchClause>,
+    pub(crate) finalizer: Option<BlockStatement>,
+}
+
+impl TryStatement {
+    pub fn new(
+        block: BlockStatement,
+        handler: Option<CatchClause>,
+        finalizer: Option<BlockStatement>,
+    ) -> Self {
+        TryStatement {
+            block,
+            handler,
+            finalizer,
+        }
+    }
+}
+
+impl ASTNode for TryStatement {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        let mut result = self.block.eval(context);
+
+        if let Some(handler) = &self.handler {
+            if let Err(Completion::Throw(exception)) = result {
+                result = handler.eval(context, exception);
+            }
+        }
+
+        // `finally` only replace the result when it doesn't complete normally
+        if let Some(finalizer) = &self.finalizer {
+            finalizer.eval(context)?;
+        }
+
+        result
+    }
+}
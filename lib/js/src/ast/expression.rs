@@ -1,4 +1,10 @@
+use std::{
+    rc::Rc,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
 use super::*;
+use crate::regexp::Regex;
 
 #[derive(Debug)]
 pub enum Expression {
@@ -6,17 +12,58 @@ pub enum Expression {
     Identifier(Identifier),
     Literal(Literal),
     CallExpression(CallExpression),
+    MemberExpression(MemberExpression),
     ObjectExpression(ObjectExpression),
+    ArrayExpression(ArrayExpression),
+    FunctionExpression(FunctionExpression),
+    UnaryExpression(UnaryExpression),
+    UpdateExpression(UpdateExpression),
+    ConditionalExpression(ConditionalExpression),
+    TemplateLiteral(TemplateLiteral),
+    TaggedTemplate(TaggedTemplate),
+    RegExpLiteral(RegExpLiteral),
+    SpreadElement(SpreadElement),
+    NewExpression(NewExpression),
+    SuperCall(SuperCall),
+    SuperProperty(SuperProperty),
+    /// `#name` after a `.`, evaluates to the private symbol the class bound it to
+    PrivateName(Identifier),
+    ClassExpression(ClassExpression),
+    YieldExpression(YieldExpression),
+    AwaitExpression(AwaitExpression),
+    This,
+    /// `new.target`, the constructor `new` was applied to in the innermost function that binds
+    /// `this`
+    NewTarget,
 }
 
 impl ASTNode for Expression {
-    fn eval(&mut self, context: &mut Context) -> Result<JsValue> {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
         match self {
             Expression::BinaryOperation(e) => e.eval(context),
             Expression::Identifier(e) => e.eval(context),
             Expression::Literal(e) => e.eval(context),
             Expression::CallExpression(e) => e.eval(context),
+            Expression::MemberExpression(e) => e.eval(context),
             Expression::ObjectExpression(e) => e.eval(context),
+            Expression::ArrayExpression(e) => e.eval(context),
+            Expression::FunctionExpression(e) => e.eval(context),
+            Expression::UnaryExpression(e) => e.eval(context),
+            Expression::UpdateExpression(e) => e.eval(context),
+            Expression::ConditionalExpression(e) => e.eval(context),
+            Expression::TemplateLiteral(e) => e.eval(context),
+            Expression::TaggedTemplate(e) => e.eval(context),
+            Expression::RegExpLiteral(e) => e.eval(context),
+            Expression::SpreadElement(e) => e.eval(context),
+            Expression::NewExpression(e) => e.eval(context),
+            Expression::SuperCall(e) => e.eval(context),
+            Expression::SuperProperty(e) => e.eval(context),
+            Expression::PrivateName(e) => e.eval(context),
+            Expression::ClassExpression(e) => e.eval(context),
+            Expression::YieldExpression(e) => e.eval(context),
+            Expression::AwaitExpression(e) => e.eval(context),
+            Expression::This => context.this(),
+            Expression::NewTarget => Ok(context.new_target()),
         }
     }
 }
@@ -27,8 +74,8 @@ pub struct Identifier {
 }
 
 impl ASTNode for Identifier {
-    fn eval(&mut self, context: &mut Context) -> Result<JsValue> {
-        Ok(context.get_variable(&self.name))
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        context.get_variable(&self.name)
     }
 }
 
@@ -50,36 +97,54 @@ pub enum Literal {
     Boolean(bool),
     Integer(i32),
     Rational(f64),
-    BigInt(()),
+    BigInt(JsBigInt),
     String(String),
 }
 
 impl Literal {
     pub fn number_from_str(str: &str) -> Literal {
-        if let Ok(v) = str.parse::<i32>() {
-            Literal::Integer(v)
-        } else if let Ok(v) = str.parse::<f64>() {
-            Literal::Rational(v)
-        } else {
-            Literal::Rational(f64::NAN)
+        match JsValue::number_from_str(str) {
+            JsValue::Integer(v) => Literal::Integer(v),
+            value => Literal::Rational(value.as_f64()),
         }
     }
 }
 
 impl ASTNode for Literal {
-    fn eval(&mut self, context: &mut Context) -> Result<JsValue> {
+    fn eval(&self, _context: &mut Context) -> Result<JsValue> {
         Ok(match self {
             Literal::Null => JsValue::null(),
             Literal::Boolean(b) => JsValue::bool(*b),
             Literal::Integer(v) => JsValue::integer(*v),
             Literal::Rational(v) => JsValue::rational(*v),
-            Literal::BigInt(_) => todo!(),
+            Literal::BigInt(v) => JsValue::BigInt(v.clone()),
             Literal::String(s) => JsValue::string(s.as_ref()),
         })
     }
 }
 
-#[derive(Debug, PartialEq)]
+/// `/pattern/flags`, compiled by the parser, each evaluation creates a new object
+#[derive(Debug)]
+pub struct RegExpLiteral {
+    pub(crate) regex: Rc<Regex>,
+}
+
+impl RegExpLiteral {
+    pub fn new(regex: Regex) -> RegExpLiteral {
+        RegExpLiteral {
+            regex: Rc::new(regex),
+        }
+    }
+}
+
+impl ASTNode for RegExpLiteral {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        let regexp = JsRegExp::create(context, self.regex.clone());
+        Ok(JsValue::object(regexp))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum BitwiseOp {
     Or,
     And,
@@ -89,7 +154,7 @@ pub enum BitwiseOp {
     UnsignedShiftRight,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum AssignmentOp {
     Assignment,
 
@@ -112,7 +177,7 @@ pub enum AssignmentOp {
     BoolOrAssignment,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum NumericOp {
     Addition,
     Subtraction,
@@ -122,7 +187,7 @@ pub enum NumericOp {
     Exponent,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CompareOp {
     Equal,
     NotEqual,
@@ -150,9 +215,9 @@ pub enum BinaryOp {
 
 #[derive(Debug)]
 pub struct BinaryOperation {
-    op: BinaryOp,
-    lhs: Box<Expression>,
-    rhs: Box<Expression>,
+    pub(crate) op: BinaryOp,
+    pub(crate) lhs: Box<Expression>,
+    pub(crate) rhs: Box<Expression>,
 }
 
 impl BinaryOperation {
@@ -182,71 +247,95 @@ impl BinaryOperation {
 }
 
 impl BinaryOperation {
-    pub fn do_numeric_op(op: &NumericOp, left_value: JsValue, right_value: JsValue) -> JsValue {
-        let left_value = left_value.to_number();
-        let right_value = right_value.to_number();
+    pub fn do_numeric_op(
+        op: &NumericOp,
+        left_value: JsValue,
+        right_value: JsValue,
+        context: &mut Context,
+    ) -> Result<JsValue> {
+        let left_value = left_value.to_primitive_with(context, PreferredType::None)?;
+        let right_value = right_value.to_primitive_with(context, PreferredType::None)?;
 
-        if let JsValue::Integer(left) = left_value {
-            if let JsValue::Integer(right) = right_value {
-                return match op {
-                    NumericOp::Addition => JsValue::Integer(left + right),
-                    NumericOp::Subtraction => JsValue::Integer(left - right),
-                    NumericOp::Multiplication => JsValue::Integer(left * right),
-                    NumericOp::Modulo => JsValue::Integer(left % right),
-                    NumericOp::Division => {
-                        let result = left / right;
-                        if result * right == left {
-                            JsValue::Integer(result)
-                        } else {
-                            JsValue::Rational((left as f64) / (right as f64))
-                        }
-                    }
-                    NumericOp::Exponent => {
-                        if right >= 0 {
-                            JsValue::Integer(left.pow(right as u32))
-                        } else {
-                            JsValue::Rational((left as f64).powi(right))
-                        }
-                    }
-                };
-            }
+        // `+` concatenate as soon as one side is a string
+        if op == &NumericOp::Addition
+            && (matches!(left_value, JsValue::String(_))
+                || matches!(right_value, JsValue::String(_)))
+        {
+            let mut res = left_value.to_string_with(context)?;
+            res.push_str(&right_value.to_string_with(context)?);
+            return Ok(JsValue::string(res.as_ref()));
         }
 
+        let left_value = left_value.to_numeric_with(context)?;
+        let right_value = right_value.to_numeric_with(context)?;
         match (&left_value, &right_value) {
-            (JsValue::Integer(_), JsValue::Rational(_))
-            | (JsValue::Rational(_), JsValue::Integer(_))
-            | (JsValue::Rational(_), JsValue::Rational(_)) => {
-                let left = left_value.as_f64();
-                let right = right_value.as_f64();
-
-                return match op {
-                    NumericOp::Addition => JsValue::Rational(left + right),
-                    NumericOp::Subtraction => JsValue::Rational(left - right),
-                    NumericOp::Multiplication => JsValue::Rational(left * right),
-                    NumericOp::Modulo => JsValue::Rational(left % right),
-                    NumericOp::Division => JsValue::Rational(left / right),
-                    NumericOp::Exponent => JsValue::Rational(left.powf(right)),
-                };
+            (JsValue::BigInt(left), JsValue::BigInt(right)) => {
+                return JsBigInt::numeric_op(context, op, left, right)
+            }
+            (JsValue::BigInt(_), _) | (_, JsValue::BigInt(_)) => {
+                return Err(Self::mixed_bigint_error(context))
             }
             _ => {}
         }
 
-        if op == &NumericOp::Addition {
-            let mut res = left_value.to_string();
-            res.push_str(&right_value.to_string());
-            return JsValue::string(res.as_ref());
+        if let JsValue::Integer(left) = left_value {
+            if let JsValue::Integer(right) = right_value {
+                // Fall back to floating point on overflow, division by zero and negative zero
+                let result = match op {
+                    NumericOp::Addition => left.checked_add(right),
+                    NumericOp::Subtraction => left.checked_sub(right),
+                    NumericOp::Multiplication => left
+                        .checked_mul(right)
+                        .filter(|r| *r != 0 || (left >= 0 && right >= 0)),
+                    NumericOp::Modulo => left.checked_rem(right).filter(|r| *r != 0 || left >= 0),
+                    NumericOp::Division => left
+                        .checked_div(right)
+                        .filter(|r| r * right == left && (*r != 0 || left >= 0 && right > 0)),
+                    NumericOp::Exponent if right >= 0 => left.checked_pow(right as u32),
+                    NumericOp::Exponent => None,
+                };
+
+                if let Some(result) = result {
+                    return Ok(JsValue::Integer(result));
+                }
+            }
         }
 
-        JsValue::nan()
+        let left = left_value.as_f64();
+        let right = right_value.as_f64();
+
+        Ok(match op {
+            NumericOp::Addition => JsValue::Rational(left + right),
+            NumericOp::Subtraction => JsValue::Rational(left - right),
+            NumericOp::Multiplication => JsValue::Rational(left * right),
+            NumericOp::Modulo => JsValue::Rational(left % right),
+            NumericOp::Division => JsValue::Rational(left / right),
+            NumericOp::Exponent => JsValue::Rational(left.powf(right)),
+        })
     }
 
-    pub fn do_bitwise_op(op: &BitwiseOp, left_value: JsValue, right_value: JsValue) -> JsValue {
-        let left_value = left_value.to_number();
-        let right_value = right_value.to_number();
+    pub fn do_bitwise_op(
+        op: &BitwiseOp,
+        left_value: JsValue,
+        right_value: JsValue,
+        context: &mut Context,
+    ) -> Result<JsValue> {
+        let left_value = left_value.to_numeric_with(context)?;
+        let right_value = right_value.to_numeric_with(context)?;
+        match (&left_value, &right_value) {
+            (JsValue::BigInt(left), JsValue::BigInt(right)) => {
+                return JsBigInt::bitwise_op(context, op, left, right)
+            }
+            (JsValue::BigInt(_), _) | (_, JsValue::BigInt(_)) => {
+                return Err(Self::mixed_bigint_error(context))
+            }
+            _ => {}
+        }
+
         let left = left_value.to_i32();
         let right = right_value.to_i32();
 
-        match op {
+        Ok(match op {
             BitwiseOp::Or => JsValue::Integer(left | right),
             BitwiseOp::And => JsValue::Integer(left & right),
             BitwiseOp::Xor => JsValue::Integer(left ^ right),
@@ -255,6 +344,75 @@ impl BinaryOperation {
             BitwiseOp::UnsignedShiftRight => {
                 JsValue::Integer((left_value.to_u32() >> (right_value.to_u32() % 32)) as i32)
             }
+        })
+    }
+
+    fn mixed_bigint_error(context: &mut Context) -> Completion {
+        let message = "Cannot mix BigInt and other types, use explicit conversions";
+        context.throw_error(ErrorKind::TypeError, message)
+    }
+
+    /// `<`, `undefined` when one of the operands is `NaN`
+    fn less_than(
+        left_value: &JsValue,
+        right_value: &JsValue,
+        context: &mut Context,
+    ) -> Result<Option<bool>> {
+        let left_value = left_value.to_primitive_with(context, PreferredType::Number)?;
+        let right_value = right_value.to_primitive_with(context, PreferredType::Number)?;
+
+        match (&left_value, &right_value) {
+            (JsValue::String(left), JsValue::String(right)) => {
+                return Ok(Some(left.string.as_ref() < right.string.as_ref()))
+            }
+            (JsValue::BigInt(left), JsValue::String(right)) => {
+                return Ok(JsBigInt::from_string(&right.string).map(|right| *left < right))
+            }
+            (JsValue::String(left), JsValue::BigInt(right)) => {
+                return Ok(JsBigInt::from_string(&left.string).map(|left| left < *right))
+            }
+            _ => {}
+        }
+
+        let left_value = left_value.to_numeric_with(context)?;
+        let right_value = right_value.to_numeric_with(context)?;
+        Ok(match (left_value, right_value) {
+            (JsValue::Integer(left), JsValue::Integer(right)) => Some(left < right),
+            (JsValue::BigInt(left), JsValue::BigInt(right)) => Some(left < right),
+            (JsValue::BigInt(left), right) => {
+                left.compare_number(right.as_f64()).map(|o| o.is_lt())
+            }
+            (left, JsValue::BigInt(right)) => {
+                right.compare_number(left.as_f64()).map(|o| o.is_gt())
+            }
+            (left, right) => left
+                .as_f64()
+                .partial_cmp(&right.as_f64())
+                .map(|o| o.is_lt()),
+        })
+    }
+
+    /// `==`, objects compared with primitives are converted first
+    fn loose_equals(
+        left_value: JsValue,
+        right_value: JsValue,
+        context: &mut Context,
+    ) -> Result<bool> {
+        match (&left_value, &right_value) {
+            (JsValue::Object(_), JsValue::Object(_))
+            | (JsValue::Object(_), JsValue::Undefined | JsValue::Null)
+            | (JsValue::Undefined | JsValue::Null, JsValue::Object(_)) => {
+                Ok(left_value.loose_equals(&right_value))
+            }
+            (JsValue::Object(_), _) => {
+                let left_value = left_value.to_primitive_with(context, PreferredType::None)?;
+                Self::loose_equals(left_value, right_value, context)
+            }
+            (_, JsValue::Object(_)) => {
+                let right_value = right_value.to_primitive_with(context, PreferredType::None)?;
+                Self::loose_equals(left_value, right_value, context)
+            }
+            _ => Ok(left_value.loose_equals(&right_value)),
         }
     }
 
@@ -262,86 +420,207 @@ impl BinaryOperation {
         op: &CompareOp,
         left_value: JsValue,
         right_value: JsValue,
+        context: &mut Context,
+    ) -> Result<JsValue> {
+        Ok(JsValue::Boolean(match op {
+            CompareOp::Equal => Self::loose_equals(left_value, right_value, context)?,
+            CompareOp::NotEqual => !Self::loose_equals(left_value, right_value, context)?,
+            CompareOp::StrictEqual => left_value.strict_equals(&right_value),
+            CompareOp::StrictNotEqual => !left_value.strict_equals(&right_value),
+            CompareOp::LessThan => {
+                Self::less_than(&left_value, &right_value, context)? == Some(true)
+            }
+            CompareOp::GreaterThan => {
+                Self::less_than(&right_value, &left_value, context)? == Some(true)
+            }
+            CompareOp::LessThanOrEqual => {
+                Self::less_than(&right_value, &left_value, context)? == Some(false)
+            }
+            CompareOp::GreaterThanOrEqual => {
+                Self::less_than(&left_value, &right_value, context)? == Some(false)
+            }
+            CompareOp::In => match &right_value {
+                JsValue::Object(obj) => obj.has_property(&PropertyKey::from_value(&left_value)),
+                _ => {
+                    let message = format!(
+                        "Cannot use 'in' operator to search for '{}' in {}",
+                        left_value.to_string(),
+                        right_value.to_string()
+                    );
+                    return Err(context.throw_error(ErrorKind::TypeError, &message));
+                }
+            },
+            CompareOp::InstanceOf => Self::instance_of(&left_value, &right_value, context)?,
+        }))
+    }
+
+    /// `InstanceofOperator`, `Symbol.hasInstance` can replace the prototype chain lookup
+    fn instance_of(value: &JsValue, target: &JsValue, context: &mut Context) -> Result<bool> {
+        if !matches!(target, JsValue::Object(_)) {
+            let message = "Right-hand side of 'instanceof' is not an object";
+            return Err(context.throw_error(ErrorKind::TypeError, message));
+        }
+
+        let key = PropertyKey::from(context.well_known_symbols().has_instance.clone());
+        let has_instance = target.get_property(context, &key)?;
+        if !has_instance.is_undefined() && !has_instance.is_null() {
+            let result = context.call(&has_instance, target.clone(), vec![value.clone()])?;
+            return Ok(result.to_boolean());
+        }
+
+        match target {
+            JsValue::Object(constructor) if constructor.is_function() => {
+                Self::ordinary_has_instance(constructor, value, context)
+            }
+            _ => Err(context.throw_error(
+                ErrorKind::TypeError,
+                "Right-hand side of 'instanceof' is not callable",
+            )),
+        }
+    }
+
+    /// Whether `constructor.prototype` is in the prototype chain of `value`
+    pub(crate) fn ordinary_has_instance(
+        constructor: &gc::GcPointer<JsObject>,
+        value: &JsValue,
+        context: &mut Context,
+    ) -> Result<bool> {
+        let mut obj = match value {
+            JsValue::Object(obj) => obj.get_prototype_of(),
+            _ => return Ok(false),
+        };
+        let receiver = JsValue::object(constructor.clone());
+        let prototype = match constructor.get(context, &PropertyKey::from("prototype"), receiver)? {
+            JsValue::Object(prototype) => prototype,
+            prototype => {
+                let message = format!(
+                    "Function has non-object prototype '{}' in instanceof check",
+                    prototype.to_string()
+                );
+                return Err(context.throw_error(ErrorKind::TypeError, &message));
+            }
+        };
+
+        while let Some(current) = obj {
+            if current.ptr_eq(&prototype) {
+                return Ok(true);
+            }
+            obj = current.get_prototype_of();
+        }
+        Ok(false)
+    }
+
+    /// Value stored by `left op= right`
+    pub fn do_assignment_value(
+        op: &AssignmentOp,
+        left_value: JsValue,
+        right_value: JsValue,
+        context: &mut Context,
     ) -> Result<JsValue> {
-        todo!()
+        Ok(match op {
+            AssignmentOp::Assignment => right_value,
+            AssignmentOp::AdditionAssignment => {
+                Self::do_numeric_op(&NumericOp::Addition, left_value, right_value, context)?
+            }
+            AssignmentOp::SubtractionAssignment => {
+                Self::do_numeric_op(&NumericOp::Subtraction, left_value, right_value, context)?
+            }
+            AssignmentOp::MultiplicationAssignment => {
+                Self::do_numeric_op(&NumericOp::Multiplication, left_value, right_value, context)?
+            }
+            AssignmentOp::DivisionAssignment => {
+                Self::do_numeric_op(&NumericOp::Division, left_value, right_value, context)?
+            }
+            AssignmentOp::ModuloAssignment => {
+                Self::do_numeric_op(&NumericOp::Modulo, left_value, right_value, context)?
+            }
+            AssignmentOp::ExponentAssignment => {
+                Self::do_numeric_op(&NumericOp::Exponent, left_value, right_value, context)?
+            }
+            AssignmentOp::BitAndAssignment => {
+                Self::do_bitwise_op(&BitwiseOp::And, left_value, right_value, context)?
+            }
+            AssignmentOp::BitOrAssignment => {
+                Self::do_bitwise_op(&BitwiseOp::Or, left_value, right_value, context)?
+            }
+            AssignmentOp::BitXorAssignment => {
+                Self::do_bitwise_op(&BitwiseOp::Xor, left_value, right_value, context)?
+            }
+            AssignmentOp::ShiftLeftAssignment => {
+                Self::do_bitwise_op(&BitwiseOp::ShiftLeft, left_value, right_value, context)?
+            }
+            AssignmentOp::ShiftRightAssignment => {
+                Self::do_bitwise_op(&BitwiseOp::ShiftRight, left_value, right_value, context)?
+            }
+            AssignmentOp::UnsignedShiftRightAssignment => Self::do_bitwise_op(
+                &BitwiseOp::UnsignedShiftRight,
+                left_value,
+                right_value,
+                context,
+            )?,
+            AssignmentOp::BoolAndAssignment => {
+                JsValue::Boolean(left_value.to_boolean() && right_value.to_boolean())
+            }
+            AssignmentOp::BoolOrAssignment => {
+                JsValue::Boolean(left_value.to_boolean() || right_value.to_boolean())
+            }
+        })
     }
 
-    pub fn do_assignment_op(&mut self, context: &mut Context) -> Result<JsValue> {
+    pub fn do_assignment_op(&self, context: &mut Context) -> Result<JsValue> {
         let op = if let BinaryOp::AssignmentOp(op) = &self.op {
             op
         } else {
             panic!("Called do_assignment_op on non-assignment operation");
         };
 
-        let left_value = self.lhs.eval(context)?;
-        let right_value = self.rhs.eval(context)?;
-        if let Expression::Identifier(ident) = self.lhs.as_ref() {
-            let value = match op {
-                AssignmentOp::Assignment => right_value,
-                AssignmentOp::AdditionAssignment => {
-                    Self::do_numeric_op(&NumericOp::Addition, left_value, right_value)
-                }
-                AssignmentOp::SubtractionAssignment => {
-                    Self::do_numeric_op(&NumericOp::Subtraction, left_value, right_value)
-                }
-                AssignmentOp::MultiplicationAssignment => {
-                    Self::do_numeric_op(&NumericOp::Multiplication, left_value, right_value)
-                }
-                AssignmentOp::DivisionAssignment => {
-                    Self::do_numeric_op(&NumericOp::Division, left_value, right_value)
-                }
-                AssignmentOp::ModuloAssignment => {
-                    Self::do_numeric_op(&NumericOp::Modulo, left_value, right_value)
-                }
-                AssignmentOp::ExponentAssignment => {
-                    Self::do_numeric_op(&NumericOp::Exponent, left_value, right_value)
-                }
-                AssignmentOp::BitAndAssignment => {
-                    Self::do_bitwise_op(&BitwiseOp::And, left_value, right_value)
-                }
-                AssignmentOp::BitOrAssignment => {
-                    Self::do_bitwise_op(&BitwiseOp::Or, left_value, right_value)
-                }
-                AssignmentOp::BitXorAssignment => {
-                    Self::do_bitwise_op(&BitwiseOp::Xor, left_value, right_value)
-                }
-                AssignmentOp::ShiftLeftAssignment => {
-                    Self::do_bitwise_op(&BitwiseOp::ShiftLeft, left_value, right_value)
-                }
-                AssignmentOp::ShiftRightAssignment => {
-                    Self::do_bitwise_op(&BitwiseOp::ShiftRight, left_value, right_value)
-                }
-                AssignmentOp::UnsignedShiftRightAssignment => {
-                    Self::do_bitwise_op(&BitwiseOp::UnsignedShiftRight, left_value, right_value)
-                }
-                AssignmentOp::BoolAndAssignment => {
-                    JsValue::Boolean(left_value.to_boolean() && right_value.to_boolean())
-                }
-                AssignmentOp::BoolOrAssignment => {
-                    JsValue::Boolean(left_value.to_boolean() || right_value.to_boolean())
-                }
-            };
-
-            let gc_value = context.allocate(value.clone());
-            context.set_variable(ident.name().clone(), gc_value);
-            Ok(value)
-        } else {
-            unreachable!()
+        match self.lhs.as_ref() {
+            Expression::Identifier(ident) => {
+                // A plain assignment never read the target, it may not even exist yet
+                let left_value = if op == &AssignmentOp::Assignment {
+                    JsValue::Undefined
+                } else {
+                    ident.eval(context)?
+                };
+                let right_value = self.rhs.eval(context)?;
+                let value = Self::do_assignment_value(op, left_value, right_value, context)?;
+                context.set_variable(ident.name(), value.clone())?;
+                Ok(value)
+            }
+            Expression::MemberExpression(member) => {
+                let (obj, key) = member.reference(context)?;
+                let left_value = if op == &AssignmentOp::Assignment {
+                    JsValue::Undefined
+                } else {
+                    obj.get_property(context, &key)?
+                };
+                let right_value = self.rhs.eval(context)?;
+                let value = Self::do_assignment_value(op, left_value, right_value, context)?;
+                obj.set_property(context, key, value.clone())?;
+                Ok(value)
+            }
+            _ => unreachable!(),
         }
     }
 }
 
 impl ASTNode for BinaryOperation {
-    fn eval(&mut self, context: &mut Context) -> Result<JsValue> {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
         Ok(match &self.op {
             BinaryOp::NumericOp(_) | BinaryOp::BitwiseOp(_) | BinaryOp::CompareOp(_) => {
                 let left_value = self.lhs.eval(context)?;
                 let right_value = self.rhs.eval(context)?;
 
                 match &self.op {
-                    BinaryOp::NumericOp(op) => Self::do_numeric_op(&op, left_value, right_value),
-                    BinaryOp::BitwiseOp(op) => Self::do_bitwise_op(&op, left_value, right_value),
-                    BinaryOp::CompareOp(op) => Self::do_compare_op(&op, left_value, right_value)?,
+                    BinaryOp::NumericOp(op) => {
+                        Self::do_numeric_op(op, left_value, right_value, context)?
+                    }
+                    BinaryOp::BitwiseOp(op) => {
+                        Self::do_bitwise_op(op, left_value, right_value, context)?
+                    }
+                    BinaryOp::CompareOp(op) => {
+                        Self::do_compare_op(op, left_value, right_value, context)?
+                    }
                     _ => unreachable!(),
                 }
             }
@@ -367,7 +646,7 @@ impl ASTNode for BinaryOperation {
 }
 
 #[derive(Debug)]
-pub struct ObjectExpression(Vec<ObjectProperty>);
+pub struct ObjectExpression(pub(crate) Vec<ObjectProperty>);
 
 impl ObjectExpression {
     pub fn new(properties: Vec<ObjectProperty>) -> Self {
@@ -375,80 +654,696 @@ impl ObjectExpression {
     }
 }
 
+impl ObjectExpression {
+    /// Define one property of a literal, getters and setters share the same accessor
+    pub(crate) fn define_property(
+        obj: &mut JsObject,
+        kind: ObjectPropertyKind,
+        key: &JsValue,
+        value: JsValue,
+    ) {
+        let key = PropertyKey::from_value(key);
+        let desc = match kind {
+            ObjectPropertyKind::KeyValue => PropertyDescriptor::data(value, Attributes::all()),
+            ObjectPropertyKind::Getter => PropertyDescriptor {
+                get: Some(value),
+                enumerable: Some(true),
+                configurable: Some(true),
+                ..Default::default()
+            },
+            ObjectPropertyKind::Setter => PropertyDescriptor {
+                set: Some(value),
+                enumerable: Some(true),
+                configurable: Some(true),
+                ..Default::default()
+            },
+            ObjectPropertyKind::Spread => unreachable!("spread properties have no key"),
+        };
+        obj.define_own_property(key, desc);
+    }
+
+    /// `CopyDataProperties`, define the own enumerable properties of `source` on `target`,
+    /// except the `excluded` ones
+    pub(crate) fn copy_data_properties(
+        context: &mut Context,
+        target: &mut gc::GcPointer<JsObject>,
+        source: &JsValue,
+        excluded: &[PropertyKey],
+    ) -> Result<()> {
+        let source = match source {
+            JsValue::Undefined | JsValue::Null => return Ok(()),
+            source => source.to_object(context)?,
+        };
+
+        for key in source.own_property_keys() {
+            let enumerable = source
+                .get_own_property(&key)
+                .is_some_and(|desc| desc.enumerable());
+            if !enumerable || excluded.contains(&key) {
+                continue;
+            }
+            let value = source.get(context, &key, JsValue::object(source.clone()))?;
+            target.create_data_property(key, value);
+        }
+        Ok(())
+    }
+
+    /// `__proto__: value`, anything but an object or `null` is ignored
+    pub(crate) fn set_prototype(obj: &mut JsObject, value: JsValue) {
+        match value {
+            JsValue::Object(prototype) => obj.set_prototype_of(Some(prototype)),
+            JsValue::Null => obj.set_prototype_of(None),
+            _ => true,
+        };
+    }
+}
+
 impl ASTNode for ObjectExpression {
-    fn eval(&mut self, context: &mut Context) -> Result<JsValue> {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
         let mut obj = JsObject::new(context);
-        for prop in self.0.iter_mut() {
-            let key = if let Expression::Identifier(ident) = prop.key.as_mut() {
-                JsValue::string(ident.name().as_ref())
-            } else {
-                prop.key.eval(context)?
-            };
+        for prop in self.0.iter() {
+            if prop.kind == ObjectPropertyKind::Spread {
+                let source = prop.key.eval(context)?;
+                Self::copy_data_properties(context, &mut obj, &source, &[])?;
+                continue;
+            }
+            if prop.is_proto_setter() {
+                let value = prop.value.as_ref().unwrap().eval(context)?;
+                Self::set_prototype(&mut obj, value);
+                continue;
+            }
 
-            let value = prop.value.as_mut().unwrap().eval(context)?;
-            let value = context.allocate(value);
-            obj.put_property(key.to_primitive_string(context), value);
+            let key = match prop.key.as_ref() {
+                Expression::Identifier(ident) if !prop.computed => {
+                    JsValue::string(ident.name().as_ref())
+                }
+                key => key.eval(context)?,
+            };
+            // `{ a }` is a shorthand for `{ a: a }`
+            let value = match &prop.value {
+                Some(value) => value.eval(context)?,
+                None => prop.key.eval(context)?,
+            };
+            Self::define_property(&mut obj, prop.kind, &key, value);
         }
 
         Ok(JsValue::object(obj))
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ObjectPropertyKind {
     KeyValue,
     Getter,
     Setter,
+    /// `...source`, the key is the spread expression
     Spread,
 }
 
 #[derive(Debug)]
 pub struct ObjectProperty {
-    key: Box<Expression>,
-    value: Option<Box<Expression>>,
-    kind: ObjectPropertyKind,
-    is_method: bool,
+    /// Name, literal or the expression of a computed key
+    pub(crate) key: Box<Expression>,
+    pub(crate) computed: bool,
+    pub(crate) value: Option<Box<Expression>>,
+    pub(crate) kind: ObjectPropertyKind,
+    pub(crate) is_method: bool,
 }
 
 impl ObjectProperty {
     pub fn new(
         key: Expression,
+        computed: bool,
         value: Option<Expression>,
         kind: ObjectPropertyKind,
         is_method: bool,
     ) -> ObjectProperty {
         ObjectProperty {
             key: Box::new(key),
+            computed,
             value: value.map(Box::new),
             kind,
             is_method,
         }
     }
+
+    /// `__proto__: value` set the prototype of the object instead of defining a property,
+    /// `['__proto__']: value` doesn't
+    pub fn is_proto_setter(&self) -> bool {
+        let is_proto = match self.key.as_ref() {
+            _ if self.computed => false,
+            Expression::Identifier(ident) => ident.name() == "__proto__",
+            Expression::Literal(Literal::String(s)) => s == "__proto__",
+            _ => false,
+        };
+        is_proto
+            && self.kind == ObjectPropertyKind::KeyValue
+            && self.value.is_some()
+            && !self.is_method
+    }
 }
 
 impl ASTNode for ObjectProperty {
-    fn eval(&mut self, _context: &mut Context) -> Result<JsValue> {
+    fn eval(&self, _context: &mut Context) -> Result<JsValue> {
         unreachable!()
     }
 }
 
+/// `[a, , ...b]`, `None` is a hole
+#[derive(Debug)]
+pub struct ArrayExpression(pub(crate) Vec<Option<Expression>>);
+
+impl ArrayExpression {
+    pub fn new(elements: Vec<Option<Expression>>) -> Self {
+        Self(elements)
+    }
+
+    /// Add the next element of a literal to `array`
+    pub(crate) fn append(array: &mut JsObject, value: Option<JsValue>) {
+        if let ObjectKind::Array(array) = &mut array.kind {
+            array.push(value);
+        }
+    }
+}
+
+impl ASTNode for ArrayExpression {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        let mut array = JsObject::array(context, Vec::new());
+        for element in self.0.iter() {
+            let value = match element {
+                Some(Expression::SpreadElement(spread)) => {
+                    let iterable = spread.argument.eval(context)?;
+                    for value in SpreadElement::values(context, iterable)? {
+                        Self::append(&mut array, Some(value));
+                    }
+                    continue;
+                }
+                Some(element) => Some(element.eval(context)?),
+                None => None,
+            };
+            Self::append(&mut array, value);
+        }
+
+        Ok(JsValue::object(array))
+    }
+}
+
+/// `object.property` or `object[property]`
+#[derive(Debug)]
+pub struct MemberExpression {
+    pub(crate) object: Box<Expression>,
+    pub(crate) property: Box<Expression>,
+    pub(crate) computed: bool,
+}
+
+impl MemberExpression {
+    pub fn new(object: Expression, property: Expression, computed: bool) -> MemberExpression {
+        MemberExpression {
+            object: Box::new(object),
+            property: Box::new(property),
+            computed,
+        }
+    }
+
+    /// The object and the key, evaluated in this order
+    pub fn reference(&self, context: &mut Context) -> Result<(JsValue, PropertyKey)> {
+        let obj = self.object.eval(context)?;
+        let key = match self.property.as_ref() {
+            Expression::Identifier(ident) if !self.computed => {
+                PropertyKey::from(ident.name().as_ref())
+            }
+            property => PropertyKey::from_value(&property.eval(context)?),
+        };
+        Ok((obj, key))
+    }
+
+    /// `delete obj.key`, false when the property can't be deleted
+    pub fn delete(context: &mut Context, obj: JsValue, key: &PropertyKey) -> Result<JsValue> {
+        match obj {
+            JsValue::Object(mut obj) => Ok(JsValue::Boolean(obj.delete(key))),
+            JsValue::Undefined | JsValue::Null => Err(context.throw_error(
+                ErrorKind::TypeError,
+                "Cannot convert undefined or null to object",
+            )),
+            _ => Ok(JsValue::Boolean(true)),
+        }
+    }
+}
+
+impl ASTNode for MemberExpression {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        let (obj, key) = self.reference(context)?;
+        obj.get_property(context, &key)
+    }
+}
+
 #[derive(Debug)]
 pub struct CallExpression {
-    ident: Box<Expression>,
-    args: Vec<Expression>,
+    pub(crate) ident: Box<Expression>,
+    pub(crate) args: Vec<Expression>,
+    pub(crate) position: Position,
 }
 
 impl CallExpression {
-    pub fn new(ident: Expression, args: Vec<Expression>) -> CallExpression {
+    pub fn new(ident: Expression, args: Vec<Expression>, position: Position) -> CallExpression {
         CallExpression {
             ident: Box::new(ident),
             args,
+            position,
+        }
+    }
+
+    /// `this` and the function called by `callee(...)`, methods are called with the object
+    /// they were read from as `this`
+    pub(crate) fn callee(callee: &Expression, context: &mut Context) -> Result<(JsValue, JsValue)> {
+        match callee {
+            Expression::MemberExpression(member) => {
+                let (obj, key) = member.reference(context)?;
+                let function = obj.get_property(context, &key)?;
+                Ok((obj, function))
+            }
+            Expression::SuperProperty(property) => {
+                let function = property.eval(context)?;
+                Ok((context.this()?, function))
+            }
+            callee => Ok((JsValue::Undefined, callee.eval(context)?)),
         }
     }
 }
 
 impl ASTNode for CallExpression {
-    fn eval(&mut self, context: &mut Context) -> Result<JsValue> {
-        todo!()
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        context.set_position(self.position);
+        let (this, callee) = Self::callee(&self.ident, context)?;
+        let args = Self::arguments(&self.args, context)?;
+
+        // The arguments may have called other functions
+        context.set_position(self.position);
+        context.call(&callee, this, args)
+    }
+}
+
+impl CallExpression {
+    /// Values of the arguments of a call, `new` or `super()`, with the spread ones expanded
+    pub(crate) fn arguments(args: &[Expression], context: &mut Context) -> Result<Vec<JsValue>> {
+        let mut values = Vec::with_capacity(args.len());
+        for arg in args.iter() {
+            match arg {
+                Expression::SpreadElement(spread) => {
+                    let iterable = spread.argument.eval(context)?;
+                    values.extend(SpreadElement::values(context, iterable)?);
+                }
+                arg => values.push(arg.eval(context)?),
+            }
+        }
+        Ok(values)
+    }
+}
+
+/// `new callee(...args)`, the arguments are optional
+#[derive(Debug)]
+pub struct NewExpression {
+    pub(crate) callee: Box<Expression>,
+    pub(crate) args: Vec<Expression>,
+    pub(crate) position: Position,
+}
+
+impl NewExpression {
+    pub fn new(callee: Expression, args: Vec<Expression>, position: Position) -> NewExpression {
+        NewExpression {
+            callee: Box::new(callee),
+            args,
+            position,
+        }
+    }
+}
+
+impl ASTNode for NewExpression {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        context.set_position(self.position);
+        let callee = self.callee.eval(context)?;
+        let args = CallExpression::arguments(&self.args, context)?;
+
+        context.set_position(self.position);
+        context.construct(&callee, args, &callee)
+    }
+}
+
+/// `super(...args)` in the constructor of a derived class
+#[derive(Debug)]
+pub struct SuperCall {
+    pub(crate) args: Vec<Expression>,
+    pub(crate) position: Position,
+}
+
+impl SuperCall {
+    pub fn new(args: Vec<Expression>, position: Position) -> SuperCall {
+        SuperCall { args, position }
+    }
+}
+
+impl ASTNode for SuperCall {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        let args = CallExpression::arguments(&self.args, context)?;
+        context.set_position(self.position);
+        context.super_call(args)
+    }
+}
+
+/// `super.property` or `super[property]` in a method, read from the parent prototype
+#[derive(Debug)]
+pub struct SuperProperty {
+    pub(crate) property: Box<Expression>,
+    pub(crate) computed: bool,
+}
+
+impl SuperProperty {
+    pub fn new(property: Expression, computed: bool) -> SuperProperty {
+        SuperProperty {
+            property: Box::new(property),
+            computed,
+        }
+    }
+}
+
+impl ASTNode for SuperProperty {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        let key = match self.property.as_ref() {
+            Expression::Identifier(ident) if !self.computed => {
+                PropertyKey::from(ident.name().as_ref())
+            }
+            property => PropertyKey::from_value(&property.eval(context)?),
+        };
+        context.super_property(&key)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UnaryOp {
+    Not,
+    Minus,
+    Plus,
+    BitNot,
+    Typeof,
+    Void,
+    Delete,
+}
+
+#[derive(Debug)]
+pub struct UnaryExpression {
+    pub(crate) op: UnaryOp,
+    pub(crate) argument: Box<Expression>,
+}
+
+impl UnaryExpression {
+    pub fn new(op: UnaryOp, argument: Expression) -> UnaryExpression {
+        UnaryExpression {
+            op,
+            argument: Box::new(argument),
+        }
+    }
+}
+
+impl ASTNode for UnaryExpression {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        match (&self.op, self.argument.as_ref()) {
+            // `typeof x` on an undeclared variable doesn't throw
+            (UnaryOp::Typeof, Expression::Identifier(ident))
+                if !context.has_variable(ident.name()) =>
+            {
+                return Ok(JsValue::string("undefined"))
+            }
+            // Variables can't be deleted, only properties
+            (UnaryOp::Delete, Expression::Identifier(_)) => return Ok(JsValue::Boolean(false)),
+            (UnaryOp::Delete, Expression::MemberExpression(member)) => {
+                let (obj, key) = member.reference(context)?;
+                return MemberExpression::delete(context, obj, &key);
+            }
+            _ => {}
+        }
+
+        let value = self.argument.eval(context)?;
+        Self::apply(self.op, value, context)
+    }
+}
+
+impl UnaryExpression {
+    pub fn apply(op: UnaryOp, value: JsValue, context: &mut Context) -> Result<JsValue> {
+        Ok(match op {
+            UnaryOp::Not => JsValue::Boolean(!value.to_boolean()),
+            UnaryOp::Minus => match value.to_numeric_with(context)? {
+                JsValue::Integer(v) if v != 0 && v != i32::MIN => JsValue::Integer(-v),
+                JsValue::BigInt(v) => JsValue::BigInt(v.negate()),
+                number => JsValue::Rational(-number.as_f64()),
+            },
+            // Unlike `-` and `~`, `+` only works on numbers
+            UnaryOp::Plus => match value.to_numeric_with(context)? {
+                JsValue::BigInt(_) => {
+                    let message = "Cannot convert a BigInt value to a number";
+                    return Err(context.throw_error(ErrorKind::TypeError, message));
+                }
+                number => number,
+            },
+            UnaryOp::BitNot => match value.to_numeric_with(context)? {
+                JsValue::BigInt(v) => JsValue::BigInt(v.not()),
+                number => JsValue::Integer(!number.to_i32()),
+            },
+            UnaryOp::Typeof => JsValue::string(value.type_of()),
+            UnaryOp::Void => JsValue::Undefined,
+            UnaryOp::Delete => JsValue::Boolean(true),
+        })
+    }
+}
+
+/// `++x`, `x--` ..., the argument is a variable or a member
+#[derive(Debug)]
+pub struct UpdateExpression {
+    pub(crate) is_increment: bool,
+    pub(crate) is_prefix: bool,
+    pub(crate) argument: Box<Expression>,
+}
+
+impl UpdateExpression {
+    pub fn new(is_increment: bool, is_prefix: bool, argument: Expression) -> UpdateExpression {
+        UpdateExpression {
+            is_increment,
+            is_prefix,
+            argument: Box::new(argument),
+        }
+    }
+
+    /// `value + 1` or `value - 1`, the value went through `ToNumeric` already
+    pub fn update(is_increment: bool, value: JsValue, context: &mut Context) -> Result<JsValue> {
+        let op = if is_increment {
+            NumericOp::Addition
+        } else {
+            NumericOp::Subtraction
+        };
+        let one = if value.is_bigint() {
+            JsValue::BigInt(JsBigInt::from(1))
+        } else {
+            JsValue::Integer(1)
+        };
+        BinaryOperation::do_numeric_op(&op, value, one, context)
+    }
+}
+
+impl ASTNode for UpdateExpression {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        let (old_value, new_value) = match self.argument.as_ref() {
+            Expression::MemberExpression(member) => {
+                let (obj, key) = member.reference(context)?;
+                let old_value = obj.get_property(context, &key)?;
+                let old_value = old_value.to_numeric_with(context)?;
+                let new_value = Self::update(self.is_increment, old_value.clone(), context)?;
+                obj.set_property(context, key, new_value.clone())?;
+                (old_value, new_value)
+            }
+            Expression::Identifier(ident) => {
+                let old_value = context.get_variable(ident.name())?;
+                let old_value = old_value.to_numeric_with(context)?;
+                let new_value = Self::update(self.is_increment, old_value.clone(), context)?;
+                context.set_variable(ident.name(), new_value.clone())?;
+                (old_value, new_value)
+            }
+            _ => unreachable!(),
+        };
+
+        Ok(if self.is_prefix { new_value } else { old_value })
+    }
+}
+
+/// `test ? consequent : alternate`
+#[derive(Debug)]
+pub struct ConditionalExpression {
+    pub(crate) test: Box<Expression>,
+    pub(crate) consequent: Box<Expression>,
+    pub(crate) alternate: Box<Expression>,
+}
+
+impl ConditionalExpression {
+    pub fn new(
+        test: Expression,
+        consequent: Expression,
+        alternate: Expression,
+    ) -> ConditionalExpression {
+        ConditionalExpression {
+            test: Box::new(test),
+            consequent: Box::new(consequent),
+            alternate: Box::new(alternate),
+        }
+    }
+}
+
+impl ASTNode for ConditionalExpression {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        if self.test.eval(context)?.to_boolean() {
+            self.consequent.eval(context)
+        } else {
+            self.alternate.eval(context)
+        }
+    }
+}
+
+/// `...iterable` in the arguments of a call or an array literal
+#[derive(Debug)]
+pub struct SpreadElement {
+    pub(crate) argument: Box<Expression>,
+}
+
+impl SpreadElement {
+    pub fn new(argument: Expression) -> SpreadElement {
+        SpreadElement {
+            argument: Box::new(argument),
+        }
+    }
+
+    /// Everything `iterable` iterates over
+    pub(crate) fn values(context: &mut Context, iterable: JsValue) -> Result<Vec<JsValue>> {
+        let mut iterator = IteratorRecord::from_iterable(context, &iterable)?;
+        let mut values = Vec::new();
+        while let Some(value) = iterator.step(context)? {
+            values.push(value);
+        }
+        Ok(values)
+    }
+}
+
+impl ASTNode for SpreadElement {
+    fn eval(&self, _context: &mut Context) -> Result<JsValue> {
+        unreachable!("spread outside of a call or an array literal")
+    }
+}
+
+/// Strings of a template, `raw` keeps the escapes as they were written
+#[derive(Debug, Clone)]
+pub struct TemplateStrings {
+    /// Identifies the template among those of every script, they have one template object each
+    pub(crate) site: usize,
+    /// `None` for a string with an invalid escape, which only tagged templates can have
+    pub(crate) cooked: Vec<Option<String>>,
+    pub(crate) raw: Vec<String>,
+}
+
+impl TemplateStrings {
+    pub fn new(cooked: Vec<Option<String>>, raw: Vec<String>) -> TemplateStrings {
+        static NEXT_SITE: AtomicUsize = AtomicUsize::new(0);
+        TemplateStrings {
+            site: NEXT_SITE.fetch_add(1, Ordering::Relaxed),
+            cooked,
+            raw,
+        }
+    }
+
+    /// `GetTemplateObject`, first argument of a tag, the cooked strings with the raw ones in a
+    /// `raw` property.
+    ///
+    /// It is created frozen on the first evaluation, later ones get the same object
+    pub(crate) fn template_object(&self, context: &mut Context) -> JsValue {
+        if let Some(template) = context.template_object(self.site) {
+            return JsValue::object(template);
+        }
+
+        let raw = self
+            .raw
+            .iter()
+            .map(|s| JsValue::string(s.as_ref()))
+            .collect();
+        let cooked = self
+            .cooked
+            .iter()
+            .map(|s| {
+                s.as_ref()
+                    .map_or(JsValue::Undefined, |s| JsValue::string(s.as_ref()))
+            })
+            .collect();
+        let mut raw = JsObject::array(context, raw);
+        raw.set_integrity_level(true);
+        let mut strings = JsObject::array(context, cooked);
+        strings.define_property("raw", JsValue::object(raw), Attributes::empty());
+        strings.set_integrity_level(true);
+        context.add_template_object(self.site, strings.clone());
+        JsValue::object(strings)
+    }
+}
+
+/// `` `a${b}c` ``, there is one more string than substitutions
+#[derive(Debug)]
+pub struct TemplateLiteral {
+    pub(crate) strings: TemplateStrings,
+    pub(crate) substitutions: Vec<Expression>,
+}
+
+impl TemplateLiteral {
+    pub fn new(strings: TemplateStrings, substitutions: Vec<Expression>) -> TemplateLiteral {
+        TemplateLiteral {
+            strings,
+            substitutions,
+        }
+    }
+}
+
+impl ASTNode for TemplateLiteral {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        let cooked = &self.strings.cooked;
+        let mut result = cooked[0].clone().unwrap_or_default();
+        for (substitution, string) in self.substitutions.iter().zip(&cooked[1..]) {
+            result.push_str(&substitution.eval(context)?.to_string_with(context)?);
+            result.push_str(string.as_deref().unwrap_or_default());
+        }
+        Ok(JsValue::string(result.as_ref()))
+    }
+}
+
+/// ``tag`a${b}c` ``, calls `tag` with the strings and the values of the substitutions
+#[derive(Debug)]
+pub struct TaggedTemplate {
+    pub(crate) tag: Box<Expression>,
+    pub(crate) template: TemplateLiteral,
+    pub(crate) position: Position,
+}
+
+impl TaggedTemplate {
+    pub fn new(tag: Expression, template: TemplateLiteral, position: Position) -> TaggedTemplate {
+        TaggedTemplate {
+            tag: Box::new(tag),
+            template,
+            position,
+        }
+    }
+}
+
+impl ASTNode for TaggedTemplate {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        context.set_position(self.position);
+        let (this, callee) = CallExpression::callee(&self.tag, context)?;
+
+        let mut args = vec![self.template.strings.template_object(context)];
+        for substitution in self.template.substitutions.iter() {
+            args.push(substitution.eval(context)?);
+        }
+
+        context.set_position(self.position);
+        context.call(&callee, this, args)
     }
 }
@@ -0,0 +1,270 @@
+use std::{cell::OnceCell, rc::Rc};
+
+use super::*;
+use crate::vm::{CodeBlock, Compiler, EnvironmentRecordKind};
+
+/// `(a, b = 1, ...rest)`, the arguments after the others go to the rest parameter as an array
+#[derive(Debug, Default)]
+pub struct FormalParameters {
+    pub(crate) params: Vec<BindingElement>,
+    pub(crate) rest: Option<BindingPattern>,
+}
+
+impl FormalParameters {
+    pub fn new(params: Vec<BindingElement>, rest: Option<BindingPattern>) -> FormalParameters {
+        FormalParameters { params, rest }
+    }
+
+    /// Number of parameters before the first one with a default value, for `length`
+    pub fn length(&self) -> usize {
+        self.params
+            .iter()
+            .take_while(|param| param.default.is_none())
+            .count()
+    }
+
+    pub fn bound_names(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        for param in self.params.iter() {
+            param.target.bound_names(&mut names);
+        }
+        if let Some(rest) = &self.rest {
+            rest.bound_names(&mut names);
+        }
+        names
+    }
+}
+
+/// What a function is created by, which decides how it binds `this` and if `new` applies to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FunctionKind {
+    #[default]
+    Normal,
+    /// Sees the `this` of its scope
+    Arrow,
+    /// Method, getter or setter of a class or an object literal
+    Method,
+    /// Constructor of a class without `extends`
+    BaseConstructor,
+    /// Constructor of a class with `extends`, `this` is bound by `super()`
+    DerivedConstructor,
+}
+
+/// Parameters and body shared by function declarations and expressions,
+/// function objects keep a reference to it to run the body when called
+#[derive(Debug)]
+pub struct Function {
+    name: Option<Identifier>,
+    params: FormalParameters,
+    body: BlockStatement,
+    kind: FunctionKind,
+    /// `function*`, its calls return a generator running the body
+    is_generator: bool,
+    /// `async function`, its calls return a promise of the result
+    is_async: bool,
+    /// The AST interpreter can't suspend a body, generators and async functions always run
+    /// their compiled code
+    code: OnceCell<Rc<CodeBlock>>,
+}
+
+impl Function {
+    pub fn new(
+        name: Option<Identifier>,
+        params: FormalParameters,
+        body: BlockStatement,
+    ) -> Function {
+        Function::with_kind(name, params, body, FunctionKind::Normal)
+    }
+
+    pub fn with_kind(
+        name: Option<Identifier>,
+        params: FormalParameters,
+        body: BlockStatement,
+        kind: FunctionKind,
+    ) -> Function {
+        Function {
+            name,
+            params,
+            body,
+            kind,
+            is_generator: false,
+            is_async: false,
+            code: OnceCell::new(),
+        }
+    }
+
+    /// Same function, made a generator or an async function
+    pub fn with_flags(self, is_generator: bool, is_async: bool) -> Function {
+        Function {
+            is_generator,
+            is_async,
+            ..self
+        }
+    }
+
+    /// `(params) => body`, a concise body is a block returning the expression
+    pub fn arrow(params: FormalParameters, body: BlockStatement) -> Function {
+        Function::with_kind(None, params, body, FunctionKind::Arrow)
+    }
+
+    pub fn name(&self) -> Option<&Identifier> {
+        self.name.as_ref()
+    }
+
+    pub fn params(&self) -> &FormalParameters {
+        &self.params
+    }
+
+    pub fn body(&self) -> &BlockStatement {
+        &self.body
+    }
+
+    /// Arrow functions don't bind `this`, they see the one of the scope they were created in
+    pub fn is_arrow(&self) -> bool {
+        self.kind == FunctionKind::Arrow
+    }
+
+    pub fn kind(&self) -> FunctionKind {
+        self.kind
+    }
+
+    pub fn is_generator(&self) -> bool {
+        self.is_generator
+    }
+
+    pub fn is_async(&self) -> bool {
+        self.is_async
+    }
+
+    /// Whether the body can stop in the middle, at a `yield` or an `await`
+    pub fn is_suspendable(&self) -> bool {
+        self.is_generator || self.is_async
+    }
+
+    /// The body compiled for the VM, on first use
+    pub fn code(&self, context: &mut Context) -> Result<Rc<CodeBlock>> {
+        if let Some(code) = self.code.get() {
+            return Ok(code.clone());
+        }
+        match Compiler::compile_function(self) {
+            Ok(code) => Ok(self.code.get_or_init(|| Rc::new(code)).clone()),
+            Err(error) => Err(context.throw_error(ErrorKind::SyntaxError, &error.to_string())),
+        }
+    }
+
+    /// Run the body in `context`, which already hold the function environment
+    pub fn call(&self, context: &mut Context, args: Vec<JsValue>) -> Result<JsValue> {
+        for name in self.params.bound_names() {
+            context.declare_variable(name, DeclarationKind::Var)?;
+        }
+
+        let mut args = args.into_iter();
+        for param in self.params.params.iter() {
+            let value = args.next().unwrap_or(JsValue::Undefined);
+            param.bind(context, value, true)?;
+        }
+        if let Some(rest) = &self.params.rest {
+            let rest_args = JsObject::array(context, args.collect());
+            rest.bind(context, JsValue::object(rest_args), true)?;
+        }
+
+        let statements = self.body.statements();
+        instantiate_var_declarations(statements, context)?;
+        instantiate_lexical_declarations(statements, context)?;
+
+        for statement in statements.iter() {
+            match statement.eval(context) {
+                Ok(_) => {}
+                Err(Completion::Return(value)) => return Ok(value),
+                Err(completion) => return Err(completion),
+            }
+        }
+
+        Ok(JsValue::Undefined)
+    }
+}
+
+#[derive(Debug)]
+pub struct FunctionExpression {
+    function: Rc<Function>,
+}
+
+impl FunctionExpression {
+    pub fn new(function: Function) -> FunctionExpression {
+        FunctionExpression {
+            function: Rc::new(function),
+        }
+    }
+
+    pub fn function(&self) -> &Rc<Function> {
+        &self.function
+    }
+}
+
+impl ASTNode for FunctionExpression {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        // A named function expression can refer to itself, from a scope only it can see
+        let name = match self.function.name() {
+            Some(name) => name.name(),
+            None => {
+                let function =
+                    JsObject::function(context, FunctionCode::Ast(self.function.clone()));
+                return Ok(JsValue::object(function));
+            }
+        };
+
+        context.push_environment(EnvironmentRecordKind::Declarative);
+        context.declare_variable(name, DeclarationKind::Const)?;
+        let function = JsValue::object(JsObject::function(
+            context,
+            FunctionCode::Ast(self.function.clone()),
+        ));
+        context.initialize_variable(name, function.clone());
+        context.pop_environment();
+
+        Ok(function)
+    }
+}
+
+/// `yield value` or `yield* iterable` in a generator
+#[derive(Debug)]
+pub struct YieldExpression {
+    pub(crate) argument: Option<Box<Expression>>,
+    /// `yield*`, yields what the iterator of the argument yields
+    pub(crate) delegate: bool,
+}
+
+impl YieldExpression {
+    pub fn new(argument: Option<Expression>, delegate: bool) -> YieldExpression {
+        YieldExpression {
+            argument: argument.map(Box::new),
+            delegate,
+        }
+    }
+}
+
+impl ASTNode for YieldExpression {
+    fn eval(&self, _context: &mut Context) -> Result<JsValue> {
+        unreachable!("generators run compiled")
+    }
+}
+
+/// `await value` in an async function
+#[derive(Debug)]
+pub struct AwaitExpression {
+    pub(crate) argument: Box<Expression>,
+}
+
+impl AwaitExpression {
+    pub fn new(argument: Expression) -> AwaitExpression {
+        AwaitExpression {
+            argument: Box::new(argument),
+        }
+    }
+}
+
+impl ASTNode for AwaitExpression {
+    fn eval(&self, _context: &mut Context) -> Result<JsValue> {
+        unreachable!("async functions run compiled")
+    }
+}
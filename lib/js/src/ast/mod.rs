@@ -1,20 +1,76 @@
 use std::*;
 
-use crate::{jsrt::*, *};
+use crate::{
+    gc::{GcTrace, Trace, Tracer},
+    jsrt::*,
+    *,
+};
 
+pub mod class;
+pub mod control_flow;
+pub mod exception;
 pub mod expression;
+pub mod function;
+pub mod module;
+pub mod pattern;
 pub mod statement;
 
+pub use class::*;
+pub use control_flow::*;
+pub use exception::*;
 pub use expression::*;
+pub use function::*;
+pub use module::*;
+pub use pattern::*;
 pub use statement::*;
 
-#[derive(Debug)]
-pub struct ExecutionError {}
+/// Abrupt completion of a statement, unwound with `?` until something handle it
+#[derive(Debug, GcTrace)]
+pub enum Completion {
+    Return(JsValue),
+    /// `break`, with the label it targets and the value of the statements run before it
+    Break(Option<String>, Option<JsValue>),
+    /// `continue`, with the label of the loop it targets and the value of the statements run
+    /// before it
+    Continue(Option<String>, Option<JsValue>),
+    /// A thrown exception, caught by `try` or reported to the host
+    Throw(JsValue),
+}
+
+impl Completion {
+    /// `UpdateEmpty`, a `break` or `continue` without a value yet takes `value`
+    pub fn update_empty(self, value: &JsValue) -> Completion {
+        match self {
+            Completion::Break(label, None) => Completion::Break(label, Some(value.clone())),
+            Completion::Continue(label, None) => Completion::Continue(label, Some(value.clone())),
+            completion => completion,
+        }
+    }
+}
 
-pub type Result<T> = core::result::Result<T, ExecutionError>;
+pub type Result<T> = core::result::Result<T, Completion>;
+
+/// Line and column of a node in the source, for stack traces
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    pub fn new(line: usize, column: usize) -> Position {
+        Position { line, column }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
 
 pub trait ASTNode: fmt::Debug {
-    fn eval(&mut self, context: &mut Context) -> Result<JsValue>;
+    fn eval(&self, context: &mut Context) -> Result<JsValue>;
 }
 
 #[derive(Debug, Default)]
@@ -40,4 +96,30 @@ impl Program {
     pub fn add_statement(&mut self, statement: Statement) {
         self.statements.push(statement);
     }
+
+    fn run(&self, context: &mut Context) -> Result<JsValue> {
+        instantiate_var_declarations(&self.statements, context)?;
+        instantiate_lexical_declarations(&self.statements, context)?;
+
+        let mut value = JsValue::Undefined;
+        for statement in self.statements.iter() {
+            let result = statement.eval(context)?;
+            if statement.has_completion_value() {
+                value = result;
+            }
+        }
+
+        Ok(value)
+    }
+}
+
+impl ASTNode for Program {
+    /// Run the script in the global scope, the result is the value of the last statement
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        context.scope(|context| {
+            let result = self.run(context);
+            context.end_job();
+            result
+        })
+    }
 }
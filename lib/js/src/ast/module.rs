@@ -0,0 +1,74 @@
+use super::Program;
+
+/// What an `import` or an indirect `export` takes from the module it requests
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportName {
+    Name(String),
+    /// `import * as ns` or `export * as ns`, the namespace object of the module
+    Namespace,
+    /// `export *`, every name the module exports except `default`
+    All,
+}
+
+/// `ImportEntry` record, one per binding an `import` declaration creates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportEntry {
+    pub module_request: String,
+    pub import_name: ImportName,
+    pub local_name: String,
+}
+
+/// `ExportEntry` record, `export * from` has no export name and local exports no request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportEntry {
+    pub export_name: Option<String>,
+    pub module_request: Option<String>,
+    pub import_name: Option<ImportName>,
+    pub local_name: Option<String>,
+}
+
+impl ExportEntry {
+    /// `export { local as name }` or an exported declaration
+    pub fn local(export_name: &str, local_name: &str) -> ExportEntry {
+        ExportEntry {
+            export_name: Some(export_name.to_string()),
+            module_request: None,
+            import_name: None,
+            local_name: Some(local_name.to_string()),
+        }
+    }
+}
+
+/// Source text of a module, its declarations are in the body without their `export`
+#[derive(Debug, Default)]
+pub struct Module {
+    pub(crate) body: Program,
+    /// Specifiers of the modules it imports from, in the order they appear
+    pub(crate) requests: Vec<String>,
+    pub(crate) imports: Vec<ImportEntry>,
+    pub(crate) exports: Vec<ExportEntry>,
+}
+
+impl Module {
+    pub fn body(&self) -> &Program {
+        &self.body
+    }
+
+    pub fn requests(&self) -> &[String] {
+        &self.requests
+    }
+
+    pub fn imports(&self) -> &[ImportEntry] {
+        &self.imports
+    }
+
+    pub fn exports(&self) -> &[ExportEntry] {
+        &self.exports
+    }
+
+    pub(crate) fn add_request(&mut self, specifier: &str) {
+        if !self.requests.iter().any(|request| request == specifier) {
+            self.requests.push(specifier.to_string());
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use super::*;
+
+/// Target of a declaration or a parameter, `x`, `{ a, b: [c] }` or `[a, , ...b]`
+#[derive(Debug)]
+pub enum BindingPattern {
+    Identifier(Identifier),
+    Object(ObjectPattern),
+    Array(ArrayPattern),
+}
+
+impl BindingPattern {
+    /// Collect the names of the variables it binds
+    pub fn bound_names<'a>(&'a self, names: &mut Vec<&'a str>) {
+        match self {
+            BindingPattern::Identifier(id) => names.push(id.name()),
+            BindingPattern::Object(pattern) => {
+                for property in pattern.properties.iter() {
+                    property.value.target.bound_names(names);
+                }
+                if let Some(rest) = &pattern.rest {
+                    rest.bound_names(names);
+                }
+            }
+            BindingPattern::Array(pattern) => {
+                for element in pattern.elements.iter().flatten() {
+                    element.target.bound_names(names);
+                }
+                if let Some(rest) = &pattern.rest {
+                    rest.bound_names(names);
+                }
+            }
+        }
+    }
+
+    /// Store the parts of `value` in the variables, initializing them when they are declared
+    /// in the current scope or assigning them like `var` does otherwise
+    pub fn bind(&self, context: &mut Context, value: JsValue, initialize: bool) -> Result<()> {
+        match self {
+            BindingPattern::Identifier(id) if initialize => {
+                context.initialize_variable(id.name(), value);
+                Ok(())
+            }
+            BindingPattern::Identifier(id) => context.set_variable(id.name(), value),
+            BindingPattern::Object(pattern) => pattern.bind(context, value, initialize),
+            BindingPattern::Array(pattern) => pattern.bind(context, value, initialize),
+        }
+    }
+
+    /// `undefined` and `null` have no properties to destructure
+    pub(crate) fn require_object_coercible(context: &mut Context, value: &JsValue) -> Result<()> {
+        match value {
+            JsValue::Undefined | JsValue::Null => {
+                let value = value.to_string();
+                let message = format!("Cannot destructure '{}' as it is {}.", value, value);
+                Err(context.throw_error(ErrorKind::TypeError, &message))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// A pattern with the value it takes when the destructured one is `undefined`
+#[derive(Debug)]
+pub struct BindingElement {
+    pub(crate) target: BindingPattern,
+    pub(crate) default: Option<Expression>,
+}
+
+impl BindingElement {
+    pub fn new(target: BindingPattern, default: Option<Expression>) -> BindingElement {
+        BindingElement { target, default }
+    }
+
+    pub fn bind(&self, context: &mut Context, value: JsValue, initialize: bool) -> Result<()> {
+        let value = match &self.default {
+            Some(default) if value.is_undefined() => default.eval(context)?,
+            _ => value,
+        };
+        self.target.bind(context, value, initialize)
+    }
+}
+
+/// `key: element`, the key is a name, a string or a number like in object literals
+#[derive(Debug)]
+pub struct BindingProperty {
+    pub(crate) key: Expression,
+    pub(crate) value: BindingElement,
+}
+
+impl BindingProperty {
+    pub fn new(key: Expression, value: BindingElement) -> BindingProperty {
+        BindingProperty { key, value }
+    }
+
+    pub(crate) fn property_key(&self, context: &mut Context) -> Result<PropertyKey> {
+        Ok(match &self.key {
+            Expression::Identifier(ident) => PropertyKey::from(ident.name().as_ref()),
+            key => PropertyKey::from_value(&key.eval(context)?),
+        })
+    }
+}
+
+/// `{ a, b: c = 1, ...rest }`, the rest gets the properties the others didn't take
+#[derive(Debug)]
+pub struct ObjectPattern {
+    pub(crate) properties: Vec<BindingProperty>,
+    pub(crate) rest: Option<Box<BindingPattern>>,
+}
+
+impl ObjectPattern {
+    pub fn new(properties: Vec<BindingProperty>, rest: Option<BindingPattern>) -> ObjectPattern {
+        ObjectPattern {
+            properties,
+            rest: rest.map(Box::new),
+        }
+    }
+
+    fn bind(&self, context: &mut Context, value: JsValue, initialize: bool) -> Result<()> {
+        BindingPattern::require_object_coercible(context, &value)?;
+
+        let mut excluded = Vec::with_capacity(self.properties.len());
+        for property in self.properties.iter() {
+            let key = property.property_key(context)?;
+            let property_value = value.get_property(context, &key)?;
+            property.value.bind(context, property_value, initialize)?;
+            excluded.push(key);
+        }
+
+        if let Some(rest) = &self.rest {
+            let mut obj = JsObject::new(context);
+            ObjectExpression::copy_data_properties(context, &mut obj, &value, &excluded)?;
+            rest.bind(context, JsValue::object(obj), initialize)?;
+        }
+        Ok(())
+    }
+}
+
+/// `[a, , b = 1, ...rest]`, destructure any iterable, `None` skips a value
+#[derive(Debug)]
+pub struct ArrayPattern {
+    pub(crate) elements: Vec<Option<BindingElement>>,
+    pub(crate) rest: Option<Box<BindingPattern>>,
+}
+
+impl ArrayPattern {
+    pub fn new(
+        elements: Vec<Option<BindingElement>>,
+        rest: Option<BindingPattern>,
+    ) -> ArrayPattern {
+        ArrayPattern {
+            elements,
+            rest: rest.map(Box::new),
+        }
+    }
+
+    fn bind(&self, context: &mut Context, value: JsValue, initialize: bool) -> Result<()> {
+        let mut iterator = ForInIterator::new(ForInKind::Of, context, value)?;
+        let result = self.bind_elements(context, &mut iterator, initialize);
+        iterator.close(context, result)
+    }
+
+    fn bind_elements(
+        &self,
+        context: &mut Context,
+        iterator: &mut ForInIterator,
+        initialize: bool,
+    ) -> Result<()> {
+        for element in self.elements.iter() {
+            let value = iterator.next(context)?.unwrap_or(JsValue::Undefined);
+            if let Some(element) = element {
+                element.bind(context, value, initialize)?;
+            }
+        }
+
+        if let Some(rest) = &self.rest {
+            let mut values = Vec::new();
+            while let Some(value) = iterator.next(context)? {
+                values.push(value);
+            }
+            let array = JsObject::array(context, values);
+            rest.bind(context, JsValue::object(array), initialize)?;
+        }
+        Ok(())
+    }
+}
@@ -1,25 +1,184 @@
+use std::rc::Rc;
+
 use super::*;
+use crate::vm::EnvironmentRecordKind;
 
 #[derive(Debug)]
 pub enum Statement {
     ExpressionStatement(Expression),
     VariableDeclaration(VariableDeclaration),
     FunctionDeclaration(FunctionDeclaration),
-    ReturnStatement(Expression),
+    ClassDeclaration(ClassDeclaration),
+    ReturnStatement(Option<Expression>),
+    BlockStatement(BlockStatement),
+    IfStatement(IfStatement),
+    WhileStatement(WhileStatement),
+    ForStatement(ForStatement),
+    ForInStatement(ForInStatement),
+    SwitchStatement(SwitchStatement),
+    LabelledStatement(LabelledStatement),
+    TryStatement(TryStatement),
+    ThrowStatement(ThrowStatement),
+    BreakStatement(Option<Identifier>),
+    ContinueStatement(Option<Identifier>),
+    EmptyStatement,
+    DebuggerStatement,
 }
 
 impl ASTNode for Statement {
-    fn eval(&mut self, context: &mut Context) -> Result<JsValue> {
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
         match self {
             Statement::ExpressionStatement(expr) => expr.eval(context),
             Statement::VariableDeclaration(vd) => vd.eval(context),
             Statement::FunctionDeclaration(fd) => fd.eval(context),
-            Statement::ReturnStatement(expr) => expr.eval(context),
+            Statement::ClassDeclaration(cd) => cd.eval(context),
+            Statement::ReturnStatement(expr) => {
+                let value = match expr {
+                    Some(expr) => expr.eval(context)?,
+                    None => JsValue::Undefined,
+                };
+                Err(Completion::Return(value))
+            }
+            Statement::BlockStatement(block) => block.eval(context),
+            Statement::IfStatement(s) => s.eval(context),
+            Statement::WhileStatement(s) => s.eval(context),
+            Statement::ForStatement(s) => s.eval(context),
+            Statement::ForInStatement(s) => s.eval(context),
+            Statement::SwitchStatement(s) => s.eval(context),
+            Statement::LabelledStatement(s) => s.eval(context),
+            Statement::TryStatement(s) => s.eval(context),
+            Statement::ThrowStatement(s) => s.eval(context),
+            Statement::BreakStatement(label) => Err(Completion::Break(
+                label.as_ref().map(|label| label.name().clone()),
+                None,
+            )),
+            Statement::ContinueStatement(label) => Err(Completion::Continue(
+                label.as_ref().map(|label| label.name().clone()),
+                None,
+            )),
+            Statement::EmptyStatement | Statement::DebuggerStatement => Ok(JsValue::Undefined),
+        }
+    }
+}
+
+impl Statement {
+    /// Collect the names declared with `var` in this statement, without looking into functions
+    pub fn var_declared_names<'a>(&'a self, names: &mut Vec<&'a str>) {
+        match self {
+            Statement::VariableDeclaration(vd) if vd.kind == DeclarationKind::Var => {
+                for (target, _) in vd.declarations.iter() {
+                    target.bound_names(names);
+                }
+            }
+            Statement::BlockStatement(block) => {
+                for statement in block.statements() {
+                    statement.var_declared_names(names);
+                }
+            }
+            Statement::IfStatement(s) => {
+                s.consequent.var_declared_names(names);
+                if let Some(alternate) = &s.alternate {
+                    alternate.var_declared_names(names);
+                }
+            }
+            Statement::WhileStatement(s) => s.body.var_declared_names(names),
+            Statement::ForStatement(s) => {
+                if let Some(init) = &s.init {
+                    init.var_declared_names(names);
+                }
+                s.body.var_declared_names(names);
+            }
+            Statement::ForInStatement(s) => {
+                if let ForBinding::Declaration(DeclarationKind::Var, target) = &s.binding {
+                    target.bound_names(names);
+                }
+                s.body.var_declared_names(names);
+            }
+            Statement::SwitchStatement(s) => {
+                for case in s.cases.iter() {
+                    for statement in case.consequent.iter() {
+                        statement.var_declared_names(names);
+                    }
+                }
+            }
+            Statement::LabelledStatement(s) => s.body.var_declared_names(names),
+            Statement::TryStatement(s) => {
+                let blocks = std::iter::once(&s.block)
+                    .chain(s.handler.as_ref().map(|handler| &handler.body))
+                    .chain(s.finalizer.as_ref());
+                for block in blocks {
+                    for statement in block.statements() {
+                        statement.var_declared_names(names);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Declarations and empty statements don't change the value of a script
+    pub fn has_completion_value(&self) -> bool {
+        !matches!(
+            self,
+            Statement::VariableDeclaration(_)
+                | Statement::FunctionDeclaration(_)
+                | Statement::ClassDeclaration(_)
+                | Statement::EmptyStatement
+                | Statement::DebuggerStatement
+        )
+    }
+}
+
+/// Hoist the `var` declared in a function or script body, they start as `undefined`
+pub(crate) fn instantiate_var_declarations(
+    statements: &[Statement],
+    context: &mut Context,
+) -> Result<()> {
+    let mut names = Vec::new();
+    for statement in statements {
+        statement.var_declared_names(&mut names);
+    }
+
+    for name in names {
+        context.declare_variable(name, DeclarationKind::Var)?;
+    }
+    Ok(())
+}
+
+/// Declare the `let`/`const` and functions of a scope before running it.
+///
+/// `let` and `const` stay uninitialized until their declaration run (the temporal dead zone),
+/// functions are usable from the start of the scope
+pub(crate) fn instantiate_lexical_declarations(
+    statements: &[Statement],
+    context: &mut Context,
+) -> Result<()> {
+    for statement in statements {
+        match statement {
+            Statement::VariableDeclaration(vd) if vd.kind != DeclarationKind::Var => {
+                for name in vd.bound_names() {
+                    context.declare_variable(name, vd.kind)?;
+                }
+            }
+            Statement::FunctionDeclaration(fd) => {
+                let name = fd.function.name().unwrap().name();
+                let function = JsValue::object(JsObject::function(
+                    context,
+                    FunctionCode::Ast(fd.function.clone()),
+                ));
+                context.declare_variable(name, DeclarationKind::Var)?;
+                context.set_variable(name, function)?;
+            }
+            Statement::ClassDeclaration(cd) => {
+                context.declare_variable(cd.name(), DeclarationKind::Let)?;
+            }
+            _ => {}
         }
     }
+    Ok(())
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DeclarationKind {
     Const,
     Let,
@@ -29,15 +188,20 @@ pub enum DeclarationKind {
 #[derive(Debug)]
 pub struct VariableDeclaration {
     pub(crate) kind: DeclarationKind,
-    pub(crate) declarations: Vec<(Identifier, Expression)>,
+    pub(crate) declarations: Vec<(BindingPattern, Option<Expression>)>,
 }
 
 impl ASTNode for VariableDeclaration {
-    fn eval(&mut self, context: &mut Context) -> Result<JsValue> {
-        for (id, init) in self.declarations.iter_mut() {
-            let init_value = init.eval(context)?;
-            let init_value = context.allocate(init_value);
-            context.set_variable(id.name().clone(), init_value);
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        for (target, init) in self.declarations.iter() {
+            let value = match init {
+                Some(init) => init.eval(context)?,
+                // `var x;` keep the current value
+                None if self.kind == DeclarationKind::Var => continue,
+                None => JsValue::Undefined,
+            };
+
+            target.bind(context, value, self.kind != DeclarationKind::Var)?;
         }
 
         Ok(JsValue::Undefined)
@@ -52,8 +216,17 @@ impl VariableDeclaration {
         }
     }
 
-    pub fn add(&mut self, identifier: Identifier, initializer: Expression) {
-        self.declarations.push((identifier, initializer));
+    pub fn add(&mut self, target: BindingPattern, initializer: Option<Expression>) {
+        self.declarations.push((target, initializer));
+    }
+
+    /// Names of the variables it declares
+    pub fn bound_names(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        for (target, _) in self.declarations.iter() {
+            target.bound_names(&mut names);
+        }
+        names
     }
 }
 
@@ -83,39 +256,53 @@ impl BlockStatement {
 }
 
 impl ASTNode for BlockStatement {
-    fn eval(&mut self, context: &mut Context) -> Result<JsValue> {
-        for statement in self.statements.iter_mut() {
-            statement.eval(context)?;
+    fn eval(&self, context: &mut Context) -> Result<JsValue> {
+        context.push_environment(EnvironmentRecordKind::Declarative);
+        let mut result =
+            instantiate_lexical_declarations(&self.statements, context).map(|_| JsValue::Undefined);
+
+        for statement in self.statements.iter() {
+            if result.is_err() {
+                break;
+            }
+
+            match statement.eval(context) {
+                Ok(value) if statement.has_completion_value() => result = Ok(value),
+                Ok(_) => {}
+                Err(completion) => {
+                    let value = result.unwrap_or_default();
+                    result = Err(completion.update_empty(&value));
+                    break;
+                }
+            }
         }
 
-        Ok(JsValue::Undefined)
+        context.pop_environment();
+        result
     }
 }
 
 #[derive(Debug)]
 pub struct FunctionDeclaration {
-    ident: Identifier,
-    params: Vec<Identifier>,
-    body: BlockStatement,
+    function: Rc<Function>,
 }
 
 impl ASTNode for FunctionDeclaration {
-    fn eval(&mut self, context: &mut Context) -> Result<JsValue> {
-        context.set_variable(self.ident.name().clone(), todo!());
+    fn eval(&self, _context: &mut Context) -> Result<JsValue> {
+        // Already created when entering the scope
         Ok(JsValue::Undefined)
     }
 }
 
 impl FunctionDeclaration {
-    pub fn new(
-        ident: Identifier,
-        params: Vec<Identifier>,
-        body: BlockStatement,
-    ) -> FunctionDeclaration {
+    /// `function` has to be named
+    pub fn new(function: Function) -> FunctionDeclaration {
         FunctionDeclaration {
-            ident,
-            params,
-            body,
+            function: Rc::new(function),
         }
     }
+
+    pub fn function(&self) -> &Rc<Function> {
+        &self.function
+    }
 }
@@ -0,0 +1,338 @@
+use std::io::{self, BufRead, Read, Write};
+
+/// Lines kept in the history, the oldest are dropped first
+const MAX_HISTORY: usize = 1000;
+
+/// What reading a line ended with
+pub enum ReadLine {
+    Line(String),
+    /// Ctrl-C, with the text typed so far
+    Interrupted(String),
+    /// Ctrl-D on an empty line or the end of the input
+    Eof,
+}
+
+/// Line editor with a history, input that doesn't come from a terminal is read line by line
+/// without any prompt
+pub struct Editor {
+    interactive: bool,
+    history: Vec<String>,
+}
+
+impl Editor {
+    pub fn new() -> Editor {
+        Editor {
+            interactive: terminal::is_tty(),
+            history: Vec::new(),
+        }
+    }
+
+    pub fn is_interactive(&self) -> bool {
+        self.interactive
+    }
+
+    pub fn add_history(&mut self, line: &str) {
+        if line.trim().is_empty() || self.history.last().map(String::as_str) == Some(line) {
+            return;
+        }
+        if self.history.len() == MAX_HISTORY {
+            self.history.remove(0);
+        }
+        self.history.push(line.to_string());
+    }
+
+    pub fn read_line(&mut self, prompt: &str) -> io::Result<ReadLine> {
+        if !self.interactive {
+            let mut line = String::new();
+            return match io::stdin().lock().read_line(&mut line)? {
+                0 => Ok(ReadLine::Eof),
+                _ => Ok(ReadLine::Line(
+                    line.trim_end_matches(&['\r', '\n'][..]).into(),
+                )),
+            };
+        }
+
+        let _raw_mode = terminal::RawMode::enable()?;
+        let mut line = LineState {
+            prompt,
+            buffer: Vec::new(),
+            cursor: 0,
+            history_index: self.history.len(),
+            edited: Vec::new(),
+        };
+        line.refresh()?;
+
+        let stdin = io::stdin();
+        let mut keys = Keys(stdin.lock());
+        loop {
+            match keys.next()? {
+                Key::Enter => {
+                    print!("\r\n");
+                    io::stdout().flush()?;
+                    return Ok(ReadLine::Line(line.buffer.iter().collect()));
+                }
+                Key::Ctrl('c') => {
+                    print!("^C\r\n");
+                    io::stdout().flush()?;
+                    return Ok(ReadLine::Interrupted(line.buffer.iter().collect()));
+                }
+                Key::Ctrl('d') | Key::Eof if line.buffer.is_empty() => {
+                    print!("\r\n");
+                    io::stdout().flush()?;
+                    return Ok(ReadLine::Eof);
+                }
+                Key::Eof => return Ok(ReadLine::Line(line.buffer.iter().collect())),
+                Key::Ctrl('d') | Key::Delete => line.delete(),
+                Key::Backspace | Key::Ctrl('h') => line.backspace(),
+                Key::Left | Key::Ctrl('b') => line.cursor = line.cursor.saturating_sub(1),
+                Key::Right | Key::Ctrl('f') => {
+                    line.cursor = (line.cursor + 1).min(line.buffer.len())
+                }
+                Key::Home | Key::Ctrl('a') => line.cursor = 0,
+                Key::End | Key::Ctrl('e') => line.cursor = line.buffer.len(),
+                Key::Up | Key::Ctrl('p') => line.history(&self.history, -1),
+                Key::Down | Key::Ctrl('n') => line.history(&self.history, 1),
+                Key::Ctrl('k') => line.buffer.truncate(line.cursor),
+                Key::Ctrl('u') => {
+                    line.buffer.drain(..line.cursor);
+                    line.cursor = 0;
+                }
+                Key::Ctrl('w') => line.delete_word(),
+                Key::Ctrl('l') => print!("\x1b[H\x1b[2J"),
+                Key::Char('\t') => {
+                    line.insert(' ');
+                    line.insert(' ');
+                }
+                Key::Char(c) if !c.is_control() => line.insert(c),
+                _ => continue,
+            }
+            line.refresh()?;
+        }
+    }
+}
+
+/// The line being edited
+struct LineState<'a> {
+    prompt: &'a str,
+    buffer: Vec<char>,
+    cursor: usize,
+    /// Entry of the history shown, its length for the line being edited
+    history_index: usize,
+    /// The line being edited while older ones are shown
+    edited: Vec<char>,
+}
+
+impl LineState<'_> {
+    fn insert(&mut self, c: char) {
+        self.buffer.insert(self.cursor, c);
+        self.cursor += 1;
+    }
+
+    fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.buffer.remove(self.cursor);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.cursor < self.buffer.len() {
+            self.buffer.remove(self.cursor);
+        }
+    }
+
+    /// Delete the word before the cursor and the spaces after it
+    fn delete_word(&mut self) {
+        let mut start = self.cursor;
+        while start > 0 && self.buffer[start - 1].is_whitespace() {
+            start -= 1;
+        }
+        while start > 0 && !self.buffer[start - 1].is_whitespace() {
+            start -= 1;
+        }
+        self.buffer.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    fn history(&mut self, history: &[String], step: isize) {
+        let index = self.history_index as isize + step;
+        if index < 0 || index > history.len() as isize {
+            return;
+        }
+        if self.history_index == history.len() {
+            self.edited = self.buffer.clone();
+        }
+        self.history_index = index as usize;
+        self.buffer = match history.get(self.history_index) {
+            Some(line) => line.chars().collect(),
+            None => self.edited.clone(),
+        };
+        self.cursor = self.buffer.len();
+    }
+
+    /// Draw the prompt and the line again, then move to the cursor
+    fn refresh(&self) -> io::Result<()> {
+        let line: String = self.buffer.iter().collect();
+        let column = self.prompt.chars().count() + self.cursor;
+        let mut stdout = io::stdout();
+        write!(stdout, "\r{}{}\x1b[0K\r", self.prompt, line)?;
+        if column > 0 {
+            write!(stdout, "\x1b[{}C", column)?;
+        }
+        stdout.flush()
+    }
+}
+
+enum Key {
+    Char(char),
+    /// A letter typed with Ctrl
+    Ctrl(char),
+    Enter,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    Unknown,
+    Eof,
+}
+
+/// Keys typed on a terminal in raw mode
+struct Keys<R>(R);
+
+impl<R: Read> Keys<R> {
+    fn byte(&mut self) -> io::Result<Option<u8>> {
+        let mut byte = [0];
+        match self.0.read(&mut byte)? {
+            0 => Ok(None),
+            _ => Ok(Some(byte[0])),
+        }
+    }
+
+    fn next(&mut self) -> io::Result<Key> {
+        let byte = match self.byte()? {
+            Some(byte) => byte,
+            None => return Ok(Key::Eof),
+        };
+        Ok(match byte {
+            b'\r' | b'\n' => Key::Enter,
+            127 => Key::Backspace,
+            0x1b => self.escape()?,
+            b'\t' => Key::Char('\t'),
+            1..=26 => Key::Ctrl((b'a' + byte - 1) as char),
+            0..=0x7f => Key::Char(byte as char),
+            _ => {
+                // Continuation bytes of a UTF-8 sequence
+                let length = match byte {
+                    0xc0..=0xdf => 2,
+                    0xe0..=0xef => 3,
+                    _ => 4,
+                };
+                let mut bytes = vec![byte];
+                for _ in 1..length {
+                    bytes.extend(self.byte()?);
+                }
+                match std::str::from_utf8(&bytes)
+                    .ok()
+                    .and_then(|s| s.chars().next())
+                {
+                    Some(c) => Key::Char(c),
+                    None => Key::Unknown,
+                }
+            }
+        })
+    }
+
+    /// `ESC [ A` or `ESC O A` sequences sent by the arrows, `ESC [ 3 ~` by delete
+    fn escape(&mut self) -> io::Result<Key> {
+        let kind = self.byte()?;
+        if kind != Some(b'[') && kind != Some(b'O') {
+            return Ok(Key::Unknown);
+        }
+        Ok(match self.byte()? {
+            Some(b'A') => Key::Up,
+            Some(b'B') => Key::Down,
+            Some(b'C') => Key::Right,
+            Some(b'D') => Key::Left,
+            Some(b'H') => Key::Home,
+            Some(b'F') => Key::End,
+            Some(digit @ b'0'..=b'9') => {
+                let mut number = vec![digit];
+                loop {
+                    match self.byte()? {
+                        Some(b'~') | None => break,
+                        Some(byte) => number.push(byte),
+                    }
+                }
+                match number.as_slice() {
+                    b"1" | b"7" => Key::Home,
+                    b"4" | b"8" => Key::End,
+                    b"3" => Key::Delete,
+                    _ => Key::Unknown,
+                }
+            }
+            _ => Key::Unknown,
+        })
+    }
+}
+
+#[cfg(unix)]
+mod terminal {
+    use std::{io, mem::MaybeUninit};
+
+    pub fn is_tty() -> bool {
+        unsafe { libc::isatty(libc::STDIN_FILENO) == 1 && libc::isatty(libc::STDOUT_FILENO) == 1 }
+    }
+
+    /// Keys are read as they are typed without being echoed, the previous mode is restored
+    /// when it is dropped
+    pub struct RawMode(libc::termios);
+
+    impl RawMode {
+        pub fn enable() -> io::Result<RawMode> {
+            let mut original = MaybeUninit::uninit();
+            if unsafe { libc::tcgetattr(libc::STDIN_FILENO, original.as_mut_ptr()) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let original = unsafe { original.assume_init() };
+
+            let mut raw = original;
+            raw.c_iflag &= !(libc::BRKINT | libc::ICRNL | libc::INPCK | libc::ISTRIP | libc::IXON);
+            raw.c_oflag &= !libc::OPOST;
+            raw.c_cflag |= libc::CS8;
+            raw.c_lflag &= !(libc::ECHO | libc::ICANON | libc::IEXTEN | libc::ISIG);
+            raw.c_cc[libc::VMIN] = 1;
+            raw.c_cc[libc::VTIME] = 0;
+            if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &raw) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(RawMode(original))
+        }
+    }
+
+    impl Drop for RawMode {
+        fn drop(&mut self) {
+            unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &self.0) };
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod terminal {
+    use std::io;
+
+    pub fn is_tty() -> bool {
+        false
+    }
+
+    pub struct RawMode;
+
+    impl RawMode {
+        pub fn enable() -> io::Result<RawMode> {
+            Ok(RawMode)
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use std::ptr::NonNull;
+
 use super::{Cell, GcCell, GcPointer, SweepType};
 
 #[repr(C)]
@@ -6,24 +8,45 @@ pub struct HeapBlock {
     cell_size: usize,
     free_cell: *mut Cell,
     used_cell: *mut Cell,
+    /// Number of cells in `used_cell`
+    len: usize,
+    /// Number of cells allocated since the last collection
+    young: usize,
     ptr: *mut u8,
 }
 
-pub const BLOCK_SIZE: usize = 4 * 1024;
+/// Size and alignment of blocks, the block of a cell is found by masking its address
+pub const BLOCK_SIZE: usize = 16 * 1024;
+
+/// Alignment of cell sizes, so every cell starts on it
+pub const CELL_ALIGN: usize = 16;
+
+/// Start of the memory of a block, before its cells
+#[repr(C)]
+struct BlockHeader {
+    /// Some old cell of the block is remembered, until the next full collection
+    dirty: bool,
+}
+
+const HEADER_SIZE: usize = CELL_ALIGN;
 
 impl HeapBlock {
     pub fn new(cell_size: usize) -> HeapBlock {
-        debug_assert!(cell_size.is_power_of_two());
-        debug_assert!(BLOCK_SIZE % cell_size == 0);
+        debug_assert!(cell_size.is_multiple_of(CELL_ALIGN));
+        debug_assert!(cell_size <= BLOCK_SIZE - HEADER_SIZE);
+        debug_assert!(std::mem::size_of::<BlockHeader>() <= HEADER_SIZE);
 
-        let layout = std::alloc::Layout::from_size_align(BLOCK_SIZE, cell_size).unwrap();
-        let ptr = unsafe { std::alloc::alloc(layout) } as *mut Cell;
+        let layout = std::alloc::Layout::from_size_align(BLOCK_SIZE, BLOCK_SIZE).unwrap();
+        let ptr = unsafe { std::alloc::alloc(layout) };
+        unsafe { (ptr as *mut BlockHeader).write(BlockHeader { dirty: false }) };
 
         let mut block = HeapBlock {
             cell_size,
             free_cell: std::ptr::null_mut(),
             used_cell: std::ptr::null_mut(),
-            ptr: ptr as *mut u8,
+            len: 0,
+            young: 0,
+            ptr,
         };
 
         block.recycle(cell_size);
@@ -43,10 +66,42 @@ impl HeapBlock {
 
         unsafe { Cell::placement_new(cell_ptr, self.used_cell, data) };
         self.used_cell = cell_ptr;
+        self.len += 1;
+        self.young += 1;
 
         GcPointer::new(cell_ptr)
     }
 
+    /// Record that a cell of the block is remembered
+    ///
+    /// # Safety
+    ///
+    /// `cell` must be in a block
+    pub(crate) unsafe fn mark_dirty(cell: *mut Cell) {
+        let header = (cell as usize & !(BLOCK_SIZE - 1)) as *mut BlockHeader;
+        (*header).dirty = true;
+    }
+
+    fn header(&mut self) -> &mut BlockHeader {
+        unsafe { &mut *(self.ptr as *mut BlockHeader) }
+    }
+
+    /// Pass each remembered cell to `f`, blocks that aren't dirty are skipped
+    pub(crate) fn for_each_remembered(&mut self, mut f: impl FnMut(NonNull<Cell>)) {
+        if !self.header().dirty {
+            return;
+        }
+
+        let mut cell_ptr = self.used_cell;
+        while let Some(cell) = NonNull::new(cell_ptr) {
+            let cell_ref = unsafe { cell.as_ref() };
+            cell_ptr = cell_ref.next;
+            if cell_ref.is_remembered() {
+                f(cell);
+            }
+        }
+    }
+
     pub fn is_empty(&self) -> bool {
         self.used_cell.is_null()
     }
@@ -55,7 +110,15 @@ impl HeapBlock {
         !self.free_cell.is_null()
     }
 
-    pub fn sweep(&mut self, sweep_type: SweepType) {
+    /// Free the unmarked cells, or all of them, returning how many are left.
+    ///
+    /// The cells left are all old afterwards, young collections skip blocks without young cells
+    pub fn sweep(&mut self, sweep_type: SweepType) -> usize {
+        if sweep_type == SweepType::Young && self.young == 0 {
+            return self.len;
+        }
+
+        let mut live = 0;
         let mut prev_ptr = std::ptr::null_mut() as *mut Cell;
         let mut cell_ptr = self.used_cell;
         loop {
@@ -64,12 +127,24 @@ impl HeapBlock {
             }
 
             let cell = unsafe { cell_ptr.as_mut().unwrap() };
-            if !cell.is_marked() || sweep_type == SweepType::Everything {
+            let garbage = match sweep_type {
+                SweepType::Everything => true,
+                SweepType::Garbage => !cell.is_marked(),
+                SweepType::Young => !cell.is_old() && !cell.is_marked(),
+            };
+            if garbage {
                 let next_ptr = cell.next;
 
                 unsafe {
                     // Clean up old cell
                     std::ptr::drop_in_place(cell.get_dyn());
+                    // Reads through a dangling pointer get garbage instead of the old value
+                    #[cfg(debug_assertions)]
+                    std::ptr::write_bytes(
+                        cell.data::<u8>(),
+                        0xdb,
+                        self.cell_size - std::mem::size_of::<Cell>(),
+                    );
                     Cell::init_free(cell_ptr, self.free_cell);
                     self.free_cell = cell_ptr;
                 };
@@ -83,35 +158,65 @@ impl HeapBlock {
                 cell_ptr = next_ptr;
             } else {
                 cell.unmark();
+                cell.promote();
+                // Everything was traced, nothing young is left for the old cells to point to
+                if sweep_type == SweepType::Garbage {
+                    cell.forget();
+                }
+                live += 1;
                 prev_ptr = cell_ptr;
                 cell_ptr = cell.next;
             }
         }
+
+        if sweep_type == SweepType::Garbage {
+            self.header().dirty = false;
+        }
+        self.len = live;
+        self.young = 0;
+        live
     }
 
     pub fn cell_size(&self) -> usize {
         self.cell_size
     }
 
+    /// Number of cells in use
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Number of cells that fit, what is left at the end of the block is never used
+    pub fn capacity(&self) -> usize {
+        (BLOCK_SIZE - HEADER_SIZE) / self.cell_size
+    }
+
     pub fn recycle(&mut self, cell_size: usize) {
         debug_assert!(self.is_empty());
-        debug_assert!(cell_size.is_power_of_two());
-        debug_assert!(BLOCK_SIZE % cell_size == 0);
+        debug_assert!(cell_size.is_multiple_of(CELL_ALIGN));
 
-        let num_cell = BLOCK_SIZE / cell_size;
+        self.cell_size = cell_size;
+        let num_cell = self.capacity();
+        let first = unsafe { self.ptr.add(HEADER_SIZE) } as *mut Cell;
 
-        let mut cell = self.ptr as *mut Cell;
-        for _ in 0..num_cell {
+        let mut cell = first;
+        for i in 0..num_cell {
             unsafe {
-                let next = (cell as *mut u8).add(cell_size) as *mut Cell;
+                // The last cell ends the free list
+                let next = if i + 1 == num_cell {
+                    std::ptr::null_mut()
+                } else {
+                    (cell as *mut u8).add(cell_size) as *mut Cell
+                };
                 Cell::init_free(cell, next);
                 cell = next;
             }
         }
 
-        self.cell_size = cell_size;
-        self.free_cell = self.ptr as *mut Cell;
+        self.free_cell = first;
         self.used_cell = std::ptr::null_mut();
+        self.len = 0;
+        self.young = 0;
     }
 }
 
@@ -124,7 +229,7 @@ impl Drop for HeapBlock {
             )
         }
 
-        let layout = std::alloc::Layout::from_size_align(BLOCK_SIZE, self.cell_size).unwrap();
+        let layout = std::alloc::Layout::from_size_align(BLOCK_SIZE, BLOCK_SIZE).unwrap();
         unsafe { std::alloc::dealloc(self.ptr, layout) }
     }
 }
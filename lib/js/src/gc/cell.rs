@@ -1,6 +1,6 @@
 use std::{any::TypeId, mem::size_of, ptr::DynMetadata};
 
-use super::Trace;
+use super::{HeapBlock, Trace};
 
 #[repr(C)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -20,6 +20,12 @@ pub trait GcCell: Trace + std::any::Any {
 pub struct Cell {
     pub(crate) next: *mut Cell,
     status: CellStatus,
+    /// Survived a collection, minor collections don't look at it
+    old: bool,
+    /// Old and written to since the last full collection, it may point to young cells
+    remembered: bool,
+    /// In the large object space rather than in a block
+    large: bool,
     type_id: TypeId,
     vtable: Option<DynMetadata<dyn GcCell>>,
 }
@@ -29,6 +35,9 @@ impl Cell {
         ptr.write(Cell {
             next,
             status: CellStatus::Unmarked,
+            old: false,
+            remembered: false,
+            large: false,
             type_id: TypeId::of::<()>(),
             vtable: None,
         });
@@ -43,6 +52,9 @@ impl Cell {
         ptr.write(Cell {
             next,
             status: CellStatus::Unmarked,
+            old: false,
+            remembered: false,
+            large: false,
             type_id: TypeId::of::<T>(),
             vtable: Some(vtable),
         });
@@ -81,6 +93,41 @@ impl Cell {
         self.status == CellStatus::Marked
     }
 
+    pub fn is_old(&self) -> bool {
+        self.old
+    }
+
+    pub(crate) fn promote(&mut self) {
+        self.old = true;
+    }
+
+    pub(crate) fn set_large(&mut self) {
+        self.large = true;
+    }
+
+    /// Write barrier, run before the data of the cell is changed.
+    ///
+    /// An old cell may get a pointer to a young one, minor collections trace it until the next
+    /// full one. Forgetting it earlier would miss the writes made through a reference taken
+    /// before a minor collection
+    pub fn write_barrier(&mut self) {
+        if self.old && !self.remembered {
+            self.remembered = true;
+            if !self.large {
+                unsafe { HeapBlock::mark_dirty(self) };
+            }
+        }
+    }
+
+    pub fn is_remembered(&self) -> bool {
+        self.remembered
+    }
+
+    /// Clear the remembered flag, once a full collection has traced the cell
+    pub(crate) fn forget(&mut self) {
+        self.remembered = false;
+    }
+
     pub fn is_free(&self) -> bool {
         self.vtable.is_none()
     }
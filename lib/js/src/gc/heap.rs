@@ -1,78 +1,224 @@
 use std::{
-    collections::{btree_map::Entry, BTreeMap, HashSet},
-    mem::{size_of, size_of_val},
+    collections::{btree_map::Entry, BTreeMap},
+    mem::{align_of, size_of, size_of_val},
 };
 
-use super::{Cell, GcCell, GcPointer, HeapBlock, SweepType, Tracer, BLOCK_SIZE};
+use super::{
+    Cell, GcCell, GcPointer, HeapBlock, LargeObjectSpace, Roots, SweepType, Tracer, BLOCK_SIZE,
+};
+
+/// Heaps smaller than this are never fully collected on their own
+const MIN_THRESHOLD: usize = 256 * 1024;
+
+/// Allocations between two minor collections
+const NURSERY_CELLS: usize = 4096;
+
+/// In stress mode, one collection out of this many is a full one
+const STRESS_FULL_INTERVAL: usize = 16;
+
+/// Cell sizes of the blocks, bigger cells go to the large object space.
+///
+/// Steps of 16 bytes up to 128 and then four sizes per power of two, so at most a quarter of a
+/// cell is wasted
+pub const SIZE_CLASSES: [usize; 22] = [
+    48, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384, 448, 512, 640, 768, 896, 1024, 1280,
+    1536, 1792, 2048,
+];
+
+/// Usage of the blocks of one size class
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeClassStats {
+    pub cell_size: usize,
+    pub blocks: usize,
+    /// Cells in use, garbage included until the next collection
+    pub cells: usize,
+    /// Cells the blocks have room for
+    pub capacity: usize,
+}
+
+impl SizeClassStats {
+    pub fn bytes(&self) -> usize {
+        self.cells * self.cell_size
+    }
+
+    /// Fraction of the cells of the blocks in use
+    pub fn occupancy(&self) -> f64 {
+        self.cells as f64 / self.capacity as f64
+    }
+}
+
+/// What the heap is made of, returned by `Heap::stats`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HeapStats {
+    /// Size classes with at least one block, smallest first
+    pub size_classes: Vec<SizeClassStats>,
+    pub large_objects: usize,
+    pub large_object_bytes: usize,
+    /// Full collections run so far
+    pub collections: usize,
+    /// Minor collections run so far
+    pub young_collections: usize,
+}
+
+impl HeapStats {
+    /// Bytes of the cells in use, like `Heap::size`
+    pub fn bytes(&self) -> usize {
+        self.size_classes
+            .iter()
+            .map(SizeClassStats::bytes)
+            .sum::<usize>()
+            + self.large_object_bytes
+    }
+}
 
 pub struct Heap {
     map_size_to_blocks: BTreeMap<usize, Vec<HeapBlock>>,
-    root_collector: Vec<Box<dyn FnMut(&mut HashSet<*mut dyn GcCell>)>>,
+    large_objects: LargeObjectSpace,
+    roots: Roots,
+    /// Bytes of the cells in use, counted again by each collection
+    size: usize,
+    /// Size the heap can grow to before a full collection
+    threshold: usize,
+    /// Cells allocated since the last collection, all young
+    allocations: usize,
+    /// Collection to run before the next allocation, decided by `allocate`
+    pending: Option<SweepType>,
+    /// Collect before every allocation, to find values that aren't rooted or barriers that are
+    /// missing
+    stress: bool,
+    collections: usize,
+    young_collections: usize,
 }
 
 impl Heap {
-    pub fn new(cell_sizes: &[usize], block_size: usize) -> Heap {
-        debug_assert!(cell_sizes.iter().all(|s| *s > std::mem::size_of::<Cell>()));
-        debug_assert!(cell_sizes.iter().all(|s| s.is_power_of_two()));
-        debug_assert!(cell_sizes.iter().all(|s| block_size % s == 0));
+    /// Empty heap, blocks are added as the size classes are used
+    pub fn new() -> Heap {
+        debug_assert!(SIZE_CLASSES[0] > size_of::<Cell>());
 
-        let mut heap = Heap {
+        Heap {
             map_size_to_blocks: BTreeMap::new(),
-            root_collector: Vec::new(),
-        };
-
-        for cell_size in cell_sizes {
-            heap.allocate_block(*cell_size);
+            large_objects: LargeObjectSpace::default(),
+            roots: Roots::default(),
+            size: 0,
+            threshold: MIN_THRESHOLD,
+            allocations: 0,
+            pending: None,
+            stress: false,
+            collections: 0,
+            young_collections: 0,
         }
+    }
 
-        heap
+    pub fn roots(&self) -> Roots {
+        self.roots.clone()
     }
 
-    pub fn add_root_collector<F>(&mut self, func: F)
-    where
-        F: FnMut(&mut HashSet<*mut dyn GcCell>) + 'static,
-    {
-        self.root_collector.push(Box::new(func));
+    /// Bytes of the cells in use, garbage included until the next collection
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn set_stress(&mut self, stress: bool) {
+        self.stress = stress;
     }
 
+    /// The collection the owner of the heap should run before allocating again, if any
+    pub fn pending_collection(&self) -> Option<SweepType> {
+        self.pending
+    }
+
+    /// Allocate a cell for `obj`, rooted by the innermost scope if there is one
     pub fn allocate<T>(&mut self, obj: T) -> GcPointer<T>
     where
         T: Sized + 'static + GcCell,
     {
-        let cell_size = usize::next_power_of_two(size_of_val(&obj) + size_of::<Cell>());
-        match self.get_block_mut(cell_size) {
-            Some(block) => block.allocate(obj),
-            None => self.allocate_block(cell_size).allocate(obj),
-        }
+        // The data follows the header, cells are aligned enough for it
+        debug_assert!(size_of::<Cell>().is_multiple_of(align_of::<T>()));
+
+        let size = size_of_val(&obj) + size_of::<Cell>();
+        let cell_size = SIZE_CLASSES
+            .get(SIZE_CLASSES.partition_point(|class| *class < size))
+            .copied();
+        let pointer = match cell_size {
+            Some(cell_size) => match self.get_block_mut(cell_size) {
+                Some(block) => block.allocate(obj),
+                None => self.allocate_block(cell_size).allocate(obj),
+            },
+            None => self.large_objects.allocate(obj, size),
+        };
+
+        self.size += cell_size.unwrap_or(size);
+        self.roots.borrow_mut().allocated(pointer.base);
+
+        // Most cells die young, a minor collection frees them without tracing the old ones.
+        // Only a heap many times bigger than the threshold, like one of large objects, goes
+        // straight to a full collection
+        self.allocations += 1;
+        let stress_full =
+            (self.collections + self.young_collections + 1).is_multiple_of(STRESS_FULL_INTERVAL);
+        self.pending = if self.size >= 4 * self.threshold || (self.stress && stress_full) {
+            Some(SweepType::Garbage)
+        } else if self.pending.is_none() && (self.stress || self.allocations >= NURSERY_CELLS) {
+            Some(SweepType::Young)
+        } else {
+            self.pending
+        };
+
+        pointer
     }
 
-    pub fn collect_garbage(&mut self, sweep_type: SweepType) {
+    /// Free the cells that can't be reached from the root set or from what `trace_roots`
+    /// visits, or every cell.
+    ///
+    /// Minor collections don't trace the old cells, only the remembered ones may point to young
+    /// cells
+    pub fn collect_garbage(
+        &mut self,
+        sweep_type: SweepType,
+        trace_roots: impl FnOnce(&mut Tracer),
+    ) {
         if sweep_type == SweepType::Everything {
             self.sweep(sweep_type);
             return;
         }
 
-        let roots = &mut HashSet::new();
-        for collector in self.root_collector.iter_mut() {
-            collector(roots);
+        let tracer = &mut Tracer::marking(sweep_type == SweepType::Young);
+        self.roots.borrow().trace(tracer);
+        trace_roots(tracer);
+        if sweep_type == SweepType::Young {
+            for block in self.map_size_to_blocks.values_mut().flatten() {
+                block.for_each_remembered(|cell| tracer.trace_children(cell));
+            }
+            self.large_objects
+                .for_each_remembered(|cell| tracer.trace_children(cell));
         }
+        tracer.drain();
+        tracer.clear_weak();
 
-        let tracer = &mut Tracer::new();
-        for root in roots.iter() {
-            unsafe { (**root).trace(tracer) };
+        self.sweep(sweep_type);
+        self.allocations = 0;
+        self.pending = None;
+        if sweep_type == SweepType::Garbage {
+            self.threshold = usize::max(2 * self.size, MIN_THRESHOLD);
+            self.collections += 1;
+        } else {
+            self.young_collections += 1;
+            // What survives is old, a full collection is needed once it has doubled the heap
+            if self.size >= self.threshold {
+                self.pending = Some(SweepType::Garbage);
+            }
         }
-
-        self.sweep(sweep_type)
     }
 
     pub fn sweep(&mut self, sweep_type: SweepType) {
+        self.size = self.large_objects.sweep(sweep_type);
         for block_list in self.map_size_to_blocks.values_mut() {
             for block in block_list {
-                block.sweep(sweep_type)
+                self.size += block.sweep(sweep_type) * block.cell_size();
             }
         }
 
-        if sweep_type == SweepType::Garbage {
+        if sweep_type != SweepType::Everything {
             self.map_size_to_blocks.retain(|_, block_list| {
                 block_list.retain(|block| !block.is_empty());
                 !block_list.is_empty()
@@ -80,9 +226,29 @@ impl Heap {
         }
     }
 
+    pub fn stats(&self) -> HeapStats {
+        let size_classes = self
+            .map_size_to_blocks
+            .iter()
+            .map(|(cell_size, blocks)| SizeClassStats {
+                cell_size: *cell_size,
+                blocks: blocks.len(),
+                cells: blocks.iter().map(HeapBlock::len).sum(),
+                capacity: blocks.iter().map(HeapBlock::capacity).sum(),
+            })
+            .collect();
+
+        HeapStats {
+            size_classes,
+            large_objects: self.large_objects.len(),
+            large_object_bytes: self.large_objects.size(),
+            collections: self.collections,
+            young_collections: self.young_collections,
+        }
+    }
+
     fn allocate_block(&mut self, cell_size: usize) -> &mut HeapBlock {
-        debug_assert!(cell_size.is_power_of_two());
-        debug_assert!(BLOCK_SIZE % cell_size == 0);
+        debug_assert!(cell_size <= BLOCK_SIZE);
 
         let blocks = match self.map_size_to_blocks.entry(cell_size) {
             Entry::Vacant(v) => v.insert(Vec::new()),
@@ -108,6 +274,6 @@ impl Heap {
 
 impl Default for Heap {
     fn default() -> Self {
-        Heap::new(&[64, 64, 64, 64, 256, 256, 1024], 4 * 1024)
+        Heap::new()
     }
 }
@@ -0,0 +1,77 @@
+use std::{alloc::Layout, ptr::NonNull};
+
+use super::{Cell, GcCell, GcPointer, SweepType, CELL_ALIGN};
+
+/// Cells too big for the size classes, each one gets its own allocation
+#[derive(Default)]
+pub struct LargeObjectSpace {
+    objects: Vec<(NonNull<Cell>, Layout)>,
+}
+
+impl LargeObjectSpace {
+    pub fn allocate<T>(&mut self, data: T, size: usize) -> GcPointer<T>
+    where
+        T: Sized + 'static + GcCell,
+    {
+        let layout = Layout::from_size_align(size, CELL_ALIGN).unwrap();
+        let ptr = unsafe { std::alloc::alloc(layout) } as *mut Cell;
+        let cell = NonNull::new(ptr).unwrap_or_else(|| std::alloc::handle_alloc_error(layout));
+
+        unsafe {
+            Cell::placement_new(ptr, std::ptr::null_mut(), data);
+            (*ptr).set_large();
+        }
+        self.objects.push((cell, layout));
+        GcPointer::new(ptr)
+    }
+
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Bytes allocated for the objects in use
+    pub fn size(&self) -> usize {
+        self.objects.iter().map(|(_, layout)| layout.size()).sum()
+    }
+
+    /// Pass each remembered object to `f`
+    pub(crate) fn for_each_remembered(&self, mut f: impl FnMut(NonNull<Cell>)) {
+        for (cell, _) in self.objects.iter() {
+            if unsafe { cell.as_ref() }.is_remembered() {
+                f(*cell);
+            }
+        }
+    }
+
+    /// Free the unmarked objects, or all of them, returning the bytes of the ones left
+    pub fn sweep(&mut self, sweep_type: SweepType) -> usize {
+        self.objects.retain(|(cell, layout)| {
+            let cell = unsafe { &mut *cell.as_ptr() };
+            let live = match sweep_type {
+                SweepType::Everything => false,
+                SweepType::Garbage => cell.is_marked(),
+                SweepType::Young => cell.is_marked() || cell.is_old(),
+            };
+            if live {
+                cell.unmark();
+                cell.promote();
+                if sweep_type == SweepType::Garbage {
+                    cell.forget();
+                }
+                return true;
+            }
+
+            unsafe {
+                std::ptr::drop_in_place(cell.get_dyn());
+                std::alloc::dealloc(cell as *mut Cell as *mut u8, *layout);
+            }
+            false
+        });
+
+        self.size()
+    }
+}
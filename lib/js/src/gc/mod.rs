@@ -1,6 +1,6 @@
 use std::{
     any::TypeId,
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     fmt::Debug,
     marker::PhantomData,
     mem::ManuallyDrop,
@@ -11,18 +11,26 @@ use std::{
 mod block;
 mod cell;
 mod heap;
+mod large;
+mod root;
 mod trace;
+mod weak;
 
 pub use block::*;
 pub use cell::*;
-pub use heap::Heap;
+pub use heap::*;
 pub use js_derive::GcTrace;
+pub use large::LargeObjectSpace;
+pub use root::*;
 pub use trace::*;
+pub use weak::*;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum SweepType {
     Everything,
     Garbage,
+    /// Minor collection, only the cells allocated since the last collection can be freed
+    Young,
 }
 
 #[derive(Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -46,6 +54,11 @@ where
         &mut *self.base.as_ptr().cast::<_>()
     }
 
+    /// Whether both pointers point to the same cell
+    pub fn ptr_eq(&self, other: &GcPointer<T>) -> bool {
+        self.base == other.base
+    }
+
     pub fn gc_mark_alive(&self) {
         unsafe { self.cell().mark() };
     }
@@ -100,7 +113,10 @@ where
     T: 'static + GcCell,
 {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        unsafe { &mut *self.base.as_mut().data() }
+        unsafe {
+            self.base.as_mut().write_barrier();
+            &mut *self.base.as_mut().data()
+        }
     }
 }
 
@@ -113,7 +129,14 @@ where
     }
 }
 
-unsafe impl<T> Trace for GcPointer<T> where T: 'static + GcCell {}
+unsafe impl<T> Trace for GcPointer<T>
+where
+    T: 'static + GcCell,
+{
+    fn trace(&mut self, tracer: &mut Tracer) {
+        tracer.visit(self);
+    }
+}
 
 macro_rules! no_op_trace {
     ($i:ident, $($is:ident),+) => {
@@ -131,6 +154,30 @@ no_op_trace!(f32, f64, bool, String);
 
 unsafe impl Trace for () {}
 
+unsafe impl<T> Trace for Option<T>
+where
+    T: Trace,
+{
+    fn trace(&mut self, tracer: &mut Tracer) {
+        if let Some(v) = self {
+            v.trace(tracer);
+        }
+    }
+}
+
+unsafe impl<T, E> Trace for Result<T, E>
+where
+    T: Trace,
+    E: Trace,
+{
+    fn trace(&mut self, tracer: &mut Tracer) {
+        match self {
+            Ok(v) => v.trace(tracer),
+            Err(e) => e.trace(tracer),
+        }
+    }
+}
+
 unsafe impl<K, V> Trace for HashMap<K, V>
 where
     K: Trace,
@@ -152,3 +199,12 @@ where
         self.iter_mut().for_each(|v| v.trace(tracer))
     }
 }
+
+unsafe impl<V> Trace for VecDeque<V>
+where
+    V: Trace,
+{
+    fn trace(&mut self, tracer: &mut Tracer) {
+        self.iter_mut().for_each(|v| v.trace(tracer))
+    }
+}
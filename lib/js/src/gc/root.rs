@@ -0,0 +1,139 @@
+use std::{cell::RefCell, collections::HashMap, ops::Deref, ptr::NonNull, rc::Rc};
+
+use super::{Cell, GcPointer, Trace, Tracer};
+
+/// Root set of a heap, shared with the handles so they can unregister themselves
+pub type Roots = Rc<RefCell<RootSet>>;
+
+/// Cells the collector marks from, besides the ones the runtime traces itself
+#[derive(Default)]
+pub struct RootSet {
+    /// Cells held by `Rooted` handles, with the number of handles holding them
+    handles: HashMap<NonNull<Cell>, usize>,
+    /// Cells allocated while a scope is open, they are dropped from here when it is left
+    allocated: Vec<NonNull<Cell>>,
+    /// Length of `allocated` when each open scope was entered
+    scopes: Vec<usize>,
+}
+
+impl RootSet {
+    /// Root everything allocated from now on, until `leave_scope` is called.
+    ///
+    /// Values held on the native stack by the code running in the scope stay alive this way
+    pub fn enter_scope(&mut self) {
+        self.scopes.push(self.allocated.len());
+    }
+
+    /// Unroot what was allocated in the innermost scope, except what `escaping` points to which
+    /// is kept by the enclosing one
+    pub fn leave_scope(&mut self, escaping: &mut dyn Trace) {
+        let len = self
+            .scopes
+            .pop()
+            .expect("leave_scope() called without a scope");
+        self.allocated.truncate(len);
+        if !self.scopes.is_empty() {
+            self.allocated.extend(Tracer::cells(escaping));
+        }
+    }
+
+    /// Where the innermost scope is at, for `release`
+    pub fn mark(&self) -> usize {
+        self.allocated.len()
+    }
+
+    /// Unroot what the innermost scope allocated since `mark`, except what `live` points to.
+    ///
+    /// Loops call it between two iterations, so a long loop doesn't keep all it allocated alive
+    /// until the call running it returns
+    pub fn release(&mut self, mark: usize, live: &mut dyn Trace) {
+        debug_assert!(self.scopes.last().is_some_and(|len| *len <= mark));
+        self.allocated.truncate(mark);
+        self.allocated.extend(Tracer::cells(live));
+    }
+
+    /// Cells allocated outside of any scope are not rooted, the host has to use handles
+    pub(crate) fn allocated(&mut self, cell: NonNull<Cell>) {
+        if !self.scopes.is_empty() {
+            self.allocated.push(cell);
+        }
+    }
+
+    fn add(&mut self, cells: &[NonNull<Cell>]) {
+        for cell in cells {
+            *self.handles.entry(*cell).or_default() += 1;
+        }
+    }
+
+    fn remove(&mut self, cells: &[NonNull<Cell>]) {
+        for cell in cells {
+            if let Some(count) = self.handles.get_mut(cell) {
+                *count -= 1;
+                if *count == 0 {
+                    self.handles.remove(cell);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn trace(&self, tracer: &mut Tracer) {
+        for cell in self.handles.keys().chain(self.allocated.iter()) {
+            tracer.visit_cell(*cell);
+        }
+    }
+}
+
+/// A value whose cells stay alive as long as the handle does, for values held by native code
+/// while it allocates.
+///
+/// The value can't be changed in place, `replace` roots the new one
+pub struct Rooted<T: Trace> {
+    value: T,
+    cells: Vec<NonNull<Cell>>,
+    roots: Roots,
+}
+
+/// A rooted pointer to a single cell
+pub type Handle<T> = Rooted<GcPointer<T>>;
+
+impl<T: Trace> Rooted<T> {
+    pub fn new(roots: Roots, mut value: T) -> Rooted<T> {
+        let cells = Tracer::cells(&mut value);
+        roots.borrow_mut().add(&cells);
+        Rooted {
+            value,
+            cells,
+            roots,
+        }
+    }
+
+    /// Root `value` instead, returning the previous value which isn't rooted anymore
+    pub fn replace(&mut self, mut value: T) -> T {
+        let cells = Tracer::cells(&mut value);
+        let mut roots = self.roots.borrow_mut();
+        roots.add(&cells);
+        roots.remove(&self.cells);
+        self.cells = cells;
+        std::mem::replace(&mut self.value, value)
+    }
+}
+
+impl<T: Trace> Deref for Rooted<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Trace + Clone> Clone for Rooted<T> {
+    fn clone(&self) -> Self {
+        Rooted::new(self.roots.clone(), self.value.clone())
+    }
+}
+
+impl<T: Trace> Drop for Rooted<T> {
+    fn drop(&mut self) {
+        self.roots.borrow_mut().remove(&self.cells);
+    }
+}
@@ -1,18 +1,134 @@
-use super::{GcCell, GcPointer};
+use std::ptr::NonNull;
 
+use super::{Cell, GcCell, GcPointer, WeakTrace};
+
+/// Walk the object graph, marking the cells it visits.
+///
+/// Marked cells go on a worklist and their own fields are traced when it is drained, so deep
+/// graphs don't recurse on the native stack
 #[derive(Debug)]
-pub struct Tracer {}
+pub struct Tracer {
+    worklist: Vec<NonNull<Cell>>,
+    marking: bool,
+    /// Old cells are taken as alive and not traced, for minor collections
+    young_only: bool,
+    /// Weak references met while marking, cleared by `clear_weak` if their cells weren't marked
+    weak: Vec<NonNull<dyn WeakTrace>>,
+    /// Values of ephemerons whose key wasn't marked yet when they were visited
+    ephemerons: Vec<(NonNull<Cell>, NonNull<dyn Trace>)>,
+}
 
 impl Tracer {
-    pub fn new() -> Tracer {
-        Tracer {}
+    pub(crate) fn marking(young_only: bool) -> Tracer {
+        Tracer {
+            worklist: Vec::new(),
+            marking: true,
+            young_only,
+            weak: Vec::new(),
+            ephemerons: Vec::new(),
+        }
+    }
+
+    /// Cells directly referenced by `value`, without marking anything
+    pub(crate) fn cells(value: &mut dyn Trace) -> Vec<NonNull<Cell>> {
+        let mut tracer = Tracer {
+            worklist: Vec::new(),
+            marking: false,
+            young_only: false,
+            weak: Vec::new(),
+            ephemerons: Vec::new(),
+        };
+        value.trace(&mut tracer);
+        tracer.worklist
     }
 
     pub fn visit<T: GcCell>(&mut self, obj: &mut GcPointer<T>) {
-        unsafe {
-            obj.cell().mark();
+        self.visit_cell(obj.base);
+    }
+
+    pub(crate) fn visit_cell(&mut self, mut cell: NonNull<Cell>) {
+        if self.marking {
+            let cell = unsafe { cell.as_mut() };
+            if cell.is_marked() || (self.young_only && cell.is_old()) {
+                return;
+            }
+            cell.mark();
+        }
+        self.worklist.push(cell);
+    }
+
+    /// Clear `weak` once marking is done if what it points to wasn't marked, rooting ignores it
+    pub fn visit_weak<W: WeakTrace + 'static>(&mut self, weak: &mut W) {
+        if self.marking {
+            self.weak.push(NonNull::from(weak as &mut dyn WeakTrace));
+        }
+    }
+
+    /// Trace `value` if `key` is alive, or later once it is found to be
+    pub(crate) fn visit_ephemeron<V: Trace + 'static>(
+        &mut self,
+        key: NonNull<Cell>,
+        value: &mut V,
+    ) {
+        if !self.marking {
+            return;
+        }
+        if self.is_alive(key) {
+            value.trace(self);
+        } else {
+            self.ephemerons
+                .push((key, NonNull::from(value as &mut dyn Trace)));
         }
     }
+
+    fn is_alive(&self, cell: NonNull<Cell>) -> bool {
+        is_alive(cell, self.young_only)
+    }
+
+    /// Trace the cells marked so far, until everything reachable from them is marked.
+    ///
+    /// Marking a key makes the value of its ephemerons reachable, which may mark other keys, so
+    /// this goes on until none is left to trace
+    pub(crate) fn drain(&mut self) {
+        debug_assert!(self.marking);
+        loop {
+            while let Some(cell) = self.worklist.pop() {
+                self.trace_children(cell);
+            }
+
+            let (ready, waiting) = std::mem::take(&mut self.ephemerons)
+                .into_iter()
+                .partition::<Vec<_>, _>(|(key, _)| self.is_alive(*key));
+            self.ephemerons = waiting;
+            if ready.is_empty() {
+                break;
+            }
+            for (_, mut value) in ready {
+                unsafe { value.as_mut().trace(self) };
+            }
+        }
+    }
+
+    /// Clear the weak references to the cells left unmarked, before they are swept
+    pub(crate) fn clear_weak(&mut self) {
+        debug_assert!(self.worklist.is_empty());
+        self.ephemerons.clear();
+        let young_only = self.young_only;
+        for mut weak in std::mem::take(&mut self.weak) {
+            unsafe { weak.as_mut().clear_dead(&|cell| is_alive(cell, young_only)) };
+        }
+    }
+
+    /// Visit what `cell` points to, without marking it
+    pub(crate) fn trace_children(&mut self, cell: NonNull<Cell>) {
+        unsafe { cell.as_ref().get_dyn().trace(self) };
+    }
+}
+
+/// Marked, or old during a minor collection which doesn't free old cells
+fn is_alive(cell: NonNull<Cell>, young_only: bool) -> bool {
+    let cell = unsafe { cell.as_ref() };
+    cell.is_marked() || (young_only && cell.is_old())
 }
 
 pub unsafe trait Trace {
@@ -0,0 +1,128 @@
+use std::{collections::HashMap, marker::PhantomData, ptr::NonNull};
+
+use super::{Cell, GcCell, GcPointer, Trace, Tracer};
+
+/// What holds cells without keeping them alive, told which of them died once marking is done
+pub trait WeakTrace {
+    fn clear_dead(&mut self, alive: &dyn Fn(NonNull<Cell>) -> bool);
+}
+
+/// A pointer that doesn't keep its cell alive, it is cleared when the cell is collected
+pub struct WeakGcPointer<T: 'static + GcCell> {
+    base: Option<NonNull<Cell>>,
+    marker: PhantomData<T>,
+}
+
+impl<T: GcCell> WeakGcPointer<T> {
+    pub fn new(pointer: &GcPointer<T>) -> WeakGcPointer<T> {
+        WeakGcPointer {
+            base: Some(pointer.base),
+            marker: PhantomData,
+        }
+    }
+
+    /// The cell, unless it was collected
+    pub fn upgrade(&self) -> Option<GcPointer<T>> {
+        self.base.map(|base| GcPointer::new(base.as_ptr()))
+    }
+
+    pub fn is_cleared(&self) -> bool {
+        self.base.is_none()
+    }
+
+    /// Whether it points to the cell of `pointer`
+    pub fn points_to(&self, pointer: &GcPointer<T>) -> bool {
+        self.base == Some(pointer.base)
+    }
+}
+
+impl<T: GcCell> Clone for WeakGcPointer<T> {
+    fn clone(&self) -> Self {
+        WeakGcPointer {
+            base: self.base,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: GcCell> std::fmt::Debug for WeakGcPointer<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("WeakGcPointer").field(&self.base).finish()
+    }
+}
+
+unsafe impl<T: GcCell> Trace for WeakGcPointer<T> {
+    fn trace(&mut self, tracer: &mut Tracer) {
+        tracer.visit_weak(self);
+    }
+}
+
+impl<T: GcCell> WeakTrace for WeakGcPointer<T> {
+    fn clear_dead(&mut self, alive: &dyn Fn(NonNull<Cell>) -> bool) {
+        if self.base.is_some_and(|base| !alive(base)) {
+            self.base = None;
+        }
+    }
+}
+
+/// Values keyed by cells, a value is only alive while its key is, for `WeakMap` and `WeakSet`.
+///
+/// The entries of dead keys are removed by the collection that frees them, before the cell can
+/// be reused for another key
+#[derive(Debug)]
+pub struct EphemeronTable<V: Trace + 'static> {
+    entries: HashMap<NonNull<Cell>, V>,
+}
+
+impl<V: Trace> EphemeronTable<V> {
+    pub fn new() -> EphemeronTable<V> {
+        EphemeronTable {
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn get<K: GcCell>(&self, key: &GcPointer<K>) -> Option<&V> {
+        self.entries.get(&key.base)
+    }
+
+    pub fn contains<K: GcCell>(&self, key: &GcPointer<K>) -> bool {
+        self.entries.contains_key(&key.base)
+    }
+
+    pub fn insert<K: GcCell>(&mut self, key: &GcPointer<K>, value: V) {
+        self.entries.insert(key.base, value);
+    }
+
+    pub fn remove<K: GcCell>(&mut self, key: &GcPointer<K>) -> bool {
+        self.entries.remove(&key.base).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<V: Trace> Default for EphemeronTable<V> {
+    fn default() -> Self {
+        EphemeronTable::new()
+    }
+}
+
+unsafe impl<V: Trace> Trace for EphemeronTable<V> {
+    fn trace(&mut self, tracer: &mut Tracer) {
+        for (key, value) in self.entries.iter_mut() {
+            tracer.visit_ephemeron(*key, value);
+        }
+        tracer.visit_weak(self);
+    }
+}
+
+impl<V: Trace> WeakTrace for EphemeronTable<V> {
+    fn clear_dead(&mut self, alive: &dyn Fn(NonNull<Cell>) -> bool) {
+        self.entries.retain(|key, _| alive(*key));
+    }
+}
@@ -0,0 +1,974 @@
+use std::{collections::BTreeMap, fmt};
+
+use super::{
+    create_iter_result, js_function, Attributes, ErrorKind, JsObject, JsValue, ObjectKind,
+    PropertyDescriptor, PropertyKey, Slot,
+};
+use crate::{ast, gc::*, vm::Context};
+
+/// Holes further than this past the last element make the array sparse
+const MAX_DENSE_GAP: usize = 1024;
+
+/// Elements of an array, a `Vec` as long as they are all plain writable, enumerable and
+/// configurable data properties close to each other
+enum ArrayStorage {
+    /// `None` is a hole
+    Dense(Vec<Option<JsValue>>),
+    Sparse(BTreeMap<u32, (Slot, Attributes)>),
+}
+
+unsafe impl Trace for ArrayStorage {
+    fn trace(&mut self, tracer: &mut Tracer) {
+        match self {
+            ArrayStorage::Dense(elements) => elements.trace(tracer),
+            ArrayStorage::Sparse(elements) => elements
+                .values_mut()
+                .for_each(|(slot, _)| slot.trace(tracer)),
+        }
+    }
+}
+
+/// Array exotic object, its indices and `length` are kept out of the property map
+#[derive(GcTrace)]
+pub struct JsArray {
+    elements: ArrayStorage,
+    length: u32,
+    length_writable: bool,
+}
+
+impl JsArray {
+    pub fn new() -> JsArray {
+        JsArray::with_length(0)
+    }
+
+    /// Array of `length` holes
+    pub fn with_length(length: u32) -> JsArray {
+        JsArray {
+            elements: ArrayStorage::Dense(Vec::new()),
+            length,
+            length_writable: true,
+        }
+    }
+
+    pub fn from_values(values: Vec<JsValue>) -> JsArray {
+        JsArray {
+            length: values.len() as u32,
+            elements: ArrayStorage::Dense(values.into_iter().map(Some).collect()),
+            length_writable: true,
+        }
+    }
+
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    /// `ToUint32` of a new `length`, `None` when it isn't a valid length
+    pub fn to_length(value: &JsValue) -> Option<u32> {
+        let number = value.number_value().as_f64();
+        let length = value.to_u32();
+        (length as f64 == number).then_some(length)
+    }
+
+    pub fn element(&self, index: u32) -> Option<PropertyDescriptor> {
+        self.slot(index)
+            .map(|(slot, attributes)| PropertyDescriptor::from_slot(&slot, attributes))
+    }
+
+    pub fn length_descriptor(&self) -> PropertyDescriptor {
+        let attributes = if self.length_writable {
+            Attributes::WRITABLE
+        } else {
+            Attributes::empty()
+        };
+        PropertyDescriptor::data(JsValue::from(self.length), attributes)
+    }
+
+    /// `[[DefineOwnProperty]]` of an index, growing `length` past it
+    pub fn define_element(
+        &mut self,
+        index: u32,
+        desc: PropertyDescriptor,
+        extensible: bool,
+    ) -> bool {
+        if index >= self.length && !self.length_writable {
+            return false;
+        }
+
+        let current = self.slot(index);
+        let current = current
+            .as_ref()
+            .map(|(slot, attributes)| (slot, *attributes));
+        match desc.validate_and_apply(current, extensible) {
+            Some((slot, attributes)) => {
+                self.store(index, slot, attributes);
+                self.length = self.length.max(index + 1);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Append an element to an array literal, `None` leaves a hole
+    pub fn push(&mut self, value: Option<JsValue>) {
+        if let Some(value) = value {
+            self.store(self.length, Slot::Data(value), Attributes::all());
+        }
+        self.length += 1;
+    }
+
+    /// Remove an element without touching `length`, it must be configurable
+    pub fn remove_element(&mut self, index: u32) {
+        match &mut self.elements {
+            ArrayStorage::Dense(elements) => {
+                if let Some(element) = elements.get_mut(index as usize) {
+                    *element = None;
+                }
+            }
+            ArrayStorage::Sparse(elements) => {
+                elements.remove(&index);
+            }
+        }
+    }
+
+    /// Indices of the elements, in ascending order
+    pub fn indices(&self) -> Vec<u32> {
+        match &self.elements {
+            ArrayStorage::Dense(elements) => elements
+                .iter()
+                .enumerate()
+                .filter(|(_, element)| element.is_some())
+                .map(|(index, _)| index as u32)
+                .collect(),
+            ArrayStorage::Sparse(elements) => elements.keys().copied().collect(),
+        }
+    }
+
+    /// `ArraySetLength`, shrinking deletes the elements past the new length until one of them
+    /// isn't configurable
+    pub fn define_length(&mut self, desc: PropertyDescriptor) -> bool {
+        if desc.configurable == Some(true)
+            || desc.enumerable == Some(true)
+            || desc.is_accessor_descriptor()
+            || (desc.writable == Some(true) && !self.length_writable)
+        {
+            return false;
+        }
+
+        let new_length = match &desc.value {
+            Some(value) => match JsArray::to_length(value) {
+                Some(length) => length,
+                None => return false,
+            },
+            None => self.length,
+        };
+        if new_length != self.length && !self.length_writable {
+            return false;
+        }
+
+        let deleted = self.truncate(new_length);
+        if desc.writable == Some(false) {
+            self.length_writable = false;
+        }
+        deleted
+    }
+
+    /// Own element at `index`, with its attributes
+    fn slot(&self, index: u32) -> Option<(Slot, Attributes)> {
+        match &self.elements {
+            ArrayStorage::Dense(elements) => elements
+                .get(index as usize)
+                .and_then(Option::as_ref)
+                .map(|value| (Slot::Data(value.clone()), Attributes::all())),
+            ArrayStorage::Sparse(elements) => elements.get(&index).cloned(),
+        }
+    }
+
+    fn store(&mut self, index: u32, slot: Slot, attributes: Attributes) {
+        if let ArrayStorage::Dense(elements) = &mut self.elements {
+            let position = index as usize;
+            match slot {
+                Slot::Data(value)
+                    if attributes == Attributes::all()
+                        && position <= elements.len() + MAX_DENSE_GAP =>
+                {
+                    if position >= elements.len() {
+                        elements.resize(position + 1, None);
+                    }
+                    elements[position] = Some(value);
+                    return;
+                }
+                slot => {
+                    self.make_sparse();
+                    return self.store(index, slot, attributes);
+                }
+            }
+        }
+
+        if let ArrayStorage::Sparse(elements) = &mut self.elements {
+            elements.insert(index, (slot, attributes));
+        }
+    }
+
+    fn make_sparse(&mut self) {
+        if let ArrayStorage::Dense(elements) = &mut self.elements {
+            let sparse = std::mem::take(elements)
+                .into_iter()
+                .enumerate()
+                .filter_map(|(index, element)| {
+                    element.map(|value| (index as u32, (Slot::Data(value), Attributes::all())))
+                })
+                .collect();
+            self.elements = ArrayStorage::Sparse(sparse);
+        }
+    }
+
+    /// Delete the elements from `length` on, false if a non configurable one stopped it
+    fn truncate(&mut self, length: u32) -> bool {
+        match &mut self.elements {
+            ArrayStorage::Dense(elements) => {
+                elements.truncate(length as usize);
+            }
+            ArrayStorage::Sparse(elements) => {
+                let removed: Vec<_> = elements.range(length..).map(|(i, _)| *i).collect();
+                for index in removed.into_iter().rev() {
+                    let (_, attributes) = elements[&index];
+                    if !attributes.contains(Attributes::CONFIGURABLE) {
+                        self.length = index + 1;
+                        return false;
+                    }
+                    elements.remove(&index);
+                }
+            }
+        }
+        self.length = length;
+        true
+    }
+}
+
+impl Default for JsArray {
+    fn default() -> Self {
+        JsArray::new()
+    }
+}
+
+impl fmt::Debug for JsArray {
+    // Elements may contain the array itself
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "JsArray {{ length: {} }}", self.length)
+    }
+}
+
+/// What an array iterator returns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IterationKind {
+    Keys,
+    Values,
+    /// `[index, value]` pairs
+    Entries,
+}
+
+unsafe impl Trace for IterationKind {}
+
+/// `%ArrayIteratorPrototype%` objects, the length is read again on every step
+#[derive(GcTrace)]
+pub struct ArrayIterator {
+    /// `None` once done
+    iterated: Option<GcPointer<JsObject>>,
+    kind: IterationKind,
+    index: u32,
+}
+
+impl ArrayIterator {
+    pub fn create(
+        ctx: &mut Context,
+        iterated: GcPointer<JsObject>,
+        kind: IterationKind,
+    ) -> GcPointer<JsObject> {
+        let prototype = ctx.array_iterator_prototype();
+        let iterator = ArrayIterator {
+            iterated: Some(iterated),
+            kind,
+            index: 0,
+        };
+        JsObject::with_prototype(ctx, Some(prototype), ObjectKind::ArrayIterator(iterator))
+    }
+
+    /// Next value, `None` once every element has been visited
+    pub fn next(&mut self, ctx: &mut Context) -> ast::Result<Option<JsValue>> {
+        let obj = match &self.iterated {
+            Some(obj) => obj.clone(),
+            None => return Ok(None),
+        };
+        if self.index >= length_of(ctx, &obj)? {
+            self.iterated = None;
+            return Ok(None);
+        }
+
+        let index = self.index;
+        self.index += 1;
+        Ok(Some(match self.kind {
+            IterationKind::Keys => JsValue::from(index),
+            IterationKind::Values => get(ctx, &obj, index)?,
+            IterationKind::Entries => {
+                let value = get(ctx, &obj, index)?;
+                JsValue::object(JsObject::array(ctx, vec![JsValue::from(index), value]))
+            }
+        }))
+    }
+}
+
+impl fmt::Debug for ArrayIterator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ArrayIterator {{ kind: {:?}, index: {} }}",
+            self.kind, self.index
+        )
+    }
+}
+
+impl JsArray {
+    /// `Array`, `Array.prototype` methods and `%ArrayIteratorPrototype%`
+    pub(crate) fn create_builtins(ctx: &mut Context) {
+        let methods = [
+            PUSH, POP, SHIFT, UNSHIFT, SLICE, SPLICE, JOIN, TO_STRING, INDEX_OF, INCLUDES,
+            FOR_EACH, MAP, FILTER, REDUCE, FIND, FIND_INDEX, SOME, EVERY, SORT, KEYS, VALUES,
+            ENTRIES,
+        ];
+        let mut prototype = ctx.array_prototype();
+        for function in methods {
+            prototype.define_method(ctx, function);
+        }
+        // The very same function as `values`
+        let symbols = ctx.well_known_symbols().clone();
+        let values = prototype.get_data_property("values");
+        prototype.define_property(
+            symbols.iterator,
+            values,
+            Attributes::WRITABLE | Attributes::CONFIGURABLE,
+        );
+
+        let mut constructor = JsObject::native_constructor(ctx, CONSTRUCT, prototype);
+        constructor.define_method(ctx, IS_ARRAY);
+        ctx.define_global("Array", JsValue::object(constructor));
+
+        let mut iterator_prototype = ctx.array_iterator_prototype();
+        iterator_prototype.define_method(ctx, ITERATOR_NEXT);
+        iterator_prototype.define_property(
+            symbols.to_string_tag,
+            JsValue::string("Array Iterator"),
+            Attributes::CONFIGURABLE,
+        );
+    }
+}
+
+// Helpers of the generic methods, which work on any object with a `length`
+
+fn this_object(ctx: &mut Context, this: JsValue, method: &str) -> ast::Result<GcPointer<JsObject>> {
+    match this {
+        JsValue::Object(obj) => Ok(obj),
+        JsValue::Undefined | JsValue::Null => {
+            let message = format!("Array.prototype.{} called on null or undefined", method);
+            Err(ctx.throw_error(ErrorKind::TypeError, &message))
+        }
+        // Primitives have no element until they get their wrapper objects
+        _ => Ok(JsObject::new(ctx)),
+    }
+}
+
+/// `LengthOfArrayLike`, clamped to the lengths arrays can have
+fn length_of(ctx: &mut Context, obj: &GcPointer<JsObject>) -> ast::Result<u32> {
+    let length = get(ctx, obj, "length")?.to_number_with(ctx)?.as_f64();
+    Ok(if length.is_nan() || length <= 0.0 {
+        0
+    } else {
+        length.min(u32::MAX as f64) as u32
+    })
+}
+
+fn get<K: Into<PropertyKey>>(
+    ctx: &mut Context,
+    obj: &GcPointer<JsObject>,
+    key: K,
+) -> ast::Result<JsValue> {
+    obj.get(ctx, &key.into(), JsValue::object(obj.clone()))
+}
+
+/// `Set(O, P, V, true)`, a read only property throws
+fn set<K: Into<PropertyKey>>(
+    ctx: &mut Context,
+    obj: &GcPointer<JsObject>,
+    key: K,
+    value: JsValue,
+) -> ast::Result<()> {
+    let key = key.into();
+    let receiver = JsValue::object(obj.clone());
+    if obj.clone().set(ctx, key.clone(), value, receiver)? {
+        Ok(())
+    } else {
+        let message = format!("Cannot assign to read only property '{}' of object", key);
+        Err(ctx.throw_error(ErrorKind::TypeError, &message))
+    }
+}
+
+fn delete<K: Into<PropertyKey>>(
+    ctx: &mut Context,
+    obj: &GcPointer<JsObject>,
+    key: K,
+) -> ast::Result<()> {
+    let key = key.into();
+    if obj.clone().delete(&key) {
+        Ok(())
+    } else {
+        let message = format!("Cannot delete property '{}' of object", key);
+        Err(ctx.throw_error(ErrorKind::TypeError, &message))
+    }
+}
+
+/// `CreateListFromArrayLike`, the arguments given to `Function.prototype.apply`
+pub(crate) fn list_from_array_like(
+    ctx: &mut Context,
+    obj: &GcPointer<JsObject>,
+) -> ast::Result<Vec<JsValue>> {
+    let length = length_of(ctx, obj)?;
+    (0..length).map(|index| get(ctx, obj, index)).collect()
+}
+
+fn has(obj: &GcPointer<JsObject>, index: u32) -> bool {
+    obj.has_property(&PropertyKey::from(index))
+}
+
+/// Move the element at `from` to `to`, deleting `to` if `from` is a hole
+fn move_element(
+    ctx: &mut Context,
+    obj: &GcPointer<JsObject>,
+    from: u32,
+    to: u32,
+) -> ast::Result<()> {
+    if has(obj, from) {
+        let value = get(ctx, obj, from)?;
+        set(ctx, obj, to, value)
+    } else {
+        delete(ctx, obj, to)
+    }
+}
+
+/// New array of `length` holes, for `map` and `splice`
+fn array_with_length(ctx: &mut Context, length: u32) -> GcPointer<JsObject> {
+    let prototype = ctx.array_prototype();
+    let array = JsArray::with_length(length);
+    JsObject::with_prototype(ctx, Some(prototype), ObjectKind::Array(array))
+}
+
+/// Index given relative to the end when negative, clamped to `0..=length`
+fn relative_index(
+    ctx: &mut Context,
+    value: Option<&JsValue>,
+    length: u32,
+    default: u32,
+) -> ast::Result<u32> {
+    let value = match value {
+        None | Some(JsValue::Undefined) => return Ok(default),
+        Some(value) => value.to_number_with(ctx)?.as_f64(),
+    };
+    let value = if value.is_nan() { 0.0 } else { value.trunc() };
+    Ok(if value < 0.0 {
+        (length as f64 + value).max(0.0) as u32
+    } else {
+        value.min(length as f64) as u32
+    })
+}
+
+/// First argument of the iteration methods
+fn callback(ctx: &mut Context, args: &[JsValue]) -> ast::Result<(JsValue, JsValue)> {
+    let callback = args.first().cloned().unwrap_or_default();
+    match &callback {
+        JsValue::Object(obj) if obj.is_function() => {
+            Ok((callback, args.get(1).cloned().unwrap_or_default()))
+        }
+        _ => {
+            let message = format!("{} is not a function", callback.to_string());
+            Err(ctx.throw_error(ErrorKind::TypeError, &message))
+        }
+    }
+}
+
+/// `callback(value, index, obj)` for every element, holes are skipped unless `visit_holes`.
+/// Stops at the first element `stop` returns true for, giving its index
+fn iterate(
+    ctx: &mut Context,
+    this: JsValue,
+    args: &[JsValue],
+    method: &str,
+    visit_holes: bool,
+    mut stop: impl FnMut(&mut Context, JsValue, JsValue) -> ast::Result<bool>,
+) -> ast::Result<Option<u32>> {
+    let obj = this_object(ctx, this, method)?;
+    let length = length_of(ctx, &obj)?;
+    let (callback, this_arg) = callback(ctx, args)?;
+
+    for index in 0..length {
+        if !visit_holes && !has(&obj, index) {
+            continue;
+        }
+        let value = get(ctx, &obj, index)?;
+        let args = vec![
+            value.clone(),
+            JsValue::from(index),
+            JsValue::object(obj.clone()),
+        ];
+        let result = ctx.call(&callback, this_arg.clone(), args)?;
+        if stop(ctx, value, result)? {
+            return Ok(Some(index));
+        }
+    }
+    Ok(None)
+}
+
+/// `undefined` sorts after everything, then the comparator or the string order decides
+fn compare(ctx: &mut Context, comparator: &JsValue, a: &JsValue, b: &JsValue) -> ast::Result<f64> {
+    if comparator.is_undefined() {
+        return Ok(match a.to_string().cmp(&b.to_string()) {
+            std::cmp::Ordering::Less => -1.0,
+            std::cmp::Ordering::Equal => 0.0,
+            std::cmp::Ordering::Greater => 1.0,
+        });
+    }
+
+    let result = ctx.call(comparator, JsValue::Undefined, vec![a.clone(), b.clone()])?;
+    let result = result.to_number_with(ctx)?.as_f64();
+    Ok(if result.is_nan() { 0.0 } else { result })
+}
+
+/// Stable merge sort, the comparator may throw or be inconsistent which `sort_by` doesn't allow
+fn merge_sort(
+    ctx: &mut Context,
+    mut values: Vec<JsValue>,
+    comparator: &JsValue,
+) -> ast::Result<Vec<JsValue>> {
+    if values.len() < 2 {
+        return Ok(values);
+    }
+
+    let right = values.split_off(values.len() / 2);
+    let mut left = merge_sort(ctx, values, comparator)?.into_iter().peekable();
+    let mut right = merge_sort(ctx, right, comparator)?.into_iter().peekable();
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+        if compare(ctx, comparator, a, b)? > 0.0 {
+            merged.extend(right.next());
+        } else {
+            merged.extend(left.next());
+        }
+    }
+    merged.extend(left);
+    merged.extend(right);
+    Ok(merged)
+}
+
+// Array
+
+#[js_function(name = "Array", length = 1)]
+fn construct(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    match args.as_slice() {
+        [length] if length.is_number() => match JsArray::to_length(length) {
+            Some(length) => Ok(JsValue::object(array_with_length(ctx, length))),
+            None => Err(ctx.throw_error(ErrorKind::RangeError, "Invalid array length")),
+        },
+        _ => Ok(JsValue::object(JsObject::array(ctx, args))),
+    }
+}
+
+#[js_function(length = 1)]
+fn is_array(_ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let is_array = matches!(args.first(), Some(JsValue::Object(obj)) if obj.is_array());
+    Ok(JsValue::Boolean(is_array))
+}
+
+// Array.prototype
+
+#[js_function(length = 1)]
+fn push(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = this_object(ctx, this, "push")?;
+    let mut length = length_of(ctx, &obj)?;
+    for value in args {
+        set(ctx, &obj, length, value)?;
+        length += 1;
+    }
+    set(ctx, &obj, "length", JsValue::from(length))?;
+    Ok(JsValue::from(length))
+}
+
+#[js_function]
+fn pop(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = this_object(ctx, this, "pop")?;
+    let length = length_of(ctx, &obj)?;
+    if length == 0 {
+        set(ctx, &obj, "length", JsValue::integer(0))?;
+        return Ok(JsValue::Undefined);
+    }
+
+    let value = get(ctx, &obj, length - 1)?;
+    delete(ctx, &obj, length - 1)?;
+    set(ctx, &obj, "length", JsValue::from(length - 1))?;
+    Ok(value)
+}
+
+#[js_function]
+fn shift(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = this_object(ctx, this, "shift")?;
+    let length = length_of(ctx, &obj)?;
+    if length == 0 {
+        set(ctx, &obj, "length", JsValue::integer(0))?;
+        return Ok(JsValue::Undefined);
+    }
+
+    let first = get(ctx, &obj, 0)?;
+    for index in 1..length {
+        move_element(ctx, &obj, index, index - 1)?;
+    }
+    delete(ctx, &obj, length - 1)?;
+    set(ctx, &obj, "length", JsValue::from(length - 1))?;
+    Ok(first)
+}
+
+#[js_function(length = 1)]
+fn unshift(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = this_object(ctx, this, "unshift")?;
+    let length = length_of(ctx, &obj)?;
+    let count = args.len() as u32;
+    if length.checked_add(count).is_none() {
+        return Err(ctx.throw_error(ErrorKind::RangeError, "Invalid array length"));
+    }
+
+    if count > 0 {
+        for index in (0..length).rev() {
+            move_element(ctx, &obj, index, index + count)?;
+        }
+        for (index, value) in args.into_iter().enumerate() {
+            set(ctx, &obj, index as u32, value)?;
+        }
+    }
+    set(ctx, &obj, "length", JsValue::from(length + count))?;
+    Ok(JsValue::from(length + count))
+}
+
+#[js_function(length = 2)]
+fn slice(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = this_object(ctx, this, "slice")?;
+    let length = length_of(ctx, &obj)?;
+    let start = relative_index(ctx, args.first(), length, 0)?;
+    let end = relative_index(ctx, args.get(1), length, length)?;
+
+    let mut result = array_with_length(ctx, 0);
+    let mut count = 0;
+    for index in start..end {
+        if has(&obj, index) {
+            let value = get(ctx, &obj, index)?;
+            result.create_data_property(PropertyKey::from(count), value);
+        }
+        count += 1;
+    }
+    set(ctx, &result, "length", JsValue::from(count))?;
+    Ok(JsValue::object(result))
+}
+
+#[js_function(length = 2)]
+fn splice(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = this_object(ctx, this, "splice")?;
+    let length = length_of(ctx, &obj)?;
+    let start = relative_index(ctx, args.first(), length, 0)?;
+    let delete_count = match args.get(1) {
+        _ if args.is_empty() => 0,
+        None => length - start,
+        Some(count) => {
+            let count = count.to_number_with(ctx)?.as_f64();
+            let count = if count.is_nan() { 0.0 } else { count.trunc() };
+            count.clamp(0.0, (length - start) as f64) as u32
+        }
+    };
+    let items: Vec<_> = args.into_iter().skip(2).collect();
+    let item_count = items.len() as u32;
+    if (length - delete_count).checked_add(item_count).is_none() {
+        return Err(ctx.throw_error(ErrorKind::RangeError, "Invalid array length"));
+    }
+
+    let mut removed = array_with_length(ctx, 0);
+    for offset in 0..delete_count {
+        if has(&obj, start + offset) {
+            let value = get(ctx, &obj, start + offset)?;
+            removed.create_data_property(PropertyKey::from(offset), value);
+        }
+    }
+    set(ctx, &removed, "length", JsValue::from(delete_count))?;
+
+    if item_count < delete_count {
+        for index in start..length - delete_count {
+            move_element(ctx, &obj, index + delete_count, index + item_count)?;
+        }
+        for index in (length - delete_count + item_count..length).rev() {
+            delete(ctx, &obj, index)?;
+        }
+    } else if item_count > delete_count {
+        for index in (start..length - delete_count).rev() {
+            move_element(ctx, &obj, index + delete_count, index + item_count)?;
+        }
+    }
+
+    for (offset, item) in items.into_iter().enumerate() {
+        set(ctx, &obj, start + offset as u32, item)?;
+    }
+    let new_length = length - delete_count + item_count;
+    set(ctx, &obj, "length", JsValue::from(new_length))?;
+    Ok(JsValue::object(removed))
+}
+
+#[js_function(length = 1)]
+fn join(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = this_object(ctx, this, "join")?;
+    let length = length_of(ctx, &obj)?;
+    let separator = match args.first() {
+        None | Some(JsValue::Undefined) => ",".to_string(),
+        Some(separator) => separator.to_string(),
+    };
+
+    let mut result = String::new();
+    for index in 0..length {
+        if index > 0 {
+            result.push_str(&separator);
+        }
+        match get(ctx, &obj, index)? {
+            JsValue::Undefined | JsValue::Null => {}
+            value => result.push_str(&value.to_string()),
+        }
+    }
+    Ok(JsValue::string(result.as_ref()))
+}
+
+#[js_function]
+fn to_string(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    join(ctx, this, Vec::new())
+}
+
+#[js_function(length = 1)]
+fn index_of(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = this_object(ctx, this, "indexOf")?;
+    let length = length_of(ctx, &obj)?;
+    let search = args.first().cloned().unwrap_or_default();
+
+    for index in relative_index(ctx, args.get(1), length, 0)?..length {
+        if has(&obj, index) && get(ctx, &obj, index)?.strict_equals(&search) {
+            return Ok(JsValue::from(index));
+        }
+    }
+    Ok(JsValue::integer(-1))
+}
+
+#[js_function(length = 1)]
+fn includes(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = this_object(ctx, this, "includes")?;
+    let length = length_of(ctx, &obj)?;
+    let search = args.first().cloned().unwrap_or_default();
+
+    // `SameValueZero`, holes are `undefined`
+    for index in relative_index(ctx, args.get(1), length, 0)?..length {
+        let value = get(ctx, &obj, index)?;
+        if value.strict_equals(&search) || (value.is_nan() && search.is_nan()) {
+            return Ok(JsValue::Boolean(true));
+        }
+    }
+    Ok(JsValue::Boolean(false))
+}
+
+#[js_function(length = 1)]
+fn for_each(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    iterate(ctx, this, &args, "forEach", false, |_, _, _| Ok(false))?;
+    Ok(JsValue::Undefined)
+}
+
+#[js_function(length = 1)]
+fn map(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = this_object(ctx, this, "map")?;
+    let length = length_of(ctx, &obj)?;
+    let (callback, this_arg) = callback(ctx, &args)?;
+
+    let mut result = array_with_length(ctx, length);
+    for index in 0..length {
+        if has(&obj, index) {
+            let value = get(ctx, &obj, index)?;
+            let args = vec![value, JsValue::from(index), JsValue::object(obj.clone())];
+            let mapped = ctx.call(&callback, this_arg.clone(), args)?;
+            result.create_data_property(PropertyKey::from(index), mapped);
+        }
+    }
+    Ok(JsValue::object(result))
+}
+
+#[js_function(length = 1)]
+fn filter(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let mut kept = Vec::new();
+    iterate(ctx, this, &args, "filter", false, |_, value, result| {
+        if result.to_boolean() {
+            kept.push(value);
+        }
+        Ok(false)
+    })?;
+    Ok(JsValue::object(JsObject::array(ctx, kept)))
+}
+
+#[js_function(length = 1)]
+fn reduce(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = this_object(ctx, this, "reduce")?;
+    let length = length_of(ctx, &obj)?;
+    let (callback, _) = callback(ctx, &args)?;
+
+    let mut start = 0;
+    let mut accumulator = match args.get(1) {
+        Some(initial) => initial.clone(),
+        None => {
+            while start < length && !has(&obj, start) {
+                start += 1;
+            }
+            if start == length {
+                let message = "Reduce of empty array with no initial value";
+                return Err(ctx.throw_error(ErrorKind::TypeError, message));
+            }
+            start += 1;
+            get(ctx, &obj, start - 1)?
+        }
+    };
+
+    for index in start..length {
+        if !has(&obj, index) {
+            continue;
+        }
+        let value = get(ctx, &obj, index)?;
+        let args = vec![
+            accumulator,
+            value,
+            JsValue::from(index),
+            JsValue::object(obj.clone()),
+        ];
+        accumulator = ctx.call(&callback, JsValue::Undefined, args)?;
+    }
+    Ok(accumulator)
+}
+
+#[js_function(length = 1)]
+fn find(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let mut found = JsValue::Undefined;
+    iterate(ctx, this, &args, "find", true, |_, value, result| {
+        if result.to_boolean() {
+            found = value;
+        }
+        Ok(result.to_boolean())
+    })?;
+    Ok(found)
+}
+
+#[js_function(length = 1)]
+fn find_index(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let found = iterate(ctx, this, &args, "findIndex", true, |_, _, result| {
+        Ok(result.to_boolean())
+    })?;
+    Ok(found.map_or(JsValue::integer(-1), JsValue::from))
+}
+
+#[js_function(length = 1)]
+fn some(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let found = iterate(ctx, this, &args, "some", false, |_, _, result| {
+        Ok(result.to_boolean())
+    })?;
+    Ok(JsValue::Boolean(found.is_some()))
+}
+
+#[js_function(length = 1)]
+fn every(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let found = iterate(ctx, this, &args, "every", false, |_, _, result| {
+        Ok(!result.to_boolean())
+    })?;
+    Ok(JsValue::Boolean(found.is_none()))
+}
+
+#[js_function(length = 1)]
+fn sort(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let comparator = args.first().cloned().unwrap_or_default();
+    if !matches!(&comparator, JsValue::Undefined)
+        && !matches!(&comparator, JsValue::Object(obj) if obj.is_function())
+    {
+        let message = "The comparison function must be either a function or undefined";
+        return Err(ctx.throw_error(ErrorKind::TypeError, message));
+    }
+
+    let obj = this_object(ctx, this, "sort")?;
+    let length = length_of(ctx, &obj)?;
+    let mut values = Vec::new();
+    let mut undefined_count = 0;
+    for index in 0..length {
+        if has(&obj, index) {
+            match get(ctx, &obj, index)? {
+                JsValue::Undefined => undefined_count += 1,
+                value => values.push(value),
+            }
+        }
+    }
+
+    // `undefined` then the holes go last
+    let sorted = merge_sort(ctx, values, &comparator)?;
+    let present = (sorted.len() + undefined_count) as u32;
+    let undefineds = std::iter::repeat_n(JsValue::Undefined, undefined_count);
+    for (index, value) in sorted.into_iter().chain(undefineds).enumerate() {
+        set(ctx, &obj, index as u32, value)?;
+    }
+    for index in present..length {
+        delete(ctx, &obj, index)?;
+    }
+    Ok(JsValue::object(obj))
+}
+
+fn iterator(
+    ctx: &mut Context,
+    this: JsValue,
+    method: &str,
+    kind: IterationKind,
+) -> ast::Result<JsValue> {
+    let obj = this_object(ctx, this, method)?;
+    Ok(JsValue::object(ArrayIterator::create(ctx, obj, kind)))
+}
+
+#[js_function]
+fn keys(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    iterator(ctx, this, "keys", IterationKind::Keys)
+}
+
+#[js_function]
+fn values(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    iterator(ctx, this, "values", IterationKind::Values)
+}
+
+#[js_function]
+fn entries(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    iterator(ctx, this, "entries", IterationKind::Entries)
+}
+
+// %ArrayIteratorPrototype%
+
+#[js_function(name = "next")]
+fn iterator_next(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let next = match this {
+        JsValue::Object(mut obj) => match &mut obj.kind {
+            ObjectKind::ArrayIterator(iterator) => iterator.next(ctx)?,
+            _ => return Err(incompatible_receiver(ctx)),
+        },
+        _ => return Err(incompatible_receiver(ctx)),
+    };
+
+    let done = next.is_none();
+    Ok(create_iter_result(ctx, next.unwrap_or_default(), done))
+}
+
+fn incompatible_receiver(ctx: &mut Context) -> ast::Completion {
+    let message = "Array Iterator.prototype.next called on incompatible receiver";
+    ctx.throw_error(ErrorKind::TypeError, message)
+}
@@ -0,0 +1,220 @@
+use std::{cmp::Ordering, fmt, ops::Deref, rc::Rc};
+
+use ::bigint::{indexing::BitIndexing, BigInt, BigUInt};
+
+use super::{ErrorKind, JsValue};
+use crate::{
+    ast::{self, BitwiseOp, NumericOp},
+    gc::Trace,
+    vm::Context,
+};
+
+/// A BigInt value, they can't be mutated so copies share the digits
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct JsBigInt(Rc<BigInt>);
+
+unsafe impl Trace for JsBigInt {}
+
+impl JsBigInt {
+    /// Longest BigInt an operation may create, in bits
+    pub const MAX_BITS: usize = 1 << 30;
+
+    pub fn new(value: BigInt) -> JsBigInt {
+        JsBigInt(Rc::new(value))
+    }
+
+    /// `StringToBigInt`, the empty string is `0n`, `None` if it isn't an integer
+    pub fn from_string(str: &str) -> Option<JsBigInt> {
+        let str = str.trim();
+        let radix = match str.get(..2) {
+            Some("0x" | "0X") => 16,
+            Some("0o" | "0O") => 8,
+            Some("0b" | "0B") => 2,
+            _ => 10,
+        };
+
+        if str.is_empty() {
+            return Some(JsBigInt::from(0));
+        }
+
+        // Prefixed literals have no sign
+        let (negative, digits) = match (radix, str.as_bytes()[0]) {
+            (10, b'-') => (true, &str[1..]),
+            (10, b'+') => (false, &str[1..]),
+            (10, _) => (false, str),
+            _ => (false, &str[2..]),
+        };
+        if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+            return None;
+        }
+
+        let magnitude = BigUInt::from_str_radix(digits, radix)?;
+        let value = BigInt::from_magnitude(negative, magnitude);
+        Some(JsBigInt::new(value))
+    }
+
+    /// `NumberToBigInt`, `None` for numbers with a fractional part
+    pub fn from_number(number: f64) -> Option<JsBigInt> {
+        if number.fract() != 0.0 {
+            return None;
+        }
+        BigInt::from_f64(number).map(JsBigInt::new)
+    }
+
+    /// Compare with a number exactly, `None` when it is `NaN`
+    pub fn compare_number(&self, number: f64) -> Option<Ordering> {
+        if number.is_nan() {
+            return None;
+        }
+        if number.is_infinite() {
+            return Some(if number > 0.0 {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            });
+        }
+
+        // `x` and `floor(n)` are integers, `x == floor(n)` is below `n` if `n` has a fraction
+        let floor = number.floor();
+        let ordering = self.0.as_ref().cmp(&BigInt::from_f64(floor).unwrap());
+        Some(if ordering.is_eq() && floor != number {
+            Ordering::Less
+        } else {
+            ordering
+        })
+    }
+}
+
+// Operators, the operands were already checked to both be BigInts
+impl JsBigInt {
+    pub fn numeric_op(
+        ctx: &mut Context,
+        op: &NumericOp,
+        left: &JsBigInt,
+        right: &JsBigInt,
+    ) -> ast::Result<JsValue> {
+        let value = match op {
+            NumericOp::Addition => &**left + &**right,
+            NumericOp::Subtraction => &**left - &**right,
+            NumericOp::Multiplication => {
+                if left.magnitude().bit_count() + right.magnitude().bit_count() > Self::MAX_BITS {
+                    return Err(too_big(ctx));
+                }
+                &**left * &**right
+            }
+            NumericOp::Division | NumericOp::Modulo => match left.div_rem(right) {
+                Some((quotient, _)) if op == &NumericOp::Division => quotient,
+                Some((_, remainder)) => remainder,
+                None => return Err(ctx.throw_error(ErrorKind::RangeError, "Division by zero")),
+            },
+            NumericOp::Exponent => left.exponent(ctx, right)?,
+        };
+        Ok(JsValue::BigInt(JsBigInt::new(value)))
+    }
+
+    fn exponent(&self, ctx: &mut Context, exponent: &JsBigInt) -> ast::Result<BigInt> {
+        if exponent.is_negative() {
+            let message = "Exponent must be non-negative";
+            return Err(ctx.throw_error(ErrorKind::RangeError, message));
+        }
+
+        // 0, 1 and -1 never grow
+        let bits = self.magnitude().bit_count();
+        if bits <= 1 {
+            let is_odd = exponent.magnitude().chunk_at(0) & 1 == 1;
+            return Ok(if exponent.is_zero() || (self.is_negative() && !is_odd) {
+                BigInt::from(1)
+            } else {
+                (**self).clone()
+            });
+        }
+
+        let exponent = exponent.magnitude();
+        if exponent.bit_count() > 32
+            || (bits - 1) * exponent.to_u64_clamp() as usize > Self::MAX_BITS
+        {
+            return Err(too_big(ctx));
+        }
+        Ok(self.pow(exponent.to_u64_clamp() as u32))
+    }
+
+    pub fn bitwise_op(
+        ctx: &mut Context,
+        op: &BitwiseOp,
+        left: &JsBigInt,
+        right: &JsBigInt,
+    ) -> ast::Result<JsValue> {
+        let value = match op {
+            BitwiseOp::And => &**left & &**right,
+            BitwiseOp::Or => &**left | &**right,
+            BitwiseOp::Xor => &**left ^ &**right,
+            BitwiseOp::ShiftLeft => left.shift(ctx, !right.is_negative(), right.magnitude())?,
+            BitwiseOp::ShiftRight => left.shift(ctx, right.is_negative(), right.magnitude())?,
+            BitwiseOp::UnsignedShiftRight => {
+                let message = "BigInts have no unsigned right shift, use >> instead";
+                return Err(ctx.throw_error(ErrorKind::TypeError, message));
+            }
+        };
+        Ok(JsValue::BigInt(JsBigInt::new(value)))
+    }
+
+    /// `self << amount` or `self >> amount`, a negative amount was already turned around
+    fn shift(&self, ctx: &mut Context, left: bool, amount: &BigUInt) -> ast::Result<BigInt> {
+        let amount = if amount.bit_count() > 32 {
+            usize::MAX
+        } else {
+            amount.to_u64_clamp() as usize
+        };
+
+        if !left {
+            return Ok(&**self >> amount);
+        }
+        if self.is_zero() {
+            return Ok(BigInt::new());
+        }
+        if amount > Self::MAX_BITS || self.magnitude().bit_count() + amount > Self::MAX_BITS {
+            return Err(too_big(ctx));
+        }
+        Ok(&**self << amount)
+    }
+
+    /// `-x`
+    pub fn negate(&self) -> JsBigInt {
+        JsBigInt::new(-&**self)
+    }
+
+    /// `~x`
+    pub fn not(&self) -> JsBigInt {
+        JsBigInt::new(!&**self)
+    }
+}
+
+fn too_big(ctx: &mut Context) -> ast::Completion {
+    ctx.throw_error(ErrorKind::RangeError, "Maximum BigInt size exceeded")
+}
+
+impl Deref for JsBigInt {
+    type Target = BigInt;
+
+    fn deref(&self) -> &BigInt {
+        &self.0
+    }
+}
+
+impl From<i64> for JsBigInt {
+    fn from(v: i64) -> Self {
+        JsBigInt::new(BigInt::from(v))
+    }
+}
+
+impl fmt::Display for JsBigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl fmt::Debug for JsBigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}n", self.0)
+    }
+}
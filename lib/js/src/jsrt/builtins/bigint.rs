@@ -0,0 +1,141 @@
+use super::{argument, define_to_string_tag, to_integer};
+use crate::{
+    ast,
+    jsrt::{js_function, ErrorKind, JsBigInt, JsObject, JsValue, ObjectKind, PreferredType},
+    vm::Context,
+};
+
+pub(super) fn install(ctx: &mut Context) {
+    let mut prototype = ctx.bigint_prototype();
+    for function in [TO_STRING, TO_LOCALE_STRING, VALUE_OF] {
+        prototype.define_method(ctx, function);
+    }
+    define_to_string_tag(ctx, &mut prototype, "BigInt");
+
+    let mut constructor = JsObject::native_constructor(ctx, CONSTRUCT, prototype);
+    for function in [AS_INT_N, AS_UINT_N] {
+        constructor.define_method(ctx, function);
+    }
+    ctx.define_global("BigInt", JsValue::object(constructor));
+}
+
+/// `ToBigInt`, numbers are not converted implicitly
+fn to_bigint(ctx: &mut Context, value: &JsValue) -> ast::Result<JsBigInt> {
+    match value.to_primitive(PreferredType::Number) {
+        JsValue::BigInt(v) => Ok(v),
+        JsValue::Boolean(b) => Ok(JsBigInt::from(b as i64)),
+        JsValue::String(s) => JsBigInt::from_string(&s.string).ok_or_else(|| {
+            let message = format!("Cannot convert {} to a BigInt", s.string);
+            ctx.throw_error(ErrorKind::SyntaxError, &message)
+        }),
+        JsValue::Symbol(_) => {
+            let message = "Cannot convert a Symbol value to a BigInt";
+            Err(ctx.throw_error(ErrorKind::TypeError, message))
+        }
+        value => {
+            let message = format!("Cannot convert {} to a BigInt", value.to_string());
+            Err(ctx.throw_error(ErrorKind::TypeError, &message))
+        }
+    }
+}
+
+/// `ToIndex`, the bit count of `asIntN` and `asUintN`
+fn to_index(ctx: &mut Context, value: &JsValue) -> ast::Result<usize> {
+    let index = to_integer(ctx, value)?;
+    if !(0.0..=9007199254740991.0).contains(&index) {
+        let message = "Invalid value: not (convertible to) a safe integer";
+        return Err(ctx.throw_error(ErrorKind::RangeError, message));
+    }
+    Ok(index as usize)
+}
+
+/// `thisBigIntValue`
+fn this_bigint(ctx: &mut Context, this: &JsValue, method: &str) -> ast::Result<JsBigInt> {
+    match this {
+        JsValue::BigInt(v) => Ok(v.clone()),
+        JsValue::Object(obj) => match &obj.kind {
+            ObjectKind::BigInt(v) => Ok(v.clone()),
+            _ => Err(incompatible_receiver(ctx, method)),
+        },
+        _ => Err(incompatible_receiver(ctx, method)),
+    }
+}
+
+fn incompatible_receiver(ctx: &mut Context, method: &str) -> ast::Completion {
+    let message = format!(
+        "BigInt.prototype.{} requires that 'this' be a BigInt",
+        method
+    );
+    ctx.throw_error(ErrorKind::TypeError, &message)
+}
+
+// BigInt
+
+/// Unlike the implicit conversions, `BigInt(1)` takes integral numbers
+#[js_function(name = "BigInt", length = 1)]
+fn construct(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    if ctx.is_constructing() {
+        return Err(ctx.throw_error(ErrorKind::TypeError, "BigInt is not a constructor"));
+    }
+    let value = argument(&args, 0).to_primitive(PreferredType::Number);
+    if !value.is_number() {
+        return Ok(JsValue::BigInt(to_bigint(ctx, &value)?));
+    }
+
+    match JsBigInt::from_number(value.as_f64()) {
+        Some(v) => Ok(JsValue::BigInt(v)),
+        None => {
+            let message = format!(
+                "The number {} cannot be converted to a BigInt because it is not an integer",
+                value.to_string()
+            );
+            Err(ctx.throw_error(ErrorKind::RangeError, &message))
+        }
+    }
+}
+
+#[js_function(name = "asIntN", length = 2)]
+fn as_int_n(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let bits = to_index(ctx, &argument(&args, 0))?;
+    let value = to_bigint(ctx, &argument(&args, 1))?;
+    Ok(JsValue::BigInt(JsBigInt::new(value.as_int_n(bits))))
+}
+
+#[js_function(name = "asUintN", length = 2)]
+fn as_uint_n(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let bits = to_index(ctx, &argument(&args, 0))?;
+    let value = to_bigint(ctx, &argument(&args, 1))?;
+    // The result of a negative value is about as long as `bits`
+    if value.is_negative() && bits > JsBigInt::MAX_BITS {
+        return Err(ctx.throw_error(ErrorKind::RangeError, "Maximum BigInt size exceeded"));
+    }
+    Ok(JsValue::BigInt(JsBigInt::new(value.as_uint_n(bits))))
+}
+
+// BigInt.prototype
+
+#[js_function]
+fn to_string(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let x = this_bigint(ctx, &this, "toString")?;
+    let radix = match argument(&args, 0) {
+        JsValue::Undefined => 10.0,
+        radix => to_integer(ctx, &radix)?,
+    };
+    if !(2.0..=36.0).contains(&radix) {
+        let message = "toString() radix must be between 2 and 36";
+        return Err(ctx.throw_error(ErrorKind::RangeError, message));
+    }
+
+    Ok(JsValue::string(x.to_string_radix(radix as u32).as_ref()))
+}
+
+#[js_function]
+fn to_locale_string(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let x = this_bigint(ctx, &this, "toLocaleString")?;
+    Ok(JsValue::string(x.to_string().as_ref()))
+}
+
+#[js_function]
+fn value_of(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    Ok(JsValue::BigInt(this_bigint(ctx, &this, "valueOf")?))
+}
@@ -0,0 +1,57 @@
+use super::argument;
+use crate::{
+    ast,
+    jsrt::{js_function, ErrorKind, JsObject, JsValue, ObjectKind},
+    vm::Context,
+};
+
+pub(super) fn install(ctx: &mut Context) {
+    let mut prototype = ctx.boolean_prototype();
+    for function in [TO_STRING, VALUE_OF] {
+        prototype.define_method(ctx, function);
+    }
+
+    let constructor = JsObject::native_constructor(ctx, CONSTRUCT, prototype);
+    ctx.define_global("Boolean", JsValue::object(constructor));
+}
+
+/// `thisBooleanValue`
+fn this_boolean(ctx: &mut Context, this: &JsValue, method: &str) -> ast::Result<bool> {
+    match this {
+        JsValue::Boolean(b) => Ok(*b),
+        JsValue::Object(obj) => match obj.kind {
+            ObjectKind::Boolean(b) => Ok(b),
+            _ => Err(incompatible_receiver(ctx, method)),
+        },
+        _ => Err(incompatible_receiver(ctx, method)),
+    }
+}
+
+fn incompatible_receiver(ctx: &mut Context, method: &str) -> ast::Completion {
+    let message = format!(
+        "Boolean.prototype.{} requires that 'this' be a Boolean",
+        method
+    );
+    ctx.throw_error(ErrorKind::TypeError, &message)
+}
+
+#[js_function(name = "Boolean", length = 1)]
+fn construct(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let value = JsValue::Boolean(argument(&args, 0).to_boolean());
+    // `new Boolean(x)` wraps the value
+    match ctx.is_constructing() {
+        true => Ok(JsValue::object(value.to_object(ctx)?)),
+        false => Ok(value),
+    }
+}
+
+#[js_function]
+fn to_string(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let b = this_boolean(ctx, &this, "toString")?;
+    Ok(JsValue::string(if b { "true" } else { "false" }))
+}
+
+#[js_function]
+fn value_of(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    Ok(JsValue::Boolean(this_boolean(ctx, &this, "valueOf")?))
+}
@@ -0,0 +1,55 @@
+use utils::prelude::{debug, error, info, warn};
+
+use super::namespace;
+use crate::{
+    ast,
+    jsrt::{inspect, js_function, JsValue},
+    vm::Context,
+};
+
+pub(super) fn install(ctx: &mut Context) {
+    let console = namespace(ctx, &[LOG, INFO, WARN, ERROR, DEBUG]);
+    ctx.define_global("console", JsValue::object(console));
+}
+
+/// The arguments separated by spaces, strings without their quotes and other values inspected
+fn message(ctx: &Context, args: &[JsValue]) -> String {
+    let parts: Vec<_> = args
+        .iter()
+        .map(|arg| match arg {
+            JsValue::String(s) => s.string.to_string(),
+            arg => inspect(ctx, arg),
+        })
+        .collect();
+    parts.join(" ")
+}
+
+#[js_function]
+fn log(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    info!("{}", message(ctx, &args));
+    Ok(JsValue::Undefined)
+}
+
+#[js_function]
+fn info(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    info!("{}", message(ctx, &args));
+    Ok(JsValue::Undefined)
+}
+
+#[js_function]
+fn warn(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    warn!("{}", message(ctx, &args));
+    Ok(JsValue::Undefined)
+}
+
+#[js_function]
+fn error(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    error!("{}", message(ctx, &args));
+    Ok(JsValue::Undefined)
+}
+
+#[js_function]
+fn debug(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    debug!("{}", message(ctx, &args));
+    Ok(JsValue::Undefined)
+}
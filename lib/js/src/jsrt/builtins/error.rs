@@ -0,0 +1,36 @@
+use crate::{
+    ast,
+    jsrt::{js_function, ErrorKind, JsValue, PropertyKey},
+    vm::Context,
+};
+
+pub(super) fn install(ctx: &mut Context) {
+    let mut prototype = ctx.error_prototype(ErrorKind::Error);
+    prototype.define_method(ctx, TO_STRING);
+}
+
+#[js_function]
+fn to_string(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    if !matches!(this, JsValue::Object(_)) {
+        let message = "Error.prototype.toString requires that 'this' be an Object";
+        return Err(ctx.throw_error(ErrorKind::TypeError, message));
+    }
+
+    let name = match this.get_property(ctx, &PropertyKey::from("name"))? {
+        JsValue::Undefined => "Error".to_string(),
+        name => name.to_string_with(ctx)?,
+    };
+    let message = match this.get_property(ctx, &PropertyKey::from("message"))? {
+        JsValue::Undefined => String::new(),
+        message => message.to_string_with(ctx)?,
+    };
+
+    Ok(JsValue::string(
+        match (name.is_empty(), message.is_empty()) {
+            (_, true) => name,
+            (true, false) => message,
+            (false, false) => format!("{}: {}", name, message),
+        }
+        .as_ref(),
+    ))
+}
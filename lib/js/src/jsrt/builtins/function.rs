@@ -0,0 +1,132 @@
+use super::{argument, define_symbol_method};
+use crate::{
+    ast::{self, ASTNode, BinaryOperation},
+    jsrt::{
+        js_function, list_from_array_like, Attributes, BoundFunction, ErrorKind, JsObject, JsValue,
+        ObjectKind, PreferredType,
+    },
+    parser::Parser,
+    vm::Context,
+};
+
+pub(super) fn install(ctx: &mut Context) {
+    let mut prototype = ctx.function_prototype();
+    for function in [CALL, APPLY, BIND, TO_STRING] {
+        prototype.define_method(ctx, function);
+    }
+    let has_instance = ctx.well_known_symbols().has_instance.clone();
+    define_symbol_method(
+        ctx,
+        &mut prototype,
+        has_instance,
+        HAS_INSTANCE,
+        Attributes::empty(),
+    );
+
+    let constructor = JsObject::native_constructor(ctx, CONSTRUCT, prototype);
+    ctx.define_global("Function", JsValue::object(constructor));
+}
+
+/// `Function(...params, body)`, compiled in the global scope
+#[js_function(name = "Function", length = 1)]
+fn construct(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let mut args: Vec<_> = args.iter().map(JsValue::to_string).collect();
+    let body = args.pop().unwrap_or_default();
+    let source = format!(
+        "(function anonymous({}\n) {{\n{}\n}});",
+        args.join(","),
+        body
+    );
+
+    let program = match Parser::new(&source).parse_program() {
+        Ok(program) => program,
+        Err(err) => return Err(ctx.throw_error(ErrorKind::SyntaxError, &err.to_string())),
+    };
+    let global_environment = ctx.global_environment();
+    let environment = ctx.set_environment(global_environment);
+    let environment = ctx.root(environment);
+    let function = program.eval(ctx);
+    ctx.set_environment((*environment).clone());
+    function
+}
+
+#[js_function(length = 1)]
+fn call(ctx: &mut Context, this: JsValue, mut args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let this_arg = if args.is_empty() {
+        JsValue::Undefined
+    } else {
+        args.remove(0)
+    };
+    ctx.call(&this, this_arg, args)
+}
+
+#[js_function(length = 2)]
+fn apply(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let this_arg = argument(&args, 0);
+    let args = match argument(&args, 1) {
+        JsValue::Undefined | JsValue::Null => Vec::new(),
+        JsValue::Object(obj) => list_from_array_like(ctx, &obj)?,
+        _ => {
+            let message = "CreateListFromArrayLike called on non-object";
+            return Err(ctx.throw_error(ErrorKind::TypeError, message));
+        }
+    };
+    ctx.call(&this, this_arg, args)
+}
+
+#[js_function(length = 1)]
+fn bind(ctx: &mut Context, this: JsValue, mut args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let target = match this {
+        JsValue::Object(obj) if obj.is_function() => obj,
+        _ => return Err(ctx.throw_error(ErrorKind::TypeError, "Bind must be called on a function")),
+    };
+    let this_arg = if args.is_empty() {
+        JsValue::Undefined
+    } else {
+        args.remove(0)
+    };
+
+    let length = match target.get_data_property("length") {
+        length if length.is_number() => (length.as_f64() - args.len() as f64).max(0.0),
+        _ => 0.0,
+    };
+    let name = format!("bound {}", target.get_data_property("name").to_string());
+    let bound = BoundFunction {
+        target: target.clone(),
+        this: this_arg,
+        args,
+    };
+
+    let prototype = target.get_prototype_of();
+    let mut function = JsObject::with_prototype(ctx, prototype, ObjectKind::BoundFunction(bound));
+    function.define_property("length", JsValue::from(length), Attributes::CONFIGURABLE);
+    function.define_property(
+        "name",
+        JsValue::string(name.as_ref()),
+        Attributes::CONFIGURABLE,
+    );
+    Ok(JsValue::object(function))
+}
+
+/// The body of functions isn't kept around, they all look native
+#[js_function]
+fn to_string(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    match &this {
+        JsValue::Object(obj) if obj.is_function() => Ok(this.to_primitive(PreferredType::String)),
+        _ => {
+            let message = "Function.prototype.toString requires that 'this' be a Function";
+            Err(ctx.throw_error(ErrorKind::TypeError, message))
+        }
+    }
+}
+
+/// What `instanceof` does unless a constructor has its own `Symbol.hasInstance`
+#[js_function(name = "[Symbol.hasInstance]", length = 1)]
+fn has_instance(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    Ok(JsValue::Boolean(match &this {
+        JsValue::Object(obj) if obj.is_function() => {
+            BinaryOperation::ordinary_has_instance(obj, &argument(&args, 0), ctx)?
+        }
+        _ => false,
+    }))
+}
@@ -0,0 +1,52 @@
+//! `%GeneratorPrototype%` and the prototypes of generator and async functions, which aren't
+//! reachable from the global object
+use super::{argument, define_to_string_tag};
+use crate::{
+    ast,
+    jsrt::{js_function, Attributes, JsGenerator, JsValue},
+    vm::{Context, Resume},
+};
+
+pub(super) fn install(ctx: &mut Context) {
+    let mut prototype = ctx.generator_prototype();
+    for function in [NEXT, RETURN, THROW] {
+        prototype.define_method(ctx, function);
+    }
+    define_to_string_tag(ctx, &mut prototype, "Generator");
+
+    let mut function_prototype = ctx.generator_function_prototype();
+    function_prototype.define_property(
+        "prototype",
+        JsValue::object(prototype.clone()),
+        Attributes::CONFIGURABLE,
+    );
+    prototype.define_property(
+        "constructor",
+        JsValue::object(function_prototype.clone()),
+        Attributes::CONFIGURABLE,
+    );
+    define_to_string_tag(ctx, &mut function_prototype, "GeneratorFunction");
+
+    let mut async_function_prototype = ctx.async_function_prototype();
+    define_to_string_tag(ctx, &mut async_function_prototype, "AsyncFunction");
+}
+
+#[js_function(length = 1)]
+fn next(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let resumed = Resume::Next(argument(&args, 0));
+    JsGenerator::resume_generator(ctx, &this, resumed, "Generator.prototype.next")
+}
+
+/// Run the `finally` blocks around the `yield` the generator is suspended at, then complete
+#[js_function(length = 1)]
+fn r#return(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let resumed = Resume::Return(argument(&args, 0));
+    JsGenerator::resume_generator(ctx, &this, resumed, "Generator.prototype.return")
+}
+
+/// Throw at the `yield` the generator is suspended at
+#[js_function(length = 1)]
+fn throw(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let resumed = Resume::Throw(argument(&args, 0));
+    JsGenerator::resume_generator(ctx, &this, resumed, "Generator.prototype.throw")
+}
@@ -0,0 +1,132 @@
+use super::argument;
+use crate::{
+    ast,
+    jsrt::{js_function, Attributes, JsValue},
+    vm::Context,
+};
+
+pub(super) fn install(ctx: &mut Context) {
+    let mut global = ctx.global_object();
+    for (name, value) in [
+        ("undefined", JsValue::Undefined),
+        ("NaN", JsValue::nan()),
+        ("Infinity", JsValue::Rational(f64::INFINITY)),
+    ] {
+        global.define_property(name, value, Attributes::empty());
+    }
+    ctx.define_global("globalThis", JsValue::object(global.clone()));
+
+    for function in [PARSE_INT, PARSE_FLOAT, IS_NAN, IS_FINITE] {
+        global.define_method(ctx, function);
+    }
+}
+
+/// Whitespace and line terminators, which `trim` and the number parsers skip
+pub(super) fn is_js_whitespace(c: char) -> bool {
+    (c.is_whitespace() && c != '\u{85}') || c == '\u{feff}'
+}
+
+#[js_function(length = 2)]
+fn parse_int(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let input = argument(&args, 0).to_string();
+    let input = input.trim_start_matches(is_js_whitespace);
+    let (sign, input) = match input.as_bytes().first() {
+        Some(b'-') => (-1.0, &input[1..]),
+        Some(b'+') => (1.0, &input[1..]),
+        _ => (1.0, input),
+    };
+
+    // A radix of 0 means 10, or 16 with a `0x` prefix
+    let mut radix = argument(&args, 1).to_number_with(ctx)?.to_i32() as u32;
+    let mut digits = input;
+    if radix == 0 || radix == 16 {
+        if let Some(hex) = input
+            .strip_prefix("0x")
+            .or_else(|| input.strip_prefix("0X"))
+        {
+            digits = hex;
+            radix = 16;
+        }
+    }
+    if radix == 0 {
+        radix = 10;
+    } else if !(2..=36).contains(&radix) {
+        return Ok(JsValue::nan());
+    }
+
+    let mut value = None;
+    for c in digits.chars() {
+        match c.to_digit(radix) {
+            Some(digit) => value = Some(value.unwrap_or(0.0) * radix as f64 + digit as f64),
+            None => break,
+        }
+    }
+    Ok(value.map_or_else(JsValue::nan, |value| JsValue::from(sign * value)))
+}
+
+#[js_function(length = 1)]
+fn parse_float(_ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let input = argument(&args, 0).to_string();
+    let input = input.trim_start_matches(is_js_whitespace);
+    let bytes = input.as_bytes();
+
+    let mut end = 0;
+    if matches!(bytes.first(), Some(b'+' | b'-')) {
+        end += 1;
+    }
+    if input[end..].starts_with("Infinity") {
+        let value = if bytes[0] == b'-' {
+            f64::NEG_INFINITY
+        } else {
+            f64::INFINITY
+        };
+        return Ok(JsValue::from(value));
+    }
+
+    // Longest prefix that is a decimal literal
+    let digits = |from: usize| {
+        from + bytes[from..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+    };
+    let integer_end = digits(end);
+    let mut has_digits = integer_end > end;
+    end = integer_end;
+    if bytes.get(end) == Some(&b'.') {
+        let fraction_end = digits(end + 1);
+        if has_digits || fraction_end > end + 1 {
+            has_digits = true;
+            end = fraction_end;
+        }
+    }
+    if !has_digits {
+        return Ok(JsValue::nan());
+    }
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let mut exponent = end + 1;
+        if matches!(bytes.get(exponent), Some(b'+' | b'-')) {
+            exponent += 1;
+        }
+        let exponent_end = digits(exponent);
+        if exponent_end > exponent {
+            end = exponent_end;
+        }
+    }
+
+    Ok(input[..end]
+        .parse::<f64>()
+        .map_or_else(|_| JsValue::nan(), JsValue::from))
+}
+
+#[js_function(name = "isNaN", length = 1)]
+fn is_nan(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let number = argument(&args, 0).to_number_with(ctx)?.as_f64();
+    Ok(JsValue::Boolean(number.is_nan()))
+}
+
+#[js_function(length = 1)]
+fn is_finite(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let number = argument(&args, 0).to_number_with(ctx)?.as_f64();
+    Ok(JsValue::Boolean(number.is_finite()))
+}
@@ -0,0 +1,24 @@
+use super::define_symbol_method;
+use crate::{
+    ast,
+    jsrt::{js_function, Attributes, JsValue},
+    vm::Context,
+};
+
+pub(super) fn install(ctx: &mut Context) {
+    let mut prototype = ctx.iterator_prototype();
+    let iterator = ctx.well_known_symbols().iterator.clone();
+    define_symbol_method(
+        ctx,
+        &mut prototype,
+        iterator,
+        ITERATOR,
+        Attributes::WRITABLE | Attributes::CONFIGURABLE,
+    );
+}
+
+/// Iterators are iterable themselves, so `for of` takes them as well
+#[js_function(name = "[Symbol.iterator]")]
+fn iterator(_ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    Ok(this)
+}
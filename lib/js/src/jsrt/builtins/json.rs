@@ -0,0 +1,484 @@
+use std::iter::Peekable;
+
+use super::{argument, define_to_string_tag, namespace, to_integer};
+use crate::{
+    ast,
+    gc::GcPointer,
+    jsrt::{
+        js_function, list_from_array_like, ErrorKind, JsObject, JsValue, ObjectKind, PropertyKey,
+    },
+    vm::Context,
+};
+
+pub(super) fn install(ctx: &mut Context) {
+    let mut json = namespace(ctx, &[PARSE, STRINGIFY]);
+    define_to_string_tag(ctx, &mut json, "JSON");
+    ctx.define_global("JSON", JsValue::object(json));
+}
+
+/// Recursive descent over the text given to `JSON.parse`
+struct JsonParser<I: Iterator<Item = (usize, char)>> {
+    chars: Peekable<I>,
+}
+
+impl<I: Iterator<Item = (usize, char)>> JsonParser<I> {
+    fn skip_whitespace(&mut self) {
+        while let Some((_, ' ' | '\t' | '\n' | '\r')) = self.chars.peek() {
+            self.chars.next();
+        }
+    }
+
+    fn error(&mut self, ctx: &mut Context) -> ast::Completion {
+        let message = match self.chars.next() {
+            Some((position, c)) => {
+                format!("Unexpected token {} in JSON at position {}", c, position)
+            }
+            None => "Unexpected end of JSON input".to_string(),
+        };
+        ctx.throw_error(ErrorKind::SyntaxError, &message)
+    }
+
+    fn expect(&mut self, ctx: &mut Context, expected: char) -> ast::Result<()> {
+        match self.chars.peek() {
+            Some((_, c)) if *c == expected => {
+                self.chars.next();
+                Ok(())
+            }
+            _ => Err(self.error(ctx)),
+        }
+    }
+
+    fn parse_value(&mut self, ctx: &mut Context) -> ast::Result<JsValue> {
+        self.skip_whitespace();
+        let value = match self.chars.peek().map(|(_, c)| *c) {
+            Some('{') => self.parse_object(ctx)?,
+            Some('[') => self.parse_array(ctx)?,
+            Some('"') => JsValue::string(self.parse_string(ctx)?.as_ref()),
+            Some('-' | '0'..='9') => self.parse_number(ctx)?,
+            Some('t') => self.parse_literal(ctx, "true", JsValue::Boolean(true))?,
+            Some('f') => self.parse_literal(ctx, "false", JsValue::Boolean(false))?,
+            Some('n') => self.parse_literal(ctx, "null", JsValue::Null)?,
+            _ => return Err(self.error(ctx)),
+        };
+        self.skip_whitespace();
+        Ok(value)
+    }
+
+    fn parse_literal(
+        &mut self,
+        ctx: &mut Context,
+        word: &str,
+        value: JsValue,
+    ) -> ast::Result<JsValue> {
+        for expected in word.chars() {
+            self.expect(ctx, expected)?;
+        }
+        Ok(value)
+    }
+
+    fn parse_number(&mut self, ctx: &mut Context) -> ast::Result<JsValue> {
+        let mut text = String::new();
+        let digits = |parser: &mut Self, text: &mut String| {
+            let start = text.len();
+            while let Some((_, c @ '0'..='9')) = parser.chars.peek() {
+                text.push(*c);
+                parser.chars.next();
+            }
+            text.len() > start
+        };
+
+        if let Some((_, '-')) = self.chars.peek() {
+            text.push('-');
+            self.chars.next();
+        }
+        // No leading zero
+        if let Some((_, '0')) = self.chars.peek() {
+            text.push('0');
+            self.chars.next();
+        } else if !digits(self, &mut text) {
+            return Err(self.error(ctx));
+        }
+        if let Some((_, '.')) = self.chars.peek() {
+            text.push('.');
+            self.chars.next();
+            if !digits(self, &mut text) {
+                return Err(self.error(ctx));
+            }
+        }
+        if let Some((_, 'e' | 'E')) = self.chars.peek() {
+            text.push('e');
+            self.chars.next();
+            if let Some((_, c @ ('+' | '-'))) = self.chars.peek() {
+                text.push(*c);
+                self.chars.next();
+            }
+            if !digits(self, &mut text) {
+                return Err(self.error(ctx));
+            }
+        }
+        Ok(JsValue::number_from_str(&text))
+    }
+
+    fn parse_string(&mut self, ctx: &mut Context) -> ast::Result<String> {
+        self.expect(ctx, '"')?;
+        let mut units = Vec::new();
+        loop {
+            match self.chars.peek().map(|(_, c)| *c) {
+                Some('"') => break,
+                Some('\\') => {
+                    self.chars.next();
+                    let unit = match self.chars.peek().map(|(_, c)| *c) {
+                        Some('"') => '"' as u16,
+                        Some('\\') => '\\' as u16,
+                        Some('/') => '/' as u16,
+                        Some('b') => 0x08,
+                        Some('f') => 0x0c,
+                        Some('n') => '\n' as u16,
+                        Some('r') => '\r' as u16,
+                        Some('t') => '\t' as u16,
+                        Some('u') => {
+                            self.chars.next();
+                            let mut unit = 0;
+                            for _ in 0..4 {
+                                match self.chars.peek().and_then(|(_, c)| c.to_digit(16)) {
+                                    Some(digit) => unit = unit * 16 + digit as u16,
+                                    None => return Err(self.error(ctx)),
+                                }
+                                self.chars.next();
+                            }
+                            units.push(unit);
+                            continue;
+                        }
+                        _ => return Err(self.error(ctx)),
+                    };
+                    self.chars.next();
+                    units.push(unit);
+                }
+                Some(c) if c >= ' ' => {
+                    self.chars.next();
+                    let mut buffer = [0; 2];
+                    units.extend_from_slice(c.encode_utf16(&mut buffer));
+                }
+                _ => return Err(self.error(ctx)),
+            }
+        }
+        self.chars.next();
+        Ok(String::from_utf16_lossy(&units))
+    }
+
+    fn parse_array(&mut self, ctx: &mut Context) -> ast::Result<JsValue> {
+        self.expect(ctx, '[')?;
+        self.skip_whitespace();
+        let mut values = Vec::new();
+        if let Some((_, ']')) = self.chars.peek() {
+            self.chars.next();
+            return Ok(JsValue::object(JsObject::array(ctx, values)));
+        }
+        loop {
+            values.push(self.parse_value(ctx)?);
+            match self.chars.peek() {
+                Some((_, ',')) => self.chars.next(),
+                _ => break,
+            };
+        }
+        self.expect(ctx, ']')?;
+        Ok(JsValue::object(JsObject::array(ctx, values)))
+    }
+
+    fn parse_object(&mut self, ctx: &mut Context) -> ast::Result<JsValue> {
+        self.expect(ctx, '{')?;
+        self.skip_whitespace();
+        let mut obj = JsObject::new(ctx);
+        if let Some((_, '}')) = self.chars.peek() {
+            self.chars.next();
+            return Ok(JsValue::object(obj));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string(ctx)?;
+            self.skip_whitespace();
+            self.expect(ctx, ':')?;
+            let value = self.parse_value(ctx)?;
+            obj.create_data_property(PropertyKey::from(key.as_ref()), value);
+            match self.chars.peek() {
+                Some((_, ',')) => self.chars.next(),
+                _ => break,
+            };
+        }
+        self.expect(ctx, '}')?;
+        Ok(JsValue::object(obj))
+    }
+}
+
+/// `InternalizeJSONProperty`, passes every value to the reviver, the innermost first
+fn internalize(
+    ctx: &mut Context,
+    holder: &GcPointer<JsObject>,
+    key: PropertyKey,
+    reviver: &JsValue,
+) -> ast::Result<JsValue> {
+    let value = holder.get(ctx, &key, JsValue::object(holder.clone()))?;
+    if let JsValue::Object(obj) = &value {
+        let keys: Vec<_> = match &obj.kind {
+            ObjectKind::Array(array) => (0..array.length()).map(PropertyKey::from).collect(),
+            _ => obj
+                .own_property_keys()
+                .into_iter()
+                .filter(|key| {
+                    !key.is_symbol()
+                        && obj
+                            .get_own_property(key)
+                            .is_some_and(|desc| desc.enumerable())
+                })
+                .collect(),
+        };
+        for key in keys {
+            let element = internalize(ctx, obj, key.clone(), reviver)?;
+            let mut obj = obj.clone();
+            if element.is_undefined() {
+                obj.delete(&key);
+            } else {
+                obj.create_data_property(key, element);
+            }
+        }
+    }
+    ctx.call(
+        reviver,
+        JsValue::object(holder.clone()),
+        vec![key.to_value(), value],
+    )
+}
+
+#[js_function(length = 2)]
+fn parse(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let text = argument(&args, 0).to_string();
+    let mut parser = JsonParser {
+        chars: text.chars().enumerate().peekable(),
+    };
+    let value = parser.parse_value(ctx)?;
+    if parser.chars.peek().is_some() {
+        return Err(parser.error(ctx));
+    }
+
+    match argument(&args, 1) {
+        JsValue::Object(reviver) if reviver.is_function() => {
+            let mut root = JsObject::new(ctx);
+            root.create_data_property(PropertyKey::from(""), value);
+            internalize(ctx, &root, PropertyKey::from(""), &JsValue::object(reviver))
+        }
+        _ => Ok(value),
+    }
+}
+
+/// State of a `JSON.stringify` call
+struct Serializer {
+    replacer: Option<JsValue>,
+    property_list: Option<Vec<PropertyKey>>,
+    gap: String,
+    indent: String,
+    /// Objects being serialized, to detect cycles
+    stack: Vec<GcPointer<JsObject>>,
+}
+
+impl Serializer {
+    /// `SerializeJSONProperty`, `None` for values that are left out
+    fn property(
+        &mut self,
+        ctx: &mut Context,
+        holder: &GcPointer<JsObject>,
+        key: PropertyKey,
+    ) -> ast::Result<Option<String>> {
+        let mut value = holder.get(ctx, &key, JsValue::object(holder.clone()))?;
+        if let JsValue::Object(_) | JsValue::BigInt(_) = value {
+            let to_json = value.get_property(ctx, &PropertyKey::from("toJSON"))?;
+            if matches!(&to_json, JsValue::Object(f) if f.is_function()) {
+                value = ctx.call(&to_json, value, vec![key.to_value()])?;
+            }
+        }
+        if let Some(replacer) = &self.replacer {
+            let args = vec![key.to_value(), value];
+            value = ctx.call(replacer, JsValue::object(holder.clone()), args)?;
+        }
+
+        if let JsValue::Object(obj) = &value {
+            match &obj.kind {
+                ObjectKind::Number(n) => value = JsValue::from(*n),
+                ObjectKind::String(s) => value = JsValue::String(s.clone()),
+                ObjectKind::Boolean(b) => value = JsValue::Boolean(*b),
+                ObjectKind::BigInt(v) => value = JsValue::BigInt(v.clone()),
+                _ => {}
+            }
+        }
+
+        Ok(Some(match value {
+            JsValue::Null => "null".to_string(),
+            JsValue::Boolean(b) => b.to_string(),
+            JsValue::String(s) => quote(&s.string),
+            value if value.is_number() => match value.as_f64() {
+                n if n.is_finite() => value.to_string(),
+                _ => "null".to_string(),
+            },
+            JsValue::BigInt(_) => {
+                let message = "Do not know how to serialize a BigInt";
+                return Err(ctx.throw_error(ErrorKind::TypeError, message));
+            }
+            JsValue::Object(obj) if !obj.is_function() => {
+                if self.stack.iter().any(|o| o.ptr_eq(&obj)) {
+                    let message = "Converting circular structure to JSON";
+                    return Err(ctx.throw_error(ErrorKind::TypeError, message));
+                }
+                self.stack.push(obj.clone());
+                let stepback = self.indent.clone();
+                self.indent.push_str(&self.gap);
+                let result = if obj.is_array() {
+                    self.array(ctx, &obj)
+                } else {
+                    self.object(ctx, &obj)
+                };
+                self.indent = stepback;
+                self.stack.pop();
+                result?
+            }
+            _ => return Ok(None),
+        }))
+    }
+
+    fn object(&mut self, ctx: &mut Context, obj: &GcPointer<JsObject>) -> ast::Result<String> {
+        let keys = match &self.property_list {
+            Some(keys) => keys.clone(),
+            None => obj
+                .own_property_keys()
+                .into_iter()
+                .filter(|key| {
+                    !key.is_symbol()
+                        && obj
+                            .get_own_property(key)
+                            .is_some_and(|desc| desc.enumerable())
+                })
+                .collect(),
+        };
+
+        let separator = if self.gap.is_empty() { ":" } else { ": " };
+        let mut members = Vec::new();
+        for key in keys {
+            if let Some(value) = self.property(ctx, obj, key.clone())? {
+                members.push(format!("{}{}{}", quote(&key.to_string()), separator, value));
+            }
+        }
+        Ok(self.wrap('{', members, '}'))
+    }
+
+    fn array(&mut self, ctx: &mut Context, obj: &GcPointer<JsObject>) -> ast::Result<String> {
+        let length = match &obj.kind {
+            ObjectKind::Array(array) => array.length(),
+            _ => 0,
+        };
+        let mut elements = Vec::new();
+        for index in 0..length {
+            let element = self.property(ctx, obj, PropertyKey::from(index))?;
+            elements.push(element.unwrap_or_else(|| "null".to_string()));
+        }
+        Ok(self.wrap('[', elements, ']'))
+    }
+
+    /// Join `items` on one line, or one per line when indenting
+    fn wrap(&self, open: char, items: Vec<String>, close: char) -> String {
+        if items.is_empty() {
+            format!("{}{}", open, close)
+        } else if self.gap.is_empty() {
+            format!("{}{}{}", open, items.join(","), close)
+        } else {
+            let stepback = &self.indent[..self.indent.len() - self.gap.len()];
+            let separator = format!(",\n{}", self.indent);
+            format!(
+                "{}\n{}{}\n{}{}",
+                open,
+                self.indent,
+                items.join(&separator),
+                stepback,
+                close
+            )
+        }
+    }
+}
+
+/// `QuoteJSONString`
+fn quote(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\u{8}' => result.push_str("\\b"),
+            '\u{c}' => result.push_str("\\f"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c < ' ' => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+#[js_function(length = 3)]
+fn stringify(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let mut replacer = None;
+    let mut property_list = None;
+    match argument(&args, 1) {
+        JsValue::Object(f) if f.is_function() => replacer = Some(JsValue::object(f)),
+        JsValue::Object(list) if list.is_array() => {
+            let mut keys: Vec<PropertyKey> = Vec::new();
+            for value in list_from_array_like(ctx, &list)? {
+                let key = match &value {
+                    JsValue::String(_) => value,
+                    value if value.is_number() => JsValue::string(value.to_string().as_ref()),
+                    JsValue::Object(obj)
+                        if matches!(obj.kind, ObjectKind::Number(_) | ObjectKind::String(_)) =>
+                    {
+                        JsValue::string(value.to_string().as_ref())
+                    }
+                    _ => continue,
+                };
+                let key = PropertyKey::from_value(&key);
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+            property_list = Some(keys);
+        }
+        _ => {}
+    }
+
+    let space = match argument(&args, 2) {
+        JsValue::Object(obj) => match &obj.kind {
+            ObjectKind::Number(n) => JsValue::from(*n),
+            ObjectKind::String(s) => JsValue::String(s.clone()),
+            _ => JsValue::Undefined,
+        },
+        space => space,
+    };
+    let gap = match &space {
+        JsValue::String(s) => s.substring(0, 10).string.to_string(),
+        space if space.is_number() => " ".repeat(to_integer(ctx, space)?.clamp(0.0, 10.0) as usize),
+        _ => String::new(),
+    };
+
+    let mut serializer = Serializer {
+        replacer,
+        property_list,
+        gap,
+        indent: String::new(),
+        stack: Vec::new(),
+    };
+    let mut wrapper = JsObject::new(ctx);
+    wrapper.create_data_property(PropertyKey::from(""), argument(&args, 0));
+    Ok(
+        match serializer.property(ctx, &wrapper, PropertyKey::from(""))? {
+            Some(json) => JsValue::string(json.as_ref()),
+            None => JsValue::Undefined,
+        },
+    )
+}
@@ -0,0 +1,277 @@
+use super::{argument, define_constants, define_to_string_tag, namespace};
+use crate::{
+    ast,
+    jsrt::{js_function, JsValue},
+    vm::Context,
+};
+
+pub(super) fn install(ctx: &mut Context) {
+    let mut math = namespace(
+        ctx,
+        &[
+            ABS, ACOS, ACOSH, ASIN, ASINH, ATAN, ATANH, ATAN2, CBRT, CEIL, CLZ32, COS, COSH, EXP,
+            EXPM1, FLOOR, FROUND, HYPOT, IMUL, LOG, LOG1P, LOG10, LOG2, MAX, MIN, POW, RANDOM,
+            ROUND, SIGN, SIN, SINH, SQRT, TAN, TANH, TRUNC,
+        ],
+    );
+    define_constants(
+        &mut math,
+        &[
+            ("E", std::f64::consts::E),
+            ("LN10", std::f64::consts::LN_10),
+            ("LN2", std::f64::consts::LN_2),
+            ("LOG10E", std::f64::consts::LOG10_E),
+            ("LOG2E", std::f64::consts::LOG2_E),
+            ("PI", std::f64::consts::PI),
+            ("SQRT1_2", std::f64::consts::FRAC_1_SQRT_2),
+            ("SQRT2", std::f64::consts::SQRT_2),
+        ],
+    );
+    define_to_string_tag(ctx, &mut math, "Math");
+    ctx.define_global("Math", JsValue::object(math));
+}
+
+/// `f(ToNumber(args[0]))`
+fn unary(ctx: &mut Context, args: &[JsValue], f: fn(f64) -> f64) -> ast::Result<JsValue> {
+    Ok(JsValue::from(f(number(ctx, args, 0)?)))
+}
+
+/// `ToNumber(args[index])`
+fn number(ctx: &mut Context, args: &[JsValue], index: usize) -> ast::Result<f64> {
+    Ok(argument(args, index).to_number_with(ctx)?.as_f64())
+}
+
+fn numbers(ctx: &mut Context, args: &[JsValue]) -> ast::Result<Vec<f64>> {
+    args.iter()
+        .map(|arg| Ok(arg.to_number_with(ctx)?.as_f64()))
+        .collect()
+}
+
+#[js_function(length = 1)]
+fn abs(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::abs)
+}
+
+#[js_function(length = 1)]
+fn acos(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::acos)
+}
+
+#[js_function(length = 1)]
+fn acosh(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::acosh)
+}
+
+#[js_function(length = 1)]
+fn asin(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::asin)
+}
+
+#[js_function(length = 1)]
+fn asinh(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::asinh)
+}
+
+#[js_function(length = 1)]
+fn atan(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::atan)
+}
+
+#[js_function(length = 1)]
+fn atanh(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::atanh)
+}
+
+#[js_function(length = 2)]
+fn atan2(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let y = number(ctx, &args, 0)?;
+    let x = number(ctx, &args, 1)?;
+    Ok(JsValue::from(y.atan2(x)))
+}
+
+#[js_function(length = 1)]
+fn cbrt(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::cbrt)
+}
+
+#[js_function(length = 1)]
+fn ceil(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::ceil)
+}
+
+#[js_function(length = 1)]
+fn clz32(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let x = argument(&args, 0).to_number_with(ctx)?.to_u32();
+    Ok(JsValue::from(x.leading_zeros()))
+}
+
+#[js_function(length = 1)]
+fn cos(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::cos)
+}
+
+#[js_function(length = 1)]
+fn cosh(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::cosh)
+}
+
+#[js_function(length = 1)]
+fn exp(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::exp)
+}
+
+#[js_function(length = 1)]
+fn expm1(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::exp_m1)
+}
+
+#[js_function(length = 1)]
+fn floor(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::floor)
+}
+
+#[js_function(length = 1)]
+fn fround(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, |x| x as f32 as f64)
+}
+
+#[js_function(length = 2)]
+fn hypot(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let values = numbers(ctx, &args)?;
+    // An infinity wins over `NaN`
+    let result = if values.iter().any(|x| x.is_infinite()) {
+        f64::INFINITY
+    } else {
+        values.into_iter().fold(0.0, f64::hypot)
+    };
+    Ok(JsValue::from(result))
+}
+
+#[js_function(length = 2)]
+fn imul(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let a = argument(&args, 0).to_number_with(ctx)?.to_i32();
+    let b = argument(&args, 1).to_number_with(ctx)?.to_i32();
+    Ok(JsValue::integer(a.wrapping_mul(b)))
+}
+
+#[js_function(length = 1)]
+fn log(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::ln)
+}
+
+#[js_function(length = 1)]
+fn log1p(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::ln_1p)
+}
+
+#[js_function(length = 1)]
+fn log10(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::log10)
+}
+
+#[js_function(length = 1)]
+fn log2(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::log2)
+}
+
+#[js_function(length = 2)]
+fn max(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let mut result = f64::NEG_INFINITY;
+    for x in numbers(ctx, &args)? {
+        // `NaN` is contagious and `+0` is bigger than `-0`
+        if x.is_nan() || x > result || (x == 0.0 && result == 0.0 && x.is_sign_positive()) {
+            result = x;
+        }
+        if result.is_nan() {
+            break;
+        }
+    }
+    Ok(JsValue::from(result))
+}
+
+#[js_function(length = 2)]
+fn min(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let mut result = f64::INFINITY;
+    for x in numbers(ctx, &args)? {
+        if x.is_nan() || x < result || (x == 0.0 && result == 0.0 && x.is_sign_negative()) {
+            result = x;
+        }
+        if result.is_nan() {
+            break;
+        }
+    }
+    Ok(JsValue::from(result))
+}
+
+#[js_function(length = 2)]
+fn pow(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let base = number(ctx, &args, 0)?;
+    let exponent = number(ctx, &args, 1)?;
+    // Rust says `1 ** NaN` and `1 ** Infinity` are 1, JS says `NaN`
+    let result = if exponent.is_nan() || (base.abs() == 1.0 && exponent.is_infinite()) {
+        f64::NAN
+    } else {
+        base.powf(exponent)
+    };
+    Ok(JsValue::from(result))
+}
+
+#[js_function]
+fn random(_ctx: &mut Context, _this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    Ok(JsValue::from(rand::random::<f64>()))
+}
+
+/// Halves go up, `-0.5` rounds to `-0`
+#[js_function(length = 1)]
+fn round(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, |x| {
+        let floor = x.floor();
+        let rounded = if x - floor >= 0.5 { floor + 1.0 } else { floor };
+        if rounded == 0.0 {
+            rounded.copysign(x)
+        } else {
+            rounded
+        }
+    })
+}
+
+#[js_function(length = 1)]
+fn sign(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, |x| {
+        if x == 0.0 || x.is_nan() {
+            x
+        } else {
+            x.signum()
+        }
+    })
+}
+
+#[js_function(length = 1)]
+fn sin(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::sin)
+}
+
+#[js_function(length = 1)]
+fn sinh(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::sinh)
+}
+
+#[js_function(length = 1)]
+fn sqrt(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::sqrt)
+}
+
+#[js_function(length = 1)]
+fn tan(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::tan)
+}
+
+#[js_function(length = 1)]
+fn tanh(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::tanh)
+}
+
+#[js_function(length = 1)]
+fn trunc(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    unary(ctx, &args, f64::trunc)
+}
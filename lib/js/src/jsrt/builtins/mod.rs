@@ -0,0 +1,96 @@
+//! The properties of the global object, apart from `Array` and the error constructors which
+//! live next to their types
+mod bigint;
+mod boolean;
+mod console;
+mod error;
+mod function;
+mod generator;
+mod global;
+mod iterator;
+mod json;
+mod math;
+mod number;
+mod object;
+mod promise;
+mod regexp;
+mod string;
+mod symbol;
+mod timers;
+mod weak;
+
+use super::{Attributes, JsArray, JsObject, JsSymbol, JsValue, NativeFunction};
+use crate::{ast, gc::GcPointer, vm::Context};
+
+/// Fill the global object of a new realm
+pub fn install(ctx: &mut Context) {
+    global::install(ctx);
+    object::install(ctx);
+    function::install(ctx);
+    ctx.create_error_constructors();
+    error::install(ctx);
+    iterator::install(ctx);
+    JsArray::create_builtins(ctx);
+    boolean::install(ctx);
+    number::install(ctx);
+    string::install(ctx);
+    regexp::install(ctx);
+    bigint::install(ctx);
+    symbol::install(ctx);
+    weak::install(ctx);
+    generator::install(ctx);
+    promise::install(ctx);
+    timers::install(ctx);
+    math::install(ctx);
+    json::install(ctx);
+    console::install(ctx);
+}
+
+/// `args[index]`, missing arguments are `undefined`
+fn argument(args: &[JsValue], index: usize) -> JsValue {
+    args.get(index).cloned().unwrap_or_default()
+}
+
+/// `ToIntegerOrInfinity`
+fn to_integer(ctx: &mut Context, value: &JsValue) -> ast::Result<f64> {
+    let number = value.to_number_with(ctx)?.as_f64();
+    Ok(if number.is_nan() {
+        0.0
+    } else {
+        number.trunc() + 0.0
+    })
+}
+
+/// Plain object holding `functions`, like `Math` and `JSON`
+fn namespace(ctx: &mut Context, functions: &[NativeFunction]) -> GcPointer<JsObject> {
+    let mut obj = JsObject::new(ctx);
+    for function in functions {
+        obj.define_method(ctx, *function);
+    }
+    obj
+}
+
+/// Read only, non enumerable and non configurable values such as `Math.PI`
+fn define_constants(obj: &mut GcPointer<JsObject>, constants: &[(&str, f64)]) {
+    for (name, value) in constants {
+        obj.define_property(*name, JsValue::from(*value), Attributes::empty());
+    }
+}
+
+/// Method with a symbol key, `function` is named like `[Symbol.iterator]`
+fn define_symbol_method(
+    ctx: &mut Context,
+    obj: &mut GcPointer<JsObject>,
+    symbol: JsSymbol,
+    function: NativeFunction,
+    attributes: Attributes,
+) {
+    let function = JsObject::native_function(ctx, function);
+    obj.define_property(symbol, JsValue::object(function), attributes);
+}
+
+/// `Symbol.toStringTag`, the name `Object.prototype.toString` gives to `obj`
+fn define_to_string_tag(ctx: &mut Context, obj: &mut GcPointer<JsObject>, tag: &str) {
+    let symbol = ctx.well_known_symbols().to_string_tag.clone();
+    obj.define_property(symbol, JsValue::string(tag), Attributes::CONFIGURABLE);
+}
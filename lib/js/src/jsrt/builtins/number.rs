@@ -0,0 +1,254 @@
+use super::{argument, define_constants, to_integer};
+use crate::{
+    ast,
+    jsrt::{js_function, Attributes, ErrorKind, JsObject, JsValue, ObjectKind},
+    vm::Context,
+};
+
+/// `Number.MAX_SAFE_INTEGER`
+const MAX_SAFE_INTEGER: f64 = 9007199254740991.0;
+
+pub(super) fn install(ctx: &mut Context) {
+    let mut prototype = ctx.number_prototype();
+    for function in [TO_STRING, TO_FIXED, VALUE_OF] {
+        prototype.define_method(ctx, function);
+    }
+
+    let mut constructor = JsObject::native_constructor(ctx, CONSTRUCT, prototype);
+    define_constants(
+        &mut constructor,
+        &[
+            ("MAX_SAFE_INTEGER", MAX_SAFE_INTEGER),
+            ("MIN_SAFE_INTEGER", -MAX_SAFE_INTEGER),
+            ("MAX_VALUE", f64::MAX),
+            ("MIN_VALUE", 5e-324),
+            ("EPSILON", f64::EPSILON),
+            ("NaN", f64::NAN),
+            ("POSITIVE_INFINITY", f64::INFINITY),
+            ("NEGATIVE_INFINITY", f64::NEG_INFINITY),
+        ],
+    );
+    for function in [IS_NAN, IS_FINITE, IS_INTEGER, IS_SAFE_INTEGER] {
+        constructor.define_method(ctx, function);
+    }
+    // The very same functions as the globals
+    let global = ctx.global_object();
+    for name in ["parseInt", "parseFloat"] {
+        let function = global.get_data_property(name);
+        constructor.define_property(
+            name,
+            function,
+            Attributes::WRITABLE | Attributes::CONFIGURABLE,
+        );
+    }
+    ctx.define_global("Number", JsValue::object(constructor));
+}
+
+/// `thisNumberValue`
+fn this_number(ctx: &mut Context, this: &JsValue, method: &str) -> ast::Result<f64> {
+    match this {
+        value if value.is_number() => Ok(value.as_f64()),
+        JsValue::Object(obj) => match obj.kind {
+            ObjectKind::Number(n) => Ok(n),
+            _ => Err(incompatible_receiver(ctx, method)),
+        },
+        _ => Err(incompatible_receiver(ctx, method)),
+    }
+}
+
+fn incompatible_receiver(ctx: &mut Context, method: &str) -> ast::Completion {
+    let message = format!(
+        "Number.prototype.{} requires that 'this' be a Number",
+        method
+    );
+    ctx.throw_error(ErrorKind::TypeError, &message)
+}
+
+/// `Number::toString(x, radix)` for the radices other than 10, as short as it can be while
+/// still reading back as `x`
+fn to_radix_string(x: f64, radix: u32) -> String {
+    if !x.is_finite() {
+        return JsValue::from(x).to_string();
+    }
+    let digit = |d: f64| std::char::from_digit(d as u32, radix).unwrap();
+    let value = x.abs();
+    let mut integer = value.trunc();
+    let mut fraction = value - integer;
+
+    // Half the distance to the next double, digits past it don't change the value
+    let next = f64::from_bits(value.to_bits() + 1);
+    let mut delta = (0.5 * (next - value)).max(f64::from_bits(1));
+    let mut fraction_digits = Vec::new();
+    if fraction >= delta {
+        loop {
+            fraction *= radix as f64;
+            delta *= radix as f64;
+            let d = fraction.trunc();
+            fraction_digits.push(d as u32);
+            fraction -= d;
+            if (fraction > 0.5 || (fraction == 0.5 && d as u32 % 2 == 1)) && fraction + delta > 1.0
+            {
+                // Round up, carrying into the integer part if every digit overflows
+                loop {
+                    match fraction_digits.pop() {
+                        Some(d) if d + 1 < radix => {
+                            fraction_digits.push(d + 1);
+                            break;
+                        }
+                        Some(_) => {}
+                        None => {
+                            integer += 1.0;
+                            break;
+                        }
+                    }
+                }
+                break;
+            }
+            if fraction < delta {
+                break;
+            }
+        }
+    }
+
+    let mut integer_digits = Vec::new();
+    loop {
+        integer_digits.push(digit(integer % radix as f64));
+        integer = (integer / radix as f64).trunc();
+        if integer == 0.0 {
+            break;
+        }
+    }
+
+    let mut result = String::new();
+    if x < 0.0 {
+        result.push('-');
+    }
+    result.extend(integer_digits.iter().rev());
+    if !fraction_digits.is_empty() {
+        result.push('.');
+        result.extend(fraction_digits.iter().map(|d| digit(*d as f64)));
+    }
+    result
+}
+
+/// `x` with exactly `digits` decimals, ties rounded away from zero
+fn to_fixed_string(x: f64, digits: usize) -> String {
+    // Rust rounds ties to even, so round a longer expansion by hand
+    const EXTRA: usize = 30;
+    let long = format!("{:.*}", digits + EXTRA, x.abs());
+    let (kept, rest) = long.split_at(long.len() - EXTRA);
+    let mut kept = kept.as_bytes().to_vec();
+    if rest.as_bytes()[0] >= b'5' {
+        let mut carry = true;
+        for c in kept.iter_mut().rev().filter(|c| c.is_ascii_digit()) {
+            if *c == b'9' {
+                *c = b'0';
+            } else {
+                *c += 1;
+                carry = false;
+                break;
+            }
+        }
+        if carry {
+            kept.insert(0, b'1');
+        }
+    }
+    if kept.last() == Some(&b'.') {
+        kept.pop();
+    }
+
+    let sign = if x < 0.0 { "-" } else { "" };
+    format!("{}{}", sign, String::from_utf8(kept).unwrap())
+}
+
+// Number
+
+#[js_function(name = "Number", length = 1)]
+fn construct(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let value = match args.first() {
+        Some(value) => value.to_numeric_with(ctx)?,
+        None => JsValue::integer(0),
+    };
+    // The one place where BigInts become numbers
+    let value = match value {
+        JsValue::BigInt(v) => JsValue::from(v.to_f64()),
+        value => value,
+    };
+    match ctx.is_constructing() {
+        true => Ok(JsValue::object(value.to_object(ctx)?)),
+        false => Ok(value),
+    }
+}
+
+#[js_function(name = "isNaN", length = 1)]
+fn is_nan(_ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let value = argument(&args, 0);
+    Ok(JsValue::Boolean(
+        value.is_number() && value.as_f64().is_nan(),
+    ))
+}
+
+#[js_function(length = 1)]
+fn is_finite(_ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let value = argument(&args, 0);
+    Ok(JsValue::Boolean(
+        value.is_number() && value.as_f64().is_finite(),
+    ))
+}
+
+#[js_function(length = 1)]
+fn is_integer(_ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let value = argument(&args, 0);
+    let is_integer = value.is_number() && value.as_f64().fract() == 0.0;
+    Ok(JsValue::Boolean(is_integer))
+}
+
+#[js_function(length = 1)]
+fn is_safe_integer(_ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let value = argument(&args, 0);
+    let is_safe = value.is_number()
+        && value.as_f64().fract() == 0.0
+        && value.as_f64().abs() <= MAX_SAFE_INTEGER;
+    Ok(JsValue::Boolean(is_safe))
+}
+
+// Number.prototype
+
+#[js_function(length = 1)]
+fn to_string(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let x = this_number(ctx, &this, "toString")?;
+    let radix = match argument(&args, 0) {
+        JsValue::Undefined => 10.0,
+        radix => to_integer(ctx, &radix)?,
+    };
+    if !(2.0..=36.0).contains(&radix) {
+        let message = "toString() radix must be between 2 and 36";
+        return Err(ctx.throw_error(ErrorKind::RangeError, message));
+    }
+
+    Ok(match radix as u32 {
+        10 => JsValue::string(JsValue::from(x).to_string().as_ref()),
+        radix => JsValue::string(to_radix_string(x, radix).as_ref()),
+    })
+}
+
+#[js_function(length = 1)]
+fn to_fixed(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let x = this_number(ctx, &this, "toFixed")?;
+    let digits = to_integer(ctx, &argument(&args, 0))?;
+    if !(0.0..=100.0).contains(&digits) {
+        let message = "toFixed() digits argument must be between 0 and 100";
+        return Err(ctx.throw_error(ErrorKind::RangeError, message));
+    }
+
+    Ok(if !x.is_finite() || x.abs() >= 1e21 {
+        JsValue::string(JsValue::from(x).to_string().as_ref())
+    } else {
+        JsValue::string(to_fixed_string(x, digits as usize).as_ref())
+    })
+}
+
+#[js_function]
+fn value_of(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    Ok(JsValue::from(this_number(ctx, &this, "valueOf")?))
+}
@@ -0,0 +1,560 @@
+use super::argument;
+use crate::{
+    ast,
+    gc::GcPointer,
+    jsrt::{
+        js_function, Attributes, ErrorKind, JsObject, JsValue, ObjectKind, PropertyDescriptor,
+        PropertyKey,
+    },
+    vm::Context,
+};
+
+pub(super) fn install(ctx: &mut Context) {
+    let mut prototype = ctx.object_prototype();
+    for function in [
+        HAS_OWN_PROPERTY,
+        IS_PROTOTYPE_OF,
+        PROPERTY_IS_ENUMERABLE,
+        TO_STRING,
+        VALUE_OF,
+    ] {
+        prototype.define_method(ctx, function);
+    }
+    let getter = JsValue::object(JsObject::native_function(ctx, GET_PROTO));
+    let setter = JsValue::object(JsObject::native_function(ctx, SET_PROTO));
+    prototype.define_own_property(
+        PropertyKey::from("__proto__"),
+        PropertyDescriptor::accessor(getter, setter, Attributes::CONFIGURABLE),
+    );
+
+    let mut constructor = JsObject::native_constructor(ctx, CONSTRUCT, prototype);
+    for function in [
+        KEYS,
+        VALUES,
+        ENTRIES,
+        GET_PROTOTYPE_OF,
+        SET_PROTOTYPE_OF,
+        CREATE,
+        DEFINE_PROPERTY,
+        DEFINE_PROPERTIES,
+        GET_OWN_PROPERTY_DESCRIPTOR,
+        GET_OWN_PROPERTY_NAMES,
+        GET_OWN_PROPERTY_SYMBOLS,
+        FREEZE,
+        IS_FROZEN,
+        SEAL,
+        IS_SEALED,
+        PREVENT_EXTENSIONS,
+        IS_EXTENSIBLE,
+        ASSIGN,
+        IS,
+    ] {
+        constructor.define_method(ctx, function);
+    }
+    ctx.define_global("Object", JsValue::object(constructor));
+}
+
+/// `ToPropertyDescriptor`, `value` is the object passed to `Object.defineProperty`
+fn to_property_descriptor(ctx: &mut Context, value: &JsValue) -> ast::Result<PropertyDescriptor> {
+    let obj = match value {
+        JsValue::Object(obj) => obj,
+        value => {
+            let message = format!(
+                "Property description must be an object: {}",
+                value.to_string()
+            );
+            return Err(ctx.throw_error(ErrorKind::TypeError, &message));
+        }
+    };
+
+    let field = |ctx: &mut Context, name: &str| -> ast::Result<Option<JsValue>> {
+        let key = PropertyKey::from(name);
+        if obj.has_property(&key) {
+            obj.get(ctx, &key, value.clone()).map(Some)
+        } else {
+            Ok(None)
+        }
+    };
+    let desc = PropertyDescriptor {
+        enumerable: field(ctx, "enumerable")?.map(|v| v.to_boolean()),
+        configurable: field(ctx, "configurable")?.map(|v| v.to_boolean()),
+        value: field(ctx, "value")?,
+        writable: field(ctx, "writable")?.map(|v| v.to_boolean()),
+        get: field(ctx, "get")?,
+        set: field(ctx, "set")?,
+    };
+
+    for (accessor, name) in [(&desc.get, "Getter"), (&desc.set, "Setter")] {
+        match accessor {
+            None | Some(JsValue::Undefined) => {}
+            Some(JsValue::Object(f)) if f.is_function() => {}
+            Some(accessor) => {
+                let message = format!("{} must be a function: {}", name, accessor.to_string());
+                return Err(ctx.throw_error(ErrorKind::TypeError, &message));
+            }
+        }
+    }
+    if desc.is_accessor_descriptor() && desc.is_data_descriptor() {
+        let message = "Invalid property descriptor. Cannot both specify accessors and a value or \
+                       writable attribute";
+        return Err(ctx.throw_error(ErrorKind::TypeError, message));
+    }
+    Ok(desc)
+}
+
+/// `FromPropertyDescriptor`, the object `Object.getOwnPropertyDescriptor` returns
+fn from_property_descriptor(ctx: &mut Context, desc: PropertyDescriptor) -> JsValue {
+    let mut obj = JsObject::new(ctx);
+    let fields = [
+        ("value", desc.value),
+        ("writable", desc.writable.map(JsValue::Boolean)),
+        ("get", desc.get),
+        ("set", desc.set),
+        ("enumerable", desc.enumerable.map(JsValue::Boolean)),
+        ("configurable", desc.configurable.map(JsValue::Boolean)),
+    ];
+    for (name, value) in fields {
+        if let Some(value) = value {
+            obj.create_data_property(PropertyKey::from(name), value);
+        }
+    }
+    JsValue::object(obj)
+}
+
+/// `DefinePropertyOrThrow`
+fn define_property_or_throw(
+    ctx: &mut Context,
+    obj: &GcPointer<JsObject>,
+    key: PropertyKey,
+    desc: PropertyDescriptor,
+) -> ast::Result<()> {
+    if obj.clone().define_own_property(key.clone(), desc) {
+        Ok(())
+    } else {
+        let message = format!("Cannot redefine property: {}", key);
+        Err(ctx.throw_error(ErrorKind::TypeError, &message))
+    }
+}
+
+/// `ObjectDefineProperties`, every descriptor is read before any is applied
+fn define_properties_from(
+    ctx: &mut Context,
+    obj: &GcPointer<JsObject>,
+    properties: &JsValue,
+) -> ast::Result<()> {
+    let properties = properties.to_object(ctx)?;
+    let mut descriptors = Vec::new();
+    for key in properties.own_property_keys() {
+        if properties
+            .get_own_property(&key)
+            .is_some_and(|desc| desc.enumerable())
+        {
+            let value = properties.get(ctx, &key, JsValue::object(properties.clone()))?;
+            descriptors.push((key, to_property_descriptor(ctx, &value)?));
+        }
+    }
+    for (key, desc) in descriptors {
+        define_property_or_throw(ctx, obj, key, desc)?;
+    }
+    Ok(())
+}
+
+/// Own enumerable string keys, in the order `for in` would see them
+fn enumerable_own_keys(obj: &GcPointer<JsObject>) -> Vec<PropertyKey> {
+    obj.own_property_keys()
+        .into_iter()
+        .filter(|key| {
+            !key.is_symbol()
+                && obj
+                    .get_own_property(key)
+                    .is_some_and(|desc| desc.enumerable())
+        })
+        .collect()
+}
+
+/// Whether every own property is non configurable, and read only if `frozen`
+fn test_integrity(value: &JsValue, frozen: bool) -> bool {
+    let obj = match value {
+        JsValue::Object(obj) => obj,
+        _ => return true,
+    };
+    !obj.is_extensible()
+        && obj.own_property_keys().iter().all(|key| {
+            let desc = obj.get_own_property(key).unwrap_or_default();
+            !desc.configurable() && !(frozen && desc.is_data_descriptor() && desc.writable())
+        })
+}
+
+/// Make every own property non configurable, and read only if `freeze`
+fn set_integrity(value: &JsValue, freeze: bool) -> bool {
+    match value {
+        JsValue::Object(obj) => obj.clone().set_integrity_level(freeze),
+        _ => true,
+    }
+}
+
+// Object
+
+#[js_function(name = "Object", length = 1)]
+fn construct(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    match argument(&args, 0) {
+        JsValue::Undefined | JsValue::Null => Ok(JsValue::object(JsObject::new(ctx))),
+        value => Ok(JsValue::object(value.to_object(ctx)?)),
+    }
+}
+
+#[js_function(length = 1)]
+fn keys(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = argument(&args, 0).to_object(ctx)?;
+    let keys = enumerable_own_keys(&obj)
+        .iter()
+        .map(PropertyKey::to_value)
+        .collect();
+    Ok(JsValue::object(JsObject::array(ctx, keys)))
+}
+
+#[js_function(length = 1)]
+fn values(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = argument(&args, 0).to_object(ctx)?;
+    let mut values = Vec::new();
+    for key in enumerable_own_keys(&obj) {
+        values.push(obj.get(ctx, &key, JsValue::object(obj.clone()))?);
+    }
+    Ok(JsValue::object(JsObject::array(ctx, values)))
+}
+
+#[js_function(length = 1)]
+fn entries(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = argument(&args, 0).to_object(ctx)?;
+    let mut entries = Vec::new();
+    for key in enumerable_own_keys(&obj) {
+        let value = obj.get(ctx, &key, JsValue::object(obj.clone()))?;
+        let entry = JsObject::array(ctx, vec![key.to_value(), value]);
+        entries.push(JsValue::object(entry));
+    }
+    Ok(JsValue::object(JsObject::array(ctx, entries)))
+}
+
+#[js_function(length = 1)]
+fn get_prototype_of(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = argument(&args, 0).to_object(ctx)?;
+    Ok(obj
+        .get_prototype_of()
+        .map_or(JsValue::Null, JsValue::object))
+}
+
+#[js_function(length = 2)]
+fn set_prototype_of(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let target = argument(&args, 0);
+    let prototype = match argument(&args, 1) {
+        JsValue::Object(prototype) => Some(prototype),
+        JsValue::Null => None,
+        value => {
+            let message = format!(
+                "Object prototype may only be an Object or null: {}",
+                value.to_string()
+            );
+            return Err(ctx.throw_error(ErrorKind::TypeError, &message));
+        }
+    };
+    if let JsValue::Undefined | JsValue::Null = target {
+        let message = "Object.setPrototypeOf called on null or undefined";
+        return Err(ctx.throw_error(ErrorKind::TypeError, message));
+    }
+
+    if let JsValue::Object(obj) = &target {
+        if !obj.clone().set_prototype_of(prototype) {
+            let message = "Cannot set the prototype of this object";
+            return Err(ctx.throw_error(ErrorKind::TypeError, message));
+        }
+    }
+    Ok(target)
+}
+
+#[js_function(name = "get __proto__")]
+fn get_proto(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = this.to_object(ctx)?;
+    Ok(obj
+        .get_prototype_of()
+        .map_or(JsValue::Null, JsValue::object))
+}
+
+/// Like `Object.setPrototypeOf`, but anything else than an object or `null` is ignored
+#[js_function(name = "set __proto__", length = 1)]
+fn set_proto(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    if let JsValue::Undefined | JsValue::Null = this {
+        let message = "Object.prototype.__proto__ called on null or undefined";
+        return Err(ctx.throw_error(ErrorKind::TypeError, message));
+    }
+    let prototype = match argument(&args, 0) {
+        JsValue::Object(prototype) => Some(prototype),
+        JsValue::Null => None,
+        _ => return Ok(JsValue::Undefined),
+    };
+
+    if let JsValue::Object(mut obj) = this {
+        if !obj.set_prototype_of(prototype) {
+            let message = "Cannot set the prototype of this object";
+            return Err(ctx.throw_error(ErrorKind::TypeError, message));
+        }
+    }
+    Ok(JsValue::Undefined)
+}
+
+#[js_function(length = 2)]
+fn create(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let prototype = match argument(&args, 0) {
+        JsValue::Object(prototype) => Some(prototype),
+        JsValue::Null => None,
+        value => {
+            let message = format!(
+                "Object prototype may only be an Object or null: {}",
+                value.to_string()
+            );
+            return Err(ctx.throw_error(ErrorKind::TypeError, &message));
+        }
+    };
+
+    let obj = JsObject::with_prototype(ctx, prototype, ObjectKind::Ordinary);
+    let properties = argument(&args, 1);
+    if !properties.is_undefined() {
+        define_properties_from(ctx, &obj, &properties)?;
+    }
+    Ok(JsValue::object(obj))
+}
+
+/// The target of `Object.defineProperty` and `Object.defineProperties`
+fn target_object(
+    ctx: &mut Context,
+    value: JsValue,
+    method: &str,
+) -> ast::Result<GcPointer<JsObject>> {
+    match value {
+        JsValue::Object(obj) => Ok(obj),
+        _ => {
+            let message = format!("Object.{} called on non-object", method);
+            Err(ctx.throw_error(ErrorKind::TypeError, &message))
+        }
+    }
+}
+
+#[js_function(length = 3)]
+fn define_property(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = target_object(ctx, argument(&args, 0), "defineProperty")?;
+    let key = PropertyKey::from_value(&argument(&args, 1));
+    let desc = to_property_descriptor(ctx, &argument(&args, 2))?;
+    define_property_or_throw(ctx, &obj, key, desc)?;
+    Ok(JsValue::object(obj))
+}
+
+#[js_function(length = 2)]
+fn define_properties(
+    ctx: &mut Context,
+    _this: JsValue,
+    args: Vec<JsValue>,
+) -> ast::Result<JsValue> {
+    let obj = target_object(ctx, argument(&args, 0), "defineProperties")?;
+    define_properties_from(ctx, &obj, &argument(&args, 1))?;
+    Ok(JsValue::object(obj))
+}
+
+#[js_function(length = 2)]
+fn get_own_property_descriptor(
+    ctx: &mut Context,
+    _this: JsValue,
+    args: Vec<JsValue>,
+) -> ast::Result<JsValue> {
+    let obj = argument(&args, 0).to_object(ctx)?;
+    let key = PropertyKey::from_value(&argument(&args, 1));
+    Ok(match obj.get_own_property(&key) {
+        Some(desc) => from_property_descriptor(ctx, desc),
+        None => JsValue::Undefined,
+    })
+}
+
+#[js_function(length = 1)]
+fn get_own_property_names(
+    ctx: &mut Context,
+    _this: JsValue,
+    args: Vec<JsValue>,
+) -> ast::Result<JsValue> {
+    let obj = argument(&args, 0).to_object(ctx)?;
+    let names = obj
+        .own_property_keys()
+        .iter()
+        .filter(|key| !key.is_symbol())
+        .map(PropertyKey::to_value)
+        .collect();
+    Ok(JsValue::object(JsObject::array(ctx, names)))
+}
+
+#[js_function(length = 1)]
+fn get_own_property_symbols(
+    ctx: &mut Context,
+    _this: JsValue,
+    args: Vec<JsValue>,
+) -> ast::Result<JsValue> {
+    let obj = argument(&args, 0).to_object(ctx)?;
+    let symbols = obj
+        .own_property_keys()
+        .iter()
+        .filter(|key| key.is_symbol())
+        .map(PropertyKey::to_value)
+        .collect();
+    Ok(JsValue::object(JsObject::array(ctx, symbols)))
+}
+
+#[js_function(length = 1)]
+fn freeze(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = argument(&args, 0);
+    if !set_integrity(&obj, true) {
+        return Err(ctx.throw_error(ErrorKind::TypeError, "Cannot freeze"));
+    }
+    Ok(obj)
+}
+
+#[js_function(length = 1)]
+fn is_frozen(_ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    Ok(JsValue::Boolean(test_integrity(&argument(&args, 0), true)))
+}
+
+#[js_function(length = 1)]
+fn seal(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = argument(&args, 0);
+    if !set_integrity(&obj, false) {
+        return Err(ctx.throw_error(ErrorKind::TypeError, "Cannot seal"));
+    }
+    Ok(obj)
+}
+
+#[js_function(length = 1)]
+fn is_sealed(_ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    Ok(JsValue::Boolean(test_integrity(&argument(&args, 0), false)))
+}
+
+#[js_function(length = 1)]
+fn prevent_extensions(
+    _ctx: &mut Context,
+    _this: JsValue,
+    args: Vec<JsValue>,
+) -> ast::Result<JsValue> {
+    let value = argument(&args, 0);
+    if let JsValue::Object(obj) = &value {
+        obj.clone().prevent_extensions();
+    }
+    Ok(value)
+}
+
+#[js_function(length = 1)]
+fn is_extensible(_ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let extensible = matches!(argument(&args, 0), JsValue::Object(obj) if obj.is_extensible());
+    Ok(JsValue::Boolean(extensible))
+}
+
+#[js_function(length = 2)]
+fn assign(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let target = argument(&args, 0).to_object(ctx)?;
+    let receiver = JsValue::object(target.clone());
+    for source in args.iter().skip(1) {
+        if let JsValue::Undefined | JsValue::Null = source {
+            continue;
+        }
+        let source = source.to_object(ctx)?;
+        for key in source.own_property_keys() {
+            if !source
+                .get_own_property(&key)
+                .is_some_and(|desc| desc.enumerable())
+            {
+                continue;
+            }
+            let value = source.get(ctx, &key, JsValue::object(source.clone()))?;
+            if !target
+                .clone()
+                .set(ctx, key.clone(), value, receiver.clone())?
+            {
+                let message = format!("Cannot assign to read only property '{}' of object", key);
+                return Err(ctx.throw_error(ErrorKind::TypeError, &message));
+            }
+        }
+    }
+    Ok(receiver)
+}
+
+#[js_function(length = 2)]
+fn is(_ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let same = argument(&args, 0).same_value(&argument(&args, 1));
+    Ok(JsValue::Boolean(same))
+}
+
+// Object.prototype
+
+#[js_function(length = 1)]
+fn has_own_property(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let key = PropertyKey::from_value(&argument(&args, 0));
+    let obj = this.to_object(ctx)?;
+    Ok(JsValue::Boolean(obj.get_own_property(&key).is_some()))
+}
+
+#[js_function(length = 1)]
+fn is_prototype_of(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let mut current = match argument(&args, 0) {
+        JsValue::Object(obj) => obj.get_prototype_of(),
+        _ => return Ok(JsValue::Boolean(false)),
+    };
+    let obj = this.to_object(ctx)?;
+    while let Some(prototype) = current {
+        if prototype.ptr_eq(&obj) {
+            return Ok(JsValue::Boolean(true));
+        }
+        current = prototype.get_prototype_of();
+    }
+    Ok(JsValue::Boolean(false))
+}
+
+#[js_function(length = 1)]
+fn property_is_enumerable(
+    ctx: &mut Context,
+    this: JsValue,
+    args: Vec<JsValue>,
+) -> ast::Result<JsValue> {
+    let key = PropertyKey::from_value(&argument(&args, 0));
+    let obj = this.to_object(ctx)?;
+    let enumerable = obj
+        .get_own_property(&key)
+        .is_some_and(|desc| desc.enumerable());
+    Ok(JsValue::Boolean(enumerable))
+}
+
+#[js_function]
+fn to_string(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let obj = match &this {
+        JsValue::Undefined => return Ok(JsValue::string("[object Undefined]")),
+        JsValue::Null => return Ok(JsValue::string("[object Null]")),
+        value => value.to_object(ctx)?,
+    };
+    let builtin_tag = match &obj.kind {
+        ObjectKind::Array(_) => "Array",
+        ObjectKind::Function(_) | ObjectKind::NativeFunction(_) | ObjectKind::BoundFunction(_) => {
+            "Function"
+        }
+        ObjectKind::Error(_) => "Error",
+        ObjectKind::Boolean(_) => "Boolean",
+        ObjectKind::Number(_) => "Number",
+        ObjectKind::String(_) => "String",
+        ObjectKind::RegExp(_) => "RegExp",
+        _ => "Object",
+    };
+
+    // `Symbol.toStringTag` names everything else, like `Math` or `Symbol.prototype`
+    let key = PropertyKey::from(ctx.well_known_symbols().to_string_tag.clone());
+    let tag = match obj.get(ctx, &key, JsValue::object(obj.clone()))? {
+        JsValue::String(tag) => tag.string.to_string(),
+        _ => builtin_tag.to_string(),
+    };
+    Ok(JsValue::string(format!("[object {}]", tag).as_ref()))
+}
+
+#[js_function]
+fn value_of(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    Ok(JsValue::object(this.to_object(ctx)?))
+}
@@ -0,0 +1,324 @@
+//! `Promise`, the reactions of promises run as jobs once the current script is done
+use super::{argument, define_to_string_tag};
+use crate::{
+    ast::{self, Completion},
+    gc::GcPointer,
+    jsrt::{
+        js_function, ErrorKind, IteratorRecord, JsObject, JsPromise, JsValue, NativeFunction,
+        ObjectKind, PromiseCapability, PropertyKey,
+    },
+    vm::Context,
+};
+
+pub(super) fn install(ctx: &mut Context) {
+    let mut prototype = ctx.promise_prototype();
+    for function in [THEN, CATCH, FINALLY] {
+        prototype.define_method(ctx, function);
+    }
+    define_to_string_tag(ctx, &mut prototype, "Promise");
+
+    let mut constructor = JsObject::native_constructor(ctx, PROMISE, prototype);
+    for function in [RESOLVE, REJECT, ALL, ALL_SETTLED, RACE] {
+        constructor.define_method(ctx, function);
+    }
+    ctx.define_global("Promise", JsValue::object(constructor));
+}
+
+fn this_promise(
+    ctx: &mut Context,
+    this: &JsValue,
+    method: &str,
+) -> ast::Result<GcPointer<JsObject>> {
+    match this {
+        JsValue::Object(obj) if matches!(obj.kind, ObjectKind::Promise(_)) => Ok(obj.clone()),
+        _ => {
+            let message = format!(
+                "Method Promise.prototype.{} called on incompatible receiver {}",
+                method,
+                this.to_string()
+            );
+            Err(ctx.throw_error(ErrorKind::TypeError, &message))
+        }
+    }
+}
+
+/// `promise.then(on_fulfilled, on_rejected)`, through its own `then`
+fn invoke_then(
+    ctx: &mut Context,
+    promise: &JsValue,
+    on_fulfilled: JsValue,
+    on_rejected: JsValue,
+) -> ast::Result<JsValue> {
+    let then = promise.get_property(ctx, &PropertyKey::from("then"))?;
+    ctx.call(&then, promise.clone(), vec![on_fulfilled, on_rejected])
+}
+
+/// Object holding a value the closures of a built-in share, with no prototype
+fn shared_cell(ctx: &mut Context, kind: ObjectKind) -> JsValue {
+    JsValue::object(JsObject::with_prototype(ctx, None, kind))
+}
+
+#[js_function(name = "Promise", length = 1)]
+fn promise(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    if !ctx.is_constructing() {
+        let message = "Promise constructor cannot be invoked without 'new'";
+        return Err(ctx.throw_error(ErrorKind::TypeError, message));
+    }
+    let executor = argument(&args, 0);
+    if !executor.is_callable() {
+        let message = format!(
+            "Promise resolver {} is not a function",
+            executor.to_string()
+        );
+        return Err(ctx.throw_error(ErrorKind::TypeError, &message));
+    }
+
+    let promise = JsPromise::create(ctx);
+    let (resolve, reject) = JsPromise::create_resolving_functions(ctx, &promise);
+    if let Err(Completion::Throw(reason)) =
+        ctx.call(&executor, JsValue::Undefined, vec![resolve, reject.clone()])
+    {
+        ctx.call(&reject, JsValue::Undefined, vec![reason])?;
+    }
+    Ok(JsValue::object(promise))
+}
+
+#[js_function(length = 2)]
+fn then(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let promise = this_promise(ctx, &this, "then")?;
+    let capability = PromiseCapability::new(ctx);
+    let result = JsValue::object(capability.promise.clone());
+    JsPromise::perform_then(
+        ctx,
+        &promise,
+        argument(&args, 0),
+        argument(&args, 1),
+        Some(capability),
+    );
+    Ok(result)
+}
+
+#[js_function(length = 1)]
+fn catch(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    invoke_then(ctx, &this, JsValue::Undefined, argument(&args, 0))
+}
+
+/// `on_finally` is called without arguments, the promise it returns settles with the value or
+/// the reason of this one unless `on_finally` throws
+#[js_function(length = 1)]
+fn finally(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let on_finally = argument(&args, 0);
+    if !on_finally.is_callable() {
+        return invoke_then(ctx, &this, on_finally.clone(), on_finally);
+    }
+
+    let then_finally = JsObject::native_closure(ctx, THEN_FINALLY, vec![on_finally.clone()]);
+    let catch_finally = JsObject::native_closure(ctx, CATCH_FINALLY, vec![on_finally]);
+    invoke_then(
+        ctx,
+        &this,
+        JsValue::object(then_finally),
+        JsValue::object(catch_finally),
+    )
+}
+
+/// Call `on_finally`, then go on with `continuation` once what it returns is fulfilled
+fn run_finally(
+    ctx: &mut Context,
+    on_finally: &JsValue,
+    continuation: NativeFunction,
+    value: JsValue,
+) -> ast::Result<JsValue> {
+    let result = ctx.call(on_finally, JsValue::Undefined, Vec::new())?;
+    let promise = JsValue::object(JsPromise::promise_resolve(ctx, result));
+    let continuation = JsObject::native_closure(ctx, continuation, vec![value]);
+    invoke_then(
+        ctx,
+        &promise,
+        JsValue::object(continuation),
+        JsValue::Undefined,
+    )
+}
+
+#[js_function(name = "", length = 1)]
+fn then_finally(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    run_finally(ctx, &args[0], RETURN_VALUE, argument(&args, 1))
+}
+
+#[js_function(name = "", length = 1)]
+fn catch_finally(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    run_finally(ctx, &args[0], THROW_REASON, argument(&args, 1))
+}
+
+#[js_function(name = "")]
+fn return_value(_ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    Ok(args[0].clone())
+}
+
+#[js_function(name = "")]
+fn throw_reason(_ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    Err(Completion::Throw(args[0].clone()))
+}
+
+#[js_function(length = 1)]
+fn resolve(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let promise = JsPromise::promise_resolve(ctx, argument(&args, 0));
+    Ok(JsValue::object(promise))
+}
+
+#[js_function(length = 1)]
+fn reject(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let promise = JsPromise::create(ctx);
+    JsPromise::reject(ctx, &promise, argument(&args, 0));
+    Ok(JsValue::object(promise))
+}
+
+/// What `Promise.all`, `Promise.allSettled` and `Promise.race` do with each promise
+#[derive(Clone, Copy, PartialEq)]
+enum Combinator {
+    All,
+    AllSettled,
+    Race,
+}
+
+/// The promise of the combination of the values of `iterable`, rejected if iterating it throws
+fn combine(ctx: &mut Context, iterable: &JsValue, combinator: Combinator) -> JsValue {
+    let capability = PromiseCapability::new(ctx);
+    let result = JsValue::object(capability.promise.clone());
+    if let Err(Completion::Throw(reason)) = perform_combine(ctx, iterable, &capability, combinator)
+    {
+        let _ = ctx.call(&capability.reject, JsValue::Undefined, vec![reason]);
+    }
+    result
+}
+
+fn perform_combine(
+    ctx: &mut Context,
+    iterable: &JsValue,
+    capability: &PromiseCapability,
+    combinator: Combinator,
+) -> ast::Result<()> {
+    let mut record = IteratorRecord::from_iterable(ctx, iterable)?;
+    let values = JsValue::object(JsObject::array(ctx, Vec::new()));
+    // Starts at one so the promise can't resolve before the iteration is done
+    let remaining = shared_cell(ctx, ObjectKind::Number(1.0));
+
+    let mut index = 0;
+    while let Some(value) = record.step(ctx)? {
+        let promise = JsValue::object(JsPromise::promise_resolve(ctx, value));
+        let (on_fulfilled, on_rejected) = match combinator {
+            Combinator::Race => (capability.resolve.clone(), capability.reject.clone()),
+            _ => {
+                let key = PropertyKey::from(index);
+                values.set_property(ctx, key, JsValue::Undefined)?;
+                add_to_counter(&remaining, 1.0);
+
+                let already_called = shared_cell(ctx, ObjectKind::Boolean(false));
+                let element = |ctx: &mut Context, status: &str| {
+                    let status = match combinator {
+                        Combinator::AllSettled => JsValue::string(status),
+                        _ => JsValue::Undefined,
+                    };
+                    let captures = vec![
+                        JsValue::from(index),
+                        values.clone(),
+                        remaining.clone(),
+                        capability.resolve.clone(),
+                        already_called.clone(),
+                        status,
+                    ];
+                    JsValue::object(JsObject::native_closure(ctx, ELEMENT_FUNCTION, captures))
+                };
+                let on_fulfilled = element(ctx, "fulfilled");
+                let on_rejected = match combinator {
+                    Combinator::AllSettled => element(ctx, "rejected"),
+                    _ => capability.reject.clone(),
+                };
+                (on_fulfilled, on_rejected)
+            }
+        };
+
+        if let Err(completion) = invoke_then(ctx, &promise, on_fulfilled, on_rejected) {
+            let _ = record.close(ctx);
+            return Err(completion);
+        }
+        index += 1;
+    }
+
+    if combinator != Combinator::Race && add_to_counter(&remaining, -1.0) == 0.0 {
+        ctx.call(&capability.resolve, JsValue::Undefined, vec![values])?;
+    }
+    Ok(())
+}
+
+/// Add `n` to the number in a shared cell, returning the new count
+fn add_to_counter(counter: &JsValue, n: f64) -> f64 {
+    match counter {
+        JsValue::Object(obj) => match &mut obj.clone().kind {
+            ObjectKind::Number(count) => {
+                *count += n;
+                *count
+            }
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    }
+}
+
+/// Resolve element function of `Promise.all` and both element functions of
+/// `Promise.allSettled`, which record their result as `{ status, value }` or `{ status, reason }`
+#[js_function(name = "", length = 1)]
+fn element_function(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let (index, values, remaining, resolve, already_called, status) =
+        (&args[0], &args[1], &args[2], &args[3], &args[4], &args[5]);
+    let already_called = match already_called {
+        JsValue::Object(obj) => match &mut obj.clone().kind {
+            ObjectKind::Boolean(called) => std::mem::replace(called, true),
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    };
+    if already_called {
+        return Ok(JsValue::Undefined);
+    }
+
+    let value = argument(&args, 6);
+    let value = match status {
+        JsValue::Undefined => value,
+        status => {
+            let mut result = JsObject::new(ctx);
+            let key = match status.to_string().as_str() {
+                "fulfilled" => "value",
+                _ => "reason",
+            };
+            result.create_data_property(PropertyKey::from("status"), status.clone());
+            result.create_data_property(PropertyKey::from(key), value);
+            JsValue::object(result)
+        }
+    };
+    values.set_property(ctx, PropertyKey::from_value(index), value)?;
+
+    if add_to_counter(remaining, -1.0) == 0.0 {
+        ctx.call(resolve, JsValue::Undefined, vec![values.clone()])?;
+    }
+    Ok(JsValue::Undefined)
+}
+
+/// Fulfilled with the values of the promises of `iterable` once they all are, rejected as soon
+/// as one is
+#[js_function(length = 1)]
+fn all(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    Ok(combine(ctx, &argument(&args, 0), Combinator::All))
+}
+
+/// Fulfilled with how each promise of `iterable` settled once they all did
+#[js_function(length = 1)]
+fn all_settled(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    Ok(combine(ctx, &argument(&args, 0), Combinator::AllSettled))
+}
+
+/// Settled like the first promise of `iterable` to settle
+#[js_function(length = 1)]
+fn race(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    Ok(combine(ctx, &argument(&args, 0), Combinator::Race))
+}
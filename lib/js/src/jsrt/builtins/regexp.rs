@@ -0,0 +1,572 @@
+//! `RegExp`, with the methods `String.prototype.match`, `replace` and `split` hand over to
+//! through `Symbol.match`, `Symbol.replace` and `Symbol.split`
+use std::rc::Rc;
+
+use super::{argument, define_symbol_method, string::substitute, to_integer};
+use crate::{
+    ast,
+    jsrt::{
+        js_function, Attributes, ErrorKind, JsObject, JsRegExp, JsString, JsValue, ObjectKind,
+        PropertyDescriptor, PropertyKey,
+    },
+    regexp::{advance_string_index, Flags, Regex},
+    vm::Context,
+};
+
+pub(super) fn install(ctx: &mut Context) {
+    let symbols = ctx.well_known_symbols().clone();
+
+    let mut prototype = ctx.regexp_prototype();
+    for function in [EXEC, TEST, TO_STRING] {
+        prototype.define_method(ctx, function);
+    }
+    for getter in [
+        FLAGS,
+        SOURCE,
+        GLOBAL,
+        IGNORE_CASE,
+        MULTILINE,
+        DOT_ALL,
+        UNICODE,
+        STICKY,
+    ] {
+        let name = getter.name().trim_start_matches("get ");
+        let getter = JsValue::object(JsObject::native_function(ctx, getter));
+        prototype.define_own_property(
+            PropertyKey::from(name),
+            PropertyDescriptor::accessor(getter, JsValue::Undefined, Attributes::CONFIGURABLE),
+        );
+    }
+    for (symbol, function) in [
+        (symbols.r#match, SYMBOL_MATCH),
+        (symbols.replace, SYMBOL_REPLACE),
+        (symbols.split, SYMBOL_SPLIT),
+    ] {
+        define_symbol_method(
+            ctx,
+            &mut prototype,
+            symbol,
+            function,
+            Attributes::WRITABLE | Attributes::CONFIGURABLE,
+        );
+    }
+
+    let constructor = JsObject::native_constructor(ctx, CONSTRUCT, prototype);
+    ctx.define_global("RegExp", JsValue::object(constructor));
+}
+
+/// The compiled pattern of `this`, which must be a `RegExp`
+fn this_regexp(ctx: &mut Context, this: &JsValue, method: &str) -> ast::Result<Rc<Regex>> {
+    match this {
+        JsValue::Object(obj) => match &obj.kind {
+            ObjectKind::RegExp(regexp) => Ok(regexp.regex().clone()),
+            _ => Err(incompatible_receiver(ctx, this, method)),
+        },
+        _ => Err(incompatible_receiver(ctx, this, method)),
+    }
+}
+
+/// `this` of the methods which work on any object with the right properties
+fn this_object(ctx: &mut Context, this: &JsValue, method: &str) -> ast::Result<()> {
+    match this {
+        JsValue::Object(_) => Ok(()),
+        _ => Err(incompatible_receiver(ctx, this, method)),
+    }
+}
+
+fn incompatible_receiver(ctx: &mut Context, this: &JsValue, method: &str) -> ast::Completion {
+    let message = format!(
+        "Method RegExp.prototype.{} called on incompatible receiver {}",
+        method,
+        this.to_string()
+    );
+    ctx.throw_error(ErrorKind::TypeError, &message)
+}
+
+fn is_regexp_prototype(ctx: &Context, this: &JsValue) -> bool {
+    matches!(this, JsValue::Object(obj) if obj.ptr_eq(&ctx.regexp_prototype()))
+}
+
+fn units_argument(ctx: &mut Context, args: &[JsValue], index: usize) -> ast::Result<Vec<u16>> {
+    Ok(match argument(args, index) {
+        JsValue::String(s) => s.to_utf16(),
+        value => value.to_string_with(ctx)?.encode_utf16().collect(),
+    })
+}
+
+fn string(units: &[u16]) -> JsValue {
+    JsValue::String(JsString::from_utf16(units))
+}
+
+fn get(ctx: &mut Context, obj: &JsValue, key: &str) -> ast::Result<JsValue> {
+    obj.get_property(ctx, &PropertyKey::from(key))
+}
+
+/// `ToLength(obj[key])`, as an index into a string
+fn get_index(ctx: &mut Context, obj: &JsValue, key: &str) -> ast::Result<usize> {
+    let value = get(ctx, obj, key)?;
+    Ok(to_integer(ctx, &value)?.clamp(0.0, u32::MAX as f64) as usize)
+}
+
+fn set_last_index(ctx: &mut Context, rx: &JsValue, index: usize) -> ast::Result<()> {
+    rx.set_property(
+        ctx,
+        PropertyKey::from("lastIndex"),
+        JsValue::from(index as f64),
+    )
+}
+
+/// `EscapeRegExpPattern`, the source must be usable as a literal again
+fn escape_source(source: &str) -> String {
+    if source.is_empty() {
+        return "(?:)".into();
+    }
+
+    let mut escaped = String::with_capacity(source.len());
+    let mut chars = source.chars();
+    let mut in_class = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                escaped.push(c);
+                if let Some(c) = chars.next() {
+                    escaped.push(c);
+                }
+                continue;
+            }
+            '/' if !in_class => escaped.push_str("\\/"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\u{2028}' => escaped.push_str("\\u2028"),
+            '\u{2029}' => escaped.push_str("\\u2029"),
+            '[' => {
+                in_class = true;
+                escaped.push(c);
+            }
+            ']' => {
+                in_class = false;
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// `RegExpBuiltinExec`, the match array or `null`. The search starts at `lastIndex` with the
+/// global and sticky flags, which then hold where the match ended
+fn builtin_exec(
+    ctx: &mut Context,
+    rx: &JsValue,
+    regex: &Regex,
+    units: &[u16],
+) -> ast::Result<JsValue> {
+    let flags = regex.flags();
+    let uses_last_index = flags.intersects(Flags::GLOBAL | Flags::STICKY);
+    let start = if uses_last_index {
+        get_index(ctx, rx, "lastIndex")?
+    } else {
+        0
+    };
+
+    let found = if flags.contains(Flags::STICKY) {
+        regex.match_at(units, start)
+    } else {
+        regex.find(units, start)
+    };
+    let captures = match found {
+        Some(captures) => captures,
+        None => {
+            if uses_last_index {
+                set_last_index(ctx, rx, 0)?;
+            }
+            return Ok(JsValue::Null);
+        }
+    };
+    let whole = captures[0].clone().unwrap();
+    if uses_last_index {
+        set_last_index(ctx, rx, whole.end)?;
+    }
+
+    let capture_value = |capture: &Option<std::ops::Range<usize>>| match capture {
+        Some(range) => string(&units[range.clone()]),
+        None => JsValue::Undefined,
+    };
+    let values = captures.iter().map(capture_value).collect();
+    let mut array = JsObject::array(ctx, values);
+    array.create_data_property(
+        PropertyKey::from("index"),
+        JsValue::from(whole.start as f64),
+    );
+    array.create_data_property(PropertyKey::from("input"), string(units));
+
+    let groups = if regex.group_names().is_empty() {
+        JsValue::Undefined
+    } else {
+        let mut groups = JsObject::with_prototype(ctx, None, ObjectKind::Ordinary);
+        for (name, index) in regex.group_names() {
+            let key = PropertyKey::from(name.as_str());
+            groups.create_data_property(key, capture_value(&captures[*index]));
+        }
+        JsValue::object(groups)
+    };
+    array.create_data_property(PropertyKey::from("groups"), groups);
+    Ok(JsValue::object(array))
+}
+
+/// `RegExpExec`, a user defined `exec` is called instead of the built-in one
+fn regexp_exec(ctx: &mut Context, rx: &JsValue, units: &[u16]) -> ast::Result<JsValue> {
+    let exec = get(ctx, rx, "exec")?;
+    if exec.is_callable() {
+        let result = ctx.call(&exec, rx.clone(), vec![string(units)])?;
+        return match result {
+            JsValue::Object(_) | JsValue::Null => Ok(result),
+            _ => {
+                let message = "The result of exec must be an object or null";
+                Err(ctx.throw_error(ErrorKind::TypeError, message))
+            }
+        };
+    }
+
+    let regex = this_regexp(ctx, rx, "exec")?;
+    builtin_exec(ctx, rx, &regex, units)
+}
+
+/// The flags of `rx` as `flags` gives them, the generic methods read them from there
+fn flags_of(ctx: &mut Context, rx: &JsValue) -> ast::Result<String> {
+    get(ctx, rx, "flags")?.to_string_with(ctx)
+}
+
+/// Move `lastIndex` past an empty match, it would be found again otherwise
+fn skip_empty_match(
+    ctx: &mut Context,
+    rx: &JsValue,
+    units: &[u16],
+    unicode: bool,
+) -> ast::Result<()> {
+    let last_index = get_index(ctx, rx, "lastIndex")?;
+    set_last_index(ctx, rx, advance_string_index(units, last_index, unicode))
+}
+
+// RegExp
+
+#[js_function(name = "RegExp", length = 2)]
+fn construct(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let pattern = argument(&args, 0);
+    let original = match &pattern {
+        JsValue::Object(obj) => match &obj.kind {
+            ObjectKind::RegExp(regexp) => Some(regexp.regex().clone()),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    let source = match (&original, &pattern) {
+        (Some(regex), _) => regex.source().to_string(),
+        (None, JsValue::Undefined) => String::new(),
+        (None, pattern) => pattern.to_string_with(ctx)?,
+    };
+    let flags = match (argument(&args, 1), &original) {
+        (JsValue::Undefined, Some(regex)) => regex.flags().to_string(),
+        (JsValue::Undefined, None) => String::new(),
+        (flags, _) => flags.to_string_with(ctx)?,
+    };
+
+    match Regex::new(&source, &flags) {
+        Ok(regex) => Ok(JsValue::object(JsRegExp::create(ctx, Rc::new(regex)))),
+        Err(error) => Err(ctx.throw_error(ErrorKind::SyntaxError, &error.to_string())),
+    }
+}
+
+#[js_function(length = 1)]
+fn exec(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let regex = this_regexp(ctx, &this, "exec")?;
+    let units = units_argument(ctx, &args, 0)?;
+    builtin_exec(ctx, &this, &regex, &units)
+}
+
+#[js_function(length = 1)]
+fn test(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    this_object(ctx, &this, "test")?;
+    let units = units_argument(ctx, &args, 0)?;
+    let result = regexp_exec(ctx, &this, &units)?;
+    Ok(JsValue::bool(!result.is_null()))
+}
+
+#[js_function]
+fn to_string(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    this_object(ctx, &this, "toString")?;
+    let source = get(ctx, &this, "source")?.to_string_with(ctx)?;
+    let flags = flags_of(ctx, &this)?;
+    Ok(JsValue::string(format!("/{}/{}", source, flags).as_ref()))
+}
+
+#[js_function(name = "get flags")]
+fn flags(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    this_object(ctx, &this, "flags")?;
+    let mut flags = String::new();
+    for (letter, name) in [
+        ('g', "global"),
+        ('i', "ignoreCase"),
+        ('m', "multiline"),
+        ('s', "dotAll"),
+        ('u', "unicode"),
+        ('y', "sticky"),
+    ] {
+        if get(ctx, &this, name)?.to_boolean() {
+            flags.push(letter);
+        }
+    }
+    Ok(JsValue::string(flags.as_ref()))
+}
+
+#[js_function(name = "get source")]
+fn source(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    if is_regexp_prototype(ctx, &this) {
+        return Ok(JsValue::string("(?:)"));
+    }
+    let regex = this_regexp(ctx, &this, "source")?;
+    Ok(JsValue::string(escape_source(regex.source()).as_ref()))
+}
+
+/// Getter of one flag, `undefined` on `RegExp.prototype` itself
+fn flag(ctx: &mut Context, this: &JsValue, flag: Flags, name: &str) -> ast::Result<JsValue> {
+    if is_regexp_prototype(ctx, this) {
+        return Ok(JsValue::Undefined);
+    }
+    let regex = this_regexp(ctx, this, name)?;
+    Ok(JsValue::bool(regex.flags().contains(flag)))
+}
+
+#[js_function(name = "get global")]
+fn global(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    flag(ctx, &this, Flags::GLOBAL, "global")
+}
+
+#[js_function(name = "get ignoreCase")]
+fn ignore_case(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    flag(ctx, &this, Flags::IGNORE_CASE, "ignoreCase")
+}
+
+#[js_function(name = "get multiline")]
+fn multiline(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    flag(ctx, &this, Flags::MULTILINE, "multiline")
+}
+
+#[js_function(name = "get dotAll")]
+fn dot_all(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    flag(ctx, &this, Flags::DOT_ALL, "dotAll")
+}
+
+#[js_function(name = "get unicode")]
+fn unicode(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    flag(ctx, &this, Flags::UNICODE, "unicode")
+}
+
+#[js_function(name = "get sticky")]
+fn sticky(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    flag(ctx, &this, Flags::STICKY, "sticky")
+}
+
+/// The first match, or every matched string with the global flag
+#[js_function(name = "[Symbol.match]", length = 1)]
+fn symbol_match(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    this_object(ctx, &this, "[Symbol.match]")?;
+    let units = units_argument(ctx, &args, 0)?;
+    let flags = flags_of(ctx, &this)?;
+    if !flags.contains('g') {
+        return regexp_exec(ctx, &this, &units);
+    }
+
+    set_last_index(ctx, &this, 0)?;
+    let mut matches = Vec::new();
+    loop {
+        let result = regexp_exec(ctx, &this, &units)?;
+        if result.is_null() {
+            break;
+        }
+        let matched = get(ctx, &result, "0")?.to_string_with(ctx)?;
+        if matched.is_empty() {
+            skip_empty_match(ctx, &this, &units, flags.contains('u'))?;
+        }
+        matches.push(JsValue::string(matched.as_ref()));
+    }
+
+    if matches.is_empty() {
+        return Ok(JsValue::Null);
+    }
+    Ok(JsValue::object(JsObject::array(ctx, matches)))
+}
+
+#[js_function(name = "[Symbol.replace]", length = 2)]
+fn symbol_replace(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    this_object(ctx, &this, "[Symbol.replace]")?;
+    let units = units_argument(ctx, &args, 0)?;
+    let replacer = argument(&args, 1);
+    let replacement = if replacer.is_callable() {
+        Vec::new()
+    } else {
+        units_argument(ctx, &args, 1)?
+    };
+    let flags = flags_of(ctx, &this)?;
+    let global = flags.contains('g');
+
+    if global {
+        set_last_index(ctx, &this, 0)?;
+    }
+    let mut results = Vec::new();
+    loop {
+        let result = regexp_exec(ctx, &this, &units)?;
+        if result.is_null() {
+            break;
+        }
+        results.push(result.clone());
+        if !global {
+            break;
+        }
+        let matched = get(ctx, &result, "0")?.to_string_with(ctx)?;
+        if matched.is_empty() {
+            skip_empty_match(ctx, &this, &units, flags.contains('u'))?;
+        }
+    }
+
+    let mut replaced = Vec::new();
+    let mut next_position = 0;
+    for result in results {
+        let length = get_index(ctx, &result, "length")?;
+        let matched: Vec<u16> = get(ctx, &result, "0")?
+            .to_string_with(ctx)?
+            .encode_utf16()
+            .collect();
+        let position = get_index(ctx, &result, "index")?.min(units.len());
+
+        let mut captures = Vec::new();
+        for index in 1..length {
+            let capture = result.get_property(ctx, &PropertyKey::from(index as u32))?;
+            captures.push(match capture {
+                JsValue::Undefined => capture,
+                capture => JsValue::string(capture.to_string_with(ctx)?.as_ref()),
+            });
+        }
+        let groups = get(ctx, &result, "groups")?;
+
+        let replacement = if replacer.is_callable() {
+            let mut args = vec![string(&matched)];
+            args.extend(captures);
+            args.push(JsValue::from(position as f64));
+            args.push(string(&units));
+            if !groups.is_undefined() {
+                args.push(groups);
+            }
+            let value = ctx.call(&replacer, JsValue::Undefined, args)?;
+            value.to_string_with(ctx)?.encode_utf16().collect()
+        } else {
+            let groups = match groups {
+                JsValue::Undefined => groups,
+                groups => JsValue::object(groups.to_object(ctx)?),
+            };
+            substitute(
+                ctx,
+                &replacement,
+                &units,
+                position,
+                &matched,
+                &captures,
+                &groups,
+            )?
+        };
+
+        // A custom `exec` may report matches out of order, those are dropped
+        if position >= next_position {
+            replaced.extend_from_slice(&units[next_position..position]);
+            replaced.extend(replacement);
+            next_position = (position + matched.len()).min(units.len());
+        }
+    }
+    replaced.extend_from_slice(&units[next_position..]);
+    Ok(string(&replaced))
+}
+
+/// The pieces between the matches, with the groups of each match between them.
+///
+/// Only `RegExp` objects can split, they are matched at each position like with the sticky flag
+#[js_function(name = "[Symbol.split]", length = 2)]
+fn symbol_split(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let regex = this_regexp(ctx, &this, "[Symbol.split]")?;
+    let units = units_argument(ctx, &args, 0)?;
+    let limit = match argument(&args, 1) {
+        JsValue::Undefined => u32::MAX,
+        limit => limit.to_number_with(ctx)?.to_u32(),
+    } as usize;
+
+    let mut parts = Vec::new();
+    if limit == 0 {
+        return Ok(JsValue::object(JsObject::array(ctx, parts)));
+    }
+    if units.is_empty() {
+        if regex.match_at(&units, 0).is_none() {
+            parts.push(string(&units));
+        }
+        return Ok(JsValue::object(JsObject::array(ctx, parts)));
+    }
+
+    let mut start = 0;
+    let mut position = 0;
+    while position < units.len() {
+        let captures = match regex.match_at(&units, position) {
+            Some(captures) => captures,
+            None => {
+                position = regex.advance(&units, position);
+                continue;
+            }
+        };
+        let end = captures[0].as_ref().unwrap().end.min(units.len());
+        if end == start {
+            position = regex.advance(&units, position);
+            continue;
+        }
+
+        parts.push(string(&units[start..position]));
+        for capture in &captures[1..] {
+            if parts.len() == limit {
+                break;
+            }
+            parts.push(match capture {
+                Some(range) => string(&units[range.clone()]),
+                None => JsValue::Undefined,
+            });
+        }
+        if parts.len() == limit {
+            return Ok(JsValue::object(JsObject::array(ctx, parts)));
+        }
+        start = end;
+        position = end;
+    }
+
+    parts.push(string(&units[start..]));
+    Ok(JsValue::object(JsObject::array(ctx, parts)))
+}
+
+/// `RegExpCreate`, for `String.prototype.match` given something else than a `RegExp`
+pub(super) fn create(ctx: &mut Context, pattern: &JsValue) -> ast::Result<JsValue> {
+    construct(ctx, JsValue::Undefined, vec![pattern.clone()])
+}
+
+/// The flags of `value` when it is a `RegExp`, `replaceAll` requires them to be global
+pub(super) fn regexp_flags(ctx: &mut Context, value: &JsValue) -> ast::Result<Option<String>> {
+    let is_regexp = match value {
+        JsValue::Object(obj) => {
+            let symbol = ctx.well_known_symbols().r#match.clone();
+            let matcher = value.get_property(ctx, &PropertyKey::from(symbol))?;
+            match matcher {
+                JsValue::Undefined => matches!(obj.kind, ObjectKind::RegExp(_)),
+                matcher => matcher.to_boolean(),
+            }
+        }
+        _ => false,
+    };
+    if !is_regexp {
+        return Ok(None);
+    }
+    flags_of(ctx, value).map(Some)
+}
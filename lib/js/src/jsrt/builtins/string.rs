@@ -0,0 +1,674 @@
+use super::{
+    argument, define_symbol_method, define_to_string_tag, global::is_js_whitespace, regexp,
+    to_integer,
+};
+use crate::{
+    ast,
+    jsrt::{
+        create_iter_result, js_function, Attributes, ErrorKind, JsObject, JsString, JsSymbol,
+        JsValue, ObjectKind, PropertyKey, StringIterator,
+    },
+    vm::Context,
+};
+
+pub(super) fn install(ctx: &mut Context) {
+    let mut prototype = ctx.string_prototype();
+    for function in [
+        CHAR_AT,
+        CHAR_CODE_AT,
+        CODE_POINT_AT,
+        AT,
+        INDEX_OF,
+        LAST_INDEX_OF,
+        INCLUDES,
+        STARTS_WITH,
+        ENDS_WITH,
+        SLICE,
+        SUBSTRING,
+        TO_UPPER_CASE,
+        TO_LOWER_CASE,
+        TRIM,
+        TRIM_START,
+        TRIM_END,
+        MATCH,
+        SPLIT,
+        REPEAT,
+        PAD_START,
+        PAD_END,
+        CONCAT,
+        REPLACE,
+        REPLACE_ALL,
+        TO_STRING,
+        VALUE_OF,
+    ] {
+        prototype.define_method(ctx, function);
+    }
+    let iterator = ctx.well_known_symbols().iterator.clone();
+    define_symbol_method(
+        ctx,
+        &mut prototype,
+        iterator,
+        ITERATOR,
+        Attributes::WRITABLE | Attributes::CONFIGURABLE,
+    );
+
+    let mut constructor = JsObject::native_constructor(ctx, CONSTRUCT, prototype);
+    constructor.define_method(ctx, FROM_CHAR_CODE);
+    ctx.define_global("String", JsValue::object(constructor));
+
+    let mut iterator_prototype = ctx.string_iterator_prototype();
+    iterator_prototype.define_method(ctx, ITERATOR_NEXT);
+    define_to_string_tag(ctx, &mut iterator_prototype, "String Iterator");
+}
+
+/// `RequireObjectCoercible(this)` then `ToString`, the generic methods work on any value
+fn this_string(ctx: &mut Context, this: &JsValue, method: &str) -> ast::Result<JsString> {
+    match this {
+        JsValue::String(s) => Ok(s.clone()),
+        JsValue::Object(obj) => match &obj.kind {
+            ObjectKind::String(s) => Ok(s.clone()),
+            _ => Ok(JsString::new(this.to_string().as_ref())),
+        },
+        JsValue::Undefined | JsValue::Null => {
+            let message = format!("String.prototype.{} called on null or undefined", method);
+            Err(ctx.throw_error(ErrorKind::TypeError, &message))
+        }
+        value => Ok(JsString::new(value.to_string().as_ref())),
+    }
+}
+
+/// The code units of `args[index]` converted to a string
+fn string_argument(args: &[JsValue], index: usize) -> Vec<u16> {
+    match argument(args, index) {
+        JsValue::String(s) => s.to_utf16(),
+        value => value.to_string().encode_utf16().collect(),
+    }
+}
+
+fn string(units: &[u16]) -> JsValue {
+    JsValue::String(JsString::from_utf16(units))
+}
+
+/// `args[index]` as a position, clamped to `0..=length`
+fn position(
+    ctx: &mut Context,
+    args: &[JsValue],
+    index: usize,
+    length: usize,
+    default: usize,
+) -> ast::Result<usize> {
+    Ok(match argument(args, index) {
+        JsValue::Undefined => default,
+        value => to_integer(ctx, &value)?.clamp(0.0, length as f64) as usize,
+    })
+}
+
+/// Position given relative to the end when negative, clamped to `0..=length`
+fn relative_position(
+    ctx: &mut Context,
+    args: &[JsValue],
+    index: usize,
+    length: usize,
+    default: usize,
+) -> ast::Result<usize> {
+    Ok(match argument(args, index) {
+        JsValue::Undefined => default,
+        value => {
+            let position = to_integer(ctx, &value)?;
+            if position < 0.0 {
+                (length as f64 + position).max(0.0) as usize
+            } else {
+                position.min(length as f64) as usize
+            }
+        }
+    })
+}
+
+/// `args[0][symbol](this, ...args[1..])` when there is such a method, how regular expressions
+/// take over `match`, `replace` and `split`
+fn delegate(
+    ctx: &mut Context,
+    this: &JsValue,
+    args: &[JsValue],
+    symbol: JsSymbol,
+) -> ast::Result<Option<JsValue>> {
+    let target = argument(args, 0);
+    if target.is_undefined() || target.is_null() {
+        return Ok(None);
+    }
+    let method = target.get_property(ctx, &PropertyKey::from(symbol))?;
+    if method.is_undefined() || method.is_null() {
+        return Ok(None);
+    }
+
+    let mut method_args = vec![this.clone()];
+    method_args.extend(args.iter().skip(1).cloned());
+    ctx.call(&method, target, method_args).map(Some)
+}
+
+fn find(haystack: &[u16], needle: &[u16], from: usize) -> Option<usize> {
+    if needle.len() > haystack.len() {
+        return None;
+    }
+    (from..=haystack.len() - needle.len()).find(|&i| haystack[i..].starts_with(needle))
+}
+
+// String
+
+#[js_function(name = "String", length = 1)]
+fn construct(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    // The one place where symbols become strings, unless it is `new String(symbol)`
+    let value = match args.first() {
+        None => JsValue::string(""),
+        Some(JsValue::Symbol(symbol)) if !ctx.is_constructing() => {
+            JsValue::string(symbol.to_string().as_ref())
+        }
+        Some(JsValue::String(s)) => JsValue::String(s.clone()),
+        Some(value) => JsValue::string(value.to_string_with(ctx)?.as_ref()),
+    };
+    match ctx.is_constructing() {
+        true => Ok(JsValue::object(value.to_object(ctx)?)),
+        false => Ok(value),
+    }
+}
+
+#[js_function(length = 1)]
+fn from_char_code(_ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let units: Vec<_> = args.iter().map(JsValue::to_u16).collect();
+    Ok(string(&units))
+}
+
+// String.prototype
+
+#[js_function(length = 1)]
+fn char_at(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let s = this_string(ctx, &this, "charAt")?;
+    let index = to_integer(ctx, &argument(&args, 0))?;
+    Ok(match index {
+        index if index < 0.0 || index >= s.len() as f64 => JsValue::string(""),
+        index => JsValue::String(s.substring(index as usize, index as usize + 1)),
+    })
+}
+
+#[js_function(length = 1)]
+fn char_code_at(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let s = this_string(ctx, &this, "charCodeAt")?;
+    let index = to_integer(ctx, &argument(&args, 0))?;
+    Ok(match index {
+        index if index < 0.0 => JsValue::nan(),
+        index => s
+            .code_unit_at(index as usize)
+            .map_or_else(JsValue::nan, |unit| JsValue::integer(unit as i32)),
+    })
+}
+
+#[js_function(length = 1)]
+fn code_point_at(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let units = this_string(ctx, &this, "codePointAt")?.to_utf16();
+    let index = to_integer(ctx, &argument(&args, 0))?;
+    if index < 0.0 || index >= units.len() as f64 {
+        return Ok(JsValue::Undefined);
+    }
+    let index = index as usize;
+    let code_point = std::char::decode_utf16(units[index..].iter().copied())
+        .next()
+        .and_then(Result::ok)
+        .map_or(units[index] as u32, |c| c as u32);
+    Ok(JsValue::from(code_point))
+}
+
+#[js_function(length = 1)]
+fn at(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let s = this_string(ctx, &this, "at")?;
+    let length = s.len() as f64;
+    let index = match to_integer(ctx, &argument(&args, 0))? {
+        index if index < 0.0 => length + index,
+        index => index,
+    };
+    Ok(if index < 0.0 || index >= length {
+        JsValue::Undefined
+    } else {
+        JsValue::String(s.substring(index as usize, index as usize + 1))
+    })
+}
+
+#[js_function(length = 1)]
+fn index_of(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let units = this_string(ctx, &this, "indexOf")?.to_utf16();
+    let search = string_argument(&args, 0);
+    let start = position(ctx, &args, 1, units.len(), 0)?;
+    Ok(find(&units, &search, start).map_or(JsValue::integer(-1), |i| JsValue::from(i as u32)))
+}
+
+#[js_function(length = 1)]
+fn last_index_of(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let units = this_string(ctx, &this, "lastIndexOf")?.to_utf16();
+    let search = string_argument(&args, 0);
+    // A `NaN` position means from the end
+    let start = match argument(&args, 1).to_number_with(ctx)?.as_f64() {
+        position if position.is_nan() => units.len(),
+        _ => position(ctx, &args, 1, units.len(), units.len())?,
+    };
+    if search.len() > units.len() {
+        return Ok(JsValue::integer(-1));
+    }
+
+    let last = start.min(units.len() - search.len());
+    let found = (0..=last).rev().find(|&i| units[i..].starts_with(&search));
+    Ok(found.map_or(JsValue::integer(-1), |i| JsValue::from(i as u32)))
+}
+
+#[js_function(length = 1)]
+fn includes(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let units = this_string(ctx, &this, "includes")?.to_utf16();
+    let search = string_argument(&args, 0);
+    let start = position(ctx, &args, 1, units.len(), 0)?;
+    Ok(JsValue::Boolean(find(&units, &search, start).is_some()))
+}
+
+#[js_function(length = 1)]
+fn starts_with(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let units = this_string(ctx, &this, "startsWith")?.to_utf16();
+    let search = string_argument(&args, 0);
+    let start = position(ctx, &args, 1, units.len(), 0)?;
+    Ok(JsValue::Boolean(units[start..].starts_with(&search)))
+}
+
+#[js_function(length = 1)]
+fn ends_with(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let units = this_string(ctx, &this, "endsWith")?.to_utf16();
+    let search = string_argument(&args, 0);
+    let end = position(ctx, &args, 1, units.len(), units.len())?;
+    Ok(JsValue::Boolean(units[..end].ends_with(&search)))
+}
+
+#[js_function(length = 2)]
+fn slice(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let s = this_string(ctx, &this, "slice")?;
+    let length = s.len();
+    let start = relative_position(ctx, &args, 0, length, 0)?;
+    let end = relative_position(ctx, &args, 1, length, length)?;
+    Ok(JsValue::String(s.substring(start, end)))
+}
+
+#[js_function(length = 2)]
+fn substring(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let s = this_string(ctx, &this, "substring")?;
+    let length = s.len();
+    let start = position(ctx, &args, 0, length, 0)?;
+    let end = position(ctx, &args, 1, length, length)?;
+    Ok(JsValue::String(s.substring(start.min(end), start.max(end))))
+}
+
+#[js_function]
+fn to_upper_case(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let s = this_string(ctx, &this, "toUpperCase")?;
+    Ok(JsValue::string(s.string.to_uppercase().as_ref()))
+}
+
+#[js_function]
+fn to_lower_case(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let s = this_string(ctx, &this, "toLowerCase")?;
+    Ok(JsValue::string(s.string.to_lowercase().as_ref()))
+}
+
+#[js_function]
+fn trim(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let s = this_string(ctx, &this, "trim")?;
+    Ok(JsValue::string(s.string.trim_matches(is_js_whitespace)))
+}
+
+#[js_function]
+fn trim_start(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let s = this_string(ctx, &this, "trimStart")?;
+    Ok(JsValue::string(
+        s.string.trim_start_matches(is_js_whitespace),
+    ))
+}
+
+#[js_function]
+fn trim_end(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let s = this_string(ctx, &this, "trimEnd")?;
+    Ok(JsValue::string(s.string.trim_end_matches(is_js_whitespace)))
+}
+
+#[js_function(length = 1)]
+fn r#match(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let s = this_string(ctx, &this, "match")?;
+    let symbol = ctx.well_known_symbols().r#match.clone();
+    if let Some(result) = delegate(ctx, &this, &args, symbol.clone())? {
+        return Ok(result);
+    }
+
+    let rx = regexp::create(ctx, &argument(&args, 0))?;
+    let matcher = rx.get_property(ctx, &PropertyKey::from(symbol))?;
+    ctx.call(&matcher, rx, vec![JsValue::String(s)])
+}
+
+#[js_function(length = 2)]
+fn split(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let units = this_string(ctx, &this, "split")?.to_utf16();
+    let symbol = ctx.well_known_symbols().split.clone();
+    if let Some(result) = delegate(ctx, &this, &args, symbol)? {
+        return Ok(result);
+    }
+
+    let limit = match argument(&args, 1) {
+        JsValue::Undefined => u32::MAX,
+        limit => limit.to_number_with(ctx)?.to_u32(),
+    } as usize;
+
+    let mut parts = Vec::new();
+    match argument(&args, 0) {
+        JsValue::Undefined => parts.push(string(&units)),
+        _ => {
+            let separator = string_argument(&args, 0);
+            if separator.is_empty() {
+                parts.extend(units.iter().map(|unit| string(&[*unit])));
+            } else {
+                let mut start = 0;
+                while let Some(found) = find(&units, &separator, start) {
+                    parts.push(string(&units[start..found]));
+                    start = found + separator.len();
+                }
+                parts.push(string(&units[start..]));
+            }
+        }
+    }
+    parts.truncate(limit);
+    Ok(JsValue::object(JsObject::array(ctx, parts)))
+}
+
+#[js_function(length = 1)]
+fn repeat(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let s = this_string(ctx, &this, "repeat")?;
+    let count = to_integer(ctx, &argument(&args, 0))?;
+    if count < 0.0 || count.is_infinite() {
+        let message = format!("Invalid count value: {}", JsValue::from(count).to_string());
+        return Err(ctx.throw_error(ErrorKind::RangeError, &message));
+    }
+    if s.is_empty() {
+        return Ok(JsValue::string(""));
+    }
+    if count * s.len() as f64 > (1u64 << 29) as f64 {
+        return Err(ctx.throw_error(ErrorKind::RangeError, "Invalid string length"));
+    }
+    Ok(JsValue::string(s.string.repeat(count as usize).as_ref()))
+}
+
+/// `StringPad`, fills `this` up to `args[0]` code units with `args[1]`
+fn pad(ctx: &mut Context, this: JsValue, args: Vec<JsValue>, method: &str) -> ast::Result<JsValue> {
+    let units = this_string(ctx, &this, method)?.to_utf16();
+    let max_length = to_integer(ctx, &argument(&args, 0))?;
+    let fill = match argument(&args, 1) {
+        JsValue::Undefined => vec![b' ' as u16],
+        _ => string_argument(&args, 1),
+    };
+    if max_length <= units.len() as f64 || fill.is_empty() {
+        return Ok(string(&units));
+    }
+    if max_length > (1u64 << 29) as f64 {
+        return Err(ctx.throw_error(ErrorKind::RangeError, "Invalid string length"));
+    }
+
+    let padding: Vec<_> = fill
+        .iter()
+        .copied()
+        .cycle()
+        .take(max_length as usize - units.len())
+        .collect();
+    Ok(if method == "padStart" {
+        string(&[padding, units].concat())
+    } else {
+        string(&[units, padding].concat())
+    })
+}
+
+#[js_function(length = 1)]
+fn pad_start(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    pad(ctx, this, args, "padStart")
+}
+
+#[js_function(length = 1)]
+fn pad_end(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    pad(ctx, this, args, "padEnd")
+}
+
+#[js_function(length = 1)]
+fn concat(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let mut result = this_string(ctx, &this, "concat")?.string.to_string();
+    for arg in args {
+        result.push_str(&arg.to_string());
+    }
+    Ok(JsValue::string(result.as_ref()))
+}
+
+/// `GetSubstitution`, expands `$$`, `$&`, `` $` ``, `$'`, the groups `$1` to `$99` and the
+/// named groups `$<name>` in `replacement`
+pub(super) fn substitute(
+    ctx: &mut Context,
+    replacement: &[u16],
+    units: &[u16],
+    position: usize,
+    matched: &[u16],
+    captures: &[JsValue],
+    groups: &JsValue,
+) -> ast::Result<Vec<u16>> {
+    let digit = |i: usize| {
+        replacement
+            .get(i)
+            .and_then(|unit| char::from_u32(*unit as u32))
+            .and_then(|c| c.to_digit(10))
+            .map(|digit| digit as usize)
+    };
+    let mut result = Vec::new();
+    let mut i = 0;
+    while i < replacement.len() {
+        let next = replacement.get(i + 1).copied().unwrap_or(0);
+        if replacement[i] != b'$' as u16 {
+            result.push(replacement[i]);
+            i += 1;
+            continue;
+        }
+        match char::from_u32(next as u32) {
+            Some('$') => result.push(b'$' as u16),
+            Some('&') => result.extend_from_slice(matched),
+            Some('`') => result.extend_from_slice(&units[..position]),
+            Some('\'') => {
+                let end = (position + matched.len()).min(units.len());
+                result.extend_from_slice(&units[end..]);
+            }
+            Some('0'..='9') => {
+                // Two digits when they name a group, otherwise one
+                let first = digit(i + 1).unwrap();
+                let (index, length) = match digit(i + 2) {
+                    Some(second) if (1..=captures.len()).contains(&(first * 10 + second)) => {
+                        (first * 10 + second, 3)
+                    }
+                    _ => (first, 2),
+                };
+                if !(1..=captures.len()).contains(&index) {
+                    result.push(replacement[i]);
+                    i += 1;
+                    continue;
+                }
+                if let JsValue::String(capture) = &captures[index - 1] {
+                    result.extend(capture.to_utf16());
+                }
+                i += length;
+                continue;
+            }
+            Some('<') if !groups.is_undefined() => {
+                let close = replacement[i + 2..]
+                    .iter()
+                    .position(|unit| *unit == b'>' as u16);
+                let close = match close {
+                    Some(close) => i + 2 + close,
+                    None => {
+                        result.extend_from_slice(&replacement[i..i + 2]);
+                        i += 2;
+                        continue;
+                    }
+                };
+                let name = String::from_utf16_lossy(&replacement[i + 2..close]);
+                let capture = groups.get_property(ctx, &PropertyKey::from(name.as_str()))?;
+                if !capture.is_undefined() {
+                    result.extend(capture.to_string_with(ctx)?.encode_utf16());
+                }
+                i = close + 1;
+                continue;
+            }
+            _ => {
+                result.push(replacement[i]);
+                i += 1;
+                continue;
+            }
+        }
+        i += 2;
+    }
+    Ok(result)
+}
+
+/// `replace` and `replaceAll`, `Symbol.replace` of the pattern takes over unless it is a string.
+/// The replacement is a string or a function
+fn replace_matches(
+    ctx: &mut Context,
+    this: JsValue,
+    args: Vec<JsValue>,
+    method: &str,
+    all: bool,
+) -> ast::Result<JsValue> {
+    let units = this_string(ctx, &this, method)?.to_utf16();
+    if all {
+        let flags = regexp::regexp_flags(ctx, &argument(&args, 0))?;
+        if flags.is_some_and(|flags| !flags.contains('g')) {
+            let message = "replaceAll must be called with a global RegExp";
+            return Err(ctx.throw_error(ErrorKind::TypeError, message));
+        }
+    }
+    let symbol = ctx.well_known_symbols().replace.clone();
+    if let Some(result) = delegate(ctx, &this, &args, symbol)? {
+        return Ok(result);
+    }
+
+    let search = string_argument(&args, 0);
+    let replacer = match argument(&args, 1) {
+        JsValue::Object(obj) if obj.is_function() => Some(JsValue::object(obj)),
+        _ => None,
+    };
+    let replacement = string_argument(&args, 1);
+
+    let mut positions = Vec::new();
+    let mut start = 0;
+    while let Some(found) = find(&units, &search, start) {
+        positions.push(found);
+        if !all {
+            break;
+        }
+        // An empty pattern matches between every code unit
+        start = found + search.len().max(1);
+        if start > units.len() {
+            break;
+        }
+    }
+
+    let mut result = Vec::new();
+    let mut end = 0;
+    for position in positions {
+        result.extend_from_slice(&units[end..position]);
+        match &replacer {
+            Some(replacer) => {
+                let args = vec![
+                    string(&search),
+                    JsValue::from(position as u32),
+                    string(&units),
+                ];
+                let replaced = ctx.call(replacer, JsValue::Undefined, args)?;
+                result.extend(replaced.to_string().encode_utf16());
+            }
+            None => {
+                let substitution = substitute(
+                    ctx,
+                    &replacement,
+                    &units,
+                    position,
+                    &search,
+                    &[],
+                    &JsValue::Undefined,
+                )?;
+                result.extend(substitution);
+            }
+        }
+        end = position + search.len();
+    }
+    result.extend_from_slice(&units[end..]);
+    Ok(string(&result))
+}
+
+#[js_function(length = 2)]
+fn replace(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    replace_matches(ctx, this, args, "replace", false)
+}
+
+#[js_function(length = 2)]
+fn replace_all(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    replace_matches(ctx, this, args, "replaceAll", true)
+}
+
+/// `thisStringValue`, unlike the other methods only strings are accepted
+fn this_string_value(ctx: &mut Context, this: JsValue, method: &str) -> ast::Result<JsValue> {
+    match this {
+        JsValue::String(_) => Ok(this),
+        JsValue::Object(obj) => match &obj.kind {
+            ObjectKind::String(s) => Ok(JsValue::String(s.clone())),
+            _ => Err(incompatible_receiver(ctx, method)),
+        },
+        _ => Err(incompatible_receiver(ctx, method)),
+    }
+}
+
+fn incompatible_receiver(ctx: &mut Context, method: &str) -> ast::Completion {
+    let message = format!(
+        "String.prototype.{} requires that 'this' be a String",
+        method
+    );
+    ctx.throw_error(ErrorKind::TypeError, &message)
+}
+
+#[js_function]
+fn to_string(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    this_string_value(ctx, this, "toString")
+}
+
+#[js_function]
+fn value_of(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    this_string_value(ctx, this, "valueOf")
+}
+
+#[js_function(name = "[Symbol.iterator]")]
+fn iterator(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let s = this_string(ctx, &this, "[Symbol.iterator]")?;
+    Ok(JsValue::object(StringIterator::create(ctx, s)))
+}
+
+// %StringIteratorPrototype%
+
+#[js_function(name = "next")]
+fn iterator_next(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let next = match this {
+        JsValue::Object(mut obj) => match &mut obj.kind {
+            ObjectKind::StringIterator(iterator) => iterator.next(),
+            _ => return Err(incompatible_iterator(ctx)),
+        },
+        _ => return Err(incompatible_iterator(ctx)),
+    };
+
+    let done = next.is_none();
+    let value = next.map_or(JsValue::Undefined, JsValue::String);
+    Ok(create_iter_result(ctx, value, done))
+}
+
+fn incompatible_iterator(ctx: &mut Context) -> ast::Completion {
+    let message = "String Iterator.prototype.next called on incompatible receiver";
+    ctx.throw_error(ErrorKind::TypeError, message)
+}
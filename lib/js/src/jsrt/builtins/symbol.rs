@@ -0,0 +1,125 @@
+use super::{argument, define_symbol_method, define_to_string_tag};
+use crate::{
+    ast,
+    jsrt::{
+        js_function, Attributes, ErrorKind, JsObject, JsSymbol, JsValue, ObjectKind,
+        PropertyDescriptor, PropertyKey,
+    },
+    vm::Context,
+};
+
+pub(super) fn install(ctx: &mut Context) {
+    let symbols = ctx.well_known_symbols().clone();
+
+    let mut prototype = ctx.symbol_prototype();
+    for function in [TO_STRING, VALUE_OF] {
+        prototype.define_method(ctx, function);
+    }
+    let getter = JsValue::object(JsObject::native_function(ctx, DESCRIPTION));
+    prototype.define_own_property(
+        PropertyKey::from("description"),
+        PropertyDescriptor::accessor(getter, JsValue::Undefined, Attributes::CONFIGURABLE),
+    );
+    define_symbol_method(
+        ctx,
+        &mut prototype,
+        symbols.to_primitive.clone(),
+        TO_PRIMITIVE,
+        Attributes::CONFIGURABLE,
+    );
+    define_to_string_tag(ctx, &mut prototype, "Symbol");
+
+    let mut constructor = JsObject::native_constructor(ctx, CONSTRUCT, prototype);
+    for function in [FOR, KEY_FOR] {
+        constructor.define_method(ctx, function);
+    }
+    for (name, symbol) in symbols.all() {
+        constructor.define_property(name, JsValue::Symbol(symbol.clone()), Attributes::empty());
+    }
+    ctx.define_global("Symbol", JsValue::object(constructor));
+}
+
+/// `thisSymbolValue`
+fn this_symbol(ctx: &mut Context, this: &JsValue, method: &str) -> ast::Result<JsSymbol> {
+    match this {
+        JsValue::Symbol(symbol) => Ok(symbol.clone()),
+        JsValue::Object(obj) => match &obj.kind {
+            ObjectKind::Symbol(symbol) => Ok(symbol.clone()),
+            _ => Err(incompatible_receiver(ctx, method)),
+        },
+        _ => Err(incompatible_receiver(ctx, method)),
+    }
+}
+
+fn incompatible_receiver(ctx: &mut Context, method: &str) -> ast::Completion {
+    let message = format!(
+        "Symbol.prototype.{} requires that 'this' be a Symbol",
+        method
+    );
+    ctx.throw_error(ErrorKind::TypeError, &message)
+}
+
+// Symbol
+
+#[js_function(name = "Symbol")]
+fn construct(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    if ctx.is_constructing() {
+        return Err(ctx.throw_error(ErrorKind::TypeError, "Symbol is not a constructor"));
+    }
+    let description = match argument(&args, 0) {
+        JsValue::Undefined => None,
+        description => Some(description.to_string_with(ctx)?.as_str().into()),
+    };
+    Ok(JsValue::Symbol(JsSymbol::new(ctx, description)))
+}
+
+#[js_function(length = 1)]
+fn r#for(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let key = argument(&args, 0).to_string_with(ctx)?;
+    Ok(JsValue::Symbol(ctx.symbol_for(&key)))
+}
+
+#[js_function(length = 1)]
+fn key_for(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    match argument(&args, 0) {
+        JsValue::Symbol(symbol) => Ok(match symbol.registry_key() {
+            Some(key) => JsValue::string(key),
+            None => JsValue::Undefined,
+        }),
+        value => {
+            let message = format!("{} is not a symbol", value.to_string());
+            Err(ctx.throw_error(ErrorKind::TypeError, &message))
+        }
+    }
+}
+
+// Symbol.prototype
+
+#[js_function]
+fn to_string(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let symbol = this_symbol(ctx, &this, "toString")?;
+    Ok(JsValue::string(symbol.to_string().as_ref()))
+}
+
+#[js_function]
+fn value_of(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    Ok(JsValue::Symbol(this_symbol(ctx, &this, "valueOf")?))
+}
+
+#[js_function(name = "get description")]
+fn description(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let symbol = this_symbol(ctx, &this, "description")?;
+    Ok(match symbol.description() {
+        Some(description) => JsValue::string(description),
+        None => JsValue::Undefined,
+    })
+}
+
+#[js_function(name = "[Symbol.toPrimitive]", length = 1)]
+fn to_primitive(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    Ok(JsValue::Symbol(this_symbol(
+        ctx,
+        &this,
+        "[Symbol.toPrimitive]",
+    )?))
+}
@@ -0,0 +1,55 @@
+//! `setTimeout`, `clearTimeout` and `queueMicrotask`, timers are macrotasks the host runs
+//! through `Context::run_macrotask`
+use std::time::Duration;
+
+use super::{argument, to_integer};
+use crate::{
+    ast,
+    jsrt::{js_function, ErrorKind, Job, JsValue},
+    vm::Context,
+};
+
+pub(super) fn install(ctx: &mut Context) {
+    let mut global = ctx.global_object();
+    for function in [SET_TIMEOUT, CLEAR_TIMEOUT, QUEUE_MICROTASK] {
+        global.define_method(ctx, function);
+    }
+}
+
+fn callable_argument(ctx: &mut Context, args: &[JsValue], name: &str) -> ast::Result<JsValue> {
+    let callback = argument(args, 0);
+    if !callback.is_callable() {
+        let message = format!(
+            "Failed to execute '{}': parameter 1 is not of type 'Function'",
+            name
+        );
+        return Err(ctx.throw_error(ErrorKind::TypeError, &message));
+    }
+    Ok(callback)
+}
+
+/// `setTimeout(callback, delay, ...args)`, returns the id of the timer
+#[js_function(name = "setTimeout", length = 1)]
+fn set_timeout(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let callback = callable_argument(ctx, &args, "setTimeout")?;
+    let delay = to_integer(ctx, &argument(&args, 1))?.clamp(0.0, u32::MAX as f64);
+    let args = args.into_iter().skip(2).collect();
+    let id = ctx.enqueue_macrotask(Duration::from_millis(delay as u64), callback, args);
+    Ok(JsValue::from(id))
+}
+
+#[js_function(name = "clearTimeout", length = 1)]
+fn clear_timeout(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let id = to_integer(ctx, &argument(&args, 0))?;
+    if id >= 1.0 && id <= u32::MAX as f64 {
+        ctx.cancel_macrotask(id as u32);
+    }
+    Ok(JsValue::Undefined)
+}
+
+#[js_function(name = "queueMicrotask", length = 1)]
+fn queue_microtask(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let callback = callable_argument(ctx, &args, "queueMicrotask")?;
+    ctx.enqueue_job(Job::Callback(callback));
+    Ok(JsValue::Undefined)
+}
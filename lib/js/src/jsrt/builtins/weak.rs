@@ -0,0 +1,349 @@
+//! `WeakRef`, `WeakMap`, `WeakSet` and `FinalizationRegistry`, their targets and keys are
+//! objects held weakly by the collector
+use super::{argument, define_to_string_tag};
+use crate::{
+    ast,
+    gc::{EphemeronTable, GcPointer, WeakGcPointer},
+    jsrt::{
+        js_function, ErrorKind, FinalizationRegistry, IteratorRecord, JsObject, JsValue,
+        NativeFunction, ObjectKind, PropertyKey,
+    },
+    vm::Context,
+};
+
+pub(super) fn install(ctx: &mut Context) {
+    let classes: [(_, _, &[NativeFunction]); 4] = [
+        (ctx.weak_ref_prototype(), WEAK_REF, &[DEREF]),
+        (
+            ctx.weak_map_prototype(),
+            WEAK_MAP,
+            &[MAP_GET, MAP_SET, MAP_HAS, MAP_DELETE],
+        ),
+        (
+            ctx.weak_set_prototype(),
+            WEAK_SET,
+            &[SET_ADD, SET_HAS, SET_DELETE],
+        ),
+        (
+            ctx.finalization_registry_prototype(),
+            FINALIZATION_REGISTRY,
+            &[REGISTER, UNREGISTER],
+        ),
+    ];
+    for (mut prototype, constructor, methods) in classes {
+        for function in methods {
+            prototype.define_method(ctx, *function);
+        }
+        let name = constructor.name();
+        define_to_string_tag(ctx, &mut prototype, name);
+
+        let constructor = JsObject::native_constructor(ctx, constructor, prototype);
+        ctx.define_global(name, JsValue::object(constructor));
+    }
+}
+
+/// `this` if it is an object of the class, whose kind `is` matches
+fn this_object(
+    ctx: &mut Context,
+    this: &JsValue,
+    is: fn(&ObjectKind) -> bool,
+    class: &str,
+    method: &str,
+) -> ast::Result<GcPointer<JsObject>> {
+    match this {
+        JsValue::Object(obj) if is(&obj.kind) => Ok(obj.clone()),
+        _ => {
+            let message = format!(
+                "{}.prototype.{} requires that 'this' be a {}",
+                class, method, class
+            );
+            Err(ctx.throw_error(ErrorKind::TypeError, &message))
+        }
+    }
+}
+
+fn this_weak_map(
+    ctx: &mut Context,
+    this: &JsValue,
+    method: &str,
+) -> ast::Result<GcPointer<JsObject>> {
+    let is = |kind: &ObjectKind| matches!(kind, ObjectKind::WeakMap(_));
+    this_object(ctx, this, is, "WeakMap", method)
+}
+
+fn this_weak_set(
+    ctx: &mut Context,
+    this: &JsValue,
+    method: &str,
+) -> ast::Result<GcPointer<JsObject>> {
+    let is = |kind: &ObjectKind| matches!(kind, ObjectKind::WeakSet(_));
+    this_object(ctx, this, is, "WeakSet", method)
+}
+
+fn this_registry(
+    ctx: &mut Context,
+    this: &JsValue,
+    method: &str,
+) -> ast::Result<GcPointer<JsObject>> {
+    let is = |kind: &ObjectKind| matches!(kind, ObjectKind::FinalizationRegistry(_));
+    this_object(ctx, this, is, "FinalizationRegistry", method)
+}
+
+/// Call `add` with each value of `iterable`, closing it if `add` throws
+fn add_from_iterable(
+    ctx: &mut Context,
+    iterable: &JsValue,
+    mut add: impl FnMut(&mut Context, JsValue) -> ast::Result<()>,
+) -> ast::Result<()> {
+    if iterable.is_undefined() || iterable.is_null() {
+        return Ok(());
+    }
+
+    let mut record = IteratorRecord::from_iterable(ctx, iterable)?;
+    while let Some(value) = record.step(ctx)? {
+        if let Err(completion) = add(ctx, value) {
+            let _ = record.close(ctx);
+            return Err(completion);
+        }
+    }
+    Ok(())
+}
+
+// WeakRef
+
+#[js_function(name = "WeakRef", length = 1)]
+fn weak_ref(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let target = match argument(&args, 0) {
+        JsValue::Object(target) => target,
+        _ => {
+            let message = "WeakRef: invalid target";
+            return Err(ctx.throw_error(ErrorKind::TypeError, message));
+        }
+    };
+
+    ctx.keep_alive(target.clone());
+    let prototype = ctx.weak_ref_prototype();
+    let kind = ObjectKind::WeakRef(WeakGcPointer::new(&target));
+    let weak_ref = JsObject::with_prototype(ctx, Some(prototype), kind);
+    Ok(JsValue::object(weak_ref))
+}
+
+/// The target, or `undefined` once it was collected
+#[js_function]
+fn deref(ctx: &mut Context, this: JsValue, _args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let is = |kind: &ObjectKind| matches!(kind, ObjectKind::WeakRef(_));
+    let weak_ref = this_object(ctx, &this, is, "WeakRef", "deref")?;
+    let target = match &weak_ref.kind {
+        ObjectKind::WeakRef(target) => target.upgrade(),
+        _ => None,
+    };
+
+    match target {
+        Some(target) => {
+            ctx.keep_alive(target.clone());
+            Ok(JsValue::object(target))
+        }
+        None => Ok(JsValue::Undefined),
+    }
+}
+
+// WeakMap
+
+#[js_function(name = "WeakMap")]
+fn weak_map(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let prototype = ctx.weak_map_prototype();
+    let kind = ObjectKind::WeakMap(EphemeronTable::new());
+    let map = JsValue::object(JsObject::with_prototype(ctx, Some(prototype), kind));
+
+    add_from_iterable(ctx, &argument(&args, 0), |ctx, entry| {
+        if !matches!(entry, JsValue::Object(_)) {
+            let message = format!(
+                "Iterator value {} is not an entry object",
+                entry.to_string()
+            );
+            return Err(ctx.throw_error(ErrorKind::TypeError, &message));
+        }
+        let key = entry.get_property(ctx, &PropertyKey::from("0"))?;
+        let value = entry.get_property(ctx, &PropertyKey::from("1"))?;
+        map_set(ctx, map.clone(), vec![key, value]).map(|_| ())
+    })?;
+    Ok(map)
+}
+
+#[js_function(name = "get", length = 1)]
+fn map_get(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let map = this_weak_map(ctx, &this, "get")?;
+
+    Ok(match (&map.kind, argument(&args, 0)) {
+        (ObjectKind::WeakMap(entries), JsValue::Object(key)) => {
+            entries.get(&key).cloned().unwrap_or_default()
+        }
+        _ => JsValue::Undefined,
+    })
+}
+
+#[js_function(name = "set", length = 2)]
+fn map_set(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let mut map = this_weak_map(ctx, &this, "set")?;
+    let key = match argument(&args, 0) {
+        JsValue::Object(key) => key,
+        _ => {
+            let message = "Invalid value used as weak map key";
+            return Err(ctx.throw_error(ErrorKind::TypeError, message));
+        }
+    };
+
+    if let ObjectKind::WeakMap(entries) = &mut map.kind {
+        entries.insert(&key, argument(&args, 1));
+    }
+    Ok(this)
+}
+
+#[js_function(name = "has", length = 1)]
+fn map_has(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let map = this_weak_map(ctx, &this, "has")?;
+
+    Ok(JsValue::Boolean(match (&map.kind, argument(&args, 0)) {
+        (ObjectKind::WeakMap(entries), JsValue::Object(key)) => entries.contains(&key),
+        _ => false,
+    }))
+}
+
+#[js_function(name = "delete", length = 1)]
+fn map_delete(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let mut map = this_weak_map(ctx, &this, "delete")?;
+
+    Ok(JsValue::Boolean(
+        match (&mut map.kind, argument(&args, 0)) {
+            (ObjectKind::WeakMap(entries), JsValue::Object(key)) => entries.remove(&key),
+            _ => false,
+        },
+    ))
+}
+
+// WeakSet
+
+#[js_function(name = "WeakSet")]
+fn weak_set(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let prototype = ctx.weak_set_prototype();
+    let kind = ObjectKind::WeakSet(EphemeronTable::new());
+    let set = JsValue::object(JsObject::with_prototype(ctx, Some(prototype), kind));
+
+    add_from_iterable(ctx, &argument(&args, 0), |ctx, value| {
+        set_add(ctx, set.clone(), vec![value]).map(|_| ())
+    })?;
+    Ok(set)
+}
+
+#[js_function(name = "add", length = 1)]
+fn set_add(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let mut set = this_weak_set(ctx, &this, "add")?;
+    let value = match argument(&args, 0) {
+        JsValue::Object(value) => value,
+        _ => {
+            let message = "Invalid value used in weak set";
+            return Err(ctx.throw_error(ErrorKind::TypeError, message));
+        }
+    };
+
+    if let ObjectKind::WeakSet(entries) = &mut set.kind {
+        entries.insert(&value, ());
+    }
+    Ok(this)
+}
+
+#[js_function(name = "has", length = 1)]
+fn set_has(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let set = this_weak_set(ctx, &this, "has")?;
+
+    Ok(JsValue::Boolean(match (&set.kind, argument(&args, 0)) {
+        (ObjectKind::WeakSet(entries), JsValue::Object(value)) => entries.contains(&value),
+        _ => false,
+    }))
+}
+
+#[js_function(name = "delete", length = 1)]
+fn set_delete(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let mut set = this_weak_set(ctx, &this, "delete")?;
+
+    Ok(JsValue::Boolean(
+        match (&mut set.kind, argument(&args, 0)) {
+            (ObjectKind::WeakSet(entries), JsValue::Object(value)) => entries.remove(&value),
+            _ => false,
+        },
+    ))
+}
+
+// FinalizationRegistry
+
+#[js_function(name = "FinalizationRegistry", length = 1)]
+fn finalization_registry(
+    ctx: &mut Context,
+    _this: JsValue,
+    args: Vec<JsValue>,
+) -> ast::Result<JsValue> {
+    let cleanup = argument(&args, 0);
+    if !cleanup.is_callable() {
+        let message = "FinalizationRegistry: cleanup must be callable";
+        return Err(ctx.throw_error(ErrorKind::TypeError, message));
+    }
+
+    let prototype = ctx.finalization_registry_prototype();
+    let kind = ObjectKind::FinalizationRegistry(FinalizationRegistry::new(cleanup));
+    let registry = JsObject::with_prototype(ctx, Some(prototype), kind);
+    ctx.add_finalization_registry(&registry);
+    Ok(JsValue::object(registry))
+}
+
+/// `register(target, heldValue, unregisterToken)`, the callback gets `heldValue` once `target`
+/// is collected
+#[js_function(length = 2)]
+fn register(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let mut registry = this_registry(ctx, &this, "register")?;
+    let target = match argument(&args, 0) {
+        JsValue::Object(target) => target,
+        _ => {
+            let message = "FinalizationRegistry.prototype.register: invalid target";
+            return Err(ctx.throw_error(ErrorKind::TypeError, message));
+        }
+    };
+    let held_value = argument(&args, 1);
+    if held_value.same_value(&JsValue::object(target.clone())) {
+        let message =
+            "FinalizationRegistry.prototype.register: target and holdings must not be same";
+        return Err(ctx.throw_error(ErrorKind::TypeError, message));
+    }
+    let token = match argument(&args, 2) {
+        JsValue::Object(token) => Some(token),
+        JsValue::Undefined => None,
+        _ => {
+            let message = "FinalizationRegistry.prototype.register: invalid unregister token";
+            return Err(ctx.throw_error(ErrorKind::TypeError, message));
+        }
+    };
+
+    if let ObjectKind::FinalizationRegistry(registry) = &mut registry.kind {
+        registry.register(&target, held_value, token.as_ref());
+    }
+    Ok(JsValue::Undefined)
+}
+
+#[js_function(length = 1)]
+fn unregister(ctx: &mut Context, this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let mut registry = this_registry(ctx, &this, "unregister")?;
+    let token = match argument(&args, 0) {
+        JsValue::Object(token) => token,
+        _ => {
+            let message = format!(
+                "Invalid unregisterToken ('{}')",
+                argument(&args, 0).to_string()
+            );
+            return Err(ctx.throw_error(ErrorKind::TypeError, &message));
+        }
+    };
+
+    Ok(JsValue::Boolean(match &mut registry.kind {
+        ObjectKind::FinalizationRegistry(registry) => registry.unregister(&token),
+        _ => false,
+    }))
+}
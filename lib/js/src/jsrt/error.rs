@@ -0,0 +1,69 @@
+use std::fmt;
+
+use super::{JsObject, JsValue, NativeFn, NativeFunction};
+use crate::{ast, gc::Trace, vm::Context};
+
+/// The native error constructors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Error,
+    TypeError,
+    RangeError,
+    SyntaxError,
+    ReferenceError,
+}
+
+unsafe impl Trace for ErrorKind {}
+
+impl ErrorKind {
+    pub const ALL: [ErrorKind; 5] = [
+        ErrorKind::Error,
+        ErrorKind::TypeError,
+        ErrorKind::RangeError,
+        ErrorKind::SyntaxError,
+        ErrorKind::ReferenceError,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErrorKind::Error => "Error",
+            ErrorKind::TypeError => "TypeError",
+            ErrorKind::RangeError => "RangeError",
+            ErrorKind::SyntaxError => "SyntaxError",
+            ErrorKind::ReferenceError => "ReferenceError",
+        }
+    }
+
+    /// `Error(message)` and friends
+    pub fn constructor(&self) -> NativeFunction {
+        fn construct(
+            kind: ErrorKind,
+            context: &mut Context,
+            args: Vec<JsValue>,
+        ) -> ast::Result<JsValue> {
+            let message = match args.into_iter().next() {
+                None | Some(JsValue::Undefined) => String::new(),
+                Some(message) => message.to_string(),
+            };
+            Ok(JsValue::object(JsObject::error(context, kind, &message)))
+        }
+
+        let function: NativeFn = match self {
+            ErrorKind::Error => |ctx, _, args| construct(ErrorKind::Error, ctx, args),
+            ErrorKind::TypeError => |ctx, _, args| construct(ErrorKind::TypeError, ctx, args),
+            ErrorKind::RangeError => |ctx, _, args| construct(ErrorKind::RangeError, ctx, args),
+            ErrorKind::SyntaxError => |ctx, _, args| construct(ErrorKind::SyntaxError, ctx, args),
+            ErrorKind::ReferenceError => {
+                |ctx, _, args| construct(ErrorKind::ReferenceError, ctx, args)
+            }
+        };
+
+        NativeFunction::new(self.name(), 1, function)
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
@@ -0,0 +1,219 @@
+use std::{fmt, rc::Rc};
+
+use super::{JsObject, JsValue, PrivateElement};
+use crate::{
+    ast::{self, FunctionKind},
+    gc::*,
+    vm::{CodeBlock, Context, LexicalEnvironment},
+};
+
+/// Body of a function, walked by the AST interpreter or run by the VM
+#[derive(Clone)]
+pub enum FunctionCode {
+    Ast(Rc<ast::Function>),
+    Bytecode(Rc<CodeBlock>),
+}
+
+/// Field of a class, defined on each instance with the value of its initializer
+#[derive(Debug, Clone, GcTrace)]
+pub struct ClassField {
+    /// Property key, or the private symbol of a `#field`
+    pub(crate) key: JsValue,
+    /// Method returning the initial value, called with the instance as `this`
+    pub(crate) initializer: Option<JsValue>,
+}
+
+/// Closure over the scope the function was created in
+#[derive(Clone, GcTrace)]
+pub struct JsFunction {
+    #[unsafe_ignore_trace]
+    code: FunctionCode,
+    environment: GcPointer<LexicalEnvironment>,
+    /// Object of the method, `super.x` looks into its prototype
+    home_object: Option<GcPointer<JsObject>>,
+    /// What a class constructor adds to its instances, the private methods first
+    private_methods: Vec<PrivateElement>,
+    fields: Vec<ClassField>,
+}
+
+impl JsFunction {
+    pub fn new(code: FunctionCode, environment: GcPointer<LexicalEnvironment>) -> JsFunction {
+        JsFunction {
+            code,
+            environment,
+            home_object: None,
+            private_methods: Vec::new(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match &self.code {
+            FunctionCode::Ast(body) => body.name().map_or("", |ident| ident.name()),
+            FunctionCode::Bytecode(code) => &code.name,
+        }
+    }
+
+    /// Number of parameters before the first one with a default value or the rest one
+    pub fn length(&self) -> usize {
+        match &self.code {
+            FunctionCode::Ast(body) => body.params().length(),
+            FunctionCode::Bytecode(code) => code.length,
+        }
+    }
+
+    pub fn kind(&self) -> FunctionKind {
+        match &self.code {
+            FunctionCode::Ast(body) => body.kind(),
+            FunctionCode::Bytecode(code) => code.kind,
+        }
+    }
+
+    pub fn is_generator(&self) -> bool {
+        match &self.code {
+            FunctionCode::Ast(body) => body.is_generator(),
+            FunctionCode::Bytecode(code) => code.is_generator,
+        }
+    }
+
+    pub fn is_async(&self) -> bool {
+        match &self.code {
+            FunctionCode::Ast(body) => body.is_async(),
+            FunctionCode::Bytecode(code) => code.is_async,
+        }
+    }
+
+    /// Whether it sees the `this` of its scope instead of getting one when called
+    pub fn is_arrow(&self) -> bool {
+        self.kind() == FunctionKind::Arrow
+    }
+
+    /// Whether `new` can be applied to it, methods, arrow functions, generators and async
+    /// functions can only be called
+    pub fn is_constructor(&self) -> bool {
+        matches!(
+            self.kind(),
+            FunctionKind::Normal | FunctionKind::BaseConstructor | FunctionKind::DerivedConstructor
+        ) && !self.is_generator()
+            && !self.is_async()
+    }
+
+    /// Constructors of classes throw when called without `new`
+    pub fn is_class_constructor(&self) -> bool {
+        matches!(
+            self.kind(),
+            FunctionKind::BaseConstructor | FunctionKind::DerivedConstructor
+        )
+    }
+
+    pub fn home_object(&self) -> Option<GcPointer<JsObject>> {
+        self.home_object.clone()
+    }
+
+    pub(crate) fn set_home_object(&mut self, home_object: GcPointer<JsObject>) {
+        self.home_object = Some(home_object);
+    }
+
+    pub(crate) fn private_methods(&self) -> &[PrivateElement] {
+        &self.private_methods
+    }
+
+    pub(crate) fn fields(&self) -> &[ClassField] {
+        &self.fields
+    }
+
+    /// Instance elements of the class this constructor belongs to
+    pub(crate) fn set_class_elements(
+        &mut self,
+        private_methods: Vec<PrivateElement>,
+        fields: Vec<ClassField>,
+    ) {
+        self.private_methods = private_methods;
+        self.fields = fields;
+    }
+
+    pub fn code(&self) -> &FunctionCode {
+        &self.code
+    }
+
+    pub fn environment(&self) -> GcPointer<LexicalEnvironment> {
+        self.environment.clone()
+    }
+}
+
+impl fmt::Debug for JsFunction {
+    // The environment usually contains the function itself
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "JsFunction {{ name: {:?} }}", self.name())
+    }
+}
+
+/// Body of a native function, called with `this` and the arguments
+pub type NativeFn = fn(&mut Context, JsValue, Vec<JsValue>) -> ast::Result<JsValue>;
+
+/// Function implemented in Rust, usually declared with `#[js_function]`
+#[derive(Clone, Copy)]
+pub struct NativeFunction {
+    name: &'static str,
+    length: u32,
+    function: NativeFn,
+    /// Built-in constructors also run for `new`, see `Context::is_constructing`
+    constructor: bool,
+}
+
+unsafe impl Trace for NativeFunction {}
+
+impl NativeFunction {
+    pub const fn new(name: &'static str, length: u32, function: NativeFn) -> NativeFunction {
+        NativeFunction {
+            name,
+            length,
+            function,
+            constructor: false,
+        }
+    }
+
+    /// Same function, which `new` can be applied to
+    pub const fn as_constructor(self) -> NativeFunction {
+        NativeFunction {
+            constructor: true,
+            ..self
+        }
+    }
+
+    pub fn is_constructor(&self) -> bool {
+        self.constructor
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Number of arguments it expects, for the `length` property
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    pub fn call(
+        &self,
+        context: &mut Context,
+        this: JsValue,
+        args: Vec<JsValue>,
+    ) -> ast::Result<JsValue> {
+        (self.function)(context, this, args)
+    }
+}
+
+impl fmt::Debug for NativeFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "NativeFunction {{ name: {:?} }}", self.name)
+    }
+}
+
+/// Result of `Function.prototype.bind`, calls `target` with a fixed `this` and leading arguments
+#[derive(Debug, GcTrace)]
+pub struct BoundFunction {
+    pub(crate) target: GcPointer<JsObject>,
+    pub(crate) this: JsValue,
+    pub(crate) args: Vec<JsValue>,
+}
@@ -0,0 +1,230 @@
+use std::{fmt, rc::Rc};
+
+use super::{
+    create_iter_result, js_function, ErrorKind, JsObject, JsPromise, JsValue, ObjectKind,
+    PropertyKey,
+};
+use crate::{
+    ast::{self, Completion},
+    gc::{GcPointer, GcTrace, Trace, Tracer},
+    vm::{CodeBlock, Context, Frame, Outcome, Resume, Suspension},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GeneratorState {
+    /// Created by the call, its body hasn't run past the parameters yet
+    SuspendedStart,
+    SuspendedYield,
+    Executing,
+    Completed,
+}
+
+unsafe impl Trace for GeneratorState {}
+
+/// A suspended call of a generator function, or of an async function waiting for a promise
+#[derive(GcTrace)]
+pub struct JsGenerator {
+    state: GeneratorState,
+    #[unsafe_ignore_trace]
+    code: Rc<CodeBlock>,
+    /// Taken while the body runs
+    frame: Option<Frame>,
+    /// Promise of the result of an async function
+    promise: Option<GcPointer<JsObject>>,
+}
+
+impl fmt::Debug for JsGenerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "JsGenerator {{ name: {:?}, state: {:?} }}",
+            self.code.name, self.state
+        )
+    }
+}
+
+impl JsGenerator {
+    /// Generator object of a call of a generator function, inheriting from its `prototype`
+    pub fn create(ctx: &mut Context, code: Rc<CodeBlock>, frame: Frame) -> ast::Result<JsValue> {
+        let prototype = match ctx.active_function() {
+            Some(function) => {
+                let function = JsValue::object(function);
+                function.get_property(ctx, &PropertyKey::from("prototype"))?
+            }
+            None => JsValue::Undefined,
+        };
+        let prototype = match prototype {
+            JsValue::Object(prototype) => prototype,
+            _ => ctx.generator_prototype(),
+        };
+
+        let generator = JsGenerator {
+            state: GeneratorState::SuspendedStart,
+            code,
+            frame: Some(frame),
+            promise: None,
+        };
+        let generator =
+            JsObject::with_prototype(ctx, Some(prototype), ObjectKind::Generator(generator));
+        Ok(JsValue::object(generator))
+    }
+
+    /// Run an async function until its first `await`, returning the promise of its result
+    pub fn start_async(ctx: &mut Context, code: Rc<CodeBlock>, frame: Frame) -> JsValue {
+        let promise = JsPromise::create(ctx);
+        let generator = JsGenerator {
+            state: GeneratorState::SuspendedStart,
+            code,
+            frame: Some(frame),
+            promise: Some(promise.clone()),
+        };
+        let generator = JsObject::with_prototype(ctx, None, ObjectKind::Generator(generator));
+        JsGenerator::async_step(ctx, generator, None);
+        JsValue::object(promise)
+    }
+
+    /// Run the body until it suspends again, `None` once it returned
+    fn resume(
+        ctx: &mut Context,
+        generator: &mut GcPointer<JsObject>,
+        resumed: Option<Resume>,
+    ) -> ast::Result<(Option<Suspension>, JsValue)> {
+        let (code, frame) = match &mut generator.kind {
+            ObjectKind::Generator(g) => {
+                g.state = GeneratorState::Executing;
+                (
+                    g.code.clone(),
+                    g.frame.take().expect("generator without a frame"),
+                )
+            }
+            _ => unreachable!("resume() on an object which isn't a generator"),
+        };
+
+        let outcome = ctx.resume(&code, frame, resumed);
+        let state = match &outcome {
+            Ok(Outcome::Suspended(..)) => GeneratorState::SuspendedYield,
+            _ => GeneratorState::Completed,
+        };
+        let (suspension, value, frame) = match outcome? {
+            Outcome::Suspended(suspension, value, frame) => (Some(suspension), value, Some(frame)),
+            Outcome::Returned(value) => (None, value, None),
+        };
+        if let ObjectKind::Generator(g) = &mut generator.kind {
+            g.state = state;
+            g.frame = frame;
+        }
+        Ok((suspension, value))
+    }
+
+    /// `GeneratorResume` and `GeneratorResumeAbrupt`, for `next`, `throw` and `return`
+    pub fn resume_generator(
+        ctx: &mut Context,
+        this: &JsValue,
+        resumed: Resume,
+        method: &str,
+    ) -> ast::Result<JsValue> {
+        let state = match this {
+            JsValue::Object(obj) => match &obj.kind {
+                ObjectKind::Generator(g) if g.promise.is_none() => Some(g.state),
+                _ => None,
+            },
+            _ => None,
+        };
+        let mut generator = match (this, state) {
+            (JsValue::Object(obj), Some(_)) => obj.clone(),
+            _ => {
+                let message = format!(
+                    "{} method called on incompatible receiver {}",
+                    method,
+                    this.to_string()
+                );
+                return Err(ctx.throw_error(ErrorKind::TypeError, &message));
+            }
+        };
+
+        let resumed = match (state.unwrap(), resumed) {
+            (GeneratorState::Executing, _) => {
+                let message = "Generator is already running";
+                return Err(ctx.throw_error(ErrorKind::TypeError, message));
+            }
+            // A generator which hasn't started completes without running
+            (GeneratorState::SuspendedStart, resumed @ Resume::Return(_))
+            | (GeneratorState::SuspendedStart, resumed @ Resume::Throw(_)) => {
+                if let ObjectKind::Generator(g) = &mut generator.kind {
+                    g.state = GeneratorState::Completed;
+                    g.frame = None;
+                }
+                return JsGenerator::completed(ctx, resumed);
+            }
+            (GeneratorState::Completed, resumed) => return JsGenerator::completed(ctx, resumed),
+            (GeneratorState::SuspendedStart, _) => None,
+            (GeneratorState::SuspendedYield, resumed) => Some(resumed),
+        };
+
+        match JsGenerator::resume(ctx, &mut generator, resumed)? {
+            (Some(Suspension::YieldDelegate), result) => Ok(result),
+            (Some(_), value) => Ok(create_iter_result(ctx, value, false)),
+            (None, value) => Ok(create_iter_result(ctx, value, true)),
+        }
+    }
+
+    /// What a generator which is done does when resumed
+    fn completed(ctx: &mut Context, resumed: Resume) -> ast::Result<JsValue> {
+        match resumed {
+            Resume::Next(_) => Ok(create_iter_result(ctx, JsValue::Undefined, true)),
+            Resume::Return(value) => Ok(create_iter_result(ctx, value, true)),
+            Resume::Throw(value) => Err(Completion::Throw(value)),
+        }
+    }
+
+    /// Run an async function until its next `await`, which resumes it once the awaited value
+    /// settles, or until it settles its promise
+    fn async_step(ctx: &mut Context, mut generator: GcPointer<JsObject>, resumed: Option<Resume>) {
+        let promise = match &generator.kind {
+            ObjectKind::Generator(g) => g.promise.clone().expect("async step of a generator"),
+            _ => unreachable!("async step of an object which isn't a generator"),
+        };
+
+        match JsGenerator::resume(ctx, &mut generator, resumed) {
+            Ok((Some(_), value)) => {
+                let awaited = JsPromise::promise_resolve(ctx, value);
+                let captures = vec![JsValue::object(generator)];
+                let on_fulfilled = JsObject::native_closure(ctx, ASYNC_FULFILLED, captures.clone());
+                let on_rejected = JsObject::native_closure(ctx, ASYNC_REJECTED, captures);
+                JsPromise::perform_then(
+                    ctx,
+                    &awaited,
+                    JsValue::object(on_fulfilled),
+                    JsValue::object(on_rejected),
+                    None,
+                );
+            }
+            Ok((None, value)) => JsPromise::resolve(ctx, &promise, value),
+            Err(Completion::Throw(reason)) => JsPromise::reject(ctx, &promise, reason),
+            Err(_) => unreachable!("an async function completed with a jump"),
+        }
+    }
+}
+
+/// `[generator, value]`, the generator is bound
+fn async_captures(args: Vec<JsValue>) -> (GcPointer<JsObject>, JsValue) {
+    let mut args = args.into_iter();
+    match (args.next(), args.next()) {
+        (Some(JsValue::Object(generator)), value) => (generator, value.unwrap_or_default()),
+        _ => unreachable!("await reaction without its generator"),
+    }
+}
+
+#[js_function(name = "", length = 1)]
+fn async_fulfilled(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let (generator, value) = async_captures(args);
+    JsGenerator::async_step(ctx, generator, Some(Resume::Next(value)));
+    Ok(JsValue::Undefined)
+}
+
+#[js_function(name = "", length = 1)]
+fn async_rejected(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let (generator, reason) = async_captures(args);
+    JsGenerator::async_step(ctx, generator, Some(Resume::Throw(reason)));
+    Ok(JsValue::Undefined)
+}
@@ -0,0 +1,364 @@
+use super::{
+    JsObject, JsSymbol, JsValue, ObjectKind, PromiseState, PropertyDescriptor, PropertyKey,
+};
+use crate::{ast::FunctionKind, gc::GcPointer, vm::Context};
+
+/// Objects nested deeper are shown as `[Object]`
+const MAX_DEPTH: usize = 2;
+const MAX_ARRAY_LENGTH: u32 = 100;
+/// Longest single line output before the entries go on their own lines
+const BREAK_LENGTH: usize = 72;
+
+/// Readable representation of `value` in the style of Node's `util.inspect`, for a REPL.
+///
+/// No JS code runs, getters are shown as `[Getter]` instead of being called
+pub fn inspect(ctx: &Context, value: &JsValue) -> String {
+    let mut inspector = Inspector {
+        to_string_tag: ctx.well_known_symbols().to_string_tag.clone(),
+        function_prototype: ctx.function_prototype(),
+        seen: Vec::new(),
+    };
+    inspector.value(value, 0)
+}
+
+struct Inspector {
+    to_string_tag: JsSymbol,
+    function_prototype: GcPointer<JsObject>,
+    /// The objects being inspected, from the outermost one
+    seen: Vec<GcPointer<JsObject>>,
+}
+
+impl Inspector {
+    fn value(&mut self, value: &JsValue, depth: usize) -> String {
+        match value {
+            JsValue::Rational(n) if *n == 0.0 && n.is_sign_negative() => "-0".into(),
+            JsValue::BigInt(n) => format!("{}n", n),
+            JsValue::String(s) => quote(s.string.as_ref()),
+            JsValue::Object(object) => self.object(object, depth),
+            value => value.to_string(),
+        }
+    }
+
+    fn object(&mut self, object: &GcPointer<JsObject>, depth: usize) -> String {
+        if self.seen.iter().any(|seen| seen.ptr_eq(object)) {
+            return "[Circular]".into();
+        }
+
+        // Shown alone when there is no property to add after it
+        let base = match &object.kind {
+            ObjectKind::Function(_)
+            | ObjectKind::NativeFunction(_)
+            | ObjectKind::BoundFunction(_) => Some(self.function(object)),
+            ObjectKind::Error(_) => Some(error(object)),
+            ObjectKind::RegExp(regexp) => {
+                let regex = regexp.regex();
+                Some(format!("/{}/{}", regex.source(), regex.flags()))
+            }
+            ObjectKind::Boolean(b) => Some(format!("[Boolean: {}]", b)),
+            ObjectKind::Number(n) => Some(format!(
+                "[Number: {}]",
+                self.value(&JsValue::Rational(*n), depth)
+            )),
+            ObjectKind::BigInt(n) => Some(format!("[BigInt: {}n]", n)),
+            ObjectKind::String(s) => Some(format!("[String: {}]", quote(s.string.as_ref()))),
+            ObjectKind::Symbol(symbol) => Some(format!("[Symbol: {}]", symbol)),
+            _ => None,
+        };
+        let prefix = match (&base, &object.kind) {
+            (Some(base), _) => base.clone(),
+            (None, ObjectKind::Namespace(_)) => "[Module: null prototype]".into(),
+            (None, _) => self.prefix(object),
+        };
+
+        if depth > MAX_DEPTH {
+            return match (base, &object.kind) {
+                (Some(base), _) => base,
+                (None, ObjectKind::Array(_)) => "[Array]".into(),
+                (None, _) => {
+                    let name = self.constructor_name(object);
+                    format!("[{}]", name.as_deref().unwrap_or("Object"))
+                }
+            };
+        }
+
+        self.seen.push(object.clone());
+        let mut entries = Vec::new();
+        let (open, close) = match &object.kind {
+            ObjectKind::Array(_) => ("[", "]"),
+            _ => ("{", "}"),
+        };
+        match &object.kind {
+            ObjectKind::Array(array) => {
+                let length = array.length();
+                let mut next = 0;
+                for index in array.indices() {
+                    if entries.len() as u32 >= MAX_ARRAY_LENGTH {
+                        break;
+                    }
+                    if index > next {
+                        entries.push(empty_items(index - next));
+                        next = index;
+                        if entries.len() as u32 >= MAX_ARRAY_LENGTH {
+                            break;
+                        }
+                    }
+                    if let Some(desc) = array.element(index) {
+                        entries.push(self.property(&desc, depth));
+                    }
+                    next = index + 1;
+                }
+                if next < length {
+                    entries.push(match entries.len() as u32 >= MAX_ARRAY_LENGTH {
+                        true => format!("... {} more item{}", length - next, plural(length - next)),
+                        false => empty_items(length - next),
+                    });
+                }
+            }
+            ObjectKind::Promise(promise) => entries.push(match promise.state() {
+                PromiseState::Pending => "<pending>".into(),
+                PromiseState::Fulfilled => self.value(&promise.result(), depth + 1),
+                PromiseState::Rejected => {
+                    format!("<rejected> {}", self.value(&promise.result(), depth + 1))
+                }
+            }),
+            ObjectKind::WeakMap(_) | ObjectKind::WeakSet(_) => {
+                entries.push("<items unknown>".into())
+            }
+            ObjectKind::Namespace(namespace) => {
+                for key in namespace.exports() {
+                    let value = match namespace.value(key) {
+                        Some(Ok(value)) => self.value(&value, depth + 1),
+                        _ => "<uninitialized>".into(),
+                    };
+                    entries.push(format!("{}: {}", property_key(key), value));
+                }
+            }
+            _ => {}
+        }
+
+        for key in object.own_property_keys() {
+            let skip = match (&object.kind, &key) {
+                (ObjectKind::Array(_), PropertyKey::Index(_)) => true,
+                (ObjectKind::String(s), PropertyKey::Index(index)) => (*index as usize) < s.len(),
+                (ObjectKind::Array(_), key) | (ObjectKind::String(_), key) => key.is_length(),
+                (ObjectKind::Namespace(_), _) => true,
+                _ => false,
+            };
+            if skip {
+                continue;
+            }
+            if let Some(desc) = object
+                .get_own_property(&key)
+                .filter(|desc| desc.enumerable())
+            {
+                let value = self.property(&desc, depth);
+                entries.push(format!("{}: {}", property_key(&key), value));
+            }
+        }
+        self.seen.pop();
+
+        match base {
+            Some(base) if entries.is_empty() => base,
+            _ => join(&prefix, open, close, &entries),
+        }
+    }
+
+    fn property(&mut self, desc: &PropertyDescriptor, depth: usize) -> String {
+        if let Some(value) = &desc.value {
+            return self.value(value, depth + 1);
+        }
+        let is_function =
+            |accessor: &Option<JsValue>| accessor.as_ref().is_some_and(JsValue::is_callable);
+        match (is_function(&desc.get), is_function(&desc.set)) {
+            (true, true) => "[Getter/Setter]".into(),
+            (true, false) => "[Getter]".into(),
+            (false, true) => "[Setter]".into(),
+            (false, false) => "undefined".into(),
+        }
+    }
+
+    fn function(&self, object: &GcPointer<JsObject>) -> String {
+        let name = match own_data_property(object, "name") {
+            Some(JsValue::String(name)) if !name.is_empty() => Some(name.string.to_string()),
+            _ => None,
+        };
+        let kind = match &object.kind {
+            ObjectKind::Function(function) if function.is_class_constructor() => {
+                let name = name.unwrap_or_else(|| "(anonymous)".into());
+                if function.kind() != FunctionKind::DerivedConstructor {
+                    return format!("[class {}]", name);
+                }
+                let parent = match object.get_prototype_of() {
+                    Some(parent) if parent.ptr_eq(&self.function_prototype) => {
+                        return format!("[class {}]", name)
+                    }
+                    Some(parent) => match own_data_property(&parent, "name") {
+                        Some(JsValue::String(parent)) if !parent.is_empty() => {
+                            parent.string.to_string()
+                        }
+                        _ => "(anonymous)".into(),
+                    },
+                    None => "null".into(),
+                };
+                return format!("[class {} extends {}]", name, parent);
+            }
+            ObjectKind::Function(function) => {
+                match (function.is_async(), function.is_generator()) {
+                    (true, true) => "AsyncGeneratorFunction",
+                    (true, false) => "AsyncFunction",
+                    (false, true) => "GeneratorFunction",
+                    (false, false) => "Function",
+                }
+            }
+            _ => "Function",
+        };
+        match name {
+            Some(name) => format!("[{}: {}]", kind, name),
+            None => format!("[{} (anonymous)]", kind),
+        }
+    }
+
+    /// Name of the constructor and `@@toStringTag` of the object, left out when it is a plain
+    /// object or array
+    fn prefix(&self, object: &GcPointer<JsObject>) -> String {
+        let tag = match object.get_data_property(self.to_string_tag.clone()) {
+            JsValue::String(tag) if !tag.is_empty() => Some(tag.string.to_string()),
+            _ => None,
+        };
+        let name = match self.constructor_name(object) {
+            Some(name) => name.to_string(),
+            None => "[Object: null prototype]".into(),
+        };
+        let mut prefix = match (&object.kind, name.as_str()) {
+            (ObjectKind::Array(_), "Array") if tag.is_none() => return String::new(),
+            (ObjectKind::Array(array), _) => format!("{}({})", name, array.length()),
+            (_, "Object") if tag.is_none() => return String::new(),
+            _ => name.clone(),
+        };
+        if let Some(tag) = tag.filter(|tag| *tag != name) {
+            prefix = format!("{} [{}]", prefix, tag);
+        }
+        prefix
+    }
+
+    /// `name` of the first function found as the `constructor` of a prototype of the object,
+    /// `None` for objects without prototype
+    fn constructor_name(&self, object: &GcPointer<JsObject>) -> Option<String> {
+        let mut prototype = object.get_prototype_of()?;
+        loop {
+            if let Some(JsValue::Object(constructor)) = own_data_property(&prototype, "constructor")
+            {
+                if constructor.is_function() {
+                    if let Some(JsValue::String(name)) = own_data_property(&constructor, "name") {
+                        return Some(name.string.to_string());
+                    }
+                }
+            }
+            prototype = match prototype.get_prototype_of() {
+                Some(next) => next,
+                None => return Some("Object".into()),
+            };
+        }
+    }
+}
+
+/// Value of a data property of the object itself, namespaces aren't read to not throw
+fn own_data_property(object: &GcPointer<JsObject>, key: &str) -> Option<JsValue> {
+    if let ObjectKind::Namespace(_) = object.kind {
+        return None;
+    }
+    object.get_own_property(&PropertyKey::from(key))?.value
+}
+
+/// The stack of an error, between brackets when it has no frame
+fn error(object: &GcPointer<JsObject>) -> String {
+    let stack = match own_data_property(object, "stack") {
+        Some(JsValue::String(stack)) => stack.string.to_string(),
+        _ => JsValue::object(object.clone()).to_string(),
+    };
+    match stack.contains("\n    at ") {
+        true => stack,
+        false => format!("[{}]", stack),
+    }
+}
+
+fn empty_items(count: u32) -> String {
+    format!("<{} empty item{}>", count, plural(count))
+}
+
+fn plural(count: u32) -> &'static str {
+    match count {
+        1 => "",
+        _ => "s",
+    }
+}
+
+/// `{ a: 1 }` on one line when it is short enough, else each entry on its own line
+fn join(prefix: &str, open: &str, close: &str, entries: &[String]) -> String {
+    let start = match prefix.is_empty() {
+        true => open.to_string(),
+        false => format!("{} {}", prefix, open),
+    };
+    if entries.is_empty() {
+        return format!("{}{}", start, close);
+    }
+
+    let length = start.len() + entries.iter().map(|entry| entry.len() + 2).sum::<usize>() + 1;
+    if length <= BREAK_LENGTH && !entries.iter().any(|entry| entry.contains('\n')) {
+        format!("{} {} {}", start, entries.join(", "), close)
+    } else {
+        let body = entries.join(",\n").replace('\n', "\n  ");
+        format!("{}\n  {}\n{}", start, body, close)
+    }
+}
+
+fn property_key(key: &PropertyKey) -> String {
+    match key {
+        PropertyKey::Index(index) => index.to_string(),
+        PropertyKey::String(name) if is_identifier(name.as_ref()) => name.to_string(),
+        PropertyKey::String(name) => quote(name.as_ref()),
+        PropertyKey::Symbol(symbol) => format!("[{}]", symbol),
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+/// String literal of `s`, in single quotes unless it contains some
+fn quote(s: &str) -> String {
+    let quote = if !s.contains('\'') {
+        '\''
+    } else if !s.contains('"') {
+        '"'
+    } else if !s.contains('`') && !s.contains("${") {
+        '`'
+    } else {
+        '\''
+    };
+
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push(quote);
+    for c in s.chars() {
+        match c {
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            '\r' => result.push_str("\\r"),
+            '\u{8}' => result.push_str("\\b"),
+            '\u{c}' => result.push_str("\\f"),
+            '\u{b}' => result.push_str("\\v"),
+            c if c == quote || c == '\\' => {
+                result.push('\\');
+                result.push(c);
+            }
+            c if c.is_control() => result.push_str(&format!("\\x{:02X}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push(quote);
+    result
+}
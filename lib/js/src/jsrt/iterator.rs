@@ -0,0 +1,117 @@
+use super::{ErrorKind, JsObject, JsValue, PropertyKey};
+use crate::{
+    ast,
+    gc::{GcPointer, GcTrace, Trace, Tracer},
+    vm::Context,
+};
+
+/// `IteratorRecord`, an iterator object with the `next` method it had when it was obtained
+#[derive(GcTrace)]
+pub struct IteratorRecord {
+    iterator: JsValue,
+    next_method: JsValue,
+    done: bool,
+}
+
+impl IteratorRecord {
+    pub fn new(iterator: JsValue, next_method: JsValue) -> IteratorRecord {
+        IteratorRecord {
+            iterator,
+            next_method,
+            done: false,
+        }
+    }
+
+    /// `GetIterator`, what `value[Symbol.iterator]()` returns
+    pub fn from_iterable(ctx: &mut Context, value: &JsValue) -> ast::Result<IteratorRecord> {
+        let key = PropertyKey::from(ctx.well_known_symbols().iterator.clone());
+        let method = value.get_property(ctx, &key)?;
+        if !method.is_callable() {
+            let message = format!("{} is not iterable", value.to_string());
+            return Err(ctx.throw_error(ErrorKind::TypeError, &message));
+        }
+
+        let iterator = ctx.call(&method, value.clone(), Vec::new())?;
+        if !matches!(iterator, JsValue::Object(_)) {
+            let message = "Result of the Symbol.iterator method is not an object";
+            return Err(ctx.throw_error(ErrorKind::TypeError, message));
+        }
+        let next_method = iterator.get_property(ctx, &PropertyKey::from("next"))?;
+        Ok(IteratorRecord::new(iterator, next_method))
+    }
+
+    /// The iterator and its `next` method
+    pub fn parts(self) -> (JsValue, JsValue) {
+        (self.iterator, self.next_method)
+    }
+
+    /// `IteratorStep` and `IteratorValue`, `None` once the iterator is done.
+    ///
+    /// An iterator that throws is done as well, it won't be closed
+    pub fn step(&mut self, ctx: &mut Context) -> ast::Result<Option<JsValue>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        self.done = true;
+        let result = ctx.call(&self.next_method, self.iterator.clone(), Vec::new())?;
+        let result = iterator_result(ctx, result)?;
+        if result
+            .get(
+                ctx,
+                &PropertyKey::from("done"),
+                JsValue::object(result.clone()),
+            )?
+            .to_boolean()
+        {
+            return Ok(None);
+        }
+
+        let value = result.get(
+            ctx,
+            &PropertyKey::from("value"),
+            JsValue::object(result.clone()),
+        )?;
+        self.done = false;
+        Ok(Some(value))
+    }
+
+    /// `IteratorClose`, for loops left before the iterator is done.
+    ///
+    /// Callers leaving because of an exception ignore the result, their exception wins
+    pub fn close(&mut self, ctx: &mut Context) -> ast::Result<()> {
+        if self.done {
+            return Ok(());
+        }
+
+        self.done = true;
+        let method = self
+            .iterator
+            .get_property(ctx, &PropertyKey::from("return"))?;
+        if method.is_undefined() || method.is_null() {
+            return Ok(());
+        }
+        let result = ctx.call(&method, self.iterator.clone(), Vec::new())?;
+        iterator_result(ctx, result)?;
+        Ok(())
+    }
+}
+
+/// `CreateIterResultObject`, what the `next` of the built-in iterators return
+pub fn create_iter_result(ctx: &mut Context, value: JsValue, done: bool) -> JsValue {
+    let mut result = JsObject::new(ctx);
+    result.create_data_property(PropertyKey::from("value"), value);
+    result.create_data_property(PropertyKey::from("done"), JsValue::Boolean(done));
+    JsValue::object(result)
+}
+
+/// The objects returned by `next` and `return`
+fn iterator_result(ctx: &mut Context, result: JsValue) -> ast::Result<GcPointer<JsObject>> {
+    match result {
+        JsValue::Object(obj) => Ok(obj),
+        result => {
+            let message = format!("Iterator result {} is not an object", result.to_string());
+            Err(ctx.throw_error(ErrorKind::TypeError, &message))
+        }
+    }
+}
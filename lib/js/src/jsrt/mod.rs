@@ -1,7 +1,37 @@
+mod array;
+mod bigint;
+mod builtins;
+mod error;
+mod function;
+mod generator;
+mod inspect;
+mod iterator;
 mod object;
+mod promise;
+mod property;
+mod regexp;
+mod shape;
 mod string;
+mod symbol;
 mod value;
+mod weak;
 
-pub use object::JsObject;
-pub use string::JsString;
-pub use value::JsValue;
+pub(crate) use array::list_from_array_like;
+pub use array::{ArrayIterator, IterationKind, JsArray};
+pub use bigint::JsBigInt;
+pub(crate) use builtins::install as install_builtins;
+pub use error::ErrorKind;
+pub use function::{BoundFunction, ClassField, FunctionCode, JsFunction, NativeFn, NativeFunction};
+pub use generator::{GeneratorState, JsGenerator};
+pub use inspect::inspect;
+pub use iterator::{create_iter_result, IteratorRecord};
+pub(crate) use js_derive::js_function;
+pub use object::{JsObject, ObjectKind, PrivateElement, PrivateElementKind};
+pub use promise::{Job, JsPromise, PromiseCapability, PromiseReaction, PromiseState};
+pub use property::{Attributes, PropertyDescriptor, PropertyKey, Slot};
+pub use regexp::JsRegExp;
+pub use shape::{PropertyMap, Shape};
+pub use string::{JsString, StringIterator};
+pub use symbol::{JsSymbol, SymbolData, WellKnownSymbols};
+pub use value::{JsValue, PreferredType};
+pub use weak::FinalizationRegistry;
@@ -1,29 +1,676 @@
-use std::{collections::HashMap, ops::Deref};
+use std::{collections::HashSet, fmt, rc::Rc};
 
-use super::{JsString, JsValue};
-use crate::{gc::*, vm::Context};
+use super::{
+    ArrayIterator, Attributes, BoundFunction, ErrorKind, FinalizationRegistry, FunctionCode,
+    JsArray, JsBigInt, JsFunction, JsGenerator, JsPromise, JsRegExp, JsString, JsSymbol, JsValue,
+    NativeFunction, PropertyDescriptor, PropertyKey, PropertyMap, Shape, Slot, StringIterator,
+};
+use crate::{
+    ast::{self, FunctionKind},
+    gc::*,
+    vm::{Context, ModuleNamespace},
+};
 
 #[derive(Debug, GcTrace)]
+pub enum ObjectKind {
+    Ordinary,
+    Function(JsFunction),
+    NativeFunction(NativeFunction),
+    BoundFunction(BoundFunction),
+    Error(ErrorKind),
+    /// Wrappers made by `Object(primitive)` and the prototypes of the primitives
+    Boolean(bool),
+    Number(f64),
+    BigInt(JsBigInt),
+    String(JsString),
+    Symbol(JsSymbol),
+    Array(JsArray),
+    ArrayIterator(ArrayIterator),
+    StringIterator(StringIterator),
+    RegExp(JsRegExp),
+    /// The target of a `WeakRef`, cleared once it is collected
+    WeakRef(WeakGcPointer<JsObject>),
+    /// The entries of a `WeakMap`, by key
+    WeakMap(EphemeronTable<JsValue>),
+    WeakSet(EphemeronTable<()>),
+    FinalizationRegistry(FinalizationRegistry),
+    Promise(JsPromise),
+    /// Also the suspended body of an async function, which has no prototype
+    Generator(JsGenerator),
+    /// `import * as ns`, its exports are properties which can't be changed from it
+    Namespace(ModuleNamespace),
+}
+
+/// Field, method or accessor of an object named by a `#name` of its class, which only the code
+/// of the class can reach
+#[derive(Debug, Clone, GcTrace)]
+pub struct PrivateElement {
+    pub(crate) key: JsSymbol,
+    pub(crate) kind: PrivateElementKind,
+}
+
+#[derive(Debug, Clone, GcTrace)]
+pub enum PrivateElementKind {
+    Field(JsValue),
+    Method(JsValue),
+    /// `undefined` when the getter or the setter is missing
+    Accessor {
+        get: JsValue,
+        set: JsValue,
+    },
+}
+
+#[derive(GcTrace)]
 pub struct JsObject {
-    properties: HashMap<u64, GcPointer<JsValue>>,
+    properties: PropertyMap,
+    prototype: Option<GcPointer<JsObject>>,
+    extensible: bool,
+    /// Not properties, they are not inherited and even frozen objects get new ones
+    private_elements: Vec<PrivateElement>,
+    pub(crate) kind: ObjectKind,
 }
 
 impl JsObject {
+    /// Object with no property, not allocated yet
+    pub fn from_parts(
+        shape: Rc<Shape>,
+        prototype: Option<GcPointer<JsObject>>,
+        kind: ObjectKind,
+    ) -> JsObject {
+        JsObject {
+            properties: PropertyMap::new(shape),
+            prototype,
+            extensible: true,
+            private_elements: Vec::new(),
+            kind,
+        }
+    }
+
+    /// Empty object inheriting from `Object.prototype`
     pub fn new(ctx: &mut Context) -> GcPointer<JsObject> {
-        ctx.allocate(JsObject {
-            properties: HashMap::new(),
+        let prototype = ctx.object_prototype();
+        JsObject::with_prototype(ctx, Some(prototype), ObjectKind::Ordinary)
+    }
+
+    pub fn with_prototype(
+        ctx: &mut Context,
+        prototype: Option<GcPointer<JsObject>>,
+        kind: ObjectKind,
+    ) -> GcPointer<JsObject> {
+        let shape = ctx.root_shape();
+        ctx.allocate(JsObject::from_parts(shape, prototype, kind))
+    }
+
+    /// Function object closing over the current scope of `ctx`, plain functions get a fresh
+    /// `prototype` object and generator functions one for their generators
+    pub fn function(ctx: &mut Context, code: FunctionCode) -> GcPointer<JsObject> {
+        let function = JsFunction::new(code, ctx.environment());
+        let name = JsValue::string(function.name());
+        let length = JsValue::integer(function.length() as i32);
+        let (is_generator, is_async) = (function.is_generator(), function.is_async());
+        let has_prototype = function.kind() == FunctionKind::Normal && !is_async;
+
+        let function_prototype = match (is_generator, is_async) {
+            (true, _) => ctx.generator_function_prototype(),
+            (false, true) => ctx.async_function_prototype(),
+            _ => ctx.function_prototype(),
+        };
+        let mut obj = JsObject::with_prototype(
+            ctx,
+            Some(function_prototype),
+            ObjectKind::Function(function),
+        );
+        obj.define_property("length", length, Attributes::CONFIGURABLE);
+        obj.define_property("name", name, Attributes::CONFIGURABLE);
+        if !has_prototype {
+            return obj;
+        }
+
+        // The generators inherit from it but don't see the function as their constructor
+        let prototype = if is_generator {
+            let generator_prototype = ctx.generator_prototype();
+            JsObject::with_prototype(ctx, Some(generator_prototype), ObjectKind::Ordinary)
+        } else {
+            let mut prototype = JsObject::new(ctx);
+            prototype.define_property(
+                "constructor",
+                JsValue::object(obj.clone()),
+                Attributes::WRITABLE | Attributes::CONFIGURABLE,
+            );
+            prototype
+        };
+        obj.define_property(
+            "prototype",
+            JsValue::object(prototype),
+            Attributes::WRITABLE,
+        );
+        obj
+    }
+
+    pub fn native_function(ctx: &mut Context, function: NativeFunction) -> GcPointer<JsObject> {
+        let name = JsValue::string(function.name());
+        let length = JsValue::from(function.length());
+        let function_prototype = ctx.function_prototype();
+        let mut obj = JsObject::with_prototype(
+            ctx,
+            Some(function_prototype),
+            ObjectKind::NativeFunction(function),
+        );
+        obj.define_property("length", length, Attributes::CONFIGURABLE);
+        obj.define_property("name", name, Attributes::CONFIGURABLE);
+        obj
+    }
+
+    /// Native function called with `captures` before its arguments, for the functions the
+    /// built-ins create such as the resolving functions of promises
+    pub fn native_closure(
+        ctx: &mut Context,
+        function: NativeFunction,
+        captures: Vec<JsValue>,
+    ) -> GcPointer<JsObject> {
+        let target = JsObject::native_function(ctx, function);
+        let bound = BoundFunction {
+            target,
+            this: JsValue::Undefined,
+            args: captures,
+        };
+        let function_prototype = ctx.function_prototype();
+        let mut closure = JsObject::with_prototype(
+            ctx,
+            Some(function_prototype),
+            ObjectKind::BoundFunction(bound),
+        );
+        let length = JsValue::from(function.length());
+        closure.define_property("length", length, Attributes::CONFIGURABLE);
+        closure.define_property(
+            "name",
+            JsValue::string(function.name()),
+            Attributes::CONFIGURABLE,
+        );
+        closure
+    }
+
+    /// Built-in constructor linked both ways with its `prototype`
+    pub fn native_constructor(
+        ctx: &mut Context,
+        function: NativeFunction,
+        mut prototype: GcPointer<JsObject>,
+    ) -> GcPointer<JsObject> {
+        let mut constructor = JsObject::native_function(ctx, function.as_constructor());
+        constructor.define_property(
+            "prototype",
+            JsValue::object(prototype.clone()),
+            Attributes::empty(),
+        );
+        prototype.define_property(
+            "constructor",
+            JsValue::object(constructor.clone()),
+            Attributes::WRITABLE | Attributes::CONFIGURABLE,
+        );
+        constructor
+    }
+
+    /// Array holding `values`, inheriting from `Array.prototype`
+    pub fn array(ctx: &mut Context, values: Vec<JsValue>) -> GcPointer<JsObject> {
+        let prototype = ctx.array_prototype();
+        JsObject::with_prototype(
+            ctx,
+            Some(prototype),
+            ObjectKind::Array(JsArray::from_values(values)),
+        )
+    }
+
+    /// Error object with the current call stack of `ctx` as its `stack`, its `name` comes from
+    /// the prototype of `kind`
+    pub fn error(ctx: &mut Context, kind: ErrorKind, message: &str) -> GcPointer<JsObject> {
+        let prototype = ctx.error_prototype(kind);
+        let mut error = JsObject::with_prototype(ctx, Some(prototype), ObjectKind::Error(kind));
+
+        let summary = if message.is_empty() {
+            kind.name().to_string()
+        } else {
+            format!("{}: {}", kind.name(), message)
+        };
+        let stack = format!("{}{}", summary, ctx.stack_trace());
+
+        let attributes = Attributes::WRITABLE | Attributes::CONFIGURABLE;
+        if !message.is_empty() {
+            error.define_property("message", JsValue::string(message), attributes);
+        }
+        error.define_property("stack", JsValue::string(stack.as_ref()), attributes);
+        error
+    }
+
+    pub fn is_function(&self) -> bool {
+        matches!(
+            self.kind,
+            ObjectKind::Function(_) | ObjectKind::NativeFunction(_) | ObjectKind::BoundFunction(_)
+        )
+    }
+
+    /// `IsConstructor`, whether `new` can be applied to it
+    pub fn is_constructor(&self) -> bool {
+        match &self.kind {
+            ObjectKind::Function(function) => function.is_constructor(),
+            ObjectKind::NativeFunction(function) => function.is_constructor(),
+            ObjectKind::BoundFunction(bound) => bound.target.is_constructor(),
+            _ => false,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self.kind, ObjectKind::Error(_))
+    }
+
+    pub fn is_array(&self) -> bool {
+        matches!(self.kind, ObjectKind::Array(_))
+    }
+
+    pub fn properties(&self) -> &PropertyMap {
+        &self.properties
+    }
+
+    // Internal methods of ordinary objects
+
+    pub fn get_prototype_of(&self) -> Option<GcPointer<JsObject>> {
+        self.prototype.clone()
+    }
+
+    /// Fails on a non extensible object or if it would create a cycle
+    pub fn set_prototype_of(&mut self, prototype: Option<GcPointer<JsObject>>) -> bool {
+        let same = match (&self.prototype, &prototype) {
+            (Some(current), Some(new)) => current.ptr_eq(new),
+            (None, None) => true,
+            _ => false,
+        };
+        if same {
+            return true;
+        }
+        if !self.extensible {
+            return false;
+        }
+
+        let mut parent = prototype.clone();
+        while let Some(obj) = parent {
+            if std::ptr::eq(&*obj, self) {
+                return false;
+            }
+            parent = obj.prototype.clone();
+        }
+
+        self.prototype = prototype;
+        true
+    }
+
+    pub fn is_extensible(&self) -> bool {
+        self.extensible
+    }
+
+    pub fn prevent_extensions(&mut self) -> bool {
+        self.extensible = false;
+        true
+    }
+
+    /// `SetIntegrityLevel`, make every own property non configurable, and read only if `frozen`
+    pub fn set_integrity_level(&mut self, frozen: bool) -> bool {
+        self.prevent_extensions();
+        self.own_property_keys().into_iter().all(|key| {
+            let current = self.get_own_property(&key).unwrap_or_default();
+            let desc = PropertyDescriptor {
+                configurable: Some(false),
+                writable: (frozen && current.is_data_descriptor()).then_some(false),
+                ..Default::default()
+            };
+            self.define_own_property(key, desc)
         })
     }
 
-    pub fn put_property(&mut self, key: GcPointer<JsString>, value: GcPointer<JsValue>) {
-        self.properties.insert(key.string.hash(), value);
+    pub fn get_own_property(&self, key: &PropertyKey) -> Option<PropertyDescriptor> {
+        if let ObjectKind::Array(array) = &self.kind {
+            match key {
+                PropertyKey::Index(index) => return array.element(*index),
+                key if key.is_length() => return Some(array.length_descriptor()),
+                _ => {}
+            }
+        }
+        if let ObjectKind::String(s) = &self.kind {
+            if let Some(desc) = string_property(s, key) {
+                return Some(desc);
+            }
+        }
+        if let ObjectKind::Namespace(namespace) = &self.kind {
+            if let Some(value) = namespace.value(key) {
+                let attributes = Attributes::WRITABLE | Attributes::ENUMERABLE;
+                return Some(PropertyDescriptor::data(
+                    value.unwrap_or_default(),
+                    attributes,
+                ));
+            }
+        }
+
+        self.properties
+            .get(key)
+            .map(|(slot, attributes)| PropertyDescriptor::from_slot(slot, attributes))
+    }
+
+    /// `[[DefineOwnProperty]]`, false if the change isn't allowed
+    pub fn define_own_property(&mut self, key: PropertyKey, desc: PropertyDescriptor) -> bool {
+        let extensible = self.extensible;
+        if let ObjectKind::Array(array) = &mut self.kind {
+            match &key {
+                PropertyKey::Index(index) => return array.define_element(*index, desc, extensible),
+                key if key.is_length() => return array.define_length(desc),
+                _ => {}
+            }
+        }
+        if let ObjectKind::String(s) = &self.kind {
+            if let Some(current) = string_property(s, &key) {
+                let slot = Slot::Data(current.value.clone().unwrap_or_default());
+                let current = Some((&slot, current.attributes()));
+                return desc.validate_and_apply(current, false).is_some();
+            }
+        }
+        // Only a descriptor matching what the export already is succeeds
+        if let ObjectKind::Namespace(namespace) = &self.kind {
+            if let Some(current) = namespace.value(&key) {
+                let current = current.unwrap_or_default();
+                return !desc.is_accessor_descriptor()
+                    && desc.configurable != Some(true)
+                    && desc.enumerable != Some(false)
+                    && desc.writable != Some(false)
+                    && desc.value.is_none_or(|value| value.same_value(&current));
+            }
+        }
+
+        let current = self.properties.get(&key);
+        let exists = current.is_some();
+        match desc.validate_and_apply(current, extensible) {
+            Some((slot, attributes)) if exists => self.properties.update(&key, slot, attributes),
+            Some((slot, attributes)) => self.properties.insert(key, slot, attributes),
+            None => return false,
+        }
+        true
+    }
+
+    /// Own or inherited
+    pub fn has_property(&self, key: &PropertyKey) -> bool {
+        self.find_property(key).is_some()
+    }
+
+    /// `[[Get]]`, getters are called with `receiver` as `this`
+    pub fn get(
+        &self,
+        ctx: &mut Context,
+        key: &PropertyKey,
+        receiver: JsValue,
+    ) -> ast::Result<JsValue> {
+        if let ObjectKind::Namespace(namespace) = &self.kind {
+            if let Some(Err(_)) = namespace.value(key) {
+                let message = format!("Cannot access '{}' before initialization", key);
+                return Err(ctx.throw_error(ErrorKind::ReferenceError, &message));
+            }
+        }
+
+        match self.find_property(key) {
+            Some(PropertyDescriptor {
+                get: Some(getter), ..
+            }) => match getter {
+                JsValue::Undefined => Ok(JsValue::Undefined),
+                getter => ctx.call(&getter, receiver, Vec::new()),
+            },
+            Some(PropertyDescriptor {
+                value: Some(value), ..
+            }) => Ok(value),
+            _ => Ok(JsValue::Undefined),
+        }
+    }
+
+    /// `[[Get]]` without running getters, for code that has no `Context` at hand
+    pub fn get_data_property<K: Into<PropertyKey>>(&self, key: K) -> JsValue {
+        match self.find_property(&key.into()) {
+            Some(PropertyDescriptor {
+                value: Some(value), ..
+            }) => value,
+            _ => JsValue::Undefined,
+        }
+    }
+
+    /// `[[Set]]`, false when the property is read only or has no setter. Setters are called with
+    /// `receiver` as `this`
+    pub fn set(
+        &mut self,
+        ctx: &mut Context,
+        key: PropertyKey,
+        value: JsValue,
+        receiver: JsValue,
+    ) -> ast::Result<bool> {
+        if matches!(self.kind, ObjectKind::Array(_)) && key.is_length() {
+            let length = match &value {
+                // `valueOf` can't run while the array is borrowed
+                JsValue::Object(_) => value.number_value(),
+                value => value.to_number_with(ctx)?,
+            };
+            if JsArray::to_length(&length).is_none() {
+                return Err(ctx.throw_error(ErrorKind::RangeError, "Invalid array length"));
+            }
+        }
+        if let ObjectKind::Namespace(_) = &self.kind {
+            return Ok(false);
+        }
+
+        let own = self.get_own_property(&key);
+        let inherited = match own {
+            Some(_) => None,
+            None => self.prototype.as_ref().and_then(|p| p.find_property(&key)),
+        };
+
+        match own.as_ref().or(inherited.as_ref()) {
+            Some(PropertyDescriptor {
+                set: Some(setter), ..
+            }) => match setter {
+                JsValue::Undefined => Ok(false),
+                setter => {
+                    ctx.call(setter, receiver, vec![value])?;
+                    Ok(true)
+                }
+            },
+            Some(PropertyDescriptor { get: Some(_), .. }) => Ok(false),
+            Some(desc) if !desc.writable() => Ok(false),
+            _ if own.is_some() => Ok(self.define_own_property(
+                key,
+                PropertyDescriptor {
+                    value: Some(value),
+                    ..Default::default()
+                },
+            )),
+            _ => Ok(self.create_data_property(key, value)),
+        }
+    }
+
+    /// `[[Delete]]`, only configurable properties can be deleted
+    pub fn delete(&mut self, key: &PropertyKey) -> bool {
+        match self.get_own_property(key) {
+            Some(desc) if !desc.configurable() => false,
+            Some(_) => {
+                match (&mut self.kind, key) {
+                    (ObjectKind::Array(array), PropertyKey::Index(index)) => {
+                        array.remove_element(*index)
+                    }
+                    _ => self.properties.remove(key),
+                }
+                true
+            }
+            None => true,
+        }
+    }
+
+    /// `[[OwnPropertyKeys]]`, array indices in ascending order, then strings and symbols in
+    /// insertion order
+    pub fn own_property_keys(&self) -> Vec<PropertyKey> {
+        let entries = self.properties.entries();
+        let mut keys: Vec<_> = match &self.kind {
+            // `length` is the first property of an array
+            ObjectKind::Array(array) => array
+                .indices()
+                .into_iter()
+                .map(PropertyKey::Index)
+                .chain(std::iter::once(PropertyKey::from("length")))
+                .collect(),
+            // The chars of a string object come before its other indices
+            ObjectKind::String(string) => {
+                let mut indices: Vec<_> =
+                    entries.iter().filter_map(|(k, _)| k.as_index()).collect();
+                indices.sort_unstable();
+                (0..string.len() as u32)
+                    .chain(indices)
+                    .map(PropertyKey::Index)
+                    .chain(std::iter::once(PropertyKey::from("length")))
+                    .collect()
+            }
+            // Only `@@toStringTag` is an ordinary property
+            ObjectKind::Namespace(namespace) => namespace.exports().to_vec(),
+            _ => {
+                let mut indices: Vec<_> =
+                    entries.iter().filter_map(|(k, _)| k.as_index()).collect();
+                indices.sort_unstable();
+                indices.into_iter().map(PropertyKey::Index).collect()
+            }
+        };
+        let strings = entries
+            .iter()
+            .filter(|(k, _)| matches!(k, PropertyKey::String(_)))
+            .map(|(k, _)| k.clone());
+        let symbols = entries
+            .iter()
+            .filter(|(k, _)| k.is_symbol())
+            .map(|(k, _)| k.clone());
+        keys.extend(strings.chain(symbols));
+        keys
+    }
+
+    /// Writable, enumerable and configurable data property, as made by an assignment
+    pub fn create_data_property(&mut self, key: PropertyKey, value: JsValue) -> bool {
+        self.define_own_property(key, PropertyDescriptor::data(value, Attributes::all()))
+    }
+
+    /// Define a data property with exactly `attributes`, for objects built by the runtime
+    pub fn define_property<K: Into<PropertyKey>>(
+        &mut self,
+        key: K,
+        value: JsValue,
+        attributes: Attributes,
+    ) -> bool {
+        self.define_own_property(key.into(), PropertyDescriptor::data(value, attributes))
+    }
+
+    /// Non enumerable native method, as the built-in prototypes have
+    pub fn define_method(&mut self, ctx: &mut Context, function: NativeFunction) -> bool {
+        let name = function.name();
+        let function = JsObject::native_function(ctx, function);
+        let attributes = Attributes::WRITABLE | Attributes::CONFIGURABLE;
+        self.define_property(name, JsValue::object(function), attributes)
+    }
+
+    /// String keys visited by `for (key in obj)`, inherited ones included unless shadowed
+    pub fn enumerable_keys(&self) -> Vec<PropertyKey> {
+        let mut visited = HashSet::new();
+        let mut keys = Vec::new();
+
+        let mut obj = Some(self);
+        while let Some(current) = obj {
+            for key in current.own_property_keys() {
+                if key.is_symbol() || !visited.insert(key.clone()) {
+                    continue;
+                }
+                if current
+                    .get_own_property(&key)
+                    .is_some_and(|desc| desc.enumerable())
+                {
+                    keys.push(key);
+                }
+            }
+            obj = current.prototype.as_deref();
+        }
+        keys
     }
 
-    pub fn get_property(&self, key: GcPointer<JsString>) -> JsValue {
-        match self.properties.get(&key.string.hash()) {
-            Some(v) => v.deref().clone(),
-            None => JsValue::undefined(),
+    pub fn find_private_element(&self, key: &JsSymbol) -> Option<&PrivateElement> {
+        self.private_elements
+            .iter()
+            .find(|element| &element.key == key)
+    }
+
+    /// `PrivateFieldAdd` and `PrivateMethodOrAccessorAdd`, each element is added once
+    pub fn add_private_element(
+        &mut self,
+        ctx: &mut Context,
+        element: PrivateElement,
+    ) -> ast::Result<()> {
+        if self.find_private_element(&element.key).is_some() {
+            let message = format!(
+                "Cannot initialize {} twice on the same object",
+                element.key.description().unwrap_or_default()
+            );
+            return Err(ctx.throw_error(ErrorKind::TypeError, &message));
+        }
+        self.private_elements.push(element);
+        Ok(())
+    }
+
+    /// Assign a private field, false if the element is not a field
+    pub fn set_private_field(&mut self, key: &JsSymbol, value: JsValue) -> bool {
+        let element = self
+            .private_elements
+            .iter_mut()
+            .find(|element| &element.key == key);
+        match element {
+            Some(PrivateElement {
+                kind: PrivateElementKind::Field(field),
+                ..
+            }) => {
+                *field = value;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Own property of `self` or of the closest prototype that has one
+    fn find_property(&self, key: &PropertyKey) -> Option<PropertyDescriptor> {
+        let mut obj = Some(self);
+        while let Some(current) = obj {
+            if let Some(desc) = current.get_own_property(key) {
+                return Some(desc);
+            }
+            obj = current.prototype.as_deref();
         }
+        None
+    }
+}
+
+/// The read only `length` and characters of a `String` object
+fn string_property(s: &JsString, key: &PropertyKey) -> Option<PropertyDescriptor> {
+    let value = match key {
+        PropertyKey::Index(index) if (*index as usize) < s.len() => {
+            let index = *index as usize;
+            JsValue::String(s.substring(index, index + 1))
+        }
+        key if key.is_length() => JsValue::from(s.len() as u32),
+        _ => return None,
+    };
+    let attributes = match key {
+        PropertyKey::Index(_) => Attributes::ENUMERABLE,
+        _ => Attributes::empty(),
+    };
+    Some(PropertyDescriptor::data(value, attributes))
+}
+
+impl fmt::Debug for JsObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsObject")
+            .field("kind", &self.kind)
+            .field("properties", &self.properties)
+            .finish()
     }
 }
 
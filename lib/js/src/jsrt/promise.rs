@@ -0,0 +1,329 @@
+use std::fmt;
+
+use super::{js_function, ErrorKind, JsObject, JsValue, ObjectKind, PropertyKey};
+use crate::{
+    ast::{self, Completion},
+    gc::{GcPointer, GcTrace, Trace, Tracer},
+    vm::Context,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PromiseState {
+    Pending,
+    Fulfilled,
+    Rejected,
+}
+
+unsafe impl Trace for PromiseState {}
+
+/// `PromiseCapability`, a promise with the functions settling it
+#[derive(Debug, Clone, GcTrace)]
+pub struct PromiseCapability {
+    pub promise: GcPointer<JsObject>,
+    pub resolve: JsValue,
+    pub reject: JsValue,
+}
+
+impl PromiseCapability {
+    /// `NewPromiseCapability(%Promise%)`, subclasses of `Promise` get plain promises as well
+    pub fn new(ctx: &mut Context) -> PromiseCapability {
+        let promise = JsPromise::create(ctx);
+        let (resolve, reject) = JsPromise::create_resolving_functions(ctx, &promise);
+        PromiseCapability {
+            promise,
+            resolve,
+            reject,
+        }
+    }
+}
+
+/// `PromiseReaction`, a handler waiting for a promise to settle
+#[derive(Debug, Clone, GcTrace)]
+pub struct PromiseReaction {
+    /// Settled with the result of the handler, `None` for the reactions of `await`
+    capability: Option<PromiseCapability>,
+    /// `undefined` passes the value or the reason through
+    handler: JsValue,
+    #[unsafe_ignore_trace]
+    on_rejected: bool,
+}
+
+/// `[[PromiseState]]`, `[[PromiseResult]]` and the reactions of a pending promise
+#[derive(Debug, GcTrace)]
+pub struct JsPromise {
+    state: PromiseState,
+    result: JsValue,
+    fulfill_reactions: Vec<PromiseReaction>,
+    reject_reactions: Vec<PromiseReaction>,
+}
+
+/// Work the host runs once the current script is done, the reactions of the promises first
+#[derive(Clone, GcTrace)]
+pub enum Job {
+    /// `NewPromiseReactionJob`, run a handler with the value of the settled promise
+    Reaction {
+        reaction: PromiseReaction,
+        argument: JsValue,
+    },
+    /// `NewPromiseResolveThenableJob`, follow a promise resolved with a thenable
+    ResolveThenable {
+        promise: GcPointer<JsObject>,
+        thenable: JsValue,
+        then: JsValue,
+    },
+    /// `queueMicrotask(callback)`
+    Callback(JsValue),
+}
+
+impl fmt::Debug for Job {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Job::Reaction { .. } => write!(f, "Job::Reaction"),
+            Job::ResolveThenable { .. } => write!(f, "Job::ResolveThenable"),
+            Job::Callback(_) => write!(f, "Job::Callback"),
+        }
+    }
+}
+
+impl Job {
+    /// What the callbacks throw is reported to nothing, like `end_job` does
+    pub(crate) fn run(self, ctx: &mut Context) -> ast::Result<()> {
+        match self {
+            Job::Reaction { reaction, argument } => {
+                let result = if reaction.handler.is_undefined() {
+                    match reaction.on_rejected {
+                        false => Ok(argument),
+                        true => Err(Completion::Throw(argument)),
+                    }
+                } else {
+                    ctx.call(&reaction.handler, JsValue::Undefined, vec![argument])
+                };
+
+                let capability = match reaction.capability {
+                    Some(capability) => capability,
+                    None => return result.map(|_| ()),
+                };
+                match result {
+                    Ok(value) => ctx.call(&capability.resolve, JsValue::Undefined, vec![value]),
+                    Err(Completion::Throw(reason)) => {
+                        ctx.call(&capability.reject, JsValue::Undefined, vec![reason])
+                    }
+                    Err(completion) => return Err(completion),
+                }?;
+            }
+            Job::ResolveThenable {
+                promise,
+                thenable,
+                then,
+            } => {
+                let (resolve, reject) = JsPromise::create_resolving_functions(ctx, &promise);
+                if let Err(Completion::Throw(reason)) =
+                    ctx.call(&then, thenable, vec![resolve, reject.clone()])
+                {
+                    ctx.call(&reject, JsValue::Undefined, vec![reason])?;
+                }
+            }
+            Job::Callback(callback) => {
+                ctx.call(&callback, JsValue::Undefined, Vec::new())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl JsPromise {
+    /// Pending promise inheriting from `Promise.prototype`
+    pub fn create(ctx: &mut Context) -> GcPointer<JsObject> {
+        let prototype = ctx.promise_prototype();
+        let promise = JsPromise {
+            state: PromiseState::Pending,
+            result: JsValue::Undefined,
+            fulfill_reactions: Vec::new(),
+            reject_reactions: Vec::new(),
+        };
+        JsObject::with_prototype(ctx, Some(prototype), ObjectKind::Promise(promise))
+    }
+
+    pub fn state(&self) -> PromiseState {
+        self.state
+    }
+
+    /// The value or the reason once settled
+    pub fn result(&self) -> JsValue {
+        self.result.clone()
+    }
+
+    /// `CreateResolvingFunctions`, the pair shares a flag so only the first call counts
+    pub fn create_resolving_functions(
+        ctx: &mut Context,
+        promise: &GcPointer<JsObject>,
+    ) -> (JsValue, JsValue) {
+        let already_resolved = JsObject::with_prototype(ctx, None, ObjectKind::Boolean(false));
+        let captures = vec![
+            JsValue::object(promise.clone()),
+            JsValue::object(already_resolved),
+        ];
+        let resolve = JsObject::native_closure(ctx, RESOLVE_FUNCTION, captures.clone());
+        let reject = JsObject::native_closure(ctx, REJECT_FUNCTION, captures);
+        (JsValue::object(resolve), JsValue::object(reject))
+    }
+
+    /// `PromiseResolve(%Promise%, value)`, promises are taken as they are
+    pub fn promise_resolve(ctx: &mut Context, value: JsValue) -> GcPointer<JsObject> {
+        if let JsValue::Object(obj) = &value {
+            let prototype = obj.get_prototype_of();
+            let is_plain_promise = matches!(obj.kind, ObjectKind::Promise(_))
+                && prototype.is_some_and(|p| p.ptr_eq(&ctx.promise_prototype()));
+            if is_plain_promise {
+                return obj.clone();
+            }
+        }
+
+        let promise = JsPromise::create(ctx);
+        JsPromise::resolve(ctx, &promise, value);
+        promise
+    }
+
+    /// What the resolve function does once, a thenable is followed in a later job
+    pub fn resolve(ctx: &mut Context, promise: &GcPointer<JsObject>, resolution: JsValue) {
+        let thenable = match &resolution {
+            JsValue::Object(obj) if obj.ptr_eq(promise) => {
+                let message = "Chaining cycle detected for promise #<Promise>";
+                if let Completion::Throw(error) = ctx.throw_error(ErrorKind::TypeError, message) {
+                    JsPromise::reject(ctx, promise, error);
+                }
+                return;
+            }
+            JsValue::Object(obj) => obj.clone(),
+            _ => return JsPromise::settle(ctx, promise, PromiseState::Fulfilled, resolution),
+        };
+
+        let then = match thenable.get(ctx, &PropertyKey::from("then"), resolution.clone()) {
+            Ok(then) => then,
+            Err(Completion::Throw(reason)) => return JsPromise::reject(ctx, promise, reason),
+            Err(_) => unreachable!("a getter completed with a jump"),
+        };
+        if !then.is_callable() {
+            return JsPromise::settle(ctx, promise, PromiseState::Fulfilled, resolution);
+        }
+        ctx.enqueue_job(Job::ResolveThenable {
+            promise: promise.clone(),
+            thenable: resolution,
+            then,
+        });
+    }
+
+    /// `RejectPromise`
+    pub fn reject(ctx: &mut Context, promise: &GcPointer<JsObject>, reason: JsValue) {
+        JsPromise::settle(ctx, promise, PromiseState::Rejected, reason);
+    }
+
+    /// `FulfillPromise` or `RejectPromise`, then `TriggerPromiseReactions`
+    fn settle(
+        ctx: &mut Context,
+        promise: &GcPointer<JsObject>,
+        state: PromiseState,
+        result: JsValue,
+    ) {
+        let mut promise = promise.clone();
+        let reactions = match &mut promise.kind {
+            ObjectKind::Promise(p) if p.state == PromiseState::Pending => {
+                p.state = state;
+                p.result = result.clone();
+                let fulfill_reactions = std::mem::take(&mut p.fulfill_reactions);
+                let reject_reactions = std::mem::take(&mut p.reject_reactions);
+                match state {
+                    PromiseState::Rejected => reject_reactions,
+                    _ => fulfill_reactions,
+                }
+            }
+            _ => return,
+        };
+
+        for reaction in reactions {
+            ctx.enqueue_job(Job::Reaction {
+                reaction,
+                argument: result.clone(),
+            });
+        }
+    }
+
+    /// `PerformPromiseThen`, the handlers which aren't callable pass the result through
+    pub fn perform_then(
+        ctx: &mut Context,
+        promise: &GcPointer<JsObject>,
+        on_fulfilled: JsValue,
+        on_rejected: JsValue,
+        capability: Option<PromiseCapability>,
+    ) {
+        let handler = |handler: JsValue| match handler.is_callable() {
+            true => handler,
+            false => JsValue::Undefined,
+        };
+        let fulfill_reaction = PromiseReaction {
+            capability: capability.clone(),
+            handler: handler(on_fulfilled),
+            on_rejected: false,
+        };
+        let reject_reaction = PromiseReaction {
+            capability,
+            handler: handler(on_rejected),
+            on_rejected: true,
+        };
+
+        let mut promise = promise.clone();
+        let (reaction, argument) = match &mut promise.kind {
+            ObjectKind::Promise(p) => match p.state {
+                PromiseState::Pending => {
+                    p.fulfill_reactions.push(fulfill_reaction);
+                    p.reject_reactions.push(reject_reaction);
+                    return;
+                }
+                PromiseState::Fulfilled => (fulfill_reaction, p.result.clone()),
+                PromiseState::Rejected => (reject_reaction, p.result.clone()),
+            },
+            _ => unreachable!("then() on an object which isn't a promise"),
+        };
+        ctx.enqueue_job(Job::Reaction { reaction, argument });
+    }
+}
+
+/// `[promise, alreadyResolved, resolution]`, the first two are bound
+fn resolving_function_captures(
+    args: &[JsValue],
+) -> (GcPointer<JsObject>, GcPointer<JsObject>, JsValue) {
+    match args {
+        [JsValue::Object(promise), JsValue::Object(already_resolved), rest @ ..] => (
+            promise.clone(),
+            already_resolved.clone(),
+            rest.first().cloned().unwrap_or_default(),
+        ),
+        _ => unreachable!("resolving function without its captures"),
+    }
+}
+
+/// Set the flag shared by the resolving functions, returning whether it was already set
+fn mark_resolved(mut already_resolved: GcPointer<JsObject>) -> bool {
+    match &mut already_resolved.kind {
+        ObjectKind::Boolean(resolved) => std::mem::replace(resolved, true),
+        _ => unreachable!(),
+    }
+}
+
+#[js_function(name = "", length = 1)]
+fn resolve_function(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let (promise, already_resolved, resolution) = resolving_function_captures(&args);
+    if !mark_resolved(already_resolved) {
+        JsPromise::resolve(ctx, &promise, resolution);
+    }
+    Ok(JsValue::Undefined)
+}
+
+#[js_function(name = "", length = 1)]
+fn reject_function(ctx: &mut Context, _this: JsValue, args: Vec<JsValue>) -> ast::Result<JsValue> {
+    let (promise, already_resolved, reason) = resolving_function_captures(&args);
+    if !mark_resolved(already_resolved) {
+        JsPromise::reject(ctx, &promise, reason);
+    }
+    Ok(JsValue::Undefined)
+}
@@ -0,0 +1,303 @@
+use std::fmt;
+
+use bitflags::bitflags;
+use utils::flystring::FlyString;
+
+use super::{JsSymbol, JsValue};
+use crate::gc::*;
+
+/// Name of a property, array indices are kept as numbers so they can be ordered
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum PropertyKey {
+    /// A canonical array index, `"0"` to `"4294967294"`
+    Index(u32),
+    String(FlyString),
+    Symbol(JsSymbol),
+}
+
+unsafe impl Trace for PropertyKey {
+    fn trace(&mut self, tracer: &mut Tracer) {
+        if let PropertyKey::Symbol(symbol) = self {
+            symbol.trace(tracer);
+        }
+    }
+}
+
+impl PropertyKey {
+    /// `ToPropertyKey`, anything but a symbol is converted to a string
+    pub fn from_value(value: &JsValue) -> PropertyKey {
+        match value {
+            JsValue::Symbol(symbol) => PropertyKey::Symbol(symbol.clone()),
+            JsValue::Integer(index) if *index >= 0 => PropertyKey::Index(*index as u32),
+            JsValue::String(s) => PropertyKey::from(s.string.clone()),
+            value => PropertyKey::from(value.to_string().as_ref()),
+        }
+    }
+
+    /// The key as seen from JS, indices are strings
+    pub fn to_value(&self) -> JsValue {
+        match self {
+            PropertyKey::Index(index) => JsValue::string(index.to_string().as_ref()),
+            PropertyKey::String(s) => JsValue::String(super::JsString::new(s.clone())),
+            PropertyKey::Symbol(symbol) => JsValue::Symbol(symbol.clone()),
+        }
+    }
+
+    pub fn as_index(&self) -> Option<u32> {
+        match self {
+            PropertyKey::Index(index) => Some(*index),
+            _ => None,
+        }
+    }
+
+    pub fn is_symbol(&self) -> bool {
+        matches!(self, PropertyKey::Symbol(_))
+    }
+
+    /// The `length` of arrays and strings
+    pub fn is_length(&self) -> bool {
+        matches!(self, PropertyKey::String(s) if s.as_ref() == "length")
+    }
+}
+
+impl From<FlyString> for PropertyKey {
+    fn from(s: FlyString) -> Self {
+        // Only the shortest form is an index, `"01"` and `"+1"` are plain strings
+        match s.parse::<u32>() {
+            Ok(index) if index != u32::MAX && index.to_string() == s.as_ref() => {
+                PropertyKey::Index(index)
+            }
+            _ => PropertyKey::String(s),
+        }
+    }
+}
+
+impl From<&str> for PropertyKey {
+    fn from(s: &str) -> Self {
+        PropertyKey::from(FlyString::from(s))
+    }
+}
+
+impl From<u32> for PropertyKey {
+    fn from(index: u32) -> Self {
+        if index == u32::MAX {
+            PropertyKey::String(FlyString::from(index.to_string().as_ref()))
+        } else {
+            PropertyKey::Index(index)
+        }
+    }
+}
+
+impl From<JsSymbol> for PropertyKey {
+    fn from(symbol: JsSymbol) -> Self {
+        PropertyKey::Symbol(symbol)
+    }
+}
+
+impl fmt::Display for PropertyKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PropertyKey::Index(index) => write!(f, "{}", index),
+            PropertyKey::String(s) => write!(f, "{}", s),
+            PropertyKey::Symbol(symbol) => write!(f, "{}", symbol),
+        }
+    }
+}
+
+impl fmt::Debug for PropertyKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PropertyKey::Index(index) => write!(f, "{}", index),
+            PropertyKey::String(s) => write!(f, "{:?}", s.as_ref()),
+            PropertyKey::Symbol(symbol) => write!(f, "{}", symbol),
+        }
+    }
+}
+
+bitflags! {
+    /// `[[Writable]]` only means something for data properties
+    #[derive(Default)]
+    pub struct Attributes: u8 {
+        const WRITABLE = 1 << 0;
+        const ENUMERABLE = 1 << 1;
+        const CONFIGURABLE = 1 << 2;
+    }
+}
+
+unsafe impl Trace for Attributes {}
+
+/// What a property hold, its attributes are kept apart
+#[derive(Debug, Clone, GcTrace)]
+pub enum Slot {
+    Data(JsValue),
+    Accessor { get: JsValue, set: JsValue },
+}
+
+/// A property as passed to `[[DefineOwnProperty]]`, missing fields are left as they are.
+///
+/// The ones returned by `[[GetOwnProperty]]` have every field of their kind
+#[derive(Debug, Clone, Default)]
+pub struct PropertyDescriptor {
+    pub value: Option<JsValue>,
+    pub writable: Option<bool>,
+    pub get: Option<JsValue>,
+    pub set: Option<JsValue>,
+    pub enumerable: Option<bool>,
+    pub configurable: Option<bool>,
+}
+
+impl PropertyDescriptor {
+    pub fn data(value: JsValue, attributes: Attributes) -> PropertyDescriptor {
+        PropertyDescriptor {
+            value: Some(value),
+            writable: Some(attributes.contains(Attributes::WRITABLE)),
+            get: None,
+            set: None,
+            enumerable: Some(attributes.contains(Attributes::ENUMERABLE)),
+            configurable: Some(attributes.contains(Attributes::CONFIGURABLE)),
+        }
+    }
+
+    pub fn accessor(get: JsValue, set: JsValue, attributes: Attributes) -> PropertyDescriptor {
+        PropertyDescriptor {
+            value: None,
+            writable: None,
+            get: Some(get),
+            set: Some(set),
+            enumerable: Some(attributes.contains(Attributes::ENUMERABLE)),
+            configurable: Some(attributes.contains(Attributes::CONFIGURABLE)),
+        }
+    }
+
+    pub(crate) fn from_slot(slot: &Slot, attributes: Attributes) -> PropertyDescriptor {
+        match slot {
+            Slot::Data(value) => PropertyDescriptor::data(value.clone(), attributes),
+            Slot::Accessor { get, set } => {
+                PropertyDescriptor::accessor(get.clone(), set.clone(), attributes)
+            }
+        }
+    }
+
+    pub fn is_accessor_descriptor(&self) -> bool {
+        self.get.is_some() || self.set.is_some()
+    }
+
+    pub fn is_data_descriptor(&self) -> bool {
+        self.value.is_some() || self.writable.is_some()
+    }
+
+    pub fn is_generic_descriptor(&self) -> bool {
+        !self.is_accessor_descriptor() && !self.is_data_descriptor()
+    }
+
+    pub fn enumerable(&self) -> bool {
+        self.enumerable.unwrap_or(false)
+    }
+
+    pub fn configurable(&self) -> bool {
+        self.configurable.unwrap_or(false)
+    }
+
+    pub fn writable(&self) -> bool {
+        self.writable.unwrap_or(false)
+    }
+
+    /// Attributes of the property, absent fields are `false`
+    pub fn attributes(&self) -> Attributes {
+        let mut attributes = Attributes::empty();
+        attributes.set(Attributes::WRITABLE, self.writable());
+        attributes.set(Attributes::ENUMERABLE, self.enumerable());
+        attributes.set(Attributes::CONFIGURABLE, self.configurable());
+        attributes
+    }
+
+    /// `ValidateAndApplyPropertyDescriptor`, what the property becomes or `None` if the change
+    /// isn't allowed
+    pub fn validate_and_apply(
+        self,
+        current: Option<(&Slot, Attributes)>,
+        extensible: bool,
+    ) -> Option<(Slot, Attributes)> {
+        let (current_slot, current) = match current {
+            Some((slot, attributes)) => (slot.clone(), attributes),
+            None if !extensible => return None,
+            None => {
+                let attributes = self.attributes();
+                let slot = if self.is_accessor_descriptor() {
+                    Slot::Accessor {
+                        get: self.get.unwrap_or_default(),
+                        set: self.set.unwrap_or_default(),
+                    }
+                } else {
+                    Slot::Data(self.value.unwrap_or_default())
+                };
+                return Some((slot, attributes));
+            }
+        };
+
+        let is_accessor = matches!(current_slot, Slot::Accessor { .. });
+        if !current.contains(Attributes::CONFIGURABLE) {
+            if self.configurable == Some(true)
+                || self
+                    .enumerable
+                    .is_some_and(|e| e != current.contains(Attributes::ENUMERABLE))
+            {
+                return None;
+            }
+            if !self.is_generic_descriptor() && self.is_accessor_descriptor() != is_accessor {
+                return None;
+            }
+            match &current_slot {
+                Slot::Accessor { get, set } => {
+                    let changed = |new: &Option<JsValue>, old: &JsValue| {
+                        new.as_ref().is_some_and(|new| !new.same_value(old))
+                    };
+                    if changed(&self.get, get) || changed(&self.set, set) {
+                        return None;
+                    }
+                }
+                Slot::Data(value) if !current.contains(Attributes::WRITABLE) => {
+                    if self.writable == Some(true)
+                        || self.value.as_ref().is_some_and(|v| !v.same_value(value))
+                    {
+                        return None;
+                    }
+                }
+                Slot::Data(_) => {}
+            }
+        }
+
+        let mut attributes = current;
+        if let Some(enumerable) = self.enumerable {
+            attributes.set(Attributes::ENUMERABLE, enumerable);
+        }
+        if let Some(configurable) = self.configurable {
+            attributes.set(Attributes::CONFIGURABLE, configurable);
+        }
+
+        let slot = match current_slot {
+            Slot::Data(_) if self.is_accessor_descriptor() => {
+                attributes.remove(Attributes::WRITABLE);
+                Slot::Accessor {
+                    get: self.get.unwrap_or_default(),
+                    set: self.set.unwrap_or_default(),
+                }
+            }
+            Slot::Accessor { .. } if self.is_data_descriptor() => {
+                attributes.set(Attributes::WRITABLE, self.writable());
+                Slot::Data(self.value.unwrap_or_default())
+            }
+            Slot::Data(value) => {
+                if let Some(writable) = self.writable {
+                    attributes.set(Attributes::WRITABLE, writable);
+                }
+                Slot::Data(self.value.unwrap_or(value))
+            }
+            Slot::Accessor { get, set } => Slot::Accessor {
+                get: self.get.unwrap_or(get),
+                set: self.set.unwrap_or(set),
+            },
+        };
+        Some((slot, attributes))
+    }
+}
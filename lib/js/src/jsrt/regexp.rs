@@ -0,0 +1,32 @@
+use std::rc::Rc;
+
+use super::{Attributes, JsObject, JsValue, ObjectKind};
+use crate::{
+    gc::{GcPointer, Trace},
+    regexp::Regex,
+    vm::Context,
+};
+
+/// `[[RegExpMatcher]]`, `[[OriginalSource]]` and `[[OriginalFlags]]` of a `RegExp`, a literal
+/// shares its compiled pattern with every object it evaluates to
+#[derive(Debug, Clone)]
+pub struct JsRegExp {
+    regex: Rc<Regex>,
+}
+
+unsafe impl Trace for JsRegExp {}
+
+impl JsRegExp {
+    /// `RegExpCreate` with an already compiled pattern
+    pub fn create(ctx: &mut Context, regex: Rc<Regex>) -> GcPointer<JsObject> {
+        let prototype = ctx.regexp_prototype();
+        let kind = ObjectKind::RegExp(JsRegExp { regex });
+        let mut obj = JsObject::with_prototype(ctx, Some(prototype), kind);
+        obj.define_property("lastIndex", JsValue::from(0), Attributes::WRITABLE);
+        obj
+    }
+
+    pub fn regex(&self) -> &Rc<Regex> {
+        &self.regex
+    }
+}
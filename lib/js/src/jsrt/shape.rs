@@ -0,0 +1,278 @@
+use std::{
+    cell::{OnceCell, RefCell},
+    collections::HashMap,
+    fmt,
+    rc::{Rc, Weak},
+};
+
+use super::{Attributes, PropertyKey, Slot};
+use crate::gc::*;
+
+/// Shapes with more properties than this get a hash table instead of walking their parents
+const LINEAR_LOOKUP_LIMIT: usize = 8;
+
+/// Hidden class of an object, the keys and attributes of its properties in insertion order.
+///
+/// Each shape add one property to its parent, objects that get the same properties in the same
+/// order end up sharing the same shape
+pub struct Shape {
+    parent: Option<Rc<Shape>>,
+    /// Property added by this shape, `None` for the root
+    property: Option<(PropertyKey, Attributes)>,
+    len: usize,
+    transitions: RefCell<HashMap<(PropertyKey, Attributes), Weak<Shape>>>,
+    /// Slot of every property, built on the first lookup of a big shape
+    table: OnceCell<HashMap<PropertyKey, (usize, Attributes)>>,
+}
+
+impl Shape {
+    /// The empty shape, every object start from
+    pub fn root() -> Rc<Shape> {
+        Rc::new(Shape {
+            parent: None,
+            property: None,
+            len: 0,
+            transitions: RefCell::new(HashMap::new()),
+            table: OnceCell::new(),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Shape with one more property, shared with every object that made the same transition
+    pub fn add(self: &Rc<Shape>, key: PropertyKey, attributes: Attributes) -> Rc<Shape> {
+        let transition = (key, attributes);
+        if let Some(shape) = self
+            .transitions
+            .borrow()
+            .get(&transition)
+            .and_then(Weak::upgrade)
+        {
+            return shape;
+        }
+
+        let shape = Rc::new(Shape {
+            parent: Some(self.clone()),
+            property: Some(transition.clone()),
+            len: self.len + 1,
+            transitions: RefCell::new(HashMap::new()),
+            table: OnceCell::new(),
+        });
+        let mut transitions = self.transitions.borrow_mut();
+        transitions.retain(|_, shape| shape.strong_count() > 0);
+        transitions.insert(transition, Rc::downgrade(&shape));
+        shape
+    }
+
+    /// Index of the slot of `key` and its attributes
+    pub fn lookup(&self, key: &PropertyKey) -> Option<(usize, Attributes)> {
+        if self.len > LINEAR_LOOKUP_LIMIT {
+            return self
+                .table
+                .get_or_init(|| {
+                    self.properties()
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, (key, attributes))| (key, (index, attributes)))
+                        .collect()
+                })
+                .get(key)
+                .copied();
+        }
+
+        let mut shape = self;
+        while let Some((property, attributes)) = &shape.property {
+            if property == key {
+                return Some((shape.len - 1, *attributes));
+            }
+            shape = shape.parent.as_ref()?;
+        }
+        None
+    }
+
+    /// Keep the symbols used as keys alive, the shapes themselves are reference counted
+    fn trace_keys(&self, tracer: &mut Tracer) {
+        let mut shape = Some(self);
+        while let Some(current) = shape {
+            if let Some((PropertyKey::Symbol(symbol), _)) = &current.property {
+                symbol.clone().trace(tracer);
+            }
+            shape = current.parent.as_deref();
+        }
+    }
+
+    /// Every property, in the order they were added
+    pub fn properties(&self) -> Vec<(PropertyKey, Attributes)> {
+        let mut properties = Vec::with_capacity(self.len);
+        let mut shape = Some(self);
+        while let Some(current) = shape {
+            if let Some(property) = &current.property {
+                properties.push(property.clone());
+            }
+            shape = current.parent.as_deref();
+        }
+        properties.reverse();
+        properties
+    }
+}
+
+impl fmt::Debug for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.properties()).finish()
+    }
+}
+
+/// Own properties of an object
+pub enum PropertyMap {
+    /// Values in the order of the shape
+    Shaped { shape: Rc<Shape>, slots: Vec<Slot> },
+    /// Deleting or reconfiguring a property moves the object off the shared shapes for good
+    Dictionary {
+        keys: Vec<PropertyKey>,
+        properties: HashMap<PropertyKey, (Slot, Attributes)>,
+    },
+}
+
+unsafe impl Trace for PropertyMap {
+    fn trace(&mut self, tracer: &mut Tracer) {
+        match self {
+            PropertyMap::Shaped { shape, slots } => {
+                shape.trace_keys(tracer);
+                slots.trace(tracer);
+            }
+            PropertyMap::Dictionary { keys, properties } => {
+                keys.trace(tracer);
+                properties
+                    .values_mut()
+                    .for_each(|(slot, _)| slot.trace(tracer));
+            }
+        }
+    }
+}
+
+impl PropertyMap {
+    pub fn new(shape: Rc<Shape>) -> PropertyMap {
+        PropertyMap::Shaped {
+            shape,
+            slots: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            PropertyMap::Shaped { shape, .. } => shape.len(),
+            PropertyMap::Dictionary { keys, .. } => keys.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_dictionary(&self) -> bool {
+        matches!(self, PropertyMap::Dictionary { .. })
+    }
+
+    /// The shared shape, `None` in dictionary mode
+    pub fn shape(&self) -> Option<&Rc<Shape>> {
+        match self {
+            PropertyMap::Shaped { shape, .. } => Some(shape),
+            PropertyMap::Dictionary { .. } => None,
+        }
+    }
+
+    pub fn get(&self, key: &PropertyKey) -> Option<(&Slot, Attributes)> {
+        match self {
+            PropertyMap::Shaped { shape, slots } => shape
+                .lookup(key)
+                .map(|(index, attributes)| (&slots[index], attributes)),
+            PropertyMap::Dictionary { properties, .. } => properties
+                .get(key)
+                .map(|(slot, attributes)| (slot, *attributes)),
+        }
+    }
+
+    /// Add a property that doesn't exist yet
+    pub fn insert(&mut self, key: PropertyKey, slot: Slot, attributes: Attributes) {
+        match self {
+            PropertyMap::Shaped { shape, slots } => {
+                *shape = shape.add(key, attributes);
+                slots.push(slot);
+            }
+            PropertyMap::Dictionary { keys, properties } => {
+                keys.push(key.clone());
+                properties.insert(key, (slot, attributes));
+            }
+        }
+    }
+
+    /// Replace an existing property, keeping its place in the order
+    pub fn update(&mut self, key: &PropertyKey, slot: Slot, attributes: Attributes) {
+        if let PropertyMap::Shaped { shape, slots } = self {
+            match shape.lookup(key) {
+                Some((index, current)) if current == attributes => {
+                    slots[index] = slot;
+                    return;
+                }
+                _ => self.make_dictionary(),
+            }
+        }
+
+        if let PropertyMap::Dictionary { properties, .. } = self {
+            if let Some(property) = properties.get_mut(key) {
+                *property = (slot, attributes);
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &PropertyKey) {
+        self.make_dictionary();
+        if let PropertyMap::Dictionary { keys, properties } = self {
+            if properties.remove(key).is_some() {
+                keys.retain(|k| k != key);
+            }
+        }
+    }
+
+    /// Own keys with their attributes, in insertion order
+    pub fn entries(&self) -> Vec<(PropertyKey, Attributes)> {
+        match self {
+            PropertyMap::Shaped { shape, .. } => shape.properties(),
+            PropertyMap::Dictionary { keys, properties } => keys
+                .iter()
+                .map(|key| (key.clone(), properties[key].1))
+                .collect(),
+        }
+    }
+
+    fn make_dictionary(&mut self) {
+        if let PropertyMap::Shaped { shape, slots } = self {
+            let keys: Vec<_> = shape.properties();
+            let properties = keys
+                .iter()
+                .cloned()
+                .zip(slots.drain(..))
+                .map(|((key, attributes), slot)| (key, (slot, attributes)))
+                .collect();
+            *self = PropertyMap::Dictionary {
+                keys: keys.into_iter().map(|(key, _)| key).collect(),
+                properties,
+            };
+        }
+    }
+}
+
+// Values are left out, objects usually reach themselves through their prototype
+impl fmt::Debug for PropertyMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.entries().into_iter().map(|(key, _)| key))
+            .finish()
+    }
+}
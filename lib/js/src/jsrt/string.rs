@@ -1,6 +1,10 @@
 use utils::flystring::FlyString;
 
-use crate::gc::{GcCell, GcPointer, Heap, Trace};
+use super::{JsObject, ObjectKind};
+use crate::{
+    gc::{GcCell, GcPointer, GcTrace, Trace, Tracer},
+    vm::Context,
+};
 
 #[derive(Debug, Clone)]
 pub struct JsString {
@@ -17,4 +21,72 @@ impl JsString {
     {
         JsString { string: str.into() }
     }
+
+    /// Lone surrogates can't be stored, they become U+FFFD
+    pub fn from_utf16(units: &[u16]) -> JsString {
+        JsString::new(String::from_utf16_lossy(units).as_ref())
+    }
+
+    /// The UTF-16 code units, which is what indices and lengths count in JS
+    pub fn to_utf16(&self) -> Vec<u16> {
+        self.string.encode_utf16().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.string.encode_utf16().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.string.is_empty()
+    }
+
+    pub fn code_unit_at(&self, index: usize) -> Option<u16> {
+        self.string.encode_utf16().nth(index)
+    }
+
+    /// Code units `start..end`, both clamped to the length
+    pub fn substring(&self, start: usize, end: usize) -> JsString {
+        let units = self.to_utf16();
+        let end = end.min(units.len());
+        JsString::from_utf16(&units[start.min(end)..end])
+    }
+}
+
+/// `%StringIteratorPrototype%` objects, they step through code points rather than code units
+#[derive(Debug, GcTrace)]
+pub struct StringIterator {
+    /// `None` once done
+    iterated: Option<JsString>,
+    /// Byte offset of the next character
+    position: usize,
+}
+
+impl StringIterator {
+    pub fn create(ctx: &mut Context, iterated: JsString) -> GcPointer<JsObject> {
+        let prototype = ctx.string_iterator_prototype();
+        let iterator = StringIterator {
+            iterated: Some(iterated),
+            position: 0,
+        };
+        JsObject::with_prototype(ctx, Some(prototype), ObjectKind::StringIterator(iterator))
+    }
+}
+
+impl Iterator for StringIterator {
+    type Item = JsString;
+
+    /// Next character, `None` once the end of the string was reached
+    fn next(&mut self) -> Option<JsString> {
+        let iterated = self.iterated.as_ref()?;
+        match iterated.string[self.position..].chars().next() {
+            Some(c) => {
+                self.position += c.len_utf8();
+                Some(JsString::new(c.encode_utf8(&mut [0; 4]) as &str))
+            }
+            None => {
+                self.iterated = None;
+                None
+            }
+        }
+    }
 }
@@ -0,0 +1,161 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+use utils::flystring::FlyString;
+
+use crate::{
+    gc::{GcCell, GcPointer, GcTrace, Heap, Trace, Tracer},
+    vm::Context,
+};
+
+/// What a symbol holds, the cell it lives in is what makes it unique
+#[derive(Debug)]
+pub struct SymbolData {
+    description: Option<FlyString>,
+    /// Created by `Symbol.for`, the description is then its key in the registry
+    registered: bool,
+    /// `#name` of a class, keys its private elements instead of properties
+    private: bool,
+}
+
+unsafe impl Trace for SymbolData {}
+impl GcCell for SymbolData {}
+
+/// A unique value, two symbols are only equal if they are the same heap cell
+#[derive(Clone)]
+pub struct JsSymbol(GcPointer<SymbolData>);
+
+unsafe impl Trace for JsSymbol {
+    fn trace(&mut self, tracer: &mut Tracer) {
+        tracer.visit(&mut self.0);
+    }
+}
+
+impl JsSymbol {
+    pub fn new(ctx: &mut Context, description: Option<FlyString>) -> JsSymbol {
+        JsSymbol(ctx.allocate(SymbolData {
+            description,
+            registered: false,
+            private: false,
+        }))
+    }
+
+    /// Private name declared by a class, each evaluation of the class creates new ones
+    pub fn private(ctx: &mut Context, name: &str) -> JsSymbol {
+        JsSymbol(ctx.allocate(SymbolData {
+            description: Some(name.into()),
+            registered: false,
+            private: true,
+        }))
+    }
+
+    /// Symbol for the registry of `Symbol.for`, which lives next to the heap
+    pub(crate) fn registered(heap: &mut Heap, key: FlyString) -> JsSymbol {
+        JsSymbol(heap.allocate(SymbolData {
+            description: Some(key),
+            registered: true,
+            private: false,
+        }))
+    }
+
+    /// Key of the symbol in the registry, `None` unless it came from `Symbol.for`
+    pub fn registry_key(&self) -> Option<&str> {
+        match self.0.registered {
+            true => self.description(),
+            false => None,
+        }
+    }
+
+    pub fn is_private(&self) -> bool {
+        self.0.private
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.0
+            .description
+            .as_ref()
+            .map(|description| description.as_ref())
+    }
+}
+
+impl PartialEq for JsSymbol {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.ptr_eq(&other.0)
+    }
+}
+
+impl Eq for JsSymbol {}
+
+impl Hash for JsSymbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (&*self.0 as *const SymbolData).hash(state)
+    }
+}
+
+impl fmt::Display for JsSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Symbol({})", self.description().unwrap_or_default())
+    }
+}
+
+impl fmt::Debug for JsSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// The symbols the language itself looks up, shared by every context of a runtime
+#[derive(Clone, GcTrace)]
+pub struct WellKnownSymbols {
+    /// `Symbol.hasInstance`, called by `instanceof`
+    pub has_instance: JsSymbol,
+    /// `Symbol.iterator`, called by `for of`
+    pub iterator: JsSymbol,
+    /// `Symbol.match`, called by `String.prototype.match`
+    pub r#match: JsSymbol,
+    /// `Symbol.replace`, called by `String.prototype.replace` and `replaceAll`
+    pub replace: JsSymbol,
+    /// `Symbol.split`, called by `String.prototype.split`
+    pub split: JsSymbol,
+    /// `Symbol.toPrimitive`, called when an object is converted to a primitive
+    pub to_primitive: JsSymbol,
+    /// `Symbol.toStringTag`, read by `Object.prototype.toString`
+    pub to_string_tag: JsSymbol,
+}
+
+impl WellKnownSymbols {
+    pub(crate) fn new(heap: &mut Heap) -> WellKnownSymbols {
+        let mut symbol = |name: &str| {
+            JsSymbol(heap.allocate(SymbolData {
+                description: Some(format!("Symbol.{}", name).as_str().into()),
+                registered: false,
+                private: false,
+            }))
+        };
+
+        WellKnownSymbols {
+            has_instance: symbol("hasInstance"),
+            iterator: symbol("iterator"),
+            r#match: symbol("match"),
+            replace: symbol("replace"),
+            split: symbol("split"),
+            to_primitive: symbol("toPrimitive"),
+            to_string_tag: symbol("toStringTag"),
+        }
+    }
+
+    /// Name and symbol of each, in the order they are defined on `Symbol`
+    pub fn all(&self) -> [(&'static str, &JsSymbol); 7] {
+        [
+            ("hasInstance", &self.has_instance),
+            ("iterator", &self.iterator),
+            ("match", &self.r#match),
+            ("replace", &self.replace),
+            ("split", &self.split),
+            ("toPrimitive", &self.to_primitive),
+            ("toStringTag", &self.to_string_tag),
+        ]
+    }
+}
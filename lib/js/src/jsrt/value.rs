@@ -2,20 +2,24 @@ use std::{fmt, ops::Deref};
 
 use utils::prelude::FlyString;
 
-use super::{string::JsString, JsObject};
-use crate::{gc::*, vm::Context};
+use super::{
+    string::JsString, ErrorKind, JsBigInt, JsObject, JsSymbol, ObjectKind, PrivateElementKind,
+    PropertyKey,
+};
+use crate::{ast, gc::*, vm::Context};
 
-#[derive(Clone, GcTrace)]
+#[derive(Clone, Default, GcTrace)]
 pub enum JsValue {
+    #[default]
     Undefined,
     Null,
     Boolean(bool),
     Rational(f64),
     Integer(i32),
-    BigInt(()),
+    BigInt(JsBigInt),
     String(JsString),
     Object(GcPointer<JsObject>),
-    Symbol(()),
+    Symbol(JsSymbol),
 }
 
 impl GcCell for JsValue {}
@@ -36,8 +40,8 @@ impl fmt::Debug for JsValue {
             JsValue::Integer(v) => write!(f, "Value{{number: {}}}", v),
             JsValue::String(s) => write!(f, "Value{{string: {}}}", s.clone().string),
             JsValue::Object(o) => write!(f, "Value{{object: {:#?}}}", o),
-            JsValue::BigInt(_) => write!(f, "Value{{bigint}}"),
-            JsValue::Symbol(_) => write!(f, "Value{{symbol}}"),
+            JsValue::BigInt(v) => write!(f, "Value{{bigint: {}}}", v),
+            JsValue::Symbol(symbol) => write!(f, "Value{{symbol: {}}}", symbol),
         }
     }
 }
@@ -77,7 +81,30 @@ impl JsValue {
         JsValue::Object(obj)
     }
 
+    /// Decimal numbers, or integers with a `0x`, `0o` or `0b` prefix
     pub fn number_from_str(str: &str) -> JsValue {
+        let radix = match str.get(..2) {
+            Some("0x" | "0X") => 16,
+            Some("0o" | "0O") => 8,
+            Some("0b" | "0B") => 2,
+            _ => 10,
+        };
+        if radix != 10 {
+            let digits = &str[2..];
+            if digits.is_empty() {
+                return JsValue::nan();
+            }
+            let value = digits.chars().try_fold(0.0, |value, c| {
+                c.to_digit(radix)
+                    .map(|digit| value * radix as f64 + digit as f64)
+            });
+            return match value {
+                Some(v) if v <= i32::MAX as f64 => JsValue::Integer(v as i32),
+                Some(v) => JsValue::Rational(v),
+                None => JsValue::nan(),
+            };
+        }
+
         if let Ok(v) = str.parse::<i32>() {
             JsValue::Integer(v)
         } else if let Ok(v) = str.parse::<f64>() {
@@ -133,11 +160,347 @@ impl JsValue {
         }
     }
 
+    pub fn is_number(&self) -> bool {
+        matches!(self, JsValue::Integer(_) | JsValue::Rational(_))
+    }
+
+    pub fn is_callable(&self) -> bool {
+        matches!(self, JsValue::Object(obj) if obj.is_function())
+    }
+
+    /// Name returned by the `typeof` operator
+    pub fn type_of(&self) -> &'static str {
+        match self {
+            JsValue::Undefined => "undefined",
+            JsValue::Null => "object",
+            JsValue::Boolean(_) => "boolean",
+            JsValue::Rational(_) | JsValue::Integer(_) => "number",
+            JsValue::BigInt(_) => "bigint",
+            JsValue::String(_) => "string",
+            JsValue::Object(obj) if obj.is_function() => "function",
+            JsValue::Object(_) => "object",
+            JsValue::Symbol(_) => "symbol",
+        }
+    }
+
+    // Property access
+
+    /// `value[key]`, primitives look their properties up on their prototype
+    pub fn get_property(&self, ctx: &mut Context, key: &PropertyKey) -> ast::Result<JsValue> {
+        if let PropertyKey::Symbol(symbol) = key {
+            if symbol.is_private() {
+                return self.private_get(ctx, symbol);
+            }
+        }
+
+        let prototype = match self {
+            JsValue::Object(obj) => return obj.get(ctx, key, self.clone()),
+            JsValue::String(s) => match key {
+                key if key.is_length() => return Ok(JsValue::from(s.len() as u32)),
+                PropertyKey::Index(index) if (*index as usize) < s.len() => {
+                    let index = *index as usize;
+                    return Ok(JsValue::String(s.substring(index, index + 1)));
+                }
+                _ => ctx.string_prototype(),
+            },
+            JsValue::Boolean(_) => ctx.boolean_prototype(),
+            JsValue::Integer(_) | JsValue::Rational(_) => ctx.number_prototype(),
+            JsValue::BigInt(_) => ctx.bigint_prototype(),
+            JsValue::Symbol(_) => ctx.symbol_prototype(),
+            JsValue::Undefined | JsValue::Null => {
+                let message = format!(
+                    "Cannot read properties of {} (reading '{}')",
+                    self.to_string(),
+                    key
+                );
+                return Err(ctx.throw_error(ErrorKind::TypeError, &message));
+            }
+        };
+        prototype.get(ctx, key, self.clone())
+    }
+
+    /// `value[key] = v`, failing silently like sloppy mode code does
+    pub fn set_property(
+        &self,
+        ctx: &mut Context,
+        key: PropertyKey,
+        value: JsValue,
+    ) -> ast::Result<()> {
+        if let PropertyKey::Symbol(symbol) = &key {
+            if symbol.is_private() {
+                return self.private_set(ctx, symbol, value);
+            }
+        }
+
+        match self {
+            JsValue::Object(obj) => {
+                obj.clone().set(ctx, key, value, self.clone())?;
+                Ok(())
+            }
+            JsValue::Undefined | JsValue::Null => {
+                let message = format!(
+                    "Cannot set properties of {} (setting '{}')",
+                    self.to_string(),
+                    key
+                );
+                Err(ctx.throw_error(ErrorKind::TypeError, &message))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// `PrivateGet`, `this.#name` inside of a class
+    fn private_get(&self, ctx: &mut Context, key: &JsSymbol) -> ast::Result<JsValue> {
+        let name = key.description().unwrap_or_default();
+        let element = match self {
+            JsValue::Object(obj) => obj.find_private_element(key).map(|e| e.kind.clone()),
+            _ => None,
+        };
+        match element {
+            Some(PrivateElementKind::Field(value)) | Some(PrivateElementKind::Method(value)) => {
+                Ok(value)
+            }
+            Some(PrivateElementKind::Accessor { get, .. }) if get.is_undefined() => {
+                let message = format!("'{}' was defined without a getter", name);
+                Err(ctx.throw_error(ErrorKind::TypeError, &message))
+            }
+            Some(PrivateElementKind::Accessor { get, .. }) => ctx.call(&get, self.clone(), vec![]),
+            None => {
+                let message = format!(
+                    "Cannot read private member {} from an object whose class did not declare it",
+                    name
+                );
+                Err(ctx.throw_error(ErrorKind::TypeError, &message))
+            }
+        }
+    }
+
+    /// `PrivateSet`, only fields can be assigned, or accessors with a setter
+    fn private_set(&self, ctx: &mut Context, key: &JsSymbol, value: JsValue) -> ast::Result<()> {
+        let name = key.description().unwrap_or_default();
+        let mut obj = match self {
+            JsValue::Object(obj) if obj.find_private_element(key).is_some() => obj.clone(),
+            _ => {
+                let message = format!(
+                    "Cannot write private member {} to an object whose class did not declare it",
+                    name
+                );
+                return Err(ctx.throw_error(ErrorKind::TypeError, &message));
+            }
+        };
+        if obj.set_private_field(key, value.clone()) {
+            return Ok(());
+        }
+
+        match obj.find_private_element(key).map(|e| e.kind.clone()) {
+            Some(PrivateElementKind::Accessor { set, .. }) if !set.is_undefined() => {
+                ctx.call(&set, self.clone(), vec![value])?;
+                Ok(())
+            }
+            Some(PrivateElementKind::Accessor { .. }) => {
+                let message = format!("'{}' was defined without a setter", name);
+                Err(ctx.throw_error(ErrorKind::TypeError, &message))
+            }
+            _ => Err(ctx.throw_error(ErrorKind::TypeError, "Private method is not writable")),
+        }
+    }
+
+    /// `ToObject`, primitives are wrapped in a new object
+    pub fn to_object(&self, ctx: &mut Context) -> ast::Result<GcPointer<JsObject>> {
+        let (prototype, kind) = match self {
+            JsValue::Object(obj) => return Ok(obj.clone()),
+            JsValue::Undefined | JsValue::Null => {
+                let message = "Cannot convert undefined or null to object";
+                return Err(ctx.throw_error(ErrorKind::TypeError, message));
+            }
+            JsValue::Boolean(b) => (ctx.boolean_prototype(), ObjectKind::Boolean(*b)),
+            JsValue::Integer(_) | JsValue::Rational(_) => {
+                (ctx.number_prototype(), ObjectKind::Number(self.as_f64()))
+            }
+            JsValue::String(s) => (ctx.string_prototype(), ObjectKind::String(s.clone())),
+            JsValue::BigInt(v) => (ctx.bigint_prototype(), ObjectKind::BigInt(v.clone())),
+            JsValue::Symbol(symbol) => (ctx.symbol_prototype(), ObjectKind::Symbol(symbol.clone())),
+        };
+        Ok(JsObject::with_prototype(ctx, Some(prototype), kind))
+    }
+
+    // JS Value comparison
+
+    /// `===`, numbers compare by value whatever their representation
+    pub fn strict_equals(&self, other: &JsValue) -> bool {
+        match (self, other) {
+            (JsValue::Undefined, JsValue::Undefined) | (JsValue::Null, JsValue::Null) => true,
+            (JsValue::Boolean(a), JsValue::Boolean(b)) => a == b,
+            (JsValue::Integer(a), JsValue::Integer(b)) => a == b,
+            (a, b) if a.is_number() && b.is_number() => a.as_f64() == b.as_f64(),
+            (JsValue::String(a), JsValue::String(b)) => a.string == b.string,
+            (JsValue::BigInt(a), JsValue::BigInt(b)) => a == b,
+            (JsValue::Object(a), JsValue::Object(b)) => a.ptr_eq(b),
+            (JsValue::Symbol(a), JsValue::Symbol(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// `Object.is`, unlike `===` `NaN` is itself and `0` is not `-0`
+    pub fn same_value(&self, other: &JsValue) -> bool {
+        if self.is_number() && other.is_number() {
+            let (a, b) = (self.as_f64(), other.as_f64());
+            return (a.is_nan() && b.is_nan())
+                || (a == b && a.is_sign_negative() == b.is_sign_negative());
+        }
+        self.strict_equals(other)
+    }
+
+    /// `==`, converting the operands to the same type first
+    pub fn loose_equals(&self, other: &JsValue) -> bool {
+        match (self, other) {
+            (JsValue::Undefined | JsValue::Null, JsValue::Undefined | JsValue::Null) => true,
+            (JsValue::Undefined | JsValue::Null, _) | (_, JsValue::Undefined | JsValue::Null) => {
+                false
+            }
+            (JsValue::Boolean(_), _) => self.number_value().loose_equals(other),
+            (_, JsValue::Boolean(_)) => self.loose_equals(&other.number_value()),
+            (JsValue::String(_), b) if b.is_number() => self.number_value().strict_equals(other),
+            (a, JsValue::String(_)) if a.is_number() => self.strict_equals(&other.number_value()),
+            (JsValue::BigInt(a), JsValue::String(b)) | (JsValue::String(b), JsValue::BigInt(a)) => {
+                JsBigInt::from_string(&b.string).is_some_and(|b| *a == b)
+            }
+            (JsValue::BigInt(a), b) | (b, JsValue::BigInt(a)) if b.is_number() => {
+                a.compare_number(b.as_f64()).is_some_and(|o| o.is_eq())
+            }
+            (JsValue::Object(_), JsValue::Object(_)) => self.strict_equals(other),
+            (JsValue::Object(_), _) => self.to_primitive(PreferredType::None).loose_equals(other),
+            (_, JsValue::Object(_)) => self.loose_equals(&other.to_primitive(PreferredType::None)),
+            _ => self.strict_equals(other),
+        }
+    }
+
     // JS Value type conversion
 
+    /// `ToPrimitive`, objects are converted by their `Symbol.toPrimitive` method or else by
+    /// `valueOf` and `toString`
+    pub fn to_primitive_with(
+        &self,
+        ctx: &mut Context,
+        preferred_type: PreferredType,
+    ) -> ast::Result<JsValue> {
+        if !matches!(self, JsValue::Object(_)) {
+            return Ok(self.clone());
+        }
+
+        let key = PropertyKey::from(ctx.well_known_symbols().to_primitive.clone());
+        let exotic_to_primitive = self.get_property(ctx, &key)?;
+        if !exotic_to_primitive.is_undefined() && !exotic_to_primitive.is_null() {
+            let hint = match preferred_type {
+                PreferredType::None => "default",
+                PreferredType::Number => "number",
+                PreferredType::String => "string",
+            };
+            let result = ctx.call(
+                &exotic_to_primitive,
+                self.clone(),
+                vec![JsValue::string(hint)],
+            )?;
+            if matches!(result, JsValue::Object(_)) {
+                let message = "Cannot convert object to primitive value";
+                return Err(ctx.throw_error(ErrorKind::TypeError, message));
+            }
+            return Ok(result);
+        }
+
+        // OrdinaryToPrimitive
+        let methods = match preferred_type {
+            PreferredType::String => ["toString", "valueOf"],
+            _ => ["valueOf", "toString"],
+        };
+        for name in methods {
+            let method = self.get_property(ctx, &PropertyKey::from(name))?;
+            if method.is_callable() {
+                let result = ctx.call(&method, self.clone(), Vec::new())?;
+                if !matches!(result, JsValue::Object(_)) {
+                    return Ok(result);
+                }
+            }
+        }
+        let message = "Cannot convert object to primitive value";
+        Err(ctx.throw_error(ErrorKind::TypeError, message))
+    }
+
+    /// `ToNumeric`, which unlike `to_numeric` throws for symbols
+    pub fn to_numeric_with(&self, ctx: &mut Context) -> ast::Result<JsValue> {
+        match self.to_primitive_with(ctx, PreferredType::Number)? {
+            JsValue::Symbol(_) => {
+                let message = "Cannot convert a Symbol value to a number";
+                Err(ctx.throw_error(ErrorKind::TypeError, message))
+            }
+            value if value.is_bigint() => Ok(value),
+            value => Ok(value.number_value()),
+        }
+    }
+
+    /// `ToNumber`, which throws for BigInts and symbols
+    pub fn to_number_with(&self, ctx: &mut Context) -> ast::Result<JsValue> {
+        match self.to_primitive_with(ctx, PreferredType::Number)? {
+            JsValue::BigInt(_) => {
+                let message = "Cannot convert a BigInt value to a number";
+                Err(ctx.throw_error(ErrorKind::TypeError, message))
+            }
+            JsValue::Symbol(_) => {
+                let message = "Cannot convert a Symbol value to a number";
+                Err(ctx.throw_error(ErrorKind::TypeError, message))
+            }
+            value => Ok(value.number_value()),
+        }
+    }
+
+    /// `ToString`, which unlike `to_string` throws for symbols
+    pub fn to_string_with(&self, ctx: &mut Context) -> ast::Result<String> {
+        match self.to_primitive_with(ctx, PreferredType::String)? {
+            JsValue::Symbol(_) => {
+                let message = "Cannot convert a Symbol value to a string";
+                Err(ctx.throw_error(ErrorKind::TypeError, message))
+            }
+            value => Ok(value.to_string()),
+        }
+    }
+
+    /// `ToPrimitive` for the callers without a context, objects don't run any JS code
     pub fn to_primitive(&self, _preferred_type: PreferredType) -> JsValue {
         if let JsValue::Object(obj) = self {
-            JsValue::string(format!("Object: {:?}", obj).as_ref())
+            if obj.is_error() {
+                // Error.prototype.toString
+                let name = obj.get_data_property("name").to_string();
+                let message = obj.get_data_property("message").to_string();
+                return if message.is_empty() {
+                    JsValue::string(name.as_ref())
+                } else {
+                    JsValue::string(format!("{}: {}", name, message).as_ref())
+                };
+            }
+            match &obj.kind {
+                ObjectKind::Boolean(b) => return JsValue::Boolean(*b),
+                ObjectKind::Number(n) => return JsValue::Rational(*n),
+                ObjectKind::BigInt(v) => return JsValue::BigInt(v.clone()),
+                ObjectKind::String(s) => return JsValue::String(s.clone()),
+                ObjectKind::Symbol(symbol) => return JsValue::Symbol(symbol.clone()),
+                _ if obj.is_function() => {
+                    let name = obj.get_data_property("name").to_string();
+                    let source = format!("function {}() {{ [native code] }}", name);
+                    return JsValue::string(source.as_ref());
+                }
+                _ => {}
+            }
+            if let ObjectKind::Array(array) = &obj.kind {
+                // Array.prototype.join, getters are left out
+                let elements: Vec<_> = (0..array.length())
+                    .map(|index| match obj.get_data_property(index) {
+                        JsValue::Undefined | JsValue::Null => String::new(),
+                        value => value.to_string(),
+                    })
+                    .collect();
+                return JsValue::string(elements.join(",").as_ref());
+            }
+            JsValue::string("[object Object]")
         } else {
             self.clone()
         }
@@ -148,28 +511,34 @@ impl JsValue {
             JsValue::Undefined => false,
             JsValue::Null => false,
             JsValue::Boolean(v) => *v,
-            JsValue::Rational(v) => !(v.is_nan() && *v == 0.0),
-            JsValue::Integer(v) => *v == 0,
-            JsValue::BigInt(_) => todo!(),
+            JsValue::Rational(v) => !(v.is_nan() || *v == 0.0),
+            JsValue::Integer(v) => *v != 0,
+            JsValue::BigInt(v) => !v.is_zero(),
             JsValue::Symbol(_) => true,
             JsValue::String(s) => !s.string.is_empty(),
             JsValue::Object(_) => true,
         }
     }
 
-    pub fn to_number(&self) -> JsValue {
+    /// `ToNumber` for the callers without a context, BigInts and symbols are `NaN` as only
+    /// `to_number_with` can throw for them
+    pub(crate) fn number_value(&self) -> JsValue {
         match self {
             JsValue::Undefined => JsValue::nan(),
             JsValue::Null => JsValue::Integer(0),
             JsValue::Boolean(b) => JsValue::Integer(if *b { 1 } else { 0 }),
             JsValue::Rational(_) => self.clone(),
             JsValue::Integer(_) => self.clone(),
-            JsValue::BigInt(_) => panic!("TypeError"),
-            JsValue::Symbol(_) => panic!("TypeError"),
+            JsValue::BigInt(_) | JsValue::Symbol(_) => JsValue::nan(),
             JsValue::String(s) => {
-                JsValue::Rational(*s.string.parse::<f64>().ok().get_or_insert(f64::NAN))
+                let s = s.string.trim();
+                if s.is_empty() {
+                    JsValue::Integer(0)
+                } else {
+                    JsValue::number_from_str(s)
+                }
             }
-            JsValue::Object(_) => self.to_primitive(PreferredType::Number).to_number(),
+            JsValue::Object(_) => self.to_primitive(PreferredType::Number).number_value(),
         }
     }
 
@@ -178,7 +547,7 @@ impl JsValue {
         if primitive_value.is_bigint() {
             primitive_value
         } else {
-            primitive_value.to_number()
+            primitive_value.number_value()
         }
     }
 
@@ -187,12 +556,17 @@ impl JsValue {
             JsValue::Undefined => "undefined".into(),
             JsValue::Null => "null".into(),
             JsValue::Boolean(v) => v.to_string(),
-            JsValue::Rational(v) => v.to_string(),
+            JsValue::Rational(v) if v.is_infinite() => {
+                if *v > 0.0 { "Infinity" } else { "-Infinity" }.into()
+            }
+            JsValue::Rational(v) if v.is_nan() => "NaN".into(),
+            JsValue::Rational(v) => number_to_string(*v),
             JsValue::Integer(v) => v.to_string(),
-            JsValue::BigInt(_) => todo!(),
+            JsValue::BigInt(v) => v.to_string(),
             JsValue::String(v) => v.clone().string.to_string(),
             JsValue::Object(_) => self.to_primitive(PreferredType::String).to_string(),
-            JsValue::Symbol(_) => panic!("TypeError"),
+            // `ToString` throws, this is the descriptive string of `String(symbol)`
+            JsValue::Symbol(symbol) => symbol.to_string(),
         }
     }
 
@@ -201,7 +575,7 @@ impl JsValue {
     }
 
     pub fn to_i32(&self) -> i32 {
-        let number = self.to_number().as_f64();
+        let number = self.number_value().as_f64();
 
         if number.is_infinite() || number.is_nan() || number == 0.0 {
             return 0;
@@ -221,7 +595,7 @@ impl JsValue {
     }
 
     pub fn to_u32(&self) -> u32 {
-        let number = self.to_number().as_f64();
+        let number = self.number_value().as_f64();
 
         if number.is_infinite() || number.is_nan() || number == 0.0 {
             return 0;
@@ -236,7 +610,7 @@ impl JsValue {
     }
 
     pub fn to_i16(&self) -> i16 {
-        let number = self.to_number().as_f64();
+        let number = self.number_value().as_f64();
 
         if number.is_infinite() || number.is_nan() || number == 0.0 {
             return 0;
@@ -256,7 +630,7 @@ impl JsValue {
     }
 
     pub fn to_u16(&self) -> u16 {
-        let number = self.to_number().as_f64();
+        let number = self.number_value().as_f64();
 
         if number.is_infinite() || number.is_nan() || number == 0.0 {
             return 0;
@@ -271,7 +645,7 @@ impl JsValue {
     }
 
     pub fn to_i8(&self) -> i8 {
-        let number = self.to_number().as_f64();
+        let number = self.number_value().as_f64();
 
         if number.is_infinite() || number.is_nan() || number == 0.0 {
             return 0;
@@ -291,7 +665,7 @@ impl JsValue {
     }
 
     pub fn to_u8(&self) -> u8 {
-        let number = self.to_number().as_f64();
+        let number = self.number_value().as_f64();
 
         if number.is_infinite() || number.is_nan() || number == 0.0 {
             return 0;
@@ -306,12 +680,56 @@ impl JsValue {
     }
 }
 
+/// `Number::toString` for finite numbers, exponents are used past 21 digits and for numbers
+/// below 10^-6
+fn number_to_string(v: f64) -> String {
+    if v == 0.0 {
+        return "0".into();
+    }
+    // `{:e}` gives the shortest digits that read back as `v`
+    let scientific = format!("{:e}", v.abs());
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let digits = mantissa.replace('.', "");
+    let k = digits.len() as i32;
+    let n = exponent.parse::<i32>().unwrap() + 1;
+
+    let sign = if v < 0.0 { "-" } else { "" };
+    let body = if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat(-n as usize), digits)
+    } else {
+        let exponent = if n > 0 {
+            format!("+{}", n - 1)
+        } else {
+            (n - 1).to_string()
+        };
+        match digits.split_at(1) {
+            (first, "") => format!("{}e{}", first, exponent),
+            (first, rest) => format!("{}.{}e{}", first, rest, exponent),
+        }
+    };
+    format!("{}{}", sign, body)
+}
+
 impl From<f64> for JsValue {
     fn from(v: f64) -> Self {
         JsValue::Rational(v)
     }
 }
 
+impl From<u32> for JsValue {
+    fn from(v: u32) -> Self {
+        if v <= i32::MAX as u32 {
+            JsValue::Integer(v as i32)
+        } else {
+            JsValue::Rational(v as f64)
+        }
+    }
+}
+
 impl From<i32> for JsValue {
     fn from(v: i32) -> Self {
         JsValue::Integer(v)
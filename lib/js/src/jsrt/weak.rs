@@ -0,0 +1,70 @@
+use super::{JsObject, JsValue};
+use crate::gc::{GcPointer, GcTrace, Trace, Tracer, WeakGcPointer};
+
+/// A target registered with `FinalizationRegistry.prototype.register`
+#[derive(Debug, GcTrace)]
+struct RegistryCell {
+    target: WeakGcPointer<JsObject>,
+    held_value: JsValue,
+    unregister_token: Option<WeakGcPointer<JsObject>>,
+}
+
+/// `[[Cells]]` and `[[CleanupCallback]]` of a `FinalizationRegistry`, the held values of the
+/// targets that died are passed to the callback after the collection that freed them
+#[derive(Debug, GcTrace)]
+pub struct FinalizationRegistry {
+    cleanup: JsValue,
+    cells: Vec<RegistryCell>,
+}
+
+impl FinalizationRegistry {
+    pub fn new(cleanup: JsValue) -> FinalizationRegistry {
+        FinalizationRegistry {
+            cleanup,
+            cells: Vec::new(),
+        }
+    }
+
+    pub fn cleanup_callback(&self) -> JsValue {
+        self.cleanup.clone()
+    }
+
+    pub fn register(
+        &mut self,
+        target: &GcPointer<JsObject>,
+        held_value: JsValue,
+        unregister_token: Option<&GcPointer<JsObject>>,
+    ) {
+        self.cells.push(RegistryCell {
+            target: WeakGcPointer::new(target),
+            held_value,
+            unregister_token: unregister_token.map(WeakGcPointer::new),
+        });
+    }
+
+    /// Forget the targets registered with `token`, returning whether there was any
+    pub fn unregister(&mut self, token: &GcPointer<JsObject>) -> bool {
+        let len = self.cells.len();
+        self.cells.retain(|cell| {
+            !cell
+                .unregister_token
+                .as_ref()
+                .is_some_and(|t| t.points_to(token))
+        });
+        self.cells.len() != len
+    }
+
+    /// Some target was collected and its callback hasn't run yet
+    pub fn has_cleared_targets(&self) -> bool {
+        self.cells.iter().any(|cell| cell.target.is_cleared())
+    }
+
+    /// Remove the cells of the collected targets, returning their held values
+    pub fn take_cleared(&mut self) -> Vec<JsValue> {
+        let (cleared, alive) = std::mem::take(&mut self.cells)
+            .into_iter()
+            .partition::<Vec<_>, _>(|cell| cell.target.is_cleared());
+        self.cells = alive;
+        cleared.into_iter().map(|cell| cell.held_value).collect()
+    }
+}
@@ -1,13 +1,16 @@
 #![allow(clippy::upper_case_acronyms)]
-#![feature(default_free_fn)]
 #![feature(ptr_metadata)]
 
 pub mod ast;
 pub mod gc;
 pub mod jsrt;
 pub mod parser;
+pub mod regexp;
 pub mod vm;
 
+#[cfg(test)]
+mod tests;
+
 #[macro_use]
 extern crate lazy_static;
 
@@ -1,44 +1,260 @@
-#![feature(assoc_char_funcs)]
+mod editor;
+mod repl;
+
+use std::{env, fs, process, thread};
 
 use js::{
-    ast::{ASTNode, Expression},
+    ast::{Completion, Program},
+    jsrt::{inspect, JsValue},
     parser::{lexer::Lexer, token::TokenKind, Parser},
-    runtime::Value,
-    vm::Context,
+    vm::{Compiler, Context, FileSystemLoader, Interpreter, Runtime, MAX_CALL_DEPTH},
 };
+use log::{Level, LevelFilter, Metadata, Record};
 
-fn main() {
-    let mut context = Context::new();
+const USAGE: &str = "\
+Usage: js [options] [file]
 
-    let mut parser = Parser::new(
-        r#"
-            function a() {
-                return 4;
-            }
+Run the script `file`, or start a REPL without one
+
+Options:
+  -m, --module       Run the file as a module, `.mjs` files always are
+  --dump-tokens      Print the tokens of the source before running it, builds with the
+                     `debug_lexer` feature also trace each char the lexer reads
+  --dump-ast         Print the syntax tree of the source before running it
+  --dump-bytecode    Print the compiled code of the source before running it
+  -h, --help         Print this help";
 
-            function b(a) {
+#[derive(Debug, Default)]
+struct Options {
+    module: bool,
+    dump_tokens: bool,
+    dump_ast: bool,
+    dump_bytecode: bool,
+    file: Option<String>,
+}
+
+impl Options {
+    fn parse(args: impl Iterator<Item = String>) -> Result<Options, String> {
+        let mut options = Options::default();
+        for arg in args {
+            match arg.as_str() {
+                "-m" | "--module" => options.module = true,
+                "--dump-tokens" => options.dump_tokens = true,
+                "--dump-ast" => options.dump_ast = true,
+                "--dump-bytecode" => options.dump_bytecode = true,
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    process::exit(0);
+                }
+                option if option.starts_with('-') => {
+                    return Err(format!("Unknown option '{}'", option))
+                }
+                _ if options.file.is_some() => return Err("Only one file can be run".into()),
+                _ => options.file = Some(arg),
             }
+        }
+        Ok(options)
+    }
+}
+
+/// Prints what `console` logs, errors and warnings on stderr
+struct ConsoleLogger;
+
+impl log::Log for ConsoleLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target().starts_with("js")
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        match record.level() {
+            Level::Error | Level::Warn => eprintln!("{}", record.args()),
+            _ => println!("{}", record.args()),
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Native stack of the thread running the scripts, the frames of a debug build are large
+const STACK_SIZE: usize = MAX_CALL_DEPTH * 64 * 1024;
+
+fn main() {
+    let runner = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(run)
+        .expect("the runner thread can be spawned");
+    if runner.join().is_err() {
+        process::exit(101);
+    }
+}
+
+fn run() {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
 
-            function c(a, b, c) {
-                let a = 4;
+    log::set_logger(&ConsoleLogger).expect("no other logger is set");
+    log::set_max_level(LevelFilter::Debug);
 
-                b = 3;
-                var c = 2;
-                return a + b * c;
+    let runtime = Runtime::new();
+    let root = env::current_dir().unwrap_or_default();
+    runtime
+        .lock()
+        .unwrap()
+        .set_module_loader(Box::new(FileSystemLoader::new(root)));
+    let mut context = Context::new(runtime);
+
+    let succeeded = match &options.file {
+        Some(file) if options.module || file.ends_with(".mjs") => {
+            run_module(&mut context, file, &options)
+        }
+        Some(file) => run_file(&mut context, file, &options),
+        None => match repl::run(&mut context, &options) {
+            Ok(()) => true,
+            Err(error) => {
+                eprintln!("{}", error);
+                false
             }
-        "#,
-    );
+        },
+    };
+    if !succeeded {
+        process::exit(1);
+    }
+}
+
+fn read_file(path: &str) -> Option<String> {
+    match fs::read_to_string(path) {
+        Ok(source) => Some(source),
+        Err(error) => {
+            eprintln!("Cannot read '{}': {}", path, error);
+            None
+        }
+    }
+}
+
+fn run_file(context: &mut Context, path: &str, options: &Options) -> bool {
+    let source = match read_file(path) {
+        Some(source) => source,
+        None => return false,
+    };
+    if options.dump_tokens {
+        dump_tokens(&source);
+    }
+    let program = match Parser::new(&source).parse_program() {
+        Ok(program) => program,
+        Err(error) => {
+            eprintln!("{}", error);
+            return false;
+        }
+    };
 
-    match parser.parse_program() {
-        Ok(mut program) => {
-            println!("{:#?}", program);
+    let result = run_program(context, &program, options);
+    let result = result.and_then(|_| {
+        context
+            .run_macrotasks()
+            .map_err(|completion| uncaught(context, completion))
+    });
+    match result {
+        Ok(()) => true,
+        Err(message) => {
+            eprintln!("{}", message);
+            false
+        }
+    }
+}
 
-            for statement in program.statements_mut().iter_mut() {
-                println!("{:?}", statement.eval(&mut context));
+fn run_module(context: &mut Context, path: &str, options: &Options) -> bool {
+    let source = match read_file(path) {
+        Some(source) => source,
+        None => return false,
+    };
+    if options.dump_tokens {
+        dump_tokens(&source);
+    }
+    if options.dump_ast || options.dump_bytecode {
+        let module = match Parser::new(&source).parse_module() {
+            Ok(module) => module,
+            Err(error) => {
+                eprintln!("{}", error);
+                return false;
             }
+        };
+        if options.dump_ast {
+            println!("{:#?}", module);
         }
-        Err(err) => {
-            println!("{}", err);
+        if options.dump_bytecode {
+            match Compiler::compile_module(&module) {
+                Ok((declarations, body)) => println!("{}\n{}", declarations, body),
+                Err(error) => {
+                    eprintln!("SyntaxError: {}", error);
+                    return false;
+                }
+            }
         }
     }
+
+    // Relative to the current directory like the file of a script
+    let specifier = match path.starts_with('/') || path.starts_with('.') {
+        true => path.to_string(),
+        false => format!("./{}", path),
+    };
+    let result = context.import(&specifier);
+    let result = result.and_then(|_| context.run_macrotasks());
+    match result {
+        Ok(()) => true,
+        Err(completion) => {
+            eprintln!("{}", uncaught(context, completion));
+            false
+        }
+    }
+}
+
+/// Compile and run `program`, printing the dumps asked for, errors are the message to report
+fn run_program(
+    context: &mut Context,
+    program: &Program,
+    options: &Options,
+) -> Result<JsValue, String> {
+    if options.dump_ast {
+        println!("{:#?}", program);
+    }
+    let code = match Compiler::compile_program(program) {
+        Ok(code) => code,
+        Err(error) => return Err(format!("SyntaxError: {}", error)),
+    };
+    if options.dump_bytecode {
+        println!("{}", code);
+    }
+    Interpreter::run(context, &code).map_err(|completion| uncaught(context, completion))
+}
+
+fn dump_tokens(source: &str) {
+    let mut lexer = Lexer::new(source);
+    loop {
+        let token = lexer.next_token();
+        println!(
+            "{}:{} {:?} {:?}",
+            token.line_number(),
+            token.line_column(),
+            token.kind(),
+            token.value()
+        );
+        if token.kind() == TokenKind::Eof {
+            break;
+        }
+    }
+}
+
+fn uncaught(context: &Context, completion: Completion) -> String {
+    match completion {
+        Completion::Throw(error) => format!("Uncaught {}", inspect(context, &error)),
+        completion => format!("{:?}", completion),
+    }
 }
@@ -1,6 +1,7 @@
 use std::fmt::Display;
 
-use super::token::{Token, TokenKind};
+use super::token::{InvalidEscape, Token, TokenKind};
+use crate::regexp::RegexError;
 
 #[derive(Debug)]
 pub struct ParseError<'a> {
@@ -12,10 +13,23 @@ pub struct ParseError<'a> {
 enum ErrorKind {
     Expect(TokenKind),
     Unexpected,
+    InvalidRegExp(RegexError),
+    InvalidEscape(InvalidEscape),
+    /// `#name` used outside of the classes declaring it
+    UndeclaredPrivateName,
+    DuplicatePrivateName,
+    DuplicateConstructor,
+    /// A module exporting the name twice
+    DuplicateExport(String),
+    /// The source ended before the construct being parsed did
+    IncompleteInput,
 }
 
 impl<'a> ParseError<'a> {
     pub fn expect(expect: TokenKind, token: Token<'a>) -> ParseError<'a> {
+        if ends_input(&token) {
+            return ParseError::incomplete_input(token);
+        }
         ParseError {
             kind: ErrorKind::Expect(expect),
             token,
@@ -23,24 +37,240 @@ impl<'a> ParseError<'a> {
     }
 
     pub fn unexpected(token: Token<'a>) -> ParseError<'a> {
+        if ends_input(&token) {
+            return ParseError::incomplete_input(token);
+        }
         ParseError {
             kind: ErrorKind::Unexpected,
             token,
         }
     }
+
+    pub fn incomplete_input(token: Token<'a>) -> ParseError<'a> {
+        ParseError {
+            kind: ErrorKind::IncompleteInput,
+            token,
+        }
+    }
+
+    pub fn undeclared_private_name(token: Token<'a>) -> ParseError<'a> {
+        ParseError {
+            kind: ErrorKind::UndeclaredPrivateName,
+            token,
+        }
+    }
+
+    pub fn duplicate_private_name(token: Token<'a>) -> ParseError<'a> {
+        ParseError {
+            kind: ErrorKind::DuplicatePrivateName,
+            token,
+        }
+    }
+
+    pub fn duplicate_constructor(token: Token<'a>) -> ParseError<'a> {
+        ParseError {
+            kind: ErrorKind::DuplicateConstructor,
+            token,
+        }
+    }
+
+    pub fn duplicate_export(name: &str, token: Token<'a>) -> ParseError<'a> {
+        ParseError {
+            kind: ErrorKind::DuplicateExport(name.to_string()),
+            token,
+        }
+    }
+
+    pub fn invalid_regexp(error: RegexError, token: Token<'a>) -> ParseError<'a> {
+        ParseError {
+            kind: ErrorKind::InvalidRegExp(error),
+            token,
+        }
+    }
+
+    pub fn invalid_escape(error: InvalidEscape, token: Token<'a>) -> ParseError<'a> {
+        ParseError {
+            kind: ErrorKind::InvalidEscape(error),
+            token,
+        }
+    }
+
+    /// Whether more source text could make the program valid, a REPL keeps reading lines then
+    pub fn is_incomplete(&self) -> bool {
+        matches!(self.kind, ErrorKind::IncompleteInput)
+    }
+}
+
+/// The source ends at `token`, a template literal or a block comment is only terminated by its
+/// closing characters
+fn ends_input(token: &Token) -> bool {
+    matches!(
+        token.kind(),
+        TokenKind::Eof
+            | TokenKind::UnterminatedTemplateLiteral
+            | TokenKind::UnterminatedBlockComment
+    )
 }
 
 impl<'a> Display for ParseError<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "SyntaxError at {}:{}: error={:?} token={:?}",
-            self.token.line_number(),
-            self.token.line_column(),
-            self.kind,
-            self.token,
-        )?;
-
-        Ok(())
+        let (line, column) = (self.token.line_number(), self.token.line_column());
+        write!(f, "SyntaxError at {}:{}: ", line, column)?;
+        match &self.kind {
+            ErrorKind::Expect(kind) => write!(
+                f,
+                "Expected {} but found '{}'",
+                describe(*kind),
+                self.token.value()
+            ),
+            ErrorKind::Unexpected => match self.token.kind() {
+                TokenKind::Invalid => {
+                    write!(f, "Invalid or unexpected token '{}'", self.token.value())
+                }
+                TokenKind::UnterminatedStringLiteral => write!(f, "Unterminated string literal"),
+                TokenKind::UnterminatedRegexLiteral => {
+                    write!(f, "Unterminated regular expression literal")
+                }
+                _ => write!(f, "Unexpected token '{}'", self.token.value()),
+            },
+            ErrorKind::InvalidRegExp(error) => write!(f, "{}", error),
+            ErrorKind::InvalidEscape(error) => write!(f, "{}", error),
+            ErrorKind::UndeclaredPrivateName => write!(
+                f,
+                "Private field '{}' must be declared in an enclosing class",
+                self.token.value()
+            ),
+            ErrorKind::DuplicatePrivateName => write!(
+                f,
+                "Identifier '{}' has already been declared",
+                self.token.value()
+            ),
+            ErrorKind::DuplicateConstructor => write!(f, "A class may only have one constructor"),
+            ErrorKind::DuplicateExport(name) => write!(f, "Duplicate export of '{}'", name),
+            ErrorKind::IncompleteInput => write!(f, "Unexpected end of input"),
+        }
+    }
+}
+
+/// What a token of `kind` is called in the messages, its text when it's always the same
+fn describe(kind: TokenKind) -> &'static str {
+    match kind {
+        TokenKind::Invalid => "an invalid token",
+        TokenKind::Eof => "the end of input",
+        TokenKind::Async => "'async'",
+        TokenKind::Await => "'await'",
+        TokenKind::BoolLiteral => "a boolean",
+        TokenKind::Break => "'break'",
+        TokenKind::Class => "'class'",
+        TokenKind::Const => "'const'",
+        TokenKind::Debugger => "'debugger'",
+        TokenKind::Delete => "'delete'",
+        TokenKind::Enum => "'enum'",
+        TokenKind::Export => "'export'",
+        TokenKind::Extends => "'extends'",
+        TokenKind::Function => "'function'",
+        TokenKind::Implements => "'implements'",
+        TokenKind::Import => "'import'",
+        TokenKind::In => "'in'",
+        TokenKind::InstanceOf => "'instanceof'",
+        TokenKind::Interface => "'interface'",
+        TokenKind::Let => "'let'",
+        TokenKind::New => "'new'",
+        TokenKind::NullLiteral => "'null'",
+        TokenKind::Package => "'package'",
+        TokenKind::Private => "'private'",
+        TokenKind::Protected => "'protected'",
+        TokenKind::Public => "'public'",
+        TokenKind::Static => "'static'",
+        TokenKind::Super => "'super'",
+        TokenKind::This => "'this'",
+        TokenKind::Typeof => "'typeof'",
+        TokenKind::Var => "'var'",
+        TokenKind::Void => "'void'",
+        TokenKind::Case => "'case'",
+        TokenKind::Catch => "'catch'",
+        TokenKind::Continue => "'continue'",
+        TokenKind::Default => "'default'",
+        TokenKind::Do => "'do'",
+        TokenKind::Else => "'else'",
+        TokenKind::Finally => "'finally'",
+        TokenKind::For => "'for'",
+        TokenKind::If => "'if'",
+        TokenKind::Return => "'return'",
+        TokenKind::Switch => "'switch'",
+        TokenKind::Throw => "'throw'",
+        TokenKind::Try => "'try'",
+        TokenKind::While => "'while'",
+        TokenKind::With => "'with'",
+        TokenKind::Yield => "'yield'",
+        TokenKind::BracketClose => "']'",
+        TokenKind::BracketOpen => "'['",
+        TokenKind::Colon => "':'",
+        TokenKind::Comma => "','",
+        TokenKind::CurlyClose | TokenKind::TemplateLiteralExprEnd => "'}'",
+        TokenKind::CurlyOpen => "'{'",
+        TokenKind::ParenClose => "')'",
+        TokenKind::ParenOpen => "'('",
+        TokenKind::Semicolon => "';'",
+        TokenKind::TemplateLiteralExprStart => "'${'",
+        TokenKind::Ampersand => "'&'",
+        TokenKind::AmpersandEquals => "'&='",
+        TokenKind::Arrow => "'=>'",
+        TokenKind::Asterisk => "'*'",
+        TokenKind::AsteriskEquals => "'*='",
+        TokenKind::Caret => "'^'",
+        TokenKind::CaretEquals => "'^='",
+        TokenKind::DoubleAmpersand => "'&&'",
+        TokenKind::DoubleAmpersandEquals => "'&&='",
+        TokenKind::DoubleAsterisk => "'**'",
+        TokenKind::DoubleAsteriskEquals => "'**='",
+        TokenKind::DoublePipe => "'||'",
+        TokenKind::DoublePipeEquals => "'||='",
+        TokenKind::DoubleQuestionMark => "'??'",
+        TokenKind::DoubleQuestionMarkEquals => "'??='",
+        TokenKind::Equals => "'='",
+        TokenKind::EqualsEquals => "'=='",
+        TokenKind::EqualsEqualsEquals => "'==='",
+        TokenKind::ExclamationMark => "'!'",
+        TokenKind::ExclamationMarkEquals => "'!='",
+        TokenKind::ExclamationMarkEqualsEquals => "'!=='",
+        TokenKind::GreaterThan => "'>'",
+        TokenKind::GreaterThanEquals => "'>='",
+        TokenKind::LessThan => "'<'",
+        TokenKind::LessThanEquals => "'<='",
+        TokenKind::Minus => "'-'",
+        TokenKind::MinusEquals => "'-='",
+        TokenKind::MinusMinus => "'--'",
+        TokenKind::Percent => "'%'",
+        TokenKind::PercentEquals => "'%='",
+        TokenKind::Period => "'.'",
+        TokenKind::Pipe => "'|'",
+        TokenKind::PipeEquals => "'|='",
+        TokenKind::Plus => "'+'",
+        TokenKind::PlusEquals => "'+='",
+        TokenKind::PlusPlus => "'++'",
+        TokenKind::QuestionMark => "'?'",
+        TokenKind::QuestionMarkPeriod => "'?.'",
+        TokenKind::ShiftLeft => "'<<'",
+        TokenKind::ShiftLeftEquals => "'<<='",
+        TokenKind::ShiftRight => "'>>'",
+        TokenKind::ShiftRightEquals => "'>>='",
+        TokenKind::Slash => "'/'",
+        TokenKind::SlashEquals => "'/='",
+        TokenKind::Tilde => "'~'",
+        TokenKind::TripleDot => "'...'",
+        TokenKind::UnsignedShiftRight => "'>>>'",
+        TokenKind::UnsignedShiftRightEquals => "'>>>='",
+        TokenKind::Identifier => "an identifier",
+        TokenKind::PrivateIdentifier => "a private name",
+        TokenKind::BigIntLiteral => "a BigInt",
+        TokenKind::NumericLiteral => "a number",
+        TokenKind::RegexFlags => "regular expression flags",
+        TokenKind::RegexLiteral | TokenKind::UnterminatedRegexLiteral => "a regular expression",
+        TokenKind::StringLiteral | TokenKind::UnterminatedStringLiteral => "a string",
+        TokenKind::TemplateLiteralEnd | TokenKind::TemplateLiteralStart => "'`'",
+        TokenKind::TemplateLiteralString => "a template string",
+        TokenKind::UnterminatedTemplateLiteral => "a template literal",
+        TokenKind::UnterminatedBlockComment => "a comment",
     }
 }
@@ -4,11 +4,21 @@ use super::token::*;
 
 const EOF: char = '\0';
 
-#[derive(Debug)]
+/// A template literal being lexed, `}` ends its substitution unless it closes a `{` of it
+#[derive(Debug, Clone, Copy)]
+struct TemplateState {
+    in_expr: bool,
+    open_curly_count: usize,
+}
+
+#[derive(Debug, Clone)]
 pub struct Lexer<'s> {
     source: &'s str,
+    /// Byte offset of the char after `current_char`
     position: usize,
     previous_token_kind: TokenKind,
+    /// Templates nested in the substitutions of each other, the innermost last
+    template_states: Vec<TemplateState>,
     current_char: char,
     line_number: usize,
     line_column: usize,
@@ -20,6 +30,7 @@ impl<'s> Lexer<'s> {
             source,
             position: 0,
             previous_token_kind: TokenKind::Eof,
+            template_states: Vec::new(),
             current_char: '\0',
             line_number: 1,
             line_column: 0,
@@ -29,10 +40,8 @@ impl<'s> Lexer<'s> {
         lexer
     }
 
-    pub fn next_token(&mut self) -> Token<'s> {
-        let trivia_start = self.position - 1;
-
-        // consume whitespace and comments
+    /// Consume whitespace and comments, false if the source ends in a block comment
+    fn consume_trivia(&mut self) -> bool {
         loop {
             if self.is_line_terminator() {
                 loop {
@@ -56,9 +65,13 @@ impl<'s> Lexer<'s> {
                     }
                 }
             } else if self.is_block_comment_start() {
+                self.consume(); // consume '/'
                 loop {
                     self.consume();
-                    if self.is_eof() || self.is_block_comment_end() {
+                    if self.current_char == EOF {
+                        return false;
+                    }
+                    if self.is_block_comment_end() {
                         break;
                     }
                 }
@@ -66,16 +79,39 @@ impl<'s> Lexer<'s> {
                 self.consume(); // consume '*'
                 self.consume(); // consume '/'
             } else {
-                break;
+                return true;
             }
         }
+    }
 
-        let value_start = self.position - 1;
+    pub fn next_token(&mut self) -> Token<'s> {
+        let trivia_start = self.current_start();
+        // Whitespace is part of the strings of a template
+        let in_template = self
+            .template_states
+            .last()
+            .is_some_and(|state| !state.in_expr);
+
+        let comment_closed = in_template || self.consume_trivia();
+
+        let value_start = self.current_start();
         let value_start_line_number = self.line_number;
         let value_start_line_column = self.line_column;
         let mut token_kind = TokenKind::Invalid;
 
-        if self.is_identifier_start() {
+        if in_template {
+            token_kind = self.consume_template_part();
+        } else if self.previous_token_kind == TokenKind::RegexLiteral
+            && value_start == trivia_start
+            && self.is_identifier_start()
+        {
+            while self.is_identifier_body() {
+                self.consume();
+            }
+            token_kind = TokenKind::RegexFlags;
+        } else if self.current_char == '/' && self.is_regex_allowed() {
+            token_kind = self.consume_regex_literal();
+        } else if self.is_identifier_start() {
             // Identifier or keywords
 
             loop {
@@ -85,85 +121,99 @@ impl<'s> Lexer<'s> {
                 }
             }
 
-            if let Some(tk) = KEY_WORDS.get(&self.source[value_start..self.position - 1]) {
+            if let Some(tk) = KEY_WORDS.get(&self.source[value_start..self.current_start()]) {
                 token_kind = *tk;
             } else {
                 token_kind = TokenKind::Identifier;
             }
-        } else if self.is_numeric_literal_start() {
-            token_kind = TokenKind::NumericLiteral;
-            while self.current_char.is_digit(10) {
+        } else if self.is_private_identifier_start() {
+            self.consume();
+            while self.is_identifier_body() {
                 self.consume();
             }
+            token_kind = TokenKind::PrivateIdentifier;
+        } else if self.is_numeric_literal_start() {
+            token_kind = self.consume_numeric_literal();
         } else if self.current_char == '\'' || self.current_char == '"' {
             token_kind = TokenKind::StringLiteral;
             let stop_char = self.current_char;
+            self.consume();
             loop {
-                self.consume();
-
-                if self.current_char == '\\'
-                    && !self.is_eof()
-                    && self.unchecked_peekato(0) == stop_char
-                {
-                    self.consume();
-                    self.consume();
+                if self.current_char == EOF {
+                    token_kind = TokenKind::UnterminatedStringLiteral;
+                    break;
                 }
 
-                if self.current_char == stop_char {
+                // Skip the escaped char, it may be the quote
+                if self.current_char == '\\' {
+                    self.consume();
+                    if self.current_char == EOF {
+                        continue;
+                    }
+                } else if self.current_char == stop_char {
                     self.consume();
                     break;
                 }
+
+                self.consume();
             }
+        } else if self.current_char == '`' {
+            self.consume();
+            self.template_states.push(TemplateState {
+                in_expr: false,
+                open_curly_count: 0,
+            });
+            token_kind = TokenKind::TemplateLiteralStart;
+        } else if self.current_char == EOF && !comment_closed {
+            token_kind = TokenKind::UnterminatedBlockComment;
         } else if self.current_char == EOF {
             token_kind = TokenKind::Eof;
         } else {
             // The only four char operator: >>>=
-            let mut found_4_char_token = false;
             if self.match_4('>', '>', '>', '=') {
-                found_4_char_token = true;
+                token_kind = TokenKind::UnsignedShiftRightEquals;
                 self.consume();
                 self.consume();
                 self.consume();
                 self.consume();
-            }
-
-            let mut found_3_char_token = false;
-            if let Some(tk) =
-                THREE_CHAR_TOKEN.get(&self.source[self.position - 1..self.position + 2])
+            } else if let Some(tk) = self
+                .source
+                .get(self.current_start()..self.current_start() + 3)
+                .and_then(|str| THREE_CHAR_TOKEN.get(str))
             {
-                found_3_char_token = true;
                 token_kind = *tk;
                 self.consume();
                 self.consume();
                 self.consume();
-            }
-
-            let mut found_2_char_token = false;
-            if let Some(tk) = TWO_CHAR_TOKEN.get(&self.source[self.position - 1..self.position + 1])
+            } else if let Some(tk) = self
+                .source
+                .get(self.current_start()..self.current_start() + 2)
+                .and_then(|str| TWO_CHAR_TOKEN.get(str))
             {
-                found_2_char_token = true;
                 token_kind = *tk;
                 self.consume();
                 self.consume();
-            }
-
-            let mut found_single_char_token = false;
-            if let Some(tk) = SINGLE_CHAR_TOKEN.get(&self.current_char) {
-                found_single_char_token = true;
+            } else if let Some(tk) = SINGLE_CHAR_TOKEN.get(&self.current_char) {
                 token_kind = *tk;
                 self.consume();
-            }
-
-            if !found_4_char_token
-                && !found_3_char_token
-                && !found_2_char_token
-                && !found_single_char_token
-            {
+            } else {
                 self.consume();
                 token_kind = TokenKind::Invalid;
             }
         }
 
+        if let Some(state) = self.template_states.last_mut().filter(|s| s.in_expr) {
+            match token_kind {
+                TokenKind::CurlyOpen => state.open_curly_count += 1,
+                TokenKind::CurlyClose if state.open_curly_count == 0 => {
+                    state.in_expr = false;
+                    token_kind = TokenKind::TemplateLiteralExprEnd;
+                }
+                TokenKind::CurlyClose => state.open_curly_count -= 1,
+                _ => {}
+            }
+        }
+
         #[cfg(feature = "debug_lexer")]
         println!(
             "[Pos {} at {}:{}]: value={}:{} trivia={}:{}",
@@ -171,7 +221,7 @@ impl<'s> Lexer<'s> {
             self.line_number,
             self.line_column,
             value_start,
-            self.position - 1,
+            self.current_start(),
             trivia_start,
             value_start
         );
@@ -180,7 +230,7 @@ impl<'s> Lexer<'s> {
 
         Token::new(
             token_kind,
-            &self.source[value_start..self.position - 1],
+            &self.source[value_start..self.current_start()],
             &self.source[trivia_start..value_start],
             value_start_line_number,
             value_start_line_column,
@@ -199,16 +249,15 @@ impl<'s> Lexer<'s> {
         );
 
         if self.is_eof() {
-            self.position += 1;
+            self.position = self.source.len() + 1;
             self.line_column += 1;
             self.current_char = '\0';
             return;
         }
 
         if self.is_line_terminator() {
-            let is_second_char_of_crlf = self.position > 1
-                && self.unchecked_peekato(-2) == '\r'
-                && self.current_char == '\n';
+            let is_second_char_of_crlf =
+                self.current_char == '\n' && self.previous_char() == Some('\r');
 
             if !is_second_char_of_crlf {
                 self.line_column = 1;
@@ -218,8 +267,152 @@ impl<'s> Lexer<'s> {
             self.line_column += 1
         }
 
-        self.current_char = self.unchecked_peekato(0);
-        self.position += 1
+        self.current_char = self.source[self.position..].chars().next().unwrap();
+        self.position += self.current_char.len_utf8();
+    }
+
+    /// Byte offset of `current_char`, the length of the source once it's been read
+    fn current_start(&self) -> usize {
+        self.position - self.current_char.len_utf8()
+    }
+
+    /// The end of a template, the start of a substitution or the string before them
+    fn consume_template_part(&mut self) -> TokenKind {
+        if self.current_char == '`' {
+            self.consume();
+            self.template_states.pop();
+            return TokenKind::TemplateLiteralEnd;
+        }
+        if self.match_2('$', '{') {
+            self.consume();
+            self.consume();
+            if let Some(state) = self.template_states.last_mut() {
+                state.in_expr = true;
+            }
+            return TokenKind::TemplateLiteralExprStart;
+        }
+
+        loop {
+            if self.current_char == EOF {
+                self.template_states.pop();
+                return TokenKind::UnterminatedTemplateLiteral;
+            }
+            if self.current_char == '`' || self.match_2('$', '{') {
+                return TokenKind::TemplateLiteralString;
+            }
+
+            // Skip the escaped char, it may be a backtick
+            if self.current_char == '\\' {
+                self.consume();
+                if self.current_char == EOF {
+                    continue;
+                }
+            }
+            self.consume();
+        }
+    }
+
+    /// A `/` after these is a division, it starts a regular expression anywhere else
+    fn is_regex_allowed(&self) -> bool {
+        !matches!(
+            self.previous_token_kind,
+            TokenKind::BigIntLiteral
+                | TokenKind::BoolLiteral
+                | TokenKind::BracketClose
+                | TokenKind::CurlyClose
+                | TokenKind::Identifier
+                | TokenKind::MinusMinus
+                | TokenKind::NullLiteral
+                | TokenKind::NumericLiteral
+                | TokenKind::ParenClose
+                | TokenKind::PlusPlus
+                | TokenKind::PrivateIdentifier
+                | TokenKind::RegexFlags
+                | TokenKind::RegexLiteral
+                | TokenKind::StringLiteral
+                | TokenKind::Super
+                | TokenKind::TemplateLiteralEnd
+                | TokenKind::This
+        )
+    }
+
+    /// `/pattern/`, a `/` inside of a class or after a `\` doesn't end it
+    fn consume_regex_literal(&mut self) -> TokenKind {
+        let mut in_class = false;
+        self.consume();
+        loop {
+            if self.current_char == EOF || self.is_line_terminator() {
+                return TokenKind::UnterminatedRegexLiteral;
+            }
+
+            match self.current_char {
+                '/' if !in_class => {
+                    self.consume();
+                    return TokenKind::RegexLiteral;
+                }
+                '\\' => {
+                    self.consume();
+                    if self.current_char == EOF || self.is_line_terminator() {
+                        continue;
+                    }
+                }
+                '[' => in_class = true,
+                ']' => in_class = false,
+                _ => {}
+            }
+            self.consume();
+        }
+    }
+
+    /// Decimal, `0x`, `0o` and `0b` numbers, integers followed by `n` are BigInts
+    fn consume_numeric_literal(&mut self) -> TokenKind {
+        let radix = if self.current_char == '0' {
+            match self.peek(0) {
+                Some('x' | 'X') => 16,
+                Some('o' | 'O') => 8,
+                Some('b' | 'B') => 2,
+                _ => 10,
+            }
+        } else {
+            10
+        };
+
+        let mut is_integer = true;
+        if radix != 10 {
+            self.consume();
+            self.consume();
+            while self.current_char.is_digit(radix) {
+                self.consume();
+            }
+        } else {
+            while self.current_char.is_ascii_digit() {
+                self.consume();
+            }
+            if self.current_char == '.' {
+                is_integer = false;
+                self.consume();
+                while self.current_char.is_ascii_digit() {
+                    self.consume();
+                }
+            }
+            if matches!(self.current_char, 'e' | 'E') {
+                is_integer = false;
+                self.consume();
+                if matches!(self.current_char, '+' | '-') {
+                    self.consume();
+                }
+                while self.current_char.is_ascii_digit() {
+                    self.consume();
+                }
+            }
+        }
+
+        if is_integer && self.current_char == 'n' {
+            self.consume();
+            TokenKind::BigIntLiteral
+        } else {
+            TokenKind::NumericLiteral
+        }
     }
 
     fn is_identifier_start(&self) -> bool {
@@ -230,11 +423,17 @@ impl<'s> Lexer<'s> {
         self.current_char.is_digit(10) || self.is_identifier_start()
     }
 
+    /// `#` followed by an identifier
+    fn is_private_identifier_start(&self) -> bool {
+        self.current_char == '#'
+            && self
+                .peek(0)
+                .is_some_and(|next| next.is_alphabetic() || next == '$' || next == '_')
+    }
+
     fn is_numeric_literal_start(&self) -> bool {
         self.current_char.is_digit(10)
-            || (self.current_char == '.'
-                && !self.is_eof()
-                && self.unchecked_peekato(0).is_digit(10))
+            || (self.current_char == '.' && self.peek(0).is_some_and(|next| next.is_digit(10)))
     }
 
     fn is_line_comment_start(&self) -> bool {
@@ -261,39 +460,25 @@ impl<'s> Lexer<'s> {
     }
 
     fn match_2(&self, c1: char, c2: char) -> bool {
-        if self.position >= self.source.len() {
-            false
-        } else {
-            self.current_char == c1 && self.unchecked_peekato(0) == c2
-        }
+        self.current_char == c1 && self.peek(0) == Some(c2)
     }
 
     fn match_3(&self, c1: char, c2: char, c3: char) -> bool {
-        if self.position + 1 >= self.source.len() {
-            false
-        } else {
-            self.current_char == c1
-                && self.unchecked_peekato(0) == c2
-                && self.unchecked_peekato(1) == c3
-        }
+        self.match_2(c1, c2) && self.peek(1) == Some(c3)
     }
 
     fn match_4(&self, c1: char, c2: char, c3: char, c4: char) -> bool {
-        if self.position + 2 >= self.source.len() {
-            false
-        } else {
-            self.current_char == c1
-                && self.unchecked_peekato(0) == c2
-                && self.unchecked_peekato(1) == c3
-                && self.unchecked_peekato(2) == c4
-        }
+        self.match_3(c1, c2, c3) && self.peek(2) == Some(c4)
+    }
+
+    /// The char `offset` chars after `current_char`
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.source.get(self.position..)?.chars().nth(offset)
     }
 
-    fn unchecked_peekato(&self, offset: isize) -> char {
-        self.source
-            .chars()
-            .nth((self.position as isize + offset) as usize)
-            .unwrap()
+    /// The char before `current_char`
+    fn previous_char(&self) -> Option<char> {
+        self.source[..self.current_start()].chars().next_back()
     }
 }
 
@@ -1,21 +1,30 @@
-mod error;
+pub mod error;
 pub mod lexer;
 pub mod token;
 
-use core::panic;
-use std::default::default;
-
 use error::ParseError;
 use lexer::Lexer;
 use token::{Token, TokenKind};
 use utils::prelude::*;
 
-use crate::ast::*;
+use crate::{ast::*, jsrt::JsBigInt, regexp::Regex};
 
 #[derive(Debug)]
 pub struct Parser<'s> {
     lexer: Lexer<'s>,
     current_token: Token<'s>,
+    /// Private names of the classes being parsed, the innermost last
+    private_scopes: Vec<PrivateScope<'s>>,
+    /// Whether `yield` and `await` are expressions in the function being parsed
+    in_generator: bool,
+    in_async: bool,
+}
+
+/// `#names` a class body declares and the ones used in it, which may belong to an outer class
+#[derive(Debug, Default)]
+struct PrivateScope<'s> {
+    declared: Vec<(String, bool, ClassElementKind)>,
+    used: Vec<Token<'s>>,
 }
 
 type Result<'s, T> = std::result::Result<T, ParseError<'s>>;
@@ -25,6 +34,9 @@ impl<'s> Parser<'s> {
         Self {
             lexer: Lexer::new(source),
             current_token: Token::default(),
+            private_scopes: Vec::new(),
+            in_generator: false,
+            in_async: false,
         }
     }
 
@@ -47,36 +59,601 @@ impl<'s> Parser<'s> {
         Ok(program)
     }
 
+    /// Source text of a module, `import` and `export` can only appear at its top level
+    pub fn parse_module(&mut self) -> Result<'s, Module> {
+        self.consume();
+        let mut module = Module::default();
+
+        while !self.done() {
+            if self.match_token(TokenKind::Import) {
+                self.parse_import_declaration(&mut module)?;
+            } else if self.match_token(TokenKind::Export) {
+                self.parse_export_declaration(&mut module)?;
+            } else if self.match_declaration() {
+                module.body.add_statement(self.parse_declaration()?);
+            } else if self.match_statement() {
+                module.body.add_statement(self.parse_statement()?);
+            } else {
+                return Err(ParseError::unexpected(self.consume()));
+            }
+            self.consume_or_insert_semicolon();
+        }
+
+        Ok(module)
+    }
+
+    /// `import x, { a as b } from 'm'`, `import x, * as ns from 'm'` or `import 'm'`
+    fn parse_import_declaration(&mut self, module: &mut Module) -> Result<'s, ()> {
+        self.consume_token(TokenKind::Import)?;
+        if self.match_token(TokenKind::StringLiteral) {
+            module.add_request(&self.consume_string()?);
+            return Ok(());
+        }
+
+        let mut bindings = Vec::new();
+        let mut has_more = true;
+        if self.match_token(TokenKind::Identifier) {
+            let local = self.consume().value().to_string();
+            bindings.push((ImportName::Name("default".to_string()), local));
+            has_more = self.match_token(TokenKind::Comma);
+            if has_more {
+                self.consume();
+            }
+        }
+        if has_more && self.match_token(TokenKind::Asterisk) {
+            self.consume();
+            self.consume_contextual("as")?;
+            let local = self.consume_token(TokenKind::Identifier)?;
+            bindings.push((ImportName::Namespace, local.value().to_string()));
+        } else if has_more {
+            for (_, name, local) in self.parse_module_specifiers(true)? {
+                bindings.push((ImportName::Name(name), local));
+            }
+        }
+
+        let request = self.parse_from_clause()?;
+        module.add_request(&request);
+        for (import_name, local_name) in bindings {
+            module.imports.push(ImportEntry {
+                module_request: request.clone(),
+                import_name,
+                local_name,
+            });
+        }
+        Ok(())
+    }
+
+    /// `export` of a declaration, of the default value, of local names or of another module
+    fn parse_export_declaration(&mut self, module: &mut Module) -> Result<'s, ()> {
+        self.consume_token(TokenKind::Export)?;
+        let token = self.current_token;
+        let mut entries = Vec::new();
+
+        match self.current_token.kind() {
+            TokenKind::Default => {
+                self.consume();
+                let statement =
+                    if self.match_token(TokenKind::Function) || self.match_async_function() {
+                        let declaration = self.parse_function_declaration(Some("default"))?;
+                        Statement::FunctionDeclaration(declaration)
+                    } else if self.match_token(TokenKind::Class) {
+                        let class = self.parse_class(true, Some("default"))?;
+                        Statement::ClassDeclaration(ClassDeclaration::new(class))
+                    } else {
+                        // Bound to a name the code of the module can't refer to
+                        let value = self.parse_expression(0, Associativity::Right)?;
+                        let mut declaration = VariableDeclaration::new(DeclarationKind::Const);
+                        let target = BindingPattern::Identifier(Identifier::new("*default*"));
+                        declaration.add(target, Some(value));
+                        Statement::VariableDeclaration(declaration)
+                    };
+                for name in declaration_names(&statement) {
+                    entries.push(ExportEntry::local("default", &name));
+                }
+                module.body.add_statement(statement);
+            }
+            TokenKind::Asterisk => {
+                self.consume();
+                let (export_name, import_name) = if self.match_contextual("as") {
+                    self.consume();
+                    let name = self.parse_module_export_name()?;
+                    (Some(name), ImportName::Namespace)
+                } else {
+                    (None, ImportName::All)
+                };
+                let request = self.parse_from_clause()?;
+                module.add_request(&request);
+                entries.push(ExportEntry {
+                    export_name,
+                    module_request: Some(request),
+                    import_name: Some(import_name),
+                    local_name: None,
+                });
+            }
+            TokenKind::CurlyOpen => {
+                let specifiers = self.parse_module_specifiers(false)?;
+                if self.match_contextual("from") {
+                    let request = self.parse_from_clause()?;
+                    module.add_request(&request);
+                    for (_, name, export_name) in specifiers {
+                        entries.push(ExportEntry {
+                            export_name: Some(export_name),
+                            module_request: Some(request.clone()),
+                            import_name: Some(ImportName::Name(name)),
+                            local_name: None,
+                        });
+                    }
+                } else {
+                    for (token, name, export_name) in specifiers {
+                        if token.kind() != TokenKind::Identifier {
+                            return Err(ParseError::unexpected(token));
+                        }
+                        entries.push(ExportEntry::local(&export_name, &name));
+                    }
+                }
+            }
+            _ if self.match_declaration() || self.match_variable_declaration() => {
+                let statement = self.parse_declaration()?;
+                for name in declaration_names(&statement) {
+                    entries.push(ExportEntry::local(&name, &name));
+                }
+                module.body.add_statement(statement);
+            }
+            _ => return Err(ParseError::unexpected(self.consume())),
+        }
+
+        for entry in entries {
+            if let Some(name) = &entry.export_name {
+                if module
+                    .exports
+                    .iter()
+                    .any(|e| e.export_name.as_ref() == Some(name))
+                {
+                    return Err(ParseError::duplicate_export(name, token));
+                }
+            }
+            module.exports.push(entry);
+        }
+        Ok(())
+    }
+
+    /// `{ a, b as c }` of an import or an export, as the token of each name, the name and its
+    /// alias, the aliases of imports are the names of local bindings
+    fn parse_module_specifiers(
+        &mut self,
+        is_import: bool,
+    ) -> Result<'s, Vec<(Token<'s>, String, String)>> {
+        self.consume_token(TokenKind::CurlyOpen)?;
+        let mut specifiers = Vec::new();
+        while !self.match_token(TokenKind::CurlyClose) {
+            let token = self.current_token;
+            let name = self.parse_module_export_name()?;
+            let alias = if self.match_contextual("as") {
+                self.consume();
+                match is_import {
+                    true => self
+                        .consume_token(TokenKind::Identifier)?
+                        .value()
+                        .to_string(),
+                    false => self.parse_module_export_name()?,
+                }
+            } else if is_import && token.kind() != TokenKind::Identifier {
+                return Err(ParseError::unexpected(token));
+            } else {
+                name.clone()
+            };
+            specifiers.push((token, name, alias));
+
+            if !self.match_token(TokenKind::Comma) {
+                break;
+            }
+            self.consume();
+        }
+        self.consume_token(TokenKind::CurlyClose)?;
+        Ok(specifiers)
+    }
+
+    /// Name an import or an export refers to, which can be a keyword or a string
+    fn parse_module_export_name(&mut self) -> Result<'s, String> {
+        if self.match_token(TokenKind::StringLiteral) {
+            self.consume_string()
+        } else if self.current_token.is_identifier_name() {
+            Ok(self.consume().value().to_string())
+        } else {
+            Err(ParseError::unexpected(self.current_token))
+        }
+    }
+
+    /// `from 'specifier'`
+    fn parse_from_clause(&mut self) -> Result<'s, String> {
+        self.consume_contextual("from")?;
+        self.consume_string()
+    }
+
     fn parse_declaration(&mut self) -> Result<'s, Statement> {
         if self.match_variable_declaration() {
             Ok(Statement::VariableDeclaration(
                 self.parse_variable_declaration()?,
             ))
-        } else if self.current_token.kind() == TokenKind::Function {
+        } else if self.match_token(TokenKind::Function) || self.match_async_function() {
             Ok(Statement::FunctionDeclaration(
-                self.parse_function_declaration()?,
+                self.parse_function_declaration(None)?,
             ))
         } else {
-            todo!("Unhandle: {:?}", self.current_token)
+            let class = self.parse_class(true, None)?;
+            Ok(Statement::ClassDeclaration(ClassDeclaration::new(class)))
+        }
+    }
+
+    /// `class Name extends Parent { ... }`, the name is optional for class expressions and
+    /// `export default class`, which is named `default_name`
+    fn parse_class(
+        &mut self,
+        is_declaration: bool,
+        default_name: Option<&str>,
+    ) -> Result<'s, Class> {
+        self.consume_token(TokenKind::Class)?;
+        let name = if self.match_token(TokenKind::Identifier)
+            || (is_declaration && default_name.is_none())
+        {
+            Some(
+                self.consume_token(TokenKind::Identifier)?
+                    .value()
+                    .to_string(),
+            )
+        } else {
+            default_name.map(str::to_string)
+        };
+        let heritage = if self.match_token(TokenKind::Extends) {
+            self.consume();
+            Some(self.parse_expression(19, Associativity::Right)?)
+        } else {
+            None
+        };
+
+        self.consume_token(TokenKind::CurlyOpen)?;
+        self.private_scopes.push(PrivateScope::default());
+        let mut constructor = None;
+        let mut elements = Vec::new();
+        loop {
+            match self.current_token.kind() {
+                TokenKind::CurlyClose => break,
+                TokenKind::Semicolon => {
+                    self.consume();
+                }
+                _ => {
+                    let element = self.parse_class_element(name.as_deref(), heritage.is_some())?;
+                    match element {
+                        ClassBodyElement::Element(element) => elements.push(element),
+                        ClassBodyElement::Constructor(token, function) => {
+                            if constructor.is_some() {
+                                return Err(ParseError::duplicate_constructor(token));
+                            }
+                            constructor = Some(function);
+                        }
+                    }
+                }
+            }
+        }
+        self.consume_token(TokenKind::CurlyClose)?;
+        self.resolve_private_names()?;
+
+        let constructor =
+            constructor.unwrap_or_else(|| default_constructor(name.as_deref(), heritage.is_some()));
+        Ok(Class::new(
+            name.as_deref().map(Identifier::new),
+            heritage,
+            constructor,
+            elements,
+        ))
+    }
+
+    /// Method, accessor, field or the constructor of a class body
+    fn parse_class_element(
+        &mut self,
+        class_name: Option<&str>,
+        is_derived: bool,
+    ) -> Result<'s, ClassBodyElement<'s>> {
+        // `static` and `get`/`set` are names when nothing follows them
+        let is_modifier = |parser: &Self| {
+            !matches!(
+                parser.peek().kind(),
+                TokenKind::ParenOpen
+                    | TokenKind::Equals
+                    | TokenKind::Semicolon
+                    | TokenKind::CurlyClose
+            )
+        };
+        let is_static = self.match_token(TokenKind::Identifier)
+            && self.current_token.value() == "static"
+            && is_modifier(self);
+        if is_static {
+            self.consume();
+        }
+        let (is_generator, is_async) = self.parse_method_prefix()?;
+
+        let mut kind = ClassElementKind::Method;
+        if !is_generator
+            && !is_async
+            && self.match_token(TokenKind::Identifier)
+            && is_modifier(self)
+        {
+            match self.current_token.value() {
+                "get" => kind = ClassElementKind::Getter,
+                "set" => kind = ClassElementKind::Setter,
+                _ => {}
+            }
+            if kind != ClassElementKind::Method {
+                self.consume();
+            }
+        }
+
+        let key_token = self.current_token;
+        let computed = self.match_token(TokenKind::BracketOpen);
+        let key = match key_token.kind() {
+            TokenKind::PrivateIdentifier => {
+                self.consume();
+                Expression::PrivateName(Identifier::new(key_token.value()))
+            }
+            TokenKind::BracketOpen => {
+                self.consume();
+                let key = self.parse_expression(0, Associativity::Right)?;
+                self.consume_token(TokenKind::BracketClose)?;
+                key
+            }
+            _ => self.parse_property_key()?,
+        };
+        let name = match &key {
+            _ if computed => None,
+            Expression::Identifier(ident) | Expression::PrivateName(ident) => {
+                Some(ident.name().clone())
+            }
+            Expression::Literal(Literal::String(s)) => Some(s.clone()),
+            _ => Some(key_token.value().to_string()),
+        };
+
+        if !self.match_token(TokenKind::ParenOpen) {
+            if kind != ClassElementKind::Method || is_generator || is_async {
+                return Err(ParseError::expect(TokenKind::ParenOpen, self.current_token));
+            }
+            kind = ClassElementKind::Field;
+        }
+        if key_token.kind() == TokenKind::PrivateIdentifier {
+            self.declare_private_name(key_token, is_static, kind)?;
+        }
+
+        if kind == ClassElementKind::Field {
+            let initializer = if self.match_token(TokenKind::Equals) {
+                self.consume();
+                let init = self.in_function(false, false, |parser| {
+                    parser.parse_expression(0, Associativity::Right)
+                })?;
+                // The initializer runs as a method of the instance
+                let mut body = BlockStatement::new();
+                body.add_statement(Statement::ReturnStatement(Some(init)));
+                Some(Function::with_kind(
+                    None,
+                    FormalParameters::default(),
+                    body,
+                    FunctionKind::Method,
+                ))
+            } else {
+                None
+            };
+            self.consume_or_insert_semicolon();
+            let element = ClassElement::new(key, computed, kind, is_static, initializer);
+            return Ok(ClassBodyElement::Element(element));
+        }
+
+        let (params, body) = self.parse_function_rest(is_generator, is_async)?;
+        let is_constructor = !is_static
+            && !computed
+            && kind == ClassElementKind::Method
+            && name.as_deref() == Some("constructor")
+            && key_token.kind() != TokenKind::PrivateIdentifier;
+        if is_constructor {
+            if is_generator || is_async {
+                return Err(ParseError::unexpected(key_token));
+            }
+            let kind = match is_derived {
+                true => FunctionKind::DerivedConstructor,
+                false => FunctionKind::BaseConstructor,
+            };
+            let name = class_name.map(Identifier::new);
+            let function = Function::with_kind(name, params, body, kind);
+            return Ok(ClassBodyElement::Constructor(key_token, function));
+        }
+
+        let name = name.map(|name| match kind {
+            ClassElementKind::Getter => Identifier::new(&format!("get {}", name)),
+            ClassElementKind::Setter => Identifier::new(&format!("set {}", name)),
+            _ => Identifier::new(&name),
+        });
+        let function = Function::with_kind(name, params, body, FunctionKind::Method)
+            .with_flags(is_generator, is_async);
+        let element = ClassElement::new(key, computed, kind, is_static, Some(function));
+        Ok(ClassBodyElement::Element(element))
+    }
+
+    /// Only a getter and a setter of the same placement can share a private name
+    fn declare_private_name(
+        &mut self,
+        token: Token<'s>,
+        is_static: bool,
+        kind: ClassElementKind,
+    ) -> Result<'s, ()> {
+        let scope = self.private_scopes.last_mut().unwrap();
+        let conflict = scope
+            .declared
+            .iter()
+            .any(|(name, other_static, other_kind)| {
+                let is_accessor_pair = matches!(
+                    (other_kind, kind),
+                    (ClassElementKind::Getter, ClassElementKind::Setter)
+                        | (ClassElementKind::Setter, ClassElementKind::Getter)
+                );
+                *name == token.value() && !(is_accessor_pair && *other_static == is_static)
+            });
+        if conflict {
+            return Err(ParseError::duplicate_private_name(token));
+        }
+        scope
+            .declared
+            .push((token.value().to_string(), is_static, kind));
+        Ok(())
+    }
+
+    /// `#name` after a `.`, which an enclosing class has to declare
+    fn use_private_name(&mut self, token: Token<'s>) -> Result<'s, Expression> {
+        match self.private_scopes.last_mut() {
+            Some(scope) => scope.used.push(token),
+            None => return Err(ParseError::undeclared_private_name(token)),
         }
+        Ok(Expression::PrivateName(Identifier::new(token.value())))
     }
 
-    fn parse_function_declaration(&mut self) -> Result<'s, FunctionDeclaration> {
+    /// At the end of a class body, the names it doesn't declare are left to the outer classes
+    fn resolve_private_names(&mut self) -> Result<'s, ()> {
+        let PrivateScope { declared, used } = self.private_scopes.pop().unwrap();
+        let mut unresolved = used
+            .into_iter()
+            .filter(|token| !declared.iter().any(|(name, ..)| name == token.value()));
+        match self.private_scopes.last_mut() {
+            Some(outer) => outer.used.extend(unresolved),
+            None => {
+                if let Some(token) = unresolved.next() {
+                    return Err(ParseError::undeclared_private_name(token));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `function name() {}`, only `export default function` can leave out the name
+    fn parse_function_declaration(
+        &mut self,
+        default_name: Option<&str>,
+    ) -> Result<'s, FunctionDeclaration> {
+        let (is_generator, is_async) = self.parse_function_keyword()?;
+
+        let name = match default_name {
+            Some(name) if !self.match_token(TokenKind::Identifier) => name.to_string(),
+            _ => self
+                .consume_token(TokenKind::Identifier)?
+                .value()
+                .to_string(),
+        };
+        let (params, body) = self.parse_function_rest(is_generator, is_async)?;
+
+        let name = Some(Identifier::new(&name));
+        let function = Function::new(name, params, body).with_flags(is_generator, is_async);
+        Ok(FunctionDeclaration::new(function))
+    }
+
+    fn parse_function_expression(&mut self) -> Result<'s, FunctionExpression> {
+        let (is_generator, is_async) = self.parse_function_keyword()?;
+
+        let name = if self.match_token(TokenKind::Identifier) {
+            Some(Identifier::new(self.consume().value()))
+        } else {
+            None
+        };
+        let (params, body) = self.parse_function_rest(is_generator, is_async)?;
+
+        let function = Function::new(name, params, body).with_flags(is_generator, is_async);
+        Ok(FunctionExpression::new(function))
+    }
+
+    /// `function`, `function*` or `async function`, async generators aren't supported
+    fn parse_function_keyword(&mut self) -> Result<'s, (bool, bool)> {
+        let is_async = self.match_async_function();
+        if is_async {
+            self.consume();
+        }
         self.consume_token(TokenKind::Function)?;
 
-        let ident = self.consume_token(TokenKind::Identifier)?;
+        let is_generator = self.match_token(TokenKind::Asterisk);
+        if is_generator {
+            let token = self.consume();
+            if is_async {
+                return Err(ParseError::unexpected(token));
+            }
+        }
+        Ok((is_generator, is_async))
+    }
+
+    /// `*` or `async` before the key of a method
+    fn parse_method_prefix(&mut self) -> Result<'s, (bool, bool)> {
+        let is_async = self.match_async()
+            && !matches!(
+                self.peek().kind(),
+                TokenKind::ParenOpen
+                    | TokenKind::Colon
+                    | TokenKind::Comma
+                    | TokenKind::Equals
+                    | TokenKind::Semicolon
+                    | TokenKind::CurlyClose
+            );
+        if is_async {
+            self.consume();
+        }
+
+        let is_generator = self.match_token(TokenKind::Asterisk);
+        if is_generator {
+            let token = self.consume();
+            if is_async {
+                return Err(ParseError::unexpected(token));
+            }
+        }
+        Ok((is_generator, is_async))
+    }
+
+    /// Parameters and body of a function, in which `yield` and `await` depend on its kind
+    fn parse_function_rest(
+        &mut self,
+        is_generator: bool,
+        is_async: bool,
+    ) -> Result<'s, (FormalParameters, BlockStatement)> {
+        self.in_function(is_generator, is_async, |parser| {
+            let params = parser.parse_function_params()?;
+            let body = parser.parse_block_statement()?;
+            Ok((params, body))
+        })
+    }
+
+    /// Run `parse` for the code of a nested function, restoring the flags of the outer one
+    fn in_function<T>(
+        &mut self,
+        is_generator: bool,
+        is_async: bool,
+        parse: impl FnOnce(&mut Self) -> Result<'s, T>,
+    ) -> Result<'s, T> {
+        let outer = (self.in_generator, self.in_async);
+        self.in_generator = is_generator;
+        self.in_async = is_async;
+        let result = parse(self);
+        (self.in_generator, self.in_async) = outer;
+        result
+    }
 
+    fn parse_function_params(&mut self) -> Result<'s, FormalParameters> {
         self.consume_token(TokenKind::ParenOpen)?;
 
         let mut params = Vec::new();
+        let mut rest = None;
         loop {
             if self.current_token.kind() == TokenKind::ParenClose {
                 break;
             }
 
-            params.push(Identifier::new(
-                self.consume_token(TokenKind::Identifier)?.value(),
-            ));
+            // The rest parameter is the last one
+            if self.current_token.kind() == TokenKind::TripleDot {
+                self.consume();
+                rest = Some(self.parse_binding_pattern()?);
+                break;
+            }
+
+            params.push(self.parse_binding_element()?);
 
             if self.current_token.kind() == TokenKind::ParenClose {
                 break;
@@ -87,12 +664,211 @@ impl<'s> Parser<'s> {
 
         self.consume_token(TokenKind::ParenClose)?;
 
-        let body = self.parse_block_statement()?;
+        Ok(FormalParameters::new(params, rest))
+    }
 
-        Ok(FunctionDeclaration::new(
-            Identifier::new(ident.value()),
-            params,
-            body,
+    /// `x => body` or `(params) => body`, a concise body is a returned expression
+    fn parse_arrow_function(&mut self) -> Result<'s, FunctionExpression> {
+        // `async => 1` is an arrow function with a parameter named `async`
+        let is_async = self.match_async() && self.peek().kind() != TokenKind::Arrow;
+        if is_async {
+            self.consume();
+        }
+
+        let (params, body) = self.in_function(false, is_async, |parser| {
+            let params = if parser.match_token(TokenKind::Identifier) {
+                let param = Identifier::new(parser.consume().value());
+                let param = BindingElement::new(BindingPattern::Identifier(param), None);
+                FormalParameters::new(vec![param], None)
+            } else {
+                parser.parse_function_params()?
+            };
+            parser.consume_token(TokenKind::Arrow)?;
+
+            let body = if parser.match_token(TokenKind::CurlyOpen) {
+                parser.parse_block_statement()?
+            } else {
+                let mut body = BlockStatement::new();
+                let expr = parser.parse_expression(0, Associativity::Right)?;
+                body.add_statement(Statement::ReturnStatement(Some(expr)));
+                body
+            };
+            Ok((params, body))
+        })?;
+
+        let function = Function::arrow(params, body).with_flags(false, is_async);
+        Ok(FunctionExpression::new(function))
+    }
+
+    /// Whether the current token start the parameters of an arrow function
+    fn match_arrow_function(&self) -> bool {
+        let mut lexer = self.lexer.clone();
+        let mut token = self.current_token;
+        if self.match_async()
+            && matches!(
+                self.peek().kind(),
+                TokenKind::Identifier | TokenKind::ParenOpen
+            )
+        {
+            token = lexer.next_token();
+        }
+
+        match token.kind() {
+            TokenKind::Identifier => lexer.next_token().kind() == TokenKind::Arrow,
+            TokenKind::ParenOpen => {
+                // Look for the `=>` after the matching `)`
+                let mut depth = 0;
+                loop {
+                    match lexer.next_token().kind() {
+                        TokenKind::ParenOpen => depth += 1,
+                        TokenKind::ParenClose if depth == 0 => break,
+                        TokenKind::ParenClose => depth -= 1,
+                        TokenKind::Eof => return false,
+                        _ => {}
+                    }
+                }
+                lexer.next_token().kind() == TokenKind::Arrow
+            }
+            _ => false,
+        }
+    }
+
+    /// `name`, `{ a, b: c }` or `[a, b]`
+    fn parse_binding_pattern(&mut self) -> Result<'s, BindingPattern> {
+        match self.current_token.kind() {
+            TokenKind::CurlyOpen => Ok(BindingPattern::Object(self.parse_object_pattern()?)),
+            TokenKind::BracketOpen => Ok(BindingPattern::Array(self.parse_array_pattern()?)),
+            _ => {
+                let ident = self.consume_token(TokenKind::Identifier)?;
+                Ok(BindingPattern::Identifier(Identifier::new(ident.value())))
+            }
+        }
+    }
+
+    /// A pattern with an optional `= default`
+    fn parse_binding_element(&mut self) -> Result<'s, BindingElement> {
+        let target = self.parse_binding_pattern()?;
+        let default = if self.match_token(TokenKind::Equals) {
+            self.consume();
+            Some(self.parse_expression(0, Associativity::Right)?)
+        } else {
+            None
+        };
+        Ok(BindingElement::new(target, default))
+    }
+
+    fn parse_object_pattern(&mut self) -> Result<'s, ObjectPattern> {
+        self.consume_token(TokenKind::CurlyOpen)?;
+
+        let mut properties = Vec::new();
+        let mut rest = None;
+        while !self.match_token(TokenKind::CurlyClose) {
+            if self.match_token(TokenKind::TripleDot) {
+                self.consume();
+                let ident = self.consume_token(TokenKind::Identifier)?;
+                rest = Some(BindingPattern::Identifier(Identifier::new(ident.value())));
+                break;
+            }
+
+            // `{ a = 1 }` is a shorthand for `{ a: a = 1 }`
+            let property = if self.match_token(TokenKind::Identifier)
+                && self.peek().kind() != TokenKind::Colon
+            {
+                let key = Expression::Identifier(Identifier::new(self.current_token.value()));
+                let element = self.parse_binding_element()?;
+                BindingProperty::new(key, element)
+            } else {
+                let key = self.parse_property_key()?;
+                self.consume_token(TokenKind::Colon)?;
+                BindingProperty::new(key, self.parse_binding_element()?)
+            };
+            properties.push(property);
+
+            if self.match_token(TokenKind::Comma) {
+                self.consume();
+            } else {
+                break;
+            }
+        }
+
+        self.consume_token(TokenKind::CurlyClose)?;
+        Ok(ObjectPattern::new(properties, rest))
+    }
+
+    /// Elements of `[a, , b = 1, ...rest]`, a comma without an element before it skips a value
+    fn parse_array_pattern(&mut self) -> Result<'s, ArrayPattern> {
+        self.consume_token(TokenKind::BracketOpen)?;
+
+        let mut elements = Vec::new();
+        let mut rest = None;
+        loop {
+            match self.current_token.kind() {
+                TokenKind::BracketClose => break,
+                TokenKind::Comma => {
+                    self.consume();
+                    elements.push(None);
+                }
+                TokenKind::TripleDot => {
+                    self.consume();
+                    rest = Some(self.parse_binding_pattern()?);
+                    break;
+                }
+                _ => {
+                    elements.push(Some(self.parse_binding_element()?));
+                    if self.match_token(TokenKind::Comma) {
+                        self.consume();
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+
+        self.consume_token(TokenKind::BracketClose)?;
+        Ok(ArrayPattern::new(elements, rest))
+    }
+
+    /// `` `a${b}c` ``, an empty string stands for the missing strings around substitutions.
+    ///
+    /// An invalid escape is a syntax error unless the template is `tagged`, its cooked string
+    /// is `undefined` then
+    fn parse_template_literal(&mut self, tagged: bool) -> Result<'s, TemplateLiteral> {
+        self.consume_token(TokenKind::TemplateLiteralStart)?;
+
+        let mut cooked = Vec::new();
+        let mut raw = Vec::new();
+        let mut substitutions = Vec::new();
+        loop {
+            if self.match_token(TokenKind::TemplateLiteralString) {
+                let token = self.consume();
+                match token.template_value() {
+                    Ok(value) => cooked.push(Some(value)),
+                    Err(_) if tagged => cooked.push(None),
+                    Err(error) => return Err(ParseError::invalid_escape(error, token)),
+                }
+                raw.push(token.value().to_string());
+            } else {
+                cooked.push(Some(String::new()));
+                raw.push(String::new());
+            }
+
+            match self.current_token.kind() {
+                TokenKind::TemplateLiteralEnd => {
+                    self.consume();
+                    break;
+                }
+                TokenKind::TemplateLiteralExprStart => {
+                    self.consume();
+                    substitutions.push(self.parse_expression(0, Associativity::Right)?);
+                    self.consume_token(TokenKind::TemplateLiteralExprEnd)?;
+                }
+                _ => return Err(ParseError::unexpected(self.current_token)),
+            }
+        }
+
+        Ok(TemplateLiteral::new(
+            TemplateStrings::new(cooked, raw),
+            substitutions,
         ))
     }
 
@@ -105,7 +881,11 @@ impl<'s> Parser<'s> {
                 break;
             }
 
-            block_statement.add_statement(self.parse_statement()?);
+            if self.match_declaration() {
+                block_statement.add_statement(self.parse_declaration()?);
+            } else {
+                block_statement.add_statement(self.parse_statement()?);
+            }
             self.consume_or_insert_semicolon();
         }
         self.consume_token(TokenKind::CurlyClose)?;
@@ -114,19 +894,281 @@ impl<'s> Parser<'s> {
     }
 
     fn parse_statement(&mut self) -> Result<'s, Statement> {
-        Ok(if self.match_variable_declaration() {
-            Statement::VariableDeclaration(self.parse_variable_declaration()?)
-        } else if self.match_expression() {
-            Statement::ExpressionStatement(self.parse_expression(0, Associativity::Right)?)
-        } else if self.current_token.kind() == TokenKind::Return {
+        Ok(match self.current_token.kind() {
+            TokenKind::Const | TokenKind::Let | TokenKind::Var => {
+                Statement::VariableDeclaration(self.parse_variable_declaration()?)
+            }
+            TokenKind::CurlyOpen => Statement::BlockStatement(self.parse_block_statement()?),
+            TokenKind::Return => {
+                self.consume();
+                // No value if the expression is not on the same line
+                if self.match_expression() && !self.current_token.trivia().contains('\n') {
+                    Statement::ReturnStatement(Some(
+                        self.parse_expression(0, Associativity::Right)?,
+                    ))
+                } else {
+                    Statement::ReturnStatement(None)
+                }
+            }
+            TokenKind::If => Statement::IfStatement(self.parse_if_statement()?),
+            TokenKind::While | TokenKind::Do => {
+                Statement::WhileStatement(self.parse_while_statement()?)
+            }
+            TokenKind::For => self.parse_for_statement()?,
+            TokenKind::Switch => Statement::SwitchStatement(self.parse_switch_statement()?),
+            TokenKind::Try => Statement::TryStatement(self.parse_try_statement()?),
+            TokenKind::Throw => {
+                let position = self.position();
+                self.consume();
+                // No line break allowed between `throw` and its expression
+                if self.current_token.trivia().contains('\n') {
+                    return Err(ParseError::unexpected(self.current_token));
+                }
+                let argument = self.parse_expression(0, Associativity::Right)?;
+                Statement::ThrowStatement(ThrowStatement::new(argument, position))
+            }
+            TokenKind::Break => {
+                self.consume();
+                Statement::BreakStatement(self.parse_jump_label())
+            }
+            TokenKind::Continue => {
+                self.consume();
+                Statement::ContinueStatement(self.parse_jump_label())
+            }
+            TokenKind::Debugger => {
+                self.consume();
+                Statement::DebuggerStatement
+            }
+            // The semicolon itself is consumed by the caller
+            TokenKind::Semicolon => Statement::EmptyStatement,
+            TokenKind::Identifier if self.peek().kind() == TokenKind::Colon => {
+                let label = Identifier::new(self.consume().value());
+                self.consume_token(TokenKind::Colon)?;
+                Statement::LabelledStatement(LabelledStatement::new(
+                    label,
+                    self.parse_sub_statement()?,
+                ))
+            }
+            _ if self.match_expression() => {
+                Statement::ExpressionStatement(self.parse_expression(0, Associativity::Right)?)
+            }
+            _ => return Err(ParseError::unexpected(self.current_token)),
+        })
+    }
+
+    /// Body of a control-flow statement, with its optional semicolon
+    fn parse_sub_statement(&mut self) -> Result<'s, Statement> {
+        let statement = self.parse_statement()?;
+        self.consume_or_insert_semicolon();
+        Ok(statement)
+    }
+
+    /// Label of a `break` or `continue`, it must be on the same line
+    fn parse_jump_label(&mut self) -> Option<Identifier> {
+        if self.match_token(TokenKind::Identifier) && !self.current_token.trivia().contains('\n') {
+            Some(Identifier::new(self.consume().value()))
+        } else {
+            None
+        }
+    }
+
+    fn parse_if_statement(&mut self) -> Result<'s, IfStatement> {
+        self.consume_token(TokenKind::If)?;
+        self.consume_token(TokenKind::ParenOpen)?;
+        let test = self.parse_expression(0, Associativity::Right)?;
+        self.consume_token(TokenKind::ParenClose)?;
+
+        let consequent = self.parse_sub_statement()?;
+        let alternate = if self.match_token(TokenKind::Else) {
             self.consume();
-            Statement::ReturnStatement(self.parse_expression(default(), default())?)
+            Some(self.parse_sub_statement()?)
         } else {
-            return Err(ParseError::unexpected(self.current_token));
-        })
+            None
+        };
+
+        Ok(IfStatement::new(test, consequent, alternate))
+    }
+
+    fn parse_while_statement(&mut self) -> Result<'s, WhileStatement> {
+        if self.match_token(TokenKind::Do) {
+            self.consume();
+            let body = self.parse_sub_statement()?;
+            self.consume_token(TokenKind::While)?;
+            self.consume_token(TokenKind::ParenOpen)?;
+            let test = self.parse_expression(0, Associativity::Right)?;
+            self.consume_token(TokenKind::ParenClose)?;
+
+            return Ok(WhileStatement::new(test, body, true));
+        }
+
+        self.consume_token(TokenKind::While)?;
+        self.consume_token(TokenKind::ParenOpen)?;
+        let test = self.parse_expression(0, Associativity::Right)?;
+        self.consume_token(TokenKind::ParenClose)?;
+        let body = self.parse_sub_statement()?;
+
+        Ok(WhileStatement::new(test, body, false))
+    }
+
+    /// `for (init; test; update)`, `for (x in obj)` and `for (x of iterable)`
+    fn parse_for_statement(&mut self) -> Result<'s, Statement> {
+        self.consume_token(TokenKind::For)?;
+        self.consume_token(TokenKind::ParenOpen)?;
+
+        let init = if self.match_variable_declaration() {
+            let kind = self.parse_declaration_kind();
+            let target = self.parse_binding_pattern()?;
+            if let Some(for_kind) = self.match_in_of() {
+                let binding = ForBinding::Declaration(kind, target);
+                return self.parse_for_in_statement(for_kind, binding);
+            }
+            Some(Statement::VariableDeclaration(
+                self.parse_variable_declarators(kind, target)?,
+            ))
+        } else if self.match_token(TokenKind::Semicolon) {
+            None
+        } else {
+            if let Some(kind) = self.match_for_in_of() {
+                let id = Identifier::new(self.consume_token(TokenKind::Identifier)?.value());
+                return self.parse_for_in_statement(kind, ForBinding::Identifier(id));
+            }
+            Some(Statement::ExpressionStatement(
+                self.parse_expression(0, Associativity::Right)?,
+            ))
+        };
+        self.consume_token(TokenKind::Semicolon)?;
+
+        let test = if self.match_token(TokenKind::Semicolon) {
+            None
+        } else {
+            Some(self.parse_expression(0, Associativity::Right)?)
+        };
+        self.consume_token(TokenKind::Semicolon)?;
+
+        let update = if self.match_token(TokenKind::ParenClose) {
+            None
+        } else {
+            Some(self.parse_expression(0, Associativity::Right)?)
+        };
+        self.consume_token(TokenKind::ParenClose)?;
+
+        let body = self.parse_sub_statement()?;
+        Ok(Statement::ForStatement(ForStatement::new(
+            init, test, update, body,
+        )))
+    }
+
+    /// An identifier followed by `in` or `of`
+    fn match_for_in_of(&self) -> Option<ForInKind> {
+        if !self.match_token(TokenKind::Identifier) {
+            return None;
+        }
+        in_of_kind(&self.peek())
+    }
+
+    /// `in` or `of` after the binding of a `for`
+    fn match_in_of(&self) -> Option<ForInKind> {
+        in_of_kind(&self.current_token)
+    }
+
+    fn parse_for_in_statement(
+        &mut self,
+        kind: ForInKind,
+        binding: ForBinding,
+    ) -> Result<'s, Statement> {
+        // `in` or `of`
+        self.consume();
+        let iterated = self.parse_expression(0, Associativity::Right)?;
+        self.consume_token(TokenKind::ParenClose)?;
+        let body = self.parse_sub_statement()?;
+
+        Ok(Statement::ForInStatement(ForInStatement::new(
+            kind, binding, iterated, body,
+        )))
+    }
+
+    fn parse_try_statement(&mut self) -> Result<'s, TryStatement> {
+        self.consume_token(TokenKind::Try)?;
+        let block = self.parse_block_statement()?;
+
+        let handler = if self.match_token(TokenKind::Catch) {
+            self.consume();
+            let param = if self.match_token(TokenKind::ParenOpen) {
+                self.consume();
+                let param = self.parse_binding_pattern()?;
+                self.consume_token(TokenKind::ParenClose)?;
+                Some(param)
+            } else {
+                None
+            };
+            Some(CatchClause::new(param, self.parse_block_statement()?))
+        } else {
+            None
+        };
+
+        let finalizer = if self.match_token(TokenKind::Finally) {
+            self.consume();
+            Some(self.parse_block_statement()?)
+        } else if handler.is_none() {
+            return Err(ParseError::expect(TokenKind::Catch, self.current_token));
+        } else {
+            None
+        };
+
+        Ok(TryStatement::new(block, handler, finalizer))
+    }
+
+    fn parse_switch_statement(&mut self) -> Result<'s, SwitchStatement> {
+        self.consume_token(TokenKind::Switch)?;
+        self.consume_token(TokenKind::ParenOpen)?;
+        let discriminant = self.parse_expression(0, Associativity::Right)?;
+        self.consume_token(TokenKind::ParenClose)?;
+        self.consume_token(TokenKind::CurlyOpen)?;
+
+        let mut cases = Vec::new();
+        let mut has_default = false;
+        while !self.match_token(TokenKind::CurlyClose) {
+            let test = match self.current_token.kind() {
+                TokenKind::Case => {
+                    self.consume();
+                    Some(self.parse_expression(0, Associativity::Right)?)
+                }
+                TokenKind::Default if !has_default => {
+                    self.consume();
+                    has_default = true;
+                    None
+                }
+                _ => return Err(ParseError::unexpected(self.current_token)),
+            };
+            self.consume_token(TokenKind::Colon)?;
+
+            let mut consequent = Vec::new();
+            while !matches!(
+                self.current_token.kind(),
+                TokenKind::Case | TokenKind::Default | TokenKind::CurlyClose | TokenKind::Eof
+            ) {
+                if self.match_declaration() {
+                    consequent.push(self.parse_declaration()?);
+                } else {
+                    consequent.push(self.parse_statement()?);
+                }
+                self.consume_or_insert_semicolon();
+            }
+
+            cases.push(SwitchCase::new(test, consequent));
+        }
+        self.consume_token(TokenKind::CurlyClose)?;
+
+        Ok(SwitchStatement::new(discriminant, cases))
     }
 
     fn parse_variable_declaration(&mut self) -> Result<'s, VariableDeclaration> {
+        let kind = self.parse_declaration_kind();
+        let target = self.parse_binding_pattern()?;
+        self.parse_variable_declarators(kind, target)
+    }
+
+    fn parse_declaration_kind(&mut self) -> DeclarationKind {
         let kind = match self.current_token.kind() {
             TokenKind::Const => DeclarationKind::Const,
             TokenKind::Let => DeclarationKind::Let,
@@ -134,17 +1176,33 @@ impl<'s> Parser<'s> {
             _ => unreachable!(),
         };
         self.consume();
+        kind
+    }
 
+    /// The declarators after the first target, which the caller already parsed
+    fn parse_variable_declarators(
+        &mut self,
+        kind: DeclarationKind,
+        first_target: BindingPattern,
+    ) -> Result<'s, VariableDeclaration> {
         let mut vars = VariableDeclaration::new(kind);
 
+        let mut target = first_target;
         loop {
-            let identifier = self.consume_token(TokenKind::Identifier)?;
-            self.consume_token(TokenKind::Equals)?;
-            let initializer = self.parse_expression(0, default())?;
-            vars.add(Identifier::new(identifier.value()), initializer);
+            // Patterns need a value to destructure
+            let needs_initializer =
+                kind == DeclarationKind::Const || !matches!(target, BindingPattern::Identifier(_));
+            let initializer = if needs_initializer || self.match_token(TokenKind::Equals) {
+                self.consume_token(TokenKind::Equals)?;
+                Some(self.parse_expression(0, Associativity::Right)?)
+            } else {
+                None
+            };
+            vars.add(target, initializer);
 
             if let TokenKind::Comma = self.current_token.kind() {
                 self.consume();
+                target = self.parse_binding_pattern()?;
             } else {
                 break;
             }
@@ -160,6 +1218,15 @@ impl<'s> Parser<'s> {
     ) -> Result<'s, Expression> {
         let mut expr = self.parse_primary_expression()?;
         while self.match_secondary_expression() {
+            // `a \n ++b` is two statements
+            if matches!(
+                self.current_token.kind(),
+                TokenKind::PlusPlus | TokenKind::MinusMinus
+            ) && self.current_token.trivia().contains('\n')
+            {
+                break;
+            }
+
             let new_precedence = token_precedence(&self.current_token.kind());
             if (new_precedence < min_precedence)
                 || (new_precedence == min_precedence && associativity == Associativity::Left)
@@ -177,37 +1244,167 @@ impl<'s> Parser<'s> {
         Ok(expr)
     }
 
+    /// The pattern is compiled here, an invalid one is an early error
+    fn parse_regexp_literal(&mut self) -> Result<'s, RegExpLiteral> {
+        let token = self.consume();
+        let value = token.value();
+        let pattern = &value[1..value.len() - 1];
+        let flags = if self.current_token.kind() == TokenKind::RegexFlags {
+            self.consume().value().to_string()
+        } else {
+            String::new()
+        };
+
+        match Regex::new(pattern, &flags) {
+            Ok(regex) => Ok(RegExpLiteral::new(regex)),
+            Err(error) => Err(ParseError::invalid_regexp(error, token)),
+        }
+    }
+
     fn parse_primary_expression(&mut self) -> Result<'s, Expression> {
+        if self.match_arrow_function() {
+            return Ok(Expression::FunctionExpression(self.parse_arrow_function()?));
+        }
+
         Ok(match self.current_token.kind() {
             TokenKind::ParenOpen => {
                 self.consume_token(TokenKind::ParenOpen)?;
-                let expr = self.parse_expression(0, default())?;
+                let expr = self.parse_expression(0, Associativity::Right)?;
                 self.consume_token(TokenKind::ParenClose)?;
                 expr
             }
             TokenKind::BoolLiteral => {
                 Expression::Literal(Literal::Boolean(self.consume().bool_value()))
             }
-            TokenKind::NullLiteral => Expression::Literal(Literal::Null),
+            TokenKind::NullLiteral => {
+                self.consume();
+                Expression::Literal(Literal::Null)
+            }
             TokenKind::StringLiteral => {
-                Expression::Literal(Literal::String(self.consume().string_value()))
+                Expression::Literal(Literal::String(self.consume_string()?))
             }
             TokenKind::NumericLiteral => {
                 Expression::Literal(Literal::number_from_str(self.consume().value()))
             }
+            TokenKind::BigIntLiteral => {
+                let token = self.consume();
+                match JsBigInt::from_string(token.value().trim_end_matches('n')) {
+                    Some(value) => Expression::Literal(Literal::BigInt(value)),
+                    None => return Err(ParseError::unexpected(token)),
+                }
+            }
+            TokenKind::Identifier if self.match_async_function() => {
+                Expression::FunctionExpression(self.parse_function_expression()?)
+            }
             TokenKind::Identifier => {
                 Expression::Identifier(Identifier::new(self.consume().value()))
             }
+            TokenKind::Yield if self.in_generator => {
+                Expression::YieldExpression(self.parse_yield_expression()?)
+            }
+            TokenKind::Await if self.in_async => {
+                self.consume();
+                let argument = self.parse_expression(17, Associativity::Right)?;
+                Expression::AwaitExpression(AwaitExpression::new(argument))
+            }
+            TokenKind::RegexLiteral => Expression::RegExpLiteral(self.parse_regexp_literal()?),
+            TokenKind::This => {
+                self.consume();
+                Expression::This
+            }
+            TokenKind::New => self.parse_new_expression()?,
+            TokenKind::Super => self.parse_super()?,
+            TokenKind::Class => {
+                Expression::ClassExpression(ClassExpression::new(self.parse_class(false, None)?))
+            }
+            TokenKind::Function => {
+                Expression::FunctionExpression(self.parse_function_expression()?)
+            }
+            TokenKind::TemplateLiteralStart => {
+                Expression::TemplateLiteral(self.parse_template_literal(false)?)
+            }
             TokenKind::CurlyOpen => {
                 self.consume_token(TokenKind::CurlyOpen)?;
                 let expr = self.parse_object_expression()?;
                 self.consume_token(TokenKind::CurlyClose)?;
                 Expression::ObjectExpression(expr)
             }
-            _ => todo!("Unhandle: {:?}", self.current_token),
+            TokenKind::BracketOpen => {
+                self.consume_token(TokenKind::BracketOpen)?;
+                let expr = self.parse_array_expression()?;
+                self.consume_token(TokenKind::BracketClose)?;
+                Expression::ArrayExpression(expr)
+            }
+            TokenKind::PlusPlus | TokenKind::MinusMinus => {
+                let is_increment = self.consume().kind() == TokenKind::PlusPlus;
+                let argument = self.parse_expression(18, Associativity::Right)?;
+                Expression::UpdateExpression(UpdateExpression::new(
+                    is_increment,
+                    true,
+                    self.update_target(argument)?,
+                ))
+            }
+            TokenKind::ExclamationMark
+            | TokenKind::Tilde
+            | TokenKind::Plus
+            | TokenKind::Minus
+            | TokenKind::Typeof
+            | TokenKind::Void
+            | TokenKind::Delete => {
+                let op = match self.consume().kind() {
+                    TokenKind::ExclamationMark => UnaryOp::Not,
+                    TokenKind::Tilde => UnaryOp::BitNot,
+                    TokenKind::Plus => UnaryOp::Plus,
+                    TokenKind::Minus => UnaryOp::Minus,
+                    TokenKind::Typeof => UnaryOp::Typeof,
+                    TokenKind::Void => UnaryOp::Void,
+                    _ => UnaryOp::Delete,
+                };
+                let token = self.current_token;
+                let argument = self.parse_expression(17, Associativity::Right)?;
+                // Private elements can't be deleted
+                if let Expression::MemberExpression(member) = &argument {
+                    if op == UnaryOp::Delete
+                        && !member.computed
+                        && matches!(member.property.as_ref(), Expression::PrivateName(_))
+                    {
+                        return Err(ParseError::unexpected(token));
+                    }
+                }
+                Expression::UnaryExpression(UnaryExpression::new(op, argument))
+            }
+            _ => return Err(ParseError::unexpected(self.current_token)),
         })
     }
 
+    /// `yield`, `yield value` or `yield* iterable`, the value is optional at the end of an
+    /// expression or a line
+    fn parse_yield_expression(&mut self) -> Result<'s, YieldExpression> {
+        self.consume_token(TokenKind::Yield)?;
+        let delegate = self.match_token(TokenKind::Asterisk);
+        if delegate {
+            self.consume();
+        }
+
+        let has_argument = delegate
+            || !(self.current_token.trivia().contains('\n')
+                || matches!(
+                    self.current_token.kind(),
+                    TokenKind::ParenClose
+                        | TokenKind::BracketClose
+                        | TokenKind::CurlyClose
+                        | TokenKind::Comma
+                        | TokenKind::Semicolon
+                        | TokenKind::Colon
+                        | TokenKind::Eof
+                ));
+        let argument = match has_argument {
+            true => Some(self.parse_expression(0, Associativity::Right)?),
+            false => None,
+        };
+        Ok(YieldExpression::new(argument, delegate))
+    }
+
     fn parse_secondary_expression(
         &mut self,
         lhs: Expression,
@@ -500,7 +1697,55 @@ impl<'s> Parser<'s> {
             TokenKind::ParenOpen => {
                 self.parse_call_expression(lhs, min_precedence, associativity)?
             }
-            _ => unreachable!(),
+            TokenKind::TemplateLiteralStart => {
+                let position = self.position();
+                let template = self.parse_template_literal(true)?;
+                Expression::TaggedTemplate(TaggedTemplate::new(lhs, template, position))
+            }
+            TokenKind::Period => {
+                self.consume();
+                if self.match_token(TokenKind::PrivateIdentifier) {
+                    let token = self.consume();
+                    let property = self.use_private_name(token)?;
+                    return Ok(Expression::MemberExpression(MemberExpression::new(
+                        lhs, property, false,
+                    )));
+                }
+                // Keywords are fine after a `.`, `a.if` is the property "if"
+                let token = self.consume();
+                if !is_identifier_name(token.value()) {
+                    return Err(ParseError::unexpected(token));
+                }
+                Expression::MemberExpression(MemberExpression::new(
+                    lhs,
+                    Expression::Identifier(Identifier::new(token.value())),
+                    false,
+                ))
+            }
+            TokenKind::BracketOpen => {
+                self.consume();
+                let property = self.parse_expression(0, Associativity::Right)?;
+                self.consume_token(TokenKind::BracketClose)?;
+                Expression::MemberExpression(MemberExpression::new(lhs, property, true))
+            }
+            TokenKind::PlusPlus | TokenKind::MinusMinus => {
+                let is_increment = self.consume().kind() == TokenKind::PlusPlus;
+                Expression::UpdateExpression(UpdateExpression::new(
+                    is_increment,
+                    false,
+                    self.update_target(lhs)?,
+                ))
+            }
+            TokenKind::QuestionMark => {
+                self.consume();
+                let consequent = self.parse_expression(0, Associativity::Right)?;
+                self.consume_token(TokenKind::Colon)?;
+                let alternate = self.parse_expression(min_precedence, associativity)?;
+                Expression::ConditionalExpression(ConditionalExpression::new(
+                    lhs, consequent, alternate,
+                ))
+            }
+            _ => return Err(ParseError::unexpected(self.current_token)),
         })
     }
 
@@ -532,7 +1777,10 @@ impl<'s> Parser<'s> {
         ));
         self.consume();
 
-        if matches!(lhs, Expression::Identifier(_),) {
+        if matches!(
+            lhs,
+            Expression::Identifier(_) | Expression::MemberExpression(_)
+        ) {
             Ok(Expression::BinaryOperation(BinaryOperation::assignment(
                 op,
                 lhs,
@@ -543,6 +1791,39 @@ impl<'s> Parser<'s> {
         }
     }
 
+    /// `++` and `--` apply to variables and members
+    fn update_target(&self, argument: Expression) -> Result<'s, Expression> {
+        match argument {
+            Expression::Identifier(_) | Expression::MemberExpression(_) => Ok(argument),
+            _ => Err(ParseError::unexpected(self.current_token)),
+        }
+    }
+
+    /// Elements of `[a, , b]`, a comma without an expression before it is a hole
+    fn parse_array_expression(&mut self) -> Result<'s, ArrayExpression> {
+        let mut elements = Vec::new();
+        loop {
+            match self.current_token.kind() {
+                TokenKind::BracketClose => return Ok(ArrayExpression::new(elements)),
+                TokenKind::Comma => {
+                    self.consume();
+                    elements.push(None);
+                }
+                _ => {
+                    elements.push(Some(self.parse_spreadable_expression()?));
+                    if self.current_token.kind() == TokenKind::Comma {
+                        self.consume();
+                    } else if self.current_token.kind() != TokenKind::BracketClose {
+                        return Err(ParseError::expect(
+                            TokenKind::BracketClose,
+                            self.current_token,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
     fn parse_object_expression(&mut self) -> Result<'s, ObjectExpression> {
         let mut properties = Vec::new();
         loop {
@@ -550,26 +1831,82 @@ impl<'s> Parser<'s> {
                 return Ok(ObjectExpression::new(properties));
             }
 
-            let key = self.consume_token(TokenKind::Identifier)?;
-            match self.current_token.kind() {
-                TokenKind::Colon => {
+            if self.current_token.kind() == TokenKind::TripleDot {
+                self.consume();
+                let source = self.parse_expression(0, Associativity::Right)?;
+                let kind = ObjectPropertyKind::Spread;
+                properties.push(ObjectProperty::new(source, false, None, kind, false));
+                if self.current_token.kind() == TokenKind::Comma {
                     self.consume();
-                    let value = self.parse_expression(0, default())?;
-                    properties.push(ObjectProperty::new(
-                        Expression::Identifier(Identifier::new(key.value())),
-                        Some(value),
-                        ObjectPropertyKind::KeyValue,
-                        false,
-                    ));
                 }
-                TokenKind::Comma => {
+                continue;
+            }
+
+            let (is_generator, is_async) = self.parse_method_prefix()?;
+
+            // `get` and `set` are only accessors when a key follow them, `{ get: 1 }` is fine
+            let mut kind = ObjectPropertyKind::KeyValue;
+            if !is_generator
+                && !is_async
+                && self.current_token.kind() == TokenKind::Identifier
+                && matches!(
+                    self.peek().kind(),
+                    TokenKind::Identifier
+                        | TokenKind::StringLiteral
+                        | TokenKind::NumericLiteral
+                        | TokenKind::BracketOpen
+                )
+            {
+                match self.current_token.value() {
+                    "get" => kind = ObjectPropertyKind::Getter,
+                    "set" => kind = ObjectPropertyKind::Setter,
+                    _ => {}
+                }
+                if kind != ObjectPropertyKind::KeyValue {
+                    self.consume();
+                }
+            }
+
+            // `[expression]` is a computed key, evaluated in the order of the properties
+            let computed = self.match_token(TokenKind::BracketOpen);
+            let key = if computed {
+                self.consume();
+                let key = self.parse_expression(0, Associativity::Right)?;
+                self.consume_token(TokenKind::BracketClose)?;
+                key
+            } else {
+                self.parse_property_key()?
+            };
+            match self.current_token.kind() {
+                TokenKind::ParenOpen => {
+                    let (params, body) = self.parse_function_rest(is_generator, is_async)?;
+                    let function = Function::new(None, params, body);
+                    let function =
+                        FunctionExpression::new(function.with_flags(is_generator, is_async));
                     properties.push(ObjectProperty::new(
-                        Expression::Identifier(Identifier::new(key.value())),
-                        None,
-                        ObjectPropertyKind::KeyValue,
-                        false,
+                        key,
+                        computed,
+                        Some(Expression::FunctionExpression(function)),
+                        kind,
+                        true,
                     ));
                 }
+                TokenKind::Colon
+                    if kind == ObjectPropertyKind::KeyValue && !is_generator && !is_async =>
+                {
+                    self.consume();
+                    let value = self.parse_expression(0, Associativity::Right)?;
+                    properties.push(ObjectProperty::new(key, computed, Some(value), kind, false));
+                }
+                TokenKind::Comma | TokenKind::CurlyClose
+                    if kind == ObjectPropertyKind::KeyValue
+                        && !is_generator
+                        && !is_async
+                        && !computed
+                        && matches!(key, Expression::Identifier(_)) =>
+                {
+                    properties.push(ObjectProperty::new(key, false, None, kind, false));
+                }
                 _ => return Err(ParseError::unexpected(self.current_token)),
             }
 
@@ -579,12 +1916,38 @@ impl<'s> Parser<'s> {
         }
     }
 
+    /// `name`, `'string'` or `1` before the `:` of a property, keywords are names there
+    fn parse_property_key(&mut self) -> Result<'s, Expression> {
+        match self.current_token.kind() {
+            TokenKind::StringLiteral => {
+                Ok(Expression::Literal(Literal::String(self.consume_string()?)))
+            }
+            TokenKind::NumericLiteral => Ok(Expression::Literal(Literal::number_from_str(
+                self.consume().value(),
+            ))),
+            _ if is_identifier_name(self.current_token.value()) => Ok(Expression::Identifier(
+                Identifier::new(self.consume().value()),
+            )),
+            _ => Err(ParseError::unexpected(self.current_token)),
+        }
+    }
+
     fn parse_call_expression(
         &mut self,
         lhs: Expression,
         min_precedence: u32,
         associativity: Associativity,
     ) -> Result<'s, Expression> {
+        let position = self.position();
+        let args = self.parse_arguments()?;
+
+        Ok(Expression::CallExpression(CallExpression::new(
+            lhs, args, position,
+        )))
+    }
+
+    /// `(a, ...b)` after a callee, `new` or `super`
+    fn parse_arguments(&mut self) -> Result<'s, Vec<Expression>> {
         self.consume_token(TokenKind::ParenOpen)?;
 
         let mut args = Vec::new();
@@ -593,7 +1956,7 @@ impl<'s> Parser<'s> {
                 break;
             }
 
-            args.push(self.parse_expression(0, default())?);
+            args.push(self.parse_spreadable_expression()?);
 
             if self.current_token.kind() == TokenKind::Comma {
                 self.consume();
@@ -603,8 +1966,77 @@ impl<'s> Parser<'s> {
         }
 
         self.consume_token(TokenKind::ParenClose)?;
+        Ok(args)
+    }
+
+    /// `new callee(args)`, the arguments belong to the innermost `new`, `new a.b()` constructs
+    /// `a.b` and `new (f())()` the result of `f()`.
+    ///
+    /// Or the `new.target` meta-property
+    fn parse_new_expression(&mut self) -> Result<'s, Expression> {
+        let position = self.position();
+        self.consume_token(TokenKind::New)?;
+        if self.match_token(TokenKind::Period) {
+            self.consume();
+            self.consume_contextual("target")?;
+            return Ok(Expression::NewTarget);
+        }
+
+        let mut callee = self.parse_primary_expression()?;
+        while matches!(
+            self.current_token.kind(),
+            TokenKind::Period | TokenKind::BracketOpen
+        ) {
+            callee = self.parse_secondary_expression(callee, 20, Associativity::Left)?;
+        }
+        let args = if self.match_token(TokenKind::ParenOpen) {
+            self.parse_arguments()?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Expression::NewExpression(NewExpression::new(
+            callee, args, position,
+        )))
+    }
+
+    /// `super(args)`, `super.name` or `super[key]`
+    fn parse_super(&mut self) -> Result<'s, Expression> {
+        let position = self.position();
+        let token = self.consume_token(TokenKind::Super)?;
+
+        Ok(match self.current_token.kind() {
+            TokenKind::ParenOpen => {
+                Expression::SuperCall(SuperCall::new(self.parse_arguments()?, position))
+            }
+            TokenKind::Period => {
+                self.consume();
+                let token = self.consume();
+                if !is_identifier_name(token.value()) {
+                    return Err(ParseError::unexpected(token));
+                }
+                let property = Expression::Identifier(Identifier::new(token.value()));
+                Expression::SuperProperty(SuperProperty::new(property, false))
+            }
+            TokenKind::BracketOpen => {
+                self.consume();
+                let property = self.parse_expression(0, Associativity::Right)?;
+                self.consume_token(TokenKind::BracketClose)?;
+                Expression::SuperProperty(SuperProperty::new(property, true))
+            }
+            _ => return Err(ParseError::unexpected(token)),
+        })
+    }
 
-        Ok(Expression::CallExpression(CallExpression::new(lhs, args)))
+    /// An argument or an array element, which may be `...iterable`
+    fn parse_spreadable_expression(&mut self) -> Result<'s, Expression> {
+        if self.match_token(TokenKind::TripleDot) {
+            self.consume();
+            let argument = self.parse_expression(0, Associativity::Right)?;
+            Ok(Expression::SpreadElement(SpreadElement::new(argument)))
+        } else {
+            self.parse_expression(0, Associativity::Right)
+        }
     }
 
     fn consume_or_insert_semicolon(&mut self) {
@@ -625,7 +2057,31 @@ impl<'s> Parser<'s> {
         matches!(
             self.current_token.kind(),
             TokenKind::Const | TokenKind::Class | TokenKind::Let | TokenKind::Function
-        )
+        ) || self.match_async_function()
+    }
+
+    /// `async` starting an async function or arrow function, a line break can't follow it
+    fn match_async(&self) -> bool {
+        self.match_token(TokenKind::Identifier)
+            && self.current_token.value() == "async"
+            && !self.peek().trivia().contains('\n')
+    }
+
+    /// `as` or `from`, which are identifiers everywhere else
+    fn match_contextual(&self, keyword: &str) -> bool {
+        self.match_token(TokenKind::Identifier) && self.current_token.value() == keyword
+    }
+
+    fn consume_contextual(&mut self, keyword: &str) -> Result<'s, Token<'s>> {
+        if self.match_contextual(keyword) {
+            Ok(self.consume())
+        } else {
+            Err(ParseError::unexpected(self.current_token))
+        }
+    }
+
+    fn match_async_function(&self) -> bool {
+        self.match_async() && self.peek().kind() == TokenKind::Function
     }
 
     fn match_variable_declaration(&self) -> bool {
@@ -664,6 +2120,7 @@ impl<'s> Parser<'s> {
                 TokenKind::BigIntLiteral
                     | TokenKind::BoolLiteral
                     | TokenKind::BracketOpen
+                    | TokenKind::Class
                     | TokenKind::CurlyOpen
                     | TokenKind::Function
                     | TokenKind::Identifier
@@ -676,6 +2133,8 @@ impl<'s> Parser<'s> {
                     | TokenKind::Super
                     | TokenKind::TemplateLiteralStart
                     | TokenKind::This
+                    | TokenKind::Yield
+                    | TokenKind::Await
             )
     }
 
@@ -719,6 +2178,7 @@ impl<'s> Parser<'s> {
                 | TokenKind::LessThan
                 | TokenKind::LessThanEquals
                 | TokenKind::ParenOpen
+                | TokenKind::TemplateLiteralStart
                 | TokenKind::Period
                 | TokenKind::BracketOpen
                 | TokenKind::PlusPlus
@@ -755,6 +2215,26 @@ impl<'s> Parser<'s> {
         }
     }
 
+    /// Value of the string literal at the current token
+    fn consume_string(&mut self) -> Result<'s, String> {
+        let token = self.consume_token(TokenKind::StringLiteral)?;
+        token
+            .string_value()
+            .map_err(|error| ParseError::invalid_escape(error, token))
+    }
+
+    fn position(&self) -> Position {
+        Position::new(
+            self.current_token.line_number(),
+            self.current_token.line_column(),
+        )
+    }
+
+    /// Token after the current one
+    fn peek(&self) -> Token<'s> {
+        self.lexer.clone().next_token()
+    }
+
     fn consume(&mut self) -> Token<'s> {
         let old_token = self.current_token;
         self.current_token = self.lexer.next_token();
@@ -762,6 +2242,55 @@ impl<'s> Parser<'s> {
     }
 }
 
+/// Names an exported declaration binds
+fn declaration_names(statement: &Statement) -> Vec<String> {
+    match statement {
+        Statement::VariableDeclaration(declaration) => declaration
+            .bound_names()
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+        Statement::FunctionDeclaration(declaration) => declaration
+            .function()
+            .name()
+            .map(|name| name.name().clone())
+            .into_iter()
+            .collect(),
+        Statement::ClassDeclaration(declaration) => vec![declaration.name().to_string()],
+        _ => Vec::new(),
+    }
+}
+
+/// What `parse_class_element` found, the constructor is kept apart from the other elements
+enum ClassBodyElement<'s> {
+    Element(ClassElement),
+    Constructor(Token<'s>, Function),
+}
+
+/// Constructor of a class without one, a derived class passes its arguments to the parent
+fn default_constructor(name: Option<&str>, is_derived: bool) -> Function {
+    let name = name.map(Identifier::new);
+    if !is_derived {
+        let params = FormalParameters::default();
+        return Function::with_kind(
+            name,
+            params,
+            BlockStatement::new(),
+            FunctionKind::BaseConstructor,
+        );
+    }
+
+    let args = || Identifier::new("args");
+    let params = FormalParameters::new(Vec::new(), Some(BindingPattern::Identifier(args())));
+    let spread = Expression::SpreadElement(SpreadElement::new(Expression::Identifier(args())));
+    let super_call = SuperCall::new(vec![spread], Position::default());
+    let mut body = BlockStatement::new();
+    body.add_statement(Statement::ExpressionStatement(Expression::SuperCall(
+        super_call,
+    )));
+    Function::with_kind(name, params, body, FunctionKind::DerivedConstructor)
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Associativity {
     Left,
@@ -779,6 +2308,7 @@ fn operator_associativity(tk: &TokenKind) -> Associativity {
         TokenKind::Period
         | TokenKind::BracketOpen
         | TokenKind::ParenOpen
+        | TokenKind::TemplateLiteralStart
         | TokenKind::QuestionMarkPeriod
         | TokenKind::Asterisk
         | TokenKind::Slash
@@ -813,11 +2343,30 @@ fn operator_associativity(tk: &TokenKind) -> Associativity {
     }
 }
 
+/// Kind of `for` loop `token` makes
+fn in_of_kind(token: &Token) -> Option<ForInKind> {
+    match token.kind() {
+        TokenKind::In => Some(ForInKind::In),
+        TokenKind::Identifier if token.value() == "of" => Some(ForInKind::Of),
+        _ => None,
+    }
+}
+
+/// Identifiers and keywords, what can follow a `.`
+fn is_identifier_name(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .enumerate()
+            .all(|(i, c)| c == '_' || c == '$' || c.is_alphabetic() || (i > 0 && c.is_numeric()))
+}
+
 fn token_precedence(tk: &TokenKind) -> u32 {
     match tk {
         TokenKind::Period
         | TokenKind::BracketOpen
         | TokenKind::ParenOpen
+        | TokenKind::TemplateLiteralStart
         | TokenKind::QuestionMarkPeriod => 20,
 
         TokenKind::New => 19,
@@ -1,3 +1,5 @@
+use std::{fmt, str::Chars};
+
 #[derive(Debug, Clone, Copy)]
 pub struct Token<'l> {
     kind: TokenKind,
@@ -45,8 +47,19 @@ impl<'l> Token<'l> {
         self.value.eq("true")
     }
 
-    pub fn string_value(&self) -> String {
-        String::from(self.value)
+    /// Content of a string literal, without the quotes and with the escapes resolved
+    pub fn string_value(&self) -> Result<String, InvalidEscape> {
+        assert_eq!(self.kind, TokenKind::StringLiteral);
+        let mut chars = self.value.chars();
+        chars.next();
+        chars.next_back();
+        unescape(chars.as_str(), false)
+    }
+
+    /// Cooked value of a string between the substitutions of a template
+    pub fn template_value(&self) -> Result<String, InvalidEscape> {
+        assert_eq!(self.kind, TokenKind::TemplateLiteralString);
+        unescape(self.value, true)
     }
 
     pub fn trivia(&self) -> &str {
@@ -230,6 +243,8 @@ pub enum TokenKind {
     UnsignedShiftRightEquals,
 
     Identifier,
+    /// `#name` of a private class element, the value includes the `#`
+    PrivateIdentifier,
 
     BigIntLiteral,
     NumericLiteral,
@@ -240,7 +255,142 @@ pub enum TokenKind {
     TemplateLiteralEnd,
     TemplateLiteralStart,
     TemplateLiteralString,
+    UnterminatedBlockComment,
     UnterminatedRegexLiteral,
     UnterminatedStringLiteral,
     UnterminatedTemplateLiteral,
 }
+
+/// Escape sequence a string literal or template can't have
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidEscape {
+    /// `\\x` without two hex digits
+    Hexadecimal,
+    /// `\\u` without four hex digits or a code point in braces
+    Unicode,
+    /// Legacy octal escapes, which templates don't have
+    Octal,
+}
+
+impl fmt::Display for InvalidEscape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            InvalidEscape::Hexadecimal => "Invalid hexadecimal escape sequence",
+            InvalidEscape::Unicode => "Invalid Unicode escape sequence",
+            InvalidEscape::Octal => "Octal escape sequences are not allowed in template strings",
+        })
+    }
+}
+
+/// Replace the escape sequences of a string literal or template.
+///
+/// Surrogates that aren't part of a pair become U+FFFD as Rust strings can't hold them
+fn unescape(value: &str, template: bool) -> Result<String, InvalidEscape> {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some('r') => result.push('\r'),
+            Some('b') => result.push('\u{8}'),
+            Some('f') => result.push('\u{c}'),
+            Some('v') => result.push('\u{b}'),
+            Some('x') => {
+                let code = hex_digits(&mut chars, 2).ok_or(InvalidEscape::Hexadecimal)?;
+                result.push(char::from(code as u8));
+            }
+            Some('u') => {
+                let code = unicode_escape(&mut chars).ok_or(InvalidEscape::Unicode)?;
+                result.push(code_point(code, &mut chars));
+            }
+            Some('0') if !chars.clone().next().is_some_and(|c| c.is_ascii_digit()) => {
+                result.push('\0')
+            }
+            Some('0'..='9') if template => return Err(InvalidEscape::Octal),
+            // Legacy octal escapes of sloppy mode
+            Some(digit @ '0'..='7') => {
+                let mut code = digit.to_digit(8).unwrap();
+                let max_digits = if digit <= '3' { 3 } else { 2 };
+                for _ in 1..max_digits {
+                    match chars.clone().next().and_then(|c| c.to_digit(8)) {
+                        Some(digit) => {
+                            code = code * 8 + digit;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                result.push(char::from(code as u8));
+            }
+            // Line continuations
+            Some('\r') => {
+                if chars.clone().next() == Some('\n') {
+                    chars.next();
+                }
+            }
+            Some('\n' | '\u{2028}' | '\u{2029}') => {}
+            Some(c) => result.push(c),
+            None => {}
+        }
+    }
+
+    Ok(result)
+}
+
+/// Exactly `count` hex digits, `chars` is left as is if there aren't
+fn hex_digits(chars: &mut Chars, count: usize) -> Option<u32> {
+    let mut ahead = chars.clone();
+    let mut code = 0;
+    for _ in 0..count {
+        code = code * 16 + ahead.next()?.to_digit(16)?;
+    }
+    *chars = ahead;
+    Some(code)
+}
+
+/// `XXXX` or `{X...}` after a `\\u`
+fn unicode_escape(chars: &mut Chars) -> Option<u32> {
+    if chars.clone().next() != Some('{') {
+        return hex_digits(chars, 4);
+    }
+
+    let mut ahead = chars.clone();
+    ahead.next();
+    let mut code: u32 = 0;
+    let mut digits = 0;
+    loop {
+        match ahead.next()? {
+            '}' if digits > 0 => break,
+            c => {
+                code = code.checked_mul(16)? + c.to_digit(16)?;
+                digits += 1;
+            }
+        }
+    }
+    if code > 0x10FFFF {
+        return None;
+    }
+    *chars = ahead;
+    Some(code)
+}
+
+/// The character of an escaped code unit or point, a high surrogate is paired with the low
+/// one escaped right after it
+fn code_point(code: u32, chars: &mut Chars) -> char {
+    if (0xD800..0xDC00).contains(&code) {
+        let mut ahead = chars.clone();
+        if ahead.next() == Some('\\') && ahead.next() == Some('u') {
+            if let Some(low @ 0xDC00..=0xDFFF) = unicode_escape(&mut ahead) {
+                *chars = ahead;
+                return char::from_u32(0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00)).unwrap();
+            }
+        }
+    }
+    char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+}
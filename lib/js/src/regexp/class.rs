@@ -0,0 +1,136 @@
+/// Set of code points, `[a-z\d]`, `\w` or `.`
+#[derive(Debug, Clone, Default)]
+pub(super) struct CharClass {
+    ranges: Vec<(u32, u32)>,
+    negated: bool,
+}
+
+const MAX_CODE_POINT: u32 = 0x10FFFF;
+
+const DIGIT: &[(u32, u32)] = &[(0x30, 0x39)];
+
+const WORD: &[(u32, u32)] = &[(0x30, 0x39), (0x41, 0x5A), (0x5F, 0x5F), (0x61, 0x7A)];
+
+/// `WhiteSpace` and `LineTerminator`
+const SPACE: &[(u32, u32)] = &[
+    (0x09, 0x0D),
+    (0x20, 0x20),
+    (0xA0, 0xA0),
+    (0x1680, 0x1680),
+    (0x2000, 0x200A),
+    (0x2028, 0x2029),
+    (0x202F, 0x202F),
+    (0x205F, 0x205F),
+    (0x3000, 0x3000),
+    (0xFEFF, 0xFEFF),
+];
+
+impl CharClass {
+    pub fn new(negated: bool) -> CharClass {
+        CharClass {
+            ranges: Vec::new(),
+            negated,
+        }
+    }
+
+    /// `\d`, `\w` or `\s`, and their complement when the letter is upper case
+    pub fn escape(letter: char) -> Option<CharClass> {
+        let ranges = match letter.to_ascii_lowercase() {
+            'd' => DIGIT,
+            'w' => WORD,
+            's' => SPACE,
+            _ => return None,
+        };
+        let mut class = CharClass::new(false);
+        class.ranges.extend_from_slice(ranges);
+        if letter.is_ascii_uppercase() {
+            class = class.complement();
+        }
+        Some(class)
+    }
+
+    pub fn add_range(&mut self, start: u32, end: u32) {
+        self.ranges.push((start, end));
+    }
+
+    /// Add everything `other` matches, a class inside another one
+    pub fn add_class(&mut self, other: &CharClass) {
+        let other = if other.negated {
+            other.complement()
+        } else {
+            other.clone()
+        };
+        self.ranges.extend(other.ranges);
+    }
+
+    /// The same set without negation
+    fn complement(&self) -> CharClass {
+        let mut ranges = self.ranges.clone();
+        ranges.sort_unstable();
+
+        let mut complement = CharClass::new(false);
+        let mut next = 0;
+        for (start, end) in ranges {
+            if start > next {
+                complement.add_range(next, start - 1);
+            }
+            next = next.max(end + 1);
+        }
+        if next <= MAX_CODE_POINT {
+            complement.add_range(next, MAX_CODE_POINT);
+        }
+        complement
+    }
+
+    fn contains(&self, c: u32) -> bool {
+        self.ranges
+            .iter()
+            .any(|(start, end)| (*start..=*end).contains(&c))
+    }
+
+    /// Whether it matches `c`, ignoring case means any case variant of `c` is enough
+    pub fn matches(&self, c: u32, ignore_case: bool) -> bool {
+        let found = self.contains(c)
+            || (ignore_case && case_variants(c).iter().flatten().any(|v| self.contains(*v)));
+        found != self.negated
+    }
+}
+
+/// Lower and upper case of `c`, when they are a single code point
+fn case_variants(c: u32) -> [Option<u32>; 2] {
+    let c = match char::from_u32(c) {
+        Some(c) => c,
+        None => return [None, None],
+    };
+    [single(c.to_lowercase()), single(c.to_uppercase())]
+}
+
+fn single(mut chars: impl Iterator<Item = char>) -> Option<u32> {
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c as u32),
+        _ => None,
+    }
+}
+
+/// `Canonicalize`, the value two code points have in common when they only differ by case.
+///
+/// Without the unicode flag non ASCII letters never become ASCII ones, like `ſ` and `s`
+pub(super) fn canonicalize(c: u32, unicode: bool) -> u32 {
+    let [lower, upper] = case_variants(c);
+    if unicode {
+        return lower.unwrap_or(c);
+    }
+    match upper {
+        Some(upper) if c >= 128 && upper < 128 => c,
+        Some(upper) => upper,
+        None => c,
+    }
+}
+
+pub(super) fn is_line_terminator(c: u32) -> bool {
+    matches!(c, 0x0A | 0x0D | 0x2028 | 0x2029)
+}
+
+pub(super) fn is_word_char(c: u32) -> bool {
+    WORD.iter().any(|(start, end)| (*start..=*end).contains(&c))
+}
@@ -0,0 +1,322 @@
+use super::{
+    class::{canonicalize, is_line_terminator, is_word_char},
+    combine_surrogates, is_lead_surrogate, is_trail_surrogate,
+    parser::{Node, Repeat},
+    Captures, Flags,
+};
+
+/// What to match after a node, given where the node ended
+type Continuation<'k, 'i> = &'k mut dyn FnMut(&mut Matcher<'i>, usize) -> bool;
+
+/// Backtracking in continuation passing style, like the semantics of the spec: each node
+/// succeeds when what follows it does, and tries its other choices otherwise
+pub(super) struct Matcher<'i> {
+    input: &'i [u16],
+    flags: Flags,
+    /// Start and end of each group
+    captures: Vec<Option<(usize, usize)>>,
+}
+
+impl<'i> Matcher<'i> {
+    pub fn new(input: &'i [u16], flags: Flags, group_count: usize) -> Matcher<'i> {
+        Matcher {
+            input,
+            flags,
+            captures: vec![None; group_count + 1],
+        }
+    }
+
+    pub fn run(mut self, node: &Node, start: usize) -> Option<Captures> {
+        let mut end = None;
+        if !self.matches(node, start, true, &mut |_, position| {
+            end = Some(position);
+            true
+        }) {
+            return None;
+        }
+
+        self.captures[0] = Some((start, end.unwrap()));
+        let captures = self.captures.into_iter();
+        Some(
+            captures
+                .map(|capture| capture.map(|(start, end)| start..end))
+                .collect(),
+        )
+    }
+
+    /// Match `node` at `position`, backwards inside of a lookbehind
+    fn matches(
+        &mut self,
+        node: &Node,
+        position: usize,
+        forward: bool,
+        k: Continuation<'_, 'i>,
+    ) -> bool {
+        match node {
+            Node::Empty => k(self, position),
+            Node::Char(_) | Node::Any | Node::Class(_) => match self.read(position, forward) {
+                Some((c, next)) if self.char_matches(node, c) => k(self, next),
+                _ => false,
+            },
+            Node::LineStart => {
+                let at_start = position == 0
+                    || (self.flags.contains(Flags::MULTILINE)
+                        && is_line_terminator(self.input[position - 1] as u32));
+                at_start && k(self, position)
+            }
+            Node::LineEnd => {
+                let at_end = position == self.input.len()
+                    || (self.flags.contains(Flags::MULTILINE)
+                        && is_line_terminator(self.input[position] as u32));
+                at_end && k(self, position)
+            }
+            Node::WordBoundary { negated } => {
+                let before = position > 0 && is_word_char(self.input[position - 1] as u32);
+                let after =
+                    position < self.input.len() && is_word_char(self.input[position] as u32);
+                ((before != after) != *negated) && k(self, position)
+            }
+            Node::Capture { index, node } => self.capture(*index, node, position, forward, k),
+            Node::Look {
+                node,
+                behind,
+                negated,
+            } => self.look(node, *behind, *negated, position, k),
+            Node::BackReference(index) => self.back_reference(*index, position, forward, k),
+            Node::Repeat(repeat) => {
+                let single = matches!(*repeat.node, Node::Char(_) | Node::Any | Node::Class(_));
+                if single {
+                    self.repeat_single(repeat, position, forward, k)
+                } else {
+                    self.repeat(repeat, position, forward, 0, k)
+                }
+            }
+            Node::Sequence(nodes) => self.sequence(nodes, position, forward, k),
+            Node::Alternation(alternatives) => alternatives
+                .iter()
+                .any(|alternative| self.matches(alternative, position, forward, &mut *k)),
+        }
+    }
+
+    /// The code point at `position`, or before it when going backward, with where it ends
+    fn read(&self, position: usize, forward: bool) -> Option<(u32, usize)> {
+        let unicode = self.flags.contains(Flags::UNICODE);
+        if forward {
+            let unit = *self.input.get(position)?;
+            match self.input.get(position + 1) {
+                Some(trail) if unicode && is_lead_surrogate(unit) && is_trail_surrogate(*trail) => {
+                    Some((combine_surrogates(unit, *trail), position + 2))
+                }
+                _ => Some((unit as u32, position + 1)),
+            }
+        } else {
+            let unit = *self.input.get(position.checked_sub(1)?)?;
+            match position.checked_sub(2).map(|lead| self.input[lead]) {
+                Some(lead) if unicode && is_trail_surrogate(unit) && is_lead_surrogate(lead) => {
+                    Some((combine_surrogates(lead, unit), position - 2))
+                }
+                _ => Some((unit as u32, position - 1)),
+            }
+        }
+    }
+
+    fn char_matches(&self, node: &Node, c: u32) -> bool {
+        let ignore_case = self.flags.contains(Flags::IGNORE_CASE);
+        let unicode = self.flags.contains(Flags::UNICODE);
+        match node {
+            Node::Char(expected) => {
+                c == *expected
+                    || (ignore_case && canonicalize(c, unicode) == canonicalize(*expected, unicode))
+            }
+            Node::Any => self.flags.contains(Flags::DOT_ALL) || !is_line_terminator(c),
+            Node::Class(class) => class.matches(c, ignore_case),
+            _ => unreachable!(),
+        }
+    }
+
+    fn sequence(
+        &mut self,
+        nodes: &[Node],
+        position: usize,
+        forward: bool,
+        k: Continuation<'_, 'i>,
+    ) -> bool {
+        let (node, rest) = match (forward, nodes) {
+            (_, []) => return k(self, position),
+            (true, [first, rest @ ..]) => (first, rest),
+            (false, [rest @ .., last]) => (last, rest),
+        };
+        self.matches(node, position, forward, &mut |matcher, next| {
+            matcher.sequence(rest, next, forward, &mut *k)
+        })
+    }
+
+    fn capture(
+        &mut self,
+        index: usize,
+        node: &Node,
+        position: usize,
+        forward: bool,
+        k: Continuation<'_, 'i>,
+    ) -> bool {
+        let saved = self.captures[index];
+        let matched = self.matches(node, position, forward, &mut |matcher, end| {
+            let inner = matcher.captures[index];
+            let range = if forward {
+                (position, end)
+            } else {
+                (end, position)
+            };
+            matcher.captures[index] = Some(range);
+            if k(matcher, end) {
+                return true;
+            }
+            matcher.captures[index] = inner;
+            false
+        });
+        if !matched {
+            self.captures[index] = saved;
+        }
+        matched
+    }
+
+    /// Lookarounds are atomic, only their first match counts
+    fn look(
+        &mut self,
+        node: &Node,
+        behind: bool,
+        negated: bool,
+        position: usize,
+        k: Continuation<'_, 'i>,
+    ) -> bool {
+        let saved = self.captures.clone();
+        let matched = self.matches(node, position, !behind, &mut |_, _| true);
+        if matched == negated {
+            self.captures = saved;
+            return false;
+        }
+        if negated {
+            // Groups inside a negative lookaround never take part in the match
+            self.captures = saved;
+            return k(self, position);
+        }
+        if k(self, position) {
+            return true;
+        }
+        self.captures = saved;
+        false
+    }
+
+    fn back_reference(
+        &mut self,
+        index: usize,
+        position: usize,
+        forward: bool,
+        k: Continuation<'_, 'i>,
+    ) -> bool {
+        let (start, end) = match self.captures[index] {
+            Some(range) => range,
+            None => return k(self, position),
+        };
+        let length = end - start;
+        let from = if forward {
+            if position + length > self.input.len() {
+                return false;
+            }
+            position
+        } else {
+            match position.checked_sub(length) {
+                Some(from) => from,
+                None => return false,
+            }
+        };
+
+        let ignore_case = self.flags.contains(Flags::IGNORE_CASE);
+        let unicode = self.flags.contains(Flags::UNICODE);
+        let same = (0..length).all(|i| {
+            let (a, b) = (self.input[start + i] as u32, self.input[from + i] as u32);
+            a == b || (ignore_case && canonicalize(a, unicode) == canonicalize(b, unicode))
+        });
+        same && k(self, if forward { from + length } else { from })
+    }
+
+    fn repeat(
+        &mut self,
+        repeat: &Repeat,
+        position: usize,
+        forward: bool,
+        count: usize,
+        k: Continuation<'_, 'i>,
+    ) -> bool {
+        if repeat.max == Some(count) {
+            return k(self, position);
+        }
+        let done = count >= repeat.min;
+        if done && !repeat.greedy && k(self, position) {
+            return true;
+        }
+
+        let saved = self.captures[repeat.groups.clone()].to_vec();
+        for capture in &mut self.captures[repeat.groups.clone()] {
+            *capture = None;
+        }
+        let matched = self.matches(&repeat.node, position, forward, &mut |matcher, next| {
+            // An empty iteration can't go on forever
+            if done && next == position {
+                return false;
+            }
+            matcher.repeat(repeat, next, forward, count + 1, &mut *k)
+        });
+        if matched {
+            return true;
+        }
+        self.captures[repeat.groups.clone()].copy_from_slice(&saved);
+
+        done && repeat.greedy && k(self, position)
+    }
+
+    /// Repeating a single character needs no recursion, `.*` over a long string would
+    /// otherwise go as deep as the string is long
+    fn repeat_single(
+        &mut self,
+        repeat: &Repeat,
+        position: usize,
+        forward: bool,
+        k: Continuation<'_, 'i>,
+    ) -> bool {
+        let max = repeat.max.unwrap_or(usize::MAX);
+        let next = |matcher: &Matcher, position: usize| match matcher.read(position, forward) {
+            Some((c, next)) if matcher.char_matches(&repeat.node, c) => Some(next),
+            _ => None,
+        };
+
+        if !repeat.greedy {
+            let mut position = position;
+            let mut count = 0;
+            loop {
+                if count >= repeat.min && k(self, position) {
+                    return true;
+                }
+                match next(self, position) {
+                    Some(after) if count < max => position = after,
+                    _ => return false,
+                }
+                count += 1;
+            }
+        }
+
+        let mut positions = vec![position];
+        while positions.len() <= max {
+            match next(self, *positions.last().unwrap()) {
+                Some(after) => positions.push(after),
+                None => break,
+            }
+        }
+        positions
+            .into_iter()
+            .enumerate()
+            .rev()
+            .take_while(|(count, _)| *count >= repeat.min)
+            .any(|(_, position)| k(self, position))
+    }
+}
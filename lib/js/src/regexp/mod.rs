@@ -0,0 +1,191 @@
+//! Backtracking engine for the ECMAScript regular expressions, it matches UTF-16 code units
+//! like JS strings are indexed
+mod class;
+mod matcher;
+mod parser;
+
+use std::{fmt, ops::Range};
+
+use bitflags::bitflags;
+use matcher::Matcher;
+use parser::{Node, PatternParser};
+
+bitflags! {
+    pub struct Flags: u8 {
+        const GLOBAL = 1 << 0;
+        const IGNORE_CASE = 1 << 1;
+        const MULTILINE = 1 << 2;
+        const DOT_ALL = 1 << 3;
+        const UNICODE = 1 << 4;
+        const STICKY = 1 << 5;
+    }
+}
+
+impl Flags {
+    /// The letters in the order `RegExp.prototype.flags` gives them
+    const LETTERS: [(char, Flags); 6] = [
+        ('g', Flags::GLOBAL),
+        ('i', Flags::IGNORE_CASE),
+        ('m', Flags::MULTILINE),
+        ('s', Flags::DOT_ALL),
+        ('u', Flags::UNICODE),
+        ('y', Flags::STICKY),
+    ];
+
+    /// Flags of a literal or of the `RegExp` constructor, each letter at most once
+    pub fn parse(flags: &str) -> Option<Flags> {
+        let mut result = Flags::empty();
+        for c in flags.chars() {
+            let (_, flag) = Flags::LETTERS.iter().find(|(letter, _)| *letter == c)?;
+            if result.contains(*flag) {
+                return None;
+            }
+            result |= *flag;
+        }
+        Some(result)
+    }
+}
+
+impl fmt::Display for Flags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (letter, flag) in Flags::LETTERS.iter() {
+            if self.contains(*flag) {
+                write!(f, "{}", letter)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Early error of a pattern, reported as a `SyntaxError`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegexError {
+    pattern: String,
+    message: String,
+}
+
+impl RegexError {
+    fn new(pattern: &str, message: &str) -> RegexError {
+        RegexError {
+            pattern: pattern.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for RegexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid regular expression: /{}/: {}",
+            self.pattern, self.message
+        )
+    }
+}
+
+/// Ranges of code units of the match, `captures[0]` is the whole match and a group that didn't
+/// take part in it is `None`
+pub type Captures = Vec<Option<Range<usize>>>;
+
+/// A compiled pattern with its flags
+#[derive(Debug)]
+pub struct Regex {
+    source: String,
+    flags: Flags,
+    node: Node,
+    group_count: usize,
+    group_names: Vec<(String, usize)>,
+}
+
+impl Regex {
+    pub fn new(source: &str, flags: &str) -> Result<Regex, RegexError> {
+        let flags = Flags::parse(flags).ok_or_else(|| {
+            RegexError::new(
+                source,
+                &format!("Invalid flags supplied to RegExp '{}'", flags),
+            )
+        })?;
+        let parser = PatternParser::new(source, flags.contains(Flags::UNICODE));
+        let (node, group_count, group_names) = parser
+            .parse()
+            .map_err(|message| RegexError::new(source, message))?;
+
+        Ok(Regex {
+            source: source.into(),
+            flags,
+            node,
+            group_count,
+            group_names,
+        })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn flags(&self) -> Flags {
+        self.flags
+    }
+
+    /// Number of capturing groups, without the whole match
+    pub fn group_count(&self) -> usize {
+        self.group_count
+    }
+
+    /// Named groups with their index, in the order they appear in the pattern
+    pub fn group_names(&self) -> &[(String, usize)] {
+        &self.group_names
+    }
+
+    /// Match starting exactly at `start`, as the sticky flag does
+    pub fn match_at(&self, input: &[u16], start: usize) -> Option<Captures> {
+        if start > input.len() {
+            return None;
+        }
+        Matcher::new(input, self.flags, self.group_count).run(&self.node, start)
+    }
+
+    /// First match starting at `start` or after it
+    pub fn find(&self, input: &[u16], start: usize) -> Option<Captures> {
+        let mut start = start;
+        while start <= input.len() {
+            if let Some(captures) = self.match_at(input, start) {
+                return Some(captures);
+            }
+            start = self.advance(input, start);
+        }
+        None
+    }
+
+    pub fn advance(&self, input: &[u16], index: usize) -> usize {
+        advance_string_index(input, index, self.flags.contains(Flags::UNICODE))
+    }
+}
+
+/// `AdvanceStringIndex`, a surrogate pair isn't split when `unicode` is set
+pub fn advance_string_index(input: &[u16], index: usize, unicode: bool) -> usize {
+    let is_pair = unicode
+        && input
+            .get(index)
+            .is_some_and(|unit| is_lead_surrogate(*unit))
+        && input
+            .get(index + 1)
+            .is_some_and(|unit| is_trail_surrogate(*unit));
+    if is_pair {
+        index + 2
+    } else {
+        index + 1
+    }
+}
+
+fn is_lead_surrogate(unit: u16) -> bool {
+    (0xD800..=0xDBFF).contains(&unit)
+}
+
+fn is_trail_surrogate(unit: u16) -> bool {
+    (0xDC00..=0xDFFF).contains(&unit)
+}
+
+fn combine_surrogates(lead: u16, trail: u16) -> u32 {
+    0x10000 + (((lead as u32) - 0xD800) << 10) + ((trail as u32) - 0xDC00)
+}
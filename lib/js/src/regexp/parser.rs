@@ -0,0 +1,560 @@
+use std::ops::Range;
+
+use super::{class::CharClass, combine_surrogates, is_lead_surrogate, is_trail_surrogate};
+
+#[derive(Debug)]
+pub(super) enum Node {
+    Empty,
+    /// A code point, or a code unit without the unicode flag
+    Char(u32),
+    /// `.`
+    Any,
+    Class(CharClass),
+    /// `^`
+    LineStart,
+    /// `$`
+    LineEnd,
+    /// `\b`, `\B` when negated
+    WordBoundary {
+        negated: bool,
+    },
+    Capture {
+        index: usize,
+        node: Box<Node>,
+    },
+    /// `(?=...)` and `(?!...)`, `(?<=...)` and `(?<!...)` when looking behind
+    Look {
+        node: Box<Node>,
+        behind: bool,
+        negated: bool,
+    },
+    BackReference(usize),
+    Repeat(Repeat),
+    Sequence(Vec<Node>),
+    Alternation(Vec<Node>),
+}
+
+/// `node*`, `node{min,max}?` and the like
+#[derive(Debug)]
+pub(super) struct Repeat {
+    pub node: Box<Node>,
+    pub min: usize,
+    pub max: Option<usize>,
+    pub greedy: bool,
+    /// The groups inside, they are cleared before each iteration
+    pub groups: Range<usize>,
+}
+
+type Result<T> = std::result::Result<T, &'static str>;
+
+/// The root node, the number of groups and their names
+pub(super) type Pattern = (Node, usize, Vec<(String, usize)>);
+
+/// What a class is made of, a class escape can't be the bound of a range
+enum ClassAtom {
+    Char(u32),
+    Class(CharClass),
+}
+
+/// Parse the `Pattern` grammar, with the extensions of Annex B when the unicode flag is off
+pub(super) struct PatternParser {
+    chars: Vec<char>,
+    position: usize,
+    unicode: bool,
+    /// Groups opened so far
+    group_count: usize,
+    /// Known before parsing so that `\10` can tell a back reference from an escape
+    total_groups: usize,
+    group_names: Vec<(String, usize)>,
+}
+
+impl PatternParser {
+    pub fn new(source: &str, unicode: bool) -> PatternParser {
+        PatternParser {
+            chars: source.chars().collect(),
+            position: 0,
+            unicode,
+            group_count: 0,
+            total_groups: 0,
+            group_names: Vec::new(),
+        }
+    }
+
+    pub fn parse(mut self) -> Result<Pattern> {
+        self.scan_groups()?;
+        let node = self.disjunction()?;
+        if self.position < self.chars.len() {
+            return Err("Unmatched ')'");
+        }
+        Ok((node, self.total_groups, self.group_names))
+    }
+
+    /// Count the groups and find their names, they may be referred to before they appear
+    fn scan_groups(&mut self) -> Result<()> {
+        let chars = &self.chars;
+        let mut in_class = false;
+        let mut i = 0;
+        while i < chars.len() {
+            match chars[i] {
+                '\\' => i += 1,
+                '[' => in_class = true,
+                ']' => in_class = false,
+                '(' if !in_class && chars.get(i + 1) != Some(&'?') => self.total_groups += 1,
+                '(' if !in_class
+                    && chars.get(i + 2) == Some(&'<')
+                    && !matches!(chars.get(i + 3), Some('=') | Some('!')) =>
+                {
+                    self.total_groups += 1;
+                    let name: String = chars[i + 3..].iter().take_while(|c| **c != '>').collect();
+                    if self.group_names.iter().any(|(other, _)| *other == name) {
+                        return Err("Duplicate capture group name");
+                    }
+                    self.group_names.push((name, self.total_groups));
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+        Ok(())
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.position + offset).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.position += 1;
+        c
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn disjunction(&mut self) -> Result<Node> {
+        let mut alternatives = vec![self.alternative()?];
+        while self.eat('|') {
+            alternatives.push(self.alternative()?);
+        }
+
+        Ok(if alternatives.len() == 1 {
+            alternatives.pop().unwrap()
+        } else {
+            Node::Alternation(alternatives)
+        })
+    }
+
+    fn alternative(&mut self) -> Result<Node> {
+        let mut terms = Vec::new();
+        while !matches!(self.peek(), None | Some('|') | Some(')')) {
+            terms.push(self.term()?);
+        }
+
+        Ok(match terms.len() {
+            0 => Node::Empty,
+            1 => terms.pop().unwrap(),
+            _ => Node::Sequence(terms),
+        })
+    }
+
+    /// An assertion or an atom with its quantifier
+    fn term(&mut self) -> Result<Node> {
+        let groups_before = self.group_count;
+        let (atom, quantifiable) = match self.next().unwrap() {
+            '^' => (Node::LineStart, false),
+            '$' => (Node::LineEnd, false),
+            '\\' if self.eat('b') => (Node::WordBoundary { negated: false }, false),
+            '\\' if self.eat('B') => (Node::WordBoundary { negated: true }, false),
+            '\\' => (self.atom_escape()?, true),
+            '(' => self.group()?,
+            '.' => (Node::Any, true),
+            '[' => (Node::Class(self.class()?), true),
+            '*' | '+' | '?' => return Err("Nothing to repeat"),
+            '{' | '}' | ']' if self.unicode => return Err("Lone quantifier brackets"),
+            '{' => {
+                // Only a literal when it doesn't look like a quantifier
+                self.position -= 1;
+                if self.braces()?.is_some() {
+                    return Err("Nothing to repeat");
+                }
+                self.position += 1;
+                (Node::Char('{' as u32), true)
+            }
+            c => (self.literal(c), true),
+        };
+        self.quantifier(atom, quantifiable, groups_before)
+    }
+
+    fn quantifier(&mut self, atom: Node, quantifiable: bool, groups_before: usize) -> Result<Node> {
+        let (min, max) = if self.eat('*') {
+            (0, None)
+        } else if self.eat('+') {
+            (1, None)
+        } else if self.eat('?') {
+            (0, Some(1))
+        } else if self.peek() == Some('{') {
+            match self.braces()? {
+                Some(bounds) => bounds,
+                None => return Ok(atom),
+            }
+        } else {
+            return Ok(atom);
+        };
+        if !quantifiable {
+            return Err("Nothing to repeat");
+        }
+        if max.is_some_and(|max| max < min) {
+            return Err("numbers out of order in {} quantifier");
+        }
+
+        let greedy = !self.eat('?');
+        Ok(Node::Repeat(Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+            greedy,
+            groups: groups_before + 1..self.group_count + 1,
+        }))
+    }
+
+    /// `{n}`, `{n,}` or `{n,m}`, `None` when what follows `{` isn't a quantifier
+    fn braces(&mut self) -> Result<Option<(usize, Option<usize>)>> {
+        let start = self.position;
+        self.position += 1;
+        let bounds = match self.decimal() {
+            Some(min) if self.eat('}') => Some((min, Some(min))),
+            Some(min) if self.eat(',') => {
+                if self.eat('}') {
+                    Some((min, None))
+                } else {
+                    match self.decimal() {
+                        Some(max) if self.eat('}') => Some((min, Some(max))),
+                        _ => None,
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        match bounds {
+            Some(bounds) => Ok(Some(bounds)),
+            None if self.unicode => Err("Incomplete quantifier"),
+            None => {
+                self.position = start;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Digits, saturating on overflow
+    fn decimal(&mut self) -> Option<usize> {
+        let mut value: Option<usize> = None;
+        while let Some(digit) = self.peek().and_then(|c| c.to_digit(10)) {
+            self.position += 1;
+            let current = value.unwrap_or(0);
+            value = Some(current.saturating_mul(10).saturating_add(digit as usize));
+        }
+        value
+    }
+
+    /// After `(`
+    fn group(&mut self) -> Result<(Node, bool)> {
+        if !self.eat('?') {
+            self.group_count += 1;
+            let index = self.group_count;
+            let node = self.disjunction()?;
+            self.close_group()?;
+            return Ok((
+                Node::Capture {
+                    index,
+                    node: Box::new(node),
+                },
+                true,
+            ));
+        }
+
+        let (behind, negated) = match self.next() {
+            Some(':') => {
+                let node = self.disjunction()?;
+                self.close_group()?;
+                return Ok((node, true));
+            }
+            Some('=') => (false, false),
+            Some('!') => (false, true),
+            Some('<') if self.eat('=') => (true, false),
+            Some('<') if self.eat('!') => (true, true),
+            Some('<') => {
+                self.group_name()?;
+                self.group_count += 1;
+                let index = self.group_count;
+                let node = self.disjunction()?;
+                self.close_group()?;
+                return Ok((
+                    Node::Capture {
+                        index,
+                        node: Box::new(node),
+                    },
+                    true,
+                ));
+            }
+            _ => return Err("Invalid group"),
+        };
+
+        let node = self.disjunction()?;
+        self.close_group()?;
+        // Annex B allows quantifying a lookahead
+        let quantifiable = !behind && !self.unicode;
+        let look = Node::Look {
+            node: Box::new(node),
+            behind,
+            negated,
+        };
+        Ok((look, quantifiable))
+    }
+
+    fn close_group(&mut self) -> Result<()> {
+        if self.eat(')') {
+            Ok(())
+        } else {
+            Err("Unterminated group")
+        }
+    }
+
+    /// `name>` after `(?<` or `\k<`
+    fn group_name(&mut self) -> Result<String> {
+        let mut name = String::new();
+        loop {
+            match self.next() {
+                Some('>') if !name.is_empty() => return Ok(name),
+                Some(c) if c == '_' || c == '$' || c.is_alphabetic() => name.push(c),
+                Some(c) if !name.is_empty() && c.is_alphanumeric() => name.push(c),
+                _ => return Err("Invalid capture group name"),
+            }
+        }
+    }
+
+    /// After a `\` outside of a class
+    fn atom_escape(&mut self) -> Result<Node> {
+        let c = self.next().ok_or("\\ at end of pattern")?;
+        Ok(match c {
+            'd' | 'D' | 'w' | 'W' | 's' | 'S' => Node::Class(CharClass::escape(c).unwrap()),
+            '1'..='9' => {
+                let start = self.position - 1;
+                self.position = start;
+                let index = self.decimal().unwrap();
+                if index <= self.total_groups {
+                    return Ok(Node::BackReference(index));
+                }
+                if self.unicode {
+                    return Err("Invalid escape");
+                }
+                // A legacy octal escape or the digit itself
+                self.position = start + 1;
+                Node::Char(self.character_escape(c, false)?)
+            }
+            'k' if self.unicode || !self.group_names.is_empty() => {
+                if !self.eat('<') {
+                    return Err("Invalid named reference");
+                }
+                let name = self.group_name()?;
+                match self.group_names.iter().find(|(other, _)| *other == name) {
+                    Some((_, index)) => Node::BackReference(*index),
+                    None => return Err("Invalid named capture referenced"),
+                }
+            }
+            c => Node::Char(self.character_escape(c, false)?),
+        })
+    }
+
+    /// Escapes standing for one character, `c` follows the `\`
+    fn character_escape(&mut self, c: char, in_class: bool) -> Result<u32> {
+        Ok(match c {
+            't' => 0x09,
+            'n' => 0x0A,
+            'v' => 0x0B,
+            'f' => 0x0C,
+            'r' => 0x0D,
+            'c' => match self.peek() {
+                Some(letter) if letter.is_ascii_alphabetic() => {
+                    self.position += 1;
+                    letter as u32 % 32
+                }
+                Some(l) if in_class && !self.unicode && (l.is_ascii_digit() || l == '_') => {
+                    self.position += 1;
+                    l as u32 % 32
+                }
+                _ if self.unicode => return Err("Invalid unicode escape"),
+                _ => {
+                    // The `\` stands for itself, the `c` is read again
+                    self.position -= 1;
+                    '\\' as u32
+                }
+            },
+            '0' if !self.peek().is_some_and(|d| d.is_ascii_digit()) => 0,
+            '0'..='7' if !self.unicode => {
+                let mut value = c.to_digit(8).unwrap();
+                for _ in 0..2 {
+                    match self.peek().and_then(|d| d.to_digit(8)) {
+                        Some(digit) if value * 8 + digit <= 0o377 => {
+                            self.position += 1;
+                            value = value * 8 + digit;
+                        }
+                        _ => break,
+                    }
+                }
+                value
+            }
+            'x' => match self.hex(2) {
+                Some(value) => value,
+                None if self.unicode => return Err("Invalid escape"),
+                None => 'x' as u32,
+            },
+            'u' => match self.unicode_escape()? {
+                Some(value) => value,
+                None if self.unicode => return Err("Invalid Unicode escape"),
+                None => 'u' as u32,
+            },
+            '-' if in_class => '-' as u32,
+            c if self.unicode => {
+                if "^$\\.*+?()[]{}|/".contains(c) {
+                    c as u32
+                } else {
+                    return Err("Invalid escape");
+                }
+            }
+            c => c as u32,
+        })
+    }
+
+    /// Exactly `digits` hex digits, nothing is consumed otherwise
+    fn hex(&mut self, digits: usize) -> Option<u32> {
+        let mut value = 0;
+        for offset in 0..digits {
+            value = value * 16 + self.peek_at(offset)?.to_digit(16)?;
+        }
+        self.position += digits;
+        Some(value)
+    }
+
+    /// After `\u`, `\u{...}` and surrogate pairs of escapes only mean something with the
+    /// unicode flag
+    fn unicode_escape(&mut self) -> Result<Option<u32>> {
+        if self.unicode && self.eat('{') {
+            let mut value: u32 = 0;
+            let mut digits = 0;
+            while let Some(digit) = self.peek().and_then(|c| c.to_digit(16)) {
+                self.position += 1;
+                digits += 1;
+                value = value.saturating_mul(16).saturating_add(digit);
+            }
+            if digits == 0 || !self.eat('}') || value > 0x10FFFF {
+                return Err("Invalid Unicode escape");
+            }
+            return Ok(Some(value));
+        }
+
+        let lead = match self.hex(4) {
+            Some(lead) => lead,
+            None => return Ok(None),
+        };
+        if self.unicode
+            && is_lead_surrogate(lead as u16)
+            && self.peek() == Some('\\')
+            && self.peek_at(1) == Some('u')
+        {
+            let start = self.position;
+            self.position += 2;
+            match self.hex(4) {
+                Some(trail) if is_trail_surrogate(trail as u16) => {
+                    return Ok(Some(combine_surrogates(lead as u16, trail as u16)));
+                }
+                _ => self.position = start,
+            }
+        }
+        Ok(Some(lead))
+    }
+
+    /// A character standing for itself, split in code units without the unicode flag
+    fn literal(&self, c: char) -> Node {
+        if self.unicode || (c as u32) < 0x10000 {
+            return Node::Char(c as u32);
+        }
+        let mut units = [0; 2];
+        c.encode_utf16(&mut units);
+        Node::Sequence(vec![
+            Node::Char(units[0] as u32),
+            Node::Char(units[1] as u32),
+        ])
+    }
+
+    /// After `[`
+    fn class(&mut self) -> Result<CharClass> {
+        let mut class = CharClass::new(self.eat('^'));
+        loop {
+            match self.peek() {
+                None => return Err("Unterminated character class"),
+                Some(']') => {
+                    self.position += 1;
+                    return Ok(class);
+                }
+                _ => {}
+            }
+
+            let start = self.class_atom()?;
+            let is_range = self.peek() == Some('-') && !matches!(self.peek_at(1), None | Some(']'));
+            if !is_range {
+                add_class_atom(&mut class, start);
+                continue;
+            }
+
+            self.position += 1;
+            let end = self.class_atom()?;
+            match (start, end) {
+                (ClassAtom::Char(start), ClassAtom::Char(end)) => {
+                    if start > end {
+                        return Err("Range out of order in character class");
+                    }
+                    class.add_range(start, end);
+                }
+                _ if self.unicode => return Err("Invalid character class"),
+                // `[\d-x]` is the three of them
+                (start, end) => {
+                    add_class_atom(&mut class, start);
+                    add_class_atom(&mut class, ClassAtom::Char('-' as u32));
+                    add_class_atom(&mut class, end);
+                }
+            }
+        }
+    }
+
+    fn class_atom(&mut self) -> Result<ClassAtom> {
+        let c = self.next().unwrap();
+        if c != '\\' {
+            return Ok(ClassAtom::Char(c as u32));
+        }
+
+        let c = self.next().ok_or("\\ at end of pattern")?;
+        Ok(match CharClass::escape(c) {
+            Some(class) => ClassAtom::Class(class),
+            None if c == 'b' => ClassAtom::Char(0x08),
+            None => ClassAtom::Char(self.character_escape(c, true)?),
+        })
+    }
+}
+
+fn add_class_atom(class: &mut CharClass, atom: ClassAtom) {
+    match atom {
+        ClassAtom::Char(c) => class.add_range(c, c),
+        ClassAtom::Class(other) => class.add_class(&other),
+    }
+}
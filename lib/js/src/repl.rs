@@ -0,0 +1,104 @@
+use std::io;
+
+use js::{jsrt::inspect, parser::Parser, vm::Context};
+
+use crate::{
+    dump_tokens,
+    editor::{Editor, ReadLine},
+    run_program, Options,
+};
+
+const HELP: &str = "\
+.break    Forget the lines typed so far
+.exit     Exit the REPL
+.help     Print this help
+
+Ctrl-C clears the line, Ctrl-D on an empty line exits";
+
+/// Read, evaluate and print the inputs until `.exit` or the end of the input, an input spans
+/// lines until it parses
+pub fn run(context: &mut Context, options: &Options) -> io::Result<()> {
+    let mut editor = Editor::new();
+    if editor.is_interactive() {
+        println!("Welcome to js, type .help for more information");
+    }
+
+    let mut input = String::new();
+    // Ctrl-C was pressed on an empty line, pressing it again exits
+    let mut interrupted = false;
+    loop {
+        let prompt = if input.is_empty() { "> " } else { "... " };
+        let line = match editor.read_line(prompt)? {
+            ReadLine::Line(line) => line,
+            ReadLine::Interrupted(line) => {
+                if input.is_empty() && line.is_empty() {
+                    if interrupted {
+                        return Ok(());
+                    }
+                    println!("(To exit, press Ctrl-C again or Ctrl-D or type .exit)");
+                }
+                interrupted = input.is_empty() && line.is_empty();
+                input.clear();
+                continue;
+            }
+            ReadLine::Eof if input.trim().is_empty() => return Ok(()),
+            // What is left can't be complete anymore, report it
+            ReadLine::Eof => {
+                evaluate(context, &input, options);
+                return Ok(());
+            }
+        };
+        interrupted = false;
+        editor.add_history(&line);
+
+        match line.trim() {
+            ".exit" => return Ok(()),
+            ".break" => {
+                input.clear();
+                continue;
+            }
+            ".help" if input.is_empty() => {
+                println!("{}", HELP);
+                continue;
+            }
+            command if input.is_empty() && command.starts_with('.') => {
+                println!("Invalid REPL keyword");
+                continue;
+            }
+            _ => {}
+        }
+
+        input.push_str(&line);
+        input.push('\n');
+        if input.trim().is_empty() {
+            input.clear();
+            continue;
+        }
+        if matches!(Parser::new(&input).parse_program(), Err(error) if error.is_incomplete()) {
+            continue;
+        }
+        evaluate(context, &input, options);
+        input.clear();
+    }
+}
+
+/// Run `source` as a script, then print its completion value or what it threw
+fn evaluate(context: &mut Context, source: &str, options: &Options) {
+    if options.dump_tokens {
+        dump_tokens(source);
+    }
+    let program = match Parser::new(source).parse_program() {
+        Ok(program) => program,
+        Err(error) => {
+            println!("Uncaught {}", error);
+            return;
+        }
+    };
+    match run_program(context, &program, options) {
+        Ok(value) => println!("{}", inspect(context, &value)),
+        Err(message) => println!("{}", message),
+    }
+    if let Err(completion) = context.run_macrotasks() {
+        println!("{}", crate::uncaught(context, completion));
+    }
+}
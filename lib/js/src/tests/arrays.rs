@@ -0,0 +1,207 @@
+use super::*;
+use crate::jsrt::{JsObject, PropertyDescriptor, PropertyKey};
+
+#[test]
+fn array_literals() {
+    assert_eq!(eval_number("[1, 2, 3].length;"), 3.0);
+    assert_eq!(eval_number("[1, , 3, ].length;"), 3.0);
+    assert_eq!(eval_number("[].length;"), 0.0);
+    assert_eq!(
+        eval_string("[1, [2, 3], 'a', null, undefined];"),
+        "1,2,3,a,,"
+    );
+    assert!(!eval("1 in [1, , 3];").to_boolean());
+    assert!(eval("Array.isArray([]) && !Array.isArray({ length: 0 });").to_boolean());
+}
+
+#[test]
+fn member_expressions() {
+    let source = r#"
+        let o = { a: { b: 1 }, 'c d': 2, if: 3 };
+        o.a.b += 10;
+        o['c' + ' d']++;
+        let k = o.if++;
+        o.a.b + o['c d'] + k + o.if;
+    "#;
+    assert_eq!(eval_number(source), 11.0 + 3.0 + 3.0 + 4.0);
+    assert_eq!(eval_number("'abc'.length + 'abc'[1].length;"), 4.0);
+    assert_eq!(
+        eval_string("let o = { a: 1 }; delete o.a; typeof o.a;"),
+        "undefined"
+    );
+    assert_eq!(
+        eval_string("try { null.x; } catch (e) { e.message; }"),
+        "Cannot read properties of null (reading 'x')"
+    );
+    assert_eq!(
+        eval_string("try { let u; u.x = 1; } catch (e) { e.message; }"),
+        "Cannot set properties of undefined (setting 'x')"
+    );
+}
+
+#[test]
+fn length_semantics() {
+    assert_eq!(eval_number("let a = []; a[9] = 1; a.length;"), 10.0);
+    assert_eq!(
+        eval_string("let a = [1, 2, 3, 4]; a.length = 2; a.length + ':' + a + ':' + a[2];"),
+        "2:1,2:undefined"
+    );
+    assert_eq!(
+        eval_string("let a = []; try { a.length = -1; } catch (e) { e.name; }"),
+        "RangeError"
+    );
+    // Far away indices keep the array sparse
+    assert_eq!(
+        eval_string("let a = [1]; a[100000] = 2; a.length + ' ' + a[100000] + ' ' + a[5];"),
+        "100001 2 undefined"
+    );
+}
+
+#[test]
+fn non_writable_length() {
+    let mut context = Context::new(Runtime::new());
+    let mut array = JsObject::array(&mut context, vec![JsValue::integer(1)]);
+    let length = PropertyKey::from("length");
+
+    assert!(array.define_own_property(
+        PropertyKey::from(3),
+        PropertyDescriptor::data(JsValue::Null, Default::default())
+    ));
+    assert_eq!(array.get_data_property("length").as_i32(), 4);
+    // The non configurable element stops the truncation
+    assert!(!array.define_own_property(
+        length.clone(),
+        PropertyDescriptor {
+            value: Some(JsValue::integer(0)),
+            writable: Some(false),
+            ..Default::default()
+        }
+    ));
+    assert_eq!(array.get_data_property("length").as_i32(), 4);
+    assert!(!array.get_own_property(&length).unwrap().writable());
+    assert!(!array.create_data_property(PropertyKey::from(4), JsValue::Null));
+    assert_eq!(
+        array.own_property_keys(),
+        [PropertyKey::Index(0), PropertyKey::Index(3), length]
+    );
+}
+
+#[test]
+fn mutators() {
+    let source = r#"
+        let a = [1, 2, 3];
+        let s = '';
+        s += a.push(4, 5) + ' ' + a + '|';
+        s += a.pop() + ' ' + a.shift() + ' ' + a + '|';
+        s += a.unshift(0, 1) + ' ' + a + '|';
+        s += a.splice(1, 2, 'x', 'y', 'z') + ' ' + a + '|';
+        s += a.splice(-2) + ' ' + a + '|';
+        s += a.slice(1, -1) + ' ' + a.slice(-2) + ' ' + a;
+        s;
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "5 1,2,3,4,5|5 1 2,3,4|5 0,1,2,3,4|1,2 0,x,y,z,3,4|3,4 0,x,y,z|x,y y,z 0,x,y,z"
+    );
+}
+
+#[test]
+fn iteration_methods() {
+    let source = r#"
+        let a = [1, 2, 3, 4];
+        let s = [];
+        a.forEach(function (v, i) { s.push(v * i); });
+        [
+            s.join('-'),
+            a.map(function (v) { return v * 2; }),
+            a.filter(function (v) { return v % 2; }),
+            a.reduce(function (acc, v) { return acc + v; }),
+            a.reduce(function (acc, v) { return acc + v; }, 10),
+            a.find(function (v) { return v > 2; }),
+            a.findIndex(function (v) { return v > 5; }),
+            a.some(function (v) { return v > 3; }),
+            a.every(function (v) { return v > 3; }),
+            a.indexOf(3),
+            [NaN].indexOf(NaN),
+            [NaN].includes(NaN),
+            [, 1].map(function (v) { return 1; }),
+        ].join(' ');
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "0-2-6-12 2,4,6,8 1,3 10 20 3 -1 true false 2 -1 true ,1"
+    );
+    assert_eq!(
+        eval_string("try { [].reduce(function () {}); } catch (e) { e.message; }"),
+        "Reduce of empty array with no initial value"
+    );
+    assert_eq!(
+        eval_string("try { [1].map(1); } catch (e) { e.message; }"),
+        "1 is not a function"
+    );
+}
+
+#[test]
+fn sort() {
+    assert_eq!(
+        eval_string("[3, 1, undefined, 10, , 2].sort();"),
+        "1,10,2,3,,"
+    );
+    assert_eq!(
+        eval_string("[3, 1, 10, 2].sort(function (a, b) { return a - b; });"),
+        "1,2,3,10"
+    );
+    // Equal elements keep their order
+    let source = r#"
+        let a = [{ k: 1, v: 'a' }, { k: 0, v: 'b' }, { k: 1, v: 'c' }, { k: 0, v: 'd' }];
+        a.sort(function (x, y) { return x.k - y.k; });
+        a.map(function (x) { return x.v; }).join('');
+    "#;
+    assert_eq!(eval_string(source), "bdac");
+    assert_eq!(
+        eval_string("try { [2, 1].sort(function () { throw 'no'; }); } catch (e) { e; }"),
+        "no"
+    );
+}
+
+#[test]
+fn iterators() {
+    let source = r#"
+        let a = ['a', 'b'];
+        let s = '';
+        for (let v of a) s += v;
+        for (let k of a.keys()) s += k;
+        for (let e of a.entries()) s += e[0] + e[1];
+        let it = a.values();
+        it.next();
+        it.next();
+        s + it.next().done + it.next().value;
+    "#;
+    assert_eq!(eval_string(source), "ab010a1btrueundefined");
+}
+
+#[test]
+fn methods_receive_this() {
+    let source = r#"
+        let array_like = { length: 2, 0: 'x', 1: 'y', join: [].join };
+        array_like.join('+');
+    "#;
+    assert_eq!(eval_string(source), "x+y");
+    assert_eq!(
+        eval_number("let o = { push: [].push }; o.push(1, 2); o.length;"),
+        2.0
+    );
+}
+
+#[test]
+fn spread() {
+    assert_eq!(
+        eval_string("let a = [2, 3]; [1, ...a, 4, ...'ab'].join();"),
+        "1,2,3,4,a,b"
+    );
+    assert_eq!(eval_number("Math.max(...[1, 5, 3], 2);"), 5.0);
+    assert_eq!(
+        eval_number("function f(a, b, c) { return c; } f(...[1], ...[2, 3]);"),
+        3.0
+    );
+}
@@ -0,0 +1,149 @@
+use super::*;
+
+#[test]
+fn numeric_literals() {
+    assert_eq!(
+        eval_string("[1.5, .25, 1e3, 2.5E-3, 0x1F, 0o17, 0b101, 0xffn, 10n].join(' ');"),
+        "1.5 0.25 1000 0.0025 31 15 5 255 10"
+    );
+    assert_eq!(
+        eval_string("typeof 1n + ' ' + typeof Object(1n);"),
+        "bigint object"
+    );
+    assert!(Parser::new("0xn;").parse_program().is_err());
+}
+
+#[test]
+fn arithmetic() {
+    let source = r#"
+        [
+            1n + 2n, 7n - 10n, 12345678901234567890n * 98765432109876543210n, -7n / 2n,
+            -7n % 2n, 2n ** 100n, (-3n) ** 3n, -(5n), ~5n, 0n ** 0n,
+        ].join(' ');
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "3 -3 1219326311370217952237463801111263526900 -3 -1 \
+         1267650600228229401496703205376 -27 -5 -6 1"
+    );
+    assert_eq!(
+        eval_string("let a = 1n; a++; ++a; a += 10n; a-- + ' ' + a + ' ' + typeof a;"),
+        "13 12 bigint"
+    );
+    assert_eq!(
+        eval_string("let o = { x: 9007199254740993n }; o.x++; o.x;"),
+        "9007199254740994"
+    );
+    assert_eq!(eval_string("'x' + 10n + 1n;"), "x101");
+}
+
+#[test]
+fn errors() {
+    let cases = [
+        ("1n + 1", "TypeError: Cannot mix BigInt and other types, use explicit conversions"),
+        ("1n | 1", "TypeError: Cannot mix BigInt and other types, use explicit conversions"),
+        ("+1n", "TypeError: Cannot convert a BigInt value to a number"),
+        ("1n / 0n", "RangeError: Division by zero"),
+        ("1n % 0n", "RangeError: Division by zero"),
+        ("2n ** -1n", "RangeError: Exponent must be non-negative"),
+        ("2n ** 10000000000n", "RangeError: Maximum BigInt size exceeded"),
+        ("1n << 10000000000n", "RangeError: Maximum BigInt size exceeded"),
+        ("1n >>> 1n", "TypeError: BigInts have no unsigned right shift, use >> instead"),
+        ("BigInt(1.5)", "RangeError: The number 1.5 cannot be converted to a BigInt because it is not an integer"),
+        ("BigInt('1.5')", "SyntaxError: Cannot convert 1.5 to a BigInt"),
+        ("BigInt.asIntN(8, 1)", "TypeError: Cannot convert 1 to a BigInt"),
+        ("BigInt.asIntN(-1, 1n)", "RangeError: Invalid value: not (convertible to) a safe integer"),
+        ("JSON.stringify({ a: 1n })", "TypeError: Do not know how to serialize a BigInt"),
+        ("BigInt.prototype.valueOf.call(1)", "TypeError: BigInt.prototype.valueOf requires that 'this' be a BigInt"),
+    ];
+    for (source, message) in cases {
+        let source = format!(
+            "try {{ {}; }} catch (e) {{ e.name + ': ' + e.message; }}",
+            source
+        );
+        assert_eq!(eval_string(&source), message, "{}", source);
+    }
+}
+
+#[test]
+fn built_ins_throw_on_number_conversion() {
+    let sources = [
+        "Math.max(1n)",
+        "Math.floor(1n)",
+        "Math.clz32(1n)",
+        "isNaN(1n)",
+        "parseInt('1', 1n)",
+        "'ab'.charAt(1n)",
+        "'ab'.split('', 1n)",
+        "[1, 2].slice(1n)",
+        "[1, 2].indexOf(1, 1n)",
+        "[2, 1].sort(() => 1n)",
+        "let a = []; a.length = 1n",
+        "Array.prototype.map.call({ length: 1n }, x => x)",
+    ];
+    for source in sources {
+        let source = format!(
+            "try {{ {}; 'no error'; }} catch (e) {{ e.name + ': ' + e.message; }}",
+            source
+        );
+        assert_eq!(
+            eval_string(&source),
+            "TypeError: Cannot convert a BigInt value to a number",
+            "{}",
+            source
+        );
+    }
+}
+
+#[test]
+fn comparisons() {
+    let source = r#"
+        [
+            1n == 1, 1n === 1, 2n == '2', 2n == '0x2', 1n == 'x', 1n < 2, 2n > 1.5, 2n < 2.5,
+            -1n < -0.5, 1n < NaN, 1n < Infinity, 10n > '9', '10' < 9n, 0n == false, 3n >= 3,
+            Object.is(0n, -0n), [1n].includes(1n), 2n ** 64n > 2 ** 63,
+        ].join(' ');
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "true false true true false true true true true false true true false true true \
+         true true true"
+    );
+    assert!(!eval("0n;").to_boolean());
+    assert!(eval("-1n;").to_boolean());
+}
+
+#[test]
+fn bitwise() {
+    let source = r#"
+        [
+            -6n & 13n, -6n | 13n, -6n ^ 13n, -6n & -3n, 1n << 70n, -7n >> 1n, 5n << -1n,
+            -1n >> 100n, (1n << 64n) - 1n & -(1n << 32n),
+        ].join(' ');
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "8 -1 -9 -8 1180591620717411303424 -4 2 -1 18446744069414584320"
+    );
+}
+
+#[test]
+fn bigint_builtins() {
+    let source = r#"
+        [
+            BigInt(10), BigInt(-0), BigInt(' 0x10 '), BigInt(''), BigInt(true), BigInt(2 ** 60),
+            BigInt.asIntN(8, 255n), BigInt.asIntN(64, -1n), BigInt.asUintN(8, -1n),
+            BigInt.asUintN(64, -1n), BigInt.asIntN(3, 25n), (255n).toString(16),
+            (-255n).toString(2), BigInt.prototype.toString.call(Object(7n)), Number(2n ** 70n),
+            Number(-5n), Object.prototype.toString.call(1n), JSON.stringify({
+                a: Object(1n),
+                toJSON: function () { return 'ok'; },
+            }),
+        ].join(' ');
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "10 0 16 0 1 1152921504606846976 -1 -1 255 18446744073709551615 1 ff -11111111 7 \
+         1.1805916207174113e+21 -5 [object BigInt] \"ok\""
+    );
+}
@@ -0,0 +1,295 @@
+use super::*;
+
+#[test]
+fn global_object() {
+    assert!(
+        eval("globalThis.Array === Array && globalThis.globalThis === globalThis;").to_boolean()
+    );
+    assert_eq!(
+        eval_string("typeof console.log + ' ' + typeof nope;"),
+        "function undefined"
+    );
+    // Undeclared assignments create global properties, the constants can't be changed
+    assert_eq!(eval_number("x = 4; globalThis.x;"), 4.0);
+    assert_eq!(
+        eval_string("undefined = 1; NaN = 2; typeof undefined + NaN;"),
+        "undefinedNaN"
+    );
+    assert_eq!(
+        eval_string("Object.keys(globalThis).join();"),
+        "",
+        "built-ins are not enumerable"
+    );
+    assert_eq!(
+        eval_string("try { missing; } catch (e) { e.message; }"),
+        "missing is not defined"
+    );
+    assert!(eval("console.log('hello', 1, [2, 3]) === undefined;").to_boolean());
+}
+
+#[test]
+fn global_functions() {
+    let source = r#"
+        [
+            parseInt('  42px'), parseInt('-0x1F'), parseInt('101', 2), parseInt('z', 37),
+            parseFloat('3.5e2xyz'), parseFloat('-.5'), parseFloat('Infinityx'), parseFloat('e1'),
+            isNaN('abc'), isNaN('12'), isFinite('1e400'), Number.parseInt === parseInt,
+        ].join(' ');
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "42 -31 5 NaN 350 -0.5 Infinity NaN true false false true"
+    );
+}
+
+#[test]
+fn object_statics() {
+    let source = r#"
+        let o = { a: 1, b: 2 };
+        Object.defineProperty(o, 'hidden', { value: 3 });
+        let d = Object.getOwnPropertyDescriptor(o, 'hidden');
+        [
+            Object.keys(o), Object.values(o), Object.entries(o).join(';'),
+            Object.getOwnPropertyNames(o), d.writable, d.enumerable, d.value,
+            Object.getPrototypeOf(o) === Object.prototype,
+            String(Object.getPrototypeOf(Object.create(null))),
+            Object.assign({}, o, null, { c: 3 }).c,
+            Object.is(NaN, NaN), Object.is(0, -0),
+        ].join(' ');
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "a,b 1,2 a,1;b,2 a,b,hidden false false 3 true null 3 true false"
+    );
+
+    let source = r#"
+        let o = Object.freeze({ a: 1 });
+        o.a = 2;
+        o.b = 3;
+        [o.a, o.b === undefined, Object.isFrozen(o), Object.isExtensible(o), Object.isFrozen({})].join(' ');
+    "#;
+    assert_eq!(eval_string(source), "1 true true false false");
+    assert_eq!(
+        eval_string(
+            "try { Object.defineProperty(Object.freeze({ a: 1 }), 'a', { value: 2 }); } \
+             catch (e) { e.message; }"
+        ),
+        "Cannot redefine property: a"
+    );
+
+    let source = r#"
+        let counter = Object.create({}, {
+            count: { get: function () { return 7; }, enumerable: true },
+        });
+        counter.count + Object.keys(counter).length;
+    "#;
+    assert_eq!(eval_number(source), 8.0);
+}
+
+#[test]
+fn object_prototype() {
+    let source = r#"
+        let proto = { inherited: 1 };
+        let o = Object.create(proto);
+        o.own = 2;
+        [
+            o.hasOwnProperty('own'), o.hasOwnProperty('inherited'),
+            proto.isPrototypeOf(o), o.propertyIsEnumerable('own'),
+            Object.prototype.toString.call([]), Object.prototype.toString.call(null),
+            Object.prototype.toString.call(1), String({}),
+        ].join(' ');
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "true false true true [object Array] [object Null] [object Number] [object Object]"
+    );
+}
+
+#[test]
+fn function_prototype() {
+    let source = r#"
+        let join = [].join;
+        let array_like = { length: 2, 0: 'a', 1: 'b' };
+        let greet = ''.concat.bind('Hi ', 'there');
+        [
+            join.call(array_like, '+'), join.apply(array_like, ['-']), greet('!'),
+            greet.length, greet.name, Math.max.apply(null, [1, 5, 2]),
+            typeof Function.prototype, Function.prototype(),
+        ].join(' ');
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "a+b a-b Hi there! 0 bound concat 5 function "
+    );
+    assert_eq!(
+        eval_number("let add = Function('a', 'b', 'return a + b;'); add(2, 3) + add.length;"),
+        7.0
+    );
+    assert_eq!(
+        eval_string("[].push.toString() + ' ' + parseInt.name + ' ' + parseInt.length;"),
+        "function push() { [native code] } parseInt 2"
+    );
+    assert_eq!(
+        eval_string("try { Function('return ('); } catch (e) { e.name; }"),
+        "SyntaxError"
+    );
+}
+
+#[test]
+fn numbers_and_booleans() {
+    let source = r#"
+        [
+            Number('  12 '), Number(''), Number(), Number.isInteger(5), Number.isInteger('5'),
+            Number.isSafeInteger(Number.MAX_SAFE_INTEGER + 1), (255).toString(16),
+            (255).toString(2), (1 / 2).toString(2), (-15 / 4).toString(4),
+            parseFloat('1.005').toFixed(2), (5 / 2).toFixed(0), (-3 / 2).toFixed(0),
+            parseFloat('123.456').toFixed(1), parseFloat('1e21').toFixed(2),
+            1 / 3, 2 / 10000000, 1 / 1000000,
+            Boolean(''), Boolean('0'), true.toString(), (5).valueOf(),
+        ].join(' ');
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "12 0 0 true false false ff 11111111 0.1 -3.3 1.00 3 -2 123.5 1e+21 0.3333333333333333 2e-7 \
+         0.000001 false true true 5"
+    );
+    assert_eq!(
+        eval_string("try { (1).toString(1); } catch (e) { e.name; }"),
+        "RangeError"
+    );
+}
+
+#[test]
+fn strings() {
+    let source = r#"
+        let s = 'Hello, World';
+        [
+            s.length, s.charAt(4), s.charCodeAt(0), s.at(-1), s.indexOf('o'),
+            s.lastIndexOf('o'), s.includes('World'), s.startsWith('Hell'), s.endsWith('d'),
+            s.slice(-5, -1), s.substring(5, 0), s.toUpperCase(), '  pad  '.trim() + '|',
+            'a-b-c'.split('-'), 'abc'.split(''), 'a,b,c'.split(',', 2), 'ab'.repeat(3),
+            '5'.padStart(3, '0'), 'x'.padEnd(4, 'ab'), 'a'.concat('b', 1),
+            'aaa'.replace('a', 'b'), 'aaa'.replaceAll('a', '$&$&'), 'abc'.replace('b', '[$`]'),
+            String.fromCharCode(72, 105), String(null),
+            String.fromCharCode(55357, 56832).length,
+        ].join(' ');
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "12 o 72 d 4 8 true true true Worl Hello HELLO, WORLD pad| a,b,c a,b,c a,b ababab \
+         005 xaba ab1 baa aaaaaa a[a]c Hi null 2"
+    );
+    assert_eq!(
+        eval_string("'abc'.replace('b', function (m, i) { return m.toUpperCase() + i; });"),
+        "aB1c"
+    );
+    assert_eq!(
+        eval_string("let s = Object('ab'); typeof s + s.length + s[1] + (s + 'c');"),
+        "object2babc"
+    );
+}
+
+#[test]
+fn math() {
+    let source = r#"
+        [
+            Math.abs(-2), Math.floor(-3 / 2), Math.ceil(6 / 5), Math.round(5 / 2),
+            Math.round(-5 / 2), Math.trunc(-47 / 10), Math.sign(-3), Math.max(1, 3, 2),
+            Math.min(), Math.max(1, NaN), Math.pow(2, 10), Math.pow(1, Infinity), Math.sqrt(16),
+            Math.hypot(3, 4), Math.clz32(1), Math.imul(-1, 5), Math.PI > 3,
+            1 / Math.round(-1 / 5),
+        ].join(' ');
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "2 -2 2 3 -2 -4 -1 3 Infinity NaN 1024 NaN 4 5 31 -5 true -Infinity"
+    );
+    assert!(eval("let r = Math.random(); r >= 0 && r < 1;").to_boolean());
+}
+
+#[test]
+fn json() {
+    let source = r#"
+        let o = JSON.parse(' { "a": [1, 2.5, -3e2, true, null], "b": { "c": "x\\ny\\u0041" } } ');
+        [o.a.length, o.a[2], o.a[3], o.a[4], o.b.c].join(' ');
+    "#;
+    assert_eq!(eval_string(source), "5 -300 true  x\nyA");
+    assert_eq!(
+        eval_string(
+            "JSON.stringify(JSON.parse('[1, 2, 3]', function (k, v) { \
+               return typeof v === 'number' ? v * 2 : v; }));"
+        ),
+        "[2,4,6]"
+    );
+    assert_eq!(
+        eval_string("try { JSON.parse('{\"a\": 1,}'); } catch (e) { e.name + ': ' + e.message; }"),
+        "SyntaxError: Unexpected token } in JSON at position 8"
+    );
+
+    let source = r#"
+        let value = {
+            s: 'q"\n', n: 3 / 2, inf: Infinity, u: undefined, f: function () {}, a: [undefined, 1],
+            nested: { toJSON: function (key) { return 'json:' + key; } },
+        };
+        JSON.stringify(value);
+    "#;
+    assert_eq!(
+        eval_string(source),
+        r#"{"s":"q\"\n","n":1.5,"inf":null,"a":[null,1],"nested":"json:nested"}"#
+    );
+    assert_eq!(
+        eval_string("JSON.stringify({ a: 1, b: [2], c: 3 }, ['a', 'b'], 2);"),
+        "{\n  \"a\": 1,\n  \"b\": [\n    2\n  ]\n}"
+    );
+    assert_eq!(
+        eval_string(
+            "JSON.stringify({ a: 1, b: 'x' }, function (k, v) { \
+               return typeof v === 'number' ? undefined : v; });"
+        ),
+        r#"{"b":"x"}"#
+    );
+    assert_eq!(
+        eval_string("let o = {}; o.self = o; try { JSON.stringify(o); } catch (e) { e.message; }"),
+        "Converting circular structure to JSON"
+    );
+    assert!(eval("JSON.stringify(undefined) === undefined;").to_boolean());
+}
+
+#[test]
+fn weak_collections() {
+    let source = r#"
+        let a = {}, b = {};
+        let map = WeakMap([[a, 1]]);
+        map.set(b, 2).set(a, 3);
+        let set = WeakSet([a]);
+        let registry = FinalizationRegistry(function () {});
+        [
+            map.get(a), map.get(b), map.has({}), map.get(1), map.delete(b), map.has(b),
+            set.has(a), set.has(b), set.add(b) === set, set.delete(a), set.has(a),
+            WeakRef(a).deref() === a, registry.register(b, 'b', a), registry.unregister(a),
+            registry.unregister(b), Object.prototype.toString.call(map),
+            Object.prototype.toString.call(WeakRef(b)),
+        ].join(' ');
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "3 2 false  true false true false true true false true  true false [object WeakMap] [object WeakRef]"
+    );
+
+    for (source, message) in [
+        ("WeakMap().set(1, 2);", "Invalid value used as weak map key"),
+        ("WeakSet(['a']);", "Invalid value used in weak set"),
+        ("WeakRef(Symbol());", "WeakRef: invalid target"),
+        (
+            "FinalizationRegistry(1);",
+            "FinalizationRegistry: cleanup must be callable",
+        ),
+        (
+            "WeakMap.prototype.get.call(WeakSet(), {});",
+            "WeakMap.prototype.get requires that 'this' be a WeakMap",
+        ),
+    ] {
+        let source = format!("try {{ {} }} catch (e) {{ e.message; }}", source);
+        assert_eq!(eval_string(&source), message);
+    }
+}
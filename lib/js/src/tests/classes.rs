@@ -0,0 +1,308 @@
+use super::*;
+
+/// Run `source` and return "Name: message" of what it throws
+fn error_of(source: &str) -> String {
+    eval_string(&format!(
+        "try {{ {}; }} catch (e) {{ e.name + ': ' + e.message; }}",
+        source
+    ))
+}
+
+#[test]
+fn new_calls_functions_as_constructors() {
+    let source = r#"
+        function Point(x, y) {
+            this.x = x;
+            this.y = y;
+        }
+        Point.prototype.sum = function () { return this.x + this.y; };
+        let p = new Point(3, 4);
+        p.sum() + ' ' + (p instanceof Point) + ' ' + (p.constructor === Point);
+    "#;
+    assert_eq!(eval_string(source), "7 true true");
+
+    // An object returned by the constructor replaces the new one
+    assert_eq!(
+        eval_number("function F() { this.a = 1; return { a: 2 }; } new F().a;"),
+        2.0
+    );
+    assert_eq!(
+        eval_number("function F() { this.a = 1; return 5; } new F().a;"),
+        1.0
+    );
+    assert_eq!(
+        eval_number("let o = { F: function () { this.a = 3; } }; new o.F().a;"),
+        3.0
+    );
+    assert_eq!(eval_number("function F() { this.a = 4; } (new F).a;"), 4.0);
+    assert_eq!(
+        eval_string("typeof new Number(1) + ' ' + new String('ab').length + ' ' + new Boolean(0);"),
+        "object 2 false"
+    );
+
+    assert_eq!(error_of("new 1"), "TypeError: 1 is not a constructor");
+    // Neither are arrow functions and methods
+    assert!(error_of("let f = () => 1; new f()").ends_with("is not a constructor"));
+    assert!(error_of("class A { m() {} } new new A().m()").ends_with("is not a constructor"));
+    assert_eq!(
+        error_of("new Symbol()"),
+        "TypeError: Symbol is not a constructor"
+    );
+}
+
+#[test]
+fn class_methods_and_statics() {
+    let source = r#"
+        class Counter {
+            constructor(start) {
+                this.count = start;
+            }
+            increment() {
+                this.count += 1;
+                return this;
+            }
+            static zero() {
+                return new Counter(0);
+            }
+            get double() {
+                return this.count * 2;
+            }
+            set double(value) {
+                this.count = value / 2;
+            }
+        }
+        let c = Counter.zero().increment().increment();
+        let before = c.double;
+        c.double = 10;
+        [before, c.count, typeof Counter, Counter.name, Object.keys(Counter.prototype).length].join();
+    "#;
+    assert_eq!(eval_string(source), "4,5,function,Counter,0");
+
+    let source = r#"
+        const key = 'computed';
+        class A {
+            [key + 'Name']() { return 1; }
+            [Symbol.iterator]() { return [1, 2][Symbol.iterator](); }
+            'quoted'() { return 2; }
+        }
+        let a = new A();
+        [a.computedName(), a.quoted(), [...a].length, A.prototype[Symbol.iterator].name].join();
+    "#;
+    assert_eq!(eval_string(source), "1,2,2,[Symbol.iterator]");
+
+    // Classes are not hoisted and are bound like `let`
+    assert_eq!(
+        error_of("new A(); class A {}"),
+        "ReferenceError: Cannot access 'A' before initialization"
+    );
+    assert_eq!(
+        error_of("class A {} A()"),
+        "TypeError: Class constructor A cannot be invoked without 'new'"
+    );
+    assert_eq!(eval_string("let B = class Named {}; B.name;"), "Named");
+}
+
+#[test]
+fn class_fields() {
+    let source = r#"
+        let order = [];
+        class A {
+            x = 1;
+            y = this.x + 1;
+            z;
+            static count = 3;
+            static twice = A.count * 2;
+            constructor() {
+                order.push(this.y);
+            }
+        }
+        let a = new A();
+        [a.x, a.y, a.z, A.count, A.twice, order[0], a.hasOwnProperty('x')].join();
+    "#;
+    assert_eq!(eval_string(source), "1,2,,3,6,2,true");
+}
+
+#[test]
+fn extends_and_super() {
+    let source = r#"
+        class Animal {
+            constructor(name) {
+                this.name = name;
+            }
+            speak() {
+                return this.name + ' makes a sound';
+            }
+            static create(name) {
+                return new this(name);
+            }
+        }
+        class Dog extends Animal {
+            constructor(name) {
+                super(name);
+                this.kind = 'dog';
+            }
+            speak() {
+                return super.speak() + ', woof';
+            }
+        }
+        class Puppy extends Dog {
+            speak() {
+                return super['speak']() + '!';
+            }
+        }
+        let p = Puppy.create('Rex');
+        [p.speak(), p.kind, p instanceof Animal, Object.getPrototypeOf(Puppy) === Dog].join();
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "Rex makes a sound, woof!,dog,true,true"
+    );
+
+    // Fields of a derived class are defined once `super()` returns
+    let source = r#"
+        class Base { constructor() { this.seen = this.field; } }
+        class Derived extends Base { field = 1; }
+        let d = new Derived();
+        [d.seen, d.field].join();
+    "#;
+    assert_eq!(eval_string(source), ",1");
+
+    assert_eq!(
+        eval_string("class A extends null {} Object.getPrototypeOf(A.prototype);"),
+        "null"
+    );
+    assert_eq!(
+        error_of("class A extends 1 {}"),
+        "TypeError: Class extends value 1 is not a constructor or null"
+    );
+    assert_eq!(
+        error_of("class A {} class B extends A { constructor() { this.x = 1; } } new B()"),
+        "ReferenceError: Must call super constructor in derived class before accessing 'this' \
+         or returning from derived constructor"
+    );
+    assert_eq!(
+        error_of("class A {} class B extends A { constructor() { super(); super(); } } new B()"),
+        "ReferenceError: Super constructor may only be called once"
+    );
+    assert_eq!(
+        error_of("class A {} class B extends A { constructor() { super(); return 1; } } new B()"),
+        "TypeError: Derived constructors may only return object or undefined"
+    );
+}
+
+#[test]
+fn new_target() {
+    let source = r#"
+        let targets = [];
+        class Base {
+            constructor() {
+                targets.push(new.target.name);
+                let arrow = () => new.target;
+                targets.push(arrow() === new.target);
+            }
+        }
+        class Derived extends Base {
+            constructor() {
+                super();
+                targets.push(new.target === Derived);
+            }
+        }
+        new Base();
+        new Derived();
+        targets.join();
+    "#;
+    assert_eq!(eval_string(source), "Base,true,Derived,true,true");
+
+    let source = r#"
+        function F() { return typeof new.target; }
+        [F(), new F() instanceof F, typeof new.target].join();
+    "#;
+    assert_eq!(eval_string(source), "undefined,true,undefined");
+    assert_eq!(
+        eval_string("function F() { this.t = new.target; } new F().t === F;"),
+        "true"
+    );
+}
+
+#[test]
+fn extending_builtins() {
+    let source = r#"
+        class MyError extends Error {
+            constructor(message) {
+                super(message);
+                this.name = 'MyError';
+            }
+        }
+        class Stack extends Array {
+            peek() { return this[this.length - 1]; }
+        }
+        let s = new Stack();
+        s.push(1, 2);
+        let e = new MyError('boom');
+        [e instanceof Error, e instanceof MyError, e.message, String(e), s.peek(), s.length,
+            Array.isArray(s)].join();
+    "#;
+    assert_eq!(eval_string(source), "true,true,boom,MyError: boom,2,2,true");
+}
+
+#[test]
+fn private_members() {
+    let source = r#"
+        class Account {
+            #balance = 0;
+            static #count = 0;
+            constructor(amount) {
+                this.#deposit(amount);
+                Account.#count++;
+            }
+            #deposit(amount) {
+                this.#balance += amount;
+            }
+            get #doubled() { return this.#balance * 2; }
+            set #doubled(value) { this.#balance = value / 2; }
+            report(other) {
+                this.#doubled = this.#doubled + 2;
+                return this.#balance + other.#balance;
+            }
+            static count() { return Account.#count; }
+        }
+        let a = new Account(10);
+        let b = new Account(5);
+        [a.report(b), Account.count(), Object.keys(a).length].join();
+    "#;
+    assert_eq!(eval_string(source), "16,2,0");
+
+    assert_eq!(
+        error_of("class A { #x = 1; static read(o) { return o.#x; } } A.read({})"),
+        "TypeError: Cannot read private member #x from an object whose class did not declare it"
+    );
+    assert_eq!(
+        error_of("class A { #m() {} constructor() { this.#m = 1; } } new A()"),
+        "TypeError: Private method is not writable"
+    );
+    assert_eq!(
+        error_of("class A { get #x() { return 1; } constructor() { this.#x = 1; } } new A()"),
+        "TypeError: '#x' was defined without a setter"
+    );
+
+    // Each evaluation of a class makes new private names
+    let source = r#"
+        function make() {
+            return class { #x = 1; static has(o) { try { o.#x; return true; } catch (e) { return false; } } };
+        }
+        let A = make();
+        let B = make();
+        [A.has(new A()), A.has(new B())].join();
+    "#;
+    assert_eq!(eval_string(source), "true,false");
+
+    for source in [
+        "this.#x",
+        "class A { m() { return this.#y; } }",
+        "class A { #x; #x; }",
+        "class A { #x; m() { delete this.#x; } }",
+        "class A { constructor() {} constructor() {} }",
+    ] {
+        assert!(Parser::new(source).parse_program().is_err(), "{}", source);
+    }
+}
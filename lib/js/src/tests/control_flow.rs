@@ -0,0 +1,175 @@
+use super::*;
+
+#[test]
+fn if_else() {
+    assert_eq!(
+        eval_number("let x = 3; if (x > 2) x = 1; else x = 2; x;"),
+        1.0
+    );
+    assert_eq!(
+        eval_number("let x = 0; if (x) { x = 1 } else if (!x) { x = 2 } x;"),
+        2.0
+    );
+    assert!(eval("if (false) 1;").is_undefined());
+}
+
+#[test]
+fn while_and_do_while() {
+    assert_eq!(eval_number("let i = 0; while (i < 10) i++; i;"), 10.0);
+    assert_eq!(
+        eval_number("let i = 10; do { i++ } while (i < 5); i;"),
+        11.0
+    );
+    assert_eq!(
+        eval_number("let i = 0; while (true) { if (++i == 3) break; } i;"),
+        3.0
+    );
+}
+
+#[test]
+fn for_loop() {
+    let source = r#"
+        var sum = 0;
+        for (var i = 0; i < 10; i++) {
+            if (i % 2) continue;
+            sum += i;
+        }
+        sum * 100 + i;
+    "#;
+    assert_eq!(eval_number(source), 2010.0);
+    assert_eq!(
+        eval_number("let n = 0; for (;;) { if (n++ > 4) break; } n;"),
+        6.0
+    );
+}
+
+#[test]
+fn for_let_binding_per_iteration() {
+    let source = r#"
+        var fns = {};
+        var f0, f1, f2;
+        for (let i = 0; i < 3; i++) {
+            if (i == 0) f0 = function () { return i; };
+            if (i == 1) f1 = function () { return i; };
+            if (i == 2) f2 = function () { return i; };
+        }
+        f0() * 100 + f1() * 10 + f2();
+    "#;
+    assert_eq!(eval_number(source), 12.0);
+}
+
+#[test]
+#[should_panic(expected = "ReferenceError")]
+fn for_let_is_scoped_to_the_loop() {
+    eval("for (let i = 0; i < 1; i++) {} i;");
+}
+
+#[test]
+fn labelled_break_and_continue() {
+    let source = r#"
+        let count = 0;
+        outer: for (let i = 0; i < 5; i++) {
+            for (let j = 0; j < 5; j++) {
+                if (j == 2) continue outer;
+                if (i == 3) break outer;
+                count++;
+            }
+        }
+        count;
+    "#;
+    assert_eq!(eval_number(source), 6.0);
+    assert_eq!(
+        eval_number("let x = 1; block: { x = 2; break block; x = 3; } x;"),
+        2.0
+    );
+}
+
+#[test]
+fn switch_falls_through() {
+    let source = r#"
+        function f(x) {
+            let r = "";
+            switch (x) {
+                case 1:
+                    r += "a";
+                case 2:
+                    r += "b";
+                    break;
+                default:
+                    r += "d";
+                case 3:
+                    r += "c";
+            }
+            return r;
+        }
+        f(1) + f(2) + f(3) + f(4);
+    "#;
+    assert_eq!(eval_string(source), "abbcdc");
+    assert_eq!(
+        eval_string("switch ('1') { case 1: 'loose'; break; default: 'strict' }"),
+        "strict"
+    );
+}
+
+#[test]
+fn break_and_continue_keep_the_completion_value() {
+    let cases = [
+        ("while (true) { 3; break; }", "3"),
+        ("switch (1) { case 1: 5; break; }", "5"),
+        ("a: { 1; break a; 2; }", "1"),
+        ("for (let i = 0; i < 2; i++) { i; continue; }", "1"),
+        ("do { 6; continue; } while (false)", "6"),
+        (
+            "for (const x of [7, 8]) { x; if (x == 8) break; }",
+            "undefined",
+        ),
+        ("for (const x of [7, 8]) { if (x == 8) { x; break; } }", "8"),
+        ("a: for (;;) { for (;;) { 9; break a; } }", "9"),
+        (
+            "a: for (let i = 0; i < 2; i++) { while (true) { i; continue a; } }",
+            "1",
+        ),
+        ("10; while (true) break;", "undefined"),
+        (
+            "try { 11; while (true) { break; } } finally { 12; }",
+            "undefined",
+        ),
+    ];
+    for (source, expected) in cases {
+        assert_eq!(
+            eval_with(source, |value| value.to_string()),
+            expected,
+            "{}",
+            source
+        );
+    }
+}
+
+#[test]
+fn for_in_and_for_of() {
+    assert_eq!(
+        eval_string("let s = ''; for (const k in { a: 1, b: 2, c: 3 }) s += k; s;"),
+        "abc"
+    );
+    assert_eq!(
+        eval_string("let s = ''; for (let c of 'xyz') s = c + s; s;"),
+        "zyx"
+    );
+    assert_eq!(eval_string("var k; for (k in 'ab'); k;"), "1");
+}
+
+#[test]
+fn operators() {
+    assert_eq!(
+        eval_string("typeof undeclared + typeof 1 + typeof function () {}"),
+        "undefinednumberfunction"
+    );
+    assert!(
+        eval("1 == '1' && null == undefined && !(1 === '1') && 'a' < 'b' && 2 >= 2").to_boolean()
+    );
+    assert!(eval("'a' in { a: 1 } && !('b' in { a: 1 })").to_boolean());
+    assert_eq!(eval_number("let i = 5; let j = i--; j * 10 + i;"), 54.0);
+    assert_eq!(eval_number("true ? 1 : 2"), 1.0);
+    assert_eq!(eval_number("-(2147483647 + 1)"), -2147483648.0);
+    assert_eq!(eval_string("1 / 0"), "Infinity");
+}
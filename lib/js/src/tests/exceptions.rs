@@ -0,0 +1,255 @@
+use super::*;
+
+#[test]
+fn catch_thrown_value() {
+    assert_eq!(eval_number("try { throw 42; } catch (e) { e + 1; }"), 43.0);
+    assert_eq!(
+        eval_number("let r = 0; try { r = 1; } catch (e) { r = 2; } r;"),
+        1.0
+    );
+    assert_eq!(
+        eval_string("try { throw 'x'; } catch { 'no binding'; }"),
+        "no binding"
+    );
+}
+
+#[test]
+fn catch_parameter_patterns() {
+    assert_eq!(
+        eval_string("try { null.x; } catch ({ name, message: m }) { name + ': ' + typeof m; }"),
+        "TypeError: string"
+    );
+    assert_eq!(
+        eval_number("try { throw [1, 2, 3]; } catch ([a, , b = 0, c = 4]) { a + b + c; }"),
+        8.0
+    );
+    assert_eq!(
+        eval_string("let e = 'outer'; try { throw { e: 1 }; } catch ({ e }) {} e;"),
+        "outer"
+    );
+}
+
+#[test]
+fn exceptions_unwind_functions() {
+    let source = r#"
+        function inner() {
+            throw 'deep';
+        }
+        function outer() {
+            inner();
+            return 'unreachable';
+        }
+        let r;
+        try { outer(); } catch (e) { r = e; }
+        r;
+    "#;
+    assert_eq!(eval_string(source), "deep");
+}
+
+#[test]
+fn finally_always_runs() {
+    let source = r#"
+        let log = '';
+        function f() {
+            try {
+                log += 'try ';
+                return 'returned';
+            } finally {
+                log += 'finally';
+            }
+        }
+        f() + ' ' + log;
+    "#;
+    assert_eq!(eval_string(source), "returned try finally");
+
+    let source = r#"
+        let log = '';
+        try {
+            try { throw 1; } finally { log += 'inner '; }
+        } catch (e) {
+            log += 'outer ' + e;
+        }
+        log;
+    "#;
+    assert_eq!(eval_string(source), "inner outer 1");
+}
+
+#[test]
+fn finally_overrides_abrupt_completion() {
+    assert_eq!(
+        eval_number("function f() { try { return 1; } finally { return 2; } } f();"),
+        2.0
+    );
+    assert_eq!(
+        eval_number("function f() { try { throw 1; } finally { return 3; } } f();"),
+        3.0
+    );
+    assert_eq!(
+        eval_number("let i = 0; while (true) { try { i++; throw 0; } finally { break; } } i;"),
+        1.0
+    );
+}
+
+#[test]
+fn runtime_errors_are_catchable() {
+    assert_eq!(
+        eval_string("try { undeclared; } catch (e) { e; }"),
+        "ReferenceError: undeclared is not defined"
+    );
+    assert_eq!(
+        eval_string("const c = 1; try { c = 2; } catch (e) { e; }"),
+        "TypeError: Assignment to constant variable."
+    );
+    assert_eq!(
+        eval_string("try { let x = 1; x(); } catch (e) { e; }"),
+        "TypeError: 1 is not a function"
+    );
+    assert_eq!(
+        eval_string("try { for (let x of 5) {} } catch (e) { e; }"),
+        "TypeError: 5 is not iterable"
+    );
+}
+
+#[test]
+fn error_constructors() {
+    assert_eq!(eval_string("Error('boom')"), "Error: boom");
+    assert_eq!(eval_string("RangeError()"), "RangeError");
+    assert_eq!(
+        eval_string("try { throw SyntaxError('bad'); } catch (e) { e; }"),
+        "SyntaxError: bad"
+    );
+    assert_eq!(eval_string("typeof TypeError"), "function");
+}
+
+#[test]
+fn error_stack_has_call_sites() {
+    let source = r#"
+function fail() {
+    throw Error('oops');
+}
+function run() {
+    fail();
+}
+let stack;
+try { run(); } catch (e) { stack = e; }
+stack;
+"#;
+    let stack = eval_with(source, |error| match error {
+        JsValue::Object(obj) => obj.get_data_property("stack").to_string(),
+        _ => panic!("expected an error object"),
+    });
+    let lines: Vec<_> = stack.lines().collect();
+    assert_eq!(lines[0], "Error: oops");
+    assert!(lines[1].starts_with("    at fail (3:"), "{}", stack);
+    assert!(lines[2].starts_with("    at run (6:"), "{}", stack);
+    assert!(lines[3].starts_with("    at <anonymous> (9:"), "{}", stack);
+}
+
+#[test]
+fn error_stack_without_call_site() {
+    // Nothing in `fail` records a position before the member access throw
+    let source = r#"
+function fail() {
+    return null.x;
+}
+let stack;
+try { fail(); } catch (e) { stack = e; }
+stack;
+"#;
+    let stack = eval_with(source, |error| match error {
+        JsValue::Object(obj) => obj.get_data_property("stack").to_string(),
+        _ => panic!("expected an error object"),
+    });
+    let lines: Vec<_> = stack.lines().collect();
+    assert_eq!(lines[1], "    at fail", "{}", stack);
+    assert!(lines[2].starts_with("    at <anonymous> (6:"), "{}", stack);
+    assert!(!stack.contains("(0:0)"), "{}", stack);
+}
+
+#[test]
+#[should_panic(expected = "Uncaught TypeError")]
+fn uncaught_exception() {
+    eval("null();");
+}
+
+#[test]
+fn runaway_recursion_throws() {
+    assert_eq!(
+        eval_string("function f() { return f(); } try { f(); } catch (e) { e; }"),
+        "RangeError: Maximum call stack size exceeded"
+    );
+    assert_eq!(
+        eval_string("function f(n) { return n && 1 + f(n - 1); } f(50);"),
+        "50"
+    );
+}
+
+#[test]
+fn syntax_errors_at_the_end_of_the_source_are_incomplete_input() {
+    let error = |source| match Parser::new(source).parse_program() {
+        Ok(_) => panic!("{} parsed", source),
+        Err(error) => (error.is_incomplete(), error.to_string()),
+    };
+    for source in &[
+        "function f() {",
+        "1 +",
+        "if (a)",
+        "[1, 2",
+        "`a${b}",
+        "f(\n1,",
+        "/* c",
+        "1; /* never closed",
+        "let x = 1 /*",
+    ] {
+        assert!(error(source).0, "{}", source);
+    }
+    assert!(!error("let x = ;").0);
+    assert!(!error("1 +;").0);
+    assert!(!error("let let = 1;").0);
+    assert!(!error("/* */ let x = ;").0);
+    assert_eq!(error("({").1, "SyntaxError at 1:3: Unexpected end of input");
+}
+
+#[test]
+fn syntax_error_messages() {
+    let error = |source| match Parser::new(source).parse_program() {
+        Ok(_) => panic!("{} parsed", source),
+        Err(error) => error.to_string(),
+    };
+    assert_eq!(
+        error("let x = ;"),
+        "SyntaxError at 1:9: Unexpected token ';'"
+    );
+    assert_eq!(
+        error("if (1 {}"),
+        "SyntaxError at 1:7: Expected ')' but found '{'"
+    );
+    assert_eq!(
+        error("[1 2]"),
+        "SyntaxError at 1:4: Expected ']' but found '2'"
+    );
+    assert_eq!(
+        error("try {} x"),
+        "SyntaxError at 1:8: Expected 'catch' but found 'x'"
+    );
+    assert_eq!(
+        error("let 1 = 2;"),
+        "SyntaxError at 1:5: Expected an identifier but found '1'"
+    );
+    assert_eq!(
+        error("x = ✓;"),
+        "SyntaxError at 1:5: Invalid or unexpected token '✓'"
+    );
+    assert_eq!(
+        error("'abc\n"),
+        "SyntaxError at 1:1: Unterminated string literal"
+    );
+    assert_eq!(
+        error("'\\xZZ';"),
+        "SyntaxError at 1:1: Invalid hexadecimal escape sequence"
+    );
+    assert_eq!(
+        error("`a${1}\\u{110000}`;"),
+        "SyntaxError at 1:7: Invalid Unicode escape sequence"
+    );
+}
@@ -0,0 +1,195 @@
+use super::*;
+
+#[test]
+fn closure_keeps_its_scope() {
+    let source = r#"
+        function counter() {
+            let count = 0;
+            return function () {
+                count += 1;
+                return count;
+            };
+        }
+
+        let a = counter();
+        let b = counter();
+        a();
+        a();
+        b();
+        a() * 10 + b();
+    "#;
+
+    assert_eq!(eval_number(source), 32.0);
+}
+
+#[test]
+fn arguments_are_bound_to_params() {
+    assert_eq!(
+        eval_number("function f(a, b) { return a * b; } f(6, 7);"),
+        42.0
+    );
+    assert!(eval("function f(a, b) { return b; } f(1);").is_undefined());
+    assert!(eval("function f() { return; } f();").is_undefined());
+    assert!(eval("function f() { 1; } f();").is_undefined());
+}
+
+#[test]
+fn declarations_are_hoisted() {
+    assert_eq!(
+        eval_number("let r = f(); function f() { return 5; } r;"),
+        5.0
+    );
+    assert!(eval("function f() { let r = x; var x = 1; return r; } f();").is_undefined());
+}
+
+#[test]
+fn block_scoping() {
+    let source = r#"
+        let x = 1;
+        var y = 1;
+        {
+            let x = 2;
+            var y = 2;
+            x = 3;
+        }
+        x * 10 + y;
+    "#;
+
+    assert_eq!(eval_number(source), 12.0);
+}
+
+#[test]
+fn named_function_expression_sees_itself() {
+    let is_function = eval_with(
+        "let f = function g() { return g; }; f();",
+        |value| matches!(value, JsValue::Object(obj) if obj.is_function()),
+    );
+    assert!(is_function);
+}
+
+#[test]
+#[should_panic(expected = "ReferenceError")]
+fn temporal_dead_zone() {
+    eval("{ x; let x = 1; }");
+}
+
+#[test]
+#[should_panic(expected = "TypeError: Assignment to constant variable.")]
+fn const_is_immutable() {
+    eval("const x = 1; x = 2;");
+}
+
+#[test]
+#[should_panic(expected = "ReferenceError: y is not defined")]
+fn unknown_variable() {
+    eval("function f() { let y = 1; } f(); y;");
+}
+
+#[test]
+fn arrow_functions() {
+    assert_eq!(eval_number("let f = x => x * 2; f(21);"), 42.0);
+    assert_eq!(eval_number("((a, b) => { return a + b; })(40, 2);"), 42.0);
+    assert_eq!(eval_number("(() => ({ a: 42 }))().a;"), 42.0);
+    assert_eq!(
+        eval_number("[1, 2, 3].map(x => x * x).reduce((a, b) => a + b);"),
+        14.0
+    );
+}
+
+#[test]
+fn arrow_functions_see_the_this_of_their_scope() {
+    let source = r#"
+        let obj = {
+            value: 42,
+            method() {
+                let arrow = () => this.value;
+                return arrow.call({ value: 0 });
+            },
+        };
+        obj.method();
+    "#;
+    assert_eq!(eval_number(source), 42.0);
+    assert!(eval("function f() { return this; } f();").is_undefined());
+    assert!(eval("(() => this)() === globalThis;").to_boolean());
+}
+
+#[test]
+fn default_and_rest_parameters() {
+    assert_eq!(
+        eval_number("function f(a, b = a + 1) { return b; } f(1);"),
+        2.0
+    );
+    assert_eq!(
+        eval_number("function f(a, b = 5) { return b; } f(1, undefined);"),
+        5.0
+    );
+    assert_eq!(
+        eval_number("function f(a, ...rest) { return rest.length; } f(1, 2, 3);"),
+        2.0
+    );
+    assert_eq!(eval_number("function f(a, b = 1, c) {} f.length;"), 1.0);
+    assert_eq!(eval_number("((...args) => args.length).length;"), 0.0);
+}
+
+#[test]
+fn destructuring() {
+    let source = r#"
+        let { a, b: { c }, d = 4, ...rest } = { a: 1, b: { c: 3 }, e: 5, f: 6 };
+        const [x, , y = 7, ...others] = [10, 20, undefined, 30, 40];
+        a + c + d + Object.keys(rest).length + x + y + others.length;
+    "#;
+    assert_eq!(
+        eval_number(source),
+        1.0 + 3.0 + 4.0 + 2.0 + 10.0 + 7.0 + 2.0
+    );
+
+    let source = r#"
+        function f({ name }, [first, second] = 'ab') { return name + first + second; }
+        let result = '';
+        for (const [key, value] of [['x', 1], ['y', 2]]) {
+            result += key + value;
+        }
+        f({ name: 'n' }) + result;
+    "#;
+    assert_eq!(eval_string(source), "nabx1y2");
+}
+
+#[test]
+#[should_panic(expected = "TypeError: Cannot destructure 'undefined' as it is undefined.")]
+fn destructuring_undefined() {
+    eval("let { a } = undefined;");
+}
+
+#[test]
+fn template_literals() {
+    assert_eq!(eval_string("let a = 1; `a${a} + ${a + 1}\\n`;"), "a1 + 2\n");
+    assert_eq!(
+        eval_string("`outer ${`inner ${{ a: 1 }.a}`}`;"),
+        "outer inner 1"
+    );
+
+    let source = r#"
+        function tag(strings, ...values) {
+            return strings.raw.join('|') + values.join(',') + strings.length;
+        }
+        tag`a${1}\n${2}`;
+    "#;
+    assert_eq!(eval_string(source), "a|\\n|1,23");
+
+    let source = r#"
+        function tag(strings) {
+            return [typeof strings[0], strings.raw[0], typeof strings[1]].join('|');
+        }
+        tag`\unicode${1}\07`;
+    "#;
+    assert_eq!(eval_string(source), "undefined|\\unicode|undefined");
+
+    let source = r#"
+        let tag = strings => strings;
+        let f = () => tag`a${1}b`;
+        let g = () => tag`a${1}b`;
+        let t = f();
+        [t === f(), t === g(), Object.isFrozen(t), Object.isFrozen(t.raw)].join();
+    "#;
+    assert_eq!(eval_string(source), "true,false,true,true");
+}
@@ -0,0 +1,321 @@
+use std::sync::{Arc, Mutex};
+
+use super::*;
+use crate::{
+    gc::{GcCell, Trace},
+    jsrt::JsObject,
+    vm::EnvironmentRecordKind,
+};
+
+/// Run `source` with the VM in `context`, keeping the runtime alive
+fn run(context: &mut Context, source: &str) -> String {
+    let program = Parser::new(source)
+        .parse_program()
+        .unwrap_or_else(|e| panic!("{}", e));
+    let code = Compiler::compile_program(&program).unwrap_or_else(|e| panic!("{}", e));
+    describe(&Interpreter::run(context, &code))
+}
+
+fn heap_size(runtime: &Arc<Mutex<Runtime>>) -> usize {
+    runtime.lock().unwrap().heap().size()
+}
+
+#[test]
+fn reachable_cells_survive() {
+    let mut context = Context::new(Runtime::new());
+    run(
+        &mut context,
+        r#"
+            var a = { b: { c: [1, { d: 'x' }] } };
+            var s = Symbol('s');
+            a[s] = { e: 2 };
+            // Only reachable through a symbol used as a key
+            let hidden = {};
+            hidden[Symbol('hidden')] = 3;
+            var holder = hidden;
+            var head = null;
+            for (let i = 0; i < 10000; i++) head = { next: head, i: i };
+        "#,
+    );
+    context.collect_garbage();
+
+    let source = r#"
+        let length = 0;
+        for (let node = head; node; node = node.next) length++;
+        [
+            a.b.c[1].d, a[s].e, Object.getOwnPropertySymbols(a)[0] === s,
+            Object.getOwnPropertySymbols(holder)[0].toString(), length,
+        ].join(' ');
+    "#;
+    assert_eq!(
+        run(&mut context, source),
+        "string x 2 true Symbol(hidden) 10000"
+    );
+}
+
+#[test]
+fn garbage_is_freed() {
+    let runtime = Runtime::new();
+    let mut context = Context::new(runtime.clone());
+    context.collect_garbage();
+    let before = heap_size(&runtime);
+
+    run(
+        &mut context,
+        "function f() { for (let i = 0; i < 1000; i++) { let o = { i: [i] }; } } f();",
+    );
+    let grown = heap_size(&runtime);
+    context.collect_garbage();
+    let after = heap_size(&runtime);
+
+    assert!(grown > before + 1000, "{} {}", before, grown);
+    assert!(after < before + 1024, "{} {}", before, after);
+}
+
+#[test]
+fn handles_keep_values_alive() {
+    let runtime = Runtime::new();
+    let mut context = Context::new(runtime.clone());
+    context.collect_garbage();
+    let before = heap_size(&runtime);
+
+    let outer = JsObject::new(&mut context);
+    let outer = context.root(outer);
+    let inner = JsObject::new(&mut context);
+    (*outer)
+        .clone()
+        .create_data_property("inner".into(), JsValue::object(inner));
+    context.collect_garbage();
+    assert!(matches!(
+        outer.get_data_property("inner"),
+        JsValue::Object(_)
+    ));
+
+    let copy = outer.clone();
+    drop(outer);
+    context.collect_garbage();
+    assert!(matches!(
+        copy.get_data_property("inner"),
+        JsValue::Object(_)
+    ));
+
+    drop(copy);
+    context.collect_garbage();
+    assert_eq!(heap_size(&runtime), before);
+}
+
+#[test]
+fn old_cells_are_remembered() {
+    let runtime = Runtime::new();
+    let mut context = Context::new(runtime.clone());
+    run(&mut context, "var holder = {}; var list = [];");
+    // Everything left is old now
+    context.collect_garbage();
+
+    run(
+        &mut context,
+        "holder.child = { v: 1 }; list.push({ v: 2 }); var young = { v: 3 };",
+    );
+    runtime.lock().unwrap().collect_young_garbage();
+    assert_eq!(
+        run(&mut context, "holder.child.v + list[0].v + young.v;"),
+        "number 6"
+    );
+}
+
+#[test]
+fn minor_collections_run_on_their_own() {
+    let runtime = Runtime::new();
+    let mut context = Context::new(runtime.clone());
+    context.collect_garbage();
+    let before = runtime.lock().unwrap().heap_stats();
+
+    run(
+        &mut context,
+        "var kept = []; function f() { for (let i = 0; i < 20000; i++) { let o = { i: i }; if (i % 1000 == 0) kept.push(o); } } f();",
+    );
+    let after = runtime.lock().unwrap().heap_stats();
+    let young = after.young_collections - before.young_collections;
+    // The loop releases each object, the heap never grows enough to need a full collection
+    assert!(young > 0);
+    assert!(after.collections - before.collections < young / 8);
+    assert_eq!(
+        run(&mut context, "kept.length + kept[19].i;"),
+        "number 19020"
+    );
+}
+
+#[test]
+fn weak_refs_are_cleared() {
+    let mut context = Context::new(Runtime::new());
+    // The targets are kept alive until the script is over
+    let source = r#"
+        var strong = { v: 2 };
+        var refs = [WeakRef({ v: 1 }), WeakRef(strong)];
+        refs[0].deref().v + refs[1].deref().v;
+    "#;
+    assert_eq!(run(&mut context, source), "number 3");
+
+    context.collect_garbage();
+    assert_eq!(
+        run(&mut context, "[refs[0].deref(), refs[1].deref().v].join();"),
+        "string ,2"
+    );
+}
+
+#[test]
+fn weak_maps_hold_values_through_their_keys() {
+    let runtime = Runtime::new();
+    let mut context = Context::new(runtime.clone());
+    run(&mut context, "var map = WeakMap(); var key = {};");
+    context.collect_garbage();
+    let before = heap_size(&runtime);
+
+    // The second key is only reachable from the value of the first, the others only from
+    // their own values
+    let source = r#"
+        let second = {};
+        map.set(key, second).set(second, { v: 5 });
+        for (let i = 0; i < 100; i++) {
+            let dead = {};
+            map.set(dead, { key: dead, data: [i] });
+        }
+    "#;
+    run(&mut context, source);
+    context.collect_garbage();
+
+    assert!(heap_size(&runtime) < before + 1024);
+    assert_eq!(run(&mut context, "map.get(map.get(key)).v;"), "number 5");
+}
+
+#[test]
+fn finalization_registries_call_back() {
+    let mut context = Context::new(Runtime::new());
+    let source = r#"
+        var log = [];
+        var registry = FinalizationRegistry(function (held) { log.push(held); });
+        var kept = {}, token = {};
+        registry.register({}, 'a');
+        registry.register(kept, 'b');
+        registry.register({}, 'c', token);
+        registry.unregister(token);
+    "#;
+    run(&mut context, source);
+    context.collect_garbage();
+
+    // The callbacks run once the next script is over
+    assert_eq!(run(&mut context, "log.length;"), "number 0");
+    assert_eq!(run(&mut context, "log.join();"), "string a");
+}
+
+#[test]
+fn heap_stats() {
+    struct Big([u8; 10000]);
+    unsafe impl Trace for Big {}
+    impl GcCell for Big {}
+
+    let runtime = Runtime::new();
+    let mut context = Context::new(runtime.clone());
+    let big = context.allocate(Big([7; 10000]));
+    let big = context.root(big);
+    context.collect_garbage();
+
+    let stats = runtime.lock().unwrap().heap_stats();
+    assert_eq!(stats.large_objects, 1);
+    assert!(stats.large_object_bytes > 10000);
+    assert_eq!(stats.bytes(), heap_size(&runtime));
+    for class in stats.size_classes.iter() {
+        assert!(
+            class.cells <= class.capacity && class.blocks > 0,
+            "{:?}",
+            class
+        );
+    }
+    // Objects don't waste a quarter of their cell
+    let object_size = std::mem::size_of::<JsObject>() + std::mem::size_of::<crate::gc::Cell>();
+    let objects = stats
+        .size_classes
+        .iter()
+        .find(|class| class.cell_size >= object_size)
+        .unwrap();
+    assert!(objects.cell_size * 3 < object_size * 4);
+    assert!(objects.occupancy() > 0.0);
+    assert_eq!(big.0[9999], 7);
+
+    drop(big);
+    context.collect_garbage();
+    let after = runtime.lock().unwrap().heap_stats();
+    assert_eq!(after.large_objects, 0);
+    assert_eq!(after.collections, stats.collections + 1);
+}
+
+#[test]
+fn contexts_root_their_scope() {
+    let runtime = Runtime::new();
+    let mut first = Context::new(runtime.clone());
+    let mut second = Context::new(runtime);
+    first.push_environment(EnvironmentRecordKind::Declarative);
+    run(&mut first, "let x = { y: 1 };");
+
+    second.collect_garbage();
+    assert_eq!(run(&mut first, "x.y;"), "number 1");
+}
+
+#[test]
+fn stress_mode() {
+    let source = r#"
+        function counter() {
+            let n = 0;
+            return function () { return ++n; };
+        }
+        let c = counter();
+        c();
+        let parts = [];
+        for (let x of [1, 2, 3].map(function (x) { return { v: x * c() }; })) parts.push(x.v);
+        try {
+            throw TypeError('t');
+        } catch (e) {
+            parts.push(e.message);
+        }
+        let o = {};
+        o[Symbol.toPrimitive] = function () { return 'p'; };
+        parts.push(o + '', JSON.stringify({ a: [1, { b: 2 }] }), 2n ** 70n);
+        parts.join(' ');
+    "#;
+    let expected = "string 2 6 12 t p {\"a\":[1,{\"b\":2}]} 1180591620717411303424";
+    let stressed = || {
+        let runtime = Runtime::new();
+        runtime.lock().unwrap().heap().set_stress(true);
+        Context::new(runtime)
+    };
+
+    let program = Parser::new(source).parse_program().unwrap();
+    assert_eq!(describe(&program.eval(&mut stressed())), expected);
+    assert_eq!(run(&mut stressed(), source), expected);
+}
+
+#[test]
+fn calls_keep_the_scope_of_a_released_loop_iteration() {
+    // Each iteration releases the roots of the previous ones, its `let` scope is then only
+    // rooted as the current one while a call replaces it
+    let source = r#"
+        function id(n) { return n; }
+        let s = 0;
+        for (let i = 0; i < 300; i++) s += id(i);
+        for (let i = 0; i < 300; i++) s += Function('a', 'return a')(1) + i;
+        let fs = [];
+        for (let i = 0; i < 300; i++) fs.push(function () { return i; });
+        function* g() { yield 1; }
+        for (let i = 0; i < 300; i++) for (const v of g()) s += v;
+        s + fs[299]();
+    "#;
+    let stressed = || {
+        let runtime = Runtime::new();
+        runtime.lock().unwrap().heap().set_stress(true);
+        Context::new(runtime)
+    };
+
+    let program = Parser::new(source).parse_program().unwrap();
+    assert_eq!(describe(&program.eval(&mut stressed())), "number 90599");
+    assert_eq!(run(&mut stressed(), source), "number 90599");
+}
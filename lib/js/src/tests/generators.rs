@@ -0,0 +1,171 @@
+use super::*;
+
+#[test]
+fn generators_yield_values_lazily() {
+    let source = r#"
+        let log = [];
+        function* count(n) {
+            log.push('start');
+            for (let i = 0; i < n; i++) {
+                yield i;
+            }
+            return 'done';
+        }
+        let it = count(2);
+        log.push('created');
+        let results = [it.next(), it.next(), it.next(), it.next()]
+            .map(r => r.value + ':' + r.done);
+        log.join() + ' ' + results.join();
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "created,start 0:false,1:false,done:true,undefined:true"
+    );
+
+    assert_eq!(
+        eval_string("function* g() { yield 1; yield 2; yield 3; } [...g()].join();"),
+        "1,2,3"
+    );
+    assert_eq!(
+        eval_number("let s = 0; for (let x of (function* () { yield 4; yield 5; })()) s += x; s;"),
+        9.0
+    );
+    assert_eq!(
+        eval_string("let [a, , b] = (function* () { yield 1; yield 2; yield 3; })(); a + ' ' + b;"),
+        "1 3"
+    );
+}
+
+#[test]
+fn next_passes_values_in() {
+    let source = r#"
+        function* g() {
+            let a = yield 'first';
+            let b = yield a * 2;
+            return a + b;
+        }
+        let it = g();
+        [it.next('ignored').value, it.next(5).value, it.next(7).value].join();
+    "#;
+    assert_eq!(eval_string(source), "first,10,12");
+}
+
+#[test]
+fn throw_and_return_resume_abruptly() {
+    let source = r#"
+        let log = [];
+        function* g() {
+            try {
+                yield 1;
+                yield 2;
+            } catch (e) {
+                log.push('caught ' + e);
+                yield 3;
+            } finally {
+                log.push('finally');
+            }
+        }
+        let it = g();
+        it.next();
+        let thrown = it.throw('x');
+        let returned = it.return(9);
+        log.join() + ' ' + thrown.value + ' ' + returned.value + ':' + returned.done
+            + ' ' + it.next().done;
+    "#;
+    assert_eq!(eval_string(source), "caught x,finally 3 9:true true");
+
+    // A generator which hasn't started completes without running its body
+    let source = r#"
+        let ran = false;
+        function* g() { ran = true; yield 1; }
+        let it = g();
+        let r = it.return(4);
+        r.value + ' ' + r.done + ' ' + ran;
+    "#;
+    assert_eq!(eval_string(source), "4 true false");
+    assert_eq!(
+        eval_string("function* g() { yield 1; } try { g().throw(new Error('e')); } catch (e) { e.message; }"),
+        "e"
+    );
+
+    // Breaking out of a loop returns the generator
+    let source = r#"
+        let closed = false;
+        function* g() { try { yield 1; yield 2; } finally { closed = true; } }
+        for (let x of g()) break;
+        closed;
+    "#;
+    assert_eq!(eval_string(source), "true");
+}
+
+#[test]
+fn yield_star_delegates() {
+    let source = r#"
+        function* inner() { let x = yield 'a'; yield x; return 'r'; }
+        function* outer() { let r = yield* inner(); yield r; yield* [1, 2]; }
+        let it = outer();
+        [it.next().value, it.next('b').value, it.next().value, it.next().value,
+            it.next().value, it.next().done].join();
+    "#;
+    assert_eq!(eval_string(source), "a,b,r,1,2,true");
+
+    let source = r#"
+        let log = [];
+        function* inner() { try { yield 1; } finally { log.push('inner'); } }
+        function* outer() { try { yield* inner(); } finally { log.push('outer'); } }
+        let it = outer();
+        it.next();
+        it.return(5).value + ' ' + log.join();
+    "#;
+    assert_eq!(eval_string(source), "5 inner,outer");
+}
+
+#[test]
+fn generator_methods_and_objects() {
+    let source = r#"
+        class Tree {
+            constructor(items) { this.items = items; }
+            *[Symbol.iterator]() { yield* this.items; }
+        }
+        let o = { *pairs() { yield 'a'; yield 'b'; } };
+        [...new Tree([1, 2])].join() + ' ' + [...o.pairs()].join();
+    "#;
+    assert_eq!(eval_string(source), "1,2 a,b");
+
+    let source = r#"
+        function* g() {}
+        let it = g();
+        let proto = Object.getPrototypeOf(g);
+        [
+            Object.getPrototypeOf(it) === g.prototype,
+            it[Symbol.iterator]() === it,
+            Object.prototype.toString.call(it),
+            proto.prototype === Object.getPrototypeOf(g.prototype),
+        ].join();
+    "#;
+    assert_eq!(eval_string(source), "true,true,[object Generator],true");
+}
+
+#[test]
+#[should_panic(expected = "TypeError")]
+fn generators_are_not_constructors() {
+    eval("function* g() {} new g();");
+}
+
+#[test]
+#[should_panic(expected = "TypeError: Generator is already running")]
+fn running_generators_cant_be_resumed() {
+    eval("let it; function* g() { it.next(); } it = g(); it.next();");
+}
+
+#[test]
+fn yield_is_only_an_expression_in_generators() {
+    for source in [
+        "function f() { yield 1; }",
+        "function* g() { function f() { yield 1; } }",
+        "function* g() { let f = () => yield 1; }",
+        "async function* f() {}",
+    ] {
+        assert!(Parser::new(source).parse_program().is_err(), "{}", source);
+    }
+}
@@ -0,0 +1,126 @@
+use super::*;
+use crate::jsrt::inspect;
+
+/// `inspect` of the completion value of `source`
+fn inspect_source(source: &str) -> String {
+    let program = Parser::new(source)
+        .parse_program()
+        .unwrap_or_else(|e| panic!("{}", e));
+    let code = Compiler::compile_program(&program).unwrap_or_else(|e| panic!("{}", e));
+    let mut context = Context::new(Runtime::new());
+    match Interpreter::run(&mut context, &code) {
+        Ok(value) => inspect(&context, &value),
+        result => panic!("{}", describe(&result)),
+    }
+}
+
+#[test]
+fn primitives() {
+    assert_eq!(inspect_source("undefined"), "undefined");
+    assert_eq!(inspect_source("null"), "null");
+    assert_eq!(inspect_source("-0"), "-0");
+    assert_eq!(inspect_source("0.5 + 1"), "1.5");
+    assert_eq!(inspect_source("10n ** 20n"), "100000000000000000000n");
+    assert_eq!(inspect_source("Symbol('a')"), "Symbol(a)");
+    assert_eq!(inspect_source("'a\\nb'"), "'a\\nb'");
+    assert_eq!(inspect_source(r#""it's""#), r#""it's""#);
+    assert_eq!(inspect_source(r#"`'"`"#), r#"`'"`"#);
+    assert_eq!(inspect_source(r#"`'"\``"#), r#"'\'"`'"#);
+}
+
+#[test]
+fn objects_and_arrays() {
+    assert_eq!(inspect_source("({})"), "{}");
+    assert_eq!(
+        inspect_source("var o = { a: 1, 'b-c': 'd' }; o[Symbol('e')] = [1, , 3]; o"),
+        "{ a: 1, 'b-c': 'd', [Symbol(e)]: [ 1, <1 empty item>, 3 ] }"
+    );
+    assert_eq!(inspect_source("[]"), "[]");
+    assert_eq!(
+        inspect_source("var a = [1]; a.length = 4; a.x = 2; a"),
+        "[ 1, <3 empty items>, x: 2 ]"
+    );
+    assert_eq!(
+        inspect_source("({ a: { b: { c: { d: {} } }, e: [[[[]]]] } })"),
+        "{ a: { b: { c: [Object] }, e: [ [Array] ] } }"
+    );
+    assert_eq!(
+        inspect_source(
+            "var o = { get a() {}, set b(v) {}, get c() {}, set c(v) {} }; o.self = o; o"
+        ),
+        "{ a: [Getter], b: [Setter], c: [Getter/Setter], self: [Circular] }"
+    );
+    assert_eq!(
+        inspect_source("Object.create(null)"),
+        "[Object: null prototype] {}"
+    );
+    assert_eq!(
+        inspect_source("class A { constructor() { this.x = 1; } } new A()"),
+        "A { x: 1 }"
+    );
+    assert!(
+        inspect_source("var a = []; for (var i = 0; i < 150; i++) a.push(i); a")
+            .ends_with("99,\n  ... 50 more items\n]")
+    );
+}
+
+#[test]
+fn long_objects_break_lines() {
+    assert_eq!(
+        inspect_source("({ first: 'a'.repeat(30), second: { third: 'b'.repeat(70) } })"),
+        format!(
+            "{{\n  first: '{}',\n  second: {{\n    third: '{}'\n  }}\n}}",
+            "a".repeat(30),
+            "b".repeat(70)
+        )
+    );
+}
+
+#[test]
+fn functions_and_classes() {
+    assert_eq!(inspect_source("(function f() {})"), "[Function: f]");
+    assert_eq!(inspect_source("(() => {})"), "[Function (anonymous)]");
+    assert_eq!(inspect_source("Math.max"), "[Function: max]");
+    assert_eq!(
+        inspect_source("(function* g() {})"),
+        "[GeneratorFunction: g]"
+    );
+    assert_eq!(
+        inspect_source("(async function f() {})"),
+        "[AsyncFunction: f]"
+    );
+    assert_eq!(inspect_source("class A {} A"), "[class A]");
+    assert_eq!(
+        inspect_source("class A {} (class B extends A {})"),
+        "[class B extends A]"
+    );
+    assert_eq!(
+        inspect_source("function f() {} f.x = 1; f"),
+        "[Function: f] { x: 1 }"
+    );
+}
+
+#[test]
+fn builtin_objects() {
+    assert!(inspect_source("new Error('boom')").starts_with("Error: boom\n    at "));
+    assert_eq!(inspect_source("/a+b/gi"), "/a+b/gi");
+    assert_eq!(inspect_source("new Number(-0)"), "[Number: -0]");
+    assert_eq!(inspect_source("new String('ab')"), "[String: 'ab']");
+    assert_eq!(inspect_source("Promise.resolve(1)"), "Promise { 1 }");
+    assert_eq!(
+        inspect_source("new Promise(() => {})"),
+        "Promise { <pending> }"
+    );
+    assert_eq!(
+        inspect_source("Promise.reject(2)"),
+        "Promise { <rejected> 2 }"
+    );
+    assert_eq!(
+        inspect_source("new WeakMap()"),
+        "WeakMap { <items unknown> }"
+    );
+    assert_eq!(
+        inspect_source("[][Symbol.iterator]()"),
+        "Object [Array Iterator] {}"
+    );
+}
@@ -0,0 +1,139 @@
+use super::*;
+use crate::parser::{
+    lexer::Lexer,
+    token::{InvalidEscape, Token, TokenKind},
+};
+
+fn string_value(literal: &str) -> Result<String, InvalidEscape> {
+    Token::new(TokenKind::StringLiteral, literal, "", 1, 0).string_value()
+}
+
+fn template_value(literal: &str) -> Result<String, InvalidEscape> {
+    Token::new(TokenKind::TemplateLiteralString, literal, "", 1, 0).template_value()
+}
+
+#[test]
+fn simple_escapes() {
+    assert_eq!(
+        string_value(r"'a\nb\tc\\d\'e'"),
+        Ok("a\nb\tc\\d'e".to_string())
+    );
+    assert_eq!(
+        string_value(r"'\b\f\v\r\0'"),
+        Ok("\u{8}\u{c}\u{b}\r\0".to_string())
+    );
+    assert_eq!(string_value(r#""\q\"""#), Ok("q\"".to_string()));
+}
+
+#[test]
+fn hex_escapes() {
+    assert_eq!(string_value(r"'\x41\x7a\xe9'"), Ok("Az\u{e9}".to_string()));
+    assert_eq!(string_value(r"'\x4'"), Err(InvalidEscape::Hexadecimal));
+}
+
+#[test]
+fn unicode_escapes() {
+    assert_eq!(
+        string_value(r"'\u0041\u00e9\u2713'"),
+        Ok("A\u{e9}\u{2713}".to_string())
+    );
+    assert_eq!(
+        string_value(r"'\u{41}\u{1F600}\u{000000e9}'"),
+        Ok("A\u{1F600}\u{e9}".to_string())
+    );
+    assert_eq!(string_value(r"'\uD83D\uDE00'"), Ok("\u{1F600}".to_string()));
+    assert_eq!(string_value(r"'\uD83Dx'"), Ok("\u{FFFD}x".to_string()));
+    assert_eq!(string_value(r"'\u{110000}'"), Err(InvalidEscape::Unicode));
+}
+
+#[test]
+fn template_escapes() {
+    assert_eq!(
+        template_value(r"a\x41\u{1F600}\0"),
+        Ok("aA\u{1F600}\0".to_string())
+    );
+    assert_eq!(template_value(r"\u00"), Err(InvalidEscape::Unicode));
+    assert_eq!(template_value(r"\01"), Err(InvalidEscape::Octal));
+    assert_eq!(template_value(r"\7"), Err(InvalidEscape::Octal));
+}
+
+#[test]
+fn octal_escapes() {
+    assert_eq!(
+        string_value(r"'\101\60\7\08'"),
+        Ok("A0\u{7}\08".to_string())
+    );
+    assert_eq!(string_value(r"'\477'"), Ok("\u{27}7".to_string()));
+}
+
+#[test]
+fn line_continuations() {
+    assert_eq!(
+        string_value("'a\\\nb\\\r\nc\\\u{2028}d'"),
+        Ok("abcd".to_string())
+    );
+}
+
+#[test]
+fn non_ascii_literal() {
+    assert_eq!(string_value("'héllo ✓ 😀'"), Ok("héllo ✓ 😀".to_string()));
+    assert_eq!(string_value("\"é\\u00e9\""), Ok("éé".to_string()));
+}
+
+#[test]
+fn non_ascii_source() {
+    let source = "
+        // ünïcode ✓
+        let café = 'héllo ✓'; /* 😀 */
+        let ñ = `${café} 😀`;
+        [café, ñ, 'é' < 'ê', \"✓\".charAt(0) === '\\u2713'].join('|');
+    ";
+    assert_eq!(eval_string(source), "héllo ✓|héllo ✓ 😀|true|true");
+}
+
+#[test]
+fn non_ascii_tokens() {
+    let mut lexer = Lexer::new("é ✓\r\n'😀' >>>= ü");
+    let tokens: Vec<_> = (0..6)
+        .map(|_| {
+            let token = lexer.next_token();
+            (token.kind(), token.value().to_string(), token.line_number())
+        })
+        .collect();
+    let expected = [
+        (TokenKind::Identifier, "é", 1),
+        (TokenKind::Invalid, "✓", 1),
+        (TokenKind::StringLiteral, "'😀'", 2),
+        (TokenKind::UnsignedShiftRightEquals, ">>>=", 2),
+        (TokenKind::Identifier, "ü", 2),
+        (TokenKind::Eof, "", 2),
+    ];
+    assert_eq!(
+        tokens,
+        expected.map(|(kind, value, line)| (kind, value.to_string(), line))
+    );
+}
+
+#[test]
+fn block_comments() {
+    let kinds = |source| {
+        let mut lexer = Lexer::new(source);
+        let mut kinds = vec![lexer.next_token().kind()];
+        while !matches!(kinds.last(), Some(TokenKind::Eof)) {
+            kinds.push(lexer.next_token().kind());
+        }
+        kinds
+    };
+    assert_eq!(
+        kinds("/**/a/* * */"),
+        [TokenKind::Identifier, TokenKind::Eof]
+    );
+    assert_eq!(
+        kinds("a /*/ b"),
+        [
+            TokenKind::Identifier,
+            TokenKind::UnterminatedBlockComment,
+            TokenKind::Eof
+        ]
+    );
+}
@@ -0,0 +1,79 @@
+use crate::{
+    ast::{self, ASTNode, Completion},
+    jsrt::JsValue,
+    parser::Parser,
+    vm::{Compiler, Context, Interpreter, Runtime},
+};
+
+mod arrays;
+mod bigints;
+mod builtins;
+mod classes;
+mod control_flow;
+mod exceptions;
+mod functions;
+mod gc;
+mod generators;
+mod inspect;
+mod lexer;
+mod modules;
+mod objects;
+mod promises;
+mod regexp;
+mod symbols;
+mod vm;
+
+/// Run `source` as a script in a fresh runtime, returning its completion value.
+///
+/// The runtime is gone once this returns, use `eval_with` to look into objects
+fn eval(source: &str) -> JsValue {
+    eval_with(source, |value| value)
+}
+
+/// Run `source` with the bytecode VM and pass its completion value to `f` while the runtime
+/// is still alive.
+///
+/// The AST interpreter runs it too, both have to agree on the result
+fn eval_with<T>(source: &str, f: impl FnOnce(JsValue) -> T) -> T {
+    let program = Parser::new(source)
+        .parse_program()
+        .unwrap_or_else(|e| panic!("{}", e));
+
+    let expected = {
+        let mut context = Context::new(Runtime::new());
+        describe(&program.eval(&mut context))
+    };
+
+    let code = Compiler::compile_program(&program).unwrap_or_else(|e| panic!("{}", e));
+    let mut context = Context::new(Runtime::new());
+    let result = Interpreter::run(&mut context, &code);
+    assert_eq!(
+        describe(&result),
+        expected,
+        "the VM and the AST interpreter disagree on\n{}",
+        source
+    );
+
+    match result {
+        Ok(value) => f(value),
+        Err(Completion::Throw(error)) => panic!("Uncaught {}", error.to_string()),
+        Err(completion) => panic!("{:?} outside of its statement", completion),
+    }
+}
+
+/// Result of a script, comparable between runtimes
+fn describe(result: &ast::Result<JsValue>) -> String {
+    match result {
+        Ok(value) => format!("{} {}", value.type_of(), value.to_string()),
+        Err(Completion::Throw(error)) => format!("Uncaught {}", error.to_string()),
+        Err(completion) => format!("{:?}", completion),
+    }
+}
+
+fn eval_number(source: &str) -> f64 {
+    eval_with(source, |value| value.as_f64())
+}
+
+fn eval_string(source: &str) -> String {
+    eval_with(source, |value| value.to_string())
+}
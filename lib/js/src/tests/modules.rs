@@ -0,0 +1,402 @@
+use super::*;
+use crate::{
+    ast::{ExportEntry, ImportName},
+    jsrt::Attributes,
+    vm::{FileSystemLoader, MemoryLoader, ModuleLoader},
+};
+
+/// Context whose runtime loads its modules with `loader`
+fn context_with(loader: impl ModuleLoader + 'static) -> Context {
+    let runtime = Runtime::new();
+    runtime.lock().unwrap().set_module_loader(Box::new(loader));
+    Context::new(runtime)
+}
+
+/// Import `specifier` from `modules`, then run `expression` as a script with the namespace of
+/// the module as `ns`
+fn import(modules: &[(&str, &str)], specifier: &str, expression: &str) -> String {
+    let mut loader = MemoryLoader::new();
+    for (key, source) in modules {
+        loader.insert(key, source);
+    }
+    let mut context = context_with(loader);
+    run_with_namespace(&mut context, specifier, expression)
+}
+
+fn run_with_namespace(context: &mut Context, specifier: &str, expression: &str) -> String {
+    let namespace = match context.import(specifier) {
+        Ok(namespace) => namespace,
+        Err(error) => return describe(&Err(error)),
+    };
+    let attributes = Attributes::WRITABLE | Attributes::CONFIGURABLE;
+    context
+        .global_object()
+        .define_property("ns", namespace, attributes);
+
+    let program = Parser::new(expression)
+        .parse_program()
+        .unwrap_or_else(|e| panic!("{}", e));
+    let code = Compiler::compile_program(&program).unwrap_or_else(|e| panic!("{}", e));
+    describe(&Interpreter::run(context, &code))
+}
+
+#[test]
+fn parses_imports_and_exports() {
+    let source = r#"
+        import def, { a, 'b' as c } from './a.js';
+        import * as ns from './b.js';
+        import './a.js';
+        export { a as default, c };
+        export * from './c.js';
+        export * as d from './d.js';
+        export const [e, f] = [1, 2];
+    "#;
+    let module = Parser::new(source)
+        .parse_module()
+        .unwrap_or_else(|e| panic!("{}", e));
+
+    assert_eq!(module.requests(), ["./a.js", "./b.js", "./c.js", "./d.js"]);
+    let imports: Vec<_> = module
+        .imports()
+        .iter()
+        .map(|entry| (entry.import_name.clone(), entry.local_name.as_str()))
+        .collect();
+    assert_eq!(
+        imports,
+        [
+            (ImportName::Name("default".into()), "def"),
+            (ImportName::Name("a".into()), "a"),
+            (ImportName::Name("b".into()), "c"),
+            (ImportName::Namespace, "ns"),
+        ]
+    );
+    assert_eq!(module.exports()[0], ExportEntry::local("default", "a"));
+    assert_eq!(module.exports()[2].import_name, Some(ImportName::All));
+    assert_eq!(module.exports()[3].export_name.as_deref(), Some("d"));
+    assert_eq!(module.exports()[5], ExportEntry::local("f", "f"));
+    assert_eq!(module.body().statements().len(), 1);
+}
+
+#[test]
+fn rejects_invalid_module_syntax() {
+    let error = |source| match Parser::new(source).parse_module() {
+        Ok(_) => panic!("{} parsed", source),
+        Err(error) => error.to_string(),
+    };
+    assert!(error("export const a = 1; export { a };").contains("Duplicate export of 'a'"));
+    assert!(error("export default 1; export default 2;").contains("Duplicate export"));
+    error("import { default } from './a.js';");
+    error("export { 'a' };");
+    error("import * from './a.js';");
+}
+
+#[test]
+fn named_default_and_namespace_imports() {
+    let modules = [
+        (
+            "main.js",
+            r#"
+                import def, { a, b as c } from './lib.js';
+                import * as lib from './lib.js';
+                export const result = [def(), a, c, lib.a, lib.default.name].join();
+            "#,
+        ),
+        (
+            "lib.js",
+            r#"
+                export const a = 1;
+                let b = 2;
+                export { b };
+                export default function () { return 'def'; }
+            "#,
+        ),
+    ];
+    assert_eq!(
+        import(&modules, "main.js", "ns.result;"),
+        "string def,1,2,1,default"
+    );
+}
+
+#[test]
+fn default_export_of_an_expression() {
+    let modules = [("main.js", "export default 6 * 7; export class C {}")];
+    assert_eq!(
+        import(&modules, "main.js", "[ns.default, typeof ns.C].join();"),
+        "string 42,function"
+    );
+}
+
+#[test]
+fn re_exports() {
+    let modules = [
+        (
+            "main.js",
+            r#"
+                export { a as x } from './lib.js';
+                export * from './other.js';
+                export * as lib from './lib.js';
+                import { b } from './lib.js';
+                export { b };
+            "#,
+        ),
+        ("lib.js", "export const a = 'a', b = 'b';"),
+        (
+            "other.js",
+            "export const y = 'y'; export default 'skipped';",
+        ),
+    ];
+    assert_eq!(
+        import(
+            &modules,
+            "main.js",
+            "[ns.x, ns.y, ns.b, ns.lib.a, Object.keys(ns).join('|')].join();",
+        ),
+        "string a,y,b,a,b|lib|x|y"
+    );
+}
+
+#[test]
+fn namespace_objects_are_sealed() {
+    let modules = [("main.js", "export let a = 1; export function f() {}")];
+    let source = r#"
+        var results = [
+            Object.prototype.toString.call(ns),
+            Object.getPrototypeOf(ns),
+            Object.isExtensible(ns),
+            delete ns.a,
+            Object.defineProperty(ns, 'a', { value: 1 }) === ns,
+            Object.getOwnPropertyNames(ns),
+            ns.a,
+            'a' in ns,
+        ];
+        try { Object.defineProperty(ns, 'a', { value: 2 }); } catch (e) { results.push(e.name); }
+        ns.a = 3;
+        results.push(ns.a);
+        results.join();
+    "#;
+    assert_eq!(
+        import(&modules, "main.js", source),
+        "string [object Module],,false,false,true,a,f,1,true,TypeError,1"
+    );
+}
+
+#[test]
+fn imports_are_live_bindings() {
+    let modules = [
+        (
+            "main.js",
+            r#"
+                import { count, increment } from './counter.js';
+                import * as counter from './counter.js';
+                const before = count;
+                increment();
+                increment();
+                export const seen = [before, count, counter.count].join();
+                export function assign() {
+                    try { count = 10; } catch (e) { return e.name; }
+                }
+            "#,
+        ),
+        (
+            "counter.js",
+            "export let count = 0; export function increment() { count++; }",
+        ),
+    ];
+    assert_eq!(
+        import(&modules, "main.js", "[ns.seen, ns.assign()].join();"),
+        "string 0,2,2,TypeError"
+    );
+}
+
+#[test]
+fn cycles_run_in_import_order() {
+    let modules = [
+        (
+            "main.js",
+            r#"
+                import { log } from './log.js';
+                import { a } from './a.js';
+                import { fromA } from './b.js';
+                log.push('main');
+                export const result = log.join() + ' ' + fromA();
+            "#,
+        ),
+        ("log.js", "export const log = [];"),
+        (
+            "a.js",
+            r#"
+                import { log } from './log.js';
+                import { b } from './b.js';
+                log.push('a');
+                export function a() { return 'a' + b(); }
+            "#,
+        ),
+        (
+            "b.js",
+            r#"
+                import { log } from './log.js';
+                import { a } from './a.js';
+                // Hoisted, the body of a.js didn't run yet
+                log.push('b ' + typeof a);
+                export function b() { return 'b'; }
+                export const fromA = () => a();
+            "#,
+        ),
+    ];
+    assert_eq!(
+        import(&modules, "main.js", "ns.result;"),
+        "string b function,a,main ab"
+    );
+}
+
+#[test]
+fn bindings_of_a_cycle_are_uninitialized_until_their_declaration_runs() {
+    let modules = [
+        ("main.js", "import './a.js';"),
+        ("a.js", "import './b.js'; export let x = 1;"),
+        ("b.js", "import { x } from './a.js'; export const y = x;"),
+    ];
+    assert_eq!(
+        import(&modules, "main.js", ""),
+        "Uncaught ReferenceError: Cannot access 'x' before initialization"
+    );
+}
+
+#[test]
+fn a_module_is_evaluated_once() {
+    let modules = MemoryLoader::new()
+        .with_source("main.js", "import './counter.js'; export default 1;")
+        .with_source("other.js", "import './counter.js';")
+        .with_source(
+            "counter.js",
+            "globalThis.count = (globalThis.count || 0) + 1;",
+        );
+    let mut context = context_with(modules);
+    run_with_namespace(&mut context, "main.js", "var first = ns;");
+    assert_eq!(
+        run_with_namespace(&mut context, "other.js", "count;"),
+        "number 1"
+    );
+    assert_eq!(
+        run_with_namespace(&mut context, "main.js", "ns === first;"),
+        "boolean true"
+    );
+}
+
+#[test]
+fn link_errors() {
+    let modules = [
+        ("main.js", "import { x } from './lib.js';"),
+        (
+            "lib.js",
+            "export * from './p.js'; export * from './q.js'; export const a = 1;",
+        ),
+        ("p.js", "export const x = 1;"),
+        ("q.js", "export const x = 2;"),
+    ];
+    assert_eq!(
+        import(&modules, "main.js", ""),
+        "Uncaught SyntaxError: The requested module './lib.js' contains conflicting star exports \
+         for name 'x'"
+    );
+    assert_eq!(
+        import(&modules[1..], "lib.js", "Object.keys(ns).join();"),
+        "string a"
+    );
+
+    assert_eq!(
+        import(&modules[..2], "main.js", ""),
+        "Uncaught Error: Cannot find module 'p.js'"
+    );
+    assert_eq!(
+        import(
+            &[("main.js", "import { b } from './main.js';")],
+            "main.js",
+            ""
+        ),
+        "Uncaught SyntaxError: The requested module './main.js' does not provide an export \
+         named 'b'"
+    );
+    assert_eq!(
+        import(&[("main.js", "export { c };")], "main.js", ""),
+        "Uncaught SyntaxError: Export 'c' is not defined in module"
+    );
+    assert_eq!(
+        import(
+            &[
+                ("main.js", "import { a } from './lib.js'; let a;"),
+                ("lib.js", "export const a = 1;")
+            ],
+            "main.js",
+            ""
+        ),
+        "Uncaught SyntaxError: Identifier 'a' has already been declared"
+    );
+    assert!(import(&[("main.js", "export let;")], "main.js", "")
+        .starts_with("Uncaught SyntaxError: SyntaxError at 1:"));
+}
+
+#[test]
+fn evaluation_errors_are_thrown_again() {
+    let modules = MemoryLoader::new()
+        .with_source("main.js", "import './lib.js';")
+        .with_source("lib.js", "throw new Error('lib');");
+    let mut context = context_with(modules);
+    assert_eq!(
+        run_with_namespace(&mut context, "main.js", ""),
+        "Uncaught Error: lib"
+    );
+    assert_eq!(
+        run_with_namespace(&mut context, "lib.js", ""),
+        "Uncaught Error: lib"
+    );
+}
+
+#[test]
+fn builtin_modules() {
+    let modules = MemoryLoader::new()
+        .with_source(
+            "main.js",
+            r#"
+                import { answer, greet } from 'host';
+                import * as host from 'host';
+                export const result = greet + ' ' + answer + ' ' + Object.keys(host);
+            "#,
+        )
+        .with_builtin("host", |_| {
+            vec![
+                ("answer".to_string(), JsValue::from(42)),
+                ("greet".to_string(), JsValue::string("hello")),
+            ]
+        });
+    let mut context = context_with(modules);
+    assert_eq!(
+        run_with_namespace(&mut context, "main.js", "ns.result;"),
+        "string hello 42 answer,greet"
+    );
+}
+
+#[test]
+fn file_system_modules() {
+    let directory = std::env::temp_dir().join(format!("js-modules-{}", std::process::id()));
+    std::fs::create_dir_all(directory.join("lib")).unwrap();
+    std::fs::write(
+        directory.join("main.js"),
+        "import { twice } from './lib/twice.js'; export const result = twice(21);",
+    )
+    .unwrap();
+    std::fs::write(
+        directory.join("lib/twice.js"),
+        "import { two } from '../two.js'; export const twice = n => n * two;",
+    )
+    .unwrap();
+    std::fs::write(directory.join("two.js"), "export const two = 2;").unwrap();
+
+    let mut context = context_with(FileSystemLoader::new(&directory));
+    let result = run_with_namespace(&mut context, "./main.js", "ns.result;");
+    let missing = run_with_namespace(&mut context, "./missing.js", "");
+    std::fs::remove_dir_all(&directory).unwrap();
+
+    assert_eq!(result, "number 42");
+    assert!(missing.starts_with("Uncaught Error: Cannot find module"));
+}
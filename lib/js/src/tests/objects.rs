@@ -0,0 +1,258 @@
+use std::rc::Rc;
+
+use super::*;
+use crate::jsrt::{
+    Attributes, JsObject, JsSymbol, NativeFunction, ObjectKind, PropertyDescriptor, PropertyKey,
+};
+
+fn key(name: &str) -> PropertyKey {
+    PropertyKey::from(name)
+}
+
+/// Run `f` in a scope of a fresh context, nothing else roots the objects built from Rust
+fn with_context(f: impl FnOnce(&mut Context)) {
+    let mut context = Context::new(Runtime::new());
+    context.scope(f);
+}
+
+#[test]
+fn integer_keys_come_first() {
+    assert_eq!(
+        eval_string(
+            "let s = ''; for (let k in { b: 1, 10: 1, a: 1, 2: 1, '01': 1 }) s += k + ' '; s;"
+        ),
+        "2 10 b a 01 "
+    );
+}
+
+#[test]
+fn prototype_literals() {
+    assert!(eval("'a' in { __proto__: { a: 1 } };").to_boolean());
+    assert!(!eval("'__proto__' in { __proto__: null };").to_boolean());
+    assert_eq!(
+        eval_string("let s = ''; for (let k in { b: 1, __proto__: { a: 1, b: 2 } }) s += k; s;"),
+        "ba"
+    );
+}
+
+#[test]
+fn proto_accessor() {
+    assert!(eval("({}).__proto__ === Object.prototype;").to_boolean());
+    assert!(eval("[].__proto__ === Array.prototype;").to_boolean());
+    assert!(eval("(1).__proto__ === Number.prototype;").to_boolean());
+    assert!(eval("Object.create(null).__proto__ === undefined;").to_boolean());
+    assert!(!eval("Object.prototype.propertyIsEnumerable('__proto__');").to_boolean());
+
+    let source = r#"
+        let p = { a: 1 };
+        let o = {};
+        o.__proto__ = p;
+        o.a === 1 && Object.getPrototypeOf(o) === p && o.__proto__ === p;
+    "#;
+    assert!(eval(source).to_boolean());
+    // Only objects and null are prototypes, the rest is ignored
+    assert!(eval("let o = {}; o.__proto__ = 1; o.__proto__ === Object.prototype;").to_boolean());
+    assert!(
+        eval("let o = {}; o.__proto__ = null; Object.getPrototypeOf(o) === null;").to_boolean()
+    );
+    assert_eq!(
+        eval_string(
+            "let a = {}; let b = { __proto__: a }; try { a.__proto__ = b; } catch (e) { e.name; }"
+        ),
+        "TypeError"
+    );
+}
+
+#[test]
+fn computed_keys() {
+    let source = r#"
+        let order = '';
+        let key = (k) => { order += k; return k; };
+        let o = { [key('a')]: key('1'), b: 2, [key('c') + 'd']: key('3'), [1 + 1]: 'two' };
+        order + ' ' + Object.keys(o).join() + ' ' + o.a + o.cd + o[2];
+    "#;
+    assert_eq!(eval_string(source), "a1c3 2,a,b,cd 13two");
+
+    let source = r#"
+        let sym = Symbol('s');
+        let o = { [sym]: 1, ['__proto__']: 2, [`x${1}`]() { return 3; }, get ['y']() { return 4; } };
+        [o[sym], o.x1(), o.y, Object.getPrototypeOf(o) === Object.prototype, o.__proto__].join();
+    "#;
+    assert_eq!(eval_string(source), "1,3,4,true,2");
+
+    // A computed name is a reference, not the name itself
+    assert_eq!(eval_string("let a = 'b'; ({ [a]: 1 }).b;"), "1");
+    assert!(eval("let a = 'b'; !('a' in { [a]: 1 });").to_boolean());
+}
+
+#[test]
+fn accessor_literals() {
+    let source = r#"
+        let s = '';
+        for (let k in { get x() { return 1; }, set y(v) {}, get: 1, set() {}, 'z': 2 }) s += k;
+        s;
+    "#;
+    assert_eq!(eval_string(source), "xygetsetz");
+}
+
+#[test]
+fn instanceof_walks_the_prototype_chain() {
+    assert!(eval("TypeError('a') instanceof Error;").to_boolean());
+    assert!(eval("TypeError('a') instanceof TypeError;").to_boolean());
+    assert!(!eval("Error('a') instanceof TypeError;").to_boolean());
+    assert!(!eval("({}) instanceof Error;").to_boolean());
+    assert!(!eval("1 instanceof Error;").to_boolean());
+    assert!(eval("'name' in RangeError() && 'stack' in RangeError();").to_boolean());
+}
+
+#[test]
+fn define_own_property() {
+    let mut context = Context::new(Runtime::new());
+    let mut obj = JsObject::new(&mut context);
+
+    assert!(obj.define_property("a", JsValue::integer(1), Attributes::ENUMERABLE));
+    // Read only and not configurable, only the same value can be "changed"
+    assert!(obj.define_property("a", JsValue::integer(1), Attributes::ENUMERABLE));
+    assert!(!obj.define_property("a", JsValue::integer(2), Attributes::ENUMERABLE));
+    assert!(!obj.define_own_property(
+        key("a"),
+        PropertyDescriptor {
+            configurable: Some(true),
+            ..Default::default()
+        }
+    ));
+    assert!(!obj.delete(&key("a")));
+    assert!(!obj
+        .set(
+            &mut context,
+            key("a"),
+            JsValue::integer(3),
+            JsValue::Undefined
+        )
+        .unwrap());
+    assert_eq!(obj.get_data_property("a").as_i32(), 1);
+
+    assert!(obj.create_data_property(key("b"), JsValue::integer(1)));
+    assert!(obj.define_own_property(
+        key("b"),
+        PropertyDescriptor {
+            writable: Some(false),
+            ..Default::default()
+        }
+    ));
+    let desc = obj.get_own_property(&key("b")).unwrap();
+    assert!(!desc.writable() && desc.enumerable() && desc.configurable());
+    assert!(obj.delete(&key("b")));
+    assert!(obj.get_own_property(&key("b")).is_none());
+
+    obj.prevent_extensions();
+    assert!(!obj.create_data_property(key("c"), JsValue::Null));
+}
+
+#[test]
+fn accessors() {
+    with_context(|context| {
+        let getter = JsObject::native_function(
+            context,
+            NativeFunction::new("get", 0, |_, _, _| Ok(JsValue::integer(42))),
+        );
+        let mut prototype = JsObject::new(context);
+        prototype.define_own_property(
+            key("x"),
+            PropertyDescriptor::accessor(
+                JsValue::object(getter),
+                JsValue::Undefined,
+                Attributes::CONFIGURABLE,
+            ),
+        );
+
+        let mut obj = JsObject::with_prototype(context, Some(prototype), ObjectKind::Ordinary);
+        let receiver = JsValue::object(obj.clone());
+        let value = obj.get(context, &key("x"), receiver.clone()).unwrap();
+        assert_eq!(value.as_i32(), 42);
+        // An inherited accessor without setter can't be shadowed by an assignment
+        assert!(!obj.set(context, key("x"), JsValue::Null, receiver).unwrap());
+        assert!(obj.get_own_property(&key("x")).is_none());
+        assert!(obj.has_property(&key("x")));
+    });
+}
+
+#[test]
+fn prototype_cycles_are_rejected() {
+    with_context(|context| {
+        let mut a = JsObject::new(context);
+        let mut b = JsObject::new(context);
+
+        assert!(b.set_prototype_of(Some(a.clone())));
+        assert!(!a.set_prototype_of(Some(b.clone())));
+        assert!(a.set_prototype_of(None));
+        assert!(a.get_prototype_of().is_none());
+    });
+}
+
+#[test]
+fn own_property_keys_order() {
+    with_context(|context| {
+        let mut obj = JsObject::new(context);
+        let symbol = JsSymbol::new(context, Some("s".into()));
+
+        for key in [
+            PropertyKey::from(symbol.clone()),
+            key("b"),
+            key("4294967295"),
+            key("3"),
+            key("a"),
+            PropertyKey::from(0),
+        ] {
+            obj.create_data_property(key, JsValue::Null);
+        }
+
+        assert_eq!(
+            obj.own_property_keys(),
+            [
+                PropertyKey::Index(0),
+                PropertyKey::Index(3),
+                key("b"),
+                key("4294967295"),
+                key("a"),
+                PropertyKey::Symbol(symbol),
+            ]
+        );
+    });
+}
+
+#[test]
+fn objects_share_shapes() {
+    with_context(|context| {
+        let mut a = JsObject::new(context);
+        let mut b = JsObject::new(context);
+
+        for i in 0..20 {
+            let name = format!("p{}", i);
+            a.create_data_property(key(&name), JsValue::integer(i));
+            b.create_data_property(key(&name), JsValue::integer(-i));
+        }
+        assert!(Rc::ptr_eq(
+            a.properties().shape().unwrap(),
+            b.properties().shape().unwrap()
+        ));
+        assert_eq!(b.get_data_property("p13").as_i32(), -13);
+
+        // Deleting leaves the shared shape, without losing the order
+        assert!(a.delete(&key("p3")));
+        assert!(a.properties().is_dictionary());
+        assert_eq!(a.own_property_keys().len(), 19);
+        assert_eq!(a.own_property_keys()[3], key("p4"));
+        assert!(b.properties().shape().is_some());
+    });
+}
+
+#[test]
+fn spread_properties() {
+    let source = r#"
+        let base = { a: 1, b: 2 };
+        let copy = { ...base, b: 3, ...null, ...'x' };
+        Object.keys(copy).join() + copy.b;
+    "#;
+    assert_eq!(eval_string(source), "0,a,b3");
+}
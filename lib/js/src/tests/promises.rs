@@ -0,0 +1,210 @@
+use super::*;
+
+/// Run `source` then `expression` in the same runtime, the jobs `source` queued run in between
+fn eval_after_jobs(source: &str, expression: &str) -> String {
+    let parse = |source| {
+        Parser::new(source)
+            .parse_program()
+            .unwrap_or_else(|e| panic!("{}", e))
+    };
+    let (program, expression) = (parse(source), parse(expression));
+
+    let expected = {
+        let mut context = Context::new(Runtime::new());
+        describe(&program.eval(&mut context));
+        describe(&expression.eval(&mut context))
+    };
+
+    let mut context = Context::new(Runtime::new());
+    let mut run = |program| {
+        let code = Compiler::compile_program(program).unwrap_or_else(|e| panic!("{}", e));
+        describe(&Interpreter::run(&mut context, &code))
+    };
+    run(&program);
+    let result = run(&expression);
+    assert_eq!(
+        result, expected,
+        "the VM and the AST interpreter disagree on\n{}",
+        source
+    );
+    result
+}
+
+#[test]
+fn reactions_run_after_the_script() {
+    let source = r#"
+        var log = [];
+        Promise.resolve(1).then(v => log.push('then ' + v));
+        queueMicrotask(() => log.push('microtask'));
+        log.push('script');
+    "#;
+    assert_eq!(
+        eval_after_jobs(source, "log.join();"),
+        "string script,then 1,microtask"
+    );
+}
+
+#[test]
+fn then_chains_and_catches() {
+    let source = r#"
+        var log = [];
+        new Promise((resolve, reject) => reject(new Error('no')))
+            .then(() => log.push('skipped'))
+            .catch(e => { log.push(e.message); return 2; })
+            .then(v => { throw v * 2; })
+            .finally(() => log.push('finally'))
+            .then(null, e => log.push('caught ' + e));
+        new Promise(() => { throw 'executor'; }).catch(e => log.push(e));
+    "#;
+    assert_eq!(
+        eval_after_jobs(source, "log.join();"),
+        "string executor,no,finally,caught 4"
+    );
+
+    // Thenables are followed, the resolving functions only count once
+    let source = r#"
+        var log = [];
+        let thenable = { then(resolve) { resolve('thenable'); } };
+        Promise.resolve(thenable).then(v => log.push(v));
+        new Promise((resolve, reject) => { resolve(1); reject(2); resolve(3); })
+            .then(v => log.push(v));
+        let p = Promise.resolve(5);
+        log.push(Promise.resolve(p) === p);
+    "#;
+    assert_eq!(
+        eval_after_jobs(source, "log.join();"),
+        "string true,1,thenable"
+    );
+}
+
+#[test]
+fn promise_combinators() {
+    let source = r#"
+        var log = [];
+        let later = new Promise(resolve => Promise.resolve().then(() => resolve('later')));
+        Promise.all([1, later, Promise.resolve(3)]).then(v => log.push('all ' + v));
+        Promise.all([]).then(v => log.push('empty ' + v.length));
+        Promise.all([1, Promise.reject('x')]).catch(e => log.push('all rejected ' + e));
+        Promise.race([later, 2]).then(v => log.push('race ' + v));
+        Promise.allSettled([Promise.reject('r'), 1]).then(results => {
+            log.push(results.map(r => r.status + ':' + (r.value || r.reason)).join('|'));
+        });
+    "#;
+    let result = eval_after_jobs(source, "log.sort().join();");
+    assert_eq!(
+        result,
+        "string all 1,later,3,all rejected x,empty 0,race 2,rejected:r|fulfilled:1"
+    );
+}
+
+#[test]
+fn async_functions_await_promises() {
+    let source = r#"
+        var log = [];
+        async function add(a, b) {
+            log.push('start');
+            let x = await a;
+            let y = await Promise.resolve(b);
+            return x + y;
+        }
+        async function fails() {
+            try {
+                await Promise.reject(new Error('boom'));
+            } catch (e) {
+                log.push('caught ' + e.message);
+            }
+            throw 'rethrown';
+        }
+        let p = add(1, 2);
+        log.push(p instanceof Promise);
+        p.then(v => log.push('sum ' + v));
+        fails().catch(e => log.push(e));
+        let arrow = async x => (await x) * 10;
+        arrow(4).then(v => log.push('arrow ' + v));
+        ({ async m() { return 'method'; } }).m().then(v => log.push(v));
+    "#;
+    assert_eq!(
+        eval_after_jobs(source, "log.sort().join();"),
+        "string arrow 40,caught boom,method,rethrown,start,sum 3,true"
+    );
+}
+
+#[test]
+fn await_is_only_an_expression_in_async_functions() {
+    for source in [
+        "function f() { await 1; }",
+        "async function f() { function g() { await 1; } }",
+        "async function f() { let g = () => await 1; }",
+    ] {
+        assert!(Parser::new(source).parse_program().is_err(), "{}", source);
+    }
+    // `async` stays a name
+    assert_eq!(eval_number("let async = 3; async;"), 3.0);
+    assert_eq!(eval_number("let f = async => async + 1; f(1);"), 2.0);
+}
+
+#[test]
+fn promise_prototype() {
+    let source = r#"
+        [
+            Object.prototype.toString.call(Promise.resolve()),
+            typeof Promise.prototype.then,
+            Object.getPrototypeOf(async function () {}) === Function.prototype,
+            Object.prototype.toString.call(async function () {}),
+        ].join();
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "[object Promise],function,false,[object AsyncFunction]"
+    );
+}
+
+#[test]
+#[should_panic(expected = "TypeError: Promise constructor cannot be invoked without 'new'")]
+fn promise_requires_new() {
+    eval("Promise(() => {});");
+}
+
+#[test]
+fn timers_are_macrotasks_of_the_host() {
+    use std::{cell::RefCell, rc::Rc, time::Duration};
+
+    let runtime = Runtime::new();
+    let scheduled = Rc::new(RefCell::new(Vec::new()));
+    let hook = scheduled.clone();
+    runtime
+        .lock()
+        .unwrap()
+        .set_macrotask_hook(Box::new(move |id, delay| {
+            hook.borrow_mut().push((id, delay))
+        }));
+    let mut context = Context::new(runtime);
+
+    let source = r#"
+        var log = [];
+        setTimeout((a, b) => log.push('late ' + a + b), 20, 'x', 'y');
+        let cancelled = setTimeout(() => log.push('cancelled'), 5);
+        setTimeout(() => {
+            log.push('early');
+            Promise.resolve().then(() => log.push('microtask'));
+        });
+        clearTimeout(cancelled);
+    "#;
+    let program = Parser::new(source).parse_program().unwrap();
+    let code = Compiler::compile_program(&program).unwrap();
+    Interpreter::run(&mut context, &code).unwrap();
+    assert_eq!(
+        *scheduled.borrow(),
+        [
+            (1, Duration::from_millis(20)),
+            (2, Duration::from_millis(5)),
+            (3, Duration::from_millis(0))
+        ]
+    );
+
+    context.run_macrotasks().unwrap();
+    let program = Parser::new("log.join();").parse_program().unwrap();
+    let code = Compiler::compile_program(&program).unwrap();
+    let log = Interpreter::run(&mut context, &code).unwrap();
+    assert_eq!(log.to_string(), "early,microtask,late xy");
+}
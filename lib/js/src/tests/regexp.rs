@@ -0,0 +1,181 @@
+use super::*;
+
+#[test]
+fn literals_and_test() {
+    let source = r#"
+        let re = /ab+c/i;
+        [
+            re.test('xABBCx'), re.test('ac'), re.source, re.flags, re.global, re.ignoreCase,
+            String(re), typeof re, Object.prototype.toString.call(re), re.lastIndex,
+            /a\/b[/]/.source, RegExp('a/b').source, RegExp('').toString(),
+            RegExp.prototype.source, RegExp.prototype.global, 10 / 2 / 5,
+        ].join(' ');
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "true false ab+c i false true /ab+c/i object [object RegExp] 0 a\\/b[/] a\\/b /(?:)/ \
+         (?:)  1"
+    );
+}
+
+#[test]
+fn exec() {
+    let source = r#"
+        let m = /(\d+)-(?<word>[a-z]+)(x)?/.exec('at 12-ab!');
+        [m.length, m[0], m[1], m[2], m[3], m.index, m.input, m.groups.word].join('|');
+    "#;
+    assert_eq!(eval_string(source), "4|12-ab|12|ab||3|at 12-ab!|ab");
+}
+
+#[test]
+fn syntax() {
+    let cases = [
+        ("/a|b|/.exec('c')[0]", ""),
+        ("/^(?:a|ab)c$/.test('abc')", "true"),
+        ("/a{2,3}/.exec('aaaa')[0]", "aaa"),
+        ("/a{2,}?/.exec('aaaa')[0]", "aa"),
+        ("/a+?b/.exec('aaab')[0]", "aaab"),
+        ("/<.*?>/.exec('<a><b>')[0]", "<a>"),
+        ("/<.*>/.exec('<a><b>')[0]", "<a><b>"),
+        ("/[^\\d\\s]+/.exec('12 ab3')[0]", "ab"),
+        ("/[a-c-e]+/.exec('x-bae')[0]", "-bae"),
+        ("/\\bfoo\\b/.test('a foo b') + ' ' + /\\Bfoo/.test('afoo')", "true true"),
+        ("/(a)|b/.exec('b')[1]", "undefined"),
+        ("/(?:(a)|b)+/.exec('ab')[1]", "undefined"),
+        ("/(a*)*b/.exec('aab')[1]", "aa"),
+        ("/(?=(\\d+))\\d/.exec('123')[1]", "123"),
+        ("/(?!a)\\w/.exec('ab')[0]", "b"),
+        ("/(?<=\\$)\\d+/.exec('a1 $42')[0]", "42"),
+        ("/(?<!\\$)\\b\\d+/.exec('$4 5')[0]", "5"),
+        ("/(?<=(\\d)(\\d))x/.exec('12x').slice(1).join()", "1,2"),
+        ("/(\\w)\\1/.exec('abccd')[0]", "cc"),
+        ("/(?<q>['\"]).*?\\k<q>/.exec(`a \"b' c\" d`)[0]", "\"b' c\""),
+        ("/\\1(a)/.exec('aa')[0]", "a"),
+        ("/\\x41\\u0042\\103/.test('ABC')", "true"),
+        ("/\\cJ/.test('\\n')", "true"),
+        ("/a{,2}/.test('a{,2}')", "true"),
+        ("/]/.test(']')", "true"),
+        ("/\\u{1F600}/u.test(String.fromCharCode(0xD83D, 0xDE00))", "true"),
+        ("/^.$/u.test(String.fromCharCode(0xD83D, 0xDE00)) + ' ' + /^.$/.test(String.fromCharCode(0xD83D, 0xDE00))", "true false"),
+        ("/[\\u{1F600}-\\u{1F64F}]/u.test(String.fromCharCode(0xD83D, 0xDE01))", "true"),
+    ];
+    for (source, expected) in cases {
+        assert_eq!(eval_string(source), expected, "{}", source);
+    }
+}
+
+#[test]
+fn flags() {
+    let cases = [
+        ("/ABC/i.test('abc')", "true"),
+        ("/[a-z]+/i.exec('xYz')[0]", "xYz"),
+        ("/(a)\\1/i.test('aA')", "true"),
+        (
+            "/^b/m.test('a\\nb') + ' ' + /^b/.test('a\\nb')",
+            "true false",
+        ),
+        (
+            "/a$/m.test('a\\nb') + ' ' + /a$/.test('a\\nb')",
+            "true false",
+        ),
+        (
+            "/a.b/s.test('a\\nb') + ' ' + /a.b/.test('a\\nb')",
+            "true false",
+        ),
+        ("RegExp('a', 'gimsuy').flags", "gimsuy"),
+        ("/a/ysg.flags", "gsy"),
+    ];
+    for (source, expected) in cases {
+        assert_eq!(eval_string(source), expected, "{}", source);
+    }
+}
+
+#[test]
+fn last_index() {
+    let source = r#"
+        let log = [];
+        let g = /a/g;
+        log.push(g.test('aba'), g.lastIndex, g.test('aba'), g.lastIndex, g.test('aba'), g.lastIndex);
+        let y = /a/y;
+        log.push(y.test('ba'), y.lastIndex);
+        y.lastIndex = 1;
+        log.push(y.test('ba'), y.lastIndex);
+        log.join();
+    "#;
+    assert_eq!(eval_string(source), "true,1,true,3,false,0,false,0,true,2");
+}
+
+#[test]
+fn string_methods() {
+    let cases = [
+        ("'a1b22c333'.match(/\\d+/g).join()", "1,22,333"),
+        ("'abc'.match(/x/g)", "null"),
+        ("'abc'.match(/(b)(c)/).join()", "bc,b,c"),
+        ("'a.b'.match('.')[0]", "a"),
+        ("''.match(/$/g).length", "1"),
+        ("'aaa'.replace(/a/, 'b')", "baa"),
+        ("'aaa'.replace(/a/g, 'b')", "bbb"),
+        ("'aaa'.replaceAll(/a/g, '$&$&')", "aaaaaa"),
+        (
+            "'john smith'.replace(/(\\w+) (\\w+)/, '$2, $1 ($$)')",
+            "smith, john ($)",
+        ),
+        ("'ab'.replace(/(?<x>a)/, '[$<x>]')", "[a]b"),
+        ("'ab'.replace(/a/, \"$`|$'|$3\")", "|b|$3b"),
+        ("'abc'.replace(/x*/g, '-')", "-a-b-c-"),
+        (
+            "'a1b2'.replace(/([a-z])(\\d)/g, function (m, l, d, i, s) { return d + l + i; })",
+            "1a02b2",
+        ),
+        ("'a,b;c'.split(/[,;]/).join(' ')", "a b c"),
+        ("'a1b2c'.split(/(\\d)/).join(' ')", "a 1 b 2 c"),
+        ("'abc'.split(/(?:)/).join(' ')", "a b c"),
+        ("'abc'.split(/b/, 1).join(' ')", "a"),
+        (
+            "''.split(/a/).length + ' ' + ''.split(/(?:)/).length",
+            "1 0",
+        ),
+    ];
+    for (source, expected) in cases {
+        assert_eq!(eval_string(source), expected, "{}", source);
+    }
+}
+
+#[test]
+fn custom_exec_and_symbols() {
+    let source = r#"
+        let re = /x/;
+        re.exec = function (s) { return { 0: 'b', length: 1, index: 1 }; };
+        let log = [re.test('abc'), 'abc'.replace(re, 'B')];
+        let matcher = {};
+        matcher[Symbol.replace] = function (s, r) { return s + r; };
+        log.push('a'.replace(matcher, 'b'), typeof Symbol.match, typeof RegExp.prototype[Symbol.split]);
+        log.join();
+    "#;
+    assert_eq!(eval_string(source), "true,aBc,ab,symbol,function");
+}
+
+#[test]
+fn errors() {
+    let cases = [
+        ("RegExp('(')", "SyntaxError: Invalid regular expression: /(/: Unterminated group"),
+        ("RegExp('a', 'gg')", "SyntaxError: Invalid regular expression: /a/: Invalid flags supplied to RegExp 'gg'"),
+        ("RegExp('*')", "SyntaxError: Invalid regular expression: /*/: Nothing to repeat"),
+        ("RegExp('a{2,1}')", "SyntaxError: Invalid regular expression: /a{2,1}/: numbers out of order in {} quantifier"),
+        ("RegExp('[b-a]')", "SyntaxError: Invalid regular expression: /[b-a]/: Range out of order in character class"),
+        ("RegExp('\\\\p', 'u')", "SyntaxError: Invalid regular expression: /\\p/: Invalid escape"),
+        ("RegExp('\\\\k<x>(?<y>.)')", "SyntaxError: Invalid regular expression: /\\k<x>(?<y>.)/: Invalid named capture referenced"),
+        ("'a'.replaceAll(/a/, 'b')", "TypeError: replaceAll must be called with a global RegExp"),
+        ("RegExp.prototype.exec.call({}, '')", "TypeError: Method RegExp.prototype.exec called on incompatible receiver [object Object]"),
+    ];
+    for (source, expected) in cases {
+        let source = format!(
+            "try {{ {}; }} catch (e) {{ e.name + ': ' + e.message; }}",
+            source
+        );
+        assert_eq!(eval_string(&source), expected, "{}", source);
+    }
+
+    assert!(Parser::new("/(/").parse_program().is_err());
+    assert!(Parser::new("let a = /a/gg;").parse_program().is_err());
+}
@@ -0,0 +1,211 @@
+use super::*;
+
+#[test]
+fn symbol_values() {
+    let source = r#"
+        let a = Symbol('a');
+        let o = { x: 1 };
+        o[a] = 2;
+        [
+            typeof a, a === a, a === Symbol('a'), a.description, Symbol().description,
+            a.toString(), String(a), Object(a) == a, typeof Object(a), o[a], a in o,
+            Object.keys(o).length, Object.getOwnPropertySymbols(o)[0] === a, JSON.stringify(o),
+            Symbol.for('k') === Symbol.for('k'), Symbol.keyFor(Symbol.for('k')),
+            Symbol.keyFor(a), Symbol.iterator.toString(),
+        ].join(' ');
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "symbol true false a  Symbol(a) Symbol(a) true object 2 true 1 true {\"x\":1} true k  \
+         Symbol(Symbol.iterator)"
+    );
+}
+
+#[test]
+fn registry_is_shared() {
+    let runtime = Runtime::new();
+    let mut first = Context::new(runtime.clone());
+    let mut second = Context::new(runtime);
+    assert!(first.symbol_for("k") == second.symbol_for("k"));
+    assert!(first.symbol_for("k") != second.symbol_for("l"));
+    assert!(first.well_known_symbols().iterator == second.well_known_symbols().iterator);
+}
+
+#[test]
+fn errors() {
+    let cases = [
+        (
+            "Symbol() + ''",
+            "TypeError: Cannot convert a Symbol value to a string",
+        ),
+        (
+            "Symbol() * 2",
+            "TypeError: Cannot convert a Symbol value to a number",
+        ),
+        (
+            "-Symbol()",
+            "TypeError: Cannot convert a Symbol value to a number",
+        ),
+        (
+            "isNaN(Symbol())",
+            "TypeError: Cannot convert a Symbol value to a number",
+        ),
+        (
+            "Math.abs(Symbol())",
+            "TypeError: Cannot convert a Symbol value to a number",
+        ),
+        (
+            "'ab'.charAt(Symbol())",
+            "TypeError: Cannot convert a Symbol value to a number",
+        ),
+        (
+            "[1].slice(Symbol())",
+            "TypeError: Cannot convert a Symbol value to a number",
+        ),
+        (
+            "Symbol(Symbol())",
+            "TypeError: Cannot convert a Symbol value to a string",
+        ),
+        ("Symbol.keyFor('x')", "TypeError: x is not a symbol"),
+        (
+            "Symbol.prototype.toString.call(1)",
+            "TypeError: Symbol.prototype.toString requires that 'this' be a Symbol",
+        ),
+        ("for (let x of 1) {}", "TypeError: 1 is not iterable"),
+        (
+            "1 instanceof 1",
+            "TypeError: Right-hand side of 'instanceof' is not an object",
+        ),
+        (
+            "let o = {}; o[Symbol.toPrimitive] = function () { return {}; }; o + 1",
+            "TypeError: Cannot convert object to primitive value",
+        ),
+        (
+            "let o = {}; o.valueOf = o.toString = null; o + 1",
+            "TypeError: Cannot convert object to primitive value",
+        ),
+    ];
+    for (source, message) in cases {
+        let source = format!(
+            "try {{ {}; }} catch (e) {{ e.name + ': ' + e.message; }}",
+            source
+        );
+        assert_eq!(eval_string(&source), message, "{}", source);
+    }
+}
+
+#[test]
+fn iterator_protocol() {
+    let source = r#"
+        let log = [];
+        let range = {};
+        range[Symbol.iterator] = function () {
+            let i = 0;
+            return {
+                next: function () {
+                    i++;
+                    return { value: i, done: i > 5 };
+                },
+                return: function () {
+                    log.push('closed at ' + i);
+                    return {};
+                },
+            };
+        };
+        for (let x of range) {
+            log.push(x);
+        }
+        for (let x of range) {
+            if (x == 2) break;
+        }
+        try {
+            for (let x of range) {
+                throw 'thrown';
+            }
+        } catch (e) {
+            log.push(e);
+        }
+        function first() {
+            for (let x of range) {
+                return x;
+            }
+        }
+        log.push(first());
+        // Surrogate pairs stay together
+        for (let c of 'a' + String.fromCharCode(0xd83d, 0xde00)) log.push(c.length);
+        let iterator = [7, 8][Symbol.iterator]();
+        iterator.next();
+        for (let x of iterator) log.push(x);
+        log.push(Array.prototype[Symbol.iterator] === Array.prototype.values);
+        log.join(' ');
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "1 2 3 4 5 closed at 2 closed at 1 thrown closed at 1 1 1 2 8 true"
+    );
+}
+
+#[test]
+fn conversion_hooks() {
+    let source = r#"
+        let hints = [];
+        let o = {};
+        o[Symbol.toPrimitive] = function (hint) {
+            hints.push(hint);
+            return hint == 'number' ? 42 : 'str';
+        };
+        let valued = { valueOf: function () { return 5; }, toString: function () { return 'v'; } };
+        [
+            +o, o + '', String(o), o < 50, o == 'str', valued * 2, valued + '', String(valued),
+            Number(valued), hints.join(','), String(Error('m')), '' + TypeError('t'),
+        ].join(' ');
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "42 str str true true 10 5 v 5 number,default,string,number,default Error: m TypeError: t"
+    );
+}
+
+#[test]
+fn has_instance_and_to_string_tag() {
+    let source = r#"
+        let Even = {};
+        Even[Symbol.hasInstance] = function (value) { return value % 2 == 0; };
+        function F() {}
+        let tagged = {};
+        tagged[Symbol.toStringTag] = 'Tagged';
+        let tag = function (value) { return Object.prototype.toString.call(value); };
+        [
+            2 instanceof Even, 3 instanceof Even, [] instanceof Array,
+            Function.prototype[Symbol.hasInstance].call(Array, []), tag(tagged), tag(Math),
+            tag(JSON), tag(Symbol()), tag(1n), tag([].values()), tag(''[Symbol.iterator]()),
+            tag(F),
+        ].join(' ');
+    "#;
+    assert_eq!(
+        eval_string(source),
+        "true false true true [object Tagged] [object Math] [object JSON] [object Symbol] \
+         [object BigInt] [object Array Iterator] [object String Iterator] [object Function]"
+    );
+}
+
+#[test]
+fn iterator_method_literal() {
+    let source = r#"
+        let range = {
+            from: 1,
+            to: 4,
+            *[Symbol.iterator]() {
+                for (let i = this.from; i <= this.to; i++) yield i;
+            },
+        };
+        let manual = {
+            [Symbol.iterator]() {
+                let i = 0;
+                return { next: () => ({ value: i, done: i++ >= 2 }) };
+            },
+        };
+        [...range].join() + ' ' + [...manual].join();
+    "#;
+    assert_eq!(eval_string(source), "1,2,3,4 0,1");
+}
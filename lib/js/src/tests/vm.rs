@@ -0,0 +1,118 @@
+use super::*;
+use crate::vm::CompileError;
+
+fn compile_error(source: &str) -> CompileError {
+    let program = Parser::new(source)
+        .parse_program()
+        .unwrap_or_else(|e| panic!("{}", e));
+
+    match Compiler::compile_program(&program) {
+        Ok(code) => panic!("expected a compile error, got\n{}", code),
+        Err(error) => error,
+    }
+}
+
+#[test]
+fn jumps_out_of_finally_and_scopes() {
+    let source = r#"
+        let log = '';
+        outer: for (let i = 0; i < 3; i++) {
+            for (const c of 'ab') {
+                try {
+                    if (i == 1) continue outer;
+                    if (i == 2) break outer;
+                    log += c;
+                } finally {
+                    log += i;
+                }
+            }
+        }
+        log;
+    "#;
+    assert_eq!(eval_string(source), "a0b012");
+}
+
+#[test]
+fn return_runs_finally() {
+    let source = r#"
+        let log = '';
+        function f() {
+            try {
+                for (let k in { a: 1 }) {
+                    try { return k; } finally { log += 'inner '; }
+                }
+            } finally {
+                log += 'outer';
+            }
+        }
+        f() + ' ' + log;
+    "#;
+    assert_eq!(eval_string(source), "a inner outer");
+    assert_eq!(
+        eval_number("function f() { try { return 1; } finally { return 2; } } f();"),
+        2.0
+    );
+}
+
+#[test]
+fn exceptions_restore_the_frame() {
+    let source = r#"
+        let caught = 0;
+        for (let x of 'abc') {
+            try {
+                1 + (function () { throw x; })();
+            } catch (e) {
+                caught++;
+            }
+        }
+        caught;
+    "#;
+    assert_eq!(eval_number(source), 3.0);
+    assert_eq!(
+        eval_string(
+            "let r; try { try { throw 1; } finally { r = 'f'; } } catch (e) { r += e; } r;"
+        ),
+        "f1"
+    );
+}
+
+#[test]
+fn shorthand_properties() {
+    assert_eq!(
+        eval_string("let a = 4; for (let k in { a, b: 2 }) a += k; a;"),
+        "4ab"
+    );
+}
+
+#[test]
+fn early_errors() {
+    assert_eq!(compile_error("break;"), CompileError::IllegalBreak);
+    assert_eq!(
+        compile_error("while (true) { function f() { continue; } }"),
+        CompileError::IllegalContinue
+    );
+    assert_eq!(
+        compile_error("a: { continue a; }"),
+        CompileError::IllegalContinue
+    );
+    assert_eq!(
+        compile_error("while (true) break b;"),
+        CompileError::UndefinedLabel("b".into())
+    );
+    assert_eq!(compile_error("return 1;"), CompileError::IllegalReturn);
+}
+
+#[test]
+fn disassembly() {
+    let program = Parser::new("function add(a, b) { return a + b; } add(1, 2);")
+        .parse_program()
+        .unwrap();
+    let code = Compiler::compile_program(&program).unwrap();
+    let text = code.to_string();
+
+    assert!(text.starts_with("<anonymous>():"), "{}", text);
+    assert!(text.contains("CreateFunction(0)"), "{}", text);
+    assert!(text.contains("; function add"), "{}", text);
+    assert!(text.contains("add(a, b):"), "{}", text);
+    assert!(text.contains("Numeric(Addition)"), "{}", text);
+}
@@ -0,0 +1,251 @@
+use std::{fmt, rc::Rc};
+
+use crate::{
+    ast::{
+        AssignmentOp, BitwiseOp, Class, CompareOp, DeclarationKind, ForInKind, FunctionKind,
+        NumericOp, ObjectPropertyKind, Position, TemplateStrings, UnaryOp,
+    },
+    jsrt::JsValue,
+    regexp::Regex,
+};
+
+/// One operation of the VM.
+///
+/// Operands index the tables of the `CodeBlock` running it, jump targets are instruction indexes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Instruction {
+    /// Push `constants[i]`
+    LoadConstant(u32),
+    LoadUndefined,
+    LoadNull,
+    LoadTrue,
+    LoadFalse,
+    Pop,
+    Dup,
+    /// Duplicate the two values on top of the stack, an object and a key
+    Dup2,
+    /// Pop a value and insert it below the `n` values under it
+    MoveDown(u32),
+    LoadThis,
+    LoadNewTarget,
+    /// Push the argument `i` of the call, `undefined` if there are less
+    LoadArgument(u32),
+    /// Push an array of the arguments from `i` on, for a rest parameter
+    LoadRestArguments(u32),
+
+    /// Push the value of the variable `names[i]`
+    GetVariable(u32),
+    /// Pop a value and assign it to `names[i]`
+    SetVariable(u32),
+    DeclareVariable(u32, DeclarationKind),
+    /// Pop a value and give it to `names[i]`, declared in the current scope
+    InitializeVariable(u32),
+    /// `typeof name`, which doesn't throw for an undeclared variable
+    TypeofVariable(u32),
+    PushEnvironment,
+    PopEnvironment,
+    /// Replace the current scope by a copy of it, for the `let` of a `for` loop
+    CopyEnvironment,
+
+    /// Pop the right then the left operand, push the result
+    Numeric(NumericOp),
+    Bitwise(BitwiseOp),
+    Compare(CompareOp),
+    Assign(AssignmentOp),
+    Unary(UnaryOp),
+    ToNumeric,
+    Increment,
+    Decrement,
+
+    Jump(u32),
+    /// Pop a value and jump if it is falsy
+    JumpIfFalse(u32),
+    JumpIfTrue(u32),
+    /// `&&`, jump keeping the value if it is falsy, pop it otherwise
+    JumpIfFalseKeep(u32),
+    /// `||`, jump keeping the value if it is truthy, pop it otherwise
+    JumpIfTrueKeep(u32),
+    /// Skip the default value of a pattern, jump keeping the value unless it is `undefined`
+    JumpIfNotUndefined(u32),
+
+    /// Push a closure over the current scope running `functions[i]`
+    CreateFunction(u32),
+    /// Push an empty object
+    CreateObject,
+    /// Pop a value and a key, define them on the object below
+    DefineProperty(ObjectPropertyKind),
+    /// Pop a value, make it the prototype of the object below if it is an object or `null`
+    SetPrototype,
+    /// Pop a value and copy its own enumerable properties to the object below
+    CopyDataProperties,
+    /// Pop an object then `n` keys, push an object with the other properties, for `...rest`
+    CreateRestObject(u32),
+    /// Throw if the value on top of the stack is `undefined` or `null`, before destructuring it
+    RequireObjectCoercible,
+    /// Push an empty array
+    CreateArray,
+    /// Pop a value and append it to the array below
+    AppendElement,
+    /// Leave a hole at the end of the array on top of the stack
+    AppendHole,
+    /// Pop an iterable and append what it iterates over to the array below
+    AppendSpread,
+    /// Push the array of strings passed to a tag, from `templates[i]`
+    CreateTemplateObject(u32),
+    /// Push a new `RegExp` object matching `regexps[i]`
+    CreateRegExp(u32),
+    /// Pop a value and push it converted to a string
+    ToString,
+    /// Pop a key and an object, push `object[key]`
+    GetProperty,
+    /// Pop a value, a key and an object, assign `object[key]` and push the value back
+    SetProperty,
+    /// Pop a key and an object, push whether `delete object[key]` succeeded
+    DeleteProperty,
+    /// Pop `n` arguments, the callee and `this`, push the returned value
+    Call(u32),
+    /// Pop an array of arguments, the callee and `this`, push the returned value
+    CallSpread,
+    /// Pop `n` arguments and a constructor, push the object `new` made
+    New(u32),
+    /// Pop an array of arguments and a constructor, push the object `new` made
+    NewSpread,
+    /// Pop `n` arguments, construct the parent class and bind `this` to the result
+    SuperCall(u32),
+    SuperCallSpread,
+    /// Pop a key, push `super[key]`
+    GetSuperProperty,
+    /// Bind the private names of `classes[i]` in the current scope
+    DeclarePrivateNames(u32),
+    /// Pop a key and a function or `undefined` for each element of `classes[i]`, its
+    /// constructor and its parent if it has one, push the class once its name is initialized
+    CreateClass(u32),
+    /// Record `positions[i]` as the position of the current function, for stack traces
+    SetPosition(u32),
+
+    /// Pop a value and throw it
+    Throw,
+    /// Exceptions thrown until the matching `LeaveTry` jump to the target, pushed on the stack
+    EnterTry(u32),
+    LeaveTry,
+
+    /// Pop a value and push an iterator over its keys or values
+    CreateIterator(ForInKind),
+    /// Push the next value of the innermost iterator, or jump when it is done
+    IteratorNext(u32),
+    /// Push the next value of the innermost iterator, `undefined` once it is done
+    IteratorValue,
+    /// Push an array of the values left in the innermost iterator
+    IteratorRest,
+    /// Drop the innermost iterator, closing it if its loop was left early
+    PopIterator,
+    /// Pop an iterable, push its iterator and the `next` method of it, for `yield*`
+    GetIterator,
+
+    /// Suspend a generator once its parameters are bound, until the first `next()`
+    GeneratorStart,
+    /// Pop a value and suspend the generator, yielding it
+    Yield,
+    /// Step the iterator and `next` method on top of the stack with what the generator was
+    /// resumed with, suspending to yield its result as is until it is done. Then pop them and
+    /// push its value and whether the generator has to return it, like `Resume`
+    YieldDelegate,
+    /// Pop a value and suspend the async function until it settles
+    Await,
+    /// Push the value the function was resumed with and whether it has to return it, throw it
+    /// instead if it was thrown in
+    Resume,
+
+    /// Pop the value of the last statement of a script
+    SetCompletion,
+    LoadCompletion,
+    /// Pop the value returned while `finally` blocks run
+    StoreReturn,
+    LoadReturn,
+    /// Pop a value and return it
+    Return,
+}
+
+/// Compiled script or function body
+#[derive(Debug, Default)]
+pub struct CodeBlock {
+    pub name: String,
+    /// Names bound by the parameters
+    pub params: Vec<String>,
+    /// `length` of the functions running it
+    pub length: usize,
+    pub kind: FunctionKind,
+    pub is_generator: bool,
+    pub is_async: bool,
+    pub code: Vec<Instruction>,
+    /// Primitive values loaded with `LoadConstant`
+    pub constants: Vec<JsValue>,
+    /// Variable names
+    pub names: Vec<String>,
+    /// Nested functions
+    pub functions: Vec<Rc<CodeBlock>>,
+    pub positions: Vec<Position>,
+    /// Strings of the tagged templates
+    pub templates: Vec<TemplateStrings>,
+    /// Patterns of the regular expression literals
+    pub regexps: Vec<Rc<Regex>>,
+    pub classes: Vec<Rc<Class>>,
+}
+
+impl CodeBlock {
+    pub fn new(name: &str, params: Vec<String>) -> CodeBlock {
+        CodeBlock {
+            name: name.into(),
+            params,
+            ..Default::default()
+        }
+    }
+
+    /// What an operand refers to, for the disassembly
+    fn operand_note(&self, instruction: Instruction) -> Option<String> {
+        use Instruction::*;
+
+        match instruction {
+            LoadConstant(i) => Some(format!("{:?}", self.constants[i as usize])),
+            GetVariable(i)
+            | SetVariable(i)
+            | DeclareVariable(i, _)
+            | InitializeVariable(i)
+            | TypeofVariable(i) => Some(self.names[i as usize].clone()),
+            CreateFunction(i) => Some(format!("function {}", self.functions[i as usize].name)),
+            SetPosition(i) => Some(self.positions[i as usize].to_string()),
+            DeclarePrivateNames(i) | CreateClass(i) => {
+                let class = &self.classes[i as usize];
+                Some(format!(
+                    "class {}",
+                    class.name().map_or("", |name| name.name())
+                ))
+            }
+            CreateRegExp(i) => {
+                let regex = &self.regexps[i as usize];
+                Some(format!("/{}/{}", regex.source(), regex.flags()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CodeBlock {
+    /// Disassembly of the block followed by its nested functions
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}({}):", self.name, self.params.join(", "))?;
+        for (index, instruction) in self.code.iter().enumerate() {
+            let text = format!("{:?}", instruction);
+            match self.operand_note(*instruction) {
+                Some(note) => writeln!(f, "{:>6}  {:<32} ; {}", index, text, note)?,
+                None => writeln!(f, "{:>6}  {}", index, text)?,
+            }
+        }
+
+        for function in self.functions.iter() {
+            writeln!(f)?;
+            write!(f, "{}", function)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,1330 @@
+use std::{collections::HashMap, fmt, rc::Rc};
+
+use Instruction::*;
+
+use super::{CodeBlock, Instruction};
+use crate::{
+    ast::{
+        AssignmentOp, BinaryOp, BinaryOperation, BindingElement, BindingPattern, BlockStatement,
+        CallExpression, CatchClause, Class, CompareOp, DeclarationKind, Expression, ForBinding,
+        ForInKind, ForInStatement, ForStatement, Function, Identifier, LabelledStatement, Literal,
+        MemberExpression, Module, NewExpression, NumericOp, ObjectPropertyKind, Position, Program,
+        Statement, SuperCall, SuperProperty, SwitchStatement, TemplateLiteral, TryStatement,
+        UnaryOp, WhileStatement,
+    },
+    jsrt::JsValue,
+};
+
+/// Early errors found while compiling, the AST interpreter only notices them when they run
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompileError {
+    IllegalBreak,
+    IllegalContinue,
+    IllegalReturn,
+    UndefinedLabel(String),
+    InvalidAssignmentTarget,
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompileError::IllegalBreak => write!(f, "Illegal break statement"),
+            CompileError::IllegalContinue => {
+                write!(
+                    f,
+                    "Illegal continue statement: no surrounding iteration statement"
+                )
+            }
+            CompileError::IllegalReturn => write!(f, "Illegal return statement"),
+            CompileError::UndefinedLabel(label) => write!(f, "Undefined label '{}'", label),
+            CompileError::InvalidAssignmentTarget => {
+                write!(f, "Invalid left-hand side in assignment")
+            }
+        }
+    }
+}
+
+type Result<T> = core::result::Result<T, CompileError>;
+
+#[derive(Debug, PartialEq)]
+enum BreakableKind {
+    Loop,
+    Switch,
+    /// Labelled statement that is not a loop, only `break label` can leave it
+    Label,
+}
+
+/// Statement `break` and `continue` can jump out of, their jumps are patched once it is compiled
+struct Breakable<'a> {
+    kind: BreakableKind,
+    labels: Vec<&'a str>,
+    breaks: Vec<usize>,
+    continues: Vec<usize>,
+}
+
+/// What the code being compiled is nested in, `break`, `continue` and `return` have to undo it
+enum Control<'a> {
+    /// A scope pushed with `PushEnvironment`
+    Environment,
+    /// A `try` without `finally`
+    Handler,
+    /// A `try` or `catch` with a `finally` to run on the way out
+    Finally(&'a BlockStatement),
+    /// The iterator of a `for in` / `for of`
+    Iterator,
+    /// A value left on the stack, the exception while its `finally` run
+    StackValue,
+    Breakable(Breakable<'a>),
+}
+
+/// Compile an AST into a `CodeBlock` for the `Interpreter`, one per function
+pub struct Compiler<'a> {
+    block: CodeBlock,
+    name_indexes: HashMap<String, u32>,
+    control: Vec<Control<'a>>,
+    /// Scripts result in the value of their last statement, function bodies don't
+    track_completion: bool,
+    is_function: bool,
+}
+
+impl<'a> Compiler<'a> {
+    fn new(name: &str, params: Vec<String>, is_function: bool) -> Compiler<'a> {
+        Compiler {
+            block: CodeBlock::new(name, params),
+            name_indexes: HashMap::new(),
+            control: Vec::new(),
+            track_completion: !is_function,
+            is_function,
+        }
+    }
+
+    pub fn compile_program(program: &'a Program) -> Result<CodeBlock> {
+        let mut compiler = Compiler::new("<anonymous>", Vec::new(), false);
+        compiler.declarations(program.statements())?;
+        for statement in program.statements() {
+            compiler.statement(statement)?;
+        }
+
+        compiler.emit(LoadCompletion);
+        compiler.emit(Return);
+        Ok(compiler.block)
+    }
+
+    /// The declarations of a module, which run while it is linked, and its body, which runs
+    /// once the modules it imports from ran theirs
+    pub fn compile_module(module: &'a Module) -> Result<(CodeBlock, CodeBlock)> {
+        let statements = module.body().statements();
+        let mut declarations = Compiler::new("<module>", Vec::new(), false);
+        declarations.declarations(statements)?;
+        declarations.emit(LoadUndefined);
+        declarations.emit(Return);
+
+        let mut body = Compiler::new("<module>", Vec::new(), false);
+        body.track_completion = false;
+        for statement in statements {
+            body.statement(statement)?;
+        }
+        body.emit(LoadUndefined);
+        body.emit(Return);
+        Ok((declarations.block, body.block))
+    }
+
+    pub fn compile_function(function: &'a Function) -> Result<CodeBlock> {
+        let name = function.name().map_or("", |ident| ident.name());
+        let params = function.params();
+        let names = params.bound_names().into_iter().map(String::from).collect();
+
+        let mut compiler = Compiler::new(name, names, true);
+        compiler.block.length = params.length();
+        compiler.block.kind = function.kind();
+        compiler.block.is_generator = function.is_generator();
+        compiler.block.is_async = function.is_async();
+        compiler.parameters(function)?;
+
+        let statements = function.body().statements();
+        compiler.declarations(statements)?;
+        if function.is_generator() {
+            compiler.emit(GeneratorStart);
+        }
+        for statement in statements {
+            compiler.statement(statement)?;
+        }
+
+        compiler.emit(LoadUndefined);
+        compiler.emit(Return);
+        Ok(compiler.block)
+    }
+
+    /// Bind the arguments to the parameters, defaults see the parameters before them
+    fn parameters(&mut self, function: &'a Function) -> Result<()> {
+        let params = function.params();
+        for name in params.bound_names() {
+            let name = self.name(name);
+            self.emit(DeclareVariable(name, DeclarationKind::Var));
+        }
+
+        for (index, param) in params.params.iter().enumerate() {
+            self.emit(LoadArgument(index as u32));
+            self.binding_element(param, true)?;
+        }
+        if let Some(rest) = &params.rest {
+            self.emit(LoadRestArguments(params.params.len() as u32));
+            self.binding(rest, true)?;
+        }
+        Ok(())
+    }
+
+    fn emit(&mut self, instruction: Instruction) -> usize {
+        self.block.code.push(instruction);
+        self.block.code.len() - 1
+    }
+
+    fn here(&self) -> usize {
+        self.block.code.len()
+    }
+
+    /// Point the jump at `at` to the next instruction
+    fn patch(&mut self, at: usize) {
+        self.patch_to(at, self.here());
+    }
+
+    fn patch_to(&mut self, at: usize, target: usize) {
+        let target = target as u32;
+        self.block.code[at] = match self.block.code[at] {
+            Jump(_) => Jump(target),
+            JumpIfFalse(_) => JumpIfFalse(target),
+            JumpIfTrue(_) => JumpIfTrue(target),
+            JumpIfFalseKeep(_) => JumpIfFalseKeep(target),
+            JumpIfTrueKeep(_) => JumpIfTrueKeep(target),
+            JumpIfNotUndefined(_) => JumpIfNotUndefined(target),
+            EnterTry(_) => EnterTry(target),
+            IteratorNext(_) => IteratorNext(target),
+            instruction => unreachable!("{:?} has no jump target", instruction),
+        };
+    }
+
+    fn name(&mut self, name: &str) -> u32 {
+        if let Some(index) = self.name_indexes.get(name) {
+            return *index;
+        }
+
+        let index = self.block.names.len() as u32;
+        self.block.names.push(name.into());
+        self.name_indexes.insert(name.into(), index);
+        index
+    }
+
+    fn constant(&mut self, value: JsValue) -> u32 {
+        self.block.constants.push(value);
+        (self.block.constants.len() - 1) as u32
+    }
+
+    fn position(&mut self, position: Position) -> u32 {
+        self.block.positions.push(position);
+        (self.block.positions.len() - 1) as u32
+    }
+
+    fn function(&mut self, function: &'a Function) -> Result<u32> {
+        let code = Compiler::compile_function(function)?;
+        self.block.functions.push(Rc::new(code));
+        Ok((self.block.functions.len() - 1) as u32)
+    }
+
+    fn reset_completion(&mut self) {
+        if self.track_completion {
+            self.emit(LoadUndefined);
+            self.emit(SetCompletion);
+        }
+    }
+
+    /// Hoist the `var` of a script or function body, then declare its lexical declarations
+    fn declarations(&mut self, statements: &'a [Statement]) -> Result<()> {
+        let mut names = Vec::new();
+        for statement in statements {
+            statement.var_declared_names(&mut names);
+        }
+        for name in names {
+            let name = self.name(name);
+            self.emit(DeclareVariable(name, DeclarationKind::Var));
+        }
+
+        self.lexical_declarations(statements)
+    }
+
+    /// Same as `instantiate_lexical_declarations`
+    fn lexical_declarations(&mut self, statements: &'a [Statement]) -> Result<()> {
+        for statement in statements {
+            match statement {
+                Statement::VariableDeclaration(vd) if vd.kind != DeclarationKind::Var => {
+                    for name in vd.bound_names() {
+                        let name = self.name(name);
+                        self.emit(DeclareVariable(name, vd.kind));
+                    }
+                }
+                Statement::FunctionDeclaration(fd) => {
+                    let function = fd.function();
+                    let name = self.name(function.name().unwrap().name());
+                    let index = self.function(function)?;
+                    self.emit(DeclareVariable(name, DeclarationKind::Var));
+                    self.emit(CreateFunction(index));
+                    self.emit(SetVariable(name));
+                }
+                Statement::ClassDeclaration(cd) => {
+                    let name = self.name(cd.name());
+                    self.emit(DeclareVariable(name, DeclarationKind::Let));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self, statement: &'a Statement) -> Result<()> {
+        match statement {
+            Statement::ExpressionStatement(expr) => {
+                self.expression(expr)?;
+                if self.track_completion {
+                    self.emit(SetCompletion);
+                } else {
+                    self.emit(Pop);
+                }
+            }
+            Statement::VariableDeclaration(vd) => {
+                for (target, init) in vd.declarations.iter() {
+                    match init {
+                        Some(init) => self.expression(init)?,
+                        // `var x;` keep the current value
+                        None if vd.kind == DeclarationKind::Var => continue,
+                        None => {
+                            self.emit(LoadUndefined);
+                        }
+                    }
+                    self.binding(target, vd.kind != DeclarationKind::Var)?;
+                }
+            }
+            Statement::FunctionDeclaration(_) => {}
+            Statement::ClassDeclaration(cd) => {
+                self.class(cd.class())?;
+                let name = self.name(cd.name());
+                self.emit(InitializeVariable(name));
+            }
+            Statement::ReturnStatement(argument) => self.return_statement(argument.as_ref())?,
+            Statement::BlockStatement(block) => {
+                self.reset_completion();
+                self.block(block)?;
+            }
+            Statement::IfStatement(s) => {
+                self.reset_completion();
+                self.expression(&s.test)?;
+                let to_alternate = self.emit(JumpIfFalse(0));
+                self.statement(&s.consequent)?;
+                match &s.alternate {
+                    Some(alternate) => {
+                        let to_end = self.emit(Jump(0));
+                        self.patch(to_alternate);
+                        self.statement(alternate)?;
+                        self.patch(to_end);
+                    }
+                    None => self.patch(to_alternate),
+                }
+            }
+            Statement::WhileStatement(s) => self.while_statement(s, Vec::new())?,
+            Statement::ForStatement(s) => self.for_statement(s, Vec::new())?,
+            Statement::ForInStatement(s) => self.for_in_statement(s, Vec::new())?,
+            Statement::SwitchStatement(s) => self.switch_statement(s)?,
+            Statement::LabelledStatement(s) => self.labelled_statement(s, Vec::new())?,
+            Statement::TryStatement(s) => self.try_statement(s)?,
+            Statement::ThrowStatement(s) => {
+                self.expression(&s.argument)?;
+                let position = self.position(s.position);
+                self.emit(SetPosition(position));
+                self.emit(Throw);
+            }
+            Statement::BreakStatement(label) => self.jump_statement(label.as_ref(), false)?,
+            Statement::ContinueStatement(label) => self.jump_statement(label.as_ref(), true)?,
+            Statement::EmptyStatement | Statement::DebuggerStatement => {}
+        }
+        Ok(())
+    }
+
+    fn block(&mut self, block: &'a BlockStatement) -> Result<()> {
+        let statements = block.statements();
+        // A scope nothing is declared in can't be observed
+        let has_scope = statements.iter().any(|statement| match statement {
+            Statement::VariableDeclaration(vd) => vd.kind != DeclarationKind::Var,
+            Statement::FunctionDeclaration(_) | Statement::ClassDeclaration(_) => true,
+            _ => false,
+        });
+
+        if has_scope {
+            self.emit(PushEnvironment);
+            self.control.push(Control::Environment);
+            self.lexical_declarations(statements)?;
+        }
+
+        for statement in statements {
+            self.statement(statement)?;
+        }
+
+        if has_scope {
+            self.control.pop();
+            self.emit(PopEnvironment);
+        }
+        Ok(())
+    }
+
+    /// `finally` blocks don't change the completion value
+    fn finally_block(&mut self, block: &'a BlockStatement) -> Result<()> {
+        let track_completion = std::mem::replace(&mut self.track_completion, false);
+        self.block(block)?;
+        self.track_completion = track_completion;
+        Ok(())
+    }
+
+    fn push_breakable(&mut self, kind: BreakableKind, labels: Vec<&'a str>) {
+        self.control.push(Control::Breakable(Breakable {
+            kind,
+            labels,
+            breaks: Vec::new(),
+            continues: Vec::new(),
+        }));
+    }
+
+    /// Point the `continue` of the innermost breakable to `continue_target`, its `break` here
+    fn pop_breakable(&mut self, continue_target: usize) {
+        let breakable = match self.control.pop() {
+            Some(Control::Breakable(breakable)) => breakable,
+            _ => unreachable!("pop_breakable() without a breakable"),
+        };
+
+        for jump in breakable.continues {
+            self.patch_to(jump, continue_target);
+        }
+        for jump in breakable.breaks {
+            self.patch(jump);
+        }
+    }
+
+    /// Undo everything in `control[depth..]`, innermost first, running the `finally` blocks
+    fn unwind(&mut self, depth: usize) -> Result<()> {
+        for index in (depth..self.control.len()).rev() {
+            match &self.control[index] {
+                Control::Environment => {
+                    self.emit(PopEnvironment);
+                }
+                Control::Handler => {
+                    self.emit(LeaveTry);
+                }
+                Control::Iterator => {
+                    self.emit(PopIterator);
+                }
+                Control::StackValue => {
+                    self.emit(Pop);
+                }
+                Control::Finally(block) => {
+                    let block = *block;
+                    self.emit(LeaveTry);
+                    // The finally block only see what is around its `try`
+                    let inner = self.control.split_off(index);
+                    self.finally_block(block)?;
+                    self.control.extend(inner);
+                }
+                Control::Breakable(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn jump_statement(&mut self, label: Option<&'a Identifier>, is_continue: bool) -> Result<()> {
+        let label = label.map(|label| label.name().as_str());
+        let target = self.control.iter().rposition(|control| match control {
+            Control::Breakable(breakable) => match label {
+                Some(label) => breakable.labels.contains(&label),
+                None if is_continue => breakable.kind == BreakableKind::Loop,
+                None => breakable.kind != BreakableKind::Label,
+            },
+            _ => false,
+        });
+
+        let target = match (target, label) {
+            (Some(target), _) => target,
+            (None, Some(label)) => return Err(CompileError::UndefinedLabel(label.into())),
+            (None, None) if is_continue => return Err(CompileError::IllegalContinue),
+            (None, None) => return Err(CompileError::IllegalBreak),
+        };
+
+        self.unwind(target + 1)?;
+        let jump = self.emit(Jump(0));
+        match &mut self.control[target] {
+            Control::Breakable(breakable) if is_continue => {
+                if breakable.kind != BreakableKind::Loop {
+                    return Err(CompileError::IllegalContinue);
+                }
+                breakable.continues.push(jump);
+            }
+            Control::Breakable(breakable) => breakable.breaks.push(jump),
+            _ => unreachable!(),
+        }
+        Ok(())
+    }
+
+    fn return_statement(&mut self, argument: Option<&'a Expression>) -> Result<()> {
+        if !self.is_function {
+            return Err(CompileError::IllegalReturn);
+        }
+
+        match argument {
+            Some(argument) => self.expression(argument)?,
+            None => {
+                self.emit(LoadUndefined);
+            }
+        }
+        self.return_value()
+    }
+
+    /// Return the value on top of the stack
+    fn return_value(&mut self) -> Result<()> {
+        // Handlers and scopes belong to the frame, only `finally` blocks need to run and the
+        // iterators of `for of` to be closed
+        let needs_unwind = self.control.iter().any(|control| {
+            matches!(
+                control,
+                Control::Finally(_) | Control::StackValue | Control::Iterator
+            )
+        });
+        if needs_unwind {
+            self.emit(StoreReturn);
+            self.unwind(0)?;
+            self.emit(LoadReturn);
+        }
+        self.emit(Return);
+        Ok(())
+    }
+
+    fn while_statement(&mut self, s: &'a WhileStatement, labels: Vec<&'a str>) -> Result<()> {
+        self.reset_completion();
+        self.push_breakable(BreakableKind::Loop, labels);
+
+        if s.is_do_while {
+            let start = self.here();
+            self.statement(&s.body)?;
+            let test = self.here();
+            self.expression(&s.test)?;
+            self.emit(JumpIfTrue(start as u32));
+            self.pop_breakable(test);
+        } else {
+            let start = self.here();
+            self.expression(&s.test)?;
+            let to_end = self.emit(JumpIfFalse(0));
+            self.statement(&s.body)?;
+            self.emit(Jump(start as u32));
+            self.patch(to_end);
+            self.pop_breakable(start);
+        }
+        Ok(())
+    }
+
+    fn for_statement(&mut self, s: &'a ForStatement, labels: Vec<&'a str>) -> Result<()> {
+        self.reset_completion();
+        self.emit(PushEnvironment);
+        self.control.push(Control::Environment);
+
+        match s.init.as_deref() {
+            Some(Statement::ExpressionStatement(init)) => {
+                self.expression(init)?;
+                self.emit(Pop);
+            }
+            Some(init) => {
+                if let Statement::VariableDeclaration(vd) = init {
+                    if vd.kind != DeclarationKind::Var {
+                        for name in vd.bound_names() {
+                            let name = self.name(name);
+                            self.emit(DeclareVariable(name, vd.kind));
+                        }
+                    }
+                }
+                self.statement(init)?;
+            }
+            None => {}
+        }
+
+        let per_iteration = !s.per_iteration_names().is_empty();
+        if per_iteration {
+            self.emit(CopyEnvironment);
+        }
+
+        self.push_breakable(BreakableKind::Loop, labels);
+        let start = self.here();
+        let to_end = match &s.test {
+            Some(test) => {
+                self.expression(test)?;
+                Some(self.emit(JumpIfFalse(0)))
+            }
+            None => None,
+        };
+
+        self.statement(&s.body)?;
+
+        let next = self.here();
+        if per_iteration {
+            self.emit(CopyEnvironment);
+        }
+        if let Some(update) = &s.update {
+            self.expression(update)?;
+            self.emit(Pop);
+        }
+        self.emit(Jump(start as u32));
+
+        if let Some(to_end) = to_end {
+            self.patch(to_end);
+        }
+        self.pop_breakable(next);
+        self.control.pop();
+        self.emit(PopEnvironment);
+        Ok(())
+    }
+
+    fn for_in_statement(&mut self, s: &'a ForInStatement, labels: Vec<&'a str>) -> Result<()> {
+        self.reset_completion();
+        self.expression(&s.iterated)?;
+        self.emit(CreateIterator(s.kind));
+        self.control.push(Control::Iterator);
+        self.push_breakable(BreakableKind::Loop, labels);
+
+        let start = self.here();
+        let to_end = self.emit(IteratorNext(0));
+        match &s.binding {
+            ForBinding::Identifier(id) => {
+                let name = self.name(id.name());
+                self.emit(SetVariable(name));
+                self.statement(&s.body)?;
+            }
+            ForBinding::Declaration(DeclarationKind::Var, target) => {
+                self.binding(target, false)?;
+                self.statement(&s.body)?;
+            }
+            ForBinding::Declaration(kind, target) => {
+                self.emit(PushEnvironment);
+                self.control.push(Control::Environment);
+                let mut names = Vec::new();
+                target.bound_names(&mut names);
+                for name in names {
+                    let name = self.name(name);
+                    self.emit(DeclareVariable(name, *kind));
+                }
+                self.binding(target, true)?;
+                self.statement(&s.body)?;
+                self.control.pop();
+                self.emit(PopEnvironment);
+            }
+        }
+        self.emit(Jump(start as u32));
+
+        self.patch(to_end);
+        self.pop_breakable(start);
+        self.control.pop();
+        self.emit(PopIterator);
+        Ok(())
+    }
+
+    fn switch_statement(&mut self, s: &'a SwitchStatement) -> Result<()> {
+        self.reset_completion();
+        self.expression(&s.discriminant)?;
+
+        // All the cases share one scope
+        self.emit(PushEnvironment);
+        self.control.push(Control::Environment);
+        for case in s.cases.iter() {
+            self.lexical_declarations(&case.consequent)?;
+        }
+
+        // The discriminant stays on the stack while the tests run
+        let mut to_cases = Vec::with_capacity(s.cases.len());
+        for case in s.cases.iter() {
+            match &case.test {
+                Some(test) => {
+                    self.emit(Dup);
+                    self.expression(test)?;
+                    self.emit(Compare(CompareOp::StrictEqual));
+                    let to_next = self.emit(JumpIfFalse(0));
+                    self.emit(Pop);
+                    to_cases.push(Some(self.emit(Jump(0))));
+                    self.patch(to_next);
+                }
+                None => to_cases.push(None),
+            }
+        }
+        self.emit(Pop);
+        let to_default = self.emit(Jump(0));
+
+        // Fall through every case after the matching one
+        self.push_breakable(BreakableKind::Switch, Vec::new());
+        let mut has_default = false;
+        for (case, to_case) in s.cases.iter().zip(to_cases) {
+            match to_case {
+                Some(to_case) => self.patch(to_case),
+                None => {
+                    has_default = true;
+                    self.patch(to_default);
+                }
+            }
+            for statement in case.consequent.iter() {
+                self.statement(statement)?;
+            }
+        }
+        if !has_default {
+            self.patch(to_default);
+        }
+
+        let end = self.here();
+        self.pop_breakable(end);
+        self.control.pop();
+        self.emit(PopEnvironment);
+        Ok(())
+    }
+
+    /// `outer` are the labels directly around this one, as in `a: b: while (...)`
+    fn labelled_statement(&mut self, s: &'a LabelledStatement, outer: Vec<&'a str>) -> Result<()> {
+        let mut labels = outer;
+        labels.push(s.label.name());
+
+        match s.body.as_ref() {
+            Statement::LabelledStatement(s) => self.labelled_statement(s, labels),
+            Statement::WhileStatement(s) => self.while_statement(s, labels),
+            Statement::ForStatement(s) => self.for_statement(s, labels),
+            Statement::ForInStatement(s) => self.for_in_statement(s, labels),
+            body => {
+                self.push_breakable(BreakableKind::Label, labels);
+                self.statement(body)?;
+                let end = self.here();
+                self.pop_breakable(end);
+                Ok(())
+            }
+        }
+    }
+
+    /// The `finally` block is compiled twice, once for the normal path and once for
+    /// exceptions, where it rethrow the exception at the end
+    fn try_statement(&mut self, s: &'a TryStatement) -> Result<()> {
+        self.reset_completion();
+        let to_handler = self.emit(EnterTry(0));
+        self.control.push(match &s.finalizer {
+            Some(finalizer) => Control::Finally(finalizer),
+            None => Control::Handler,
+        });
+        self.block(&s.block)?;
+        self.control.pop();
+        self.emit(LeaveTry);
+        let to_finally = self.emit(Jump(0));
+
+        self.patch(to_handler);
+        let handler = match &s.handler {
+            Some(handler) => handler,
+            None => {
+                // Only a finally, the exception is on the stack
+                let finalizer = s.finalizer.as_ref().unwrap();
+                self.rethrowing_finally(finalizer)?;
+                self.patch(to_finally);
+                return self.finally_block(finalizer);
+            }
+        };
+
+        let to_catch_finally = match &s.finalizer {
+            Some(finalizer) => {
+                let jump = self.emit(EnterTry(0));
+                self.control.push(Control::Finally(finalizer));
+                Some(jump)
+            }
+            None => None,
+        };
+        self.reset_completion();
+        self.catch_clause(handler)?;
+
+        match &s.finalizer {
+            Some(finalizer) => {
+                self.control.pop();
+                self.emit(LeaveTry);
+                self.patch(to_finally);
+                self.finally_block(finalizer)?;
+                let to_end = self.emit(Jump(0));
+                self.patch(to_catch_finally.unwrap());
+                self.rethrowing_finally(finalizer)?;
+                self.patch(to_end);
+            }
+            None => self.patch(to_finally),
+        }
+        Ok(())
+    }
+
+    /// Run `finally` with the exception on the stack, then throw it again
+    fn rethrowing_finally(&mut self, finalizer: &'a BlockStatement) -> Result<()> {
+        self.control.push(Control::StackValue);
+        self.finally_block(finalizer)?;
+        self.control.pop();
+        self.emit(Throw);
+        Ok(())
+    }
+
+    /// The exception is on the stack
+    fn catch_clause(&mut self, handler: &'a CatchClause) -> Result<()> {
+        let param = match &handler.param {
+            Some(param) => param,
+            None => {
+                self.emit(Pop);
+                return self.block(&handler.body);
+            }
+        };
+
+        self.emit(PushEnvironment);
+        self.control.push(Control::Environment);
+        let mut names = Vec::new();
+        param.bound_names(&mut names);
+        for name in names {
+            let name = self.name(name);
+            self.emit(DeclareVariable(name, DeclarationKind::Let));
+        }
+        self.binding(param, true)?;
+        self.block(&handler.body)?;
+        self.control.pop();
+        self.emit(PopEnvironment);
+        Ok(())
+    }
+
+    fn expression(&mut self, expr: &'a Expression) -> Result<()> {
+        match expr {
+            Expression::BinaryOperation(e) => self.binary_operation(e)?,
+            Expression::Identifier(ident) => {
+                let name = self.name(ident.name());
+                self.emit(GetVariable(name));
+            }
+            Expression::Literal(literal) => {
+                let value = match literal {
+                    Literal::Null => {
+                        self.emit(LoadNull);
+                        return Ok(());
+                    }
+                    Literal::Boolean(true) => {
+                        self.emit(LoadTrue);
+                        return Ok(());
+                    }
+                    Literal::Boolean(false) => {
+                        self.emit(LoadFalse);
+                        return Ok(());
+                    }
+                    Literal::Integer(v) => JsValue::integer(*v),
+                    Literal::Rational(v) => JsValue::rational(*v),
+                    Literal::BigInt(v) => JsValue::BigInt(v.clone()),
+                    Literal::String(s) => JsValue::string(s.as_ref()),
+                };
+                let constant = self.constant(value);
+                self.emit(LoadConstant(constant));
+            }
+            Expression::CallExpression(call) => self.call(call)?,
+            Expression::TaggedTemplate(e) => {
+                let position = self.position(e.position);
+                self.emit(SetPosition(position));
+                self.callee(&e.tag)?;
+                self.block.templates.push(e.template.strings.clone());
+                let index = self.block.templates.len() - 1;
+                self.emit(CreateTemplateObject(index as u32));
+                for substitution in e.template.substitutions.iter() {
+                    self.expression(substitution)?;
+                }
+                self.emit(SetPosition(position));
+                self.emit(Call(e.template.substitutions.len() as u32 + 1));
+            }
+            Expression::TemplateLiteral(e) => self.template_literal(e)?,
+            Expression::RegExpLiteral(e) => {
+                self.block.regexps.push(e.regex.clone());
+                let index = self.block.regexps.len() - 1;
+                self.emit(CreateRegExp(index as u32));
+            }
+            Expression::This => {
+                self.emit(LoadThis);
+            }
+            Expression::NewTarget => {
+                self.emit(LoadNewTarget);
+            }
+            Expression::NewExpression(e) => self.new_expression(e)?,
+            Expression::SuperCall(e) => self.super_call(e)?,
+            Expression::SuperProperty(e) => {
+                self.super_key(e)?;
+                self.emit(GetSuperProperty);
+            }
+            Expression::PrivateName(name) => {
+                let name = self.name(name.name());
+                self.emit(GetVariable(name));
+            }
+            Expression::ClassExpression(e) => self.class(e.class())?,
+            Expression::YieldExpression(e) => {
+                match &e.argument {
+                    Some(argument) => self.expression(argument)?,
+                    None => {
+                        self.emit(LoadUndefined);
+                    }
+                }
+                if e.delegate {
+                    self.emit(GetIterator);
+                    self.emit(YieldDelegate);
+                } else {
+                    self.emit(Yield);
+                    self.emit(Resume);
+                }
+                // `return()` called on the generator returns from the `yield`
+                let to_end = self.emit(JumpIfFalse(0));
+                self.return_value()?;
+                self.patch(to_end);
+            }
+            Expression::AwaitExpression(e) => {
+                self.expression(&e.argument)?;
+                self.emit(Await);
+                self.emit(Resume);
+                self.emit(Pop);
+            }
+            Expression::SpreadElement(_) => unreachable!("spread outside of a call or an array"),
+            Expression::ObjectExpression(object) => {
+                self.emit(CreateObject);
+                for prop in object.0.iter() {
+                    if prop.kind == ObjectPropertyKind::Spread {
+                        self.expression(&prop.key)?;
+                        self.emit(CopyDataProperties);
+                        continue;
+                    }
+                    if prop.is_proto_setter() {
+                        self.expression(prop.value.as_ref().unwrap())?;
+                        self.emit(SetPrototype);
+                        continue;
+                    }
+
+                    match prop.computed {
+                        true => self.expression(&prop.key)?,
+                        false => self.property_key(&prop.key)?,
+                    }
+                    // `{ a }` is a shorthand for `{ a: a }`
+                    match &prop.value {
+                        Some(value) => self.expression(value)?,
+                        None => self.expression(&prop.key)?,
+                    }
+                    self.emit(DefineProperty(prop.kind));
+                }
+            }
+            Expression::ArrayExpression(array) => {
+                self.emit(CreateArray);
+                for element in array.0.iter() {
+                    match element {
+                        Some(Expression::SpreadElement(spread)) => {
+                            self.expression(&spread.argument)?;
+                            self.emit(AppendSpread);
+                        }
+                        Some(element) => {
+                            self.expression(element)?;
+                            self.emit(AppendElement);
+                        }
+                        None => {
+                            self.emit(AppendHole);
+                        }
+                    }
+                }
+            }
+            Expression::MemberExpression(member) => {
+                self.expression(&member.object)?;
+                self.member_key(member)?;
+                self.emit(GetProperty);
+            }
+            Expression::FunctionExpression(e) => {
+                let function = e.function();
+                let index = self.function(function)?;
+                match function.name() {
+                    // A named function expression can refer to itself, from a scope only it can see
+                    Some(name) => {
+                        let name = self.name(name.name());
+                        self.emit(PushEnvironment);
+                        self.emit(DeclareVariable(name, DeclarationKind::Const));
+                        self.emit(CreateFunction(index));
+                        self.emit(Dup);
+                        self.emit(InitializeVariable(name));
+                        self.emit(PopEnvironment);
+                    }
+                    None => {
+                        self.emit(CreateFunction(index));
+                    }
+                }
+            }
+            Expression::UnaryExpression(e) => match (e.op, e.argument.as_ref()) {
+                (UnaryOp::Typeof, Expression::Identifier(ident)) => {
+                    let name = self.name(ident.name());
+                    self.emit(TypeofVariable(name));
+                }
+                // Variables can't be deleted, only properties
+                (UnaryOp::Delete, Expression::Identifier(_)) => {
+                    self.emit(LoadFalse);
+                }
+                (UnaryOp::Delete, Expression::MemberExpression(member)) => {
+                    self.expression(&member.object)?;
+                    self.member_key(member)?;
+                    self.emit(DeleteProperty);
+                }
+                (op, argument) => {
+                    self.expression(argument)?;
+                    self.emit(Unary(op));
+                }
+            },
+            Expression::UpdateExpression(e) => {
+                let update = if e.is_increment { Increment } else { Decrement };
+                match e.argument.as_ref() {
+                    Expression::Identifier(ident) => {
+                        let name = self.name(ident.name());
+                        self.emit(GetVariable(name));
+                        self.emit(ToNumeric);
+                        // Keep a copy of the old value for `x++`, of the new one for `++x`
+                        if e.is_prefix {
+                            self.emit(update);
+                            self.emit(Dup);
+                        } else {
+                            self.emit(Dup);
+                            self.emit(update);
+                        }
+                        self.emit(SetVariable(name));
+                    }
+                    Expression::MemberExpression(member) => {
+                        self.expression(&member.object)?;
+                        self.member_key(member)?;
+                        self.emit(Dup2);
+                        self.emit(GetProperty);
+                        self.emit(ToNumeric);
+                        // The old value of `a.x++` goes below the object and the key
+                        if !e.is_prefix {
+                            self.emit(Dup);
+                            self.emit(MoveDown(3));
+                        }
+                        self.emit(update);
+                        self.emit(SetProperty);
+                        if !e.is_prefix {
+                            self.emit(Pop);
+                        }
+                    }
+                    _ => return Err(CompileError::InvalidAssignmentTarget),
+                }
+            }
+            Expression::ConditionalExpression(e) => {
+                self.expression(&e.test)?;
+                let to_alternate = self.emit(JumpIfFalse(0));
+                self.expression(&e.consequent)?;
+                let to_end = self.emit(Jump(0));
+                self.patch(to_alternate);
+                self.expression(&e.alternate)?;
+                self.patch(to_end);
+            }
+        }
+        Ok(())
+    }
+
+    /// Push the key of a property in an object literal or pattern, names are strings
+    fn property_key(&mut self, key: &'a Expression) -> Result<()> {
+        match key {
+            Expression::Identifier(ident) => {
+                let key = self.constant(JsValue::string(ident.name().as_ref()));
+                self.emit(LoadConstant(key));
+            }
+            key => self.expression(key)?,
+        }
+        Ok(())
+    }
+
+    /// Push `this` and the function, methods are called with the object they were read from
+    fn callee(&mut self, callee: &'a Expression) -> Result<()> {
+        match callee {
+            Expression::MemberExpression(member) => {
+                self.expression(&member.object)?;
+                self.emit(Dup);
+                self.member_key(member)?;
+                self.emit(GetProperty);
+            }
+            Expression::SuperProperty(property) => {
+                self.emit(LoadThis);
+                self.super_key(property)?;
+                self.emit(GetSuperProperty);
+            }
+            callee => {
+                self.emit(LoadUndefined);
+                self.expression(callee)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn call(&mut self, call: &'a CallExpression) -> Result<()> {
+        let position = self.position(call.position);
+        self.emit(SetPosition(position));
+        self.callee(&call.ident)?;
+        let has_spread = self.arguments(&call.args)?;
+
+        // The arguments may have called other functions
+        self.emit(SetPosition(position));
+        if has_spread {
+            self.emit(CallSpread);
+        } else {
+            self.emit(Call(call.args.len() as u32));
+        }
+        Ok(())
+    }
+
+    /// Push the arguments of a call, `new` or `super()`, in an array if some are spread
+    fn arguments(&mut self, args: &'a [Expression]) -> Result<bool> {
+        let has_spread = args
+            .iter()
+            .any(|arg| matches!(arg, Expression::SpreadElement(_)));
+        if has_spread {
+            self.emit(CreateArray);
+            for arg in args.iter() {
+                match arg {
+                    Expression::SpreadElement(spread) => {
+                        self.expression(&spread.argument)?;
+                        self.emit(AppendSpread);
+                    }
+                    arg => {
+                        self.expression(arg)?;
+                        self.emit(AppendElement);
+                    }
+                }
+            }
+        } else {
+            for arg in args.iter() {
+                self.expression(arg)?;
+            }
+        }
+        Ok(has_spread)
+    }
+
+    fn new_expression(&mut self, e: &'a NewExpression) -> Result<()> {
+        let position = self.position(e.position);
+        self.emit(SetPosition(position));
+        self.expression(&e.callee)?;
+        let has_spread = self.arguments(&e.args)?;
+
+        self.emit(SetPosition(position));
+        if has_spread {
+            self.emit(NewSpread);
+        } else {
+            self.emit(New(e.args.len() as u32));
+        }
+        Ok(())
+    }
+
+    fn super_call(&mut self, e: &'a SuperCall) -> Result<()> {
+        let has_spread = self.arguments(&e.args)?;
+        let position = self.position(e.position);
+        self.emit(SetPosition(position));
+        if has_spread {
+            self.emit(SuperCallSpread);
+        } else {
+            self.emit(SuperCall(e.args.len() as u32));
+        }
+        Ok(())
+    }
+
+    /// Push the key of `super.x` or `super[x]`
+    fn super_key(&mut self, e: &'a SuperProperty) -> Result<()> {
+        match e.property.as_ref() {
+            Expression::Identifier(ident) if !e.computed => {
+                let key = self.constant(JsValue::string(ident.name().as_ref()));
+                self.emit(LoadConstant(key));
+            }
+            property => self.expression(property)?,
+        }
+        Ok(())
+    }
+
+    /// Push the class, evaluated in a scope holding its name and its private names
+    fn class(&mut self, class: &'a Rc<Class>) -> Result<()> {
+        self.block.classes.push(class.clone());
+        let index = (self.block.classes.len() - 1) as u32;
+        let name = class.name().map(|name| self.name(name.name()));
+
+        self.emit(PushEnvironment);
+        if let Some(name) = name {
+            self.emit(DeclareVariable(name, DeclarationKind::Const));
+        }
+        self.emit(DeclarePrivateNames(index));
+        if let Some(heritage) = class.heritage() {
+            self.expression(heritage)?;
+        }
+        let constructor = self.function(class.constructor())?;
+        self.emit(CreateFunction(constructor));
+
+        for element in class.elements() {
+            match &element.key {
+                Expression::Identifier(ident) if !element.computed => {
+                    let key = self.constant(JsValue::string(ident.name().as_ref()));
+                    self.emit(LoadConstant(key));
+                }
+                key => self.expression(key)?,
+            }
+            match &element.function {
+                Some(function) => {
+                    let function = self.function(function)?;
+                    self.emit(CreateFunction(function));
+                }
+                None => {
+                    self.emit(LoadUndefined);
+                }
+            }
+        }
+
+        self.emit(CreateClass(index));
+        self.emit(PopEnvironment);
+        Ok(())
+    }
+
+    /// The strings are concatenated with the substitutions converted to strings
+    fn template_literal(&mut self, e: &'a TemplateLiteral) -> Result<()> {
+        let cooked = &e.strings.cooked;
+        let first = self.constant(JsValue::string(cooked[0].as_deref().unwrap_or_default()));
+        self.emit(LoadConstant(first));
+        for (substitution, string) in e.substitutions.iter().zip(&cooked[1..]) {
+            self.expression(substitution)?;
+            self.emit(ToString);
+            self.emit(Numeric(NumericOp::Addition));
+            let string = string.as_deref().unwrap_or_default();
+            let string = self.constant(JsValue::string(string));
+            self.emit(LoadConstant(string));
+            self.emit(Numeric(NumericOp::Addition));
+        }
+        Ok(())
+    }
+
+    /// Pop a value and store it in the variables of `target`, see `BindingPattern::bind`
+    fn binding(&mut self, target: &'a BindingPattern, initialize: bool) -> Result<()> {
+        match target {
+            BindingPattern::Identifier(id) => {
+                let name = self.name(id.name());
+                if initialize {
+                    self.emit(InitializeVariable(name));
+                } else {
+                    self.emit(SetVariable(name));
+                }
+            }
+            BindingPattern::Object(pattern) => {
+                self.emit(RequireObjectCoercible);
+                // With a rest, the keys taken stay below the object to be excluded from it
+                let has_rest = pattern.rest.is_some();
+                for property in pattern.properties.iter() {
+                    self.emit(Dup);
+                    self.property_key(&property.key)?;
+                    if has_rest {
+                        self.emit(Dup);
+                        self.emit(MoveDown(3));
+                    }
+                    self.emit(GetProperty);
+                    self.binding_element(&property.value, initialize)?;
+                }
+                match &pattern.rest {
+                    Some(rest) => {
+                        self.emit(CreateRestObject(pattern.properties.len() as u32));
+                        self.binding(rest, initialize)?;
+                    }
+                    None => {
+                        self.emit(Pop);
+                    }
+                }
+            }
+            BindingPattern::Array(pattern) => {
+                self.emit(CreateIterator(ForInKind::Of));
+                self.control.push(Control::Iterator);
+                for element in pattern.elements.iter() {
+                    self.emit(IteratorValue);
+                    match element {
+                        Some(element) => self.binding_element(element, initialize)?,
+                        None => {
+                            self.emit(Pop);
+                        }
+                    }
+                }
+                if let Some(rest) = &pattern.rest {
+                    self.emit(IteratorRest);
+                    self.binding(rest, initialize)?;
+                }
+                self.control.pop();
+                self.emit(PopIterator);
+            }
+        }
+        Ok(())
+    }
+
+    fn binding_element(&mut self, element: &'a BindingElement, initialize: bool) -> Result<()> {
+        if let Some(default) = &element.default {
+            let to_binding = self.emit(JumpIfNotUndefined(0));
+            self.expression(default)?;
+            self.patch(to_binding);
+        }
+        self.binding(&element.target, initialize)
+    }
+
+    /// Push the key of `object.key` or `object[key]`
+    fn member_key(&mut self, member: &'a MemberExpression) -> Result<()> {
+        match member.property.as_ref() {
+            Expression::Identifier(ident) if !member.computed => {
+                let key = self.constant(JsValue::string(ident.name().as_ref()));
+                self.emit(LoadConstant(key));
+            }
+            property => self.expression(property)?,
+        }
+        Ok(())
+    }
+
+    fn binary_operation(&mut self, e: &'a BinaryOperation) -> Result<()> {
+        let instruction = match e.op {
+            BinaryOp::NumericOp(op) => Numeric(op),
+            BinaryOp::BitwiseOp(op) => Bitwise(op),
+            BinaryOp::CompareOp(op) => Compare(op),
+            BinaryOp::AssignmentOp(op) => {
+                match e.lhs.as_ref() {
+                    Expression::Identifier(ident) => {
+                        let name = self.name(ident.name());
+                        // A plain assignment never read the target, it may not even exist yet
+                        if op == AssignmentOp::Assignment {
+                            self.expression(&e.rhs)?;
+                        } else {
+                            self.emit(GetVariable(name));
+                            self.expression(&e.rhs)?;
+                            self.emit(Assign(op));
+                        }
+                        self.emit(Dup);
+                        self.emit(SetVariable(name));
+                    }
+                    Expression::MemberExpression(member) => {
+                        self.expression(&member.object)?;
+                        self.member_key(member)?;
+                        if op == AssignmentOp::Assignment {
+                            self.expression(&e.rhs)?;
+                        } else {
+                            self.emit(Dup2);
+                            self.emit(GetProperty);
+                            self.expression(&e.rhs)?;
+                            self.emit(Assign(op));
+                        }
+                        self.emit(SetProperty);
+                    }
+                    _ => return Err(CompileError::InvalidAssignmentTarget),
+                }
+                return Ok(());
+            }
+            BinaryOp::BoolAnd | BinaryOp::BoolOr => {
+                self.expression(&e.lhs)?;
+                let to_end = if matches!(e.op, BinaryOp::BoolAnd) {
+                    self.emit(JumpIfFalseKeep(0))
+                } else {
+                    self.emit(JumpIfTrueKeep(0))
+                };
+                self.expression(&e.rhs)?;
+                self.patch(to_end);
+                return Ok(());
+            }
+        };
+
+        self.expression(&e.lhs)?;
+        self.expression(&e.rhs)?;
+        self.emit(instruction);
+        Ok(())
+    }
+}
@@ -1,156 +1,968 @@
 use std::{
-    collections::HashMap,
+    rc::Rc,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
-use super::Runtime;
+use super::{
+    module, BindingError, CodeBlock, EnvironmentRecordKind, Frame, Interpreter, LexicalEnvironment,
+    Macrotask, MacrotaskId, ModuleLoader, ModuleRecord, ModuleSource, Outcome, Realm, Resume,
+    Runtime,
+};
 use crate::{
-    ast::DeclarationKind,
-    gc::{GcCell, GcPointer},
-    jsrt::JsValue,
+    ast::{self, Completion, DeclarationKind, FunctionKind, Position},
+    gc::{GcCell, GcPointer, Handle, Rooted, Roots, Trace},
+    jsrt::{
+        install_builtins, Attributes, ErrorKind, FunctionCode, Job, JsFunction, JsObject, JsSymbol,
+        JsValue, NativeFunction, ObjectKind, PropertyKey, Shape, WellKnownSymbols,
+    },
 };
 
+/// Calls nested deeper than this throw a `RangeError`
+pub const MAX_CALL_DEPTH: usize = 1000;
+
+/// Native stack a call has to leave free, the frames of the interpreters until the next call
+/// and throwing the `RangeError` fit in it. Calls throw before that much is left, whatever the
+/// depth, since the frames of a debug build are large
+const STACK_RESERVE: usize = 256 * 1024;
+
+/// A function being run, for the `stack` of error objects
+#[derive(Debug)]
+pub struct StackFrame {
+    pub function: String,
+    /// Last call or `throw` reached in this frame, `None` until one is
+    pub position: Option<Position>,
+}
+
 pub struct Context {
     runtime: Arc<Mutex<Runtime>>,
-    variables: HashMap<String, GcPointer<JsValue>>,
+    realm: Realm,
+    roots: Roots,
+    /// Rooted so the scope of an idle context survives the collections started by the others
+    environment: Handle<LexicalEnvironment>,
+    call_stack: Vec<StackFrame>,
+    /// Set while a native function runs for `new`
+    constructing: bool,
 }
 
 impl Context {
+    /// Context running in the realm of `rt`, the first one sets the realm up
     pub fn new(rt: Arc<Mutex<Runtime>>) -> Context {
-        Context {
+        let existing = rt.lock().unwrap().realm().cloned();
+        let realm = match &existing {
+            Some(realm) => realm.clone(),
+            None => Realm::new(rt.lock().unwrap().heap()),
+        };
+
+        let roots = rt.lock().unwrap().heap().roots();
+        let mut context = Context {
             runtime: rt,
-            variables: HashMap::new(),
+            environment: Rooted::new(roots.clone(), realm.global_environment.clone()),
+            roots,
+            realm,
+            call_stack: vec![StackFrame {
+                function: "<anonymous>".into(),
+                position: None,
+            }],
+            constructing: false,
+        };
+        if existing.is_none() {
+            // The runtime roots the intrinsics while the built-ins are installed, and then the
+            // error prototypes they add
+            let realm = context.realm.clone();
+            context.runtime.lock().unwrap().set_realm(realm);
+            context.scope(install_builtins);
+            let realm = context.realm.clone();
+            context.runtime.lock().unwrap().set_realm(realm);
+        }
+        context
+    }
+
+    /// `Error`, `TypeError`... with their prototypes, the other kinds inherit from `Error`
+    pub(crate) fn create_error_constructors(&mut self) {
+        let hidden = Attributes::WRITABLE | Attributes::CONFIGURABLE;
+        let mut base_constructor = None;
+
+        for kind in ErrorKind::ALL {
+            let parent = match self.realm.error_prototypes.first() {
+                Some(error_prototype) => error_prototype.clone(),
+                None => self.object_prototype(),
+            };
+            let mut prototype = JsObject::with_prototype(self, Some(parent), ObjectKind::Ordinary);
+            prototype.define_property("name", JsValue::string(kind.name()), hidden);
+            prototype.define_property("message", JsValue::string(""), hidden);
+            self.realm.error_prototypes.push(prototype.clone());
+
+            let mut constructor = JsObject::native_constructor(self, kind.constructor(), prototype);
+            if base_constructor.is_some() {
+                constructor.set_prototype_of(base_constructor.clone());
+            }
+
+            base_constructor.get_or_insert_with(|| constructor.clone());
+            self.define_global(kind.name(), JsValue::object(constructor));
+        }
+    }
+
+    /// Property of the global object, writable and configurable but not enumerable like the
+    /// built-ins are
+    pub(crate) fn define_global(&mut self, name: &str, value: JsValue) {
+        let attributes = Attributes::WRITABLE | Attributes::CONFIGURABLE;
+        self.realm
+            .global_object
+            .define_property(name, value, attributes);
+    }
+
+    pub fn global_object(&self) -> GcPointer<JsObject> {
+        self.realm.global_object.clone()
+    }
+
+    pub fn root_shape(&self) -> Rc<Shape> {
+        self.realm.root_shape.clone()
+    }
+
+    pub fn object_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.object_prototype.clone()
+    }
+
+    pub fn function_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.function_prototype.clone()
+    }
+
+    pub fn array_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.array_prototype.clone()
+    }
+
+    pub fn iterator_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.iterator_prototype.clone()
+    }
+
+    pub fn array_iterator_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.array_iterator_prototype.clone()
+    }
+
+    pub fn string_iterator_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.string_iterator_prototype.clone()
+    }
+
+    pub fn boolean_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.boolean_prototype.clone()
+    }
+
+    pub fn number_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.number_prototype.clone()
+    }
+
+    pub fn string_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.string_prototype.clone()
+    }
+
+    pub fn bigint_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.bigint_prototype.clone()
+    }
+
+    pub fn symbol_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.symbol_prototype.clone()
+    }
+
+    pub fn regexp_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.regexp_prototype.clone()
+    }
+
+    pub fn weak_ref_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.weak_ref_prototype.clone()
+    }
+
+    pub fn weak_map_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.weak_map_prototype.clone()
+    }
+
+    pub fn weak_set_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.weak_set_prototype.clone()
+    }
+
+    pub fn finalization_registry_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.finalization_registry_prototype.clone()
+    }
+
+    pub fn promise_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.promise_prototype.clone()
+    }
+
+    pub fn generator_function_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.generator_function_prototype.clone()
+    }
+
+    pub fn generator_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.generator_prototype.clone()
+    }
+
+    pub fn async_function_prototype(&self) -> GcPointer<JsObject> {
+        self.realm.async_function_prototype.clone()
+    }
+
+    pub fn well_known_symbols(&self) -> &WellKnownSymbols {
+        &self.realm.symbols
+    }
+
+    /// `Symbol.for(key)`, the registry is shared by every context of the runtime
+    pub fn symbol_for(&mut self, key: &str) -> JsSymbol {
+        self.runtime.lock().unwrap().registered_symbol(key)
+    }
+
+    /// The template object created the first time the template at `site` was tagged
+    pub(crate) fn template_object(&self, site: usize) -> Option<GcPointer<JsObject>> {
+        self.runtime.lock().unwrap().template_object(site)
+    }
+
+    pub(crate) fn add_template_object(&mut self, site: usize, template: GcPointer<JsObject>) {
+        self.runtime
+            .lock()
+            .unwrap()
+            .add_template_object(site, template);
+    }
+
+    /// Keep the target of a `WeakRef` alive until `end_job`
+    pub fn keep_alive(&mut self, target: GcPointer<JsObject>) {
+        self.runtime.lock().unwrap().keep_alive(target);
+    }
+
+    /// Call the cleanup callback with the held values of its collected targets, once the
+    /// registry is looked at by a collection
+    pub fn add_finalization_registry(&mut self, registry: &GcPointer<JsObject>) {
+        self.runtime
+            .lock()
+            .unwrap()
+            .add_finalization_registry(registry);
+    }
+
+    /// The end of a script, the jobs it queued run, the targets of `WeakRef`s may be collected
+    /// again and the callbacks of the registries with collected targets are run.
+    ///
+    /// What the callbacks throw is dropped, there is nothing to report it to
+    pub fn end_job(&mut self) {
+        self.run_jobs();
+        let registries = self.runtime.lock().unwrap().end_job();
+        let registries = self.root(registries);
+        for registry in registries.iter() {
+            let mut registry = registry.clone();
+            let (cleanup, held_values) = match &mut registry.kind {
+                ObjectKind::FinalizationRegistry(r) => (r.cleanup_callback(), r.take_cleared()),
+                _ => continue,
+            };
+            let held_values = self.root(held_values);
+            for held_value in held_values.iter() {
+                let _ = self.call(&cleanup, JsValue::Undefined, vec![held_value.clone()]);
+            }
+        }
+    }
+
+    /// Run `job` once the current script and the jobs queued before it are done
+    pub fn enqueue_job(&mut self, job: Job) {
+        self.runtime.lock().unwrap().enqueue_job(job);
+    }
+
+    /// Run the queued jobs until there is none left, the ones they queue included
+    pub fn run_jobs(&mut self) {
+        loop {
+            let job = self.runtime.lock().unwrap().next_job();
+            let job = match job {
+                Some(job) => self.root(job),
+                None => break,
+            };
+            let job = (*job).clone();
+            let _ = self.scope(|context| job.run(context));
+        }
+    }
+
+    /// `setTimeout`, queue `callback` for the host to call with `args` after `delay`
+    pub fn enqueue_macrotask(
+        &mut self,
+        delay: Duration,
+        callback: JsValue,
+        args: Vec<JsValue>,
+    ) -> MacrotaskId {
+        self.runtime
+            .lock()
+            .unwrap()
+            .enqueue_macrotask(delay, callback, args)
+    }
+
+    /// `clearTimeout`, the macrotask won't run
+    pub fn cancel_macrotask(&mut self, id: MacrotaskId) {
+        self.runtime.lock().unwrap().take_macrotask(id);
+    }
+
+    /// Run the macrotask `id` if it is still queued and then the jobs it queued, returning
+    /// what its callback threw
+    pub fn run_macrotask(&mut self, id: MacrotaskId) -> ast::Result<()> {
+        let task = self.runtime.lock().unwrap().take_macrotask(id);
+        match task {
+            Some(task) => self.run_task(task),
+            None => Ok(()),
+        }
+    }
+
+    /// Event loop for the hosts without their own, run the macrotasks in the order they are
+    /// due until there is none left, without waiting for their delay.
+    ///
+    /// Stops at the first callback that throws, the others stay queued
+    pub fn run_macrotasks(&mut self) -> ast::Result<()> {
+        loop {
+            let task = self.runtime.lock().unwrap().take_next_macrotask();
+            match task {
+                Some(task) => self.run_task(task)?,
+                None => return Ok(()),
+            }
+        }
+    }
+
+    fn run_task(&mut self, task: Macrotask) -> ast::Result<()> {
+        let task = self.root(task);
+        self.scope(|context| {
+            let result = context.call(&task.callback, JsValue::Undefined, task.args.clone());
+            context.end_job();
+            result.map(|_| ())
+        })
+    }
+
+    /// Load, link and evaluate the module `specifier` refers to, with the module loader of the
+    /// runtime, and the jobs it queued. Returns its namespace object.
+    ///
+    /// A module is evaluated once per runtime, importing it again gives the same namespace or
+    /// throws the same error
+    pub fn import(&mut self, specifier: &str) -> ast::Result<JsValue> {
+        self.scope(|context| {
+            let mut loaded = Vec::new();
+            let module = match module::load(context, specifier, None, &mut loaded) {
+                Ok(module) => module,
+                Err(error) => {
+                    // The modules loaded along can't be linked without the missing one
+                    for key in loaded {
+                        context.runtime.lock().unwrap().remove_module(&key);
+                    }
+                    return Err(error);
+                }
+            };
+
+            let result =
+                module::link(context, &module).and_then(|_| module::evaluate(context, &module));
+            context.end_job();
+            result?;
+            Ok(JsValue::object(module::namespace(context, &module)))
+        })
+    }
+
+    pub(crate) fn resolve_module(
+        &mut self,
+        specifier: &str,
+        referrer: Option<&str>,
+    ) -> Result<String, String> {
+        self.with_module_loader(|loader| loader.resolve(specifier, referrer))
+    }
+
+    pub(crate) fn load_module_source(&mut self, key: &str) -> Result<ModuleSource, String> {
+        self.with_module_loader(|loader| loader.load(key))
+    }
+
+    /// Run `f` with the module loader, out of the runtime so the runtime isn't locked meanwhile
+    fn with_module_loader<T>(
+        &mut self,
+        f: impl FnOnce(&mut dyn ModuleLoader) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let loader = self.runtime.lock().unwrap().take_module_loader();
+        let mut loader = match loader {
+            Some(loader) => loader,
+            None => return Err("Cannot import modules, the host has no module loader".into()),
+        };
+        let result = f(loader.as_mut());
+        self.runtime.lock().unwrap().restore_module_loader(loader);
+        result
+    }
+
+    pub(crate) fn registered_module(&self, key: &str) -> Option<GcPointer<ModuleRecord>> {
+        self.runtime.lock().unwrap().module(key)
+    }
+
+    pub(crate) fn register_module(&mut self, key: &str, module: GcPointer<ModuleRecord>) {
+        self.runtime.lock().unwrap().insert_module(key, module);
+    }
+
+    pub fn error_prototype(&self, kind: ErrorKind) -> GcPointer<JsObject> {
+        let index = ErrorKind::ALL.iter().position(|k| *k == kind).unwrap();
+        self.realm.error_prototypes[index].clone()
+    }
+
+    pub fn environment(&self) -> GcPointer<LexicalEnvironment> {
+        (*self.environment).clone()
+    }
+
+    pub fn global_environment(&self) -> GcPointer<LexicalEnvironment> {
+        self.realm.global_environment.clone()
+    }
+
+    /// Replace the current scope, returning the old one so it can be restored
+    pub fn set_environment(
+        &mut self,
+        environment: GcPointer<LexicalEnvironment>,
+    ) -> GcPointer<LexicalEnvironment> {
+        self.environment.replace(environment)
+    }
+
+    pub fn push_environment(&mut self, kind: EnvironmentRecordKind) {
+        let environment = self.allocate(LexicalEnvironment::new(kind, Some(self.environment())));
+        self.environment.replace(environment);
+    }
+
+    pub fn pop_environment(&mut self) {
+        let parent = self
+            .environment
+            .parent()
+            .expect("pop_environment() called on the global environment");
+        self.environment.replace(parent);
+    }
+
+    /// Replace the current scope by a copy of it, closures keep seeing the old one
+    pub fn copy_environment(&mut self) {
+        let environment = self.environment.copy();
+        let environment = self.allocate(environment);
+        self.environment.replace(environment);
+    }
+
+    /// Scope of the innermost function that binds `this`, or the global scope
+    fn this_environment(&self) -> GcPointer<LexicalEnvironment> {
+        let mut environment = self.environment();
+        while !environment.has_this_binding() {
+            environment = environment.parent().expect("the global scope binds `this`");
+        }
+        environment
+    }
+
+    /// Function object of the innermost function that binds `this`
+    pub fn active_function(&self) -> Option<GcPointer<JsObject>> {
+        self.this_environment().function()
+    }
+
+    /// `this` of the innermost function that binds it, the global object outside of them
+    pub fn this(&mut self) -> ast::Result<JsValue> {
+        match self.this_environment().this_value() {
+            Some(this) => Ok(this),
+            None => Err(self.uninitialized_this()),
+        }
+    }
+
+    /// `new.target`, `undefined` in the functions called without `new` and outside of functions
+    pub fn new_target(&self) -> JsValue {
+        self.this_environment().new_target()
+    }
+
+    /// Function `super` refers to in the current scope, methods and constructors of classes
+    fn super_function(&mut self) -> ast::Result<GcPointer<JsObject>> {
+        match self.this_environment().function() {
+            Some(function) => Ok(function),
+            None => {
+                Err(self.throw_error(ErrorKind::SyntaxError, "'super' keyword unexpected here"))
+            }
+        }
+    }
+
+    /// `super(...args)` in a derived constructor, construct the parent class and bind the result
+    /// to `this`
+    pub fn super_call(&mut self, args: Vec<JsValue>) -> ast::Result<JsValue> {
+        let function = self.super_function()?;
+        let is_derived = match &function.kind {
+            ObjectKind::Function(f) => f.kind() == FunctionKind::DerivedConstructor,
+            _ => false,
+        };
+        if !is_derived {
+            return Err(self.throw_error(ErrorKind::SyntaxError, "'super' keyword unexpected here"));
+        }
+
+        let parent = match function.get_prototype_of() {
+            Some(parent) if parent.is_constructor() => JsValue::object(parent),
+            parent => {
+                let parent = parent.map_or(JsValue::Null, JsValue::object);
+                let message = format!(
+                    "Super constructor {} is not a constructor",
+                    parent.to_string()
+                );
+                return Err(self.throw_error(ErrorKind::TypeError, &message));
+            }
+        };
+        let mut environment = self.this_environment();
+        let this = self.construct(&parent, args, &environment.new_target())?;
+
+        if environment.this_value().is_some() {
+            let message = "Super constructor may only be called once";
+            return Err(self.throw_error(ErrorKind::ReferenceError, message));
+        }
+        environment.bind_this(this.clone());
+        ast::initialize_instance_elements(self, &this, &function)?;
+        Ok(this)
+    }
+
+    /// `super[key]`, looked up from the prototype of the object the current method belongs to
+    pub fn super_property(&mut self, key: &PropertyKey) -> ast::Result<JsValue> {
+        let function = self.super_function()?;
+        let home_object = match &function.kind {
+            ObjectKind::Function(f) => f.home_object(),
+            _ => None,
+        };
+        let home_object = match home_object {
+            Some(home_object) => home_object,
+            None => {
+                let message = "'super' keyword unexpected here";
+                return Err(self.throw_error(ErrorKind::SyntaxError, message));
+            }
+        };
+
+        let this = self.this()?;
+        match home_object.get_prototype_of() {
+            Some(base) => base.get(self, key, this),
+            None => {
+                let message = format!("Cannot read properties of null (reading '{}')", key);
+                Err(self.throw_error(ErrorKind::TypeError, &message))
+            }
+        }
+    }
+
+    /// Closest scope that declare `name`
+    fn resolve_binding(&self, name: &str) -> Option<GcPointer<LexicalEnvironment>> {
+        let mut environment = Some(self.environment());
+        while let Some(env) = environment {
+            if env.has_binding(name) {
+                return Some(env);
+            }
+            environment = env.parent();
+        }
+
+        None
+    }
+
+    pub fn has_variable(&self, name: &str) -> bool {
+        self.resolve_binding(name).is_some()
+            || self
+                .realm
+                .global_object
+                .has_property(&PropertyKey::from(name))
+    }
+
+    /// Value of the closest variable called `name`, or of the global object property
+    pub fn get_variable(&mut self, name: &str) -> ast::Result<JsValue> {
+        let env = match self.resolve_binding(name) {
+            Some(env) => env,
+            None => {
+                let global = self.global_object();
+                let key = PropertyKey::from(name);
+                if global.has_property(&key) {
+                    return global.get(self, &key, JsValue::object(global.clone()));
+                }
+                let message = format!("{} is not defined", name);
+                return Err(self.throw_error(ErrorKind::ReferenceError, &message));
+            }
+        };
+
+        env.get_binding_value(name).map_err(|_| {
+            let message = format!("Cannot access '{}' before initialization", name);
+            self.throw_error(ErrorKind::ReferenceError, &message)
+        })
+    }
+
+    /// Assign to an existing variable, undeclared ones become properties of the global object
+    pub fn set_variable(&mut self, name: &str, value: JsValue) -> ast::Result<()> {
+        let mut env = match self.resolve_binding(name) {
+            Some(env) => env,
+            None => {
+                let mut global = self.global_object();
+                let receiver = JsValue::object(global.clone());
+                global.set(self, PropertyKey::from(name), value, receiver)?;
+                return Ok(());
+            }
+        };
+
+        match env.set_mutable_binding(name, value) {
+            Ok(_) => Ok(()),
+            Err(BindingError::Uninitialized) => {
+                let message = format!("Cannot access '{}' before initialization", name);
+                Err(self.throw_error(ErrorKind::ReferenceError, &message))
+            }
+            Err(BindingError::Immutable) => {
+                Err(self.throw_error(ErrorKind::TypeError, "Assignment to constant variable."))
+            }
+        }
+    }
+
+    /// Declare `name` in the current scope.
+    ///
+    /// Redeclaring a `var` keep its value, `let` and `const` can only be declared once
+    pub fn declare_variable(&mut self, name: &str, kind: DeclarationKind) -> ast::Result<()> {
+        let mut env = self.environment();
+        if let Some(existing) = env.declaration_kind(name) {
+            if kind == DeclarationKind::Var && existing == DeclarationKind::Var {
+                return Ok(());
+            }
+            let message = format!("Identifier '{}' has already been declared", name);
+            return Err(self.throw_error(ErrorKind::SyntaxError, &message));
+        }
+
+        match kind {
+            DeclarationKind::Const => env.create_immutable_binding(name),
+            _ => env.create_mutable_binding(name, kind),
+        }
+        Ok(())
+    }
+
+    /// Give its first value to a variable declared in the current scope
+    pub fn initialize_variable(&mut self, name: &str, value: JsValue) {
+        self.environment().initialize_binding(name, value);
+    }
+
+    /// Call a function object with `this` and `args`, in a new scope under the one it was
+    /// created in.
+    ///
+    /// `this` is passed as is, like strict mode code gets it
+    pub fn call(
+        &mut self,
+        callee: &JsValue,
+        this: JsValue,
+        args: Vec<JsValue>,
+    ) -> ast::Result<JsValue> {
+        self.scope(|context| context.call_in_scope(callee, this, args))
+    }
+
+    fn call_in_scope(
+        &mut self,
+        callee: &JsValue,
+        this: JsValue,
+        args: Vec<JsValue>,
+    ) -> ast::Result<JsValue> {
+        let (obj, function) = match callee {
+            JsValue::Object(obj) => match &obj.kind {
+                ObjectKind::Function(function) => (obj.clone(), function.clone()),
+                ObjectKind::NativeFunction(function) => {
+                    return self.call_native(*function, this, args, false)
+                }
+                ObjectKind::BoundFunction(bound) => {
+                    let target = JsValue::object(bound.target.clone());
+                    let args = bound.args.iter().cloned().chain(args).collect();
+                    return self.call(&target, bound.this.clone(), args);
+                }
+                _ => return Err(self.throw_not_a_function(callee)),
+            },
+            _ => return Err(self.throw_not_a_function(callee)),
+        };
+
+        if function.is_class_constructor() {
+            let message = format!(
+                "Class constructor {} cannot be invoked without 'new'",
+                function.name()
+            );
+            return Err(self.throw_error(ErrorKind::TypeError, &message));
+        }
+        let (result, _) = self.invoke(obj, &function, Some(this), args, JsValue::Undefined)?;
+        Ok(result)
+    }
+
+    /// `new callee(...args)`, through `[[Construct]]`.
+    ///
+    /// `new_target` is the constructor `new` was applied to, the one the new object inherits
+    /// the `prototype` of. It is `callee` unless a derived class passes it to its parent
+    pub fn construct(
+        &mut self,
+        callee: &JsValue,
+        args: Vec<JsValue>,
+        new_target: &JsValue,
+    ) -> ast::Result<JsValue> {
+        self.scope(|context| context.construct_in_scope(callee, args, new_target))
+    }
+
+    fn construct_in_scope(
+        &mut self,
+        callee: &JsValue,
+        args: Vec<JsValue>,
+        new_target: &JsValue,
+    ) -> ast::Result<JsValue> {
+        let obj = match callee {
+            JsValue::Object(obj) if obj.is_constructor() => obj.clone(),
+            _ => {
+                let message = format!("{} is not a constructor", callee.to_string());
+                return Err(self.throw_error(ErrorKind::TypeError, &message));
+            }
+        };
+
+        let function = match &obj.kind {
+            ObjectKind::Function(function) => function.clone(),
+            ObjectKind::NativeFunction(function) => {
+                let result = self.call_native(*function, JsValue::Undefined, args, true)?;
+                // The object of a built-in inherits from the class extending it
+                if let JsValue::Object(mut result) = result.clone() {
+                    if !new_target.same_value(callee) {
+                        if let JsValue::Object(prototype) = self.prototype_of(new_target)? {
+                            result.set_prototype_of(Some(prototype));
+                        }
+                    }
+                }
+                return Ok(result);
+            }
+            ObjectKind::BoundFunction(bound) => {
+                let target = JsValue::object(bound.target.clone());
+                let args = bound.args.iter().cloned().chain(args).collect();
+                let new_target = match new_target.same_value(callee) {
+                    true => target.clone(),
+                    false => new_target.clone(),
+                };
+                return self.construct(&target, args, &new_target);
+            }
+            _ => unreachable!("constructor without a body"),
+        };
+
+        // Derived classes get `this` from the parent constructor, through `super()`
+        let is_derived = function.kind() == FunctionKind::DerivedConstructor;
+        let this = if is_derived {
+            None
+        } else {
+            let prototype = match self.prototype_of(new_target)? {
+                JsValue::Object(prototype) => prototype,
+                _ => self.object_prototype(),
+            };
+            let this = JsObject::with_prototype(self, Some(prototype), ObjectKind::Ordinary);
+            let this = JsValue::object(this);
+            ast::initialize_instance_elements(self, &this, &obj)?;
+            Some(this)
+        };
+
+        let (result, environment) = self.invoke(obj, &function, this, args, new_target.clone())?;
+        if let JsValue::Object(_) = result {
+            return Ok(result);
+        }
+        if is_derived && !result.is_undefined() {
+            let message = "Derived constructors may only return object or undefined";
+            return Err(self.throw_error(ErrorKind::TypeError, message));
+        }
+        match environment.this_value() {
+            Some(this) => Ok(this),
+            None => Err(self.uninitialized_this()),
+        }
+    }
+
+    /// `this` of a derived constructor read before `super()`
+    fn uninitialized_this(&mut self) -> ast::Completion {
+        let message = "Must call super constructor in derived class before accessing 'this' or \
+                       returning from derived constructor";
+        self.throw_error(ErrorKind::ReferenceError, message)
+    }
+
+    /// `prototype` property of a constructor
+    fn prototype_of(&mut self, constructor: &JsValue) -> ast::Result<JsValue> {
+        constructor.get_property(self, &PropertyKey::from("prototype"))
+    }
+
+    /// Whether the native function being run was called by `new`
+    pub fn is_constructing(&self) -> bool {
+        self.constructing
+    }
+
+    fn call_native(
+        &mut self,
+        function: NativeFunction,
+        this: JsValue,
+        args: Vec<JsValue>,
+        constructing: bool,
+    ) -> ast::Result<JsValue> {
+        let constructing = std::mem::replace(&mut self.constructing, constructing);
+        let result = function.call(self, this, args);
+        self.constructing = constructing;
+        result
+    }
+
+    /// Run the body of `function` in a new scope under the one it was created in, returning
+    /// that scope with the result so `[[Construct]]` can read its `this`.
+    ///
+    /// `this` is `None` for a derived constructor, `super()` binds it later
+    fn invoke(
+        &mut self,
+        obj: GcPointer<JsObject>,
+        function: &JsFunction,
+        this: Option<JsValue>,
+        args: Vec<JsValue>,
+        new_target: JsValue,
+    ) -> ast::Result<(JsValue, GcPointer<LexicalEnvironment>)> {
+        if self.is_stack_exhausted() {
+            let message = "Maximum call stack size exceeded";
+            return Err(self.throw_error(ErrorKind::RangeError, message));
+        }
+
+        let name = match function.name() {
+            "" => "<anonymous>",
+            name => name,
+        };
+        self.call_stack.push(StackFrame {
+            function: name.into(),
+            position: None,
+        });
+        // Once the current scope is replaced nothing else may root the one of the caller, a
+        // loop releases the roots of its previous iterations
+        let caller_environment = self.set_environment(function.environment());
+        let caller_environment = self.root(caller_environment);
+        self.push_environment(EnvironmentRecordKind::Function);
+        let mut environment = self.environment();
+        if !function.is_arrow() {
+            environment.bind_function(obj, new_target);
+            if let Some(this) = this {
+                environment.bind_this(this);
+            }
         }
+
+        let result = match function.code() {
+            FunctionCode::Ast(body) if body.is_suspendable() => match body.code(self) {
+                Ok(code) => Interpreter::call(self, &code, args),
+                Err(error) => Err(error),
+            },
+            FunctionCode::Ast(body) => body.call(self, args),
+            FunctionCode::Bytecode(code) => Interpreter::call(self, code, args),
+        };
+
+        self.set_environment((*caller_environment).clone());
+        self.call_stack.pop();
+        result.map(|value| (value, environment))
+    }
+
+    /// Whether another call would nest deeper than `MAX_CALL_DEPTH` or leave less than
+    /// `STACK_RESERVE` of native stack
+    fn is_stack_exhausted(&self) -> bool {
+        self.call_stack.len() > MAX_CALL_DEPTH
+            || remaining_stack().is_some_and(|remaining| remaining < STACK_RESERVE)
+    }
+
+    /// Continue the suspended call of `block` in its scope, until it suspends again or returns
+    pub fn resume(
+        &mut self,
+        block: &CodeBlock,
+        mut frame: Frame,
+        resumed: Option<Resume>,
+    ) -> ast::Result<Outcome> {
+        self.scope(|context| {
+            // The frame was only reachable from the generator it was taken from, its scope
+            // included which stops being the current one once another function is called
+            let mark = context.allocation_mark();
+            context.release(mark, &mut frame);
+
+            if context.is_stack_exhausted() {
+                let message = "Maximum call stack size exceeded";
+                return Err(context.throw_error(ErrorKind::RangeError, message));
+            }
+
+            let name = match block.name.as_str() {
+                "" => "<anonymous>",
+                name => name,
+            };
+            context.call_stack.push(StackFrame {
+                function: name.into(),
+                position: None,
+            });
+            let caller_environment = context.set_environment(frame.environment());
+            let caller_environment = context.root(caller_environment);
+            let result = Interpreter::resume(context, block, frame, resumed);
+            context.set_environment((*caller_environment).clone());
+            context.call_stack.pop();
+            result
+        })
+    }
+
+    fn throw_not_a_function(&mut self, callee: &JsValue) -> Completion {
+        let message = format!("{} is not a function", callee.to_string());
+        self.throw_error(ErrorKind::TypeError, &message)
     }
 
-    pub fn get_variable(&self, name: &str) -> JsValue {
-        match self.variables.get(name) {
-            Some(v) => v.clone_inner(),
-            None => JsValue::undefined(),
+    /// Record where the current function is, before a call or a `throw`
+    pub fn set_position(&mut self, position: Position) {
+        if let Some(frame) = self.call_stack.last_mut() {
+            frame.position = Some(position);
         }
     }
 
-    pub fn set_variable(&mut self, name: String, value: GcPointer<JsValue>) {
-        self.variables.insert(name, value);
+    /// One `at` line per active function, the innermost first
+    pub fn stack_trace(&self) -> String {
+        self.call_stack
+            .iter()
+            .rev()
+            .map(|frame| match frame.position {
+                Some(position) => format!("\n    at {} ({})", frame.function, position),
+                None => format!("\n    at {}", frame.function),
+            })
+            .collect()
     }
 
+    /// Create an error object and the completion throwing it
+    pub fn throw_error(&mut self, kind: ErrorKind, message: &str) -> Completion {
+        Completion::Throw(JsValue::object(JsObject::error(self, kind, message)))
+    }
+
+    /// Allocate a cell, which may collect the garbage first.
+    ///
+    /// Values allocated outside of `scope` have to be rooted to survive the next allocations
     pub fn allocate<T>(&mut self, data: T) -> GcPointer<T>
     where
         T: GcCell,
     {
-        self.runtime.lock().unwrap().heap().allocate(data)
+        self.runtime.lock().unwrap().allocate(data)
+    }
+
+    /// Keep `value` alive until the handle is dropped, even if nothing else references it
+    pub fn root<T: Trace>(&self, value: T) -> Rooted<T> {
+        Rooted::new(self.roots.clone(), value)
     }
+
+    /// Run `f` with everything it allocates rooted, once it returns only its result stays
+    /// rooted, by the enclosing scope
+    pub fn scope<T: Trace>(&mut self, f: impl FnOnce(&mut Context) -> T) -> T {
+        self.roots.borrow_mut().enter_scope();
+        let mut result = f(self);
+        self.roots.borrow_mut().leave_scope(&mut result);
+        result
+    }
+
+    /// Where the current scope is at, see `release`
+    pub fn allocation_mark(&self) -> usize {
+        self.roots.borrow().mark()
+    }
+
+    /// Unroot what was allocated in the current scope since `mark` was taken, except the cells
+    /// `live` points to
+    pub fn release(&mut self, mark: usize, live: &mut dyn Trace) {
+        self.roots.borrow_mut().release(mark, live);
+    }
+
+    pub fn collect_garbage(&mut self) {
+        self.runtime.lock().unwrap().collect_garbage();
+    }
+}
+
+/// Bytes of native stack left to the current thread, `None` where it can't be told
+#[cfg(target_os = "linux")]
+fn remaining_stack() -> Option<usize> {
+    thread_local! {
+        /// Lowest address of the stack of this thread
+        static STACK_START: Option<usize> = unsafe {
+            let mut attributes = std::mem::MaybeUninit::uninit();
+            if libc::pthread_getattr_np(libc::pthread_self(), attributes.as_mut_ptr()) != 0 {
+                None
+            } else {
+                let mut start = std::ptr::null_mut();
+                let mut size = 0;
+                let result =
+                    libc::pthread_attr_getstack(attributes.as_ptr(), &mut start, &mut size);
+                libc::pthread_attr_destroy(attributes.as_mut_ptr());
+                (result == 0).then_some(start as usize)
+            }
+        };
+    }
+
+    let here = 0u8;
+    let here = std::hint::black_box(&here) as *const u8 as usize;
+    STACK_START.with(|start| start.map(|start| here.saturating_sub(start)))
 }
 
-// #[derive(Debug)]
-// pub struct Variable {
-//     pub(crate) value: jsrt::Value,
-//     pub(crate) declaration_kind: DeclarationKind,
-// }
-
-// impl Variable {
-//     pub fn new(value: jsrt::Value, declaration_kind: DeclarationKind) -> Variable {
-//         Variable {
-//             value,
-//             declaration_kind,
-//         }
-//     }
-
-//     pub fn from_const(value: jsrt::Value) -> Variable {
-//         Variable {
-//             value,
-//             declaration_kind: DeclarationKind::Const,
-//         }
-//     }
-
-//     pub fn from_let(value: jsrt::Value) -> Variable {
-//         Variable {
-//             value,
-//             declaration_kind: DeclarationKind::Let,
-//         }
-//     }
-// }
-
-// #[derive(Debug)]
-// pub enum EnvironmentRecordKind {
-//     Declarative,
-//     Function,
-//     Object,
-//     Global,
-//     Module,
-// }
-
-// #[derive(Debug)]
-// enum ThisBindingStatus {
-//     Lexical,
-//     Initialized,
-//     Uninitialized,
-// }
-
-// #[derive(Debug)]
-// pub struct LexicalEnvironment {
-//     kind: EnvironmentRecordKind,
-//     variables: HashMap<String, Variable>,
-//     this_status: ThisBindingStatus,
-//     this_value: Option<jsrt::Value>,
-//     super_value: Option<jsrt::Value>,
-//     with_base_value: jsrt::Value,
-//     parent: Option<Arc<LexicalEnvironment>>,
-// }
-
-// impl LexicalEnvironment {
-//     pub fn has_binding(&self, name: &str) -> bool {
-//         self.variables.contains_key(name)
-//     }
-
-//     pub fn create_mutable_binding(&mut self, name: &str, _delete: bool) {
-//         assert!(!self.variables.contains_key(name));
-
-//         self.variables
-//             .insert(name.into(), Variable::from_let(jsrt::Value::Undefined));
-
-//         // if delete is true, record that the newly created binding may be deleted by a subsequent DeleteBinding call.
-//     }
-
-//     pub fn create_immutable_binding(&mut self, name: &str, _strict: bool) {
-//         assert!(!self.variables.contains_key(name));
-
-//         self.variables
-//             .insert(name.into(), Variable::from_const(jsrt::Value::Undefined));
-//     }
-
-//     pub fn initialize_binding(&mut self, name: &str, value: jsrt::Value) {
-//         assert!(self
-//             .variables
-//             .get(name)
-//             .map(|v| matches!(&v.value, jsrt::Value::Undefined))
-//             .is_some());
-
-//         self.variables.get_mut(name).unwrap().value = value;
-//     }
-
-//     pub fn set_mutable_binding(&mut self, name: &str, value: jsrt::Value, strict: bool) {
-//         todo!()
-//     }
-
-//     pub fn get_binding_value(&self, name: &str, strict: bool) -> Result<jsrt::Value, ()> {
-//         todo!()
-//     }
-
-//     pub fn delete_binding(&mut self, name: &str) -> bool {
-//         todo!()
-//     }
-
-//     pub fn has_this_binding(&self) -> bool {
-//         self.this_value.is_some()
-//     }
-
-//     pub fn has_super_binding(&self) -> bool {
-//         self.super_value.is_some()
-//     }
-
-//     pub fn with_base_object(&self) -> jsrt::Value {
-//         // self.with_base_value
-//         todo!()
-//     }
-// }
+#[cfg(not(target_os = "linux"))]
+fn remaining_stack() -> Option<usize> {
+    None
+}
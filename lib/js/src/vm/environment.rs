@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+
+use super::ResolvedBinding;
+use crate::{
+    ast::DeclarationKind,
+    gc::*,
+    jsrt::{JsObject, JsValue},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvironmentRecordKind {
+    Declarative,
+    Function,
+    Global,
+    /// Top level scope of a module, under the global one
+    Module,
+}
+
+#[derive(Debug)]
+pub enum BindingError {
+    /// Read or written before its declaration ran
+    Uninitialized,
+    /// Assignment to a `const`
+    Immutable,
+}
+
+#[derive(Debug, Clone, GcTrace)]
+pub struct Variable {
+    /// `None` while in the temporal dead zone
+    pub(crate) value: Option<JsValue>,
+    #[unsafe_ignore_trace]
+    pub(crate) declaration_kind: DeclarationKind,
+    /// Binding of another module an `import` reads through, `value` stays `None`
+    pub(crate) import: Option<ResolvedBinding>,
+}
+
+impl Variable {
+    pub fn new(value: Option<JsValue>, declaration_kind: DeclarationKind) -> Variable {
+        Variable {
+            value,
+            declaration_kind,
+            import: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, GcTrace)]
+enum ThisBinding {
+    /// Blocks and arrow functions see the `this` of their parent
+    Lexical,
+    /// A derived constructor before `super()` returns
+    Uninitialized,
+    Initialized(JsValue),
+}
+
+/// One scope of the chain, a block, a function call or the global scope
+#[derive(Debug, GcTrace)]
+pub struct LexicalEnvironment {
+    #[unsafe_ignore_trace]
+    kind: EnvironmentRecordKind,
+    variables: HashMap<String, Variable>,
+    /// `this` of function scopes and of the global scope
+    this_binding: ThisBinding,
+    /// Function running in a function scope with the `new.target` it got, for `super`
+    function: Option<GcPointer<JsObject>>,
+    new_target: JsValue,
+    parent: Option<GcPointer<LexicalEnvironment>>,
+}
+
+impl GcCell for LexicalEnvironment {}
+
+impl LexicalEnvironment {
+    pub fn new(
+        kind: EnvironmentRecordKind,
+        parent: Option<GcPointer<LexicalEnvironment>>,
+    ) -> LexicalEnvironment {
+        LexicalEnvironment {
+            kind,
+            variables: HashMap::new(),
+            this_binding: ThisBinding::Lexical,
+            function: None,
+            new_target: JsValue::Undefined,
+            parent,
+        }
+    }
+
+    /// Same bindings with the same values, later changes to one don't affect the other
+    pub fn copy(&self) -> LexicalEnvironment {
+        LexicalEnvironment {
+            kind: self.kind,
+            variables: self.variables.clone(),
+            this_binding: self.this_binding.clone(),
+            function: self.function.clone(),
+            new_target: self.new_target.clone(),
+            parent: self.parent.clone(),
+        }
+    }
+
+    pub fn kind(&self) -> EnvironmentRecordKind {
+        self.kind
+    }
+
+    pub fn parent(&self) -> Option<GcPointer<LexicalEnvironment>> {
+        self.parent.clone()
+    }
+
+    /// Scope of a call to `function`, its `this` is bound by `bind_this`
+    pub fn bind_function(&mut self, function: GcPointer<JsObject>, new_target: JsValue) {
+        self.function = Some(function);
+        self.new_target = new_target;
+        self.this_binding = ThisBinding::Uninitialized;
+    }
+
+    pub fn bind_this(&mut self, value: JsValue) {
+        self.this_binding = ThisBinding::Initialized(value);
+    }
+
+    pub fn has_this_binding(&self) -> bool {
+        !matches!(self.this_binding, ThisBinding::Lexical)
+    }
+
+    /// `None` until bound, or if the scope has no `this`
+    pub fn this_value(&self) -> Option<JsValue> {
+        match &self.this_binding {
+            ThisBinding::Initialized(value) => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn function(&self) -> Option<GcPointer<JsObject>> {
+        self.function.clone()
+    }
+
+    pub fn new_target(&self) -> JsValue {
+        self.new_target.clone()
+    }
+
+    pub fn has_binding(&self, name: &str) -> bool {
+        self.variables.contains_key(name)
+    }
+
+    pub fn declaration_kind(&self, name: &str) -> Option<DeclarationKind> {
+        self.variables.get(name).map(|v| v.declaration_kind)
+    }
+
+    /// `var` bindings start as `undefined`, `let` ones are uninitialized
+    pub fn create_mutable_binding(&mut self, name: &str, declaration_kind: DeclarationKind) {
+        debug_assert!(declaration_kind != DeclarationKind::Const);
+
+        let value = if declaration_kind == DeclarationKind::Var {
+            Some(JsValue::Undefined)
+        } else {
+            None
+        };
+
+        self.variables
+            .insert(name.into(), Variable::new(value, declaration_kind));
+    }
+
+    pub fn create_immutable_binding(&mut self, name: &str) {
+        self.variables
+            .insert(name.into(), Variable::new(None, DeclarationKind::Const));
+    }
+
+    /// `CreateImportBinding`, an immutable binding whose value is the one of `binding`
+    pub fn create_import_binding(&mut self, name: &str, binding: ResolvedBinding) {
+        let mut variable = Variable::new(None, DeclarationKind::Const);
+        variable.import = Some(binding);
+        self.variables.insert(name.into(), variable);
+    }
+
+    pub fn initialize_binding(&mut self, name: &str, value: JsValue) {
+        let variable = self
+            .variables
+            .get_mut(name)
+            .expect("initialize_binding() called on undeclared variable");
+
+        variable.value = Some(value);
+    }
+
+    pub fn set_mutable_binding(&mut self, name: &str, value: JsValue) -> Result<(), BindingError> {
+        let variable = self
+            .variables
+            .get_mut(name)
+            .expect("set_mutable_binding() called on undeclared variable");
+
+        if variable.import.is_some() {
+            Err(BindingError::Immutable)
+        } else if variable.value.is_none() {
+            Err(BindingError::Uninitialized)
+        } else if variable.declaration_kind == DeclarationKind::Const {
+            Err(BindingError::Immutable)
+        } else {
+            variable.value = Some(value);
+            Ok(())
+        }
+    }
+
+    pub fn get_binding_value(&self, name: &str) -> Result<JsValue, BindingError> {
+        let variable = self
+            .variables
+            .get(name)
+            .expect("get_binding_value() called on undeclared variable");
+
+        match &variable.import {
+            Some(binding) => binding.value(),
+            None => variable.value.clone().ok_or(BindingError::Uninitialized),
+        }
+    }
+
+    pub fn delete_binding(&mut self, name: &str) -> bool {
+        self.variables.remove(name).is_some()
+    }
+}
@@ -1,3 +1,702 @@
-pub struct Interpreter {}
+use std::rc::Rc;
 
-impl Interpreter {}
+use Instruction::*;
+
+use super::{CodeBlock, Context, EnvironmentRecordKind, Instruction, LexicalEnvironment};
+use crate::{
+    ast::{
+        self, ArrayExpression, BinaryOperation, BindingPattern, Completion, ForInIterator,
+        MemberExpression, ObjectExpression, SpreadElement, UnaryExpression, UpdateExpression,
+    },
+    gc::{GcPointer, GcTrace, Trace, Tracer},
+    jsrt::{
+        list_from_array_like, ErrorKind, FunctionCode, IteratorRecord, JsGenerator, JsObject,
+        JsRegExp, JsValue, PropertyKey,
+    },
+};
+
+/// Where to resume when an exception is thrown inside a `try`
+#[derive(GcTrace)]
+struct Handler {
+    target: usize,
+    stack_len: usize,
+    iterators_len: usize,
+    environment: GcPointer<LexicalEnvironment>,
+}
+
+/// Why a generator or an async function stopped before the end of its body
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Suspension {
+    /// Generators stop once their parameters are bound
+    Start,
+    Yield,
+    /// `yield*`, the value is the result object of the inner iterator
+    YieldDelegate,
+    Await,
+}
+
+unsafe impl Trace for Suspension {}
+
+/// What a suspended function is resumed with, by `next()`, `throw()` and `return()` or by
+/// the promise it awaits
+#[derive(Debug, GcTrace)]
+pub enum Resume {
+    Next(JsValue),
+    Throw(JsValue),
+    Return(JsValue),
+}
+
+/// How a resumed function stopped running, exceptions are errors
+#[derive(GcTrace)]
+pub enum Outcome {
+    Suspended(Suspension, JsValue, Frame),
+    Returned(JsValue),
+}
+
+/// State of a suspended call, which `Interpreter::resume` continues from
+#[derive(GcTrace)]
+pub struct Frame {
+    ip: usize,
+    stack: Vec<JsValue>,
+    handlers: Vec<Handler>,
+    iterators: Vec<ForInIterator>,
+    return_value: JsValue,
+    arguments: Vec<JsValue>,
+    /// Scope of the instruction it stopped at
+    environment: GcPointer<LexicalEnvironment>,
+}
+
+impl Frame {
+    pub fn environment(&self) -> GcPointer<LexicalEnvironment> {
+        self.environment.clone()
+    }
+}
+
+/// Run the bytecode of one call, calls from it get their own `Interpreter`
+pub struct Interpreter<'a> {
+    block: &'a CodeBlock,
+    ip: usize,
+    stack: Vec<JsValue>,
+    handlers: Vec<Handler>,
+    iterators: Vec<ForInIterator>,
+    completion: JsValue,
+    return_value: JsValue,
+    /// Arguments of the call, bound to the parameters by the start of the function
+    arguments: Vec<JsValue>,
+    /// Set with the value to return when the function suspends instead
+    suspension: Option<Suspension>,
+    /// What the suspended function was resumed with, until `Resume` or `YieldDelegate` take it
+    resumed: Option<Resume>,
+}
+
+/// What the interpreter holds between two instructions, loops release everything else
+unsafe impl Trace for Interpreter<'_> {
+    fn trace(&mut self, tracer: &mut Tracer) {
+        self.arguments.trace(tracer);
+        self.stack.trace(tracer);
+        self.handlers.trace(tracer);
+        self.iterators.trace(tracer);
+        self.completion.trace(tracer);
+        self.return_value.trace(tracer);
+        self.resumed.trace(tracer);
+    }
+}
+
+impl<'a> Interpreter<'a> {
+    fn new(block: &'a CodeBlock) -> Interpreter<'a> {
+        Interpreter {
+            block,
+            ip: 0,
+            stack: Vec::new(),
+            handlers: Vec::new(),
+            iterators: Vec::new(),
+            completion: JsValue::Undefined,
+            return_value: JsValue::Undefined,
+            arguments: Vec::new(),
+            suspension: None,
+            resumed: None,
+        }
+    }
+
+    /// Continue a suspended call from where it stopped, in the scope it stopped in
+    fn from_frame(block: &'a CodeBlock, frame: Frame) -> Interpreter<'a> {
+        Interpreter {
+            ip: frame.ip,
+            stack: frame.stack,
+            handlers: frame.handlers,
+            iterators: frame.iterators,
+            return_value: frame.return_value,
+            arguments: frame.arguments,
+            ..Interpreter::new(block)
+        }
+    }
+
+    fn into_frame(self, environment: GcPointer<LexicalEnvironment>) -> Frame {
+        Frame {
+            ip: self.ip,
+            stack: self.stack,
+            handlers: self.handlers,
+            iterators: self.iterators,
+            return_value: self.return_value,
+            arguments: self.arguments,
+            environment,
+        }
+    }
+
+    /// Run a compiled script in the current scope of `context`, the result is the value of its
+    /// last statement
+    pub fn run(context: &mut Context, block: &CodeBlock) -> ast::Result<JsValue> {
+        // The scope may only be referenced from here while the script runs
+        let environment = context.root(context.environment());
+        let result = context.scope(|context| {
+            let result = Interpreter::new(block).execute(context);
+            context.end_job();
+            result
+        });
+        context.set_environment((*environment).clone());
+        result
+    }
+
+    /// Run the body of a function with `args`, `context` already hold its environment.
+    ///
+    /// Generators return a generator object once their parameters are bound, async functions
+    /// the promise of their result
+    pub fn call(
+        context: &mut Context,
+        block: &Rc<CodeBlock>,
+        args: Vec<JsValue>,
+    ) -> ast::Result<JsValue> {
+        let mut interpreter = Interpreter::new(block);
+        interpreter.arguments = args;
+        if block.is_async {
+            let frame = interpreter.into_frame(context.environment());
+            return Ok(JsGenerator::start_async(context, block.clone(), frame));
+        }
+
+        let result = interpreter.execute(context)?;
+        match interpreter.suspension {
+            Some(Suspension::Start) => {
+                let frame = interpreter.into_frame(context.environment());
+                JsGenerator::create(context, block.clone(), frame)
+            }
+            _ => Ok(result),
+        }
+    }
+
+    /// Run a suspended call until it suspends again or returns, `context` already hold the
+    /// scope of the frame. `resumed` is `None` at the start of the body
+    pub fn resume(
+        context: &mut Context,
+        block: &CodeBlock,
+        frame: Frame,
+        resumed: Option<Resume>,
+    ) -> ast::Result<Outcome> {
+        let mut interpreter = Interpreter::from_frame(block, frame);
+        interpreter.resumed = resumed;
+        let value = interpreter.execute(context)?;
+        match interpreter.suspension.take() {
+            Some(suspension) => {
+                let frame = interpreter.into_frame(context.environment());
+                Ok(Outcome::Suspended(suspension, value, frame))
+            }
+            None => Ok(Outcome::Returned(value)),
+        }
+    }
+
+    fn execute(&mut self, context: &mut Context) -> ast::Result<JsValue> {
+        let mark = context.allocation_mark();
+        loop {
+            let ip = self.ip;
+            let instruction = self.block.code[ip];
+            self.ip += 1;
+
+            match self.step(context, instruction) {
+                // Jumping back starts another iteration of a loop
+                Ok(None) if self.ip <= ip => context.release(mark, self),
+                Ok(None) => {}
+                Ok(Some(value)) => return Ok(value),
+                Err(Completion::Throw(exception)) => {
+                    let handler = self.handlers.pop();
+                    // The loops left by the exception close their iterators
+                    let iterators_len = handler.as_ref().map_or(0, |h| h.iterators_len);
+                    for mut iterator in self.iterators.split_off(iterators_len).into_iter().rev() {
+                        let thrown = Completion::Throw(exception.clone());
+                        let _ = iterator.close(context, Err::<(), _>(thrown));
+                    }
+
+                    match handler {
+                        Some(handler) => {
+                            self.stack.truncate(handler.stack_len);
+                            context.set_environment(handler.environment);
+                            self.stack.push(exception);
+                            self.ip = handler.target;
+                        }
+                        None => return Err(Completion::Throw(exception)),
+                    }
+                }
+                // `break`, `continue` and `return` are jumps, nothing else can complete abruptly
+                Err(completion) => return Err(completion),
+            }
+        }
+    }
+
+    fn pop(&mut self) -> JsValue {
+        self.stack.pop().expect("VM stack underflow")
+    }
+
+    /// Pop the array the arguments of a call with spread ones were collected in
+    fn pop_spread_arguments(&mut self, context: &mut Context) -> ast::Result<Vec<JsValue>> {
+        match self.pop() {
+            JsValue::Object(args) => list_from_array_like(context, &args),
+            _ => unreachable!("spread call without an array of arguments"),
+        }
+    }
+
+    fn name(&self, index: u32) -> &'a str {
+        &self.block.names[index as usize]
+    }
+
+    /// Run one instruction, returning the value of the block once it returns
+    fn step(
+        &mut self,
+        context: &mut Context,
+        instruction: Instruction,
+    ) -> ast::Result<Option<JsValue>> {
+        match instruction {
+            LoadConstant(index) => {
+                let value = self.block.constants[index as usize].clone();
+                self.stack.push(value);
+            }
+            LoadUndefined => self.stack.push(JsValue::Undefined),
+            LoadNull => self.stack.push(JsValue::Null),
+            LoadTrue => self.stack.push(JsValue::Boolean(true)),
+            LoadFalse => self.stack.push(JsValue::Boolean(false)),
+            Pop => {
+                self.pop();
+            }
+            Dup => {
+                let value = self.stack.last().expect("VM stack underflow").clone();
+                self.stack.push(value);
+            }
+            Dup2 => {
+                let top = self.stack[self.stack.len() - 2..].to_vec();
+                self.stack.extend(top);
+            }
+            MoveDown(n) => {
+                let value = self.pop();
+                self.stack.insert(self.stack.len() - n as usize, value);
+            }
+            LoadThis => self.stack.push(context.this()?),
+            LoadNewTarget => self.stack.push(context.new_target()),
+            LoadArgument(index) => {
+                let value = self.arguments.get(index as usize).cloned();
+                self.stack.push(value.unwrap_or(JsValue::Undefined));
+            }
+            LoadRestArguments(index) => {
+                let rest = self.arguments.get(index as usize..).unwrap_or_default();
+                let array = JsObject::array(context, rest.to_vec());
+                self.stack.push(JsValue::object(array));
+            }
+
+            GetVariable(name) => {
+                let value = context.get_variable(self.name(name))?;
+                self.stack.push(value);
+            }
+            SetVariable(name) => {
+                let value = self.pop();
+                context.set_variable(self.name(name), value)?;
+            }
+            DeclareVariable(name, kind) => context.declare_variable(self.name(name), kind)?,
+            InitializeVariable(name) => {
+                let value = self.pop();
+                context.initialize_variable(self.name(name), value);
+            }
+            TypeofVariable(name) => {
+                let name = self.name(name);
+                let value = if context.has_variable(name) {
+                    let value = context.get_variable(name)?;
+                    JsValue::string(value.type_of())
+                } else {
+                    JsValue::string("undefined")
+                };
+                self.stack.push(value);
+            }
+            PushEnvironment => {
+                context.push_environment(EnvironmentRecordKind::Declarative);
+            }
+            PopEnvironment => context.pop_environment(),
+            CopyEnvironment => context.copy_environment(),
+
+            Numeric(op) => {
+                let (left, right) = self.pop_operands();
+                let value = BinaryOperation::do_numeric_op(&op, left, right, context)?;
+                self.stack.push(value);
+            }
+            Bitwise(op) => {
+                let (left, right) = self.pop_operands();
+                let value = BinaryOperation::do_bitwise_op(&op, left, right, context)?;
+                self.stack.push(value);
+            }
+            Compare(op) => {
+                let (left, right) = self.pop_operands();
+                let value = BinaryOperation::do_compare_op(&op, left, right, context)?;
+                self.stack.push(value);
+            }
+            Assign(op) => {
+                let (left, right) = self.pop_operands();
+                let value = BinaryOperation::do_assignment_value(&op, left, right, context)?;
+                self.stack.push(value);
+            }
+            Unary(op) => {
+                let value = self.pop();
+                let value = UnaryExpression::apply(op, value, context)?;
+                self.stack.push(value);
+            }
+            ToNumeric => {
+                let value = self.pop().to_numeric_with(context)?;
+                self.stack.push(value);
+            }
+            Increment | Decrement => {
+                let value = self.pop();
+                let value = UpdateExpression::update(instruction == Increment, value, context)?;
+                self.stack.push(value);
+            }
+
+            Jump(target) => self.ip = target as usize,
+            JumpIfFalse(target) => {
+                if !self.pop().to_boolean() {
+                    self.ip = target as usize;
+                }
+            }
+            JumpIfTrue(target) => {
+                if self.pop().to_boolean() {
+                    self.ip = target as usize;
+                }
+            }
+            JumpIfNotUndefined(target) => {
+                if self.stack.last().is_some_and(|value| !value.is_undefined()) {
+                    self.ip = target as usize;
+                } else {
+                    self.pop();
+                }
+            }
+            JumpIfFalseKeep(target) | JumpIfTrueKeep(target) => {
+                let jump_if = matches!(instruction, JumpIfTrueKeep(_));
+                let value = self.stack.last().expect("VM stack underflow");
+                if value.to_boolean() == jump_if {
+                    self.ip = target as usize;
+                } else {
+                    self.pop();
+                }
+            }
+
+            CreateFunction(index) => {
+                let code = FunctionCode::Bytecode(self.block.functions[index as usize].clone());
+                let function = JsObject::function(context, code);
+                self.stack.push(JsValue::object(function));
+            }
+            CreateObject => {
+                let obj = JsObject::new(context);
+                self.stack.push(JsValue::object(obj));
+            }
+            DefineProperty(kind) => {
+                let (key, value) = self.pop_operands();
+                if let Some(JsValue::Object(obj)) = self.stack.last_mut() {
+                    ObjectExpression::define_property(obj, kind, &key, value);
+                }
+            }
+            SetPrototype => {
+                let value = self.pop();
+                if let Some(JsValue::Object(obj)) = self.stack.last_mut() {
+                    ObjectExpression::set_prototype(obj, value);
+                }
+            }
+            CopyDataProperties => {
+                let source = self.pop();
+                if let Some(JsValue::Object(obj)) = self.stack.last() {
+                    let mut obj = obj.clone();
+                    ObjectExpression::copy_data_properties(context, &mut obj, &source, &[])?;
+                }
+            }
+            CreateRestObject(n) => {
+                let source = self.pop();
+                let excluded: Vec<_> = self
+                    .stack
+                    .split_off(self.stack.len() - n as usize)
+                    .iter()
+                    .map(PropertyKey::from_value)
+                    .collect();
+                let mut rest = JsObject::new(context);
+                ObjectExpression::copy_data_properties(context, &mut rest, &source, &excluded)?;
+                self.stack.push(JsValue::object(rest));
+            }
+            RequireObjectCoercible => {
+                let value = self.stack.last().expect("VM stack underflow");
+                BindingPattern::require_object_coercible(context, value)?;
+            }
+            CreateArray => {
+                let array = JsObject::array(context, Vec::new());
+                self.stack.push(JsValue::object(array));
+            }
+            AppendElement | AppendHole => {
+                let value = match instruction {
+                    AppendElement => Some(self.pop()),
+                    _ => None,
+                };
+                if let Some(JsValue::Object(array)) = self.stack.last_mut() {
+                    ArrayExpression::append(array, value);
+                }
+            }
+            AppendSpread => {
+                let iterable = self.pop();
+                let values = SpreadElement::values(context, iterable)?;
+                if let Some(JsValue::Object(array)) = self.stack.last_mut() {
+                    for value in values {
+                        ArrayExpression::append(array, Some(value));
+                    }
+                }
+            }
+            CreateTemplateObject(index) => {
+                let strings = &self.block.templates[index as usize];
+                let template = strings.template_object(context);
+                self.stack.push(template);
+            }
+            CreateRegExp(index) => {
+                let regex = self.block.regexps[index as usize].clone();
+                let regexp = JsRegExp::create(context, regex);
+                self.stack.push(JsValue::object(regexp));
+            }
+            ToString => {
+                let value = self.pop().to_string_with(context)?;
+                self.stack.push(JsValue::string(value.as_ref()));
+            }
+            GetProperty => {
+                let (obj, key) = self.pop_operands();
+                let value = obj.get_property(context, &PropertyKey::from_value(&key))?;
+                self.stack.push(value);
+            }
+            SetProperty => {
+                let value = self.pop();
+                let (obj, key) = self.pop_operands();
+                obj.set_property(context, PropertyKey::from_value(&key), value.clone())?;
+                self.stack.push(value);
+            }
+            DeleteProperty => {
+                let (obj, key) = self.pop_operands();
+                let result =
+                    MemberExpression::delete(context, obj, &PropertyKey::from_value(&key))?;
+                self.stack.push(result);
+            }
+            Call(argc) => {
+                let args = self.stack.split_off(self.stack.len() - argc as usize);
+                let callee = self.pop();
+                let this = self.pop();
+                let value = context.call(&callee, this, args)?;
+                self.stack.push(value);
+            }
+            CallSpread => {
+                let args = self.pop_spread_arguments(context)?;
+                let callee = self.pop();
+                let this = self.pop();
+                let value = context.call(&callee, this, args)?;
+                self.stack.push(value);
+            }
+            New(argc) => {
+                let args = self.stack.split_off(self.stack.len() - argc as usize);
+                let callee = self.pop();
+                let value = context.construct(&callee, args, &callee)?;
+                self.stack.push(value);
+            }
+            NewSpread => {
+                let args = self.pop_spread_arguments(context)?;
+                let callee = self.pop();
+                let value = context.construct(&callee, args, &callee)?;
+                self.stack.push(value);
+            }
+            SuperCall(argc) => {
+                let args = self.stack.split_off(self.stack.len() - argc as usize);
+                let value = context.super_call(args)?;
+                self.stack.push(value);
+            }
+            SuperCallSpread => {
+                let args = self.pop_spread_arguments(context)?;
+                let value = context.super_call(args)?;
+                self.stack.push(value);
+            }
+            GetSuperProperty => {
+                let key = PropertyKey::from_value(&self.pop());
+                let value = context.super_property(&key)?;
+                self.stack.push(value);
+            }
+            DeclarePrivateNames(index) => {
+                self.block.classes[index as usize].declare_private_names(context)?;
+            }
+            CreateClass(index) => {
+                let class = &self.block.classes[index as usize];
+                let operands = self
+                    .stack
+                    .split_off(self.stack.len() - 2 * class.elements().len());
+                let mut elements = Vec::with_capacity(class.elements().len());
+                let mut operands = operands.into_iter();
+                while let (Some(key), Some(function)) = (operands.next(), operands.next()) {
+                    elements.push((key, function));
+                }
+                let constructor = match self.pop() {
+                    JsValue::Object(constructor) => constructor,
+                    _ => unreachable!("CreateClass without a constructor"),
+                };
+                let heritage = class.heritage().map(|_| self.pop());
+                let class = class.define(context, heritage, constructor, elements)?;
+                self.stack.push(class);
+            }
+            SetPosition(index) => context.set_position(self.block.positions[index as usize]),
+
+            Throw => return Err(Completion::Throw(self.pop())),
+            EnterTry(target) => self.handlers.push(Handler {
+                target: target as usize,
+                stack_len: self.stack.len(),
+                iterators_len: self.iterators.len(),
+                environment: context.environment(),
+            }),
+            LeaveTry => {
+                self.handlers.pop();
+            }
+
+            CreateIterator(kind) => {
+                let iterated = self.pop();
+                let iterator = ForInIterator::new(kind, context, iterated)?;
+                self.iterators.push(iterator);
+            }
+            IteratorNext(target) => {
+                let iterator = self.iterators.last_mut().expect("no iterator to advance");
+                match iterator.next(context)? {
+                    Some(value) => self.stack.push(value),
+                    None => self.ip = target as usize,
+                }
+            }
+            IteratorValue => {
+                let iterator = self.iterators.last_mut().expect("no iterator to advance");
+                let value = iterator.next(context)?;
+                self.stack.push(value.unwrap_or(JsValue::Undefined));
+            }
+            IteratorRest => {
+                let iterator = self.iterators.last_mut().expect("no iterator to advance");
+                let mut values = Vec::new();
+                while let Some(value) = iterator.next(context)? {
+                    values.push(value);
+                }
+                let array = JsObject::array(context, values);
+                self.stack.push(JsValue::object(array));
+            }
+            PopIterator => {
+                let mut iterator = self.iterators.pop().expect("no iterator to pop");
+                iterator.close(context, Ok(()))?;
+            }
+            GetIterator => {
+                let iterable = self.pop();
+                let (iterator, next) = IteratorRecord::from_iterable(context, &iterable)?.parts();
+                self.stack.push(iterator);
+                self.stack.push(next);
+            }
+
+            GeneratorStart => return self.suspend(Suspension::Start, JsValue::Undefined),
+            Yield => {
+                let value = self.pop();
+                return self.suspend(Suspension::Yield, value);
+            }
+            Await => {
+                let value = self.pop();
+                return self.suspend(Suspension::Await, value);
+            }
+            Resume => match self.resumed.take() {
+                Some(Resume::Next(value)) => {
+                    self.stack.push(value);
+                    self.stack.push(JsValue::Boolean(false));
+                }
+                Some(Resume::Return(value)) => {
+                    self.stack.push(value);
+                    self.stack.push(JsValue::Boolean(true));
+                }
+                Some(Resume::Throw(value)) => return Err(Completion::Throw(value)),
+                None => unreachable!("Resume without a suspension"),
+            },
+            YieldDelegate => return self.yield_delegate(context),
+
+            SetCompletion => self.completion = self.pop(),
+            LoadCompletion => self.stack.push(self.completion.clone()),
+            StoreReturn => self.return_value = self.pop(),
+            LoadReturn => self.stack.push(self.return_value.clone()),
+            Return => return Ok(Some(self.pop())),
+        }
+
+        Ok(None)
+    }
+
+    /// Stop running until the function is resumed, returning `value` to what runs it
+    fn suspend(&mut self, suspension: Suspension, value: JsValue) -> ast::Result<Option<JsValue>> {
+        self.suspension = Some(suspension);
+        Ok(Some(value))
+    }
+
+    /// One step of `yield*`, forwarding what the generator was resumed with to the inner
+    /// iterator
+    fn yield_delegate(&mut self, context: &mut Context) -> ast::Result<Option<JsValue>> {
+        let len = self.stack.len();
+        let (iterator, next) = (self.stack[len - 2].clone(), self.stack[len - 1].clone());
+        let resumed = self
+            .resumed
+            .take()
+            .unwrap_or(Resume::Next(JsValue::Undefined));
+
+        let (method, value, is_return) = match resumed {
+            Resume::Next(value) => (next, value, false),
+            Resume::Throw(value) => {
+                let method = iterator.get_property(context, &PropertyKey::from("throw"))?;
+                if method.is_undefined() || method.is_null() {
+                    // The protocol is broken, the iterator is closed before throwing
+                    let mut record = IteratorRecord::new(iterator, JsValue::Undefined);
+                    record.close(context)?;
+                    let message = "The iterator does not provide a 'throw' method";
+                    return Err(context.throw_error(ErrorKind::TypeError, message));
+                }
+                (method, value, false)
+            }
+            Resume::Return(value) => {
+                let method = iterator.get_property(context, &PropertyKey::from("return"))?;
+                if method.is_undefined() || method.is_null() {
+                    self.stack.truncate(len - 2);
+                    self.stack.push(value);
+                    self.stack.push(JsValue::Boolean(true));
+                    return Ok(None);
+                }
+                (method, value, true)
+            }
+        };
+
+        let result = context.call(&method, iterator, vec![value])?;
+        if !matches!(result, JsValue::Object(_)) {
+            let message = format!("Iterator result {} is not an object", result.to_string());
+            return Err(context.throw_error(ErrorKind::TypeError, &message));
+        }
+        if !result
+            .get_property(context, &PropertyKey::from("done"))?
+            .to_boolean()
+        {
+            // Run this instruction again once resumed
+            self.ip -= 1;
+            return self.suspend(Suspension::YieldDelegate, result);
+        }
+
+        let value = result.get_property(context, &PropertyKey::from("value"))?;
+        self.stack.truncate(len - 2);
+        self.stack.push(value);
+        self.stack.push(JsValue::Boolean(is_return));
+        Ok(None)
+    }
+
+    fn pop_operands(&mut self) -> (JsValue, JsValue) {
+        let right = self.pop();
+        let left = self.pop();
+        (left, right)
+    }
+}
@@ -1,7 +1,17 @@
+pub mod bytecode;
+pub mod compiler;
 pub mod context;
+pub mod environment;
 pub mod interpreter;
+pub mod module;
+pub mod realm;
 pub mod runtime;
 
+pub use bytecode::*;
+pub use compiler::*;
 pub use context::*;
+pub use environment::*;
 pub use interpreter::*;
+pub use module::*;
+pub use realm::*;
 pub use runtime::*;
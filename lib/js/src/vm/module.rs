@@ -0,0 +1,758 @@
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use super::{
+    BindingError, CodeBlock, Compiler, Context, EnvironmentRecordKind, Interpreter,
+    LexicalEnvironment,
+};
+use crate::{
+    ast::{self, Completion, ExportEntry, ImportEntry, ImportName, Module},
+    gc::{GcCell, GcPointer, GcTrace, Trace, Tracer},
+    jsrt::{Attributes, ErrorKind, JsObject, JsValue, ObjectKind, PropertyKey},
+    parser::Parser,
+};
+
+/// Exports of a module implemented by the host, with their values
+pub type BuiltinModule = fn(&mut Context) -> Vec<(String, JsValue)>;
+
+/// What a `ModuleLoader` found for a key
+pub enum ModuleSource {
+    Text(String),
+    Builtin(BuiltinModule),
+}
+
+/// How the host finds the modules, `import` and `Context::import` go through it.
+///
+/// Each module is loaded once per runtime, by the key `resolve` gives
+pub trait ModuleLoader {
+    /// Key of the module `specifier` refers to when imported from the module `referrer`, or
+    /// from the host
+    fn resolve(&mut self, specifier: &str, referrer: Option<&str>) -> Result<String, String>;
+
+    fn load(&mut self, key: &str) -> Result<ModuleSource, String>;
+}
+
+/// Modules read from files, the specifiers are paths relative to the importing module
+pub struct FileSystemLoader {
+    /// Directory of the specifiers imported by the host
+    root: PathBuf,
+    builtins: HashMap<String, BuiltinModule>,
+}
+
+impl FileSystemLoader {
+    pub fn new(root: impl Into<PathBuf>) -> FileSystemLoader {
+        FileSystemLoader {
+            root: root.into(),
+            builtins: HashMap::new(),
+        }
+    }
+
+    /// Import `module` by `name` instead of looking for a file
+    pub fn with_builtin(mut self, name: &str, module: BuiltinModule) -> FileSystemLoader {
+        self.builtins.insert(name.to_string(), module);
+        self
+    }
+}
+
+impl ModuleLoader for FileSystemLoader {
+    fn resolve(&mut self, specifier: &str, referrer: Option<&str>) -> Result<String, String> {
+        if self.builtins.contains_key(specifier) {
+            return Ok(specifier.to_string());
+        }
+
+        let directory = match referrer {
+            Some(referrer) if !self.builtins.contains_key(referrer) => {
+                Path::new(referrer).parent().unwrap_or(&self.root)
+            }
+            _ => &self.root,
+        };
+        let path = directory.join(specifier);
+        match path.canonicalize() {
+            Ok(path) => Ok(path.to_string_lossy().into_owned()),
+            Err(_) => Err(format!("Cannot find module '{}'", path.display())),
+        }
+    }
+
+    fn load(&mut self, key: &str) -> Result<ModuleSource, String> {
+        if let Some(module) = self.builtins.get(key) {
+            return Ok(ModuleSource::Builtin(*module));
+        }
+        fs::read_to_string(key)
+            .map(ModuleSource::Text)
+            .map_err(|error| format!("Cannot read module '{}': {}", key, error))
+    }
+}
+
+/// Modules kept in memory by key, `./` and `../` specifiers are relative to the key of the
+/// importing module like paths are
+#[derive(Default)]
+pub struct MemoryLoader {
+    sources: HashMap<String, String>,
+    builtins: HashMap<String, BuiltinModule>,
+}
+
+impl MemoryLoader {
+    pub fn new() -> MemoryLoader {
+        MemoryLoader::default()
+    }
+
+    pub fn with_source(mut self, key: &str, source: &str) -> MemoryLoader {
+        self.insert(key, source);
+        self
+    }
+
+    pub fn with_builtin(mut self, key: &str, module: BuiltinModule) -> MemoryLoader {
+        self.builtins.insert(key.to_string(), module);
+        self
+    }
+
+    pub fn insert(&mut self, key: &str, source: &str) {
+        self.sources.insert(key.to_string(), source.to_string());
+    }
+}
+
+impl ModuleLoader for MemoryLoader {
+    fn resolve(&mut self, specifier: &str, referrer: Option<&str>) -> Result<String, String> {
+        let is_relative = specifier.starts_with("./") || specifier.starts_with("../");
+        let referrer = match referrer {
+            Some(referrer) if is_relative => referrer,
+            _ => return Ok(specifier.to_string()),
+        };
+
+        let mut segments: Vec<_> = referrer.split('/').collect();
+        segments.pop();
+        for segment in specifier.split('/') {
+            match segment {
+                "." => {}
+                ".." => {
+                    segments.pop();
+                }
+                segment => segments.push(segment),
+            }
+        }
+        Ok(segments.join("/"))
+    }
+
+    fn load(&mut self, key: &str) -> Result<ModuleSource, String> {
+        if let Some(source) = self.sources.get(key) {
+            Ok(ModuleSource::Text(source.clone()))
+        } else if let Some(module) = self.builtins.get(key) {
+            Ok(ModuleSource::Builtin(*module))
+        } else {
+            Err(format!("Cannot find module '{}'", key))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleStatus {
+    Unlinked,
+    Linking,
+    Linked,
+    Evaluating,
+    /// Also when its evaluation threw, the error is rethrown to the later imports
+    Evaluated,
+}
+
+unsafe impl Trace for ModuleStatus {}
+
+/// Code of a module written in JS, compiled in two parts
+struct ModuleCode {
+    /// Hoists the declarations into the scope of the module while it is linked
+    declarations: Rc<CodeBlock>,
+    body: Rc<CodeBlock>,
+}
+
+/// `Source Text Module Record`, a module with the entries of its `import` and `export`
+/// declarations and the state of its linking and evaluation
+#[derive(GcTrace)]
+pub struct ModuleRecord {
+    key: String,
+    status: ModuleStatus,
+    /// `None` for the modules of the host, which are evaluated once loaded
+    #[unsafe_ignore_trace]
+    code: Option<ModuleCode>,
+    requests: Vec<String>,
+    /// Records of the `requests`, in the same order
+    requested_modules: Vec<GcPointer<ModuleRecord>>,
+    #[unsafe_ignore_trace]
+    imports: Vec<ImportEntry>,
+    #[unsafe_ignore_trace]
+    local_exports: Vec<ExportEntry>,
+    #[unsafe_ignore_trace]
+    indirect_exports: Vec<ExportEntry>,
+    #[unsafe_ignore_trace]
+    star_exports: Vec<ExportEntry>,
+    environment: Option<GcPointer<LexicalEnvironment>>,
+    namespace: Option<GcPointer<JsObject>>,
+    /// What its evaluation threw
+    error: Option<JsValue>,
+    /// Position in the depth first traversal of the linking or of the evaluation, and the
+    /// smallest one reachable from it, which tell the cycles apart
+    dfs_index: usize,
+    dfs_ancestor_index: usize,
+}
+
+impl GcCell for ModuleRecord {}
+
+impl fmt::Debug for ModuleRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ModuleRecord {{ key: {:?}, status: {:?} }}",
+            self.key, self.status
+        )
+    }
+}
+
+impl ModuleRecord {
+    /// `ParseModule`, the exports of imported bindings become indirect exports
+    fn new(key: &str, module: &Module, code: ModuleCode) -> ModuleRecord {
+        let mut local_exports = Vec::new();
+        let mut indirect_exports = Vec::new();
+        let mut star_exports = Vec::new();
+        for entry in module.exports() {
+            if entry.module_request.is_none() {
+                let import = module
+                    .imports()
+                    .iter()
+                    .find(|import| Some(&import.local_name) == entry.local_name.as_ref());
+                match import {
+                    Some(import) if import.import_name != ImportName::Namespace => indirect_exports
+                        .push(ExportEntry {
+                            export_name: entry.export_name.clone(),
+                            module_request: Some(import.module_request.clone()),
+                            import_name: Some(import.import_name.clone()),
+                            local_name: None,
+                        }),
+                    _ => local_exports.push(entry.clone()),
+                }
+            } else if entry.import_name == Some(ImportName::All) {
+                star_exports.push(entry.clone());
+            } else {
+                indirect_exports.push(entry.clone());
+            }
+        }
+
+        ModuleRecord {
+            key: key.to_string(),
+            status: ModuleStatus::Unlinked,
+            code: Some(code),
+            requests: module.requests().to_vec(),
+            requested_modules: Vec::new(),
+            imports: module.imports().to_vec(),
+            local_exports,
+            indirect_exports,
+            star_exports,
+            environment: None,
+            namespace: None,
+            error: None,
+            dfs_index: 0,
+            dfs_ancestor_index: 0,
+        }
+    }
+
+    /// Module of the host, already evaluated, its exports are immutable bindings
+    fn builtin(ctx: &mut Context, key: &str, module: BuiltinModule) -> ModuleRecord {
+        let exports = module(ctx);
+        let global_environment = ctx.global_environment();
+        let environment =
+            LexicalEnvironment::new(EnvironmentRecordKind::Module, Some(global_environment));
+        let mut environment = ctx.allocate(environment);
+
+        let mut local_exports = Vec::new();
+        for (name, value) in exports {
+            environment.create_immutable_binding(&name);
+            environment.initialize_binding(&name, value);
+            local_exports.push(ExportEntry::local(&name, &name));
+        }
+
+        ModuleRecord {
+            key: key.to_string(),
+            status: ModuleStatus::Evaluated,
+            code: None,
+            requests: Vec::new(),
+            requested_modules: Vec::new(),
+            imports: Vec::new(),
+            local_exports,
+            indirect_exports: Vec::new(),
+            star_exports: Vec::new(),
+            environment: Some(environment),
+            namespace: None,
+            error: None,
+            dfs_index: 0,
+            dfs_ancestor_index: 0,
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn status(&self) -> ModuleStatus {
+        self.status
+    }
+
+    /// Record of the module `request` refers to, once loaded
+    fn requested_module(&self, request: &str) -> GcPointer<ModuleRecord> {
+        let index = self
+            .requests
+            .iter()
+            .position(|r| r == request)
+            .expect("request of a module which isn't in its list");
+        self.requested_modules[index].clone()
+    }
+}
+
+/// `ResolvedBinding` record, where the value of an import or an export is read from
+#[derive(Debug, Clone, GcTrace)]
+pub enum ResolvedBinding {
+    /// A variable of the module
+    Binding(GcPointer<ModuleRecord>, String),
+    /// The namespace object of the module
+    Namespace(GcPointer<ModuleRecord>),
+}
+
+impl ResolvedBinding {
+    /// Current value, uninitialized until the declaration ran
+    pub fn value(&self) -> Result<JsValue, BindingError> {
+        match self {
+            ResolvedBinding::Binding(module, name) => match &module.environment {
+                Some(environment) => environment.get_binding_value(name),
+                None => Err(BindingError::Uninitialized),
+            },
+            ResolvedBinding::Namespace(module) => module
+                .namespace
+                .clone()
+                .map(JsValue::object)
+                .ok_or(BindingError::Uninitialized),
+        }
+    }
+
+    fn module(&self) -> &GcPointer<ModuleRecord> {
+        match self {
+            ResolvedBinding::Binding(module, _) | ResolvedBinding::Namespace(module) => module,
+        }
+    }
+
+    fn same_binding(&self, other: &ResolvedBinding) -> bool {
+        match (self, other) {
+            (ResolvedBinding::Binding(a, x), ResolvedBinding::Binding(b, y)) => {
+                a.ptr_eq(b) && x == y
+            }
+            (ResolvedBinding::Namespace(a), ResolvedBinding::Namespace(b)) => a.ptr_eq(b),
+            _ => false,
+        }
+    }
+}
+
+/// What `resolve_export` found for a name
+#[derive(Debug)]
+pub enum Resolution {
+    Found(ResolvedBinding),
+    NotFound,
+    /// Exported by more than one `export *`
+    Ambiguous,
+}
+
+/// `[[Exports]]` of a module namespace object, the values are read from the modules
+#[derive(Debug, GcTrace)]
+pub struct ModuleNamespace {
+    /// Sorted
+    exports: Vec<PropertyKey>,
+    bindings: Vec<ResolvedBinding>,
+}
+
+impl ModuleNamespace {
+    pub fn exports(&self) -> &[PropertyKey] {
+        &self.exports
+    }
+
+    /// Value of the export `key`, `None` if it isn't one
+    pub fn value(&self, key: &PropertyKey) -> Option<Result<JsValue, BindingError>> {
+        let index = self.exports.iter().position(|export| export == key)?;
+        Some(self.bindings[index].value())
+    }
+}
+
+/// Load the module `specifier` refers to and the ones it imports, recording the keys of the
+/// records it creates in `loaded`
+pub(crate) fn load(
+    ctx: &mut Context,
+    specifier: &str,
+    referrer: Option<&str>,
+    loaded: &mut Vec<String>,
+) -> ast::Result<GcPointer<ModuleRecord>> {
+    let key = match ctx.resolve_module(specifier, referrer) {
+        Ok(key) => key,
+        Err(message) => return Err(ctx.throw_error(ErrorKind::Error, &message)),
+    };
+    if let Some(module) = ctx.registered_module(&key) {
+        return Ok(module);
+    }
+
+    let source = match ctx.load_module_source(&key) {
+        Ok(source) => source,
+        Err(message) => return Err(ctx.throw_error(ErrorKind::Error, &message)),
+    };
+    let record = match source {
+        ModuleSource::Text(text) => {
+            let syntax_error = |ctx: &mut Context, error: &dyn fmt::Display| {
+                let message = format!("{} ({})", error, key);
+                ctx.throw_error(ErrorKind::SyntaxError, &message)
+            };
+            let module = match Parser::new(&text).parse_module() {
+                Ok(module) => module,
+                Err(error) => return Err(syntax_error(ctx, &error)),
+            };
+            let (declarations, body) = match Compiler::compile_module(&module) {
+                Ok(blocks) => blocks,
+                Err(error) => return Err(syntax_error(ctx, &error)),
+            };
+            let code = ModuleCode {
+                declarations: Rc::new(declarations),
+                body: Rc::new(body),
+            };
+            ModuleRecord::new(&key, &module, code)
+        }
+        ModuleSource::Builtin(module) => ModuleRecord::builtin(ctx, &key, module),
+    };
+
+    let mut record = ctx.allocate(record);
+    ctx.register_module(&key, record.clone());
+    loaded.push(key.clone());
+    for request in record.requests.clone() {
+        let requested = load(ctx, &request, Some(&key), loaded)?;
+        record.requested_modules.push(requested);
+    }
+    Ok(record)
+}
+
+/// `ResolveExport`, the binding `export_name` refers to, following the re-exports.
+///
+/// `resolve_set` holds the names being resolved, which are not found when a cycle of
+/// re-exports comes back to them
+pub(crate) fn resolve_export(
+    module: &GcPointer<ModuleRecord>,
+    export_name: &str,
+    resolve_set: &mut Vec<(GcPointer<ModuleRecord>, String)>,
+) -> Resolution {
+    if resolve_set
+        .iter()
+        .any(|(m, name)| m.ptr_eq(module) && name == export_name)
+    {
+        return Resolution::NotFound;
+    }
+    resolve_set.push((module.clone(), export_name.to_string()));
+
+    let exports_name = |entry: &&ExportEntry| entry.export_name.as_deref() == Some(export_name);
+    if let Some(entry) = module.local_exports.iter().find(exports_name) {
+        let local_name = entry.local_name.clone().unwrap_or_default();
+        return Resolution::Found(ResolvedBinding::Binding(module.clone(), local_name));
+    }
+    if let Some(entry) = module.indirect_exports.iter().find(exports_name) {
+        let imported = module.requested_module(entry.module_request.as_deref().unwrap());
+        return match &entry.import_name {
+            Some(ImportName::Name(name)) => resolve_export(&imported, name, resolve_set),
+            _ => Resolution::Found(ResolvedBinding::Namespace(imported)),
+        };
+    }
+    // `export *` doesn't re-export the default export
+    if export_name == "default" {
+        return Resolution::NotFound;
+    }
+
+    let mut star_resolution: Option<ResolvedBinding> = None;
+    for entry in module.star_exports.iter() {
+        let imported = module.requested_module(entry.module_request.as_deref().unwrap());
+        match resolve_export(&imported, export_name, resolve_set) {
+            Resolution::Ambiguous => return Resolution::Ambiguous,
+            Resolution::NotFound => {}
+            Resolution::Found(resolution) => match &star_resolution {
+                None => star_resolution = Some(resolution),
+                Some(star) if !star.same_binding(&resolution) => return Resolution::Ambiguous,
+                Some(_) => {}
+            },
+        }
+    }
+    star_resolution.map_or(Resolution::NotFound, Resolution::Found)
+}
+
+/// `GetExportedNames`, `export *` of a module already visited through `star_set` adds nothing
+pub(crate) fn exported_names(
+    module: &GcPointer<ModuleRecord>,
+    star_set: &mut Vec<GcPointer<ModuleRecord>>,
+) -> Vec<String> {
+    if star_set.iter().any(|m| m.ptr_eq(module)) {
+        return Vec::new();
+    }
+    star_set.push(module.clone());
+
+    let mut names: Vec<String> = module
+        .local_exports
+        .iter()
+        .chain(module.indirect_exports.iter())
+        .filter_map(|entry| entry.export_name.clone())
+        .collect();
+    for entry in module.star_exports.iter() {
+        let imported = module.requested_module(entry.module_request.as_deref().unwrap());
+        for name in exported_names(&imported, star_set) {
+            if name != "default" && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names
+}
+
+/// `Link()`, create the scopes of `module` and of the modules it imports from, with their
+/// imports bound and their functions declared.
+///
+/// On failure the modules it was linking are left unlinked
+pub(crate) fn link(ctx: &mut Context, module: &GcPointer<ModuleRecord>) -> ast::Result<()> {
+    let mut stack = Vec::new();
+    let result = inner_link(ctx, module.clone(), &mut stack, 0);
+    if result.is_err() {
+        for mut module in stack {
+            module.status = ModuleStatus::Unlinked;
+            module.environment = None;
+        }
+    }
+    result.map(|_| ())
+}
+
+/// `InnerModuleLinking`, the modules of a cycle are linked together once its first one is done
+fn inner_link(
+    ctx: &mut Context,
+    mut module: GcPointer<ModuleRecord>,
+    stack: &mut Vec<GcPointer<ModuleRecord>>,
+    mut index: usize,
+) -> ast::Result<usize> {
+    if module.status != ModuleStatus::Unlinked {
+        return Ok(index);
+    }
+
+    module.status = ModuleStatus::Linking;
+    module.dfs_index = index;
+    module.dfs_ancestor_index = index;
+    index += 1;
+    stack.push(module.clone());
+
+    for required in module.requested_modules.clone() {
+        index = inner_link(ctx, required.clone(), stack, index)?;
+        if required.status == ModuleStatus::Linking {
+            module.dfs_ancestor_index = module.dfs_ancestor_index.min(required.dfs_ancestor_index);
+        }
+    }
+    initialize_environment(ctx, &mut module)?;
+
+    if module.dfs_ancestor_index == module.dfs_index {
+        while let Some(mut linked) = stack.pop() {
+            linked.status = ModuleStatus::Linked;
+            if linked.ptr_eq(&module) {
+                break;
+            }
+        }
+    }
+    Ok(index)
+}
+
+/// `InitializeEnvironment`, the bindings of a module before its body runs
+fn initialize_environment(
+    ctx: &mut Context,
+    module: &mut GcPointer<ModuleRecord>,
+) -> ast::Result<()> {
+    for entry in module.indirect_exports.clone() {
+        let request = entry.module_request.as_deref().unwrap();
+        if let Some(ImportName::Name(name)) = &entry.import_name {
+            check_resolution(ctx, module, request, name)?;
+        }
+    }
+
+    let global_environment = ctx.global_environment();
+    let environment =
+        LexicalEnvironment::new(EnvironmentRecordKind::Module, Some(global_environment));
+    let mut environment = ctx.allocate(environment);
+    environment.bind_this(JsValue::Undefined);
+    module.environment = Some(environment.clone());
+
+    for import in module.imports.clone() {
+        let imported = module.requested_module(&import.module_request);
+        let binding = match &import.import_name {
+            ImportName::Name(name) => check_resolution(ctx, module, &import.module_request, name)?,
+            _ => ResolvedBinding::Namespace(imported),
+        };
+        if let ResolvedBinding::Namespace(imported) = &binding {
+            namespace(ctx, imported);
+        }
+
+        if environment.has_binding(&import.local_name) {
+            let message = format!(
+                "Identifier '{}' has already been declared",
+                import.local_name
+            );
+            return Err(ctx.throw_error(ErrorKind::SyntaxError, &message));
+        }
+        environment.create_import_binding(&import.local_name, binding);
+    }
+
+    if let Some(code) = &module.code {
+        let declarations = code.declarations.clone();
+        run_in_environment(ctx, &declarations, environment.clone())?;
+    }
+
+    for entry in module.local_exports.iter() {
+        let local_name = entry.local_name.as_deref().unwrap_or_default();
+        if !environment.has_binding(local_name) {
+            let message = format!("Export '{}' is not defined in module", local_name);
+            return Err(ctx.throw_error(ErrorKind::SyntaxError, &message));
+        }
+    }
+    Ok(())
+}
+
+/// The binding `name` of the module `request` refers to, a `SyntaxError` if there is none
+fn check_resolution(
+    ctx: &mut Context,
+    module: &GcPointer<ModuleRecord>,
+    request: &str,
+    name: &str,
+) -> ast::Result<ResolvedBinding> {
+    let imported = module.requested_module(request);
+    let message = match resolve_export(&imported, name, &mut Vec::new()) {
+        Resolution::Found(binding) => return Ok(binding),
+        Resolution::NotFound => format!(
+            "The requested module '{}' does not provide an export named '{}'",
+            request, name
+        ),
+        Resolution::Ambiguous => format!(
+            "The requested module '{}' contains conflicting star exports for name '{}'",
+            request, name
+        ),
+    };
+    Err(ctx.throw_error(ErrorKind::SyntaxError, &message))
+}
+
+/// Run one of the blocks of a module in its scope
+fn run_in_environment(
+    ctx: &mut Context,
+    block: &Rc<CodeBlock>,
+    environment: GcPointer<LexicalEnvironment>,
+) -> ast::Result<()> {
+    let outer = ctx.root(ctx.environment());
+    ctx.set_environment(environment);
+    let result = Interpreter::call(ctx, block, Vec::new());
+    ctx.set_environment((*outer).clone());
+    result.map(|_| ())
+}
+
+/// `Evaluate()`, run the body of `module` after the ones of the modules it imports from.
+///
+/// What a body throws is thrown again by the later evaluations of the modules which were
+/// being evaluated
+pub(crate) fn evaluate(ctx: &mut Context, module: &GcPointer<ModuleRecord>) -> ast::Result<()> {
+    let mut stack = Vec::new();
+    let result = inner_evaluate(ctx, module.clone(), &mut stack, 0);
+    if let Err(Completion::Throw(error)) = &result {
+        for mut module in stack {
+            module.status = ModuleStatus::Evaluated;
+            module.error = Some(error.clone());
+        }
+    }
+    result.map(|_| ())
+}
+
+/// `InnerModuleEvaluation`, the bodies of a cycle run in the order the imports reach them
+fn inner_evaluate(
+    ctx: &mut Context,
+    mut module: GcPointer<ModuleRecord>,
+    stack: &mut Vec<GcPointer<ModuleRecord>>,
+    mut index: usize,
+) -> ast::Result<usize> {
+    match module.status {
+        ModuleStatus::Evaluated => {
+            return match &module.error {
+                Some(error) => Err(Completion::Throw(error.clone())),
+                None => Ok(index),
+            }
+        }
+        ModuleStatus::Evaluating => return Ok(index),
+        _ => {}
+    }
+
+    module.status = ModuleStatus::Evaluating;
+    module.dfs_index = index;
+    module.dfs_ancestor_index = index;
+    index += 1;
+    stack.push(module.clone());
+
+    for required in module.requested_modules.clone() {
+        index = inner_evaluate(ctx, required.clone(), stack, index)?;
+        if required.status == ModuleStatus::Evaluating {
+            module.dfs_ancestor_index = module.dfs_ancestor_index.min(required.dfs_ancestor_index);
+        }
+    }
+    if let (Some(code), Some(environment)) = (&module.code, &module.environment) {
+        let body = code.body.clone();
+        run_in_environment(ctx, &body, environment.clone())?;
+    }
+
+    if module.dfs_ancestor_index == module.dfs_index {
+        while let Some(mut evaluated) = stack.pop() {
+            evaluated.status = ModuleStatus::Evaluated;
+            if evaluated.ptr_eq(&module) {
+                break;
+            }
+        }
+    }
+    Ok(index)
+}
+
+/// `GetModuleNamespace`, the object of `import * as ns`, created once per module.
+///
+/// Its properties are the exports in order, the ambiguous ones left out
+pub(crate) fn namespace(
+    ctx: &mut Context,
+    module: &GcPointer<ModuleRecord>,
+) -> GcPointer<JsObject> {
+    if let Some(namespace) = &module.namespace {
+        return namespace.clone();
+    }
+
+    let mut names = exported_names(module, &mut Vec::new());
+    names.sort();
+    let mut exports = Vec::new();
+    let mut bindings = Vec::new();
+    for name in names {
+        if let Resolution::Found(binding) = resolve_export(module, &name, &mut Vec::new()) {
+            exports.push(PropertyKey::from(name.as_str()));
+            bindings.push(binding);
+        }
+    }
+
+    let nested: Vec<_> = bindings
+        .iter()
+        .filter(|binding| matches!(binding, ResolvedBinding::Namespace(_)))
+        .map(|binding| binding.module().clone())
+        .collect();
+    let kind = ObjectKind::Namespace(ModuleNamespace { exports, bindings });
+    let mut namespace = JsObject::with_prototype(ctx, None, kind);
+    let to_string_tag = ctx.well_known_symbols().to_string_tag.clone();
+    namespace.define_property(
+        to_string_tag,
+        JsValue::string("Module"),
+        Attributes::empty(),
+    );
+    namespace.prevent_extensions();
+
+    let mut module = module.clone();
+    module.namespace = Some(namespace.clone());
+    for module in nested {
+        self::namespace(ctx, &module);
+    }
+    namespace
+}
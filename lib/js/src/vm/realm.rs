@@ -0,0 +1,143 @@
+use std::rc::Rc;
+
+use super::{EnvironmentRecordKind, LexicalEnvironment};
+use crate::{
+    gc::{GcPointer, GcTrace, Heap, Trace, Tracer},
+    jsrt::{
+        JsArray, JsObject, JsString, JsValue, NativeFunction, ObjectKind, Shape, WellKnownSymbols,
+    },
+};
+
+/// The global object and scope with the intrinsic objects, shared by the contexts of a runtime
+#[derive(Clone, GcTrace)]
+pub struct Realm {
+    /// Holds the built-ins, `var` and functions declared by scripts live in the global scope
+    pub(crate) global_object: GcPointer<JsObject>,
+    pub(crate) global_environment: GcPointer<LexicalEnvironment>,
+    /// Shape of empty objects, the transitions from it are shared by every object of the realm
+    #[unsafe_ignore_trace]
+    pub(crate) root_shape: Rc<Shape>,
+    pub(crate) object_prototype: GcPointer<JsObject>,
+    pub(crate) function_prototype: GcPointer<JsObject>,
+    pub(crate) array_prototype: GcPointer<JsObject>,
+    /// `%IteratorPrototype%`, the iterators of the built-ins inherit from it
+    pub(crate) iterator_prototype: GcPointer<JsObject>,
+    pub(crate) array_iterator_prototype: GcPointer<JsObject>,
+    pub(crate) string_iterator_prototype: GcPointer<JsObject>,
+    pub(crate) boolean_prototype: GcPointer<JsObject>,
+    pub(crate) number_prototype: GcPointer<JsObject>,
+    pub(crate) string_prototype: GcPointer<JsObject>,
+    pub(crate) bigint_prototype: GcPointer<JsObject>,
+    pub(crate) symbol_prototype: GcPointer<JsObject>,
+    pub(crate) regexp_prototype: GcPointer<JsObject>,
+    pub(crate) weak_ref_prototype: GcPointer<JsObject>,
+    pub(crate) weak_map_prototype: GcPointer<JsObject>,
+    pub(crate) weak_set_prototype: GcPointer<JsObject>,
+    pub(crate) finalization_registry_prototype: GcPointer<JsObject>,
+    pub(crate) promise_prototype: GcPointer<JsObject>,
+    /// `%GeneratorFunction.prototype%`, the prototype of generator functions
+    pub(crate) generator_function_prototype: GcPointer<JsObject>,
+    /// `%GeneratorPrototype%`, the generators inherit from it through the `prototype` of
+    /// their function
+    pub(crate) generator_prototype: GcPointer<JsObject>,
+    pub(crate) async_function_prototype: GcPointer<JsObject>,
+    pub(crate) symbols: WellKnownSymbols,
+    /// `Error.prototype` and friends, in the order of `ErrorKind::ALL`
+    pub(crate) error_prototypes: Vec<GcPointer<JsObject>>,
+}
+
+impl Realm {
+    /// Allocate the intrinsic objects, their properties are added by `install_builtins` once a
+    /// context can run native code
+    pub(crate) fn new(heap: &mut Heap) -> Realm {
+        let root_shape = Shape::root();
+        let object_prototype = heap.allocate(JsObject::from_parts(
+            root_shape.clone(),
+            None,
+            ObjectKind::Ordinary,
+        ));
+        let mut intrinsic = |kind| {
+            heap.allocate(JsObject::from_parts(
+                root_shape.clone(),
+                Some(object_prototype.clone()),
+                kind,
+            ))
+        };
+
+        // The prototypes of functions, arrays and primitives are themselves of that kind,
+        // `Function.prototype` does nothing
+        let noop = NativeFunction::new("", 0, |_, _, _| Ok(JsValue::Undefined));
+        let function_prototype = intrinsic(ObjectKind::NativeFunction(noop));
+        let array_prototype = intrinsic(ObjectKind::Array(JsArray::new()));
+        let iterator_prototype = intrinsic(ObjectKind::Ordinary);
+        let boolean_prototype = intrinsic(ObjectKind::Boolean(false));
+        let number_prototype = intrinsic(ObjectKind::Number(0.0));
+        let string_prototype = intrinsic(ObjectKind::String(JsString::new("")));
+        // Unlike the others `BigInt.prototype`, `Symbol.prototype` and `RegExp.prototype` are
+        // plain objects
+        let bigint_prototype = intrinsic(ObjectKind::Ordinary);
+        let symbol_prototype = intrinsic(ObjectKind::Ordinary);
+        let regexp_prototype = intrinsic(ObjectKind::Ordinary);
+        // Nor are the prototypes of the weak collections
+        let weak_ref_prototype = intrinsic(ObjectKind::Ordinary);
+        let weak_map_prototype = intrinsic(ObjectKind::Ordinary);
+        let weak_set_prototype = intrinsic(ObjectKind::Ordinary);
+        let finalization_registry_prototype = intrinsic(ObjectKind::Ordinary);
+        let promise_prototype = intrinsic(ObjectKind::Ordinary);
+        let global_object = intrinsic(ObjectKind::Ordinary);
+
+        let mut iterator = || {
+            heap.allocate(JsObject::from_parts(
+                root_shape.clone(),
+                Some(iterator_prototype.clone()),
+                ObjectKind::Ordinary,
+            ))
+        };
+        let array_iterator_prototype = iterator();
+        let string_iterator_prototype = iterator();
+        let generator_prototype = iterator();
+
+        let mut function = || {
+            heap.allocate(JsObject::from_parts(
+                root_shape.clone(),
+                Some(function_prototype.clone()),
+                ObjectKind::Ordinary,
+            ))
+        };
+        let generator_function_prototype = function();
+        let async_function_prototype = function();
+
+        let mut global_environment = LexicalEnvironment::new(EnvironmentRecordKind::Global, None);
+        global_environment.bind_this(JsValue::object(global_object.clone()));
+        let global_environment = heap.allocate(global_environment);
+        let symbols = WellKnownSymbols::new(heap);
+
+        Realm {
+            global_object,
+            global_environment,
+            root_shape,
+            object_prototype,
+            function_prototype,
+            array_prototype,
+            iterator_prototype,
+            array_iterator_prototype,
+            string_iterator_prototype,
+            boolean_prototype,
+            number_prototype,
+            string_prototype,
+            bigint_prototype,
+            symbol_prototype,
+            regexp_prototype,
+            weak_ref_prototype,
+            weak_map_prototype,
+            weak_set_prototype,
+            finalization_registry_prototype,
+            promise_prototype,
+            generator_function_prototype,
+            generator_prototype,
+            async_function_prototype,
+            symbols,
+            error_prototypes: Vec::new(),
+        }
+    }
+}
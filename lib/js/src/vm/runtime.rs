@@ -1,25 +1,285 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
-use crate::gc::{Heap, SweepType};
+use super::{ModuleLoader, ModuleRecord, Realm};
+use crate::{
+    gc::{GcCell, GcPointer, GcTrace, Heap, HeapStats, SweepType, Trace, Tracer, WeakGcPointer},
+    jsrt::{Job, JsObject, JsSymbol, JsValue, ObjectKind},
+};
+
+/// Identifies a queued macrotask until it runs or is cancelled, what `setTimeout` returns
+pub type MacrotaskId = u32;
+
+/// Told about each macrotask a script queues and its delay, so the event loop of the host can
+/// run it with `Context::run_macrotask` once the delay elapsed.
+///
+/// It is called with the runtime locked
+pub type MacrotaskHook = Box<dyn FnMut(MacrotaskId, Duration)>;
+
+/// Callback of `setTimeout` waiting for the host to run it
+#[derive(Clone, GcTrace)]
+pub struct Macrotask {
+    #[unsafe_ignore_trace]
+    id: MacrotaskId,
+    /// When it is due on the clock of `Context::run_macrotasks`
+    #[unsafe_ignore_trace]
+    due: Duration,
+    pub(crate) callback: JsValue,
+    pub(crate) args: Vec<JsValue>,
+}
 
 pub struct Runtime {
     heap: Heap,
+    /// Set up by the first context
+    realm: Option<Realm>,
+    /// Symbols of `Symbol.for`, by key
+    symbol_registry: HashMap<String, JsSymbol>,
+    /// `[[TemplateMap]]`, the object passed to the tag of each template, by call site
+    templates: HashMap<usize, GcPointer<JsObject>>,
+    /// `[[KeptAlive]]`, targets of the `WeakRef`s created or dereferenced by the current job
+    kept_alive: Vec<GcPointer<JsObject>>,
+    /// Every `FinalizationRegistry`, looked at after each collection
+    finalization_registries: Vec<WeakGcPointer<JsObject>>,
+    /// Registries with collected targets, waiting for their callbacks to run
+    cleanup_queue: Vec<GcPointer<JsObject>>,
+    /// Microtasks, run in order at the end of each script and macrotask
+    jobs: VecDeque<Job>,
+    macrotasks: Vec<Macrotask>,
+    next_macrotask_id: MacrotaskId,
+    /// Time of the simulated event loop of `Context::run_macrotasks`
+    clock: Duration,
+    macrotask_hook: Option<MacrotaskHook>,
+    module_loader: Option<Box<dyn ModuleLoader>>,
+    /// Every module loaded, by the key the loader resolved it to
+    modules: HashMap<String, GcPointer<ModuleRecord>>,
 }
 
 impl Runtime {
+    /// A runtime with an empty heap, collected on every allocation when `JS_GC_STRESS` is set
     pub fn new() -> Arc<Mutex<Runtime>> {
+        let mut heap = Heap::default();
+        heap.set_stress(std::env::var_os("JS_GC_STRESS").is_some());
+
         Arc::new(Mutex::new(Runtime {
-            heap: Default::default(),
+            heap,
+            realm: None,
+            symbol_registry: HashMap::new(),
+            templates: HashMap::new(),
+            kept_alive: Vec::new(),
+            finalization_registries: Vec::new(),
+            cleanup_queue: Vec::new(),
+            jobs: VecDeque::new(),
+            macrotasks: Vec::new(),
+            next_macrotask_id: 1,
+            clock: Duration::ZERO,
+            macrotask_hook: None,
+            module_loader: None,
+            modules: HashMap::new(),
         }))
     }
 
     pub fn heap(&mut self) -> &mut Heap {
         &mut self.heap
     }
+
+    /// Blocks and cells in use by each size class and the large objects
+    pub fn heap_stats(&self) -> HeapStats {
+        self.heap.stats()
+    }
+
+    pub fn realm(&self) -> Option<&Realm> {
+        self.realm.as_ref()
+    }
+
+    /// The symbol registered under `key`, created on first use
+    pub fn registered_symbol(&mut self, key: &str) -> JsSymbol {
+        if let Some(symbol) = self.symbol_registry.get(key) {
+            return symbol.clone();
+        }
+
+        let symbol = JsSymbol::registered(&mut self.heap, key.into());
+        self.symbol_registry.insert(key.into(), symbol.clone());
+        symbol
+    }
+
+    pub(crate) fn template_object(&self, site: usize) -> Option<GcPointer<JsObject>> {
+        self.templates.get(&site).cloned()
+    }
+
+    pub(crate) fn add_template_object(&mut self, site: usize, template: GcPointer<JsObject>) {
+        self.templates.insert(site, template);
+    }
+
+    /// Allocate `data`, running first the collection the heap asked for if any.
+    ///
+    /// The cells `data` points to are not rooted yet, they are traced by that collection
+    pub fn allocate<T>(&mut self, mut data: T) -> GcPointer<T>
+    where
+        T: GcCell,
+    {
+        if let Some(sweep_type) = self.heap.pending_collection() {
+            self.collect(sweep_type, &mut data);
+        }
+        self.heap.allocate(data)
+    }
+
+    /// Free what can't be reached from the realm, the registry, the handles or the open scopes
+    pub fn collect_garbage(&mut self) {
+        self.collect(SweepType::Garbage, &mut ());
+    }
+
+    /// Free the unreachable cells allocated since the last collection
+    pub fn collect_young_garbage(&mut self) {
+        self.collect(SweepType::Young, &mut ());
+    }
+
+    fn collect(&mut self, sweep_type: SweepType, extra: &mut dyn Trace) {
+        let Runtime {
+            heap,
+            realm,
+            symbol_registry,
+            templates,
+            kept_alive,
+            finalization_registries,
+            cleanup_queue,
+            jobs,
+            macrotasks,
+            modules,
+            ..
+        } = self;
+
+        heap.collect_garbage(sweep_type, |tracer| {
+            realm.trace(tracer);
+            symbol_registry.trace(tracer);
+            templates.trace(tracer);
+            kept_alive.trace(tracer);
+            finalization_registries.trace(tracer);
+            cleanup_queue.trace(tracer);
+            jobs.trace(tracer);
+            macrotasks.trace(tracer);
+            modules.trace(tracer);
+            extra.trace(tracer);
+        });
+
+        self.finalization_registries
+            .retain(|registry| !registry.is_cleared());
+        for registry in self.finalization_registries.iter() {
+            let registry = registry.upgrade().unwrap();
+            let cleared = match &registry.kind {
+                ObjectKind::FinalizationRegistry(r) => r.has_cleared_targets(),
+                _ => false,
+            };
+            if cleared && !self.cleanup_queue.iter().any(|r| r.ptr_eq(&registry)) {
+                self.cleanup_queue.push(registry);
+            }
+        }
+    }
+
+    /// `AddToKeptObjects`, keep `target` alive until the end of the current job
+    pub(crate) fn keep_alive(&mut self, target: GcPointer<JsObject>) {
+        self.kept_alive.push(target);
+    }
+
+    /// `ClearKeptObjects`, returning the registries whose callbacks have to run
+    pub(crate) fn end_job(&mut self) -> Vec<GcPointer<JsObject>> {
+        self.kept_alive.clear();
+        std::mem::take(&mut self.cleanup_queue)
+    }
+
+    pub(crate) fn add_finalization_registry(&mut self, registry: &GcPointer<JsObject>) {
+        self.finalization_registries
+            .push(WeakGcPointer::new(registry));
+    }
+
+    /// `HostEnqueuePromiseJob`
+    pub(crate) fn enqueue_job(&mut self, job: Job) {
+        self.jobs.push_back(job);
+    }
+
+    pub(crate) fn next_job(&mut self) -> Option<Job> {
+        self.jobs.pop_front()
+    }
+
+    /// Let the host run the macrotasks, instead of `Context::run_macrotasks`
+    pub fn set_macrotask_hook(&mut self, hook: MacrotaskHook) {
+        self.macrotask_hook = Some(hook);
+    }
+
+    pub fn has_pending_macrotasks(&self) -> bool {
+        !self.macrotasks.is_empty()
+    }
+
+    pub(crate) fn enqueue_macrotask(
+        &mut self,
+        delay: Duration,
+        callback: JsValue,
+        args: Vec<JsValue>,
+    ) -> MacrotaskId {
+        let id = self.next_macrotask_id;
+        self.next_macrotask_id += 1;
+        self.macrotasks.push(Macrotask {
+            id,
+            due: self.clock + delay,
+            callback,
+            args,
+        });
+        if let Some(hook) = &mut self.macrotask_hook {
+            hook(id, delay);
+        }
+        id
+    }
+
+    /// Remove the macrotask `id` from the queue, returning it unless it already ran
+    pub(crate) fn take_macrotask(&mut self, id: MacrotaskId) -> Option<Macrotask> {
+        let index = self.macrotasks.iter().position(|task| task.id == id)?;
+        Some(self.macrotasks.remove(index))
+    }
+
+    /// The macrotask due first, the clock advances to its time
+    pub(crate) fn take_next_macrotask(&mut self) -> Option<Macrotask> {
+        let index = (0..self.macrotasks.len())
+            .min_by_key(|&index| (self.macrotasks[index].due, self.macrotasks[index].id))?;
+        let task = self.macrotasks.remove(index);
+        self.clock = self.clock.max(task.due);
+        Some(task)
+    }
+
+    /// Let the host resolve and load the modules, no module can be imported without one
+    pub fn set_module_loader(&mut self, loader: Box<dyn ModuleLoader>) {
+        self.module_loader = Some(loader);
+    }
+
+    /// Taken while it runs, so it can't be called again meanwhile
+    pub(crate) fn take_module_loader(&mut self) -> Option<Box<dyn ModuleLoader>> {
+        self.module_loader.take()
+    }
+
+    pub(crate) fn restore_module_loader(&mut self, loader: Box<dyn ModuleLoader>) {
+        self.module_loader.get_or_insert(loader);
+    }
+
+    pub(crate) fn module(&self, key: &str) -> Option<GcPointer<ModuleRecord>> {
+        self.modules.get(key).cloned()
+    }
+
+    pub(crate) fn insert_module(&mut self, key: &str, module: GcPointer<ModuleRecord>) {
+        self.modules.insert(key.to_string(), module);
+    }
+
+    pub(crate) fn remove_module(&mut self, key: &str) {
+        self.modules.remove(key);
+    }
+
+    pub(crate) fn set_realm(&mut self, realm: Realm) {
+        self.realm = Some(realm);
+    }
 }
 
 impl Drop for Runtime {
     fn drop(&mut self) {
-        self.heap.collect_garbage(SweepType::Everything);
+        self.heap.collect_garbage(SweepType::Everything, |_| {});
     }
 }
@@ -1,4 +1,5 @@
 use std::{
+    cmp::Ordering,
     collections::HashMap,
     fmt::{Debug, Display},
     hash::{BuildHasher, Hash, Hasher},
@@ -9,7 +10,8 @@ use std::{
 use lazy_static::lazy_static;
 
 lazy_static! {
-    static ref STRING_CACHE: Mutex<HashMap<u64, Weak<Box<str>>>> = Mutex::new(HashMap::new());
+    /// Every string alive, by hash. Different strings can share a hash, each has its entry
+    static ref STRING_CACHE: Mutex<HashMap<u64, Vec<Weak<Box<str>>>>> = Mutex::new(HashMap::new());
 }
 
 #[derive(Clone)]
@@ -33,9 +35,13 @@ impl Hash for FlyString {
 impl Drop for FlyString {
     fn drop(&mut self) {
         if let Ok(mut cache) = STRING_CACHE.lock() {
-            if let Some(weak) = cache.get(&self.hash) {
+            if let Some(entries) = cache.get_mut(&self.hash) {
                 // If we hold the last strong ref, remove its from cache
-                if weak.strong_count() <= 1 {
+                if Arc::strong_count(&self.value) <= 1 {
+                    let ptr = Arc::as_ptr(&self.value);
+                    entries.retain(|weak| weak.as_ptr() != ptr && weak.strong_count() > 0);
+                }
+                if entries.is_empty() {
                     cache.remove(&self.hash);
                 }
             }
@@ -45,21 +51,25 @@ impl Drop for FlyString {
 
 impl PartialEq for FlyString {
     fn eq(&self, other: &Self) -> bool {
-        self.hash.eq(&other.hash)
+        // Equal strings share their value, unless the hashes collide
+        self.hash == other.hash
+            && (Arc::ptr_eq(&self.value, &other.value) || self.value == other.value)
     }
 }
 
 impl Eq for FlyString {}
 
 impl PartialOrd for FlyString {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.hash.partial_cmp(&other.hash)
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for FlyString {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.hash.cmp(&other.hash)
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.hash
+            .cmp(&other.hash)
+            .then_with(|| self.value.cmp(&other.value))
     }
 }
 
@@ -91,29 +101,39 @@ impl Debug for FlyString {
 
 impl<'a, S: Into<&'a str>> From<S> for FlyString {
     fn from(s: S) -> Self {
+        let str: &str = s.into();
+        let hash = STRING_CACHE
+            .lock()
+            .expect("FlyString: STRING_CACHE lock failed")
+            .hasher()
+            .hash_one(str);
+
+        FlyString::intern(str, hash)
+    }
+}
+
+impl FlyString {
+    /// The cached string equal to `str`, or a new one under `hash`
+    fn intern(str: &str, hash: u64) -> FlyString {
         let mut cache = STRING_CACHE
             .lock()
             .expect("FlyString: STRING_CACHE lock failed");
 
-        let str: &str = s.into();
-        let hash = {
-            let state = cache.hasher();
-            let mut hasher = state.build_hasher();
-            str.hash(&mut hasher);
-            hasher.finish()
-        };
-
-        if let Some(weak_str) = cache.get(&hash) {
-            if let Some(arc_str) = weak_str.upgrade() {
-                return Self {
-                    hash,
-                    value: arc_str,
-                };
-            }
+        let entries = cache.entry(hash).or_default();
+        if let Some(arc_str) = entries
+            .iter()
+            .filter_map(Weak::upgrade)
+            .find(|arc_str| arc_str.as_ref().as_ref() == str)
+        {
+            return Self {
+                hash,
+                value: arc_str,
+            };
         }
 
         let arc_str = Arc::new(str.to_owned().into_boxed_str());
-        cache.insert(hash, Arc::downgrade(&arc_str));
+        entries.retain(|weak| weak.strong_count() > 0);
+        entries.push(Arc::downgrade(&arc_str));
 
         Self {
             hash,
@@ -124,7 +144,7 @@ impl<'a, S: Into<&'a str>> From<S> for FlyString {
 
 #[cfg(test)]
 mod test {
-    use super::FlyString;
+    use super::*;
 
     #[test]
     fn to_and_from() {
@@ -134,4 +154,27 @@ mod test {
         let s = fly_str.to_string();
         assert_eq!(s, "abc");
     }
+
+    #[test]
+    fn same_hash_different_strings() {
+        // Strings whose hash is forced to collide
+        let a = FlyString::intern("collide a", 42);
+        let b = FlyString::intern("collide b", 42);
+        assert_eq!(&a[..], "collide a");
+        assert_eq!(&b[..], "collide b");
+        assert_ne!(a, b);
+        assert_ne!(a.cmp(&b), Ordering::Equal);
+
+        // Both stay cached
+        let a2 = FlyString::intern("collide a", 42);
+        assert_eq!(a, a2);
+        assert!(Arc::ptr_eq(&a.value, &a2.value));
+        assert!(Arc::ptr_eq(
+            &b.value,
+            &FlyString::intern("collide b", 42).value
+        ));
+
+        drop((a, a2, b));
+        assert!(STRING_CACHE.lock().unwrap().get(&42).is_none());
+    }
 }